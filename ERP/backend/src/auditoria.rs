@@ -0,0 +1,318 @@
+//! Trilha de auditoria: toda criação, alteração e exclusão feita pela API
+//! grava quem a fez, quando, em qual registro, os valores anteriores e
+//! novos de cada campo e o id da requisição.
+//!
+//! Os handlers recebem um [`Auditor`] e o chamam depois de gravar a
+//! alteração, como fazem com a contabilidade e o estoque.
+
+use std::collections::BTreeMap;
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, Request},
+    http::{request::Parts, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    auth::UsuarioAutenticado,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::{Alteracao, Empresa, OperacaoAuditoria, RegistroAuditoria, Usuario},
+    repositorio::{Registro, Repositorios},
+};
+
+/// Cabeçalho com o id da requisição, aceito do cliente ou gerado aqui.
+pub const CABECALHO_REQUISICAO: &str = "x-request-id";
+
+/// Campos cujo valor nunca é copiado para a trilha: segredos e conteúdos
+/// grandes, como o XML e o PDF da nota. Só se registra que mudaram.
+const CAMPOS_OCULTOS: [&str; 5] = ["senha_hash", "senha_certificado", "csc", "xml", "danfe"];
+
+/// Campos que mudam em toda gravação ou que não são do registro em si.
+const CAMPOS_IGNORADOS: [&str; 4] = ["_id", "empresa_id", "updated_at", "versao"];
+
+/// Id da requisição, disponível nas extensões.
+#[derive(Debug, Clone)]
+pub struct RequisicaoId(pub String);
+
+/// Usa o `X-Request-Id` enviado pelo cliente, quando razoável, ou gera um
+/// novo, e o devolve na resposta.
+pub async fn identificar_requisicao(mut req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(CABECALHO_REQUISICAO)
+        .and_then(|valor| valor.to_str().ok())
+        .map(str::trim)
+        .filter(|valor| {
+            (1..=128).contains(&valor.len())
+                && valor
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| ObjectId::new().to_hex());
+
+    req.extensions_mut().insert(RequisicaoId(id.clone()));
+    let mut response = next.run(req).await;
+    if let Ok(valor) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(CABECALHO_REQUISICAO, valor);
+    }
+    response
+}
+
+/// Registro que pode ser auditado.
+pub trait Auditavel: Serialize {
+    /// Nome da entidade na trilha.
+    const ENTIDADE: &'static str;
+
+    fn id_auditado(&self) -> Option<ObjectId>;
+    /// Empresa em cuja trilha a alteração fica; `None` usa a empresa da
+    /// requisição.
+    fn empresa_auditada(&self) -> Option<ObjectId>;
+}
+
+impl<T: Registro + Serialize> Auditavel for T {
+    const ENTIDADE: &'static str = T::COLECAO;
+
+    fn id_auditado(&self) -> Option<ObjectId> {
+        self.id()
+    }
+
+    fn empresa_auditada(&self) -> Option<ObjectId> {
+        Some(self.empresa_id())
+    }
+}
+
+impl Auditavel for Empresa {
+    const ENTIDADE: &'static str = "empresas";
+
+    fn id_auditado(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    fn empresa_auditada(&self) -> Option<ObjectId> {
+        self.id
+    }
+}
+
+/// Usuários não pertencem a uma empresa; as alterações ficam na trilha da
+/// empresa de quem as fez.
+impl Auditavel for Usuario {
+    const ENTIDADE: &'static str = "usuarios";
+
+    fn id_auditado(&self) -> Option<ObjectId> {
+        self.id
+    }
+
+    fn empresa_auditada(&self) -> Option<ObjectId> {
+        None
+    }
+}
+
+/// Autor das alterações da requisição. Usado como extractor nos handlers.
+#[derive(Clone)]
+pub struct Auditor {
+    repos: Repositorios,
+    empresa_id: ObjectId,
+    usuario_id: ObjectId,
+    usuario: String,
+    requisicao_id: String,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Auditor
+where
+    Repositorios: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let usuario = UsuarioAutenticado::from_request_parts(parts, state).await?;
+        let empresa = EmpresaAtual::from_request_parts(parts, state).await?;
+        let requisicao_id = parts
+            .extensions
+            .get::<RequisicaoId>()
+            .map(|id| id.0.clone())
+            .unwrap_or_else(|| ObjectId::new().to_hex());
+
+        Ok(Self {
+            repos: Repositorios::from_ref(state),
+            empresa_id: empresa.id(),
+            usuario_id: usuario.id,
+            usuario: usuario.email,
+            requisicao_id,
+        })
+    }
+}
+
+impl Auditor {
+    /// Autor das alterações feitas por uma rotina do servidor, fora de uma
+    /// requisição; `rotina` aparece no lugar do usuário.
+    pub fn sistema(repos: Repositorios, empresa_id: ObjectId, rotina: &str) -> Self {
+        Self {
+            repos,
+            empresa_id,
+            usuario_id: ObjectId::from_bytes([0; 12]),
+            usuario: rotina.to_string(),
+            requisicao_id: ObjectId::new().to_hex(),
+        }
+    }
+
+    pub async fn criacao<T: Auditavel>(&self, depois: &T) -> Result<()> {
+        self.registrar(OperacaoAuditoria::Criacao, None, Some(depois))
+            .await
+    }
+
+    /// Não grava nada quando nenhum campo mudou.
+    pub async fn alteracao<T: Auditavel>(&self, antes: &T, depois: &T) -> Result<()> {
+        self.registrar(OperacaoAuditoria::Alteracao, Some(antes), Some(depois))
+            .await
+    }
+
+    pub async fn exclusao<T: Auditavel>(&self, antes: &T) -> Result<()> {
+        self.registrar(OperacaoAuditoria::Exclusao, Some(antes), None)
+            .await
+    }
+
+    async fn registrar<T: Auditavel>(
+        &self,
+        operacao: OperacaoAuditoria,
+        antes: Option<&T>,
+        depois: Option<&T>,
+    ) -> Result<()> {
+        let Some(registro) = depois.or(antes) else {
+            return Ok(());
+        };
+        let entidade_id = registro
+            .id_auditado()
+            .ok_or_else(|| AppError::Internal(format!("{} auditado sem id", T::ENTIDADE)))?;
+
+        let alteracoes = diferencas(campos(antes)?, campos(depois)?);
+        if alteracoes.is_empty() {
+            return Ok(());
+        }
+
+        let registro = RegistroAuditoria {
+            id: None,
+            empresa_id: registro.empresa_auditada().unwrap_or(self.empresa_id),
+            entidade: T::ENTIDADE.to_string(),
+            entidade_id,
+            operacao,
+            usuario_id: self.usuario_id,
+            usuario: self.usuario.clone(),
+            requisicao_id: self.requisicao_id.clone(),
+            instante: Utc::now(),
+            alteracoes,
+        };
+        self.repos.auditoria.registrar(registro).await?;
+        Ok(())
+    }
+}
+
+/// Campos do registro pelo caminho com pontos, sem os nulos.
+fn campos<T: Serialize>(registro: Option<&T>) -> Result<BTreeMap<String, Value>> {
+    let mut campos = BTreeMap::new();
+    if let Some(registro) = registro {
+        let valor = serde_json::to_value(registro)
+            .map_err(|e| AppError::Internal(format!("Falha ao auditar: {}", e)))?;
+        if let Value::Object(objeto) = valor {
+            achatar("", objeto, &mut campos);
+        }
+    }
+    Ok(campos)
+}
+
+fn achatar(prefixo: &str, objeto: Map<String, Value>, campos: &mut BTreeMap<String, Value>) {
+    for (nome, valor) in objeto {
+        if prefixo.is_empty() && CAMPOS_IGNORADOS.contains(&nome.as_str()) {
+            continue;
+        }
+        let caminho = if prefixo.is_empty() {
+            nome
+        } else {
+            format!("{}.{}", prefixo, nome)
+        };
+        match normalizar(valor) {
+            Value::Null => {}
+            Value::Object(objeto) => achatar(&caminho, objeto, campos),
+            valor => {
+                campos.insert(caminho, valor);
+            }
+        }
+    }
+}
+
+/// Converte ids e instantes do formato estendido do BSON (`{"$oid": ...}`,
+/// `{"$date": ...}`) em texto.
+fn normalizar(valor: Value) -> Value {
+    match valor {
+        Value::Object(objeto) => match estendido(&objeto) {
+            Some(texto) => Value::String(texto),
+            None => Value::Object(
+                objeto
+                    .into_iter()
+                    .map(|(nome, valor)| (nome, normalizar(valor)))
+                    .collect(),
+            ),
+        },
+        Value::Array(itens) => Value::Array(itens.into_iter().map(normalizar).collect()),
+        valor => valor,
+    }
+}
+
+fn estendido(objeto: &Map<String, Value>) -> Option<String> {
+    if objeto.len() != 1 {
+        return None;
+    }
+    if let Some(id) = objeto.get("$oid") {
+        return id.as_str().map(str::to_string);
+    }
+    let data = objeto.get("$date")?;
+    let milissegundos = data
+        .get("$numberLong")
+        .and_then(Value::as_str)
+        .and_then(|texto| texto.parse().ok())
+        .or_else(|| data.as_i64())?;
+    DateTime::from_timestamp_millis(milissegundos).map(|instante| instante.to_rfc3339())
+}
+
+/// Valor gravado na trilha; os campos ocultos só registram que mudaram.
+fn registrado(campo: &str, valor: Option<Value>) -> Option<Value> {
+    let nome = campo.rsplit('.').next().unwrap_or(campo);
+    if CAMPOS_OCULTOS.contains(&nome) {
+        valor.map(|_| Value::String("[oculto]".to_string()))
+    } else {
+        valor
+    }
+}
+
+fn diferencas(
+    mut antes: BTreeMap<String, Value>,
+    depois: BTreeMap<String, Value>,
+) -> Vec<Alteracao> {
+    let mut alteracoes = Vec::new();
+    for (campo, novo) in depois {
+        let anterior = antes.remove(&campo);
+        if anterior.as_ref() != Some(&novo) {
+            alteracoes.push(Alteracao {
+                antes: registrado(&campo, anterior),
+                depois: registrado(&campo, Some(novo)),
+                campo,
+            });
+        }
+    }
+    alteracoes.extend(antes.into_iter().map(|(campo, anterior)| Alteracao {
+        antes: registrado(&campo, Some(anterior)),
+        depois: None,
+        campo,
+    }));
+    alteracoes.sort_by(|a, b| a.campo.cmp(&b.campo));
+    alteracoes
+}
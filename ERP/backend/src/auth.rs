@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts},
+    middleware::{self, Next},
+    response::Response,
+    Extension, Router,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    models::{Papel, Usuario},
+    repositorio::Repositorios,
+};
+
+/// Chaves de assinatura dos tokens, compartilhadas via `Extension`.
+#[derive(Clone)]
+pub struct ChavesJwt {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    validade: Duration,
+}
+
+impl ChavesJwt {
+    pub fn new(segredo: &str, validade: Duration) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(segredo.as_bytes()),
+            decoding: DecodingKey::from_secret(segredo.as_bytes()),
+            validade,
+        }
+    }
+}
+
+/// O token identifica apenas o usuário; papéis e empresas são lidos do
+/// cadastro a cada requisição, para que alterações e desativações valham
+/// na hora.
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    exp: i64,
+}
+
+/// Usuário do token da requisição. Usado como extractor nos handlers.
+#[derive(Debug, Clone)]
+pub struct UsuarioAutenticado {
+    pub id: ObjectId,
+    pub nome: String,
+    pub email: String,
+    pub papeis: Vec<Papel>,
+    pub empresas: Vec<ObjectId>,
+}
+
+impl UsuarioAutenticado {
+    /// Usuário cadastrado e ativo; os demais não se autenticam.
+    pub fn do_cadastro(usuario: Usuario) -> Result<Self> {
+        match usuario.id {
+            Some(id) if usuario.ativo => Ok(Self {
+                id,
+                nome: usuario.nome,
+                email: usuario.email,
+                papeis: usuario.papeis,
+                empresas: usuario.empresas,
+            }),
+            _ => Err(AppError::Unauthorized),
+        }
+    }
+
+    /// Administradores podem tudo; lista vazia exige apenas autenticação.
+    pub fn pode(&self, papeis: &[Papel]) -> bool {
+        papeis.is_empty()
+            || self.papeis.contains(&Papel::Admin)
+            || papeis.iter().any(|papel| self.papeis.contains(papel))
+    }
+
+    pub fn exigir(&self, papeis: &[Papel]) -> Result<()> {
+        if self.pode(papeis) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for UsuarioAutenticado {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<UsuarioAutenticado>()
+            .cloned()
+            .ok_or(AppError::Unauthorized)
+    }
+}
+
+pub fn hash_senha(senha: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(senha.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Internal(format!("Falha ao gerar hash da senha: {}", e)))
+}
+
+pub fn verificar_senha(senha: &str, senha_hash: &str) -> bool {
+    PasswordHash::new(senha_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(senha.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Emite um token assinado; retorna o token e o instante de expiração.
+pub fn emitir_token(
+    chaves: &ChavesJwt,
+    usuario: &Usuario,
+) -> Result<(String, chrono::DateTime<Utc>)> {
+    let expira_em = Utc::now() + chaves.validade;
+    let claims = Claims {
+        sub: usuario.id.map(|id| id.to_hex()).unwrap_or_default(),
+        exp: expira_em.timestamp(),
+    };
+
+    let token = jsonwebtoken::encode(&Header::default(), &claims, &chaves.encoding)
+        .map_err(|e| AppError::Internal(format!("Falha ao emitir token: {}", e)))?;
+    Ok((token, expira_em))
+}
+
+/// Valida o `Authorization: Bearer` quando presente e disponibiliza o
+/// usuário para os handlers, com os papéis e empresas atuais do cadastro.
+/// Tokens de usuários excluídos ou desativados são recusados. Requisições
+/// sem token seguem adiante e são barradas por [`exigir`] nas rotas
+/// protegidas.
+pub async fn autenticar(
+    State(repos): State<Repositorios>,
+    Extension(chaves): Extension<Arc<ChavesJwt>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|valor| valor.to_str().ok())
+        .and_then(|valor| valor.strip_prefix("Bearer "));
+
+    if let Some(token) = token {
+        let dados = jsonwebtoken::decode::<Claims>(token, &chaves.decoding, &Validation::default())
+            .map_err(|_| AppError::Unauthorized)?;
+        let id = ObjectId::parse_str(&dados.claims.sub).map_err(|_| AppError::Unauthorized)?;
+        let usuario = repos
+            .usuarios
+            .buscar(id)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+        req.extensions_mut()
+            .insert(UsuarioAutenticado::do_cadastro(usuario)?);
+    }
+
+    Ok(next.run(req).await)
+}
+
+/// Restringe todas as rotas do router aos papéis informados.
+pub fn exigir(router: Router, papeis: &'static [Papel]) -> Router {
+    router.route_layer(middleware::from_fn(move |req: Request, next: Next| {
+        autorizar(papeis, req, next)
+    }))
+}
+
+async fn autorizar(papeis: &'static [Papel], req: Request, next: Next) -> Result<Response> {
+    let usuario = req
+        .extensions()
+        .get::<UsuarioAutenticado>()
+        .ok_or(AppError::Unauthorized)?;
+    usuario.exigir(papeis)?;
+
+    Ok(next.run(req).await)
+}
+
+/// Cria o primeiro administrador a partir de `ADMIN_EMAIL`/`ADMIN_SENHA`
+/// quando ainda não há nenhum usuário cadastrado.
+pub async fn garantir_admin(repos: &Repositorios) -> anyhow::Result<()> {
+    if repos.usuarios.contar().await? > 0 {
+        return Ok(());
+    }
+
+    let (Ok(email), Ok(senha)) = (std::env::var("ADMIN_EMAIL"), std::env::var("ADMIN_SENHA"))
+    else {
+        tracing::warn!(
+            "Nenhum usuário cadastrado. Defina ADMIN_EMAIL e ADMIN_SENHA para criar o administrador inicial."
+        );
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    let admin = Usuario {
+        id: None,
+        nome: "Administrador".to_string(),
+        email: email.trim().to_lowercase(),
+        senha_hash: hash_senha(&senha).map_err(|e| anyhow::anyhow!(e.to_string()))?,
+        papeis: vec![Papel::Admin],
+        empresas: Vec::new(),
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+    };
+    let admin = repos.usuarios.criar(admin).await?;
+    tracing::info!("👤 Administrador inicial criado: {}", admin.email);
+
+    Ok(())
+}
+
+pub async fn find_usuario_por_email(repos: &Repositorios, email: &str) -> Result<Option<Usuario>> {
+    repos
+        .usuarios
+        .buscar_por_email(&email.trim().to_lowercase())
+        .await
+}
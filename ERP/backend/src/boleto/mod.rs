@@ -0,0 +1,598 @@
+//! Boletos de cobrança: nosso número pelas regras de cada banco, código de
+//! barras de 44 posições, linha digitável, o boleto em PDF e o arquivo de
+//! remessa CNAB 240.
+
+mod pdf;
+mod remessa;
+
+pub use pdf::pdf;
+pub use remessa::remessa;
+
+use chrono::NaiveDate;
+
+use crate::{
+    error::{AppError, Result},
+    models::{ContaBancaria, Dinheiro, Empresa},
+    validacao,
+};
+
+/// Código da moeda real no código de barras.
+const MOEDA_REAL: char = '9';
+/// Maior valor que cabe nas dez posições do código de barras, em centavos.
+const MAXIMO_CENTAVOS: i64 = 9_999_999_999;
+/// Dias após o vencimento para o banco dar baixa no título.
+const PRAZO_BAIXA: u32 = 60;
+/// Carteiras do Itaú cujo DAC do nosso número não inclui agência e conta.
+const CARTEIRAS_ITAU_SEM_CONTA: [&str; 5] = ["126", "131", "146", "150", "168"];
+
+/// Bancos que emitem boletos, pelo código FEBRABAN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BancoBoleto {
+    BancoDoBrasil,
+    Caixa,
+    Bradesco,
+    Itau,
+}
+
+impl BancoBoleto {
+    pub const SUPORTADOS: &'static str = "001, 104, 237 ou 341";
+
+    pub fn do_codigo(codigo: &str) -> Option<Self> {
+        match codigo.trim() {
+            "001" => Some(BancoBoleto::BancoDoBrasil),
+            "104" => Some(BancoBoleto::Caixa),
+            "237" => Some(BancoBoleto::Bradesco),
+            "341" => Some(BancoBoleto::Itau),
+            _ => None,
+        }
+    }
+
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            BancoBoleto::BancoDoBrasil => "001",
+            BancoBoleto::Caixa => "104",
+            BancoBoleto::Bradesco => "237",
+            BancoBoleto::Itau => "341",
+        }
+    }
+
+    /// Código com o dígito, como impresso no boleto.
+    pub fn codigo_com_dv(&self) -> &'static str {
+        match self {
+            BancoBoleto::BancoDoBrasil => "001-9",
+            BancoBoleto::Caixa => "104-0",
+            BancoBoleto::Bradesco => "237-2",
+            BancoBoleto::Itau => "341-7",
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            BancoBoleto::BancoDoBrasil => "BANCO DO BRASIL S.A.",
+            BancoBoleto::Caixa => "CAIXA ECONOMICA FEDERAL",
+            BancoBoleto::Bradesco => "BANCO BRADESCO S.A.",
+            BancoBoleto::Itau => "BANCO ITAU S.A.",
+        }
+    }
+
+    /// Algarismos da carteira.
+    pub fn tamanho_carteira(&self) -> usize {
+        match self {
+            BancoBoleto::Caixa => 1,
+            BancoBoleto::BancoDoBrasil | BancoBoleto::Bradesco => 2,
+            BancoBoleto::Itau => 3,
+        }
+    }
+
+    /// Algarismos do convênio (Banco do Brasil) ou do código do
+    /// beneficiário (Caixa); os demais bancos não o usam no nosso número.
+    pub fn tamanho_convenio(&self) -> Option<usize> {
+        match self {
+            BancoBoleto::BancoDoBrasil => Some(7),
+            BancoBoleto::Caixa => Some(6),
+            BancoBoleto::Bradesco | BancoBoleto::Itau => None,
+        }
+    }
+
+    /// Algarismos do sequencial do nosso número.
+    fn tamanho_sequencial(&self) -> u32 {
+        match self {
+            BancoBoleto::BancoDoBrasil => 10,
+            BancoBoleto::Caixa => 15,
+            BancoBoleto::Bradesco => 11,
+            BancoBoleto::Itau => 8,
+        }
+    }
+
+    pub fn maximo_sequencial(&self) -> u64 {
+        10u64.pow(self.tamanho_sequencial()) - 1
+    }
+
+    /// Algarismos da conta, sem o dígito, que cabem no campo livre (ou no
+    /// CNAB, quando a conta não entra no código de barras).
+    fn tamanho_conta(&self) -> usize {
+        match self {
+            BancoBoleto::Itau => 5,
+            BancoBoleto::Bradesco => 7,
+            BancoBoleto::BancoDoBrasil | BancoBoleto::Caixa => 12,
+        }
+    }
+
+    /// Versões do layout CNAB 240 do arquivo e do lote de cobrança.
+    pub fn versoes_cnab(&self) -> (&'static str, &'static str) {
+        match self {
+            BancoBoleto::BancoDoBrasil => ("083", "042"),
+            BancoBoleto::Caixa => ("107", "067"),
+            BancoBoleto::Bradesco => ("089", "045"),
+            BancoBoleto::Itau => ("040", "030"),
+        }
+    }
+}
+
+/// Empresa e conta que recebem os boletos, no formato que o banco espera.
+#[derive(Debug, Clone)]
+pub struct Beneficiario {
+    pub banco: BancoBoleto,
+    /// Quatro algarismos.
+    pub agencia: String,
+    pub agencia_dv: String,
+    /// Número da conta, sem o dígito; com zeros à esquerda quando entra
+    /// no campo livre.
+    pub conta: String,
+    pub conta_dv: String,
+    pub carteira: String,
+    /// Vazio nos bancos que não usam convênio.
+    pub convenio: String,
+    pub nome: String,
+    /// CNPJ ou CPF, só os algarismos.
+    pub documento: String,
+}
+
+impl Beneficiario {
+    /// Beneficiário da carteira de cobrança da conta. `BadRequest` quando a
+    /// conta não tem carteira, quando agência ou conta não cabem no layout
+    /// do banco ou quando a empresa não tem CNPJ/CPF.
+    pub fn new(conta: &ContaBancaria, empresa: &Empresa) -> Result<Self> {
+        let carteira = conta.boleto.as_ref().ok_or_else(|| {
+            AppError::BadRequest(
+                "A conta bancária não tem carteira de cobrança cadastrada".to_string(),
+            )
+        })?;
+        let banco = BancoBoleto::do_codigo(&carteira.banco).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Banco sem emissão de boletos: use {}",
+                BancoBoleto::SUPORTADOS
+            ))
+        })?;
+
+        let (agencia, agencia_dv) = numero_e_dv(&conta.agencia);
+        if agencia.is_empty() || agencia.len() > 4 {
+            return Err(AppError::BadRequest(
+                "A agência deve ter até 4 algarismos para emitir boletos".to_string(),
+            ));
+        }
+        let (numero, conta_dv) = numero_e_dv(&conta.conta);
+        if numero.is_empty() || numero.len() > banco.tamanho_conta() {
+            return Err(AppError::BadRequest(format!(
+                "A conta deve ter até {} algarismos, sem o dígito, para emitir boletos",
+                banco.tamanho_conta()
+            )));
+        }
+
+        let documento = empresa
+            .documento
+            .as_deref()
+            .filter(|documento| {
+                validacao::cnpj_valido(documento) || validacao::cpf_valido(documento)
+            })
+            .map(validacao::somente_digitos)
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    "Cadastre o CNPJ ou CPF da empresa para emitir boletos".to_string(),
+                )
+            })?;
+
+        Ok(Self {
+            banco,
+            agencia: format!("{:0>4}", agencia),
+            agencia_dv,
+            conta: match banco {
+                BancoBoleto::Bradesco | BancoBoleto::Itau => {
+                    format!("{:0>width$}", numero, width = banco.tamanho_conta())
+                }
+                BancoBoleto::BancoDoBrasil | BancoBoleto::Caixa => numero,
+            },
+            conta_dv,
+            carteira: carteira.carteira.clone(),
+            convenio: carteira.convenio.clone().unwrap_or_default(),
+            nome: empresa.nome.clone(),
+            documento,
+        })
+    }
+
+    /// Nosso número como vai na remessa e volta no retorno: com o dígito
+    /// no Bradesco e no Itaú, sem ele no Banco do Brasil e na Caixa.
+    pub fn nosso_numero(&self, sequencial: u64) -> String {
+        match self.banco {
+            BancoBoleto::BancoDoBrasil => format!("{}{:010}", self.convenio, sequencial),
+            BancoBoleto::Caixa => format!("{}4{:015}", self.carteira, sequencial),
+            BancoBoleto::Bradesco => {
+                let numero = format!("{:011}", sequencial);
+                let dv = dv_bradesco(&format!("{}{}", self.carteira, numero));
+                format!("{}{}", numero, dv)
+            }
+            BancoBoleto::Itau => {
+                let numero = format!("{:08}", sequencial);
+                format!("{}{}{}", self.carteira, numero, self.dac_itau(&numero))
+            }
+        }
+    }
+
+    /// Nosso número como impresso no boleto.
+    pub fn nosso_numero_impresso(&self, sequencial: u64) -> String {
+        let nosso_numero = self.nosso_numero(sequencial);
+        match self.banco {
+            BancoBoleto::BancoDoBrasil => nosso_numero,
+            BancoBoleto::Caixa => format!(
+                "{}/{}-{}",
+                &nosso_numero[..2],
+                &nosso_numero[2..],
+                dv_caixa(&nosso_numero)
+            ),
+            BancoBoleto::Bradesco => format!(
+                "{}/{}-{}",
+                self.carteira,
+                &nosso_numero[..11],
+                &nosso_numero[11..]
+            ),
+            BancoBoleto::Itau => format!(
+                "{}/{}-{}",
+                &nosso_numero[..3],
+                &nosso_numero[3..11],
+                &nosso_numero[11..]
+            ),
+        }
+    }
+
+    /// Agência e código do beneficiário, como impressos no boleto.
+    pub fn codigo_beneficiario(&self) -> String {
+        if self.banco == BancoBoleto::Caixa {
+            return format!(
+                "{}/{}-{}",
+                self.agencia,
+                self.convenio,
+                dv_caixa(&self.convenio)
+            );
+        }
+        let agencia = com_dv(&self.agencia, &self.agencia_dv);
+        match self.banco {
+            BancoBoleto::Itau => format!(
+                "{}/{}-{}",
+                agencia,
+                self.conta,
+                modulo10(&format!("{}{}", self.agencia, self.conta))
+            ),
+            _ => format!("{}/{}", agencia, com_dv(&self.conta, &self.conta_dv)),
+        }
+    }
+
+    /// Código de barras de 44 posições: banco, moeda, dígito geral, fator
+    /// de vencimento, valor e as 25 posições do campo livre do banco.
+    pub fn codigo_barras(
+        &self,
+        sequencial: u64,
+        valor: Dinheiro,
+        vencimento: NaiveDate,
+    ) -> Result<String> {
+        let centavos = valor.centavos();
+        if !(1..=MAXIMO_CENTAVOS).contains(&centavos) {
+            return Err(AppError::BadRequest(
+                "O valor do boleto deve estar entre 0,01 e 99.999.999,99".to_string(),
+            ));
+        }
+        let fator = fator_vencimento(vencimento).ok_or_else(|| {
+            AppError::BadRequest("Vencimento fora do intervalo aceito no boleto".to_string())
+        })?;
+
+        let sem_dv = format!(
+            "{}{}{:04}{:010}{}",
+            self.banco.codigo(),
+            MOEDA_REAL,
+            fator,
+            centavos,
+            self.campo_livre(sequencial)
+        );
+        let dv = dv_codigo_barras(&sem_dv);
+        Ok(format!("{}{}{}", &sem_dv[..4], dv, &sem_dv[4..]))
+    }
+
+    fn campo_livre(&self, sequencial: u64) -> String {
+        let nosso_numero = self.nosso_numero(sequencial);
+        match self.banco {
+            // Convênio de 7 posições: zeros, nosso número e carteira.
+            BancoBoleto::BancoDoBrasil => format!("000000{}{}", nosso_numero, self.carteira),
+            // SIGCB: o nosso número é intercalado com as duas constantes.
+            BancoBoleto::Caixa => {
+                let campo = format!(
+                    "{}{}{}{}{}{}{}",
+                    self.convenio,
+                    dv_caixa(&self.convenio),
+                    &nosso_numero[2..5],
+                    &nosso_numero[..1],
+                    &nosso_numero[5..8],
+                    &nosso_numero[1..2],
+                    &nosso_numero[8..]
+                );
+                let dv = dv_caixa(&campo);
+                format!("{}{}", campo, dv)
+            }
+            BancoBoleto::Bradesco => format!(
+                "{}{}{}{}0",
+                self.agencia,
+                self.carteira,
+                &nosso_numero[..11],
+                self.conta
+            ),
+            BancoBoleto::Itau => format!(
+                "{}{}{}{}000",
+                nosso_numero,
+                self.agencia,
+                self.conta,
+                modulo10(&format!("{}{}", self.agencia, self.conta))
+            ),
+        }
+    }
+
+    fn dac_itau(&self, numero: &str) -> u32 {
+        if CARTEIRAS_ITAU_SEM_CONTA.contains(&self.carteira.as_str()) {
+            modulo10(&format!("{}{}", self.carteira, numero))
+        } else {
+            modulo10(&format!(
+                "{}{}{}{}",
+                self.agencia, self.conta, self.carteira, numero
+            ))
+        }
+    }
+}
+
+/// Linha digitável do código de barras: três campos do campo livre com
+/// dígito módulo 10, o dígito geral e o fator de vencimento com o valor.
+pub fn linha_digitavel(codigo_barras: &str) -> String {
+    let campo_livre = &codigo_barras[19..];
+    let campo1 = format!("{}{}", &codigo_barras[..4], &campo_livre[..5]);
+    let campo2 = &campo_livre[5..15];
+    let campo3 = &campo_livre[15..];
+    let com_dv = |campo: &str| format!("{}{}", campo, modulo10(campo));
+
+    let (campo1, campo2, campo3) = (com_dv(&campo1), com_dv(campo2), com_dv(campo3));
+    format!(
+        "{}.{} {}.{} {}.{} {} {}",
+        &campo1[..5],
+        &campo1[5..],
+        &campo2[..5],
+        &campo2[5..],
+        &campo3[..5],
+        &campo3[5..],
+        &codigo_barras[4..5],
+        &codigo_barras[5..19]
+    )
+}
+
+/// Dias desde 07/10/1997, reiniciados em 1000 a cada 9000 dias (o fator
+/// 9999 foi 21/02/2025 e 22/02/2025 voltou a ser 1000).
+fn fator_vencimento(vencimento: NaiveDate) -> Option<i64> {
+    let base = NaiveDate::from_ymd_opt(1997, 10, 7)?;
+    let dias = (vencimento - base).num_days();
+    (dias >= 1000).then(|| (dias - 1000) % 9000 + 1000)
+}
+
+/// Número e dígito de agência ou conta escritas como `12345-6`.
+fn numero_e_dv(valor: &str) -> (String, String) {
+    let (numero, dv) = valor.split_once('-').unwrap_or((valor, ""));
+    (validacao::somente_digitos(numero), dv.trim().to_uppercase())
+}
+
+fn com_dv(numero: &str, dv: &str) -> String {
+    if dv.is_empty() {
+        numero.to_string()
+    } else {
+        format!("{}-{}", numero, dv)
+    }
+}
+
+fn algarismos(numero: &str) -> impl Iterator<Item = u32> + '_ {
+    numero.bytes().rev().map(|byte| u32::from(byte - b'0'))
+}
+
+/// Módulo 10: pesos 2 e 1 a partir da direita, somando os algarismos de
+/// cada produto.
+fn modulo10(numero: &str) -> u32 {
+    let soma: u32 = algarismos(numero)
+        .enumerate()
+        .map(|(posicao, algarismo)| {
+            let produto = algarismo * if posicao % 2 == 0 { 2 } else { 1 };
+            produto / 10 + produto % 10
+        })
+        .sum();
+    (10 - soma % 10) % 10
+}
+
+/// Resto da soma ponderada do módulo 11, com pesos de 2 até `peso_maximo`
+/// repetidos a partir da direita.
+fn resto_modulo11(numero: &str, peso_maximo: u32) -> u32 {
+    let soma: u32 = algarismos(numero)
+        .enumerate()
+        .map(|(posicao, algarismo)| algarismo * (2 + posicao as u32 % (peso_maximo - 1)))
+        .sum();
+    soma % 11
+}
+
+/// Dígito geral do código de barras: 0, 10 e 11 viram 1.
+fn dv_codigo_barras(numero: &str) -> u32 {
+    match 11 - resto_modulo11(numero, 9) {
+        0 | 1 | 10 | 11 => 1,
+        dv => dv,
+    }
+}
+
+/// Dígitos da Caixa (beneficiário, nosso número e campo livre): acima de
+/// 9 vira 0.
+fn dv_caixa(numero: &str) -> u32 {
+    match 11 - resto_modulo11(numero, 9) {
+        dv if dv > 9 => 0,
+        dv => dv,
+    }
+}
+
+/// Dígito do nosso número do Bradesco, módulo 11 na base 7: resto 1 vira
+/// `P`.
+fn dv_bradesco(numero: &str) -> String {
+    match resto_modulo11(numero, 7) {
+        0 => "0".to_string(),
+        1 => "P".to_string(),
+        resto => (11 - resto).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beneficiario(
+        banco: BancoBoleto,
+        agencia: &str,
+        conta: &str,
+        carteira: &str,
+    ) -> Beneficiario {
+        Beneficiario {
+            banco,
+            agencia: agencia.to_string(),
+            agencia_dv: String::new(),
+            conta: conta.to_string(),
+            conta_dv: String::new(),
+            carteira: carteira.to_string(),
+            convenio: match banco {
+                BancoBoleto::BancoDoBrasil => "1234567".to_string(),
+                BancoBoleto::Caixa => "123456".to_string(),
+                BancoBoleto::Bradesco | BancoBoleto::Itau => String::new(),
+            },
+            nome: "Empresa".to_string(),
+            documento: "11222333000181".to_string(),
+        }
+    }
+
+    fn data(ano: i32, mes: u32, dia: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(ano, mes, dia).unwrap()
+    }
+
+    /// Código de barras e linha digitável de um boleto de R$ 150,75 com
+    /// vencimento em 10/03/2025 (fator 1016) e sequencial 42.
+    fn boleto(beneficiario: &Beneficiario) -> (String, String) {
+        let codigo = beneficiario
+            .codigo_barras(42, "150.75".parse().unwrap(), data(2025, 3, 10))
+            .unwrap();
+        let linha = linha_digitavel(&codigo);
+        (codigo, linha)
+    }
+
+    #[test]
+    fn banco_do_brasil() {
+        let bb = beneficiario(BancoBoleto::BancoDoBrasil, "1234", "123456", "17");
+        assert_eq!(bb.nosso_numero(42), "12345670000000042");
+        assert_eq!(
+            boleto(&bb),
+            (
+                "00191101600000150750000001234567000000004217".to_string(),
+                "00190.00009 01234.567004 00000.042176 1 10160000015075".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn caixa() {
+        let caixa = beneficiario(BancoBoleto::Caixa, "1234", "123456", "1");
+        assert_eq!(caixa.nosso_numero(42), "14000000000000042");
+        assert_eq!(caixa.nosso_numero_impresso(42), "14/000000000000042-1");
+        assert_eq!(caixa.codigo_beneficiario(), "1234/123456-0");
+        assert_eq!(
+            boleto(&caixa),
+            (
+                "10491101600000150751234560000100040000000420".to_string(),
+                "10491.23456 60000.100044 00000.004200 1 10160000015075".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn bradesco() {
+        let bradesco = beneficiario(BancoBoleto::Bradesco, "1234", "0012345", "09");
+        assert_eq!(bradesco.nosso_numero_impresso(42), "09/00000000042-9");
+        assert_eq!(
+            boleto(&bradesco),
+            (
+                "23796101600000150751234090000000004200123450".to_string(),
+                "23791.23405 90000.000001 42001.234501 6 10160000015075".to_string()
+            )
+        );
+
+        // Exemplo do manual do Bradesco: carteira 19, nosso número 2.
+        let manual = beneficiario(BancoBoleto::Bradesco, "1234", "0012345", "19");
+        assert_eq!(manual.nosso_numero_impresso(2), "19/00000000002-8");
+    }
+
+    #[test]
+    fn itau() {
+        let itau = beneficiario(BancoBoleto::Itau, "0057", "12345", "109");
+        assert_eq!(itau.nosso_numero_impresso(42), "109/00000042-0");
+        assert_eq!(itau.codigo_beneficiario(), "0057/12345-7");
+        assert_eq!(
+            boleto(&itau),
+            (
+                "34194101600000150751090000004200057123457000".to_string(),
+                "34191.09008 00004.200051 71234.570001 4 10160000015075".to_string()
+            )
+        );
+
+        // Exemplo do manual do Itaú: agência 0057, conta 12345, carteira
+        // 110 e nosso número 12345678, com DAC 8.
+        let manual = beneficiario(BancoBoleto::Itau, "0057", "12345", "110");
+        assert_eq!(manual.nosso_numero_impresso(12345678), "110/12345678-8");
+    }
+
+    #[test]
+    fn fator_de_vencimento_reinicia_em_1000() {
+        assert_eq!(fator_vencimento(data(2000, 7, 3)), Some(1000));
+        assert_eq!(fator_vencimento(data(2025, 2, 21)), Some(9999));
+        assert_eq!(fator_vencimento(data(2025, 2, 22)), Some(1000));
+        assert_eq!(fator_vencimento(data(2000, 7, 2)), None);
+
+        let itau = beneficiario(BancoBoleto::Itau, "0057", "12345", "109");
+        let centavo = "0.01".parse().unwrap();
+        let antes = itau.codigo_barras(42, centavo, data(2025, 2, 21)).unwrap();
+        let depois = itau.codigo_barras(42, centavo, data(2025, 2, 22)).unwrap();
+        assert_eq!(antes, "34191999900000000011090000004200057123457000");
+        assert_eq!(depois, "34197100000000000011090000004200057123457000");
+        assert_eq!(
+            linha_digitavel(&depois),
+            "34191.09008 00004.200051 71234.570001 7 10000000000001"
+        );
+    }
+
+    #[test]
+    fn modulos() {
+        assert_eq!(modulo10("00571234511012345678"), 8);
+        assert_eq!(modulo10("001900000"), 9);
+        assert_eq!(dv_bradesco("1900000000002"), "8");
+        assert_eq!(dv_caixa("123456"), 0);
+    }
+
+    #[test]
+    fn valor_fora_do_limite() {
+        let itau = beneficiario(BancoBoleto::Itau, "0057", "12345", "109");
+        let vencimento = data(2025, 3, 10);
+        assert!(itau.codigo_barras(1, Dinheiro::ZERO, vencimento).is_err());
+        assert!(itau
+            .codigo_barras(1, "100000000.00".parse().unwrap(), vencimento)
+            .is_err());
+    }
+}
@@ -0,0 +1,410 @@
+//! Boleto em PDF (A4 retrato): recibo do pagador no alto e, abaixo do
+//! picote, a ficha de compensação com o código de barras.
+
+use printpdf::{
+    path::PaintMode, BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Rect,
+};
+
+use super::{Beneficiario, PRAZO_BAIXA};
+use crate::{
+    error::{AppError, Result},
+    models::Boleto,
+    validacao,
+};
+
+const PAGINA_LARGURA: Mm = Mm(210.0);
+const PAGINA_ALTURA: Mm = Mm(297.0);
+const MARGEM: f32 = 10.0;
+const LARGURA_UTIL: f32 = 190.0;
+/// Largura da coluna da direita da ficha (vencimento, valores...).
+const COLUNA_DIREITA: f32 = 50.0;
+const ALTURA_CAMPO: f32 = 9.0;
+const TAMANHO_ROTULO: f32 = 6.0;
+const TAMANHO_VALOR: f32 = 9.0;
+
+/// Barra estreita do código de barras; a larga tem três vezes essa
+/// largura, o que dá os 103 mm do padrão FEBRABAN.
+const BARRA_ESTREITA: f32 = 0.254;
+const ALTURA_BARRAS: f32 = 13.0;
+/// Larguras das barras e espaços de cada algarismo no intercalado 2 de 5
+/// (1 estreita, 3 larga).
+const PADROES_ITF: [[u32; 5]; 10] = [
+    [1, 1, 3, 3, 1],
+    [3, 1, 1, 1, 3],
+    [1, 3, 1, 1, 3],
+    [3, 3, 1, 1, 1],
+    [1, 1, 3, 1, 3],
+    [3, 1, 3, 1, 1],
+    [1, 3, 3, 1, 1],
+    [1, 1, 1, 3, 3],
+    [3, 1, 1, 3, 1],
+    [1, 3, 1, 3, 1],
+];
+
+pub fn pdf(beneficiario: &Beneficiario, boleto: &Boleto) -> Result<Vec<u8>> {
+    let titulo = format!("Boleto {}", boleto.numero_documento);
+    let (documento, pagina, camada) =
+        PdfDocument::new(&titulo, PAGINA_LARGURA, PAGINA_ALTURA, "Boleto");
+    let folha = Folha {
+        camada: documento.get_page(pagina).get_layer(camada),
+        fonte: documento
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(erro_pdf)?,
+        negrito: documento
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(erro_pdf)?,
+    };
+    folha.camada.set_outline_thickness(0.5);
+
+    let dados = Dados::new(beneficiario, boleto);
+    recibo_do_pagador(&folha, &dados);
+    picote(&folha, 88.0);
+    ficha_de_compensacao(&folha, &dados, 98.0);
+
+    documento.save_to_bytes().map_err(erro_pdf)
+}
+
+/// Textos impressos nas duas partes do boleto.
+struct Dados<'a> {
+    beneficiario: &'a Beneficiario,
+    boleto: &'a Boleto,
+    nosso_numero: String,
+    vencimento: String,
+    valor: String,
+    pagador: String,
+}
+
+impl<'a> Dados<'a> {
+    fn new(beneficiario: &'a Beneficiario, boleto: &'a Boleto) -> Self {
+        Self {
+            beneficiario,
+            boleto,
+            nosso_numero: beneficiario.nosso_numero_impresso(boleto.sequencial),
+            vencimento: boleto.vencimento.format("%d/%m/%Y").to_string(),
+            valor: boleto.valor.formatado(),
+            pagador: format!(
+                "{} - {} {}",
+                boleto.pagador.nome,
+                tipo_documento(&boleto.pagador.documento),
+                validacao::documento_formatado(&boleto.pagador.documento)
+            ),
+        }
+    }
+
+    fn identificacao_beneficiario(&self) -> String {
+        format!(
+            "{} - {} {}",
+            self.beneficiario.nome,
+            tipo_documento(&self.beneficiario.documento),
+            validacao::documento_formatado(&self.beneficiario.documento)
+        )
+    }
+}
+
+fn recibo_do_pagador(folha: &Folha, dados: &Dados) {
+    let mut y = MARGEM;
+    cabecalho(folha, dados, y);
+    y += 10.0;
+
+    let largura_esquerda = LARGURA_UTIL - COLUNA_DIREITA;
+    folha.campo(
+        MARGEM,
+        y,
+        largura_esquerda,
+        "Beneficiário",
+        &dados.identificacao_beneficiario(),
+    );
+    folha.campo(
+        MARGEM + largura_esquerda,
+        y,
+        COLUNA_DIREITA,
+        "Agência/Código do beneficiário",
+        &dados.beneficiario.codigo_beneficiario(),
+    );
+    y += ALTURA_CAMPO;
+
+    folha.campo(MARGEM, y, largura_esquerda, "Pagador", &dados.pagador);
+    folha.campo(
+        MARGEM + largura_esquerda,
+        y,
+        COLUNA_DIREITA,
+        "Nosso número",
+        &dados.nosso_numero,
+    );
+    y += ALTURA_CAMPO;
+
+    let terco = largura_esquerda / 3.0;
+    folha.campo(
+        MARGEM,
+        y,
+        terco,
+        "Nº do documento",
+        &dados.boleto.numero_documento,
+    );
+    folha.campo(MARGEM + terco, y, terco, "Espécie", "R$");
+    folha.campo(
+        MARGEM + 2.0 * terco,
+        y,
+        terco,
+        "Vencimento",
+        &dados.vencimento,
+    );
+    folha.campo(
+        MARGEM + largura_esquerda,
+        y,
+        COLUNA_DIREITA,
+        "Valor do documento",
+        &dados.valor,
+    );
+    y += ALTURA_CAMPO;
+
+    folha.texto("Recibo do Pagador", 8.0, MARGEM, y + 5.0, true);
+    folha.texto(
+        "Autenticação mecânica",
+        TAMANHO_ROTULO,
+        MARGEM + largura_esquerda,
+        y + 5.0,
+        false,
+    );
+}
+
+fn ficha_de_compensacao(folha: &Folha, dados: &Dados, topo: f32) {
+    let mut y = topo;
+    cabecalho(folha, dados, y);
+    y += 10.0;
+
+    let esquerda = LARGURA_UTIL - COLUNA_DIREITA;
+    let direita = MARGEM + esquerda;
+    let boleto = dados.boleto;
+    let emissao = boleto.created_at.format("%d/%m/%Y").to_string();
+
+    folha.campo(
+        MARGEM,
+        y,
+        esquerda,
+        "Local de pagamento",
+        "Pagável em qualquer banco até o vencimento",
+    );
+    folha.campo(direita, y, COLUNA_DIREITA, "Vencimento", &dados.vencimento);
+    y += ALTURA_CAMPO;
+
+    folha.campo(
+        MARGEM,
+        y,
+        esquerda,
+        "Beneficiário",
+        &dados.identificacao_beneficiario(),
+    );
+    folha.campo(
+        direita,
+        y,
+        COLUNA_DIREITA,
+        "Agência/Código do beneficiário",
+        &dados.beneficiario.codigo_beneficiario(),
+    );
+    y += ALTURA_CAMPO;
+
+    let colunas = [30.0, 35.0, 20.0, 15.0, 40.0];
+    let valores = [
+        ("Data do documento", emissao.as_str()),
+        ("Nº do documento", boleto.numero_documento.as_str()),
+        ("Espécie doc.", "DM"),
+        ("Aceite", "N"),
+        ("Data do processamento", emissao.as_str()),
+    ];
+    folha.linha_de_campos(y, &colunas, &valores);
+    folha.campo(
+        direita,
+        y,
+        COLUNA_DIREITA,
+        "Nosso número",
+        &dados.nosso_numero,
+    );
+    y += ALTURA_CAMPO;
+
+    let valores = [
+        ("Uso do banco", ""),
+        ("Carteira", dados.beneficiario.carteira.as_str()),
+        ("Espécie", "R$"),
+        ("Quantidade", ""),
+        ("Valor", ""),
+    ];
+    folha.linha_de_campos(y, &colunas, &valores);
+    folha.campo(
+        direita,
+        y,
+        COLUNA_DIREITA,
+        "(=) Valor do documento",
+        &dados.valor,
+    );
+    y += ALTURA_CAMPO;
+
+    let altura_instrucoes = 3.0 * ALTURA_CAMPO;
+    folha.caixa(MARGEM, y, esquerda, altura_instrucoes);
+    folha.texto(
+        "Instruções (texto de responsabilidade do beneficiário)",
+        TAMANHO_ROTULO,
+        MARGEM + 1.0,
+        y + 2.5,
+        false,
+    );
+    folha.texto(
+        &format!("Não receber após {} dias do vencimento.", PRAZO_BAIXA),
+        TAMANHO_VALOR,
+        MARGEM + 1.0,
+        y + 8.0,
+        false,
+    );
+    for (indice, rotulo) in [
+        "(-) Desconto/Abatimento",
+        "(+) Mora/Multa",
+        "(=) Valor cobrado",
+    ]
+    .iter()
+    .enumerate()
+    {
+        folha.campo(
+            direita,
+            y + indice as f32 * ALTURA_CAMPO,
+            COLUNA_DIREITA,
+            rotulo,
+            "",
+        );
+    }
+    y += altura_instrucoes;
+
+    let pagador = &boleto.pagador;
+    let endereco = [
+        pagador.endereco.as_deref(),
+        pagador.cidade.as_deref(),
+        pagador.estado.as_deref(),
+        pagador.cep.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|parte| !parte.trim().is_empty())
+    .collect::<Vec<_>>()
+    .join(" - ");
+    folha.caixa(MARGEM, y, LARGURA_UTIL, 14.0);
+    folha.texto("Pagador", TAMANHO_ROTULO, MARGEM + 1.0, y + 2.5, false);
+    folha.texto(&dados.pagador, TAMANHO_VALOR, MARGEM + 1.0, y + 7.0, false);
+    folha.texto(&endereco, TAMANHO_VALOR, MARGEM + 1.0, y + 11.5, false);
+    y += 14.0;
+
+    folha.texto(
+        "Autenticação mecânica - Ficha de Compensação",
+        TAMANHO_ROTULO,
+        direita,
+        y + 3.0,
+        false,
+    );
+    folha.barras(&boleto.codigo_barras, MARGEM, y + 5.0);
+}
+
+/// Nome do banco, código com o dígito e a linha digitável.
+fn cabecalho(folha: &Folha, dados: &Dados, y: f32) {
+    let banco = dados.beneficiario.banco;
+    folha.texto(banco.nome(), 10.0, MARGEM, y + 7.0, true);
+    folha.texto(banco.codigo_com_dv(), 14.0, MARGEM + 62.0, y + 7.5, true);
+    folha.texto(
+        &dados.boleto.linha_digitavel,
+        10.0,
+        MARGEM + 82.0,
+        y + 7.0,
+        true,
+    );
+    folha.caixa(MARGEM, y + 10.0, LARGURA_UTIL, 0.0);
+}
+
+/// Linha tracejada de corte.
+fn picote(folha: &Folha, y: f32) {
+    let mut x = MARGEM;
+    while x < MARGEM + LARGURA_UTIL {
+        folha.caixa(x, y, 2.0, 0.0);
+        x += 3.0;
+    }
+}
+
+struct Folha {
+    camada: PdfLayerReference,
+    fonte: IndirectFontRef,
+    negrito: IndirectFontRef,
+}
+
+impl Folha {
+    /// `y` em milímetros a partir do alto da página.
+    fn texto(&self, texto: &str, tamanho: f32, x: f32, y: f32, negrito: bool) {
+        let fonte = if negrito { &self.negrito } else { &self.fonte };
+        self.camada
+            .use_text(texto, tamanho, Mm(x), Mm(PAGINA_ALTURA.0 - y), fonte);
+    }
+
+    /// Contorno do retângulo com o canto superior esquerdo em (`x`, `y`).
+    fn caixa(&self, x: f32, y: f32, largura: f32, altura: f32) {
+        let topo = PAGINA_ALTURA.0 - y;
+        self.camada.add_rect(
+            Rect::new(Mm(x), Mm(topo - altura), Mm(x + largura), Mm(topo))
+                .with_mode(PaintMode::Stroke),
+        );
+    }
+
+    /// Campo com rótulo pequeno e valor.
+    fn campo(&self, x: f32, y: f32, largura: f32, rotulo: &str, valor: &str) {
+        self.caixa(x, y, largura, ALTURA_CAMPO);
+        self.texto(rotulo, TAMANHO_ROTULO, x + 1.0, y + 2.5, false);
+        self.texto(valor, TAMANHO_VALOR, x + 1.0, y + 7.5, false);
+    }
+
+    fn linha_de_campos(&self, y: f32, larguras: &[f32], campos: &[(&str, &str)]) {
+        let mut x = MARGEM;
+        for (largura, (rotulo, valor)) in larguras.iter().zip(campos) {
+            self.campo(x, y, *largura, rotulo, valor);
+            x += largura;
+        }
+    }
+
+    /// Código de barras intercalado 2 de 5: início, os algarismos aos
+    /// pares (o primeiro nas barras, o segundo nos espaços) e fim.
+    fn barras(&self, codigo: &str, x: f32, y: f32) {
+        let algarismos: Vec<usize> = codigo
+            .bytes()
+            .map(|byte| usize::from(byte - b'0'))
+            .collect();
+        let mut larguras = vec![1, 1, 1, 1];
+        for par in algarismos.chunks(2) {
+            let (barras, espacos) = (PADROES_ITF[par[0]], PADROES_ITF[par[1]]);
+            for (barra, espaco) in barras.iter().zip(espacos) {
+                larguras.push(*barra);
+                larguras.push(espaco);
+            }
+        }
+        larguras.extend([3, 1, 1]);
+
+        let base = PAGINA_ALTURA.0 - y - ALTURA_BARRAS;
+        let mut inicio = x;
+        for (indice, largura) in larguras.into_iter().enumerate() {
+            let fim = inicio + largura as f32 * BARRA_ESTREITA;
+            if indice % 2 == 0 {
+                self.camada.add_rect(Rect::new(
+                    Mm(inicio),
+                    Mm(base),
+                    Mm(fim),
+                    Mm(base + ALTURA_BARRAS),
+                ));
+            }
+            inicio = fim;
+        }
+    }
+}
+
+fn tipo_documento(documento: &str) -> &'static str {
+    if documento.len() == 11 {
+        "CPF"
+    } else {
+        "CNPJ"
+    }
+}
+
+fn erro_pdf(erro: printpdf::Error) -> AppError {
+    AppError::Internal(format!("Erro ao gerar o PDF: {}", erro))
+}
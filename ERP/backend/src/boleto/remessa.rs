@@ -0,0 +1,262 @@
+//! Remessa de cobrança no layout FEBRABAN 240: header de arquivo, um lote
+//! com os segmentos P (título) e Q (pagador) de cada boleto e os trailers.
+//! O retorno do mesmo layout é lido em `importacao::cnab`.
+
+use chrono::NaiveDateTime;
+
+use super::{Beneficiario, PRAZO_BAIXA};
+use crate::{models::Boleto, validacao};
+
+const TAMANHO_REGISTRO: usize = 240;
+const LOTE: u32 = 1;
+/// Duplicata mercantil.
+const ESPECIE_TITULO: &str = "02";
+
+/// Arquivo de remessa `numero` da conta, com os boletos na ordem dada.
+/// Linhas terminadas em CRLF, como os bancos esperam.
+pub fn remessa(
+    beneficiario: &Beneficiario,
+    numero: u32,
+    gerada_em: NaiveDateTime,
+    boletos: &[Boleto],
+) -> String {
+    let mut registros = vec![
+        header_arquivo(beneficiario, numero, gerada_em),
+        header_lote(beneficiario, numero, gerada_em),
+    ];
+    for (indice, boleto) in boletos.iter().enumerate() {
+        let sequencia = 2 * indice as u32 + 1;
+        registros.push(segmento_p(beneficiario, boleto, sequencia));
+        registros.push(segmento_q(beneficiario, boleto, sequencia + 1));
+    }
+    registros.push(trailer_lote(beneficiario, boletos));
+    registros.push(trailer_arquivo(beneficiario, boletos.len()));
+
+    let mut arquivo = registros.join("\r\n");
+    arquivo.push_str("\r\n");
+    arquivo
+}
+
+/// Registro posicional, montado campo a campo na ordem do layout.
+struct Registro(String);
+
+impl Registro {
+    fn new() -> Self {
+        Self(String::with_capacity(TAMANHO_REGISTRO))
+    }
+
+    /// Campo numérico: zeros à esquerda.
+    fn numero(mut self, valor: impl ToString, tamanho: usize) -> Self {
+        let valor = validacao::somente_digitos(&valor.to_string());
+        let inicio = valor.len().saturating_sub(tamanho);
+        self.0.push_str(&format!("{:0>tamanho$}", &valor[inicio..]));
+        self
+    }
+
+    /// Campo alfanumérico: maiúsculas sem acento, brancos à direita.
+    fn texto(mut self, valor: &str, tamanho: usize) -> Self {
+        let valor: String = validacao::sem_acentos(valor.trim())
+            .to_uppercase()
+            .chars()
+            .take(tamanho)
+            .collect();
+        self.0.push_str(&format!("{:<tamanho$}", valor));
+        self
+    }
+
+    fn brancos(self, tamanho: usize) -> Self {
+        self.texto("", tamanho)
+    }
+
+    fn zeros(self, tamanho: usize) -> Self {
+        self.numero(0, tamanho)
+    }
+
+    fn concluir(self) -> String {
+        debug_assert_eq!(self.0.len(), TAMANHO_REGISTRO);
+        self.0
+    }
+}
+
+/// Banco, lote e tipo de registro: as oito primeiras posições.
+fn controle(beneficiario: &Beneficiario, lote: u32, tipo: u32) -> Registro {
+    Registro::new()
+        .numero(beneficiario.banco.codigo(), 3)
+        .numero(lote, 4)
+        .numero(tipo, 1)
+}
+
+/// 1 para CPF, 2 para CNPJ.
+fn tipo_inscricao(documento: &str) -> u32 {
+    if documento.len() == 11 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Agência, conta e dígitos do beneficiário (20 posições).
+fn conta_corrente(registro: Registro, beneficiario: &Beneficiario) -> Registro {
+    registro
+        .numero(&beneficiario.agencia, 5)
+        .texto(&beneficiario.agencia_dv, 1)
+        .numero(&beneficiario.conta, 12)
+        .texto(&beneficiario.conta_dv, 1)
+        .brancos(1)
+}
+
+fn header_arquivo(beneficiario: &Beneficiario, numero: u32, gerada_em: NaiveDateTime) -> String {
+    let (versao_arquivo, _) = beneficiario.banco.versoes_cnab();
+    let registro = controle(beneficiario, 0, 0)
+        .brancos(9)
+        .numero(tipo_inscricao(&beneficiario.documento), 1)
+        .numero(&beneficiario.documento, 14)
+        .texto(&beneficiario.convenio, 20);
+    conta_corrente(registro, beneficiario)
+        .texto(&beneficiario.nome, 30)
+        .texto(beneficiario.banco.nome(), 30)
+        .brancos(10)
+        // Remessa.
+        .numero(1, 1)
+        .numero(gerada_em.format("%d%m%Y"), 8)
+        .numero(gerada_em.format("%H%M%S"), 6)
+        .numero(numero, 6)
+        .numero(versao_arquivo, 3)
+        .zeros(5)
+        .brancos(20)
+        .brancos(20)
+        .brancos(29)
+        .concluir()
+}
+
+fn header_lote(beneficiario: &Beneficiario, numero: u32, gerada_em: NaiveDateTime) -> String {
+    let (_, versao_lote) = beneficiario.banco.versoes_cnab();
+    let registro = controle(beneficiario, LOTE, 1)
+        // Remessa de cobrança.
+        .texto("R", 1)
+        .numero(1, 2)
+        .brancos(2)
+        .numero(versao_lote, 3)
+        .brancos(1)
+        .numero(tipo_inscricao(&beneficiario.documento), 1)
+        .numero(&beneficiario.documento, 15)
+        .texto(&beneficiario.convenio, 20);
+    conta_corrente(registro, beneficiario)
+        .texto(&beneficiario.nome, 30)
+        .brancos(40)
+        .brancos(40)
+        .numero(numero, 8)
+        .numero(gerada_em.format("%d%m%Y"), 8)
+        .zeros(8)
+        .brancos(33)
+        .concluir()
+}
+
+/// Início dos registros de detalhe: controle, sequência no lote e segmento.
+fn detalhe(beneficiario: &Beneficiario, sequencia: u32, segmento: &str) -> Registro {
+    controle(beneficiario, LOTE, 3)
+        .numero(sequencia, 5)
+        .texto(segmento, 1)
+        .brancos(1)
+        // Entrada de título.
+        .numero(1, 2)
+}
+
+fn segmento_p(beneficiario: &Beneficiario, boleto: &Boleto, sequencia: u32) -> String {
+    let registro = detalhe(beneficiario, sequencia, "P");
+    conta_corrente(registro, beneficiario)
+        .texto(&boleto.nosso_numero, 20)
+        // Cobrança simples, com registro, tradicional, emitido e
+        // distribuído pelo beneficiário.
+        .numero(1, 1)
+        .numero(1, 1)
+        .numero(1, 1)
+        .numero(2, 1)
+        .numero(2, 1)
+        .texto(&boleto.numero_documento, 15)
+        .numero(boleto.vencimento.format("%d%m%Y"), 8)
+        .numero(boleto.valor.centavos(), 15)
+        .zeros(5)
+        .zeros(1)
+        .numero(ESPECIE_TITULO, 2)
+        .texto("N", 1)
+        .numero(boleto.created_at.format("%d%m%Y"), 8)
+        // Sem juros, desconto, IOF ou abatimento.
+        .numero(3, 1)
+        .zeros(8)
+        .zeros(15)
+        .zeros(1)
+        .zeros(8)
+        .zeros(15)
+        .zeros(15)
+        .zeros(15)
+        // Identificação do título na empresa: o id do boleto.
+        .texto(&boleto.id.map(|id| id.to_hex()).unwrap_or_default(), 25)
+        // Não protestar; baixar após o prazo.
+        .numero(3, 1)
+        .zeros(2)
+        .numero(1, 1)
+        .numero(PRAZO_BAIXA, 3)
+        // Real.
+        .numero(9, 2)
+        .zeros(10)
+        .brancos(1)
+        .concluir()
+}
+
+fn segmento_q(beneficiario: &Beneficiario, boleto: &Boleto, sequencia: u32) -> String {
+    let pagador = &boleto.pagador;
+    let cep = validacao::somente_digitos(pagador.cep.as_deref().unwrap_or_default());
+    let cep = format!("{:0<8}", cep);
+
+    detalhe(beneficiario, sequencia, "Q")
+        .numero(tipo_inscricao(&pagador.documento), 1)
+        .numero(&pagador.documento, 15)
+        .texto(&pagador.nome, 40)
+        .texto(pagador.endereco.as_deref().unwrap_or_default(), 40)
+        // Bairro.
+        .brancos(15)
+        .numero(&cep[..5], 5)
+        .numero(&cep[5..8], 3)
+        .texto(pagador.cidade.as_deref().unwrap_or_default(), 15)
+        .texto(pagador.estado.as_deref().unwrap_or_default(), 2)
+        // Sem sacador/avalista nem banco correspondente.
+        .zeros(1)
+        .zeros(15)
+        .brancos(40)
+        .zeros(3)
+        .brancos(20)
+        .brancos(8)
+        .concluir()
+}
+
+fn trailer_lote(beneficiario: &Beneficiario, boletos: &[Boleto]) -> String {
+    let total: i64 = boletos.iter().map(|boleto| boleto.valor.centavos()).sum();
+
+    controle(beneficiario, LOTE, 5)
+        .brancos(9)
+        // Header, dois segmentos por boleto e trailer.
+        .numero(2 * boletos.len() + 2, 6)
+        .numero(boletos.len(), 6)
+        .numero(total, 17)
+        // Cobranças vinculada, caucionada e descontada.
+        .zeros(6)
+        .zeros(17)
+        .zeros(6)
+        .zeros(17)
+        .zeros(6)
+        .zeros(17)
+        .brancos(8)
+        .brancos(117)
+        .concluir()
+}
+
+fn trailer_arquivo(beneficiario: &Beneficiario, boletos: usize) -> String {
+    controle(beneficiario, 9999, 9)
+        .brancos(9)
+        .numero(1, 6)
+        .numero(2 * boletos + 4, 6)
+        .zeros(6)
+        .brancos(205)
+        .concluir()
+}
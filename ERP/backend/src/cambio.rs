@@ -0,0 +1,118 @@
+//! Conversão entre moedas pelas cotações gravadas. As cotações são em
+//! reais por unidade da moeda; a conversão entre duas moedas estrangeiras
+//! passa pelo real. Nos dias sem cotação vale a anterior mais recente.
+//!
+//! A contabilidade é sempre em reais: as contas bancárias em moeda
+//! estrangeira entram nas partidas pelo valor convertido.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    error::{AppError, Result},
+    models::{Dinheiro, Moeda, TaxaCambio},
+    repositorio::Repositorios,
+};
+
+/// Reais por unidade da moeda na data.
+pub async fn taxa(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    moeda: Moeda,
+    data: NaiveDate,
+) -> Result<TaxaCambio> {
+    if moeda == Moeda::Brl {
+        return Ok(TaxaCambio::UM);
+    }
+    repos
+        .cotacoes
+        .na_data(empresa_id, moeda, data)
+        .await?
+        .map(|cotacao| cotacao.taxa)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Não há cotação de {} em {} ou antes; cadastre-a em /financeiro/cotacoes",
+                moeda,
+                data.format("%d/%m/%Y")
+            ))
+        })
+}
+
+/// Valor em reais na data.
+pub async fn em_reais(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    valor: Dinheiro,
+    moeda: Moeda,
+    data: NaiveDate,
+) -> Result<Dinheiro> {
+    Ok(taxa(repos, empresa_id, moeda, data).await?.converter(valor))
+}
+
+/// Converte valores para uma moeda, guardando as cotações já lidas. Usado
+/// pelos relatórios, que convertem muitos valores nas mesmas datas.
+pub struct Conversor<'a> {
+    repos: &'a Repositorios,
+    empresa_id: ObjectId,
+    destino: Moeda,
+    taxas: HashMap<(Moeda, NaiveDate), TaxaCambio>,
+}
+
+impl<'a> Conversor<'a> {
+    pub fn new(repos: &'a Repositorios, empresa_id: ObjectId, destino: Moeda) -> Self {
+        Self {
+            repos,
+            empresa_id,
+            destino,
+            taxas: HashMap::new(),
+        }
+    }
+
+    pub fn destino(&self) -> Moeda {
+        self.destino
+    }
+
+    /// Unidades da moeda de destino por unidade de `moeda` na data.
+    pub async fn taxa(&mut self, moeda: Moeda, data: NaiveDate) -> Result<TaxaCambio> {
+        if moeda == self.destino {
+            return Ok(TaxaCambio::UM);
+        }
+        let origem = self.em_reais(moeda, data).await?;
+        let destino = self.em_reais(self.destino, data).await?;
+        origem.cruzada(destino).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "A taxa de {} em {} é pequena demais para seis casas",
+                moeda, self.destino
+            ))
+        })
+    }
+
+    /// Valor em `moeda` convertido para a moeda de destino na data,
+    /// arredondado para o centavo.
+    pub async fn converter(
+        &mut self,
+        valor: Dinheiro,
+        moeda: Moeda,
+        data: NaiveDate,
+    ) -> Result<Dinheiro> {
+        if moeda == self.destino || valor.is_zero() {
+            return Ok(valor);
+        }
+        let origem = self.em_reais(moeda, data).await?;
+        let destino = self.em_reais(self.destino, data).await?;
+        Ok(Dinheiro::arredondar(
+            valor.decimal() * origem.decimal() / destino.decimal(),
+        ))
+    }
+
+    async fn em_reais(&mut self, moeda: Moeda, data: NaiveDate) -> Result<TaxaCambio> {
+        if let Some(taxa) = self.taxas.get(&(moeda, data)) {
+            return Ok(*taxa);
+        }
+        let taxa = taxa(self.repos, self.empresa_id, moeda, data).await?;
+        self.taxas.insert((moeda, data), taxa);
+        Ok(taxa)
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::validacao;
+
+/// Faixas de BIN (seis primeiros dígitos) por bandeira. A ordem importa:
+/// Elo e Hipercard usam prefixos que também cairiam em Visa, Diners ou
+/// Discover.
+const FAIXAS_BANDEIRA: &[(&str, &[(u32, u32)])] = &[
+    (
+        "Elo",
+        &[
+            (401178, 401179),
+            (431274, 431274),
+            (438935, 438935),
+            (451416, 451416),
+            (457393, 457393),
+            (457631, 457632),
+            (504175, 504175),
+            (506699, 506778),
+            (509000, 509999),
+            (627780, 627780),
+            (636297, 636297),
+            (636368, 636368),
+            (650031, 650033),
+            (650035, 650051),
+            (650405, 650439),
+            (650485, 650538),
+            (650541, 650598),
+            (650700, 650718),
+            (650720, 650727),
+            (650901, 650978),
+            (651652, 651679),
+            (655000, 655019),
+            (655021, 655058),
+        ],
+    ),
+    (
+        "Hipercard",
+        &[
+            (384100, 384100),
+            (384140, 384140),
+            (384160, 384160),
+            (606282, 606282),
+            (637095, 637095),
+            (637568, 637568),
+            (637599, 637599),
+            (637609, 637609),
+            (637612, 637612),
+        ],
+    ),
+    ("Visa", &[(400000, 499999)]),
+    ("Mastercard", &[(510000, 559999), (222100, 272099)]),
+    ("American Express", &[(340000, 349999), (370000, 379999)]),
+    (
+        "Diners Club",
+        &[(300000, 305999), (360000, 369999), (380000, 389999)],
+    ),
+    ("JCB", &[(352800, 358999)]),
+    ("Discover", &[(601100, 601199), (644000, 659999)]),
+];
+
+/// Bandeira identificada pelo BIN do número do cartão.
+pub fn detectar_bandeira(numero: &str) -> Option<&'static str> {
+    let digitos = validacao::somente_digitos(numero);
+    let bin: u32 = digitos.get(..6)?.parse().ok()?;
+    FAIXAS_BANDEIRA
+        .iter()
+        .find(|(_, faixas)| faixas.iter().any(|(de, ate)| (*de..=*ate).contains(&bin)))
+        .map(|(bandeira, _)| *bandeira)
+}
+
+/// Os quatro últimos dígitos do número, a única parte que é gravada.
+pub fn ultimos_digitos(numero: &str) -> String {
+    let digitos = validacao::somente_digitos(numero);
+    digitos[digitos.len().saturating_sub(4)..].to_string()
+}
+
+/// Compras feitas a partir do dia de fechamento entram na fatura do mês seguinte.
+pub fn competencia_da_compra(data: NaiveDate, dia_fechamento: i32) -> NaiveDate {
+    let primeiro_dia = data.with_day(1).unwrap();
+    if (data.day() as i32) < dia_fechamento {
+        primeiro_dia
+    } else {
+        primeiro_dia + Months::new(1)
+    }
+}
+
+/// Mês da fatura no formato `AAAA-MM`.
+pub fn formatar_competencia(mes: NaiveDate) -> String {
+    mes.format("%Y-%m").to_string()
+}
@@ -0,0 +1,105 @@
+//! Controle de concorrência otimista. Os registros [`Versionado`]s saem
+//! com a versão no `ETag`; um `PUT` ou `DELETE` com `If-Match` só é
+//! aplicado se o registro ainda estiver nessa versão e, caso contrário,
+//! responde 409. Sem `If-Match` a alteração é feita sobre a versão lida
+//! pelo próprio handler, o que ainda impede que duas gravações simultâneas
+//! se sobrescrevam. O `If-Match` usa comparação forte: um ETag fraco
+//! (`W/"3"`) nunca confere e responde 412.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::{
+    openapi::path::{Parameter, ParameterBuilder, ParameterIn},
+    IntoParams, PartialSchema,
+};
+
+use crate::{
+    error::{AppError, Result},
+    repositorio::Versionado,
+};
+
+/// Versão lida pelo cliente, do cabeçalho `If-Match`. Ausente quando o
+/// cabeçalho não foi enviado ou é `*`.
+#[derive(Debug, Clone, Copy)]
+pub struct VersaoEsperada(Option<u32>);
+
+impl VersaoEsperada {
+    /// Confere se o registro ainda está na versão que o cliente leu.
+    pub fn conferir<T: Versionado>(&self, registro: &T) -> Result<()> {
+        match self.0 {
+            Some(versao) if versao != registro.versao() => Err(desatualizado()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for VersaoEsperada {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let Some(valor) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(Self(None));
+        };
+        let texto = valor.to_str().unwrap_or_default().trim();
+        if texto == "*" {
+            return Ok(Self(None));
+        }
+        if texto.starts_with("W/") {
+            return Err(AppError::PreconditionFailed(
+                "If-Match não aceita ETag fraco (W/); envie o ETag recebido, como \"3\""
+                    .to_string(),
+            ));
+        }
+        texto
+            .trim_matches('"')
+            .parse()
+            .map(|versao| Self(Some(versao)))
+            .map_err(|_| {
+                AppError::BadRequest(
+                    "If-Match inválido: envie o ETag recebido, como \"3\"".to_string(),
+                )
+            })
+    }
+}
+
+impl IntoParams for VersaoEsperada {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![ParameterBuilder::new()
+            .name("If-Match")
+            .parameter_in(ParameterIn::Header)
+            .schema(Some(String::schema()))
+            .description(Some(
+                "ETag lido do registro; se ele tiver mudado desde então, a resposta é 409. \
+                 ETags fracos (W/) respondem 412",
+            ))
+            .build()]
+    }
+}
+
+/// O registro mudou desde que foi lido.
+pub fn desatualizado() -> AppError {
+    AppError::Conflict(
+        "O registro foi alterado por outra pessoa; carregue-o de novo antes de gravar".to_string(),
+    )
+}
+
+/// `ETag` da versão: `"3"`.
+pub fn etag(versao: u32) -> String {
+    format!("\"{}\"", versao)
+}
+
+/// Resposta JSON com a versão do registro no `ETag`.
+pub struct ComVersao<T>(pub u32, pub T);
+
+impl<T: Serialize> IntoResponse for ComVersao<T> {
+    fn into_response(self) -> Response {
+        ([(header::ETAG, etag(self.0))], Json(self.1)).into_response()
+    }
+}
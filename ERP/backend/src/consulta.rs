@@ -0,0 +1,573 @@
+//! Paginação, filtros, busca e ordenação das listagens.
+//!
+//! Os endpoints de listagem aceitam na query string:
+//!
+//! - `page` (a partir de 1) e `limit` (padrão 50, máximo 200);
+//! - `sort=campo` ou `sort=-campo` para ordem decrescente;
+//! - `q`, busca sem distinguir maiúsculas nos campos de texto da entidade;
+//! - `campo=valor` para igualdade e `campo_de`/`campo_ate` para intervalos
+//!   de números, datas e instantes.
+//!
+//! Só valem os campos que a entidade declara em [`Listavel`]; parâmetros
+//! desconhecidos ou inválidos resultam em `AppError::Validacao` (422). A
+//! resposta vem no envelope [`Pagina`], com o total de registros.
+
+use std::marker::PhantomData;
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use utoipa::{
+    openapi::{
+        path::{Parameter, ParameterBuilder, ParameterIn},
+        schema::{KnownFormat, ObjectBuilder, SchemaFormat, Type},
+        RefOr, Required, Schema,
+    },
+    IntoParams, PartialSchema,
+};
+
+use crate::{
+    error::{AppError, Result},
+    validacao::Validacao,
+};
+
+pub use erp_api::Pagina;
+
+pub const LIMITE_PADRAO: u64 = 50;
+pub const LIMITE_MAXIMO: u64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipoCampo {
+    Texto,
+    Numero,
+    /// Valor monetário, comparado sem ponto flutuante.
+    Dinheiro,
+    Booleano,
+    /// Data sem horário, `AAAA-MM-DD`.
+    Data,
+    /// Data e hora em UTC. Aceita RFC 3339 ou só a data, que nos filtros
+    /// `_de` vale desde o início do dia e nos `_ate` até o fim dele.
+    Instante,
+    Id,
+    /// `true` ou `false` conforme a coluna informada tenha valor ou seja
+    /// nula. Não serve para ordenar.
+    Preenchido(&'static str),
+}
+
+impl TipoCampo {
+    /// Tipos que aceitam os filtros de intervalo `_de` e `_ate`.
+    fn aceita_intervalo(self) -> bool {
+        matches!(
+            self,
+            TipoCampo::Numero | TipoCampo::Dinheiro | TipoCampo::Data | TipoCampo::Instante
+        )
+    }
+
+    fn ordenavel(self) -> bool {
+        !matches!(self, TipoCampo::Preenchido(_))
+    }
+}
+
+/// Campo que pode ser filtrado e ordenado. O nome é o mesmo na API, no
+/// documento do MongoDB e na coluna SQL.
+#[derive(Debug, Clone, Copy)]
+pub struct Campo {
+    pub nome: &'static str,
+    pub tipo: TipoCampo,
+}
+
+impl Campo {
+    pub const fn texto(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Texto,
+        }
+    }
+
+    pub const fn numero(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Numero,
+        }
+    }
+
+    pub const fn dinheiro(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Dinheiro,
+        }
+    }
+
+    pub const fn booleano(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Booleano,
+        }
+    }
+
+    pub const fn data(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Data,
+        }
+    }
+
+    pub const fn instante(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Instante,
+        }
+    }
+
+    pub const fn id(nome: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Id,
+        }
+    }
+
+    /// Filtro `nome=true|false` sobre a presença de valor em `coluna`.
+    pub const fn preenchido(nome: &'static str, coluna: &'static str) -> Self {
+        Self {
+            nome,
+            tipo: TipoCampo::Preenchido(coluna),
+        }
+    }
+}
+
+/// Entidades listadas com [`Consulta`].
+pub trait Listavel {
+    /// Campos aceitos nos filtros e em `sort`.
+    const CAMPOS: &'static [Campo];
+    /// Campos de texto percorridos pela busca `q`.
+    const BUSCA: &'static [&'static str];
+    /// Ordem usada sem `sort`, no mesmo formato (`campo` ou `-campo`).
+    const ORDEM: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operador {
+    Igual,
+    /// Maior ou igual (`campo_de`).
+    De,
+    /// Menor ou igual (`campo_ate`).
+    Ate,
+    /// O campo tem valor (`ValorFiltro::Booleano(true)`) ou é nulo.
+    Preenchido,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValorFiltro {
+    Texto(String),
+    Numero(f64),
+    Dinheiro(Dinheiro),
+    Booleano(bool),
+    Data(NaiveDate),
+    Instante(DateTime<Utc>),
+    Id(ObjectId),
+}
+
+#[derive(Debug, Clone)]
+pub struct Filtro {
+    pub campo: &'static str,
+    pub operador: Operador,
+    pub valor: ValorFiltro,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ordem {
+    pub campo: &'static str,
+    pub decrescente: bool,
+}
+
+/// Parâmetros de uma listagem de `T`. Usado como extractor nos handlers e
+/// repassado ao repositório, que aplica tudo na consulta ao banco.
+pub struct Consulta<T> {
+    pub pagina: u64,
+    pub limite: u64,
+    /// Combinados com E.
+    pub filtros: Vec<Filtro>,
+    /// Texto buscado, sem espaços nas pontas e em minúsculas.
+    pub busca: Option<String>,
+    pub ordem: Ordem,
+    entidade: PhantomData<fn() -> T>,
+}
+
+impl<T: Listavel> Consulta<T> {
+    /// Interpreta os pares da query string, acumulando os erros de todos os
+    /// parâmetros.
+    pub fn new(parametros: &[(String, String)]) -> Result<Self> {
+        let (campo, decrescente) = ler_ordem(T::ORDEM);
+        let mut consulta = Self {
+            pagina: 1,
+            limite: LIMITE_PADRAO,
+            filtros: Vec::new(),
+            busca: None,
+            ordem: Ordem { campo, decrescente },
+            entidade: PhantomData,
+        };
+
+        let mut validacao = Validacao::new();
+        for (chave, valor) in parametros {
+            let valor = valor.trim();
+            match chave.as_str() {
+                "page" => match valor.parse() {
+                    Ok(pagina) if pagina >= 1 => consulta.pagina = pagina,
+                    _ => {
+                        validacao.checar(chave, false, "Informe um número a partir de 1");
+                    }
+                },
+                "limit" => match valor.parse() {
+                    Ok(limite) if (1..=LIMITE_MAXIMO).contains(&limite) => consulta.limite = limite,
+                    _ => {
+                        validacao.checar(
+                            chave,
+                            false,
+                            &format!("Informe um número entre 1 e {}", LIMITE_MAXIMO),
+                        );
+                    }
+                },
+                "sort" => {
+                    let (nome, decrescente) = ler_ordem(valor);
+                    match campo_de::<T>(nome).filter(|campo| campo.tipo.ordenavel()) {
+                        Some(campo) => {
+                            consulta.ordem = Ordem {
+                                campo: campo.nome,
+                                decrescente,
+                            }
+                        }
+                        None => {
+                            validacao.checar(chave, false, "Campo de ordenação desconhecido");
+                        }
+                    }
+                }
+                "q" => {
+                    validacao.checar(
+                        chave,
+                        !T::BUSCA.is_empty(),
+                        "Busca indisponível nesta listagem",
+                    );
+                    if !valor.is_empty() {
+                        consulta.busca = Some(valor.to_lowercase());
+                    }
+                }
+                _ => match ler_filtro::<T>(chave, valor) {
+                    Ok(filtro) => consulta.filtros.push(filtro),
+                    Err(mensagem) => {
+                        validacao.checar(chave, false, mensagem);
+                    }
+                },
+            }
+        }
+        // O deslocamento segue para o banco como inteiro de 64 bits com sinal.
+        let deslocamento = (consulta.pagina - 1).checked_mul(consulta.limite);
+        validacao.checar(
+            "page",
+            deslocamento.is_some_and(|deslocamento| i64::try_from(deslocamento).is_ok()),
+            "Página fora do intervalo aceito",
+        );
+        validacao.concluir()?;
+
+        Ok(consulta)
+    }
+}
+
+impl<T> Consulta<T> {
+    /// Quantos registros pular até a página pedida; [`Consulta::new`]
+    /// garante que cabe em um `i64`.
+    pub fn deslocamento(&self) -> u64 {
+        (self.pagina - 1) * self.limite
+    }
+
+    /// A mesma página, ordem e filtros, para listar outra entidade que tem
+    /// os mesmos campos.
+    pub fn converter<U>(self) -> Consulta<U> {
+        Consulta {
+            pagina: self.pagina,
+            limite: self.limite,
+            filtros: self.filtros,
+            busca: self.busca,
+            ordem: self.ordem,
+            entidade: PhantomData,
+        }
+    }
+
+    /// Filtra por igualdade quando a requisição não filtrou o campo.
+    pub fn filtro_padrao(&mut self, campo: &'static str, valor: ValorFiltro) {
+        if !self.filtros.iter().any(|filtro| filtro.campo == campo) {
+            self.filtros.push(Filtro {
+                campo,
+                operador: Operador::Igual,
+                valor,
+            });
+        }
+    }
+
+    /// Monta a página com os registros encontrados e o total da consulta.
+    pub fn pagina<U>(&self, itens: Vec<U>, total: u64) -> Pagina<U> {
+        Pagina {
+            itens,
+            total,
+            pagina: self.pagina,
+            limite: self.limite,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Consulta<T>
+where
+    S: Send + Sync,
+    T: Listavel,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let Query(parametros) = Query::<Vec<(String, String)>>::try_from_uri(&parts.uri)
+            .map_err(|e| AppError::BadRequest(e.body_text()))?;
+        Self::new(&parametros)
+    }
+}
+
+/// Descreve no documento OpenAPI os parâmetros que a entidade aceita.
+impl<T: Listavel> IntoParams for Consulta<T> {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let ordens = T::CAMPOS
+            .iter()
+            .filter(|campo| campo.tipo.ordenavel())
+            .flat_map(|campo| [campo.nome.to_string(), format!("-{}", campo.nome)]);
+        let mut parametros = vec![
+            parametro(
+                "page",
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .minimum(Some(1))
+                    .into(),
+                "Página, a partir de 1".to_string(),
+            ),
+            parametro(
+                "limit",
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .minimum(Some(1))
+                    .maximum(Some(LIMITE_MAXIMO))
+                    .into(),
+                format!("Registros por página (padrão {})", LIMITE_PADRAO),
+            ),
+            parametro(
+                "sort",
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(ordens))
+                    .into(),
+                format!("Ordem; `-campo` é decrescente (padrão `{}`)", T::ORDEM),
+            ),
+        ];
+        if !T::BUSCA.is_empty() {
+            parametros.push(parametro(
+                "q",
+                String::schema(),
+                format!("Busca sem distinguir maiúsculas em {}", T::BUSCA.join(", ")),
+            ));
+        }
+        for campo in T::CAMPOS {
+            let esquema = campo.tipo.esquema();
+            parametros.push(parametro(
+                campo.nome,
+                esquema.clone(),
+                "Igualdade".to_string(),
+            ));
+            if campo.tipo.aceita_intervalo() {
+                parametros.push(parametro(
+                    &format!("{}_de", campo.nome),
+                    esquema.clone(),
+                    "A partir de (inclusive)".to_string(),
+                ));
+                parametros.push(parametro(
+                    &format!("{}_ate", campo.nome),
+                    esquema,
+                    "Até (inclusive)".to_string(),
+                ));
+            }
+        }
+        parametros
+    }
+}
+
+impl TipoCampo {
+    fn esquema(self) -> RefOr<Schema> {
+        let formato = |formato| {
+            ObjectBuilder::new()
+                .schema_type(Type::String)
+                .format(Some(SchemaFormat::KnownFormat(formato)))
+                .into()
+        };
+        match self {
+            TipoCampo::Texto | TipoCampo::Id => String::schema(),
+            TipoCampo::Numero => f64::schema(),
+            TipoCampo::Dinheiro => Dinheiro::schema(),
+            TipoCampo::Booleano | TipoCampo::Preenchido(_) => bool::schema(),
+            TipoCampo::Data => formato(KnownFormat::Date),
+            TipoCampo::Instante => ObjectBuilder::new()
+                .schema_type(Type::String)
+                .description(Some("RFC 3339 ou só a data (AAAA-MM-DD)"))
+                .into(),
+        }
+    }
+}
+
+fn parametro(nome: &str, esquema: RefOr<Schema>, descricao: String) -> Parameter {
+    ParameterBuilder::new()
+        .name(nome)
+        .parameter_in(ParameterIn::Query)
+        .required(Required::False)
+        .schema(Some(esquema))
+        .description(Some(descricao))
+        .build()
+}
+
+/// `-campo` é decrescente.
+fn ler_ordem(valor: &str) -> (&str, bool) {
+    match valor.strip_prefix('-') {
+        Some(campo) => (campo, true),
+        None => (valor, false),
+    }
+}
+
+fn campo_de<T: Listavel>(nome: &str) -> Option<Campo> {
+    T::CAMPOS.iter().find(|campo| campo.nome == nome).copied()
+}
+
+fn ler_filtro<T: Listavel>(chave: &str, valor: &str) -> std::result::Result<Filtro, &'static str> {
+    let intervalo = |sufixo: &str| {
+        chave
+            .strip_suffix(sufixo)
+            .and_then(campo_de::<T>)
+            .filter(|campo| campo.tipo.aceita_intervalo())
+    };
+    let (campo, operador) = if let Some(campo) = campo_de::<T>(chave) {
+        (campo, Operador::Igual)
+    } else if let Some(campo) = intervalo("_de") {
+        (campo, Operador::De)
+    } else if let Some(campo) = intervalo("_ate") {
+        (campo, Operador::Ate)
+    } else {
+        return Err("Filtro desconhecido");
+    };
+
+    let valor = match campo.tipo {
+        TipoCampo::Texto => ValorFiltro::Texto(valor.to_string()),
+        TipoCampo::Numero => ValorFiltro::Numero(
+            valor
+                .parse()
+                .ok()
+                .filter(|numero: &f64| numero.is_finite())
+                .ok_or("Número inválido")?,
+        ),
+        TipoCampo::Dinheiro => ValorFiltro::Dinheiro(
+            valor
+                .parse()
+                .map_err(|_| "Valor inválido (use até duas casas decimais, ex.: 10.50)")?,
+        ),
+        TipoCampo::Booleano => {
+            ValorFiltro::Booleano(valor.parse().map_err(|_| "Use true ou false")?)
+        }
+        TipoCampo::Data => ValorFiltro::Data(
+            valor
+                .parse()
+                .map_err(|_| "Data inválida (use AAAA-MM-DD)")?,
+        ),
+        TipoCampo::Instante => {
+            if operador == Operador::Igual {
+                return Err("Filtre datas e horas com os sufixos _de e _ate");
+            }
+            ValorFiltro::Instante(
+                ler_instante(valor, operador)
+                    .ok_or("Data inválida (use AAAA-MM-DD ou RFC 3339)")?,
+            )
+        }
+        TipoCampo::Id => ValorFiltro::Id(ObjectId::parse_str(valor).map_err(|_| "ID inválido")?),
+        TipoCampo::Preenchido(coluna) => {
+            return Ok(Filtro {
+                campo: coluna,
+                operador: Operador::Preenchido,
+                valor: ValorFiltro::Booleano(valor.parse().map_err(|_| "Use true ou false")?),
+            });
+        }
+    };
+
+    Ok(Filtro {
+        campo: campo.nome,
+        operador,
+        valor,
+    })
+}
+
+fn ler_instante(valor: &str, operador: Operador) -> Option<DateTime<Utc>> {
+    if let Ok(instante) = DateTime::parse_from_rfc3339(valor) {
+        return Some(instante.with_timezone(&Utc));
+    }
+    let data: NaiveDate = valor.parse().ok()?;
+    let horario = match operador {
+        Operador::Ate => NaiveTime::from_hms_micro_opt(23, 59, 59, 999_999)?,
+        _ => NaiveTime::MIN,
+    };
+    Some(data.and_time(horario).and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Registro;
+
+    impl Listavel for Registro {
+        const CAMPOS: &'static [Campo] = &[Campo::texto("nome")];
+        const BUSCA: &'static [&'static str] = &["nome"];
+        const ORDEM: &'static str = "nome";
+    }
+
+    fn consulta(parametros: &[(&str, &str)]) -> Result<Consulta<Registro>> {
+        let parametros: Vec<_> = parametros
+            .iter()
+            .map(|(chave, valor)| (chave.to_string(), valor.to_string()))
+            .collect();
+        Consulta::new(&parametros)
+    }
+
+    fn campos_invalidos(resultado: Result<Consulta<Registro>>) -> Vec<String> {
+        match resultado {
+            Err(AppError::Validacao(campos)) => campos.into_iter().map(|c| c.campo).collect(),
+            _ => panic!("esperava erro de validação"),
+        }
+    }
+
+    #[test]
+    fn deslocamento_da_pagina() {
+        let consulta = consulta(&[("page", "3"), ("limit", "20")]).unwrap();
+        assert_eq!(consulta.deslocamento(), 40);
+    }
+
+    #[test]
+    fn pagina_grande_demais_e_recusada() {
+        let maxima = u64::MAX.to_string();
+        assert_eq!(
+            campos_invalidos(consulta(&[("page", &maxima), ("limit", "200")])),
+            ["page"]
+        );
+        let alem_do_i64 = (i64::MAX as u64 / 50 + 2).to_string();
+        assert_eq!(
+            campos_invalidos(consulta(&[("page", &alem_do_i64)])),
+            ["page"]
+        );
+        assert_eq!(
+            campos_invalidos(consulta(&[("page", "0"), ("limit", "500")])),
+            ["page", "limit"]
+        );
+    }
+}
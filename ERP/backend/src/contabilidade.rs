@@ -0,0 +1,756 @@
+//! Contabilidade em partidas dobradas. Os eventos financeiros (venda
+//! finalizada, liquidação de contas, compras e faturas de cartão, saldos
+//! iniciais das contas bancárias) geram partidas no livro diário, e o saldo
+//! das contas bancárias é calculado a partir delas.
+//!
+//! Partidas não são alteradas nem excluídas: desfazer um evento gera o
+//! estorno, uma partida com débitos e créditos invertidos.
+//!
+//! A contabilidade é em reais. Os movimentos das contas bancárias em moeda
+//! estrangeira entram pela cotação do dia, e as saídas delas pelo custo
+//! médio da moeda em caixa; a diferença é a variação cambial realizada.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    cambio,
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+    saldos,
+    validacao::Validacao,
+};
+
+pub const CAIXA: &str = "1.1.01";
+pub const BANCOS: &str = "1.1.02";
+pub const CARTOES_A_PAGAR: &str = "2.1.01";
+pub const SALDOS_INICIAIS: &str = "3.1.01";
+pub const RECEITA_VENDAS: &str = "4.1.01";
+pub const RECEITAS_DIVERSAS: &str = "4.1.02";
+pub const VARIACAO_CAMBIAL_ATIVA: &str = "4.2.01";
+pub const DESPESAS_GERAIS: &str = "5.1.01";
+pub const COMPRAS_CARTAO: &str = "5.1.02";
+pub const VARIACAO_CAMBIAL_PASSIVA: &str = "5.2.01";
+
+/// Contas que recebem as partidas dos eventos financeiros; não podem ganhar
+/// subcontas, o que as tornaria sintéticas.
+const AUTOMATICAS: &[&str] = &[
+    CAIXA,
+    CARTOES_A_PAGAR,
+    SALDOS_INICIAIS,
+    RECEITA_VENDAS,
+    RECEITAS_DIVERSAS,
+    VARIACAO_CAMBIAL_ATIVA,
+    DESPESAS_GERAIS,
+    COMPRAS_CARTAO,
+    VARIACAO_CAMBIAL_PASSIVA,
+];
+
+/// Contas criadas para toda empresa. As contas bancárias ganham subcontas
+/// de [`BANCOS`] conforme são movimentadas.
+const PLANO_PADRAO: &[(&str, &str, TipoContaContabil)] = &[
+    ("1", "Ativo", TipoContaContabil::Ativo),
+    ("1.1", "Ativo circulante", TipoContaContabil::Ativo),
+    (CAIXA, "Caixa", TipoContaContabil::Ativo),
+    (BANCOS, "Bancos", TipoContaContabil::Ativo),
+    ("2", "Passivo", TipoContaContabil::Passivo),
+    ("2.1", "Passivo circulante", TipoContaContabil::Passivo),
+    (
+        CARTOES_A_PAGAR,
+        "Cartões de crédito a pagar",
+        TipoContaContabil::Passivo,
+    ),
+    (
+        "3",
+        "Patrimônio líquido",
+        TipoContaContabil::PatrimonioLiquido,
+    ),
+    ("3.1", "Capital", TipoContaContabil::PatrimonioLiquido),
+    (
+        SALDOS_INICIAIS,
+        "Saldos iniciais",
+        TipoContaContabil::PatrimonioLiquido,
+    ),
+    ("4", "Receitas", TipoContaContabil::Receita),
+    ("4.1", "Receitas operacionais", TipoContaContabil::Receita),
+    (
+        RECEITA_VENDAS,
+        "Receita de vendas",
+        TipoContaContabil::Receita,
+    ),
+    (
+        RECEITAS_DIVERSAS,
+        "Receitas diversas",
+        TipoContaContabil::Receita,
+    ),
+    ("4.2", "Receitas financeiras", TipoContaContabil::Receita),
+    (
+        VARIACAO_CAMBIAL_ATIVA,
+        "Variação cambial ativa",
+        TipoContaContabil::Receita,
+    ),
+    ("5", "Despesas", TipoContaContabil::Despesa),
+    ("5.1", "Despesas operacionais", TipoContaContabil::Despesa),
+    (
+        DESPESAS_GERAIS,
+        "Despesas gerais",
+        TipoContaContabil::Despesa,
+    ),
+    (
+        COMPRAS_CARTAO,
+        "Compras no cartão de crédito",
+        TipoContaContabil::Despesa,
+    ),
+    ("5.2", "Despesas financeiras", TipoContaContabil::Despesa),
+    (
+        VARIACAO_CAMBIAL_PASSIVA,
+        "Variação cambial passiva",
+        TipoContaContabil::Despesa,
+    ),
+];
+
+/// Plano de contas de uma empresa, ordenado por código.
+pub struct Plano {
+    empresa_id: ObjectId,
+    contas: Vec<ContaContabil>,
+}
+
+impl Plano {
+    pub fn contas(&self) -> &[ContaContabil] {
+        &self.contas
+    }
+
+    pub fn buscar(&self, id: ObjectId) -> Option<&ContaContabil> {
+        self.contas.iter().find(|conta| conta.id == Some(id))
+    }
+
+    pub fn por_codigo(&self, codigo: &str) -> Option<&ContaContabil> {
+        self.contas.iter().find(|conta| conta.codigo == codigo)
+    }
+
+    /// Contas com subcontas não recebem partidas.
+    pub fn sintetica(&self, conta: &ContaContabil) -> bool {
+        self.contas.iter().any(|outra| conta.contem(&outra.codigo))
+    }
+
+    /// Se a conta pode ganhar subcontas cadastradas manualmente.
+    pub fn aceita_subcontas(&self, conta: &ContaContabil) -> bool {
+        conta.conta_bancaria_id.is_none() && !AUTOMATICAS.contains(&conta.codigo.as_str())
+    }
+
+    fn id(&self, codigo: &str) -> Result<ObjectId> {
+        self.por_codigo(codigo)
+            .and_then(|conta| conta.id)
+            .ok_or_else(|| {
+                AppError::Internal(format!("Conta {} ausente do plano de contas", codigo))
+            })
+    }
+}
+
+/// Partida a registrar.
+pub struct NovaPartida {
+    pub empresa_id: ObjectId,
+    pub data: NaiveDate,
+    pub historico: String,
+    pub origem: OrigemPartida,
+    pub origem_id: Option<ObjectId>,
+    pub linhas: Vec<LinhaPartida>,
+}
+
+pub fn debito(conta_id: ObjectId, valor: Dinheiro) -> LinhaPartida {
+    LinhaPartida {
+        conta_id,
+        debito: valor,
+        credito: Dinheiro::ZERO,
+        valor_moeda: None,
+    }
+}
+
+pub fn credito(conta_id: ObjectId, valor: Dinheiro) -> LinhaPartida {
+    LinhaPartida {
+        conta_id,
+        debito: Dinheiro::ZERO,
+        credito: valor,
+        valor_moeda: None,
+    }
+}
+
+/// A linha da conta bancária com o valor na moeda dela, quando não é o
+/// real.
+fn na_moeda(linha: LinhaPartida, moeda: Moeda, valor: Dinheiro) -> LinhaPartida {
+    LinhaPartida {
+        valor_moeda: (moeda != Moeda::Brl).then_some(valor),
+        ..linha
+    }
+}
+
+/// Carrega o plano de contas da empresa, criando as contas padrão que
+/// faltarem.
+pub async fn plano(repos: &Repositorios, empresa_id: ObjectId) -> Result<Plano> {
+    let mut contas = repos.contas_contabeis.plano(empresa_id).await?;
+
+    let now = Utc::now();
+    let faltantes: Vec<ContaContabil> = PLANO_PADRAO
+        .iter()
+        .filter(|(codigo, _, _)| !contas.iter().any(|conta| conta.codigo == *codigo))
+        .map(|(codigo, nome, tipo)| ContaContabil {
+            id: None,
+            empresa_id,
+            codigo: codigo.to_string(),
+            nome: nome.to_string(),
+            tipo: *tipo,
+            conta_bancaria_id: None,
+            ativo: true,
+            created_at: now,
+            updated_at: now,
+        })
+        .collect();
+    if !faltantes.is_empty() {
+        repos.contas_contabeis.criar_varios(faltantes).await?;
+        contas = repos.contas_contabeis.plano(empresa_id).await?;
+    }
+
+    Ok(Plano { empresa_id, contas })
+}
+
+/// Se a conta contábil da conta bancária já recebeu partidas.
+pub async fn banco_movimentado(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    conta_bancaria_id: ObjectId,
+) -> Result<bool> {
+    let plano = plano(repos, empresa_id).await?;
+    let Some(id) = plano
+        .contas
+        .iter()
+        .find(|conta| conta.conta_bancaria_id == Some(conta_bancaria_id))
+        .and_then(|conta| conta.id)
+    else {
+        return Ok(false);
+    };
+    let movimento = repos.partidas.movimento_por_conta(empresa_id, None).await?;
+    Ok(movimento.contains_key(&id))
+}
+
+/// Conta contábil da conta bancária, criada como subconta de [`BANCOS`] na
+/// primeira movimentação.
+async fn conta_do_banco(
+    repos: &Repositorios,
+    plano: &mut Plano,
+    conta_bancaria_id: ObjectId,
+) -> Result<ObjectId> {
+    if let Some(id) = plano
+        .contas
+        .iter()
+        .find(|conta| conta.conta_bancaria_id == Some(conta_bancaria_id))
+        .and_then(|conta| conta.id)
+    {
+        return Ok(id);
+    }
+
+    let conta_bancaria = repos
+        .contas
+        .buscar(plano.empresa_id, conta_bancaria_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let prefixo = format!("{}.", BANCOS);
+    let proxima = plano
+        .contas
+        .iter()
+        .filter_map(|conta| conta.codigo.strip_prefix(&prefixo)?.parse::<u32>().ok())
+        .max()
+        .unwrap_or_default()
+        + 1;
+
+    let now = Utc::now();
+    let criada = repos
+        .contas_contabeis
+        .criar(ContaContabil {
+            id: None,
+            empresa_id: plano.empresa_id,
+            codigo: format!("{}{:03}", prefixo, proxima),
+            nome: format!(
+                "{} ag. {} c/c {}",
+                conta_bancaria.banco, conta_bancaria.agencia, conta_bancaria.conta
+            ),
+            tipo: TipoContaContabil::Ativo,
+            conta_bancaria_id: Some(conta_bancaria_id),
+            ativo: true,
+            created_at: now,
+            updated_at: now,
+        })
+        .await?;
+    let id = criada.id.expect("criar retorna o id gerado");
+    plano.contas.push(criada);
+    Ok(id)
+}
+
+/// Grava a partida depois de conferir que débitos e créditos fecham e que
+/// todas as contas são analíticas e ativas no plano da empresa.
+pub async fn registrar(repos: &Repositorios, plano: &Plano, nova: NovaPartida) -> Result<Partida> {
+    let linhas = nova.linhas;
+
+    let mut validacao = Validacao::new();
+    validacao.obrigatorio("historico", &nova.historico).checar(
+        "linhas",
+        linhas.len() >= 2,
+        "Informe ao menos uma conta a débito e uma a crédito",
+    );
+    for (indice, linha) in linhas.iter().enumerate() {
+        let (debito, credito) = (linha.debito, linha.credito);
+        validacao.checar(
+            &format!("linhas[{}]", indice),
+            !debito.negativo() && !credito.negativo() && debito.positivo() != credito.positivo(),
+            "Informe um valor positivo a débito ou a crédito",
+        );
+
+        let conta = plano.buscar(linha.conta_id);
+        validacao.checar(
+            &format!("linhas[{}].conta_id", indice),
+            conta.is_some_and(|conta| conta.ativo && !plano.sintetica(conta)),
+            "Conta inexistente, inativa ou sintética",
+        );
+    }
+    let total_debito: Dinheiro = linhas.iter().map(|linha| linha.debito).sum();
+    let total_credito: Dinheiro = linhas.iter().map(|linha| linha.credito).sum();
+    validacao.checar(
+        "linhas",
+        total_debito == total_credito,
+        "O total a débito difere do total a crédito",
+    );
+    validacao.concluir()?;
+
+    repos
+        .partidas
+        .criar(Partida {
+            id: None,
+            empresa_id: nova.empresa_id,
+            data: nova.data,
+            historico: nova.historico,
+            origem: nova.origem,
+            origem_id: nova.origem_id,
+            estorno_de: None,
+            linhas,
+            created_at: Utc::now(),
+        })
+        .await
+}
+
+/// Grava o estorno da partida, com os lados invertidos e a data de hoje.
+pub async fn estornar_partida(repos: &Repositorios, partida: &Partida) -> Result<Partida> {
+    let id = partida.id.ok_or(AppError::NotFound)?;
+    if partida.estorno_de.is_some() {
+        return Err(AppError::Conflict(
+            "Estornos não podem ser estornados".to_string(),
+        ));
+    }
+    if repos
+        .partidas
+        .estorno_de(partida.empresa_id, id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Conflict("A partida já foi estornada".to_string()));
+    }
+
+    repos
+        .partidas
+        .criar(Partida {
+            id: None,
+            empresa_id: partida.empresa_id,
+            data: Utc::now().date_naive(),
+            historico: format!("Estorno: {}", partida.historico),
+            origem: partida.origem,
+            origem_id: partida.origem_id,
+            estorno_de: Some(id),
+            linhas: partida
+                .linhas
+                .iter()
+                .map(|linha| LinhaPartida {
+                    conta_id: linha.conta_id,
+                    debito: linha.credito,
+                    credito: linha.debito,
+                    valor_moeda: linha.valor_moeda,
+                })
+                .collect(),
+            created_at: Utc::now(),
+        })
+        .await
+}
+
+/// Estorna as partidas geradas pelo registro que ainda não foram
+/// estornadas.
+pub async fn estornar(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    origem: OrigemPartida,
+    origem_id: ObjectId,
+) -> Result<()> {
+    let partidas = repos
+        .partidas
+        .da_origem(empresa_id, origem, origem_id)
+        .await?;
+    let estornadas: HashSet<ObjectId> = partidas
+        .iter()
+        .filter_map(|partida| partida.estorno_de)
+        .collect();
+
+    for partida in &partidas {
+        let pendente =
+            partida.estorno_de.is_none() && partida.id.is_some_and(|id| !estornadas.contains(&id));
+        if pendente {
+            estornar_partida(repos, partida).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Venda finalizada: entrada no caixa contra a receita de vendas.
+pub async fn registrar_venda(repos: &Repositorios, venda: &Venda) -> Result<()> {
+    if !venda.total_final.positivo() {
+        return Ok(());
+    }
+    let plano = plano(repos, venda.empresa_id).await?;
+    registrar(
+        repos,
+        &plano,
+        NovaPartida {
+            empresa_id: venda.empresa_id,
+            data: Utc::now().date_naive(),
+            historico: format!(
+                "Venda {}",
+                venda.id.map(|id| id.to_hex()).unwrap_or_default()
+            ),
+            origem: OrigemPartida::Venda,
+            origem_id: venda.id,
+            linhas: vec![
+                debito(plano.id(CAIXA)?, venda.total_final),
+                credito(plano.id(RECEITA_VENDAS)?, venda.total_final),
+            ],
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Conta a pagar ou a receber liquidada: a conta bancária movimenta contra
+/// despesas gerais ou receitas diversas.
+pub async fn registrar_liquidacao(repos: &Repositorios, lancamento: &Lancamento) -> Result<()> {
+    let contrapartida = match lancamento.tipo {
+        TipoLancamento::Pagar => DESPESAS_GERAIS,
+        TipoLancamento::Receber => RECEITAS_DIVERSAS,
+    };
+    registrar_pagamento(repos, lancamento, contrapartida).await
+}
+
+/// Fatura de cartão paga: a conta bancária quita a dívida do cartão.
+pub async fn registrar_pagamento_fatura(
+    repos: &Repositorios,
+    lancamento: &Lancamento,
+) -> Result<()> {
+    registrar_pagamento(repos, lancamento, CARTOES_A_PAGAR).await
+}
+
+/// O lançamento já está gravado como pago. Nas contas em moeda
+/// estrangeira, recebimentos entram pela cotação do dia; pagamentos
+/// reconhecem a despesa pela cotação do dia e baixam a conta pelo custo
+/// médio, com a diferença em variação cambial.
+async fn registrar_pagamento(
+    repos: &Repositorios,
+    lancamento: &Lancamento,
+    contrapartida: &str,
+) -> Result<()> {
+    let conta_bancaria_id = lancamento
+        .conta_id
+        .ok_or_else(|| AppError::Internal("Lançamento liquidado sem conta bancária".to_string()))?;
+    let valor = lancamento.valor_pago.unwrap_or(lancamento.valor);
+    let data = lancamento
+        .data_pagamento
+        .unwrap_or_else(|| Utc::now().date_naive());
+    let conta = repos
+        .contas
+        .buscar(lancamento.empresa_id, conta_bancaria_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let mut plano = plano(repos, lancamento.empresa_id).await?;
+    let banco = conta_do_banco(repos, &mut plano, conta_bancaria_id).await?;
+    let outra = plano.id(contrapartida)?;
+    let taxa = cambio::taxa(repos, lancamento.empresa_id, conta.moeda, data).await?;
+    let em_reais = taxa.converter(valor);
+    let linhas = match lancamento.tipo {
+        TipoLancamento::Receber => vec![
+            na_moeda(debito(banco, em_reais), conta.moeda, valor),
+            credito(outra, em_reais),
+        ],
+        TipoLancamento::Pagar if conta.moeda == Moeda::Brl => {
+            vec![debito(outra, em_reais), credito(banco, em_reais)]
+        }
+        TipoLancamento::Pagar => {
+            let saldo_reais =
+                saldo_bancario(repos, lancamento.empresa_id, conta_bancaria_id).await?;
+            let saldo_moeda = saldos::conta_bancaria(repos, &conta).await?;
+            let custo = custo_medio(saldo_reais, saldo_moeda, valor, taxa);
+
+            let mut linhas = vec![
+                debito(outra, em_reais),
+                na_moeda(credito(banco, custo), conta.moeda, valor),
+            ];
+            let variacao = em_reais - custo;
+            if variacao.positivo() {
+                linhas.push(credito(plano.id(VARIACAO_CAMBIAL_ATIVA)?, variacao));
+            } else if variacao.negativo() {
+                linhas.push(debito(plano.id(VARIACAO_CAMBIAL_PASSIVA)?, -variacao));
+            }
+            linhas
+        }
+    };
+
+    registrar(
+        repos,
+        &plano,
+        NovaPartida {
+            empresa_id: lancamento.empresa_id,
+            data,
+            historico: lancamento.descricao.clone(),
+            origem: OrigemPartida::Lancamento,
+            origem_id: lancamento.id,
+            linhas,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Custo em reais de `valor` saindo de uma conta em moeda estrangeira com
+/// `saldo_moeda` antes da saída e `saldo_reais` na contabilidade: a parte
+/// proporcional do saldo contábil. O que passar do saldo da conta sai pela
+/// cotação do dia.
+fn custo_medio(
+    saldo_reais: Dinheiro,
+    saldo_moeda: Dinheiro,
+    valor: Dinheiro,
+    taxa: TaxaCambio,
+) -> Dinheiro {
+    if !saldo_moeda.positivo() || !saldo_reais.positivo() {
+        return taxa.converter(valor);
+    }
+    if valor <= saldo_moeda {
+        Dinheiro::arredondar(saldo_reais.decimal() * valor.decimal() / saldo_moeda.decimal())
+    } else {
+        saldo_reais + taxa.converter(valor - saldo_moeda)
+    }
+}
+
+/// Compra no cartão: a despesa é reconhecida na compra, contra a dívida do
+/// cartão, que a fatura quita depois.
+pub async fn registrar_compra_cartao(
+    repos: &Repositorios,
+    compra: &CompraCartao,
+    valor: Dinheiro,
+) -> Result<()> {
+    let plano = plano(repos, compra.empresa_id).await?;
+    registrar(
+        repos,
+        &plano,
+        NovaPartida {
+            empresa_id: compra.empresa_id,
+            data: compra.data_compra,
+            historico: compra.descricao.clone(),
+            origem: OrigemPartida::CompraCartao,
+            origem_id: Some(compra.compra_id),
+            linhas: vec![
+                debito(plano.id(COMPRAS_CARTAO)?, valor),
+                credito(plano.id(CARTOES_A_PAGAR)?, valor),
+            ],
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Saldo inicial informado no cadastro da conta bancária, ou a diferença
+/// quando ele é alterado, contra a conta de saldos iniciais. Nas contas em
+/// moeda estrangeira, convertido pela cotação do dia.
+pub async fn registrar_saldo_inicial(
+    repos: &Repositorios,
+    conta: &ContaBancaria,
+    diferenca: Dinheiro,
+) -> Result<()> {
+    if diferenca.is_zero() {
+        return Ok(());
+    }
+    let conta_bancaria_id = conta.id.ok_or(AppError::NotFound)?;
+    let hoje = Utc::now().date_naive();
+    let na_conta = diferenca.abs();
+    let diferenca = cambio::em_reais(repos, conta.empresa_id, diferenca, conta.moeda, hoje).await?;
+
+    let mut plano = plano(repos, conta.empresa_id).await?;
+    let existentes = repos
+        .partidas
+        .da_origem(
+            conta.empresa_id,
+            OrigemPartida::ContaBancaria,
+            conta_bancaria_id,
+        )
+        .await?;
+    let banco = conta_do_banco(repos, &mut plano, conta_bancaria_id).await?;
+    let saldos_iniciais = plano.id(SALDOS_INICIAIS)?;
+    let linhas = if diferenca.positivo() {
+        vec![
+            na_moeda(debito(banco, diferenca), conta.moeda, na_conta),
+            credito(saldos_iniciais, diferenca),
+        ]
+    } else {
+        vec![
+            debito(saldos_iniciais, -diferenca),
+            na_moeda(credito(banco, -diferenca), conta.moeda, na_conta),
+        ]
+    };
+
+    registrar(
+        repos,
+        &plano,
+        NovaPartida {
+            empresa_id: conta.empresa_id,
+            data: hoje,
+            historico: format!(
+                "{} da conta {} {}",
+                if existentes.is_empty() {
+                    "Saldo inicial"
+                } else {
+                    "Ajuste do saldo inicial"
+                },
+                conta.banco,
+                conta.conta
+            ),
+            origem: OrigemPartida::ContaBancaria,
+            origem_id: Some(conta_bancaria_id),
+            linhas,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Saldo contábil, em reais, de cada conta bancária, pelo id da conta
+/// bancária; com `ate`, só as partidas até essa data (inclusive). Para o
+/// saldo na moeda da conta, veja [`saldos::contas_bancarias`].
+pub async fn saldos_bancarios(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    ate: Option<NaiveDate>,
+) -> Result<HashMap<ObjectId, Dinheiro>> {
+    let contas = repos.contas_contabeis.plano(empresa_id).await?;
+    let movimento = repos.partidas.movimento_por_conta(empresa_id, ate).await?;
+
+    Ok(contas
+        .iter()
+        .filter_map(|conta| {
+            let conta_bancaria_id = conta.conta_bancaria_id?;
+            let saldo = movimento
+                .get(&conta.id?)
+                .map(|movimento| movimento.saldo(conta.tipo))
+                .unwrap_or_default();
+            Some((conta_bancaria_id, saldo))
+        })
+        .collect())
+}
+
+pub async fn saldo_bancario(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    conta_bancaria_id: ObjectId,
+) -> Result<Dinheiro> {
+    Ok(saldos_bancarios(repos, empresa_id, None)
+        .await?
+        .remove(&conta_bancaria_id)
+        .unwrap_or_default())
+}
+
+/// Cria o plano de contas das empresas e, nas que ainda não têm partidas,
+/// lança a abertura com os saldos calculados antes da contabilidade: o
+/// saldo de cada conta bancária e o limite utilizado dos cartões.
+pub async fn migrar(repos: &Repositorios) -> anyhow::Result<()> {
+    for empresa in repos.empresas.listar(None).await? {
+        let empresa_id = empresa.id.expect("empresa gravada sem id");
+        let mut plano = plano(repos, empresa_id).await?;
+        if repos.partidas.existe_alguma(empresa_id).await? {
+            continue;
+        }
+
+        let saldos_iniciais = plano.id(SALDOS_INICIAIS)?;
+        let movimentado = repos
+            .lancamentos
+            .movimentado_por_conta(empresa_id, None)
+            .await?;
+        let mut linhas = Vec::new();
+        for conta in repos.contas.listar(empresa_id).await? {
+            let Some(conta_bancaria_id) = conta.id else {
+                continue;
+            };
+            let saldo = conta.saldo
+                + movimentado
+                    .get(&conta_bancaria_id)
+                    .copied()
+                    .unwrap_or_default();
+            if saldo.is_zero() {
+                continue;
+            }
+            let na_conta = saldo.abs();
+            let saldo = cambio::em_reais(
+                repos,
+                empresa_id,
+                saldo,
+                conta.moeda,
+                Utc::now().date_naive(),
+            )
+            .await?;
+            let banco = conta_do_banco(repos, &mut plano, conta_bancaria_id).await?;
+            if saldo.positivo() {
+                linhas.push(na_moeda(debito(banco, saldo), conta.moeda, na_conta));
+                linhas.push(credito(saldos_iniciais, saldo));
+            } else {
+                linhas.push(debito(saldos_iniciais, -saldo));
+                linhas.push(na_moeda(credito(banco, -saldo), conta.moeda, na_conta));
+            }
+        }
+
+        let utilizado: Dinheiro = repos
+            .compras_cartao
+            .utilizado_por_cartao(empresa_id, None)
+            .await?
+            .values()
+            .sum();
+        if utilizado.positivo() {
+            linhas.push(debito(saldos_iniciais, utilizado));
+            linhas.push(credito(plano.id(CARTOES_A_PAGAR)?, utilizado));
+        }
+
+        if linhas.is_empty() {
+            continue;
+        }
+        registrar(
+            repos,
+            &plano,
+            NovaPartida {
+                empresa_id,
+                data: Utc::now().date_naive(),
+                historico: "Saldos de abertura".to_string(),
+                origem: OrigemPartida::Abertura,
+                origem_id: None,
+                linhas,
+            },
+        )
+        .await?;
+        tracing::info!(
+            "📒 Saldos de abertura lançados para a empresa {}",
+            empresa.nome
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use utoipa::{
+    openapi::path::{Parameter, ParameterBuilder, ParameterIn},
+    IntoParams, PartialSchema,
+};
+
+use crate::{
+    auth::UsuarioAutenticado,
+    error::{AppError, Result},
+    models::{Empresa, Papel},
+    repositorio::Repositorios,
+};
+
+/// Cabeçalho usado para escolher a empresa quando o usuário tem acesso a
+/// mais de uma.
+pub const CABECALHO_EMPRESA: &str = "x-empresa-id";
+
+/// Empresa da requisição. Usado como extractor nos handlers.
+///
+/// Vem do cabeçalho `X-Empresa-Id` ou, na ausência dele, da primeira
+/// empresa vinculada ao usuário. Usuários comuns só podem escolher
+/// empresas às quais estão vinculados; administradores, qualquer empresa
+/// ativa.
+#[derive(Debug, Clone, Copy)]
+pub struct EmpresaAtual(pub ObjectId);
+
+impl EmpresaAtual {
+    pub fn id(&self) -> ObjectId {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for EmpresaAtual
+where
+    Repositorios: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let usuario = UsuarioAutenticado::from_request_parts(parts, state).await?;
+
+        let escolhida = parts
+            .headers
+            .get(CABECALHO_EMPRESA)
+            .map(|valor| ObjectId::parse_str(valor.to_str().unwrap_or_default().trim()))
+            .transpose()?;
+
+        let Some(empresa_id) = escolhida else {
+            return usuario
+                .empresas
+                .first()
+                .copied()
+                .map(EmpresaAtual)
+                .ok_or_else(|| {
+                    AppError::BadRequest(
+                        "Nenhuma empresa vinculada ao usuário; informe o cabeçalho X-Empresa-Id"
+                            .to_string(),
+                    )
+                });
+        };
+
+        if usuario.empresas.contains(&empresa_id) {
+            return Ok(EmpresaAtual(empresa_id));
+        }
+        if !usuario.papeis.contains(&Papel::Admin) {
+            return Err(AppError::Forbidden);
+        }
+
+        let repos = Repositorios::from_ref(state);
+        repos
+            .empresas
+            .buscar(empresa_id)
+            .await?
+            .filter(|empresa| empresa.ativo)
+            .ok_or(AppError::NotFound)?;
+        Ok(EmpresaAtual(empresa_id))
+    }
+}
+
+impl IntoParams for EmpresaAtual {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        vec![ParameterBuilder::new()
+            .name("X-Empresa-Id")
+            .parameter_in(ParameterIn::Header)
+            .schema(Some(String::schema()))
+            .description(Some(
+                "Empresa da requisição; sem ele, a primeira vinculada ao usuário",
+            ))
+            .build()]
+    }
+}
+
+/// Garante que exista ao menos uma empresa e atribui à empresa padrão os
+/// documentos e usuários gravados antes da separação por empresa.
+pub async fn migrar(repos: &Repositorios) -> anyhow::Result<()> {
+    let padrao = match repos.empresas.primeira().await? {
+        Some(empresa) => empresa.id.expect("empresa gravada sem id"),
+        None => {
+            let now = Utc::now();
+            let empresa = Empresa {
+                id: None,
+                nome: std::env::var("EMPRESA_NOME")
+                    .unwrap_or_else(|_| "Empresa padrão".to_string()),
+                documento: None,
+                pais: "BR".to_string(),
+                fiscal: None,
+                ativo: true,
+                created_at: now,
+                updated_at: now,
+            };
+            let empresa = repos.empresas.criar(empresa).await?;
+            tracing::info!("🏢 Empresa padrão criada: {}", empresa.nome);
+            empresa.id.expect("criar retorna o id gerado")
+        }
+    };
+
+    repos.empresas.atribuir_sem_empresa(padrao).await?;
+    Ok(())
+}
+
+/// Converte e valida os ids de empresa recebidos na API.
+pub async fn parse_empresas(repos: &Repositorios, ids: &[String]) -> Result<Vec<ObjectId>> {
+    let mut empresas = Vec::with_capacity(ids.len());
+    for id in ids {
+        let oid = ObjectId::parse_str(id)?;
+        repos
+            .empresas
+            .buscar(oid)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("Empresa {} não encontrada", id)))?;
+        if !empresas.contains(&oid) {
+            empresas.push(oid);
+        }
+    }
+    Ok(empresas)
+}
@@ -3,30 +3,129 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use mongodb::error::{ErrorKind, WriteFailure};
+use serde::Serialize;
+use utoipa::ToSchema;
 
 pub type Result<T> = std::result::Result<T, AppError>;
 
+/// Código do MongoDB para chave duplicada.
+const CHAVE_DUPLICADA: i32 = 11000;
+
+/// Campo rejeitado pela validação de um payload.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErroCampo {
+    pub campo: String,
+    pub mensagem: String,
+}
+
+/// Corpo de todas as respostas de erro.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CorpoErro {
+    pub error: String,
+    /// Campos rejeitados, nas respostas 422.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub campos: Vec<ErroCampo>,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     #[error("Database error: {0}")]
     Database(#[from] mongodb::error::Error),
 
+    #[error("SQL error: {0}")]
+    Sql(#[from] sqlx::Error),
+
     #[error("Not found")]
     NotFound,
 
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Forbidden")]
+    Forbidden,
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Precondition failed: {0}")]
+    PreconditionFailed(String),
+
     #[error("Invalid ObjectId: {0}")]
     InvalidObjectId(#[from] mongodb::bson::oid::Error),
+
+    #[error("Validation failed: {0:?}")]
+    Validacao(Vec<ErroCampo>),
+}
+
+impl AppError {
+    /// Gravação recusada por um índice único do MongoDB ou uma restrição
+    /// `UNIQUE` do SQL.
+    pub fn duplicado(&self) -> bool {
+        match self {
+            AppError::Database(erro) => chave_duplicada(erro),
+            AppError::Sql(sqlx::Error::Database(erro)) => erro.is_unique_violation(),
+            _ => false,
+        }
+    }
+}
+
+pub fn chave_duplicada(erro: &mongodb::error::Error) -> bool {
+    match erro.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(falha)) => falha.code == CHAVE_DUPLICADA,
+        ErrorKind::BulkWrite(falha) => falha
+            .write_errors
+            .iter()
+            .flatten()
+            .any(|falha| falha.code == CHAVE_DUPLICADA),
+        ErrorKind::Command(falha) => falha.code == CHAVE_DUPLICADA,
+        _ => false,
+    }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if self.duplicado() {
+            let corpo = CorpoErro {
+                error: "Já existe um registro com os mesmos dados".to_string(),
+                campos: Vec::new(),
+            };
+            return (StatusCode::CONFLICT, Json(corpo)).into_response();
+        }
+
         let (status, message) = match self {
             AppError::Database(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            AppError::Sql(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             AppError::NotFound => (StatusCode::NOT_FOUND, "Recurso não encontrado".to_string()),
+            AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Não autenticado".to_string()),
+            AppError::Forbidden => (StatusCode::FORBIDDEN, "Acesso negado".to_string()),
+            AppError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            AppError::Conflict(message) => (StatusCode::CONFLICT, message),
+            AppError::PreconditionFailed(message) => (StatusCode::PRECONDITION_FAILED, message),
             AppError::InvalidObjectId(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+            AppError::Validacao(campos) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(CorpoErro {
+                        error: "Dados inválidos".to_string(),
+                        campos,
+                    }),
+                )
+                    .into_response();
+            }
         };
 
-        (status, Json(json!({ "error": message }))).into_response()
+        let corpo = CorpoErro {
+            error: message,
+            campos: Vec::new(),
+        };
+        (status, Json(corpo)).into_response()
     }
 }
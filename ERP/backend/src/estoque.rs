@@ -0,0 +1,132 @@
+use chrono::Utc;
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    error::{AppError, Result},
+    models::{EstoqueCritico, MovimentacaoEstoque, TipoMovimentacao},
+    repositorio::Repositorios,
+};
+
+/// Dados de uma movimentação a ser aplicada ao estoque de um produto.
+pub struct NovaMovimentacao {
+    pub empresa_id: ObjectId,
+    pub produto_id: ObjectId,
+    pub tipo: TipoMovimentacao,
+    pub quantidade: i32,
+    pub motivo: String,
+    pub usuario: Option<String>,
+    pub venda_id: Option<ObjectId>,
+    pub pedido_compra_id: Option<ObjectId>,
+}
+
+/// Atualiza `estoque_atual` do produto e registra a movimentação.
+///
+/// Saídas só são aplicadas se houver saldo suficiente; caso contrário
+/// retorna `AppError::Conflict` sem alterar o produto. Entradas que
+/// levariam o estoque além de `i32::MAX` retornam `AppError::BadRequest`.
+pub async fn movimentar(
+    repos: &Repositorios,
+    nova: NovaMovimentacao,
+) -> Result<MovimentacaoEstoque> {
+    if nova.quantidade <= 0 {
+        return Err(AppError::BadRequest(
+            "A quantidade movimentada deve ser maior que zero".to_string(),
+        ));
+    }
+
+    let delta = match nova.tipo {
+        TipoMovimentacao::Entrada => nova.quantidade,
+        TipoMovimentacao::Saida => -nova.quantidade,
+    };
+
+    let alterado = repos
+        .produtos
+        .ajustar_estoque(nova.empresa_id, nova.produto_id, delta)
+        .await?;
+
+    if !alterado && nova.tipo == TipoMovimentacao::Entrada {
+        repos
+            .produtos
+            .buscar(nova.empresa_id, nova.produto_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        return Err(estoque_excedido());
+    }
+    if !alterado {
+        let produto = repos
+            .produtos
+            .buscar(nova.empresa_id, nova.produto_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        return Err(AppError::Conflict(format!(
+            "Estoque insuficiente para o produto {} (disponível: {})",
+            produto.nome, produto.estoque_atual
+        )));
+    }
+
+    let movimentacao = MovimentacaoEstoque {
+        id: None,
+        empresa_id: nova.empresa_id,
+        produto_id: nova.produto_id,
+        tipo: nova.tipo,
+        quantidade: nova.quantidade,
+        motivo: nova.motivo,
+        usuario: nova.usuario,
+        venda_id: nova.venda_id,
+        pedido_compra_id: nova.pedido_compra_id,
+        created_at: Utc::now(),
+    };
+
+    repos.movimentacoes.criar(movimentacao).await
+}
+
+/// Custo médio ponderado do produto depois de uma entrada de `quantidade`
+/// unidades a `preco_unitario`, sobre o `estoque` anterior ao custo atual.
+/// Saldo negativo ou zerado não pesa: o custo passa a ser o da entrada.
+pub fn custo_medio(
+    custo_atual: Dinheiro,
+    estoque: i32,
+    preco_unitario: Dinheiro,
+    quantidade: i32,
+) -> Result<Dinheiro> {
+    let estoque = estoque.max(0);
+    let quantidade = quantidade.max(0);
+    let total = estoque
+        .checked_add(quantidade)
+        .ok_or_else(estoque_excedido)?;
+    if total == 0 {
+        return Ok(custo_atual);
+    }
+    Ok(
+        (custo_atual.vezes(i64::from(estoque)) + preco_unitario.vezes(i64::from(quantidade)))
+            .media(total as u32),
+    )
+}
+
+fn estoque_excedido() -> AppError {
+    AppError::BadRequest(format!(
+        "A entrada levaria o estoque acima do máximo ({})",
+        i32::MAX
+    ))
+}
+
+/// Produtos ativos com `estoque_atual` igual ou abaixo do `estoque_minimo`,
+/// do mais crítico para o menos crítico.
+pub async fn criticos(repos: &Repositorios, empresa_id: ObjectId) -> Result<Vec<EstoqueCritico>> {
+    let produtos = repos.produtos.criticos(empresa_id).await?;
+
+    let mut criticos: Vec<EstoqueCritico> = produtos
+        .into_iter()
+        .map(|produto| EstoqueCritico {
+            id: produto.id.map(|id| id.to_hex()).unwrap_or_default(),
+            nome: produto.nome,
+            estoque_atual: produto.estoque_atual,
+            estoque_minimo: produto.estoque_minimo,
+            falta: produto.estoque_minimo - produto.estoque_atual,
+        })
+        .collect();
+    criticos.sort_by_key(|critico| std::cmp::Reverse(critico.falta));
+
+    Ok(criticos)
+}
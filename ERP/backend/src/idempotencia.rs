@@ -0,0 +1,318 @@
+//! Requisições idempotentes: um `POST` com o cabeçalho `Idempotency-Key` é
+//! executado uma só vez por usuário e chave, e as repetições recebem a
+//! resposta gravada da primeira sem refazer a operação. Assim o cliente
+//! pode repetir um `POST` cuja resposta se perdeu sem criar duplicatas.
+//!
+//! Só as respostas de sucesso são gravadas: depois de um erro, de um pânico
+//! no handler ou de uma requisição abandonada a mesma chave pode ser usada
+//! de novo. As chaves valem por um dia.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, LOCATION},
+        request::Parts,
+        HeaderName, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::Response,
+};
+use chrono::{Duration, Utc};
+use sha1::{Digest, Sha1};
+
+use crate::{
+    auth::UsuarioAutenticado,
+    empresa::CABECALHO_EMPRESA,
+    error::{AppError, ErroCampo, Result},
+    models::{RequisicaoIdempotente, RespostaGravada},
+    repositorio::Repositorios,
+};
+
+/// Cabeçalho com a chave escolhida pelo cliente para a operação.
+pub const CABECALHO_IDEMPOTENCIA: &str = "idempotency-key";
+
+/// Presente nas respostas reproduzidas de uma requisição anterior.
+pub const CABECALHO_REPRODUZIDA: &str = "idempotent-replayed";
+
+const VALIDADE_HORAS: i64 = 24;
+
+/// Maior corpo de requisição ou resposta guardado.
+const LIMITE_CORPO: usize = 16 * 1024 * 1024;
+
+/// Cabeçalhos da resposta que são gravados e reproduzidos.
+const CABECALHOS_GRAVADOS: [HeaderName; 4] = [CONTENT_TYPE, CONTENT_DISPOSITION, ETAG, LOCATION];
+
+/// Middleware das rotas autenticadas. Requisições sem a chave, sem usuário
+/// ou que não são `POST` seguem sem alteração.
+pub async fn idempotente(
+    State(repos): State<Repositorios>,
+    req: Request,
+    next: Next,
+) -> Result<Response> {
+    let Some(chave) = req.headers().get(CABECALHO_IDEMPOTENCIA) else {
+        return Ok(next.run(req).await);
+    };
+    let usuario_id = match req.extensions().get::<UsuarioAutenticado>() {
+        Some(usuario) if req.method() == Method::POST => usuario.id,
+        _ => return Ok(next.run(req).await),
+    };
+    let chave = chave
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|chave| (1..=255).contains(&chave.len()))
+        .ok_or_else(|| {
+            AppError::BadRequest(
+                "Idempotency-Key inválida: use de 1 a 255 caracteres ASCII".to_string(),
+            )
+        })?
+        .to_string();
+
+    let (parts, corpo) = req.into_parts();
+    let corpo = to_bytes(corpo, LIMITE_CORPO)
+        .await
+        .map_err(|_| AppError::BadRequest("Corpo da requisição grande demais".to_string()))?;
+    let agora = Utc::now();
+    let requisicao = RequisicaoIdempotente {
+        id: format!("{}:{}", usuario_id.to_hex(), chave),
+        usuario_id,
+        chave,
+        impressao: impressao(&parts, &corpo),
+        resposta: None,
+        created_at: agora,
+    };
+
+    let validas_desde = agora - Duration::hours(VALIDADE_HORAS);
+    if let Some(anterior) = repos
+        .idempotencia
+        .reservar(&requisicao, validas_desde)
+        .await?
+    {
+        if anterior.impressao != requisicao.impressao {
+            return Err(AppError::Validacao(vec![ErroCampo {
+                campo: "Idempotency-Key".to_string(),
+                mensagem: "A chave já foi usada numa requisição diferente".to_string(),
+            }]));
+        }
+        return match anterior.resposta {
+            Some(resposta) => Ok(reproduzir(resposta)),
+            None => Err(AppError::Conflict(
+                "A requisição com esta Idempotency-Key ainda está em andamento".to_string(),
+            )),
+        };
+    }
+
+    let reserva = Reserva {
+        repos,
+        id: Some(requisicao.id),
+    };
+    let response = next
+        .run(Request::from_parts(parts, Body::from(corpo)))
+        .await;
+    if !response.status().is_success() {
+        if let Err(e) = reserva.liberar().await {
+            tracing::error!("❌ Erro ao liberar a Idempotency-Key: {}", e);
+        }
+        return Ok(response);
+    }
+
+    let (partes, corpo) = response.into_parts();
+    let corpo = match to_bytes(corpo, LIMITE_CORPO).await {
+        Ok(corpo) => corpo,
+        Err(e) => {
+            reserva.liberar().await?;
+            return Err(AppError::Internal(format!(
+                "Falha ao ler a resposta para gravá-la: {}",
+                e
+            )));
+        }
+    };
+    let resposta = RespostaGravada {
+        status: partes.status.as_u16(),
+        cabecalhos: CABECALHOS_GRAVADOS
+            .iter()
+            .filter_map(|nome| {
+                let valor = partes.headers.get(nome)?.to_str().ok()?;
+                Some((nome.as_str().to_string(), valor.to_string()))
+            })
+            .collect(),
+        corpo: corpo.to_vec(),
+    };
+    // A operação já foi feita: uma falha aqui não muda a resposta.
+    if let Err(e) = reserva.concluir(&resposta).await {
+        tracing::error!("❌ Erro ao gravar a resposta da Idempotency-Key: {}", e);
+    }
+
+    Ok(Response::from_parts(partes, Body::from(corpo)))
+}
+
+/// Chave reservada enquanto a requisição é executada. Se ela for
+/// descartada sem [`Reserva::concluir`] nem [`Reserva::liberar`] (pânico no
+/// handler ou cliente que desistiu), a reserva é desfeita em segundo plano;
+/// do contrário as repetições receberiam 409 até a chave expirar.
+struct Reserva {
+    repos: Repositorios,
+    /// `None` depois de concluída ou liberada.
+    id: Option<String>,
+}
+
+impl Reserva {
+    async fn concluir(mut self, resposta: &RespostaGravada) -> Result<()> {
+        let id = self.id.take().unwrap_or_default();
+        self.repos.idempotencia.concluir(&id, resposta).await
+    }
+
+    async fn liberar(mut self) -> Result<()> {
+        let id = self.id.take().unwrap_or_default();
+        self.repos.idempotencia.liberar(&id).await
+    }
+}
+
+impl Drop for Reserva {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::error!("❌ Idempotency-Key {} ficou reservada", id);
+            return;
+        };
+        let repos = self.repos.clone();
+        runtime.spawn(async move {
+            if let Err(e) = repos.idempotencia.liberar(&id).await {
+                tracing::error!("❌ Erro ao liberar a Idempotency-Key: {}", e);
+            }
+        });
+    }
+}
+
+/// Resumo do que identifica a operação: método, caminho, empresa e corpo.
+fn impressao(parts: &Parts, corpo: &[u8]) -> String {
+    let mut hash = Sha1::new();
+    hash.update(parts.method.as_str());
+    hash.update(b"\n");
+    hash.update(parts.uri.to_string());
+    hash.update(b"\n");
+    if let Some(empresa) = parts.headers.get(CABECALHO_EMPRESA) {
+        hash.update(empresa.as_bytes());
+    }
+    hash.update(b"\n");
+    hash.update(corpo);
+    format!("{:x}", hash.finalize())
+}
+
+fn reproduzir(resposta: RespostaGravada) -> Response {
+    let mut response = Response::new(Body::from(resposta.corpo));
+    *response.status_mut() = StatusCode::from_u16(resposta.status).unwrap_or(StatusCode::OK);
+    let cabecalhos = response.headers_mut();
+    for (nome, valor) in resposta.cabecalhos {
+        if let (Ok(nome), Ok(valor)) = (HeaderName::try_from(nome), HeaderValue::try_from(valor)) {
+            cabecalhos.insert(nome, valor);
+        }
+    }
+    cabecalhos.insert(CABECALHO_REPRODUZIDA, HeaderValue::from_static("true"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use axum::{middleware, routing::post, Extension, Router};
+    use mongodb::bson::oid::ObjectId;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    /// Rota cuja primeira chamada executa `primeira` e as seguintes
+    /// respondem 200, atrás do middleware.
+    async fn app<F, R>(primeira: F) -> Router
+    where
+        F: Fn() -> R + Clone + Send + Sync + 'static,
+        R: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let chamada = Arc::new(AtomicBool::new(false));
+        let handler = move || {
+            let chamada = chamada.clone();
+            let primeira = primeira.clone();
+            async move {
+                if !chamada.swap(true, Ordering::SeqCst) {
+                    primeira().await;
+                }
+                "ok"
+            }
+        };
+        let usuario = UsuarioAutenticado {
+            id: ObjectId::new(),
+            nome: "Teste".to_string(),
+            email: "teste@exemplo.com".to_string(),
+            papeis: Vec::new(),
+            empresas: Vec::new(),
+        };
+        Router::new()
+            .route("/", post(handler))
+            .layer(middleware::from_fn_with_state(
+                Repositorios::em_memoria().await,
+                idempotente,
+            ))
+            .layer(Extension(usuario))
+    }
+
+    fn requisicao() -> Request {
+        Request::post("/")
+            .header(CABECALHO_IDEMPOTENCIA, "chave-1")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    /// Repete a requisição até a reserva anterior ser desfeita, que
+    /// acontece em segundo plano.
+    async fn repetir(app: Router) -> StatusCode {
+        for _ in 0..50 {
+            let status = app.clone().oneshot(requisicao()).await.unwrap().status();
+            if status != StatusCode::CONFLICT {
+                return status;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        StatusCode::CONFLICT
+    }
+
+    #[tokio::test]
+    async fn panico_no_handler_libera_a_chave() {
+        let app = app(|| async { panic!("falha no handler") }).await;
+        let execucao = tokio::spawn(app.clone().oneshot(requisicao()));
+        assert!(execucao.await.unwrap_err().is_panic());
+
+        assert_eq!(repetir(app).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn requisicao_abandonada_libera_a_chave() {
+        let app = app(std::future::pending).await;
+        let execucao = tokio::spawn(app.clone().oneshot(requisicao()));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let status = app.clone().oneshot(requisicao()).await.unwrap().status();
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        execucao.abort();
+        assert!(execucao.await.unwrap_err().is_cancelled());
+        assert_eq!(repetir(app).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn repeticao_concluida_reproduz_a_resposta() {
+        let app = app(|| async {}).await;
+        let primeira = app.clone().oneshot(requisicao()).await.unwrap();
+        assert_eq!(primeira.status(), StatusCode::OK);
+        assert!(primeira.headers().get(CABECALHO_REPRODUZIDA).is_none());
+
+        let repetida = app.oneshot(requisicao()).await.unwrap();
+        assert_eq!(repetida.status(), StatusCode::OK);
+        assert!(repetida.headers().get(CABECALHO_REPRODUZIDA).is_some());
+    }
+}
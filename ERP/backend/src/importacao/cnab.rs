@@ -0,0 +1,269 @@
+//! Retorno de cobrança nos layouts FEBRABAN 240 e CNAB 400. Apenas as
+//! ocorrências de liquidação viram transações; as demais (entrada
+//! confirmada, baixa, tarifa...) são ignoradas.
+
+use chrono::NaiveDate;
+use erp_dinheiro::Dinheiro;
+
+use super::{ErroImportacao, TransacaoImportada};
+
+/// Códigos de movimento/ocorrência de liquidação comuns aos bancos.
+const LIQUIDACOES: [&str; 2] = ["06", "17"];
+
+pub fn ler_240(conteudo: &str) -> Result<Vec<TransacaoImportada>, ErroImportacao> {
+    let mut transacoes = Vec::new();
+    let mut segmento_t: Option<(usize, String)> = None;
+
+    for (indice, linha) in linhas(conteudo) {
+        let erro = |motivo: &str| ErroImportacao::Registro {
+            linha: indice,
+            motivo: motivo.to_string(),
+        };
+        if linha.len() != 240 {
+            return Err(erro("registro CNAB 240 deve ter 240 posições"));
+        }
+        if campo(&linha, 8, 8) != "3" {
+            continue;
+        }
+
+        match campo(&linha, 14, 14) {
+            "T" => segmento_t = Some((indice, linha)),
+            "U" => {
+                let Some((_, t)) = segmento_t.take() else {
+                    return Err(erro("segmento U sem segmento T correspondente"));
+                };
+                if !LIQUIDACOES.contains(&campo(&t, 16, 17)) {
+                    continue;
+                }
+
+                let nosso_numero = campo(&t, 38, 57).to_string();
+                let valor_pago =
+                    valor(campo(&linha, 78, 92)).ok_or_else(|| erro("valor pago inválido"))?;
+                let data = data_ddmmaaaa(campo(&linha, 146, 153))
+                    .or_else(|| data_ddmmaaaa(campo(&linha, 138, 145)))
+                    .ok_or_else(|| erro("data de crédito inválida"))?;
+                let documento = campo(&t, 59, 73);
+
+                transacoes.push(TransacaoImportada {
+                    data,
+                    valor: valor_pago,
+                    descricao: format!("Liquidação título {}", nosso_numero),
+                    documento: (!documento.is_empty()).then(|| documento.to_string()),
+                    identificador: nosso_numero,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(transacoes)
+}
+
+pub fn ler_400(conteudo: &str) -> Result<Vec<TransacaoImportada>, ErroImportacao> {
+    let mut transacoes = Vec::new();
+    let mut banco = String::new();
+
+    for (indice, linha) in linhas(conteudo) {
+        let erro = |motivo: &str| ErroImportacao::Registro {
+            linha: indice,
+            motivo: motivo.to_string(),
+        };
+        if linha.len() != 400 {
+            return Err(erro("registro CNAB 400 deve ter 400 posições"));
+        }
+
+        match campo(&linha, 1, 1) {
+            "0" => banco = campo(&linha, 77, 79).to_string(),
+            "1" => {
+                if !LIQUIDACOES.contains(&campo(&linha, 109, 110)) {
+                    continue;
+                }
+
+                // Cada banco posiciona o nosso número de um jeito no CNAB 400.
+                let nosso_numero = match banco.as_str() {
+                    "341" => campo(&linha, 63, 70),
+                    "001" => campo(&linha, 64, 80),
+                    _ => campo(&linha, 71, 82),
+                }
+                .to_string();
+                let valor_pago =
+                    valor(campo(&linha, 254, 266)).ok_or_else(|| erro("valor pago inválido"))?;
+                let data = data_ddmmaa(campo(&linha, 296, 301))
+                    .or_else(|| data_ddmmaa(campo(&linha, 111, 116)))
+                    .ok_or_else(|| erro("data de crédito inválida"))?;
+                let documento = campo(&linha, 117, 126);
+
+                transacoes.push(TransacaoImportada {
+                    data,
+                    valor: valor_pago,
+                    descricao: format!("Liquidação título {}", nosso_numero),
+                    documento: (!documento.is_empty()).then(|| documento.to_string()),
+                    identificador: nosso_numero,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(transacoes)
+}
+
+/// Linhas não vazias, numeradas a partir de 1, já convertidas para ASCII
+/// posicional (um caractere por posição).
+fn linhas(conteudo: &str) -> impl Iterator<Item = (usize, String)> + '_ {
+    conteudo
+        .lines()
+        .enumerate()
+        .map(|(indice, linha)| (indice + 1, linha.trim_end_matches('\r')))
+        .filter(|(_, linha)| !linha.trim().is_empty())
+        .map(|(indice, linha)| {
+            let ascii = linha
+                .chars()
+                .map(|c| if c.is_ascii() { c } else { ' ' })
+                .collect();
+            (indice, ascii)
+        })
+}
+
+/// Campo pelas posições inicial e final (1-based, inclusivas) do layout.
+fn campo(linha: &str, inicio: usize, fim: usize) -> &str {
+    linha.get(inicio - 1..fim).unwrap_or_default().trim()
+}
+
+/// Valores monetários CNAB têm duas casas decimais implícitas.
+fn valor(campo: &str) -> Option<Dinheiro> {
+    campo.parse::<i64>().ok().map(Dinheiro::de_centavos)
+}
+
+fn data_ddmmaaaa(campo: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(campo, "%d%m%Y").ok()
+}
+
+fn data_ddmmaa(campo: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(campo, "%d%m%y").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Registro de `tamanho` posições em branco com os campos dados pela
+    /// posição inicial (1-based).
+    fn registro(tamanho: usize, campos: &[(usize, &str)]) -> String {
+        let mut linha = vec![b' '; tamanho];
+        for (inicio, texto) in campos {
+            linha[inicio - 1..inicio - 1 + texto.len()].copy_from_slice(texto.as_bytes());
+        }
+        String::from_utf8(linha).unwrap()
+    }
+
+    fn d(valor: &str) -> Dinheiro {
+        valor.parse().unwrap()
+    }
+
+    fn segmentos(movimento: &str, nosso_numero: &str, valor: &str) -> [String; 2] {
+        [
+            registro(
+                240,
+                &[
+                    (1, "34100013"),
+                    (14, "T"),
+                    (16, movimento),
+                    (38, nosso_numero),
+                    (59, "NF 4521"),
+                ],
+            ),
+            registro(
+                240,
+                &[
+                    (1, "34100013"),
+                    (14, "U"),
+                    (78, valor),
+                    (138, "14032024"),
+                    (146, "15032024"),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn le_retorno_240() {
+        let [t, u] = segmentos("06", "00000000000000012345", "000000000015075");
+        let [entrada_t, entrada_u] = segmentos("02", "00000000000000099999", "000000000000000");
+        let arquivo = [
+            registro(240, &[(1, "34100000")]),
+            t,
+            u,
+            entrada_t,
+            entrada_u,
+            registro(240, &[(1, "34199999")]),
+        ]
+        .join("\r\n");
+
+        let transacoes = ler_240(&arquivo).unwrap();
+        assert_eq!(
+            transacoes,
+            vec![TransacaoImportada {
+                data: NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+                valor: d("150.75"),
+                descricao: "Liquidação título 00000000000000012345".to_string(),
+                documento: Some("NF 4521".to_string()),
+                identificador: "00000000000000012345".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn rejeita_240_fora_do_tamanho() {
+        let [_, u] = segmentos("06", "1", "1");
+        assert!(matches!(
+            ler_240("curta"),
+            Err(ErroImportacao::Registro { linha: 1, .. })
+        ));
+        assert!(matches!(
+            ler_240(&u),
+            Err(ErroImportacao::Registro { linha: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn le_retorno_400_pelo_layout_do_banco() {
+        let detalhe = |nosso_numero: (usize, &str)| {
+            registro(
+                400,
+                &[
+                    (1, "1"),
+                    nosso_numero,
+                    (109, "06"),
+                    (111, "140324"),
+                    (117, "DOC-77"),
+                    (254, "0000000009990"),
+                    (296, "150324"),
+                ],
+            )
+        };
+
+        let itau = [
+            registro(400, &[(1, "0"), (77, "341")]),
+            detalhe((63, "12345678")),
+            registro(400, &[(1, "9")]),
+        ]
+        .join("\n");
+        let transacoes = ler_400(&itau).unwrap();
+        assert_eq!(transacoes.len(), 1);
+        assert_eq!(transacoes[0].identificador, "12345678");
+        assert_eq!(transacoes[0].valor, d("99.90"));
+        assert_eq!(
+            transacoes[0].data,
+            NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()
+        );
+        assert_eq!(transacoes[0].documento.as_deref(), Some("DOC-77"));
+
+        let bradesco = [
+            registro(400, &[(1, "0"), (77, "237")]),
+            detalhe((71, "000000004321")),
+        ]
+        .join("\n");
+        assert_eq!(ler_400(&bradesco).unwrap()[0].identificador, "000000004321");
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::NaiveDate;
+use erp_dinheiro::{Moeda, TaxaCambio};
+
+use super::{CotacaoImportada, ErroImportacao};
+
+/// Colunas do arquivo de cotações do Banco Central: data, código, tipo,
+/// moeda, compra, venda, paridade de compra e de venda.
+const COLUNAS_PTAX: usize = 8;
+
+/// Lê uma cotação por linha, em `data;moeda;taxa` (ou separado por
+/// vírgulas), com cabeçalho opcional, ou no layout do arquivo de cotações
+/// do Banco Central, do qual usa a taxa de venda. Com ponto e vírgula, a
+/// taxa pode ter vírgula decimal.
+pub fn ler(conteudo: &str) -> Result<Vec<CotacaoImportada>, ErroImportacao> {
+    let mut cotacoes = Vec::new();
+
+    for (indice, linha) in conteudo.lines().enumerate() {
+        let linha = linha.trim().trim_start_matches('\u{feff}');
+        if linha.is_empty() {
+            continue;
+        }
+        let numero = indice + 1;
+        let erro = |motivo: String| ErroImportacao::Registro {
+            linha: numero,
+            motivo,
+        };
+
+        let separador = if linha.contains(';') { ';' } else { ',' };
+        let campos: Vec<&str> = linha
+            .split(separador)
+            .map(|campo| campo.trim().trim_matches('"'))
+            .collect();
+        let (data, moeda, taxa) = match campos.len() {
+            3 => (campos[0], campos[1], campos[2]),
+            COLUNAS_PTAX => (campos[0], campos[3], campos[5]),
+            quantidade => {
+                return Err(erro(format!(
+                    "esperadas as colunas data, moeda e taxa; encontradas {}",
+                    quantidade
+                )))
+            }
+        };
+
+        let Some(data) = ler_data(data) else {
+            // Só a primeira linha pode ser o cabeçalho.
+            if cotacoes.is_empty() && !data.chars().any(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            return Err(erro(format!("data inválida: {}", data)));
+        };
+        let moeda: Moeda = moeda.parse().map_err(erro)?;
+        if moeda == Moeda::Brl {
+            return Err(erro("o real não tem cotação".to_string()));
+        }
+        let taxa = if separador == ';' && taxa.contains(',') {
+            taxa.replace('.', "").replace(',', ".")
+        } else {
+            taxa.to_string()
+        };
+        let taxa: TaxaCambio = taxa.parse().map_err(|e| erro(format!("{}", e)))?;
+
+        cotacoes.push(CotacaoImportada { moeda, data, taxa });
+    }
+
+    Ok(cotacoes)
+}
+
+/// `AAAA-MM-DD`, `DD/MM/AAAA` ou `DDMMAAAA`, como no arquivo do Banco
+/// Central.
+fn ler_data(texto: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%d/%m/%Y", "%d%m%Y"]
+        .iter()
+        .find_map(|formato| NaiveDate::parse_from_str(texto, formato).ok())
+}
@@ -0,0 +1,96 @@
+//! Leitura de extratos bancários (OFX) e arquivos de retorno de cobrança
+//! (CNAB 240/400) em transações prontas para conciliação, e de tabelas de
+//! cotações de moedas (CSV).
+
+mod cnab;
+mod cotacoes;
+mod ofx;
+
+use chrono::NaiveDate;
+use erp_dinheiro::{Dinheiro, Moeda, TaxaCambio};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatoArquivo {
+    Ofx,
+    Cnab240,
+    Cnab400,
+}
+
+/// Transação lida do arquivo, antes de ser gravada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransacaoImportada {
+    pub data: NaiveDate,
+    /// Créditos positivos, débitos negativos.
+    pub valor: Dinheiro,
+    pub descricao: String,
+    pub documento: Option<String>,
+    /// Identificador único no banco: FITID no OFX, nosso número no CNAB.
+    pub identificador: String,
+}
+
+/// Cotação lida do arquivo, em reais por unidade da moeda.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CotacaoImportada {
+    pub moeda: Moeda,
+    pub data: NaiveDate,
+    pub taxa: TaxaCambio,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ErroImportacao {
+    #[error("formato de arquivo não reconhecido")]
+    FormatoDesconhecido,
+
+    #[error("linha {linha}: {motivo}")]
+    Registro { linha: usize, motivo: String },
+}
+
+/// Decodifica o arquivo (UTF-8 ou Latin-1, comum nos bancos) e identifica
+/// o formato quando não informado.
+pub fn importar(
+    bytes: &[u8],
+    formato: Option<FormatoArquivo>,
+) -> Result<(FormatoArquivo, Vec<TransacaoImportada>), ErroImportacao> {
+    let conteudo = decodificar(bytes);
+
+    let formato = match formato {
+        Some(formato) => formato,
+        None => detectar(&conteudo).ok_or(ErroImportacao::FormatoDesconhecido)?,
+    };
+
+    let transacoes = match formato {
+        FormatoArquivo::Ofx => ofx::ler(&conteudo)?,
+        FormatoArquivo::Cnab240 => cnab::ler_240(&conteudo)?,
+        FormatoArquivo::Cnab400 => cnab::ler_400(&conteudo)?,
+    };
+    Ok((formato, transacoes))
+}
+
+/// Lê uma tabela de cotações em CSV, em UTF-8 ou Latin-1.
+pub fn importar_cotacoes(bytes: &[u8]) -> Result<Vec<CotacaoImportada>, ErroImportacao> {
+    cotacoes::ler(&decodificar(bytes))
+}
+
+fn decodificar(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(texto) => texto.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+fn detectar(conteudo: &str) -> Option<FormatoArquivo> {
+    let inicio = conteudo.trim_start();
+    if inicio.starts_with("OFXHEADER") || inicio.contains("<OFX>") {
+        return Some(FormatoArquivo::Ofx);
+    }
+
+    let primeira = conteudo.lines().next()?.trim_end_matches('\r');
+    match primeira.chars().count() {
+        240 => Some(FormatoArquivo::Cnab240),
+        400 => Some(FormatoArquivo::Cnab400),
+        _ => None,
+    }
+}
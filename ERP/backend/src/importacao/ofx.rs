@@ -0,0 +1,191 @@
+use std::borrow::Cow;
+
+use chrono::NaiveDate;
+use erp_dinheiro::{Decimal, Dinheiro};
+
+use super::{ErroImportacao, TransacaoImportada};
+
+/// Lê os blocos `<STMTTRN>` de um OFX 1.x (SGML) ou 2.x (XML).
+pub fn ler(conteudo: &str) -> Result<Vec<TransacaoImportada>, ErroImportacao> {
+    let mut transacoes = Vec::new();
+
+    for (indice, bloco) in conteudo.split("<STMTTRN>").skip(1).enumerate() {
+        let bloco = bloco.split("</STMTTRN>").next().unwrap_or(bloco);
+        let registro = indice + 1;
+        let erro = |motivo: &str| ErroImportacao::Registro {
+            linha: registro,
+            motivo: motivo.to_string(),
+        };
+
+        let data = tag(bloco, "DTPOSTED")
+            .and_then(|valor| valor.get(..8))
+            .and_then(|valor| NaiveDate::parse_from_str(valor, "%Y%m%d").ok())
+            .ok_or_else(|| erro("DTPOSTED ausente ou inválido"))?;
+        let valor = tag(bloco, "TRNAMT")
+            .and_then(valor_decimal)
+            .map(Dinheiro::arredondar)
+            .ok_or_else(|| erro("TRNAMT ausente ou inválido"))?;
+        let identificador = tag(bloco, "FITID").ok_or_else(|| erro("FITID ausente"))?;
+
+        let descricao = tag(bloco, "MEMO")
+            .or_else(|| tag(bloco, "NAME"))
+            .unwrap_or_default();
+        let documento = tag(bloco, "CHECKNUM").or_else(|| tag(bloco, "REFNUM"));
+
+        transacoes.push(TransacaoImportada {
+            data,
+            valor,
+            descricao: desescapar(descricao).into_owned(),
+            documento: documento.map(|documento| desescapar(documento).into_owned()),
+            identificador: desescapar(identificador).into_owned(),
+        });
+    }
+
+    Ok(transacoes)
+}
+
+/// Valor de uma tag; no OFX SGML a tag não é fechada e o valor vai até a
+/// próxima tag ou quebra de linha.
+fn tag<'a>(bloco: &'a str, nome: &str) -> Option<&'a str> {
+    let abertura = format!("<{}>", nome);
+    let inicio = bloco.find(&abertura)? + abertura.len();
+    let resto = &bloco[inicio..];
+    let fim = resto.find(['<', '\n', '\r']).unwrap_or(resto.len());
+    let valor = resto[..fim].trim();
+    (!valor.is_empty()).then_some(valor)
+}
+
+/// `TRNAMT` com ponto ou vírgula decimal, com ou sem separador de milhar
+/// (`-1234.56`, `1.234,56`, `1,234.56`): o último separador é o decimal.
+fn valor_decimal(valor: &str) -> Option<Decimal> {
+    let normalizado: String = match valor.rfind([',', '.']) {
+        Some(posicao) => {
+            let (inteiro, fracao) = valor.split_at(posicao);
+            inteiro
+                .chars()
+                .filter(|c| !matches!(c, '.' | ','))
+                .chain(std::iter::once('.'))
+                .chain(fracao[1..].chars())
+                .collect()
+        }
+        None => valor.to_string(),
+    };
+    normalizado.parse().ok()
+}
+
+/// Troca as entidades XML (`&amp;`, `&lt;`, `&#233;`...) pelos caracteres;
+/// entidades desconhecidas ficam como estão.
+fn desescapar(texto: &str) -> Cow<'_, str> {
+    if !texto.contains('&') {
+        return Cow::Borrowed(texto);
+    }
+
+    let mut saida = String::with_capacity(texto.len());
+    let mut resto = texto;
+    while let Some(inicio) = resto.find('&') {
+        saida.push_str(&resto[..inicio]);
+        resto = &resto[inicio..];
+        let entidade = resto.find(';').map(|fim| (&resto[1..fim], fim));
+        let caractere = entidade.and_then(|(nome, _)| match nome {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let codigo = nome.strip_prefix('#')?;
+                let codigo = match codigo.strip_prefix(['x', 'X']) {
+                    Some(hexa) => u32::from_str_radix(hexa, 16).ok()?,
+                    None => codigo.parse().ok()?,
+                };
+                char::from_u32(codigo)
+            }
+        });
+        match (caractere, entidade) {
+            (Some(caractere), Some((_, fim))) => {
+                saida.push(caractere);
+                resto = &resto[fim + 1..];
+            }
+            _ => {
+                saida.push('&');
+                resto = &resto[1..];
+            }
+        }
+    }
+    saida.push_str(resto);
+    Cow::Owned(saida)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(valor: &str) -> Dinheiro {
+        valor.parse().unwrap()
+    }
+
+    const SGML: &str = "OFXHEADER:100\r\nDATA:OFXSGML\r\n\r\n<OFX>\r\n<BANKTRANLIST>\r\n\
+        <STMTTRN>\r\n<TRNTYPE>DEBIT\r\n<DTPOSTED>20240305120000[-3:BRT]\r\n\
+        <TRNAMT>-1.234,56\r\n<FITID>2024030501\r\n<CHECKNUM>000123\r\n\
+        <MEMO>Pgto Silva &amp; Filhos\r\n</STMTTRN>\r\n\
+        <STMTTRN>\r\n<TRNTYPE>CREDIT\r\n<DTPOSTED>20240306\r\n<TRNAMT>250,00\r\n\
+        <FITID>2024030601\r\n<NAME>PIX &lt;Maria&gt;\r\n</STMTTRN>\r\n\
+        </BANKTRANLIST>\r\n</OFX>\r\n";
+
+    #[test]
+    fn le_ofx_sgml() {
+        let transacoes = ler(SGML).unwrap();
+        assert_eq!(transacoes.len(), 2);
+
+        assert_eq!(
+            transacoes[0],
+            TransacaoImportada {
+                data: NaiveDate::from_ymd_opt(2024, 3, 5).unwrap(),
+                valor: d("-1234.56"),
+                descricao: "Pgto Silva & Filhos".to_string(),
+                documento: Some("000123".to_string()),
+                identificador: "2024030501".to_string(),
+            }
+        );
+        assert_eq!(transacoes[1].valor, d("250.00"));
+        assert_eq!(transacoes[1].descricao, "PIX <Maria>");
+        assert_eq!(transacoes[1].documento, None);
+    }
+
+    #[test]
+    fn le_ofx_xml() {
+        let ofx = "<?xml version=\"1.0\"?><OFX><STMTTRN><DTPOSTED>20240110</DTPOSTED>\
+            <TRNAMT>1,234.50</TRNAMT><FITID>A1</FITID><MEMO>Caf&#233; &amp; cia</MEMO>\
+            </STMTTRN></OFX>";
+        let transacoes = ler(ofx).unwrap();
+        assert_eq!(transacoes[0].valor, d("1234.50"));
+        assert_eq!(transacoes[0].descricao, "Café & cia");
+    }
+
+    #[test]
+    fn rejeita_valor_invalido() {
+        let ofx = "<STMTTRN><DTPOSTED>20240110<TRNAMT>abc<FITID>1</STMTTRN>";
+        assert!(matches!(
+            ler(ofx),
+            Err(ErroImportacao::Registro { linha: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn valores_com_separadores() {
+        assert_eq!(valor_decimal("-1234.56"), Some("-1234.56".parse().unwrap()));
+        assert_eq!(valor_decimal("1.234,56"), Some("1234.56".parse().unwrap()));
+        assert_eq!(
+            valor_decimal("1,234,567.8"),
+            Some("1234567.8".parse().unwrap())
+        );
+        assert_eq!(valor_decimal("100"), Some(Decimal::ONE_HUNDRED));
+        assert_eq!(valor_decimal("1.2.3a"), None);
+    }
+
+    #[test]
+    fn entidades_desconhecidas_ficam() {
+        assert_eq!(desescapar("A &foo; B & C"), "A &foo; B & C");
+        assert_eq!(desescapar("&#x41;&quot;"), "A\"");
+    }
+}
@@ -1,54 +1,198 @@
 use anyhow::Context;
-use axum::{routing::get, Router};
-use std::net::SocketAddr;
-use tower_http::cors::{Any, CorsLayer};
+use axum::{
+    http::{header, HeaderName, HeaderValue, Method},
+    middleware,
+    routing::get,
+    Extension, Router,
+};
+use std::{net::SocketAddr, sync::Arc};
+use tower_http::cors::CorsLayer;
 
+mod auditoria;
+mod auth;
+mod boleto;
+mod cambio;
+mod cartoes;
+mod concorrencia;
+mod consulta;
+mod contabilidade;
+mod empresa;
 mod error;
+mod estoque;
+mod idempotencia;
+mod importacao;
 mod models;
 mod mongodb;
+mod nfe;
+mod openapi;
+mod pix;
+mod recorrencias;
+mod relatorios;
+mod repositorio;
 mod routes;
+mod saldos;
+mod tributos;
+mod validacao;
 
-use mongodb::MongoDb;
+use auth::ChavesJwt;
+use models::Papel;
+use nfe::{Sefaz, SefazLocal};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
     tracing_subscriber::fmt::init();
 
-    let mongo_uri = std::env::var("MONGO_ATLAS_URI")
-        .context("A variável de ambiente MONGO_ATLAS_URI não está definida. Configure o arquivo .env do backend ou defina-a no ambiente antes de iniciar o servidor.")?;
+    let jwt_secret = std::env::var("JWT_SECRET")
+        .context("A variável de ambiente JWT_SECRET não está definida. Ela é usada para assinar os tokens de acesso.")?;
+    let jwt_validade_horas = std::env::var("JWT_VALIDADE_HORAS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .unwrap_or(8);
+    let chaves = Arc::new(ChavesJwt::new(
+        &jwt_secret,
+        chrono::Duration::hours(jwt_validade_horas),
+    ));
+
+    // Autorização simulada até a integração com os web services da SEFAZ.
+    let sefaz: Arc<dyn Sefaz> = Arc::new(SefazLocal::default());
+
+    let repos = repositorio::Repositorios::conectar().await?;
 
-    let mongo_db_name = std::env::var("MONGO_DB_NAME").unwrap_or_else(|_| "erp".to_string());
+    auth::garantir_admin(&repos).await?;
+    empresa::migrar(&repos).await?;
+    contabilidade::migrar(&repos).await?;
 
-    tracing::info!(
-        "Tentando conectar ao MongoDB Atlas (db: {})...",
-        mongo_db_name
-    );
+    recorrencias::agendar(repos.clone());
 
-    let mongo = match MongoDb::new(&mongo_uri, &mongo_db_name).await {
-        Ok(db) => {
-            tracing::info!("✅ Conectado ao MongoDB Atlas");
-            db
-        }
-        Err(e) => {
-            tracing::error!("❌ Erro ao conectar no MongoDB: {}", e);
-            return Err(e.into());
-        }
-    };
+    let origens = std::env::var("CORS_ALLOWED_ORIGINS")
+        .unwrap_or_else(|_| "http://localhost:8080,http://127.0.0.1:8080".to_string())
+        .split(',')
+        .map(|origem| origem.trim().parse::<HeaderValue>())
+        .collect::<Result<Vec<_>, _>>()
+        .context("CORS_ALLOWED_ORIGINS deve ser uma lista de origens separadas por vírgula")?;
 
     let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_origin(origens)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            header::IF_MATCH,
+            HeaderName::from_static(empresa::CABECALHO_EMPRESA),
+            HeaderName::from_static(auditoria::CABECALHO_REQUISICAO),
+            HeaderName::from_static(idempotencia::CABECALHO_IDEMPOTENCIA),
+        ])
+        .expose_headers([
+            header::ETAG,
+            HeaderName::from_static(auditoria::CABECALHO_REQUISICAO),
+            HeaderName::from_static(idempotencia::CABECALHO_REPRODUZIDA),
+        ]);
 
     let api_routes = Router::new()
-        .merge(routes::frontend::routes())
-        .nest("/financeiro", routes::financeiro::routes(mongo.clone()))
-        .nest("/bancos", routes::bancos::routes(mongo));
+        .route("/openapi.json", get(openapi::documento))
+        .nest("/auth", routes::auth::routes(repos.clone()))
+        .nest(
+            "/usuarios",
+            auth::exigir(routes::usuarios::routes(repos.clone()), &[Papel::Admin]),
+        )
+        .nest(
+            "/empresas",
+            auth::exigir(routes::empresas::routes(repos.clone()), &[]),
+        )
+        .nest(
+            "/auditoria",
+            auth::exigir(routes::auditoria::routes(repos.clone()), &[Papel::Admin]),
+        )
+        .nest(
+            "/dashboard",
+            auth::exigir(routes::dashboard::routes(repos.clone()), &[]),
+        )
+        .nest(
+            "/clientes",
+            auth::exigir(
+                routes::clientes::routes(repos.clone()),
+                &[Papel::Vendas, Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/produtos",
+            auth::exigir(
+                routes::produtos::routes(repos.clone()),
+                &[Papel::Vendas, Papel::Estoque],
+            ),
+        )
+        .nest(
+            "/vendas",
+            auth::exigir(routes::vendas::routes(repos.clone()), &[Papel::Vendas]),
+        )
+        .nest(
+            "/estoque",
+            auth::exigir(routes::estoque::routes(repos.clone()), &[Papel::Estoque]),
+        )
+        .nest(
+            "/fornecedores",
+            auth::exigir(
+                routes::fornecedores::routes(repos.clone()),
+                &[Papel::Estoque, Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/compras",
+            auth::exigir(
+                routes::compras::routes(repos.clone()),
+                &[Papel::Estoque, Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/financeiro",
+            auth::exigir(
+                routes::financeiro::routes(repos.clone()),
+                &[Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/contabilidade",
+            auth::exigir(
+                routes::contabilidade::routes(repos.clone()),
+                &[Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/pix",
+            auth::exigir(
+                routes::pix::routes(repos.clone()),
+                &[Papel::Vendas, Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/boletos",
+            auth::exigir(routes::boletos::routes(repos.clone()), &[Papel::Financeiro]),
+        )
+        .nest(
+            "/relatorios",
+            auth::exigir(
+                routes::relatorios::routes(repos.clone()),
+                &[Papel::Financeiro],
+            ),
+        )
+        .nest(
+            "/bancos",
+            auth::exigir(routes::bancos::routes(repos.clone()), &[]),
+        )
+        .layer(middleware::from_fn_with_state(
+            repos.clone(),
+            idempotencia::idempotente,
+        ))
+        .layer(middleware::from_fn_with_state(repos, auth::autenticar))
+        .layer(Extension(chaves))
+        .layer(Extension(sefaz));
 
     let app = Router::new()
         .route("/health", get(|| async { "OK" }))
         .nest("/api/v1", api_routes)
+        .layer(middleware::from_fn(auditoria::identificar_requisicao))
         .layer(cors);
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::consulta::{Campo, Listavel};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OperacaoAuditoria {
+    Criacao,
+    Alteracao,
+    Exclusao,
+}
+
+impl OperacaoAuditoria {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperacaoAuditoria::Criacao => "CRIACAO",
+            OperacaoAuditoria::Alteracao => "ALTERACAO",
+            OperacaoAuditoria::Exclusao => "EXCLUSAO",
+        }
+    }
+}
+
+/// Alteração feita por um usuário em um registro. A trilha só recebe
+/// inclusões: registros de auditoria nunca são alterados nem excluídos.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RegistroAuditoria {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    /// Coleção do registro alterado (`contas_bancarias`, `vendas` etc.).
+    pub entidade: String,
+    pub entidade_id: ObjectId,
+    pub operacao: OperacaoAuditoria,
+    pub usuario_id: ObjectId,
+    /// E-mail de quem fez a alteração.
+    pub usuario: String,
+    /// `X-Request-Id` da requisição, o mesmo devolvido na resposta.
+    pub requisicao_id: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub instante: DateTime<Utc>,
+    /// Campos que mudaram. Na criação, todos os campos preenchidos, sem
+    /// valor anterior; na exclusão, todos, sem valor novo.
+    pub alteracoes: Vec<Alteracao>,
+}
+
+/// Valor anterior e novo de um campo. Campos aninhados usam o caminho com
+/// pontos (`fiscal.regime`); listas são comparadas inteiras.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Alteracao {
+    pub campo: String,
+    pub antes: Option<Value>,
+    pub depois: Option<Value>,
+}
+
+impl Listavel for RegistroAuditoria {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("entidade"),
+        Campo::id("entidade_id"),
+        Campo::texto("operacao"),
+        Campo::id("usuario_id"),
+        Campo::texto("usuario"),
+        Campo::texto("requisicao_id"),
+        Campo::instante("instante"),
+    ];
+    const BUSCA: &'static [&'static str] = &["usuario"];
+    const ORDEM: &'static str = "-instante";
+}
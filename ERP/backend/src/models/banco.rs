@@ -1,13 +1,28 @@
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
+use crate::consulta::{Campo, Listavel};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Banco {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
     pub codigo: String,
     pub nome: String,
     pub pais: String,
     pub tipo: String,
     pub ativo: bool,
 }
+
+impl Listavel for Banco {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("codigo"),
+        Campo::texto("nome"),
+        Campo::texto("pais"),
+        Campo::texto("tipo"),
+        Campo::booleano("ativo"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "codigo"];
+    const ORDEM: &'static str = "nome";
+}
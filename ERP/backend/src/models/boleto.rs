@@ -0,0 +1,124 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+/// Tamanho do número do documento no CNAB 240.
+const MAXIMO_NUMERO_DOCUMENTO: usize = 15;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StatusBoleto {
+    /// Ainda não foi enviado ao banco em uma remessa.
+    Emitido,
+    Remetido,
+}
+
+impl StatusBoleto {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusBoleto::Emitido => "EMITIDO",
+            StatusBoleto::Remetido => "REMETIDO",
+        }
+    }
+}
+
+/// Cliente que paga o boleto, copiado do cadastro na emissão.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Pagador {
+    pub nome: String,
+    /// CPF ou CNPJ, só os algarismos.
+    pub documento: String,
+    pub endereco: Option<String>,
+    pub cidade: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+}
+
+/// Boleto de uma conta a receber. O `nosso_numero` é o que o banco devolve
+/// no arquivo de retorno.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Boleto {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    /// Conta bancária da carteira de cobrança.
+    pub conta_id: ObjectId,
+    pub lancamento_id: ObjectId,
+    /// Código FEBRABAN do banco emissor.
+    pub banco: String,
+    /// Sequencial do nosso número na conta.
+    pub sequencial: u64,
+    pub nosso_numero: String,
+    pub numero_documento: String,
+    pub valor: Dinheiro,
+    pub vencimento: NaiveDate,
+    pub pagador: Pagador,
+    pub codigo_barras: String,
+    pub linha_digitavel: String,
+    pub status: StatusBoleto,
+    /// Número da remessa que enviou o boleto ao banco.
+    pub remessa: Option<u32>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for Boleto {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("conta_id"),
+        Campo::id("lancamento_id"),
+        Campo::texto("status"),
+        Campo::texto("nosso_numero"),
+        Campo::texto("numero_documento"),
+        Campo::numero("remessa"),
+        Campo::dinheiro("valor"),
+        Campo::data("vencimento"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nosso_numero", "numero_documento"];
+    const ORDEM: &'static str = "-created_at";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateBoleto {
+    pub lancamento_id: String,
+    pub conta_id: String,
+    /// Padrão: valor do lançamento.
+    pub valor: Option<Dinheiro>,
+    /// Padrão: vencimento do lançamento.
+    pub vencimento: Option<NaiveDate>,
+    /// Padrão: o sequencial do nosso número.
+    pub numero_documento: Option<String>,
+}
+
+impl Validar for CreateBoleto {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "valor",
+                self.valor.is_none_or(|valor| valor.positivo()),
+                "O valor deve ser maior que zero",
+            )
+            .checar(
+                "numero_documento",
+                self.numero_documento.as_deref().is_none_or(|numero| {
+                    let numero = numero.trim();
+                    !numero.is_empty() && numero.chars().count() <= MAXIMO_NUMERO_DOCUMENTO
+                }),
+                "O número do documento deve ter de 1 a 15 caracteres",
+            )
+            .concluir()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GerarRemessa {
+    pub conta_id: String,
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{self, Validacao, Validar},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Cliente {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub nome: String,
+    pub cpf_cnpj: String,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub numero: Option<String>,
+    pub bairro: Option<String>,
+    pub cidade: Option<String>,
+    /// Código IBGE do município, exigido no destinatário da NF-e.
+    pub codigo_municipio: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+    /// Aumenta a cada alteração pela API e é devolvida como `ETag`.
+    /// Registros gravados antes da existência do campo estão na versão 0.
+    #[serde(default)]
+    pub versao: u32,
+}
+
+impl Listavel for Cliente {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("nome"),
+        Campo::texto("cpf_cnpj"),
+        Campo::texto("email"),
+        Campo::texto("cidade"),
+        Campo::texto("estado"),
+        Campo::booleano("ativo"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "cpf_cnpj", "email"];
+    const ORDEM: &'static str = "nome";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCliente {
+    pub nome: String,
+    pub cpf_cnpj: String,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub numero: Option<String>,
+    pub bairro: Option<String>,
+    pub cidade: Option<String>,
+    pub codigo_municipio: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCliente {
+    pub nome: Option<String>,
+    pub cpf_cnpj: Option<String>,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub numero: Option<String>,
+    pub bairro: Option<String>,
+    pub cidade: Option<String>,
+    pub codigo_municipio: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: Option<bool>,
+}
+
+pub(super) fn email_valido(email: &str) -> bool {
+    email
+        .split_once('@')
+        .is_some_and(|(usuario, dominio)| !usuario.is_empty() && dominio.contains('.'))
+}
+
+fn codigo_municipio_valido(codigo: &str) -> bool {
+    let codigo = codigo.trim();
+    codigo.len() == 7 && codigo.chars().all(|c| c.is_ascii_digit())
+}
+
+impl Validar for CreateCliente {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .obrigatorio("nome", &self.nome)
+            .checar(
+                "cpf_cnpj",
+                validacao::documento_valido(&self.cpf_cnpj),
+                "CPF, CNPJ ou NIF inválido",
+            )
+            .checar(
+                "email",
+                self.email.as_deref().is_none_or(email_valido),
+                "E-mail inválido",
+            )
+            .checar(
+                "codigo_municipio",
+                self.codigo_municipio
+                    .as_deref()
+                    .is_none_or(codigo_municipio_valido),
+                "O código IBGE do município tem 7 algarismos",
+            )
+            .concluir()
+    }
+}
+
+impl Validar for UpdateCliente {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "nome",
+                self.nome
+                    .as_deref()
+                    .is_none_or(|nome| !nome.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "cpf_cnpj",
+                self.cpf_cnpj
+                    .as_deref()
+                    .is_none_or(validacao::documento_valido),
+                "CPF, CNPJ ou NIF inválido",
+            )
+            .checar(
+                "email",
+                self.email.as_deref().is_none_or(email_valido),
+                "E-mail inválido",
+            )
+            .checar(
+                "codigo_municipio",
+                self.codigo_municipio
+                    .as_deref()
+                    .is_none_or(codigo_municipio_valido),
+                "O código IBGE do município tem 7 algarismos",
+            )
+            .concluir()
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::consulta::{Campo, Listavel};
+
+/// Pedido de compra a um fornecedor. Os itens chegam em um ou mais
+/// recebimentos; cada um dá entrada no estoque e gera uma conta a pagar.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PedidoCompra {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub fornecedor_id: ObjectId,
+    #[serde(default)]
+    pub itens: Vec<ItemPedidoCompra>,
+    pub total: Dinheiro,
+    pub status: StatusPedidoCompra,
+    pub previsao_entrega: Option<NaiveDate>,
+    pub observacoes: Option<String>,
+    #[serde(default)]
+    pub recebimentos: Vec<RecebimentoCompra>,
+    /// E-mail do usuário autenticado que fez o pedido.
+    pub usuario: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PedidoCompra {
+    /// Status conforme o que já foi recebido dos itens.
+    pub fn status_recebimento(&self) -> StatusPedidoCompra {
+        if self.itens.iter().all(|item| item.pendente() == 0) {
+            StatusPedidoCompra::Recebido
+        } else if self.itens.iter().any(|item| item.quantidade_recebida > 0) {
+            StatusPedidoCompra::Parcial
+        } else {
+            StatusPedidoCompra::Aberto
+        }
+    }
+}
+
+impl Listavel for PedidoCompra {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("fornecedor_id"),
+        Campo::texto("status"),
+        Campo::texto("usuario"),
+        Campo::dinheiro("total"),
+        Campo::data("previsao_entrega"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["observacoes", "usuario"];
+    const ORDEM: &'static str = "-created_at";
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StatusPedidoCompra {
+    /// Nada recebido ainda; só neste status os itens podem ser alterados.
+    Aberto,
+    /// Parte dos itens recebida.
+    Parcial,
+    Recebido,
+    Cancelado,
+}
+
+impl StatusPedidoCompra {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusPedidoCompra::Aberto => "ABERTO",
+            StatusPedidoCompra::Parcial => "PARCIAL",
+            StatusPedidoCompra::Recebido => "RECEBIDO",
+            StatusPedidoCompra::Cancelado => "CANCELADO",
+        }
+    }
+}
+
+/// Item embutido no documento do pedido.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemPedidoCompra {
+    pub id: ObjectId,
+    pub produto_id: ObjectId,
+    pub produto_nome: String,
+    pub quantidade: i32,
+    pub quantidade_recebida: i32,
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+}
+
+impl ItemPedidoCompra {
+    /// Quantidade ainda não recebida.
+    pub fn pendente(&self) -> i32 {
+        self.quantidade - self.quantidade_recebida
+    }
+}
+
+/// Entrega recebida de um pedido, com o que entrou no estoque e a conta a
+/// pagar gerada.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecebimentoCompra {
+    pub id: ObjectId,
+    pub data: NaiveDate,
+    /// Número da nota fiscal ou do documento de entrega do fornecedor.
+    pub documento: Option<String>,
+    pub itens: Vec<ItemRecebido>,
+    pub valor: Dinheiro,
+    pub lancamento_id: Option<ObjectId>,
+    pub usuario: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemRecebido {
+    pub item_id: ObjectId,
+    pub produto_id: ObjectId,
+    pub quantidade: i32,
+    /// Custo efetivo da entrega, que pode diferir do preço do pedido.
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatePedidoCompra {
+    pub fornecedor_id: String,
+    #[serde(default)]
+    pub itens: Vec<CreateItemPedidoCompra>,
+    pub previsao_entrega: Option<NaiveDate>,
+    pub observacoes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateItemPedidoCompra {
+    pub produto_id: String,
+    pub quantidade: i32,
+    /// Quando omitido, usa o `preco_custo` atual do produto.
+    pub preco_unitario: Option<Dinheiro>,
+}
+
+/// Alteração do pedido. Os itens, quando enviados, substituem os atuais e
+/// só podem mudar enquanto nada foi recebido.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdatePedidoCompra {
+    pub fornecedor_id: Option<String>,
+    pub itens: Option<Vec<CreateItemPedidoCompra>>,
+    pub previsao_entrega: Option<NaiveDate>,
+    pub observacoes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReceberPedidoCompra {
+    /// Itens entregues; vazio recebe tudo o que falta.
+    #[serde(default)]
+    pub itens: Vec<ReceberItemCompra>,
+    /// Data da entrega; hoje quando omitida.
+    pub data: Option<NaiveDate>,
+    pub documento: Option<String>,
+    /// Vencimento da conta a pagar; a data da entrega quando omitido.
+    pub vencimento: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReceberItemCompra {
+    pub item_id: String,
+    pub quantidade: i32,
+    /// Quando omitido, usa o preço do pedido.
+    pub preco_unitario: Option<Dinheiro>,
+}
@@ -1,69 +1,374 @@
 use chrono::{DateTime, Utc};
+use erp_dinheiro::{Dinheiro, Moeda};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    boleto::BancoBoleto,
+    cartoes,
+    consulta::{Campo, Listavel},
+    error::Result,
+    pix,
+    validacao::{self, Validacao, Validar},
+};
+
+pub use erp_api::{CarteiraBoleto, ChavePix};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ContaBancaria {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
     pub banco: String,
     pub agencia: String,
     pub conta: String,
     pub tipo: String,
-    pub saldo: f64,
+    /// Saldo inicial, na moeda da conta.
+    pub saldo: Dinheiro,
+    /// Moeda em que a conta é movimentada. Contas gravadas antes da
+    /// existência do campo são em reais.
+    #[serde(default)]
+    pub moeda: Moeda,
+    /// Chave para receber por PIX.
+    pub pix: Option<ChavePix>,
+    /// Carteira de cobrança para emitir boletos.
+    pub boleto: Option<CarteiraBoleto>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
+    /// Aumenta a cada alteração pela API e é devolvida como `ETag`.
+    /// Registros gravados antes da existência do campo estão na versão 0.
+    #[serde(default)]
+    pub versao: u32,
+}
+
+impl Listavel for ContaBancaria {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("banco"),
+        Campo::texto("agencia"),
+        Campo::texto("conta"),
+        Campo::texto("tipo"),
+        Campo::dinheiro("saldo"),
+        Campo::texto("moeda"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["banco", "conta"];
+    const ORDEM: &'static str = "banco";
+}
+
+/// Chave normalizada e cidade sem espaços nas pontas.
+pub fn normalizar_pix(pix: &ChavePix) -> ChavePix {
+    ChavePix {
+        chave: pix::normalizar_chave(&pix.chave).unwrap_or_else(|| pix.chave.clone()),
+        cidade: pix.cidade.trim().to_string(),
+    }
+}
+
+fn chave_pix_valida(pix: &ChavePix) -> bool {
+    pix::normalizar_chave(&pix.chave).is_some()
+}
+
+fn banco_boleto_valido(boleto: &CarteiraBoleto) -> bool {
+    BancoBoleto::do_codigo(&boleto.banco).is_some()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Carteira com o número de algarismos do banco.
+fn carteira_boleto_valida(boleto: &CarteiraBoleto) -> bool {
+    BancoBoleto::do_codigo(&boleto.banco)
+        .is_none_or(|banco| algarismos(&boleto.carteira, banco.tamanho_carteira()))
+}
+
+/// Convênio presente, com o número de algarismos do banco, só nos bancos
+/// que o usam.
+fn convenio_boleto_valido(boleto: &CarteiraBoleto) -> bool {
+    let convenio = boleto.convenio.as_deref().map(str::trim);
+    match BancoBoleto::do_codigo(&boleto.banco).and_then(|banco| banco.tamanho_convenio()) {
+        Some(tamanho) => convenio.is_some_and(|convenio| algarismos(convenio, tamanho)),
+        None => true,
+    }
+}
+
+fn algarismos(valor: &str, tamanho: usize) -> bool {
+    let valor = valor.trim();
+    valor.len() == tamanho && valor.chars().all(|c| c.is_ascii_digit())
+}
+
+const BANCO_SEM_BOLETO: &str = "Banco sem emissão de boletos: use 001, 104, 237 ou 341";
+
+const CHAVE_PIX_INVALIDA: &str =
+    "Chave PIX inválida: use CPF, CNPJ, e-mail, telefone (+55...) ou chave aleatória";
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateContaBancaria {
     pub banco: String,
     pub agencia: String,
     pub conta: String,
     pub tipo: String,
-    pub saldo: f64,
+    pub saldo: Dinheiro,
+    /// Padrão: reais. Não pode ser alterada depois.
+    #[serde(default)]
+    pub moeda: Moeda,
+    pub pix: Option<ChavePix>,
+    pub boleto: Option<CarteiraBoleto>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateContaBancaria {
     pub banco: Option<String>,
     pub agencia: Option<String>,
     pub conta: Option<String>,
     pub tipo: Option<String>,
-    pub saldo: Option<f64>,
+    pub saldo: Option<Dinheiro>,
+    pub pix: Option<ChavePix>,
+    pub boleto: Option<CarteiraBoleto>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Cartao {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
     pub banco: String,
-    pub numero: String,
+    /// Só os quatro últimos dígitos são gravados; o número completo nunca
+    /// é persistido nem devolvido pela API.
+    pub ultimos_digitos: String,
     pub bandeira: String,
-    pub limite: f64,
+    pub limite: Dinheiro,
     pub vencimento: i32,
+    pub fechamento: Option<i32>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: DateTime<Utc>,
+    /// Aumenta a cada alteração pela API e é devolvida como `ETag`.
+    /// Registros gravados antes da existência do campo estão na versão 0.
+    #[serde(default)]
+    pub versao: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Listavel for Cartao {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("banco"),
+        Campo::texto("bandeira"),
+        Campo::texto("ultimos_digitos"),
+        Campo::dinheiro("limite"),
+        Campo::numero("vencimento"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["banco", "bandeira"];
+    const ORDEM: &'static str = "banco";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateCartao {
     pub banco: String,
+    /// Usado apenas para validar e extrair a bandeira e os últimos dígitos.
     pub numero: String,
-    pub bandeira: String,
-    pub limite: f64,
+    /// Obrigatória só quando não for possível detectá-la pelo número.
+    #[serde(default)]
+    pub bandeira: Option<String>,
+    pub limite: Dinheiro,
     pub vencimento: i32,
+    pub fechamento: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateCartao {
     pub banco: Option<String>,
+    /// Não pode ser alterado: um cartão novo deve ser cadastrado.
     pub numero: Option<String>,
     pub bandeira: Option<String>,
-    pub limite: Option<f64>,
+    pub limite: Option<Dinheiro>,
     pub vencimento: Option<i32>,
+    pub fechamento: Option<i32>,
+}
+
+impl Cartao {
+    /// Cartões cadastrados antes de o fechamento ser informado fecham
+    /// sete dias antes do vencimento.
+    pub fn dia_fechamento(&self) -> i32 {
+        self.fechamento
+            .unwrap_or_else(|| (self.vencimento - 7).max(1))
+    }
+}
+
+/// Contas no exterior são informadas pelo IBAN no campo `conta`; as
+/// nacionais, por número com dígito opcional (`12345-6`).
+fn conta_ou_iban_valida(conta: &str) -> bool {
+    if validacao::parece_iban(conta) {
+        validacao::iban_valido(conta)
+    } else {
+        validacao::conta_valida(conta)
+    }
+}
+
+impl Validar for CreateContaBancaria {
+    fn validar(&self) -> Result<()> {
+        let iban = validacao::parece_iban(&self.conta);
+        Validacao::new()
+            .obrigatorio("banco", &self.banco)
+            .obrigatorio("tipo", &self.tipo)
+            .checar(
+                "agencia",
+                (iban && self.agencia.trim().is_empty())
+                    || validacao::agencia_valida(&self.agencia),
+                "Agência inválida",
+            )
+            .checar(
+                "conta",
+                conta_ou_iban_valida(&self.conta),
+                "Número de conta ou IBAN inválido",
+            )
+            .checar(
+                "pix.chave",
+                self.pix.as_ref().is_none_or(chave_pix_valida),
+                CHAVE_PIX_INVALIDA,
+            )
+            .checar(
+                "pix.cidade",
+                self.pix
+                    .as_ref()
+                    .is_none_or(|pix| !pix.cidade.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "boleto.banco",
+                self.boleto.as_ref().is_none_or(banco_boleto_valido),
+                BANCO_SEM_BOLETO,
+            )
+            .checar(
+                "boleto.carteira",
+                self.boleto.as_ref().is_none_or(carteira_boleto_valida),
+                "Carteira inválida para o banco",
+            )
+            .checar(
+                "boleto.convenio",
+                self.boleto.as_ref().is_none_or(convenio_boleto_valido),
+                "Convênio inválido para o banco",
+            )
+            .concluir()
+    }
+}
+
+impl Validar for UpdateContaBancaria {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "banco",
+                self.banco
+                    .as_deref()
+                    .is_none_or(|banco| !banco.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "agencia",
+                self.agencia.as_deref().is_none_or(|agencia| {
+                    agencia.trim().is_empty() || validacao::agencia_valida(agencia)
+                }),
+                "Agência inválida",
+            )
+            .checar(
+                "conta",
+                self.conta.as_deref().is_none_or(conta_ou_iban_valida),
+                "Número de conta ou IBAN inválido",
+            )
+            .checar(
+                "pix.chave",
+                self.pix.as_ref().is_none_or(chave_pix_valida),
+                CHAVE_PIX_INVALIDA,
+            )
+            .checar(
+                "pix.cidade",
+                self.pix
+                    .as_ref()
+                    .is_none_or(|pix| !pix.cidade.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "boleto.banco",
+                self.boleto.as_ref().is_none_or(banco_boleto_valido),
+                BANCO_SEM_BOLETO,
+            )
+            .checar(
+                "boleto.carteira",
+                self.boleto.as_ref().is_none_or(carteira_boleto_valida),
+                "Carteira inválida para o banco",
+            )
+            .checar(
+                "boleto.convenio",
+                self.boleto.as_ref().is_none_or(convenio_boleto_valido),
+                "Convênio inválido para o banco",
+            )
+            .concluir()
+    }
+}
+
+fn dia_valido(dia: i32) -> bool {
+    (1..=31).contains(&dia)
+}
+
+impl Validar for CreateCartao {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .obrigatorio("banco", &self.banco)
+            .checar(
+                "numero",
+                validacao::cartao_valido(&self.numero),
+                "Número de cartão inválido",
+            )
+            .checar(
+                "bandeira",
+                cartoes::detectar_bandeira(&self.numero).is_some()
+                    || self
+                        .bandeira
+                        .as_deref()
+                        .is_some_and(|bandeira| !bandeira.trim().is_empty()),
+                "Bandeira não reconhecida pelo número; informe-a",
+            )
+            .checar(
+                "limite",
+                !self.limite.negativo(),
+                "O limite não pode ser negativo",
+            )
+            .checar(
+                "vencimento",
+                dia_valido(self.vencimento),
+                "O dia de vencimento deve estar entre 1 e 31",
+            )
+            .checar(
+                "fechamento",
+                self.fechamento.is_none_or(dia_valido),
+                "O dia de fechamento deve estar entre 1 e 31",
+            )
+            .concluir()
+    }
+}
+
+impl Validar for UpdateCartao {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "numero",
+                self.numero.is_none(),
+                "O número do cartão não pode ser alterado; cadastre um novo cartão",
+            )
+            .checar(
+                "limite",
+                self.limite.is_none_or(|limite| !limite.negativo()),
+                "O limite não pode ser negativo",
+            )
+            .checar(
+                "vencimento",
+                self.vencimento.is_none_or(dia_valido),
+                "O dia de vencimento deve estar entre 1 e 31",
+            )
+            .checar(
+                "fechamento",
+                self.fechamento.is_none_or(dia_valido),
+                "O dia de fechamento deve estar entre 1 e 31",
+            )
+            .concluir()
+    }
 }
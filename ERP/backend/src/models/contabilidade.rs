@@ -0,0 +1,217 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TipoContaContabil {
+    Ativo,
+    Passivo,
+    PatrimonioLiquido,
+    Receita,
+    Despesa,
+}
+
+impl TipoContaContabil {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TipoContaContabil::Ativo => "ATIVO",
+            TipoContaContabil::Passivo => "PASSIVO",
+            TipoContaContabil::PatrimonioLiquido => "PATRIMONIO_LIQUIDO",
+            TipoContaContabil::Receita => "RECEITA",
+            TipoContaContabil::Despesa => "DESPESA",
+        }
+    }
+
+    /// Contas de natureza devedora aumentam com débitos; as demais, com
+    /// créditos.
+    pub fn devedora(&self) -> bool {
+        matches!(self, TipoContaContabil::Ativo | TipoContaContabil::Despesa)
+    }
+}
+
+/// Conta do plano de contas. O código é hierárquico (`1.1.02`): contas com
+/// subcontas são sintéticas e só as analíticas recebem partidas.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContaContabil {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub codigo: String,
+    pub nome: String,
+    pub tipo: TipoContaContabil,
+    /// Conta bancária que esta conta contábil representa.
+    pub conta_bancaria_id: Option<ObjectId>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ContaContabil {
+    /// Se `outra` é subconta (direta ou não) desta conta.
+    pub fn contem(&self, outra: &str) -> bool {
+        outra
+            .strip_prefix(self.codigo.as_str())
+            .is_some_and(|resto| resto.starts_with('.'))
+    }
+}
+
+impl Listavel for ContaContabil {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("codigo"),
+        Campo::texto("nome"),
+        Campo::texto("tipo"),
+        Campo::booleano("ativo"),
+    ];
+    const BUSCA: &'static [&'static str] = &["codigo", "nome"];
+    const ORDEM: &'static str = "codigo";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateContaContabil {
+    pub codigo: String,
+    pub nome: String,
+    pub tipo: TipoContaContabil,
+}
+
+impl Validar for CreateContaContabil {
+    fn validar(&self) -> Result<()> {
+        let codigo_valido = self
+            .codigo
+            .split('.')
+            .all(|parte| !parte.is_empty() && parte.chars().all(|c| c.is_ascii_digit()));
+
+        Validacao::new()
+            .checar(
+                "codigo",
+                codigo_valido,
+                "Use apenas números separados por pontos (ex.: 5.1.03)",
+            )
+            .obrigatorio("nome", &self.nome)
+            .concluir()
+    }
+}
+
+/// Evento que originou uma partida.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrigemPartida {
+    Manual,
+    /// Saldo de abertura das contas existentes quando a contabilidade foi
+    /// ativada.
+    Abertura,
+    /// Saldo inicial de uma conta bancária e seus ajustes.
+    ContaBancaria,
+    Venda,
+    /// Liquidação de uma conta a pagar ou a receber, inclusive o
+    /// pagamento de faturas de cartão.
+    Lancamento,
+    CompraCartao,
+}
+
+impl OrigemPartida {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrigemPartida::Manual => "MANUAL",
+            OrigemPartida::Abertura => "ABERTURA",
+            OrigemPartida::ContaBancaria => "CONTA_BANCARIA",
+            OrigemPartida::Venda => "VENDA",
+            OrigemPartida::Lancamento => "LANCAMENTO",
+            OrigemPartida::CompraCartao => "COMPRA_CARTAO",
+        }
+    }
+}
+
+/// Lançamento no livro diário: débitos e créditos de mesmo total. Partidas
+/// nunca são alteradas nem excluídas; correções são feitas por estorno, uma
+/// nova partida com os lados invertidos.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Partida {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub data: NaiveDate,
+    pub historico: String,
+    pub origem: OrigemPartida,
+    /// Registro que originou a partida (venda, lançamento, compra etc.).
+    pub origem_id: Option<ObjectId>,
+    /// Partida estornada por esta.
+    pub estorno_de: Option<ObjectId>,
+    pub linhas: Vec<LinhaPartida>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Débito ou crédito de uma conta; só um dos lados é maior que zero.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct LinhaPartida {
+    pub conta_id: ObjectId,
+    pub debito: Dinheiro,
+    pub credito: Dinheiro,
+    /// Nas contas bancárias em moeda estrangeira, o valor movimentado na
+    /// moeda da conta, do mesmo lado que o valor em reais.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valor_moeda: Option<Dinheiro>,
+}
+
+impl Listavel for Partida {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::data("data"),
+        Campo::texto("origem"),
+        Campo::id("origem_id"),
+        Campo::id("estorno_de"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["historico"];
+    const ORDEM: &'static str = "-data";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatePartida {
+    /// Padrão: data de hoje.
+    pub data: Option<NaiveDate>,
+    pub historico: String,
+    pub linhas: Vec<CreateLinhaPartida>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateLinhaPartida {
+    pub conta_id: String,
+    #[serde(default)]
+    pub debito: Dinheiro,
+    #[serde(default)]
+    pub credito: Dinheiro,
+}
+
+/// Débitos e créditos acumulados de uma conta.
+#[derive(Debug, Serialize, Default, Clone, Copy)]
+pub struct Movimento {
+    pub debito: Dinheiro,
+    pub credito: Dinheiro,
+}
+
+impl Movimento {
+    pub fn somar(&mut self, outro: Movimento) {
+        self.debito += outro.debito;
+        self.credito += outro.credito;
+    }
+
+    /// Saldo no sentido da natureza da conta.
+    pub fn saldo(&self, tipo: TipoContaContabil) -> Dinheiro {
+        if tipo.devedora() {
+            self.debito - self.credito
+        } else {
+            self.credito - self.debito
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::{Moeda, TaxaCambio};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+/// Cotação diária de uma moeda estrangeira em reais, como a PTAX de venda
+/// do Banco Central. Há no máximo uma por moeda e data; nos dias sem
+/// cotação vale a anterior mais recente.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Cotacao {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub moeda: Moeda,
+    pub data: NaiveDate,
+    /// Reais por unidade da moeda.
+    pub taxa: TaxaCambio,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for Cotacao {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("moeda"),
+        Campo::data("data"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["moeda"];
+    const ORDEM: &'static str = "-data";
+}
+
+/// Grava a cotação da moeda na data, substituindo a que houver.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCotacao {
+    pub moeda: Moeda,
+    pub data: NaiveDate,
+    pub taxa: TaxaCambio,
+}
+
+impl Validar for CreateCotacao {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "moeda",
+                self.moeda != Moeda::Brl,
+                "O real não tem cotação: ele é a moeda da contabilidade",
+            )
+            .concluir()
+    }
+}
@@ -0,0 +1,306 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    nfe,
+    validacao::{self, Validacao, Validar},
+};
+
+/// Empresa (tenant). Todos os documentos operacionais carregam o
+/// `empresa_id` da empresa a que pertencem.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Empresa {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub nome: String,
+    /// CNPJ, NIF ou equivalente no país da empresa.
+    pub documento: Option<String>,
+    pub pais: String,
+    /// Dados do emitente da NF-e, só para empresas do Brasil.
+    pub fiscal: Option<ConfiguracaoFiscal>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Listavel for Empresa {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("nome"),
+        Campo::texto("documento"),
+        Campo::texto("pais"),
+        Campo::booleano("ativo"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "documento"];
+    const ORDEM: &'static str = "nome";
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RegimeTributario {
+    SimplesNacional,
+    Normal,
+}
+
+impl RegimeTributario {
+    /// Código de regime tributário (CRT) da NF-e.
+    pub fn crt(&self) -> u8 {
+        match self {
+            RegimeTributario::SimplesNacional => 1,
+            RegimeTributario::Normal => 3,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Ambiente {
+    Producao,
+    /// Notas sem valor fiscal, para testes.
+    #[default]
+    Homologacao,
+}
+
+impl Ambiente {
+    /// `tpAmb` da NF-e.
+    pub fn codigo(&self) -> u8 {
+        match self {
+            Ambiente::Producao => 1,
+            Ambiente::Homologacao => 2,
+        }
+    }
+}
+
+/// Emitente das notas fiscais: inscrição estadual, endereço e o
+/// certificado A1 que assina o XML.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ConfiguracaoFiscal {
+    pub inscricao_estadual: String,
+    pub regime: RegimeTributario,
+    pub logradouro: String,
+    pub numero: String,
+    pub complemento: Option<String>,
+    pub bairro: String,
+    /// Código IBGE do município.
+    pub codigo_municipio: String,
+    pub municipio: String,
+    pub uf: String,
+    pub cep: String,
+    pub telefone: Option<String>,
+    #[serde(default)]
+    pub ambiente: Ambiente,
+    /// Série das notas, de 0 a 999.
+    #[serde(default)]
+    pub serie: u32,
+    /// Caminho do arquivo `.pfx` do certificado A1 no servidor.
+    pub certificado: String,
+    /// Nunca é devolvida pela API; vazia na alteração, mantém a atual.
+    #[serde(default)]
+    pub senha_certificado: String,
+    /// Código de segurança do contribuinte, só para NFC-e.
+    pub nfce: Option<ConfiguracaoNfce>,
+}
+
+/// CSC da NFC-e e URLs da SEFAZ da UF para o QR code e a consulta.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct ConfiguracaoNfce {
+    pub csc_id: String,
+    /// Nunca é devolvido pela API; vazio na alteração, mantém o atual.
+    #[serde(default)]
+    pub csc: String,
+    pub url_qrcode: String,
+    pub url_consulta: String,
+}
+
+impl ConfiguracaoFiscal {
+    pub fn normalizada(&self) -> Self {
+        Self {
+            inscricao_estadual: match self.inscricao_estadual.trim().to_uppercase() {
+                isento if isento == "ISENTO" => isento,
+                inscricao => validacao::somente_digitos(&inscricao),
+            },
+            codigo_municipio: validacao::somente_digitos(&self.codigo_municipio),
+            uf: self.uf.trim().to_uppercase(),
+            cep: validacao::somente_digitos(&self.cep),
+            certificado: self.certificado.trim().to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Mantém a senha do certificado e o CSC atuais quando a alteração não
+    /// os informa.
+    pub fn mantendo_segredos(mut self, atual: Option<&ConfiguracaoFiscal>) -> Self {
+        let Some(atual) = atual else {
+            return self;
+        };
+        if self.senha_certificado.is_empty() {
+            self.senha_certificado = atual.senha_certificado.clone();
+        }
+        if let (Some(nfce), Some(nfce_atual)) = (self.nfce.as_mut(), atual.nfce.as_ref()) {
+            if nfce.csc.is_empty() {
+                nfce.csc = nfce_atual.csc.clone();
+            }
+        }
+        self
+    }
+
+    /// Confere, depois de [`ConfiguracaoFiscal::mantendo_segredos`], que a
+    /// senha do certificado e o CSC foram informados.
+    pub fn validar_segredos(&self) -> Result<()> {
+        Validacao::new()
+            .obrigatorio("fiscal.senha_certificado", &self.senha_certificado)
+            .checar(
+                "fiscal.nfce.csc",
+                self.nfce
+                    .as_ref()
+                    .is_none_or(|nfce| !nfce.csc.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .concluir()
+    }
+
+    fn validar(validacao: &mut Validacao, fiscal: Option<&ConfiguracaoFiscal>, pais: &str) {
+        let Some(fiscal) = fiscal.map(ConfiguracaoFiscal::normalizada) else {
+            return;
+        };
+        let ie = fiscal.inscricao_estadual.as_str();
+        validacao
+            .checar(
+                "fiscal",
+                pais.trim().eq_ignore_ascii_case("BR"),
+                "Notas fiscais só são emitidas por empresas do Brasil",
+            )
+            .checar(
+                "fiscal.inscricao_estadual",
+                ie == "ISENTO"
+                    || (2..=14).contains(&ie.len()) && ie.chars().all(|c| c.is_ascii_digit()),
+                "Informe a inscrição estadual, de 2 a 14 algarismos, ou ISENTO",
+            )
+            .obrigatorio("fiscal.logradouro", &fiscal.logradouro)
+            .obrigatorio("fiscal.numero", &fiscal.numero)
+            .obrigatorio("fiscal.bairro", &fiscal.bairro)
+            .obrigatorio("fiscal.municipio", &fiscal.municipio)
+            .checar(
+                "fiscal.codigo_municipio",
+                fiscal.codigo_municipio.len() == 7,
+                "O código IBGE do município tem 7 algarismos",
+            )
+            .checar(
+                "fiscal.uf",
+                nfe::codigo_uf(&fiscal.uf).is_some(),
+                "UF inválida",
+            )
+            .checar(
+                "fiscal.cep",
+                fiscal.cep.len() == 8,
+                "O CEP tem 8 algarismos",
+            )
+            .checar(
+                "fiscal.serie",
+                fiscal.serie <= 999,
+                "A série vai de 0 a 999",
+            )
+            .obrigatorio("fiscal.certificado", &fiscal.certificado);
+        if let Some(nfce) = &fiscal.nfce {
+            validacao
+                .checar(
+                    "fiscal.nfce.csc_id",
+                    (1..=6).contains(&nfce.csc_id.trim().len())
+                        && nfce.csc_id.trim().chars().all(|c| c.is_ascii_digit()),
+                    "O identificador do CSC tem até 6 algarismos",
+                )
+                .obrigatorio("fiscal.nfce.url_qrcode", &nfce.url_qrcode)
+                .obrigatorio("fiscal.nfce.url_consulta", &nfce.url_consulta);
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateEmpresa {
+    pub nome: String,
+    pub documento: Option<String>,
+    pub pais: String,
+    pub fiscal: Option<ConfiguracaoFiscal>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateEmpresa {
+    pub nome: Option<String>,
+    pub documento: Option<String>,
+    pub pais: Option<String>,
+    pub fiscal: Option<ConfiguracaoFiscal>,
+    pub ativo: Option<bool>,
+}
+
+/// No Brasil a empresa é identificada por CNPJ (ou CPF, para autônomos) e
+/// em Portugal pelo NIF; nos demais países o documento não é conferido.
+fn documento_valido_no_pais(documento: &str, pais: &str) -> bool {
+    match pais.trim().to_uppercase().as_str() {
+        "BR" => validacao::cnpj_valido(documento) || validacao::cpf_valido(documento),
+        "PT" => validacao::nif_valido(documento),
+        _ => true,
+    }
+}
+
+fn pais_valido(pais: &str) -> bool {
+    let pais = pais.trim();
+    pais.len() == 2 && pais.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+impl Validar for CreateEmpresa {
+    fn validar(&self) -> Result<()> {
+        let mut validacao = Validacao::new();
+        validacao
+            .obrigatorio("nome", &self.nome)
+            .checar(
+                "pais",
+                pais_valido(&self.pais),
+                "Informe o código ISO do país com duas letras",
+            )
+            .checar(
+                "documento",
+                self.documento
+                    .as_deref()
+                    .is_none_or(|documento| documento_valido_no_pais(documento, &self.pais)),
+                "Documento inválido para o país da empresa",
+            );
+        ConfiguracaoFiscal::validar(&mut validacao, self.fiscal.as_ref(), &self.pais);
+        validacao.concluir()
+    }
+}
+
+impl UpdateEmpresa {
+    /// O documento é conferido contra o país novo, se informado, ou o atual.
+    pub fn validar_no_pais(&self, pais_atual: &str) -> Result<()> {
+        let pais = self.pais.as_deref().unwrap_or(pais_atual);
+        let mut validacao = Validacao::new();
+        validacao
+            .checar(
+                "nome",
+                self.nome
+                    .as_deref()
+                    .is_none_or(|nome| !nome.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "pais",
+                self.pais.as_deref().is_none_or(pais_valido),
+                "Informe o código ISO do país com duas letras",
+            )
+            .checar(
+                "documento",
+                self.documento
+                    .as_deref()
+                    .is_none_or(|documento| documento_valido_no_pais(documento, pais)),
+                "Documento inválido para o país da empresa",
+            );
+        ConfiguracaoFiscal::validar(&mut validacao, self.fiscal.as_ref(), pais);
+        validacao.concluir()
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::consulta::{Campo, Listavel};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TipoMovimentacao {
+    Entrada,
+    Saida,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MovimentacaoEstoque {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub produto_id: ObjectId,
+    pub tipo: TipoMovimentacao,
+    pub quantidade: i32,
+    pub motivo: String,
+    pub usuario: Option<String>,
+    pub venda_id: Option<ObjectId>,
+    #[serde(default)]
+    pub pedido_compra_id: Option<ObjectId>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for MovimentacaoEstoque {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("produto_id"),
+        Campo::texto("tipo"),
+        Campo::numero("quantidade"),
+        Campo::texto("usuario"),
+        Campo::id("venda_id"),
+        Campo::id("pedido_compra_id"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["motivo", "usuario"];
+    const ORDEM: &'static str = "-created_at";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateMovimentacao {
+    pub produto_id: String,
+    pub tipo: TipoMovimentacao,
+    pub quantidade: i32,
+    pub motivo: String,
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    importacao::FormatoArquivo,
+};
+
+/// Transação importada de um extrato ou arquivo de retorno bancário.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransacaoBancaria {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub conta_id: ObjectId,
+    pub data: NaiveDate,
+    /// Créditos positivos, débitos negativos.
+    pub valor: Dinheiro,
+    pub descricao: String,
+    pub documento: Option<String>,
+    /// FITID (OFX) ou nosso número (CNAB); único por conta.
+    pub identificador: String,
+    pub origem: FormatoArquivo,
+    /// Lançamento conciliado com esta transação.
+    pub lancamento_id: Option<ObjectId>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for TransacaoBancaria {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::data("data"),
+        Campo::dinheiro("valor"),
+        Campo::texto("identificador"),
+        Campo::texto("origem"),
+        Campo::id("lancamento_id"),
+        Campo::preenchido("conciliada", "lancamento_id"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["descricao", "documento"];
+    const ORDEM: &'static str = "-data";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConciliarTransacao {
+    pub lancamento_id: String,
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    models::MAXIMO_PARCELAS,
+    validacao::{Validacao, Validar},
+};
+
+/// Parcela de uma compra no cartão. Compras parceladas geram um documento
+/// por parcela, todos com o mesmo `compra_id`, cada um na fatura do mês
+/// correspondente.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompraCartao {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub cartao_id: ObjectId,
+    pub compra_id: ObjectId,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    pub data_compra: NaiveDate,
+    pub parcela: u32,
+    pub total_parcelas: u32,
+    /// Mês da fatura no formato `AAAA-MM`.
+    pub competencia: String,
+    /// Lançamento de pagamento da fatura que quitou esta parcela.
+    pub lancamento_id: Option<ObjectId>,
+    /// Recorrência que gerou a compra e o número da ocorrência.
+    #[serde(default)]
+    pub recorrencia_id: Option<ObjectId>,
+    #[serde(default)]
+    pub ocorrencia: Option<u32>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for CompraCartao {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("compra_id"),
+        Campo::texto("categoria"),
+        Campo::dinheiro("valor"),
+        Campo::data("data_compra"),
+        Campo::texto("competencia"),
+        Campo::id("lancamento_id"),
+        Campo::preenchido("paga", "lancamento_id"),
+        Campo::id("recorrencia_id"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["descricao", "categoria"];
+    const ORDEM: &'static str = "-data_compra";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCompraCartao {
+    pub descricao: String,
+    pub categoria: String,
+    /// Valor total da compra; quando parcelada é dividido entre as parcelas.
+    pub valor: Dinheiro,
+    pub data_compra: NaiveDate,
+    pub parcelas: Option<u32>,
+}
+
+impl Validar for CreateCompraCartao {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar("valor", self.valor.positivo(), "Deve ser maior que zero")
+            .checar(
+                "parcelas",
+                self.parcelas
+                    .is_none_or(|parcelas| (1..=MAXIMO_PARCELAS).contains(&parcelas)),
+                "Informe de 1 a 360 parcelas",
+            )
+            .concluir()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PagarFatura {
+    pub conta_id: String,
+    /// Padrão: data de hoje.
+    pub data_pagamento: Option<NaiveDate>,
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::cliente::email_valido;
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{self, Validacao, Validar},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Fornecedor {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    /// Razão social ou nome.
+    pub nome: String,
+    pub cpf_cnpj: String,
+    /// Pessoa de contato no fornecedor.
+    pub contato: Option<String>,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub cidade: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+    /// Aumenta a cada alteração pela API e é devolvida como `ETag`.
+    /// Registros gravados antes da existência do campo estão na versão 0.
+    #[serde(default)]
+    pub versao: u32,
+}
+
+impl Listavel for Fornecedor {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("nome"),
+        Campo::texto("cpf_cnpj"),
+        Campo::texto("email"),
+        Campo::texto("cidade"),
+        Campo::texto("estado"),
+        Campo::booleano("ativo"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "cpf_cnpj", "contato", "email"];
+    const ORDEM: &'static str = "nome";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateFornecedor {
+    pub nome: String,
+    pub cpf_cnpj: String,
+    pub contato: Option<String>,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub cidade: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateFornecedor {
+    pub nome: Option<String>,
+    pub cpf_cnpj: Option<String>,
+    pub contato: Option<String>,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub cidade: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: Option<bool>,
+}
+
+impl Validar for CreateFornecedor {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .obrigatorio("nome", &self.nome)
+            .checar(
+                "cpf_cnpj",
+                validacao::documento_valido(&self.cpf_cnpj),
+                "CPF, CNPJ ou NIF inválido",
+            )
+            .checar(
+                "email",
+                self.email.as_deref().is_none_or(email_valido),
+                "E-mail inválido",
+            )
+            .concluir()
+    }
+}
+
+impl Validar for UpdateFornecedor {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "nome",
+                self.nome
+                    .as_deref()
+                    .is_none_or(|nome| !nome.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "cpf_cnpj",
+                self.cpf_cnpj
+                    .as_deref()
+                    .is_none_or(validacao::documento_valido),
+                "CPF, CNPJ ou NIF inválido",
+            )
+            .checar(
+                "email",
+                self.email.as_deref().is_none_or(email_valido),
+                "E-mail inválido",
+            )
+            .concluir()
+    }
+}
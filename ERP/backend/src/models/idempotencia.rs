@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// Requisição `POST` com `Idempotency-Key`. Enquanto ela é processada não
+/// há resposta; depois, a resposta gravada é devolvida às repetições.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequisicaoIdempotente {
+    /// `<usuario_id>:<chave>`: a mesma chave de usuários diferentes não
+    /// colide.
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub usuario_id: ObjectId,
+    pub chave: String,
+    /// Resumo do método, caminho, empresa e corpo, para recusar a mesma
+    /// chave numa requisição diferente.
+    pub impressao: String,
+    pub resposta: Option<RespostaGravada>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RespostaGravada {
+    pub status: u16,
+    /// Só os cabeçalhos que descrevem o conteúdo, como `Content-Type` e
+    /// `ETag`.
+    pub cabecalhos: Vec<(String, String)>,
+    #[serde(with = "serde_bytes")]
+    pub corpo: Vec<u8>,
+}
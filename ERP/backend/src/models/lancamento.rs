@@ -0,0 +1,145 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::{Dinheiro, Moeda};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+/// Limite de parcelas de um lançamento ou compra parcelada: 30 anos de
+/// parcelas mensais.
+pub const MAXIMO_PARCELAS: u32 = 360;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TipoLancamento {
+    Pagar,
+    Receber,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StatusLancamento {
+    Aberto,
+    Pago,
+    Cancelado,
+}
+
+impl StatusLancamento {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusLancamento::Aberto => "ABERTO",
+            StatusLancamento::Pago => "PAGO",
+            StatusLancamento::Cancelado => "CANCELADO",
+        }
+    }
+}
+
+/// Conta a pagar ou a receber. Um lançamento parcelado é gravado como um
+/// documento por parcela, todos com o mesmo `grupo_id`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Lancamento {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    /// Moeda do valor; só é liquidado em conta bancária da mesma moeda.
+    #[serde(default)]
+    pub moeda: Moeda,
+    pub vencimento: NaiveDate,
+    pub cliente_id: Option<ObjectId>,
+    pub contraparte: Option<String>,
+    pub grupo_id: ObjectId,
+    pub parcela: u32,
+    pub total_parcelas: u32,
+    pub status: StatusLancamento,
+    pub conta_id: Option<ObjectId>,
+    pub data_pagamento: Option<NaiveDate>,
+    pub valor_pago: Option<Dinheiro>,
+    /// Recorrência que gerou o lançamento e o número da ocorrência.
+    #[serde(default)]
+    pub recorrencia_id: Option<ObjectId>,
+    #[serde(default)]
+    pub ocorrencia: Option<u32>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Listavel for Lancamento {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("tipo"),
+        Campo::texto("status"),
+        Campo::texto("categoria"),
+        Campo::id("cliente_id"),
+        Campo::id("conta_id"),
+        Campo::id("grupo_id"),
+        Campo::id("recorrencia_id"),
+        Campo::dinheiro("valor"),
+        Campo::texto("moeda"),
+        Campo::data("vencimento"),
+        Campo::data("data_pagamento"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["descricao", "contraparte", "categoria"];
+    const ORDEM: &'static str = "vencimento";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateLancamento {
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    /// Valor total; quando parcelado é dividido entre as parcelas.
+    pub valor: Dinheiro,
+    /// Padrão: reais.
+    #[serde(default)]
+    pub moeda: Moeda,
+    /// Vencimento da primeira parcela; as demais vencem mês a mês.
+    pub vencimento: NaiveDate,
+    pub cliente_id: Option<String>,
+    pub contraparte: Option<String>,
+    pub parcelas: Option<u32>,
+}
+
+impl Validar for CreateLancamento {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar("valor", self.valor.positivo(), "Deve ser maior que zero")
+            .checar(
+                "parcelas",
+                self.parcelas
+                    .is_none_or(|parcelas| (1..=MAXIMO_PARCELAS).contains(&parcelas)),
+                "Informe de 1 a 360 parcelas",
+            )
+            .concluir()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateLancamento {
+    pub descricao: Option<String>,
+    pub categoria: Option<String>,
+    pub valor: Option<Dinheiro>,
+    pub vencimento: Option<NaiveDate>,
+    pub cliente_id: Option<String>,
+    pub contraparte: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LiquidarLancamento {
+    /// Padrão: a conta bancária da recorrência que gerou o lançamento.
+    pub conta_id: Option<String>,
+    /// Padrão: data de hoje.
+    pub data_pagamento: Option<NaiveDate>,
+    /// Padrão: valor do lançamento.
+    pub valor_pago: Option<Dinheiro>,
+}
@@ -1,7 +1,46 @@
+mod auditoria;
 mod banco;
+mod boleto;
+mod cliente;
+mod compra;
 mod conta_bancaria;
-mod frontend;
+mod contabilidade;
+mod cotacao;
+mod empresa;
+mod estoque;
+mod extrato;
+mod fatura;
+mod fornecedor;
+mod idempotencia;
+mod lancamento;
+mod nota_fiscal;
+mod pix;
+mod produto;
+mod recorrencia;
+mod usuario;
+mod venda;
 
+pub use auditoria::*;
 pub use banco::*;
+pub use boleto::*;
+pub use cliente::*;
+pub use compra::*;
 pub use conta_bancaria::*;
-pub use frontend::*;
+pub use contabilidade::*;
+pub use cotacao::*;
+pub use empresa::*;
+pub use estoque::*;
+pub use extrato::*;
+pub use fatura::*;
+pub use fornecedor::*;
+pub use idempotencia::*;
+pub use lancamento::*;
+pub use nota_fiscal::*;
+pub use pix::*;
+pub use produto::*;
+pub use recorrencia::*;
+pub use usuario::*;
+pub use venda::*;
+
+pub use erp_api::{DashboardData, EstoqueCritico, ProdutoMaisVendido, ResumoMes, VendasHoje};
+pub use erp_dinheiro::{Dinheiro, Moeda, TaxaCambio};
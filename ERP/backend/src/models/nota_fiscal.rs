@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::consulta::{Campo, Listavel};
+
+use super::Ambiente;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ModeloNota {
+    /// NF-e, modelo 55.
+    #[default]
+    Nfe,
+    /// NFC-e, modelo 65, para o consumidor final no varejo.
+    Nfce,
+}
+
+impl ModeloNota {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModeloNota::Nfe => "NFE",
+            ModeloNota::Nfce => "NFCE",
+        }
+    }
+
+    pub fn codigo(&self) -> u8 {
+        match self {
+            ModeloNota::Nfe => 55,
+            ModeloNota::Nfce => 65,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusNota {
+    /// Número e venda reservados, aguardando o retorno da SEFAZ.
+    EmProcessamento,
+    #[default]
+    Autorizada,
+}
+
+impl StatusNota {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusNota::EmProcessamento => "EM_PROCESSAMENTO",
+            StatusNota::Autorizada => "AUTORIZADA",
+        }
+    }
+}
+
+/// Nota fiscal de uma venda, com o XML de distribuição (`nfeProc`, a nota
+/// assinada e o protocolo) e o DANFE. Enquanto está em processamento só
+/// reserva o número e a venda: protocolo, XML e DANFE ficam vazios.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotaFiscal {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub venda_id: ObjectId,
+    pub modelo: ModeloNota,
+    pub serie: u32,
+    pub numero: u32,
+    /// Chave de acesso, com 44 algarismos.
+    pub chave: String,
+    pub ambiente: Ambiente,
+    pub valor: Dinheiro,
+    #[serde(default)]
+    pub status: StatusNota,
+    /// Número do protocolo de autorização da SEFAZ.
+    pub protocolo: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub emitida_em: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub autorizada_em: DateTime<Utc>,
+    pub xml: String,
+    #[serde(with = "serde_bytes")]
+    pub danfe: Vec<u8>,
+}
+
+impl Listavel for NotaFiscal {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("venda_id"),
+        Campo::texto("modelo"),
+        Campo::numero("serie"),
+        Campo::numero("numero"),
+        Campo::texto("chave"),
+        Campo::dinheiro("valor"),
+        Campo::texto("status"),
+        Campo::instante("emitida_em"),
+    ];
+    const BUSCA: &'static [&'static str] = &["chave"];
+    const ORDEM: &'static str = "-emitida_em";
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct EmitirNota {
+    #[serde(default)]
+    pub modelo: ModeloNota,
+}
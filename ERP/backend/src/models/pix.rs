@@ -0,0 +1,114 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    pix,
+    validacao::{Validacao, Validar},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrigemCobranca {
+    Venda,
+    Lancamento,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StatusCobranca {
+    Pendente,
+    Paga,
+}
+
+impl StatusCobranca {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusCobranca::Pendente => "PENDENTE",
+            StatusCobranca::Paga => "PAGA",
+        }
+    }
+}
+
+/// Cobrança PIX de uma venda ou conta a receber. O `txid` volta no extrato
+/// e liga o crédito à cobrança na importação.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CobrancaPix {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    /// Conta bancária que recebe o pagamento.
+    pub conta_id: ObjectId,
+    pub origem: OrigemCobranca,
+    pub origem_id: ObjectId,
+    pub txid: String,
+    pub valor: Dinheiro,
+    /// URL do payload no PSP; só nas cobranças dinâmicas.
+    pub location: Option<String>,
+    /// BR Code (copia e cola).
+    pub payload: String,
+    pub status: StatusCobranca,
+    /// Transação do extrato que pagou a cobrança.
+    pub transacao_id: Option<ObjectId>,
+    pub data_pagamento: Option<NaiveDate>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Listavel for CobrancaPix {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("origem"),
+        Campo::id("origem_id"),
+        Campo::id("conta_id"),
+        Campo::texto("status"),
+        Campo::texto("txid"),
+        Campo::dinheiro("valor"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["txid"];
+    const ORDEM: &'static str = "-created_at";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateCobrancaPix {
+    pub origem: OrigemCobranca,
+    pub origem_id: String,
+    pub conta_id: String,
+    /// Padrão: total da venda ou valor do lançamento.
+    pub valor: Option<Dinheiro>,
+    /// Padrão: gerado pelo ERP.
+    pub txid: Option<String>,
+    /// URL do payload criado no PSP, para o BR Code dinâmico.
+    pub location: Option<String>,
+}
+
+impl Validar for CreateCobrancaPix {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "valor",
+                self.valor.is_none_or(|valor| valor.positivo()),
+                "O valor deve ser maior que zero",
+            )
+            .checar(
+                "txid",
+                self.txid.as_deref().is_none_or(pix::txid_valido),
+                "O txid deve ter de 1 a 25 letras e algarismos",
+            )
+            .checar(
+                "location",
+                self.location.as_deref().is_none_or(|location| {
+                    let location = location.trim();
+                    !location.is_empty()
+                        && !location.contains(char::is_whitespace)
+                        && location.len() <= 77
+                }),
+                "URL do payload inválida",
+            )
+            .concluir()
+    }
+}
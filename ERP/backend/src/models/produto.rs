@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use erp_dinheiro::{Aliquota, Decimal, Dinheiro};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+pub use erp_api::{DadosFiscais, CSOSNS, CSTS_ICMS, CSTS_IPI, CSTS_PIS_COFINS};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Produto {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub nome: String,
+    pub descricao: Option<String>,
+    pub codigo_barras: Option<String>,
+    pub preco_custo: Dinheiro,
+    pub preco_venda: Dinheiro,
+    pub estoque_atual: i32,
+    pub estoque_minimo: i32,
+    pub unidade: String,
+    /// Classificação e tributação para a NF-e.
+    pub fiscal: Option<DadosFiscais>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Listavel for Produto {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("nome"),
+        Campo::texto("codigo_barras"),
+        Campo::texto("unidade"),
+        Campo::dinheiro("preco_custo"),
+        Campo::dinheiro("preco_venda"),
+        Campo::numero("estoque_atual"),
+        Campo::numero("estoque_minimo"),
+        Campo::booleano("ativo"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "descricao", "codigo_barras"];
+    const ORDEM: &'static str = "nome";
+}
+
+const ALIQUOTA_INVALIDA: &str = "A alíquota vai de 0 a 100%";
+
+fn aliquota_valida(aliquota: Aliquota) -> bool {
+    aliquota.decimal() <= Decimal::ONE_HUNDRED
+}
+
+/// Confere os dados fiscais de um cadastro ou alteração de produto.
+fn validar_fiscal(validacao: &mut Validacao, fiscal: Option<&DadosFiscais>) {
+    let Some(fiscal) = fiscal.map(DadosFiscais::normalizados) else {
+        return;
+    };
+    let cfop = fiscal.cfop.as_str();
+    validacao
+        .checar(
+            "fiscal.ncm",
+            fiscal.ncm.len() == 8,
+            "O NCM tem 8 algarismos",
+        )
+        .checar(
+            "fiscal.cfop",
+            cfop.len() == 4 && cfop.starts_with('5') && cfop.chars().all(|c| c.is_ascii_digit()),
+            "Informe o CFOP de saída dentro do estado (5xxx)",
+        )
+        .checar(
+            "fiscal.origem",
+            fiscal.origem <= 8,
+            "A origem da mercadoria vai de 0 a 8",
+        )
+        .checar(
+            "fiscal.cst_icms",
+            CSTS_ICMS.contains(&fiscal.cst_icms.as_str()) || fiscal.simples_nacional(),
+            "CST ou CSOSN do ICMS não suportado",
+        )
+        .checar(
+            "fiscal.cst_pis_cofins",
+            CSTS_PIS_COFINS.contains(&fiscal.cst_pis_cofins.as_str()),
+            "CST de PIS/COFINS não suportado",
+        )
+        .checar(
+            "fiscal.aliquota_icms",
+            aliquota_valida(fiscal.aliquota_icms),
+            ALIQUOTA_INVALIDA,
+        )
+        .checar(
+            "fiscal.reducao_base_icms",
+            fiscal.cst_icms != "20"
+                || !fiscal.reducao_base_icms.is_zero()
+                    && fiscal.reducao_base_icms.decimal() < Decimal::ONE_HUNDRED,
+            "Informe a redução da base, entre 0 e 100%, para o CST 20",
+        )
+        .checar(
+            "fiscal.mva_st",
+            !fiscal.com_st() || !fiscal.mva_st.is_zero(),
+            "Informe a MVA da substituição tributária",
+        )
+        .checar(
+            "fiscal.cest",
+            fiscal
+                .cest
+                .as_ref()
+                .map_or(!fiscal.com_st(), |cest| cest.len() == 7),
+            "O CEST tem 7 algarismos e é obrigatório com substituição tributária",
+        )
+        .checar(
+            "fiscal.cst_ipi",
+            fiscal
+                .cst_ipi
+                .as_deref()
+                .is_none_or(|cst| CSTS_IPI.contains(&cst)),
+            "CST do IPI não suportado",
+        )
+        .checar(
+            "fiscal.aliquota_ipi",
+            aliquota_valida(fiscal.aliquota_ipi),
+            ALIQUOTA_INVALIDA,
+        )
+        .checar(
+            "fiscal.aliquota_pis",
+            aliquota_valida(fiscal.aliquota_pis),
+            ALIQUOTA_INVALIDA,
+        )
+        .checar(
+            "fiscal.aliquota_cofins",
+            aliquota_valida(fiscal.aliquota_cofins),
+            ALIQUOTA_INVALIDA,
+        );
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateProduto {
+    pub nome: String,
+    pub descricao: Option<String>,
+    pub codigo_barras: Option<String>,
+    pub preco_custo: Dinheiro,
+    pub preco_venda: Dinheiro,
+    /// Lançado como movimentação de entrada "Estoque inicial".
+    #[serde(default)]
+    pub estoque_atual: i32,
+    pub estoque_minimo: i32,
+    pub unidade: String,
+    pub fiscal: Option<DadosFiscais>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateProduto {
+    pub nome: Option<String>,
+    pub descricao: Option<String>,
+    pub codigo_barras: Option<String>,
+    pub preco_custo: Option<Dinheiro>,
+    pub preco_venda: Option<Dinheiro>,
+    pub estoque_minimo: Option<i32>,
+    pub unidade: Option<String>,
+    pub fiscal: Option<DadosFiscais>,
+    pub ativo: Option<bool>,
+}
+
+impl Validar for CreateProduto {
+    fn validar(&self) -> Result<()> {
+        let mut validacao = Validacao::new();
+        validacao
+            .obrigatorio("nome", &self.nome)
+            .obrigatorio("unidade", &self.unidade)
+            .checar(
+                "preco_custo",
+                !self.preco_custo.negativo(),
+                "O preço não pode ser negativo",
+            )
+            .checar(
+                "preco_venda",
+                !self.preco_venda.negativo(),
+                "O preço não pode ser negativo",
+            )
+            .checar(
+                "estoque_atual",
+                self.estoque_atual >= 0,
+                "O estoque inicial não pode ser negativo",
+            )
+            .checar(
+                "estoque_minimo",
+                self.estoque_minimo >= 0,
+                "O estoque mínimo não pode ser negativo",
+            );
+        validar_fiscal(&mut validacao, self.fiscal.as_ref());
+        validacao.concluir()
+    }
+}
+
+impl Validar for UpdateProduto {
+    fn validar(&self) -> Result<()> {
+        let mut validacao = Validacao::new();
+        validacao
+            .checar(
+                "nome",
+                self.nome
+                    .as_deref()
+                    .is_none_or(|nome| !nome.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "preco_custo",
+                self.preco_custo.is_none_or(|preco| !preco.negativo()),
+                "O preço não pode ser negativo",
+            )
+            .checar(
+                "preco_venda",
+                self.preco_venda.is_none_or(|preco| !preco.negativo()),
+                "O preço não pode ser negativo",
+            )
+            .checar(
+                "estoque_minimo",
+                self.estoque_minimo.is_none_or(|minimo| minimo >= 0),
+                "O estoque mínimo não pode ser negativo",
+            );
+        validar_fiscal(&mut validacao, self.fiscal.as_ref());
+        validacao.concluir()
+    }
+}
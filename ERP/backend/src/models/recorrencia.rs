@@ -0,0 +1,249 @@
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc};
+use erp_dinheiro::{Dinheiro, Moeda};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::TipoLancamento;
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Frequencia {
+    Semanal,
+    Mensal,
+    Anual,
+}
+
+/// Regra de uma conta que se repete, como aluguel, salários e assinaturas,
+/// ou de um parcelamento. As ocorrências são numeradas a partir de 1 e
+/// geradas com antecedência: em conta bancária ou sem conta viram
+/// lançamentos; em cartão, compras na fatura.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Recorrencia {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    /// Valor de cada ocorrência.
+    pub valor: Dinheiro,
+    /// Moeda do valor; a da conta bancária, quando houver, e reais no
+    /// cartão.
+    #[serde(default)]
+    pub moeda: Moeda,
+    pub frequencia: Frequencia,
+    /// Dia do mês nas recorrências mensais e anuais; nos meses mais curtos
+    /// a ocorrência cai no último dia.
+    pub dia: u32,
+    /// Data da primeira ocorrência; nas mensais e anuais, só o mês conta.
+    pub inicio: NaiveDate,
+    /// Número de parcelas; sem ele a recorrência segue até `fim` ou até ser
+    /// encerrada.
+    pub ocorrencias: Option<u32>,
+    pub fim: Option<NaiveDate>,
+    /// Conta bancária em que as ocorrências são liquidadas por padrão.
+    pub conta_id: Option<ObjectId>,
+    /// Cartão em que as ocorrências são lançadas como compras.
+    pub cartao_id: Option<ObjectId>,
+    pub cliente_id: Option<ObjectId>,
+    pub contraparte: Option<String>,
+    /// Ocorrências já geradas; a próxima é a de número `geradas + 1`.
+    pub geradas: u32,
+    pub ativa: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Recorrencia {
+    /// Data da ocorrência `numero`, ou `None` se a recorrência termina
+    /// antes dela.
+    pub fn data(&self, numero: u32) -> Option<NaiveDate> {
+        if numero == 0 || self.ocorrencias.is_some_and(|total| numero > total) {
+            return None;
+        }
+        let indice = numero - 1;
+        let data = match self.frequencia {
+            Frequencia::Semanal => self
+                .inicio
+                .checked_add_days(Days::new(7 * u64::from(indice)))?,
+            Frequencia::Mensal => self.no_dia(Months::new(indice))?,
+            Frequencia::Anual => self.no_dia(Months::new(indice.checked_mul(12)?))?,
+        };
+        match self.fim {
+            Some(fim) if data > fim => None,
+            _ => Some(data),
+        }
+    }
+
+    /// Descrição da ocorrência, com o número da parcela nos parcelamentos.
+    pub fn descricao_da(&self, numero: u32) -> String {
+        match self.ocorrencias {
+            Some(total) if total > 1 => format!("{} ({}/{})", self.descricao, numero, total),
+            _ => self.descricao.clone(),
+        }
+    }
+
+    /// Parcela e total de parcelas da ocorrência; 1/1 fora dos
+    /// parcelamentos.
+    pub fn parcela(&self, numero: u32) -> (u32, u32) {
+        match self.ocorrencias {
+            Some(total) => (numero, total),
+            None => (1, 1),
+        }
+    }
+
+    fn no_dia(&self, meses: Months) -> Option<NaiveDate> {
+        let mes = self.inicio.with_day(1)?.checked_add_months(meses)?;
+        let ultimo_dia = mes.checked_add_months(Months::new(1))?.pred_opt()?.day();
+        mes.with_day(self.dia.clamp(1, ultimo_dia))
+    }
+}
+
+impl Listavel for Recorrencia {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("tipo"),
+        Campo::texto("categoria"),
+        Campo::texto("frequencia"),
+        Campo::id("conta_id"),
+        Campo::id("cartao_id"),
+        Campo::id("cliente_id"),
+        Campo::dinheiro("valor"),
+        Campo::data("inicio"),
+        Campo::booleano("ativa"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["descricao", "contraparte", "categoria"];
+    const ORDEM: &'static str = "descricao";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateRecorrencia {
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    /// Padrão: a moeda da conta bancária ou reais.
+    pub moeda: Option<Moeda>,
+    pub frequencia: Frequencia,
+    /// Padrão: o dia de `inicio`.
+    pub dia: Option<u32>,
+    pub inicio: NaiveDate,
+    pub ocorrencias: Option<u32>,
+    pub fim: Option<NaiveDate>,
+    pub conta_id: Option<String>,
+    pub cartao_id: Option<String>,
+    pub cliente_id: Option<String>,
+    pub contraparte: Option<String>,
+}
+
+impl Validar for CreateRecorrencia {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .obrigatorio("descricao", &self.descricao)
+            .obrigatorio("categoria", &self.categoria)
+            .checar("valor", self.valor.positivo(), "Deve ser maior que zero")
+            .checar(
+                "dia",
+                self.dia.is_none_or(|dia| (1..=31).contains(&dia)),
+                "Dia do mês inválido",
+            )
+            .checar(
+                "ocorrencias",
+                self.ocorrencias.is_none_or(|total| total > 0),
+                "Deve ser maior que zero",
+            )
+            .checar(
+                "fim",
+                self.fim.is_none_or(|fim| fim >= self.inicio),
+                "Deve ser igual ou posterior ao início",
+            )
+            .checar(
+                "cartao_id",
+                self.cartao_id.is_none() || self.conta_id.is_none(),
+                "Informe a conta bancária ou o cartão, não ambos",
+            )
+            .checar(
+                "cartao_id",
+                self.cartao_id.is_none() || self.tipo == TipoLancamento::Pagar,
+                "Só contas a pagar podem ser lançadas no cartão",
+            )
+            .checar(
+                "moeda",
+                self.cartao_id.is_none() || self.moeda.is_none_or(|moeda| moeda == Moeda::Brl),
+                "Compras no cartão são sempre em reais",
+            )
+            .concluir()
+    }
+}
+
+/// Quais ocorrências uma alteração ou exclusão atinge.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EscopoOcorrencia {
+    /// Só a ocorrência informada.
+    Esta,
+    /// A ocorrência informada e as seguintes, mudando a regra.
+    EstaEFuturas,
+}
+
+/// Alteração de uma ocorrência. As já pagas nunca são alteradas.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AlterarOcorrencia {
+    pub escopo: EscopoOcorrencia,
+    pub descricao: Option<String>,
+    pub categoria: Option<String>,
+    pub valor: Option<Dinheiro>,
+    pub contraparte: Option<String>,
+    /// Só com o escopo `ESTA`.
+    pub data: Option<NaiveDate>,
+    /// Só com o escopo `ESTA_E_FUTURAS`, nas mensais e anuais.
+    pub dia: Option<u32>,
+}
+
+impl Validar for AlterarOcorrencia {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "descricao",
+                self.descricao
+                    .as_deref()
+                    .is_none_or(|descricao| !descricao.trim().is_empty()),
+                "Campo obrigatório",
+            )
+            .checar(
+                "valor",
+                self.valor.is_none_or(|valor| valor.positivo()),
+                "Deve ser maior que zero",
+            )
+            .checar(
+                "data",
+                self.data.is_none() || self.escopo == EscopoOcorrencia::Esta,
+                "A data só pode ser alterada em uma ocorrência",
+            )
+            .checar(
+                "dia",
+                self.dia.is_none_or(|dia| (1..=31).contains(&dia)),
+                "Dia do mês inválido",
+            )
+            .checar(
+                "dia",
+                self.dia.is_none() || self.escopo == EscopoOcorrencia::EstaEFuturas,
+                "O dia só pode ser alterado nesta e nas próximas ocorrências",
+            )
+            .concluir()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExcluirOcorrencia {
+    pub escopo: EscopoOcorrencia,
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::consulta::{Campo, Listavel};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Papel {
+    Admin,
+    Financeiro,
+    Vendas,
+    Estoque,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Usuario {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub nome: String,
+    /// Login do usuário, sempre em minúsculas.
+    pub email: String,
+    /// Hash Argon2 no formato PHC; a senha nunca é gravada.
+    pub senha_hash: String,
+    pub papeis: Vec<Papel>,
+    /// Empresas que o usuário pode acessar; a primeira é a padrão.
+    #[serde(default)]
+    pub empresas: Vec<ObjectId>,
+    pub ativo: bool,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Listavel for Usuario {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::texto("nome"),
+        Campo::texto("email"),
+        Campo::booleano("ativo"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["nome", "email"];
+    const ORDEM: &'static str = "nome";
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateUsuario {
+    pub nome: String,
+    pub email: String,
+    pub senha: String,
+    pub papeis: Vec<Papel>,
+    /// Quando vazio, o usuário é vinculado à empresa de quem o cadastrou.
+    #[serde(default)]
+    pub empresas: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateUsuario {
+    pub nome: Option<String>,
+    pub senha: Option<String>,
+    pub papeis: Option<Vec<Papel>>,
+    pub empresas: Option<Vec<String>>,
+    pub ativo: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Login {
+    pub email: String,
+    pub senha: String,
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use erp_dinheiro::Dinheiro;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    consulta::{Campo, Listavel},
+    error::Result,
+    validacao::{Validacao, Validar},
+};
+
+pub use erp_api::{StatusVenda, TotaisTributos, TributosItem};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Venda {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub empresa_id: ObjectId,
+    pub cliente_id: Option<ObjectId>,
+    #[serde(default)]
+    pub itens: Vec<ItemVenda>,
+    pub total: Dinheiro,
+    pub desconto: Dinheiro,
+    /// Total dos itens menos o desconto, mais o IPI e o ICMS ST, que o
+    /// cliente paga por fora do preço.
+    pub total_final: Dinheiro,
+    /// Totais dos tributos; sem a configuração fiscal da empresa ou os dados
+    /// fiscais de algum produto, a venda fica sem tributos.
+    #[serde(default)]
+    pub tributos: Option<TotaisTributos>,
+    pub forma_pagamento: String,
+    pub status: StatusVenda,
+    pub observacoes: Option<String>,
+    /// E-mail do usuário autenticado que abriu a venda.
+    pub usuario: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Listavel for Venda {
+    const CAMPOS: &'static [Campo] = &[
+        Campo::id("cliente_id"),
+        Campo::texto("status"),
+        Campo::texto("forma_pagamento"),
+        Campo::texto("usuario"),
+        Campo::dinheiro("total_final"),
+        Campo::instante("created_at"),
+    ];
+    const BUSCA: &'static [&'static str] = &["observacoes", "usuario"];
+    const ORDEM: &'static str = "-created_at";
+}
+
+/// Item embutido no documento da venda.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemVenda {
+    pub id: ObjectId,
+    pub produto_id: ObjectId,
+    pub produto_nome: String,
+    pub quantidade: i32,
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+    #[serde(default)]
+    pub tributos: Option<TributosItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateVenda {
+    pub cliente_id: Option<String>,
+    #[serde(default)]
+    pub itens: Vec<CreateItemVenda>,
+    #[serde(default)]
+    pub desconto: Dinheiro,
+    pub forma_pagamento: String,
+    pub observacoes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateItemVenda {
+    pub produto_id: String,
+    pub quantidade: i32,
+    /// Quando omitido, usa o `preco_venda` atual do produto.
+    pub preco_unitario: Option<Dinheiro>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateVenda {
+    pub cliente_id: Option<String>,
+    pub desconto: Option<Dinheiro>,
+    pub forma_pagamento: Option<String>,
+    pub observacoes: Option<String>,
+}
+
+/// Confere a quantidade e o preço do item; `prefixo` identifica o item
+/// dentro da venda nos erros.
+fn checar_item(validacao: &mut Validacao, prefixo: &str, item: &CreateItemVenda) {
+    validacao
+        .checar(
+            &format!("{prefixo}quantidade"),
+            item.quantidade > 0,
+            "Deve ser maior que zero",
+        )
+        .checar(
+            &format!("{prefixo}preco_unitario"),
+            item.preco_unitario.is_none_or(|preco| preco.positivo()),
+            "Deve ser maior que zero",
+        );
+}
+
+impl Validar for CreateItemVenda {
+    fn validar(&self) -> Result<()> {
+        let mut validacao = Validacao::new();
+        checar_item(&mut validacao, "", self);
+        validacao.concluir()
+    }
+}
+
+impl Validar for CreateVenda {
+    fn validar(&self) -> Result<()> {
+        let mut validacao = Validacao::new();
+        validacao.checar(
+            "desconto",
+            !self.desconto.negativo(),
+            "O desconto não pode ser negativo",
+        );
+        for (indice, item) in self.itens.iter().enumerate() {
+            checar_item(&mut validacao, &format!("itens[{indice}]."), item);
+        }
+        validacao.concluir()
+    }
+}
+
+impl Validar for UpdateVenda {
+    fn validar(&self) -> Result<()> {
+        Validacao::new()
+            .checar(
+                "desconto",
+                self.desconto.is_none_or(|desconto| !desconto.negativo()),
+                "O desconto não pode ser negativo",
+            )
+            .concluir()
+    }
+}
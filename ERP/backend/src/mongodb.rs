@@ -1,4 +1,6 @@
-use mongodb::{Client, Collection, Database};
+use mongodb::{bson::Document, Client, Collection, Database};
+
+use crate::repositorio::Registro;
 
 #[derive(Clone)]
 pub struct MongoDb {
@@ -12,15 +14,26 @@ impl MongoDb {
         Ok(Self { db })
     }
 
-    pub fn contas_bancarias(&self) -> Collection<crate::models::ContaBancaria> {
-        self.db.collection("contas_bancarias")
+    /// Coleção de um agregado que pertence a uma empresa.
+    pub fn colecao<T: Registro + Send + Sync>(&self) -> Collection<T> {
+        self.db.collection(T::COLECAO)
+    }
+
+    pub fn usuarios(&self) -> Collection<crate::models::Usuario> {
+        self.db.collection("usuarios")
+    }
+
+    pub fn empresas(&self) -> Collection<crate::models::Empresa> {
+        self.db.collection("empresas")
     }
 
-    pub fn cartoes(&self) -> Collection<crate::models::Cartao> {
-        self.db.collection("cartoes")
+    pub fn idempotencia(&self) -> Collection<crate::models::RequisicaoIdempotente> {
+        self.db.collection("idempotencia")
     }
 
-    pub fn bancos(&self) -> Collection<crate::models::Banco> {
-        self.db.collection("bancos")
+    /// Acesso sem tipo a uma coleção, para manutenções que valem para
+    /// várias coleções de uma vez.
+    pub fn documentos(&self, nome: &str) -> Collection<Document> {
+        self.db.collection(nome)
     }
 }
@@ -0,0 +1,619 @@
+//! DANFE em PDF (A4 retrato): emitente, chave de acesso com o código de
+//! barras (ou o QR code, na NFC-e), destinatário, impostos e itens.
+
+use printpdf::{
+    path::PaintMode, BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Rect,
+};
+use qrcode::{Color, EcLevel, QrCode};
+
+use super::{Emissao, Retorno};
+use crate::{
+    error::{AppError, Result},
+    models::{Ambiente, ModeloNota},
+    validacao,
+};
+
+const PAGINA_LARGURA: Mm = Mm(210.0);
+const PAGINA_ALTURA: Mm = Mm(297.0);
+const MARGEM: f32 = 10.0;
+const LARGURA_UTIL: f32 = 190.0;
+/// Onde a última linha de itens de cada página pode começar.
+const LIMITE_ITENS: f32 = 280.0;
+const ALTURA_CAMPO: f32 = 9.0;
+const ALTURA_ITEM: f32 = 4.5;
+const TAMANHO_ROTULO: f32 = 6.0;
+const TAMANHO_VALOR: f32 = 9.0;
+const TAMANHO_ITEM: f32 = 6.0;
+/// Descrição dos itens, em caracteres, que cabe na coluna.
+const MAXIMO_DESCRICAO: usize = 34;
+
+/// Módulo (barra mais estreita) do Code 128 e altura das barras.
+const MODULO_BARRAS: f32 = 0.23;
+const ALTURA_BARRAS: f32 = 10.0;
+const MODULO_QRCODE: f32 = 0.7;
+/// Larguras de barras e espaços dos valores 0 a 102 do Code 128.
+const PADROES_CODE128: [&str; 103] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131",
+];
+const INICIO_CODE128C: (u32, &str) = (105, "211232");
+const FIM_CODE128: &str = "2331112";
+
+/// Colunas da tabela de itens: título e largura em milímetros.
+const COLUNAS_ITENS: [(&str, f32); 12] = [
+    ("Código", 30.0),
+    ("Descrição", 40.0),
+    ("NCM", 14.0),
+    ("CST", 9.0),
+    ("CFOP", 9.0),
+    ("Un.", 8.0),
+    ("Qtd.", 12.0),
+    ("V. unit.", 16.0),
+    ("V. total", 16.0),
+    ("V. ICMS", 12.0),
+    ("Alíq. ICMS", 12.0),
+    ("V. IPI", 12.0),
+];
+
+pub fn danfe(emissao: &Emissao, chave: &str, retorno: &Retorno) -> Result<Vec<u8>> {
+    let titulo = format!("DANFE {}", chave);
+    let (documento, pagina, camada) =
+        PdfDocument::new(&titulo, PAGINA_LARGURA, PAGINA_ALTURA, "Folha 1");
+    let mut folha = Folha {
+        camada: documento.get_page(pagina).get_layer(camada),
+        fonte: documento
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(erro_pdf)?,
+        negrito: documento
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(erro_pdf)?,
+    };
+    folha.camada.set_outline_thickness(0.5);
+
+    let protocolo = format!(
+        "{} {}",
+        retorno.protocolo.as_deref().unwrap_or_default(),
+        retorno.recebida_em.format("%d/%m/%Y %H:%M:%S")
+    );
+    let mut y = cabecalho(&folha, emissao, chave, &protocolo);
+    y = destinatario(&folha, emissao, y);
+    y = impostos(&folha, emissao, y);
+    y = itens(&mut folha, &documento, emissao, y);
+    dados_adicionais(&mut folha, &documento, emissao, chave, y);
+
+    documento.save_to_bytes().map_err(erro_pdf)
+}
+
+/// Emitente, identificação da nota, código de barras e chave de acesso.
+/// Devolve onde a próxima seção começa.
+fn cabecalho(folha: &Folha, emissao: &Emissao, chave: &str, protocolo: &str) -> f32 {
+    let fiscal = emissao.fiscal;
+    let mut y = MARGEM;
+    let altura = 32.0;
+
+    folha.caixa(MARGEM, y, 85.0, altura);
+    folha.texto(&emissao.empresa.nome, 10.0, MARGEM + 2.0, y + 7.0, true);
+    let complemento = fiscal
+        .complemento
+        .as_deref()
+        .map(|complemento| format!(" - {}", complemento))
+        .unwrap_or_default();
+    folha.texto(
+        &format!("{}, {}{}", fiscal.logradouro, fiscal.numero, complemento),
+        TAMANHO_ROTULO + 1.0,
+        MARGEM + 2.0,
+        y + 13.0,
+        false,
+    );
+    folha.texto(
+        &format!(
+            "{} - {} - {} - CEP {}",
+            fiscal.bairro,
+            fiscal.municipio,
+            fiscal.uf,
+            cep_formatado(&fiscal.cep)
+        ),
+        TAMANHO_ROTULO + 1.0,
+        MARGEM + 2.0,
+        y + 17.0,
+        false,
+    );
+    if let Some(telefone) = fiscal.telefone.as_deref() {
+        folha.texto(
+            &format!("Fone: {}", telefone),
+            TAMANHO_ROTULO + 1.0,
+            MARGEM + 2.0,
+            y + 21.0,
+            false,
+        );
+    }
+
+    let meio = MARGEM + 85.0;
+    folha.caixa(meio, y, 35.0, altura);
+    let (sigla, descricao) = match emissao.modelo {
+        ModeloNota::Nfe => ("DANFE", "Documento Auxiliar da NF-e"),
+        ModeloNota::Nfce => ("DANFE NFC-e", "Documento Auxiliar da NFC-e"),
+    };
+    folha.texto(sigla, 12.0, meio + 2.0, y + 7.0, true);
+    folha.texto(descricao, TAMANHO_ROTULO, meio + 2.0, y + 11.0, false);
+    folha.texto("1 - Saída", TAMANHO_VALOR, meio + 2.0, y + 17.0, false);
+    folha.texto(
+        &format!("Nº {}", numero_formatado(emissao.numero)),
+        TAMANHO_VALOR,
+        meio + 2.0,
+        y + 23.0,
+        true,
+    );
+    folha.texto(
+        &format!("Série {}", fiscal.serie),
+        TAMANHO_VALOR,
+        meio + 2.0,
+        y + 28.0,
+        true,
+    );
+
+    let direita = meio + 35.0;
+    let largura_direita = LARGURA_UTIL - 120.0;
+    folha.caixa(direita, y, largura_direita, altura);
+    folha.barras(chave, direita + 3.0, y + 2.0);
+    folha.campo(
+        direita,
+        y + 14.0,
+        largura_direita,
+        "Chave de acesso",
+        &chave_formatada(chave),
+    );
+    folha.texto(
+        "Consulte a autenticidade no portal da NF-e ou no site da SEFAZ",
+        TAMANHO_ROTULO,
+        direita + 1.0,
+        y + 28.0,
+        false,
+    );
+    y += altura;
+
+    folha.campo(
+        MARGEM,
+        y,
+        110.0,
+        "Natureza da operação",
+        super::NATUREZA_OPERACAO,
+    );
+    folha.campo(
+        MARGEM + 110.0,
+        y,
+        LARGURA_UTIL - 110.0,
+        "Protocolo de autorização de uso",
+        protocolo,
+    );
+    y += ALTURA_CAMPO;
+
+    let emitida_em = emissao.emitida_em.format("%d/%m/%Y %H:%M:%S").to_string();
+    folha.linha_de_campos(
+        y,
+        &[60.0, 60.0, 70.0],
+        &[
+            ("Inscrição estadual", fiscal.inscricao_estadual.as_str()),
+            (
+                "CNPJ",
+                &validacao::documento_formatado(
+                    emissao.empresa.documento.as_deref().unwrap_or_default(),
+                ),
+            ),
+            ("Data de emissão", &emitida_em),
+        ],
+    );
+    y + ALTURA_CAMPO + 3.0
+}
+
+fn destinatario(folha: &Folha, emissao: &Emissao, mut y: f32) -> f32 {
+    folha.titulo("Destinatário", y);
+    y += 3.0;
+    let Some(cliente) = emissao.cliente else {
+        folha.campo(
+            MARGEM,
+            y,
+            LARGURA_UTIL,
+            "Consumidor",
+            "Consumidor não identificado",
+        );
+        return y + ALTURA_CAMPO + 3.0;
+    };
+
+    let nome = match emissao.ambiente() {
+        Ambiente::Homologacao => super::DESTINATARIO_HOMOLOGACAO,
+        Ambiente::Producao => cliente.nome.as_str(),
+    };
+    folha.linha_de_campos(
+        y,
+        &[130.0, 60.0],
+        &[
+            ("Nome / razão social", nome),
+            (
+                "CNPJ / CPF",
+                &validacao::documento_formatado(&cliente.cpf_cnpj),
+            ),
+        ],
+    );
+    y += ALTURA_CAMPO;
+
+    let endereco = [cliente.endereco.as_deref(), cliente.numero.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let cep = cep_formatado(cliente.cep.as_deref().unwrap_or_default());
+    folha.linha_de_campos(
+        y,
+        &[100.0, 60.0, 30.0],
+        &[
+            ("Endereço", endereco.as_str()),
+            ("Bairro", cliente.bairro.as_deref().unwrap_or_default()),
+            ("CEP", &cep),
+        ],
+    );
+    y += ALTURA_CAMPO;
+
+    folha.linha_de_campos(
+        y,
+        &[100.0, 20.0, 70.0],
+        &[
+            ("Município", cliente.cidade.as_deref().unwrap_or_default()),
+            ("UF", cliente.estado.as_deref().unwrap_or_default()),
+            ("Telefone", cliente.telefone.as_deref().unwrap_or_default()),
+        ],
+    );
+    y + ALTURA_CAMPO + 3.0
+}
+
+fn impostos(folha: &Folha, emissao: &Emissao, mut y: f32) -> f32 {
+    let totais = emissao.totais();
+    folha.titulo("Cálculo do imposto", y);
+    y += 3.0;
+
+    let valores = [
+        totais.base_icms.formatado(),
+        totais.icms.formatado(),
+        totais.base_icms_st.formatado(),
+        totais.icms_st.formatado(),
+        totais.produtos.formatado(),
+    ];
+    folha.linha_de_campos(
+        y,
+        &[38.0; 5],
+        &[
+            ("Base de cálculo do ICMS", valores[0].as_str()),
+            ("Valor do ICMS", &valores[1]),
+            ("Base de cálculo do ICMS ST", &valores[2]),
+            ("Valor do ICMS ST", &valores[3]),
+            ("Valor total dos produtos", &valores[4]),
+        ],
+    );
+    y += ALTURA_CAMPO;
+
+    let valores = [
+        totais.ipi.formatado(),
+        totais.pis.formatado(),
+        totais.cofins.formatado(),
+        totais.desconto.formatado(),
+        totais.nota.formatado(),
+    ];
+    folha.linha_de_campos(
+        y,
+        &[38.0; 5],
+        &[
+            ("Valor do IPI", valores[0].as_str()),
+            ("Valor do PIS", &valores[1]),
+            ("Valor da COFINS", &valores[2]),
+            ("Desconto", &valores[3]),
+            ("Valor total da nota", &valores[4]),
+        ],
+    );
+    y += ALTURA_CAMPO;
+
+    let difal = totais.icms_difal.formatado();
+    folha.linha_de_campos(
+        y,
+        &[38.0, 152.0],
+        &[
+            ("ICMS da UF de destino", difal.as_str()),
+            ("Forma de pagamento", emissao.venda.forma_pagamento.as_str()),
+        ],
+    );
+    y + ALTURA_CAMPO + 3.0
+}
+
+/// Tabela de itens, continuada em novas folhas quando não cabe.
+fn itens(folha: &mut Folha, documento: &PdfDocumentReference, emissao: &Emissao, y: f32) -> f32 {
+    folha.titulo("Dados dos produtos", y);
+    let mut y = cabecalho_itens(folha, y + 3.0);
+
+    for (indice, item) in emissao.itens().iter().enumerate() {
+        if y > LIMITE_ITENS {
+            folha.nova_pagina(documento);
+            y = cabecalho_itens(folha, MARGEM);
+        }
+        let descricao = if indice == 0
+            && emissao.modelo == ModeloNota::Nfce
+            && emissao.ambiente() == Ambiente::Homologacao
+        {
+            super::PRODUTO_HOMOLOGACAO.to_string()
+        } else {
+            item.item
+                .produto_nome
+                .chars()
+                .take(MAXIMO_DESCRICAO)
+                .collect()
+        };
+        let valores = [
+            item.produto.id.map(|id| id.to_hex()).unwrap_or_default(),
+            descricao,
+            item.fiscal.ncm.clone(),
+            format!("{}{}", item.tributos.origem, item.tributos.cst_icms),
+            item.tributos.cfop.clone(),
+            item.produto.unidade.clone(),
+            item.item.quantidade.to_string(),
+            item.item.preco_unitario.formatado(),
+            item.item.subtotal.formatado(),
+            item.tributos.icms.formatado(),
+            item.tributos
+                .aliquota_icms
+                .decimal()
+                .normalize()
+                .to_string(),
+            item.tributos.ipi.formatado(),
+        ];
+
+        let mut x = MARGEM;
+        for ((_, largura), valor) in COLUNAS_ITENS.iter().zip(&valores) {
+            folha.texto(valor, TAMANHO_ITEM, x + 1.0, y + 3.2, false);
+            x += largura;
+        }
+        folha.caixa(MARGEM, y + ALTURA_ITEM, LARGURA_UTIL, 0.0);
+        y += ALTURA_ITEM;
+    }
+    y + 3.0
+}
+
+fn cabecalho_itens(folha: &Folha, y: f32) -> f32 {
+    folha.caixa(MARGEM, y, LARGURA_UTIL, ALTURA_ITEM);
+    let mut x = MARGEM;
+    for (titulo, largura) in COLUNAS_ITENS {
+        folha.texto(titulo, TAMANHO_ITEM, x + 1.0, y + 3.2, true);
+        x += largura;
+    }
+    y + ALTURA_ITEM
+}
+
+/// Informações complementares e, na NFC-e, o QR code de consulta.
+fn dados_adicionais(
+    folha: &mut Folha,
+    documento: &PdfDocumentReference,
+    emissao: &Emissao,
+    chave: &str,
+    mut y: f32,
+) {
+    let altura = 45.0;
+    if y + altura > PAGINA_ALTURA.0 - MARGEM {
+        folha.nova_pagina(documento);
+        y = MARGEM;
+    }
+    folha.titulo("Dados adicionais", y);
+    y += 3.0;
+    folha.caixa(MARGEM, y, LARGURA_UTIL, altura - 3.0);
+    folha.texto(
+        "Informações complementares",
+        TAMANHO_ROTULO,
+        MARGEM + 1.0,
+        y + 2.5,
+        false,
+    );
+
+    let mut linha = y + 7.0;
+    if emissao.ambiente() == Ambiente::Homologacao {
+        folha.texto(
+            "EMITIDA EM AMBIENTE DE HOMOLOGAÇÃO - SEM VALOR FISCAL",
+            TAMANHO_VALOR,
+            MARGEM + 1.0,
+            linha,
+            true,
+        );
+        linha += 5.0;
+    }
+    if let Some(observacoes) = emissao.venda.observacoes.as_deref() {
+        let palavras: Vec<&str> = observacoes.split_whitespace().collect();
+        for trecho in palavras.chunks(16).take(4) {
+            folha.texto(
+                &trecho.join(" "),
+                TAMANHO_ROTULO + 1.0,
+                MARGEM + 1.0,
+                linha,
+                false,
+            );
+            linha += 4.0;
+        }
+    }
+
+    if let (Some(nfce), Some(qrcode)) = (emissao.nfce(), emissao.qrcode()) {
+        let x = MARGEM + LARGURA_UTIL - 40.0;
+        folha.qrcode(&qrcode, x, y + 2.0);
+        folha.texto(
+            "Consulte pela chave de acesso em",
+            TAMANHO_ROTULO,
+            MARGEM + 1.0,
+            y + altura - 12.0,
+            false,
+        );
+        folha.texto(
+            nfce.url_consulta.trim(),
+            TAMANHO_ROTULO,
+            MARGEM + 1.0,
+            y + altura - 9.0,
+            false,
+        );
+        folha.texto(
+            &chave_formatada(chave),
+            TAMANHO_ROTULO + 1.0,
+            MARGEM + 1.0,
+            y + altura - 5.5,
+            true,
+        );
+    }
+}
+
+struct Folha {
+    camada: PdfLayerReference,
+    fonte: IndirectFontRef,
+    negrito: IndirectFontRef,
+}
+
+impl Folha {
+    fn nova_pagina(&mut self, documento: &PdfDocumentReference) {
+        let (pagina, camada) = documento.add_page(PAGINA_LARGURA, PAGINA_ALTURA, "Folha");
+        self.camada = documento.get_page(pagina).get_layer(camada);
+        self.camada.set_outline_thickness(0.5);
+    }
+
+    /// `y` em milímetros a partir do alto da página.
+    fn texto(&self, texto: &str, tamanho: f32, x: f32, y: f32, negrito: bool) {
+        let fonte = if negrito { &self.negrito } else { &self.fonte };
+        self.camada
+            .use_text(texto, tamanho, Mm(x), Mm(PAGINA_ALTURA.0 - y), fonte);
+    }
+
+    /// Título de seção, em maiúsculas, logo acima de `y + 3`.
+    fn titulo(&self, titulo: &str, y: f32) {
+        self.texto(
+            &titulo.to_uppercase(),
+            TAMANHO_ROTULO,
+            MARGEM,
+            y + 2.5,
+            true,
+        );
+    }
+
+    /// Contorno do retângulo com o canto superior esquerdo em (`x`, `y`).
+    fn caixa(&self, x: f32, y: f32, largura: f32, altura: f32) {
+        let topo = PAGINA_ALTURA.0 - y;
+        self.camada.add_rect(
+            Rect::new(Mm(x), Mm(topo - altura), Mm(x + largura), Mm(topo))
+                .with_mode(PaintMode::Stroke),
+        );
+    }
+
+    /// Retângulo preenchido.
+    fn bloco(&self, x: f32, y: f32, largura: f32, altura: f32) {
+        let topo = PAGINA_ALTURA.0 - y;
+        self.camada.add_rect(Rect::new(
+            Mm(x),
+            Mm(topo - altura),
+            Mm(x + largura),
+            Mm(topo),
+        ));
+    }
+
+    /// Campo com rótulo pequeno e valor.
+    fn campo(&self, x: f32, y: f32, largura: f32, rotulo: &str, valor: &str) {
+        self.caixa(x, y, largura, ALTURA_CAMPO);
+        self.texto(rotulo, TAMANHO_ROTULO, x + 1.0, y + 2.5, false);
+        self.texto(valor, TAMANHO_VALOR, x + 1.0, y + 7.5, false);
+    }
+
+    fn linha_de_campos(&self, y: f32, larguras: &[f32], campos: &[(&str, &str)]) {
+        let mut x = MARGEM;
+        for (largura, (rotulo, valor)) in larguras.iter().zip(campos) {
+            self.campo(x, y, *largura, rotulo, valor);
+            x += largura;
+        }
+    }
+
+    /// Code 128 no conjunto C (pares de algarismos): início, os pares, o
+    /// dígito de controle (módulo 103) e fim.
+    fn barras(&self, chave: &str, x: f32, y: f32) {
+        let pares: Vec<u32> = chave
+            .as_bytes()
+            .chunks(2)
+            .map(|par| u32::from(par[0] - b'0') * 10 + u32::from(par[1] - b'0'))
+            .collect();
+        let controle = pares
+            .iter()
+            .zip(1..)
+            .fold(INICIO_CODE128C.0, |soma, (valor, posicao)| {
+                soma + valor * posicao
+            })
+            % 103;
+
+        let mut padroes = vec![INICIO_CODE128C.1];
+        padroes.extend(pares.iter().map(|par| PADROES_CODE128[*par as usize]));
+        padroes.push(PADROES_CODE128[controle as usize]);
+        padroes.push(FIM_CODE128);
+
+        let mut inicio = x;
+        for padrao in padroes {
+            for (indice, largura) in padrao.bytes().enumerate() {
+                let largura = f32::from(largura - b'0') * MODULO_BARRAS;
+                if indice % 2 == 0 {
+                    self.bloco(inicio, y, largura, ALTURA_BARRAS);
+                }
+                inicio += largura;
+            }
+        }
+    }
+
+    fn qrcode(&self, conteudo: &str, x: f32, y: f32) {
+        let Ok(codigo) = QrCode::with_error_correction_level(conteudo, EcLevel::M) else {
+            return;
+        };
+        let largura = codigo.width();
+        for (indice, cor) in codigo.to_colors().into_iter().enumerate() {
+            if cor == Color::Dark {
+                let (linha, coluna) = (indice / largura, indice % largura);
+                self.bloco(
+                    x + coluna as f32 * MODULO_QRCODE,
+                    y + linha as f32 * MODULO_QRCODE,
+                    MODULO_QRCODE,
+                    MODULO_QRCODE,
+                );
+            }
+        }
+    }
+}
+
+/// Chave em grupos de quatro algarismos.
+fn chave_formatada(chave: &str) -> String {
+    chave
+        .as_bytes()
+        .chunks(4)
+        .map(|grupo| String::from_utf8_lossy(grupo).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn numero_formatado(numero: u32) -> String {
+    let texto = format!("{:09}", numero);
+    format!("{}.{}.{}", &texto[..3], &texto[3..6], &texto[6..])
+}
+
+fn cep_formatado(cep: &str) -> String {
+    let digitos = validacao::somente_digitos(cep);
+    if digitos.len() == 8 {
+        format!("{}-{}", &digitos[..5], &digitos[5..])
+    } else {
+        digitos
+    }
+}
+
+fn erro_pdf(erro: printpdf::Error) -> AppError {
+    AppError::Internal(format!("Erro ao gerar o DANFE: {}", erro))
+}
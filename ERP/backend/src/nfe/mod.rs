@@ -0,0 +1,885 @@
+//! NF-e (modelo 55) e NFC-e (modelo 65), leiaute 4.00: a chave de acesso,
+//! o XML da nota a partir da venda, a assinatura com o certificado A1, a
+//! autorização pela SEFAZ e o DANFE.
+
+mod assinatura;
+mod danfe;
+mod sefaz;
+
+pub use assinatura::Certificado;
+pub use danfe::danfe;
+pub use sefaz::{nota_processada, Retorno, Sefaz, SefazLocal};
+
+use std::{collections::HashMap, fmt::Display};
+
+use chrono::{DateTime, FixedOffset};
+use erp_dinheiro::Aliquota;
+use mongodb::bson::oid::ObjectId;
+use sha1::{Digest, Sha1};
+
+use crate::{
+    error::{AppError, Result},
+    models::{
+        Ambiente, Cliente, ConfiguracaoFiscal, ConfiguracaoNfce, DadosFiscais, Dinheiro, Empresa,
+        ItemVenda, ModeloNota, Produto, RegimeTributario, TributosItem, Venda, CSOSNS,
+    },
+    tributos::{self, Operacao},
+    validacao,
+};
+
+pub const VERSAO: &str = "4.00";
+pub const NAMESPACE: &str = "http://www.portalfiscal.inf.br/nfe";
+/// Código do Brasil na tabela de países do Banco Central.
+const CODIGO_BRASIL: &str = "1058";
+const NATUREZA_OPERACAO: &str = "VENDA DE MERCADORIA";
+const SEM_GTIN: &str = "SEM GTIN";
+/// Nome do destinatário exigido pela SEFAZ em homologação.
+const DESTINATARIO_HOMOLOGACAO: &str = "NF-E EMITIDA EM AMBIENTE DE HOMOLOGACAO - SEM VALOR FISCAL";
+/// Descrição do primeiro item da NFC-e exigida pela SEFAZ em homologação.
+const PRODUTO_HOMOLOGACAO: &str =
+    "NOTA FISCAL EMITIDA EM AMBIENTE DE HOMOLOGACAO - SEM VALOR FISCAL";
+const MAXIMO_ITENS: usize = 990;
+/// Fuso de Brasília, usado na data de emissão.
+const FUSO_SEGUNDOS: i32 = -3 * 3600;
+
+/// Códigos IBGE das unidades da federação.
+const UFS: [(&str, u8); 27] = [
+    ("RO", 11),
+    ("AC", 12),
+    ("AM", 13),
+    ("RR", 14),
+    ("PA", 15),
+    ("AP", 16),
+    ("TO", 17),
+    ("MA", 21),
+    ("PI", 22),
+    ("CE", 23),
+    ("RN", 24),
+    ("PB", 25),
+    ("PE", 26),
+    ("AL", 27),
+    ("SE", 28),
+    ("BA", 29),
+    ("MG", 31),
+    ("ES", 32),
+    ("RJ", 33),
+    ("SP", 35),
+    ("PR", 41),
+    ("SC", 42),
+    ("RS", 43),
+    ("MS", 50),
+    ("MT", 51),
+    ("GO", 52),
+    ("DF", 53),
+];
+
+pub fn codigo_uf(uf: &str) -> Option<u8> {
+    let uf = uf.trim().to_uppercase();
+    UFS.iter()
+        .find(|(sigla, _)| *sigla == uf)
+        .map(|(_, codigo)| *codigo)
+}
+
+pub fn fuso() -> FixedOffset {
+    FixedOffset::east_opt(FUSO_SEGUNDOS).expect("fuso de Brasília válido")
+}
+
+/// Tudo o que entra em uma nota, já conferido contra as regras da SEFAZ
+/// que o ERP consegue checar antes do envio.
+pub struct Emissao<'a> {
+    pub empresa: &'a Empresa,
+    pub fiscal: &'a ConfiguracaoFiscal,
+    pub modelo: ModeloNota,
+    pub numero: u32,
+    pub emitida_em: DateTime<FixedOffset>,
+    pub venda: &'a Venda,
+    pub cliente: Option<&'a Cliente>,
+    cnpj: String,
+    /// Código numérico da chave (`cNF`), que a impede de ser deduzida.
+    codigo: u32,
+    itens: Vec<Item<'a>>,
+}
+
+/// Item da nota com os tributos calculados na venda.
+pub struct Item<'a> {
+    pub item: &'a ItemVenda,
+    pub produto: &'a Produto,
+    pub fiscal: &'a DadosFiscais,
+    pub tributos: &'a TributosItem,
+}
+
+/// Totais da nota (`ICMSTot`).
+pub struct Totais {
+    pub base_icms: Dinheiro,
+    pub icms: Dinheiro,
+    pub icms_difal: Dinheiro,
+    pub base_icms_st: Dinheiro,
+    pub icms_st: Dinheiro,
+    pub produtos: Dinheiro,
+    pub desconto: Dinheiro,
+    pub ipi: Dinheiro,
+    pub pis: Dinheiro,
+    pub cofins: Dinheiro,
+    pub nota: Dinheiro,
+}
+
+/// A venda com os tributos dos itens. A venda gravada sem eles, de antes da
+/// configuração fiscal ou com produtos sem dados fiscais, é calculada
+/// agora; se os tributos mudam o total já cobrado, a nota não sai.
+pub fn tributada(
+    empresa: &Empresa,
+    cliente: Option<&Cliente>,
+    venda: Venda,
+    produtos: &HashMap<ObjectId, Produto>,
+) -> Result<Venda> {
+    let Some(fiscal) = empresa.fiscal.as_ref() else {
+        return Ok(venda);
+    };
+    if venda.tributos.is_some() && venda.itens.iter().all(|item| item.tributos.is_some()) {
+        return Ok(venda);
+    }
+    let mut tributada = venda.clone();
+    tributos::calcular(&Operacao::new(fiscal, cliente), &mut tributada, produtos)?;
+    if tributada.total_final != venda.total_final {
+        return Err(AppError::Conflict(format!(
+            "Com IPI e ICMS ST o total da venda seria {}, e não os {} cobrados",
+            tributada.total_final.formatado(),
+            venda.total_final.formatado()
+        )));
+    }
+    Ok(tributada)
+}
+
+impl<'a> Emissao<'a> {
+    /// `nota_id` é o id que a nota terá no banco; dele sai o código
+    /// numérico da chave de acesso.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        empresa: &'a Empresa,
+        modelo: ModeloNota,
+        numero: u32,
+        nota_id: ObjectId,
+        emitida_em: DateTime<FixedOffset>,
+        venda: &'a Venda,
+        cliente: Option<&'a Cliente>,
+        produtos: &'a HashMap<ObjectId, Produto>,
+    ) -> Result<Self> {
+        let fiscal = empresa.fiscal.as_ref().ok_or_else(|| {
+            AppError::BadRequest(
+                "Configure os dados fiscais da empresa para emitir notas".to_string(),
+            )
+        })?;
+        let cnpj = empresa
+            .documento
+            .as_deref()
+            .filter(|documento| validacao::cnpj_valido(documento))
+            .map(validacao::somente_digitos)
+            .ok_or_else(|| {
+                AppError::BadRequest("A empresa precisa de CNPJ para emitir notas".to_string())
+            })?;
+        if modelo == ModeloNota::Nfce && fiscal.nfce.is_none() {
+            return Err(AppError::BadRequest(
+                "Configure o CSC da NFC-e nos dados fiscais da empresa".to_string(),
+            ));
+        }
+        if venda.itens.is_empty() || venda.itens.len() > MAXIMO_ITENS {
+            return Err(AppError::BadRequest(format!(
+                "A nota precisa ter de 1 a {} itens",
+                MAXIMO_ITENS
+            )));
+        }
+        if modelo == ModeloNota::Nfe {
+            validar_destinatario(cliente)?;
+        }
+
+        // Um contador no fim do ObjectId tornaria o código previsível.
+        let hash = Sha1::digest(nota_id.bytes());
+        let mut codigo = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]) % 100_000_000;
+        if codigo == numero {
+            codigo = (codigo + 1) % 100_000_000;
+        }
+
+        let mut emissao = Self {
+            empresa,
+            fiscal,
+            modelo,
+            numero,
+            emitida_em,
+            venda,
+            cliente,
+            cnpj,
+            codigo,
+            itens: Vec::new(),
+        };
+        emissao.itens = emissao.calcular_itens(produtos)?;
+        if modelo == ModeloNota::Nfce {
+            if emissao.interestadual() {
+                return Err(AppError::BadRequest(
+                    "A NFC-e é só para vendas dentro do estado; emita NF-e".to_string(),
+                ));
+            }
+            if emissao.itens.iter().any(|item| {
+                item.tributos.icms_st.positivo()
+                    || item.tributos.base_icms_st.positivo()
+                    || item.tributos.ipi.positivo()
+            }) {
+                return Err(AppError::BadRequest(
+                    "A NFC-e não aceita itens com ICMS ST ou IPI; emita NF-e".to_string(),
+                ));
+            }
+        }
+        Ok(emissao)
+    }
+
+    fn calcular_itens(&self, produtos: &'a HashMap<ObjectId, Produto>) -> Result<Vec<Item<'a>>> {
+        let simples = self.fiscal.regime == RegimeTributario::SimplesNacional;
+        self.venda
+            .itens
+            .iter()
+            .map(|item| {
+                let produto = produtos.get(&item.produto_id).ok_or(AppError::NotFound)?;
+                let fiscal = produto.fiscal.as_ref().ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "Informe os dados fiscais do produto {}",
+                        produto.nome
+                    ))
+                })?;
+                let tributos = item.tributos.as_ref().ok_or_else(|| {
+                    AppError::Internal("Item da venda sem os tributos calculados".to_string())
+                })?;
+                if CSOSNS.contains(&tributos.cst_icms.as_str()) != simples {
+                    return Err(AppError::BadRequest(format!(
+                        "Os tributos do item {} foram calculados em outro regime tributário",
+                        item.produto_nome
+                    )));
+                }
+                Ok(Item {
+                    item,
+                    produto,
+                    fiscal,
+                    tributos,
+                })
+            })
+            .collect()
+    }
+
+    pub fn itens(&self) -> &[Item<'a>] {
+        &self.itens
+    }
+
+    pub fn totais(&self) -> Totais {
+        let mut totais = Totais {
+            base_icms: Dinheiro::ZERO,
+            icms: Dinheiro::ZERO,
+            icms_difal: Dinheiro::ZERO,
+            base_icms_st: Dinheiro::ZERO,
+            icms_st: Dinheiro::ZERO,
+            produtos: Dinheiro::ZERO,
+            desconto: Dinheiro::ZERO,
+            ipi: Dinheiro::ZERO,
+            pis: Dinheiro::ZERO,
+            cofins: Dinheiro::ZERO,
+            nota: self.venda.total_final,
+        };
+        for item in &self.itens {
+            let tributos = item.tributos;
+            totais.base_icms += tributos.base_icms;
+            totais.icms += tributos.icms;
+            totais.icms_difal += tributos.icms_difal;
+            totais.base_icms_st += tributos.base_icms_st;
+            totais.icms_st += tributos.icms_st;
+            totais.produtos += item.item.subtotal;
+            totais.desconto += tributos.desconto;
+            totais.ipi += tributos.ipi;
+            totais.pis += tributos.pis;
+            totais.cofins += tributos.cofins;
+        }
+        totais
+    }
+
+    pub fn ambiente(&self) -> Ambiente {
+        self.fiscal.ambiente
+    }
+
+    pub fn uf(&self) -> u8 {
+        codigo_uf(&self.fiscal.uf).unwrap_or_default()
+    }
+
+    /// Venda para outra UF, pelo CFOP dos itens.
+    fn interestadual(&self) -> bool {
+        self.itens.iter().any(|item| item.tributos.interestadual())
+    }
+
+    /// Chave de acesso: UF, ano e mês, CNPJ, modelo, série, número, tipo de
+    /// emissão, código numérico e o dígito verificador.
+    pub fn chave(&self) -> String {
+        let sem_dv = format!(
+            "{:02}{}{}{:02}{:03}{:09}1{:08}",
+            self.uf(),
+            self.emitida_em.format("%y%m"),
+            self.cnpj,
+            self.modelo.codigo(),
+            self.fiscal.serie,
+            self.numero,
+            self.codigo
+        );
+        format!("{}{}", sem_dv, dv_chave(&sem_dv))
+    }
+
+    /// URL do QR code da NFC-e (versão 2, emissão online).
+    pub fn qrcode(&self) -> Option<String> {
+        let nfce = self.nfce()?;
+        let identificador = nfce.csc_id.trim().trim_start_matches('0');
+        let parametros = format!(
+            "{}|2|{}|{}",
+            self.chave(),
+            self.ambiente().codigo(),
+            identificador
+        );
+        let hash = Sha1::digest(format!("{}{}", parametros, nfce.csc.trim()).as_bytes());
+        let hash: String = hash.iter().map(|byte| format!("{:02X}", byte)).collect();
+        Some(format!(
+            "{}?p={}|{}",
+            nfce.url_qrcode.trim(),
+            parametros,
+            hash
+        ))
+    }
+
+    pub fn nfce(&self) -> Option<&ConfiguracaoNfce> {
+        match self.modelo {
+            ModeloNota::Nfce => self.fiscal.nfce.as_ref(),
+            ModeloNota::Nfe => None,
+        }
+    }
+
+    /// Elemento `NFe` ainda sem assinatura, já na forma canônica: sem
+    /// espaços entre os elementos e com os atributos em ordem.
+    pub fn xml(&self) -> String {
+        let chave = self.chave();
+        let mut xml = Xml::default();
+        xml.0.push_str(&format!(
+            "<NFe xmlns=\"{}\"><infNFe Id=\"NFe{}\" versao=\"{}\">",
+            NAMESPACE, chave, VERSAO
+        ));
+        self.identificacao(&mut xml, &chave);
+        self.emitente(&mut xml);
+        self.destinatario(&mut xml);
+        for (indice, item) in self.itens.iter().enumerate() {
+            self.detalhe(&mut xml, indice, item);
+        }
+        self.total(&mut xml);
+        xml.grupo("transp", |xml| xml.campo("modFrete", 9));
+        self.pagamento(&mut xml);
+        if let Some(observacoes) = self.venda.observacoes.as_deref() {
+            if !observacoes.trim().is_empty() {
+                xml.grupo("infAdic", |xml| xml.texto("infCpl", observacoes, 5000));
+            }
+        }
+        xml.0.push_str("</infNFe>");
+
+        if let (Some(nfce), Some(qrcode)) = (self.nfce(), self.qrcode()) {
+            xml.grupo("infNFeSupl", |xml| {
+                xml.campo("qrCode", escapar(&qrcode));
+                xml.campo("urlChave", escapar(nfce.url_consulta.trim()));
+            });
+        }
+        xml.0.push_str("</NFe>");
+        xml.0
+    }
+
+    fn identificacao(&self, xml: &mut Xml, chave: &str) {
+        let nfce = self.modelo == ModeloNota::Nfce;
+        xml.grupo("ide", |xml| {
+            xml.campo("cUF", self.uf());
+            xml.campo("cNF", format!("{:08}", self.codigo));
+            xml.campo("natOp", NATUREZA_OPERACAO);
+            xml.campo("mod", self.modelo.codigo());
+            xml.campo("serie", self.fiscal.serie);
+            xml.campo("nNF", self.numero);
+            xml.campo("dhEmi", self.emitida_em.format("%Y-%m-%dT%H:%M:%S%:z"));
+            // Saída.
+            xml.campo("tpNF", 1);
+            xml.campo("idDest", if self.interestadual() { 2 } else { 1 });
+            xml.campo("cMunFG", &self.fiscal.codigo_municipio);
+            // DANFE retrato, ou DANFE NFC-e.
+            xml.campo("tpImp", if nfce { 4 } else { 1 });
+            // Emissão normal.
+            xml.campo("tpEmis", 1);
+            xml.campo("cDV", &chave[43..]);
+            xml.campo("tpAmb", self.ambiente().codigo());
+            xml.campo("finNFe", 1);
+            // Consumidor final, presencial no caixa ou não informado.
+            xml.campo("indFinal", 1);
+            xml.campo("indPres", if nfce { 1 } else { 9 });
+            xml.campo("indIntermed", 0);
+            xml.campo("procEmi", 0);
+            xml.campo("verProc", concat!("ERP ", env!("CARGO_PKG_VERSION")));
+        });
+    }
+
+    fn emitente(&self, xml: &mut Xml) {
+        let fiscal = self.fiscal;
+        xml.grupo("emit", |xml| {
+            xml.campo("CNPJ", &self.cnpj);
+            xml.texto("xNome", &self.empresa.nome, 60);
+            xml.grupo("enderEmit", |xml| {
+                xml.texto("xLgr", &fiscal.logradouro, 60);
+                xml.texto("nro", &fiscal.numero, 60);
+                if let Some(complemento) = fiscal.complemento.as_deref() {
+                    if !complemento.trim().is_empty() {
+                        xml.texto("xCpl", complemento, 60);
+                    }
+                }
+                xml.texto("xBairro", &fiscal.bairro, 60);
+                xml.campo("cMun", &fiscal.codigo_municipio);
+                xml.texto("xMun", &fiscal.municipio, 60);
+                xml.campo("UF", &fiscal.uf);
+                xml.campo("CEP", &fiscal.cep);
+                xml.campo("cPais", CODIGO_BRASIL);
+                xml.campo("xPais", "BRASIL");
+                if let Some(telefone) = fiscal.telefone.as_deref().and_then(telefone) {
+                    xml.campo("fone", telefone);
+                }
+            });
+            xml.campo("IE", &fiscal.inscricao_estadual);
+            xml.campo("CRT", fiscal.regime.crt());
+        });
+    }
+
+    /// Obrigatório na NF-e; na NFC-e só quando o cliente tem CPF ou CNPJ.
+    fn destinatario(&self, xml: &mut Xml) {
+        let Some(cliente) = self.cliente else {
+            return;
+        };
+        let documento = validacao::somente_digitos(&cliente.cpf_cnpj);
+        let tag = if validacao::cpf_valido(&documento) {
+            "CPF"
+        } else if validacao::cnpj_valido(&documento) {
+            "CNPJ"
+        } else {
+            return;
+        };
+        let nome = match self.ambiente() {
+            Ambiente::Homologacao => DESTINATARIO_HOMOLOGACAO,
+            Ambiente::Producao => cliente.nome.as_str(),
+        };
+
+        xml.grupo("dest", |xml| {
+            xml.campo(tag, &documento);
+            xml.texto("xNome", nome, 60);
+            if self.modelo == ModeloNota::Nfe {
+                xml.grupo("enderDest", |xml| {
+                    xml.texto("xLgr", cliente.endereco.as_deref().unwrap_or_default(), 60);
+                    xml.texto("nro", cliente.numero.as_deref().unwrap_or_default(), 60);
+                    xml.texto("xBairro", cliente.bairro.as_deref().unwrap_or_default(), 60);
+                    xml.campo(
+                        "cMun",
+                        validacao::somente_digitos(
+                            cliente.codigo_municipio.as_deref().unwrap_or_default(),
+                        ),
+                    );
+                    xml.texto("xMun", cliente.cidade.as_deref().unwrap_or_default(), 60);
+                    xml.campo(
+                        "UF",
+                        cliente
+                            .estado
+                            .as_deref()
+                            .unwrap_or_default()
+                            .trim()
+                            .to_uppercase(),
+                    );
+                    xml.campo(
+                        "CEP",
+                        validacao::somente_digitos(cliente.cep.as_deref().unwrap_or_default()),
+                    );
+                    xml.campo("cPais", CODIGO_BRASIL);
+                    xml.campo("xPais", "BRASIL");
+                    if let Some(telefone) = cliente.telefone.as_deref().and_then(telefone) {
+                        xml.campo("fone", telefone);
+                    }
+                });
+            }
+            // Não contribuinte do ICMS.
+            xml.campo("indIEDest", 9);
+            if let Some(email) = cliente.email.as_deref() {
+                if !email.trim().is_empty() {
+                    xml.texto("email", email, 60);
+                }
+            }
+        });
+    }
+
+    fn detalhe(&self, xml: &mut Xml, indice: usize, item: &Item) {
+        let produto = item.produto;
+        let fiscal = item.fiscal;
+        let descricao = if indice == 0
+            && self.modelo == ModeloNota::Nfce
+            && self.ambiente() == Ambiente::Homologacao
+        {
+            PRODUTO_HOMOLOGACAO
+        } else {
+            item.item.produto_nome.as_str()
+        };
+        let gtin = gtin(produto);
+        let unidade: String = produto.unidade.trim().chars().take(6).collect();
+        let quantidade = format!("{}.0000", item.item.quantidade);
+
+        xml.0.push_str(&format!("<det nItem=\"{}\">", indice + 1));
+        xml.grupo("prod", |xml| {
+            xml.campo(
+                "cProd",
+                produto.id.map(|id| id.to_hex()).unwrap_or_default(),
+            );
+            xml.campo("cEAN", &gtin);
+            xml.texto("xProd", descricao, 120);
+            xml.campo("NCM", &fiscal.ncm);
+            if let Some(cest) = fiscal.cest.as_deref() {
+                xml.campo("CEST", cest);
+            }
+            xml.campo("CFOP", &item.tributos.cfop);
+            xml.texto("uCom", &unidade, 6);
+            xml.campo("qCom", &quantidade);
+            xml.campo("vUnCom", item.item.preco_unitario);
+            xml.campo("vProd", item.item.subtotal);
+            xml.campo("cEANTrib", &gtin);
+            xml.texto("uTrib", &unidade, 6);
+            xml.campo("qTrib", &quantidade);
+            xml.campo("vUnTrib", item.item.preco_unitario);
+            if item.tributos.desconto.positivo() {
+                xml.campo("vDesc", item.tributos.desconto);
+            }
+            xml.campo("indTot", 1);
+        });
+        let tributos = item.tributos;
+        xml.grupo("imposto", |xml| {
+            icms(xml, tributos);
+            if self.modelo == ModeloNota::Nfe {
+                ipi(xml, tributos);
+            }
+            pis_cofins(xml, "PIS", tributos, tributos.aliquota_pis, tributos.pis);
+            pis_cofins(
+                xml,
+                "COFINS",
+                tributos,
+                tributos.aliquota_cofins,
+                tributos.cofins,
+            );
+            if !tributos.aliquota_icms_destino.is_zero() {
+                icms_uf_destino(xml, tributos);
+            }
+        });
+        xml.0.push_str("</det>");
+    }
+
+    fn total(&self, xml: &mut Xml) {
+        let totais = self.totais();
+        xml.grupo("total", |xml| {
+            xml.grupo("ICMSTot", |xml| {
+                xml.campo("vBC", totais.base_icms);
+                xml.campo("vICMS", totais.icms);
+                xml.campo("vICMSDeson", Dinheiro::ZERO);
+                if self
+                    .itens
+                    .iter()
+                    .any(|item| !item.tributos.aliquota_icms_destino.is_zero())
+                {
+                    xml.campo("vFCPUFDest", Dinheiro::ZERO);
+                    xml.campo("vICMSUFDest", totais.icms_difal);
+                    xml.campo("vICMSUFRemet", Dinheiro::ZERO);
+                }
+                xml.campo("vFCP", Dinheiro::ZERO);
+                xml.campo("vBCST", totais.base_icms_st);
+                xml.campo("vST", totais.icms_st);
+                xml.campo("vFCPST", Dinheiro::ZERO);
+                xml.campo("vFCPSTRet", Dinheiro::ZERO);
+                xml.campo("vProd", totais.produtos);
+                xml.campo("vFrete", Dinheiro::ZERO);
+                xml.campo("vSeg", Dinheiro::ZERO);
+                xml.campo("vDesc", totais.desconto);
+                xml.campo("vII", Dinheiro::ZERO);
+                xml.campo("vIPI", totais.ipi);
+                xml.campo("vIPIDevol", Dinheiro::ZERO);
+                xml.campo("vPIS", totais.pis);
+                xml.campo("vCOFINS", totais.cofins);
+                xml.campo("vOutro", Dinheiro::ZERO);
+                xml.campo("vNF", totais.nota);
+            });
+        });
+    }
+
+    fn pagamento(&self, xml: &mut Xml) {
+        let forma = self.venda.forma_pagamento.trim();
+        let meio = meio_de_pagamento(forma);
+        xml.grupo("pag", |xml| {
+            xml.grupo("detPag", |xml| {
+                xml.campo("tPag", meio);
+                if meio == "99" {
+                    xml.texto("xPag", forma, 60);
+                }
+                xml.campo("vPag", self.venda.total_final);
+            });
+        });
+    }
+}
+
+/// A NF-e exige o destinatário com endereço completo.
+fn validar_destinatario(cliente: Option<&Cliente>) -> Result<()> {
+    let cliente = cliente.ok_or_else(|| {
+        AppError::BadRequest("Informe o cliente da venda para emitir a NF-e".to_string())
+    })?;
+    if !validacao::cpf_valido(&cliente.cpf_cnpj) && !validacao::cnpj_valido(&cliente.cpf_cnpj) {
+        return Err(AppError::BadRequest(
+            "O destinatário da NF-e precisa de CPF ou CNPJ".to_string(),
+        ));
+    }
+    let preenchido =
+        |campo: &Option<String>| campo.as_deref().is_some_and(|v| !v.trim().is_empty());
+    let endereco_completo = preenchido(&cliente.endereco)
+        && preenchido(&cliente.numero)
+        && preenchido(&cliente.bairro)
+        && preenchido(&cliente.cidade)
+        && cliente
+            .codigo_municipio
+            .as_deref()
+            .is_some_and(|codigo| validacao::somente_digitos(codigo).len() == 7)
+        && cliente.estado.as_deref().and_then(codigo_uf).is_some()
+        && cliente
+            .cep
+            .as_deref()
+            .is_some_and(|cep| validacao::somente_digitos(cep).len() == 8);
+    if !endereco_completo {
+        return Err(AppError::BadRequest(
+            "Complete o endereço do cliente (logradouro, número, bairro, município com código IBGE, UF e CEP) para emitir a NF-e"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Grupo do ICMS pelo CST ou CSOSN. A base é o valor da operação (`modBC`
+/// 3) e a da ST, a margem de valor agregado (`modBCST` 4).
+fn icms(xml: &mut Xml, tributos: &TributosItem) {
+    let cst = tributos.cst_icms.as_str();
+    let proprio = |xml: &mut Xml| {
+        xml.campo("modBC", 3);
+        if cst == "20" {
+            xml.campo("pRedBC", tributos.reducao_base_icms);
+        }
+        xml.campo("vBC", tributos.base_icms);
+        xml.campo("pICMS", tributos.aliquota_icms);
+        xml.campo("vICMS", tributos.icms);
+    };
+    let retido = |xml: &mut Xml| {
+        xml.campo("modBCST", 4);
+        xml.campo("pMVAST", tributos.mva_st);
+        xml.campo("vBCST", tributos.base_icms_st);
+        xml.campo("pICMSST", tributos.aliquota_icms_st);
+        xml.campo("vICMSST", tributos.icms_st);
+    };
+    let (grupo, tag) = match cst {
+        "00" | "10" | "20" | "60" => (format!("ICMS{}", cst), "CST"),
+        "40" | "41" | "50" => ("ICMS40".to_string(), "CST"),
+        "202" | "500" => (format!("ICMSSN{}", cst), "CSOSN"),
+        _ => ("ICMSSN102".to_string(), "CSOSN"),
+    };
+    xml.grupo("ICMS", |xml| {
+        xml.grupo(&grupo, |xml| {
+            xml.campo("orig", tributos.origem);
+            xml.campo(tag, cst);
+            match cst {
+                "00" | "20" => proprio(xml),
+                "10" => {
+                    proprio(xml);
+                    retido(xml);
+                }
+                "202" => retido(xml),
+                _ => {}
+            }
+        });
+    });
+}
+
+/// IPI tributado (CST 50 e 99) ou não tributado; `cEnq` 999 é o
+/// enquadramento "tributação normal".
+fn ipi(xml: &mut Xml, tributos: &TributosItem) {
+    let Some(cst) = tributos.cst_ipi.as_deref() else {
+        return;
+    };
+    xml.grupo("IPI", |xml| {
+        xml.campo("cEnq", 999);
+        if matches!(cst, "50" | "99") {
+            xml.grupo("IPITrib", |xml| {
+                xml.campo("CST", cst);
+                xml.campo("vBC", tributos.base_ipi);
+                xml.campo("pIPI", tributos.aliquota_ipi);
+                xml.campo("vIPI", tributos.ipi);
+            });
+        } else {
+            xml.grupo("IPINT", |xml| xml.campo("CST", cst));
+        }
+    });
+}
+
+/// Grupo de PIS ou COFINS: por alíquota (CST 01 e 02), não tributado (04 a
+/// 09) ou outras operações (49 e 99).
+fn pis_cofins(
+    xml: &mut Xml,
+    tributo: &str,
+    tributos: &TributosItem,
+    aliquota: Aliquota,
+    valor: Dinheiro,
+) {
+    let cst = tributos.cst_pis_cofins.as_str();
+    let grupo = match cst {
+        "01" | "02" => "Aliq",
+        "49" | "99" => "Outr",
+        _ => "NT",
+    };
+    xml.grupo(tributo, |xml| {
+        xml.grupo(&format!("{}{}", tributo, grupo), |xml| {
+            xml.campo("CST", cst);
+            if grupo != "NT" {
+                xml.campo("vBC", tributos.base_pis_cofins);
+                xml.campo(&format!("p{}", tributo), aliquota);
+                xml.campo(&format!("v{}", tributo), valor);
+            }
+        });
+    });
+}
+
+/// Partilha do ICMS na venda a consumidor final de outra UF: todo o
+/// diferencial de alíquotas vai para o destino.
+fn icms_uf_destino(xml: &mut Xml, tributos: &TributosItem) {
+    xml.grupo("ICMSUFDest", |xml| {
+        xml.campo("vBCUFDest", tributos.base_icms);
+        xml.campo("pICMSUFDest", tributos.aliquota_icms_destino);
+        // A interestadual vai com duas casas: 4.00, 7.00 ou 12.00.
+        xml.campo(
+            "pICMSInter",
+            format!("{:.2}", tributos.aliquota_icms.decimal()),
+        );
+        xml.campo("pICMSInterPart", "100.0000");
+        xml.campo("vICMSUFDest", tributos.icms_difal);
+        xml.campo("vICMSUFRemet", Dinheiro::ZERO);
+    });
+}
+
+/// Meio de pagamento (`tPag`) pela forma de pagamento da venda; as não
+/// reconhecidas vão como "outros" (99), com a descrição.
+fn meio_de_pagamento(forma: &str) -> &'static str {
+    let forma = validacao::sem_acentos(forma).to_lowercase();
+    if forma.contains("dinheiro") {
+        "01"
+    } else if forma.contains("cheque") {
+        "02"
+    } else if forma.contains("credito") {
+        "03"
+    } else if forma.contains("debito") {
+        "04"
+    } else if forma.contains("boleto") {
+        "15"
+    } else if forma.contains("pix") {
+        "17"
+    } else {
+        "99"
+    }
+}
+
+fn gtin(produto: &Produto) -> String {
+    produto
+        .codigo_barras
+        .as_deref()
+        .map(validacao::somente_digitos)
+        .filter(|codigo| [8, 12, 13, 14].contains(&codigo.len()))
+        .unwrap_or_else(|| SEM_GTIN.to_string())
+}
+
+fn telefone(telefone: &str) -> Option<String> {
+    let digitos = validacao::somente_digitos(telefone);
+    (6..=14).contains(&digitos.len()).then_some(digitos)
+}
+
+/// Dígito verificador da chave de acesso: módulo 11 com pesos de 2 a 9 da
+/// direita para a esquerda; restos 0 e 1 dão 0.
+fn dv_chave(chave: &str) -> u32 {
+    let soma: u32 = chave
+        .bytes()
+        .rev()
+        .zip((2..=9).cycle())
+        .map(|(byte, peso)| u32::from(byte - b'0') * peso)
+        .sum();
+    match soma % 11 {
+        0 | 1 => 0,
+        resto => 11 - resto,
+    }
+}
+
+/// Texto como a SEFAZ aceita: espaços simples, sem quebras de linha e com
+/// `&`, `<` e `>` escapados.
+fn escapar(texto: &str) -> String {
+    texto
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Conteúdo do primeiro elemento `tag` de um XML gerado aqui.
+pub fn conteudo<'x>(xml: &'x str, tag: &str) -> Option<&'x str> {
+    let abertura = format!("<{}>", tag);
+    let inicio = xml.find(&abertura)? + abertura.len();
+    let fim = xml[inicio..].find(&format!("</{}", tag))? + inicio;
+    Some(&xml[inicio..fim])
+}
+
+#[derive(Default)]
+struct Xml(String);
+
+impl Xml {
+    fn grupo(&mut self, tag: &str, conteudo: impl FnOnce(&mut Xml)) {
+        self.0.push_str(&format!("<{}>", tag));
+        conteudo(self);
+        self.0.push_str(&format!("</{}>", tag));
+    }
+
+    /// Valor já no formato do leiaute, sem escapar.
+    fn campo(&mut self, tag: &str, valor: impl Display) {
+        self.0.push_str(&format!("<{0}>{1}</{0}>", tag, valor));
+    }
+
+    /// Texto livre, escapado e cortado em `maximo` caracteres.
+    fn texto(&mut self, tag: &str, texto: &str, maximo: usize) {
+        let texto: String = texto
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(maximo)
+            .collect();
+        self.campo(tag, escapar(&texto));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digito_da_chave_de_acesso() {
+        // Exemplo do manual de orientação do contribuinte.
+        assert_eq!(dv_chave("5206043300991100250655012000000780026730161"), 5);
+        assert_eq!(dv_chave("3524011122233300018155001000000042100000042"), 8);
+    }
+
+    #[test]
+    fn escapa_texto_livre() {
+        assert_eq!(
+            escapar(" Silva  &\n<Filhos> "),
+            "Silva &amp; &lt;Filhos&gt;"
+        );
+        assert_eq!(conteudo("<a><cStat>100</cStat></a>", "cStat"), Some("100"));
+    }
+}
@@ -0,0 +1,97 @@
+//! Autorização da nota na SEFAZ. A transmissão fica atrás de [`Sefaz`]; o
+//! [`SefazLocal`] autoriza localmente toda nota assinada, para
+//! desenvolvimento e homologação do próprio ERP.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::async_trait;
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+
+use super::{conteudo, fuso, NAMESPACE, VERSAO};
+use crate::{error::Result, models::Ambiente};
+
+/// Código de status da nota autorizada.
+pub const AUTORIZADA: u16 = 100;
+
+/// Resultado do processamento da nota (`protNFe/infProt`).
+#[derive(Debug, Clone)]
+pub struct Retorno {
+    /// `cStat`: 100 é autorizada; os demais são rejeições.
+    pub status: u16,
+    pub motivo: String,
+    /// Número do protocolo de autorização.
+    pub protocolo: Option<String>,
+    pub recebida_em: DateTime<FixedOffset>,
+    /// Versão do aplicativo da SEFAZ que processou a nota.
+    pub aplicativo: String,
+}
+
+impl Retorno {
+    pub fn autorizada(&self) -> bool {
+        self.status == AUTORIZADA && self.protocolo.is_some()
+    }
+}
+
+#[async_trait]
+pub trait Sefaz: Send + Sync {
+    /// Envia a nota assinada (elemento `NFe`) para autorização síncrona.
+    async fn autorizar(&self, ambiente: Ambiente, chave: &str, nfe: &str) -> Result<Retorno>;
+}
+
+/// SEFAZ simulada: autoriza as notas assinadas e recusa as demais, com
+/// protocolos em sequência no formato da SEFAZ.
+#[derive(Default)]
+pub struct SefazLocal {
+    sequencial: AtomicU64,
+}
+
+#[async_trait]
+impl Sefaz for SefazLocal {
+    async fn autorizar(&self, ambiente: Ambiente, chave: &str, nfe: &str) -> Result<Retorno> {
+        let recebida_em = Utc::now().with_timezone(&fuso());
+        let aplicativo = "SEFAZ-LOCAL".to_string();
+        if conteudo(nfe, "SignatureValue").is_none() {
+            return Ok(Retorno {
+                status: 297,
+                motivo: "Rejeição: Assinatura difere do calculado".to_string(),
+                protocolo: None,
+                recebida_em,
+                aplicativo,
+            });
+        }
+
+        let sequencial = self.sequencial.fetch_add(1, Ordering::Relaxed) + 1;
+        Ok(Retorno {
+            status: AUTORIZADA,
+            motivo: "Autorizado o uso da NF-e".to_string(),
+            protocolo: Some(format!(
+                "{}{}{:02}{:010}",
+                ambiente.codigo(),
+                &chave[..2],
+                recebida_em.year() % 100,
+                sequencial
+            )),
+            recebida_em,
+            aplicativo,
+        })
+    }
+}
+
+/// XML de distribuição (`nfeProc`): a nota assinada com o protocolo de
+/// autorização.
+pub fn nota_processada(nfe: &str, chave: &str, ambiente: Ambiente, retorno: &Retorno) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><nfeProc xmlns=\"{ns}\" versao=\"{versao}\">{nfe}<protNFe versao=\"{versao}\"><infProt><tpAmb>{ambiente}</tpAmb><verAplic>{aplicativo}</verAplic><chNFe>{chave}</chNFe><dhRecbto>{recebida_em}</dhRecbto><nProt>{protocolo}</nProt><digVal>{digest}</digVal><cStat>{status}</cStat><xMotivo>{motivo}</xMotivo></infProt></protNFe></nfeProc>",
+        ns = NAMESPACE,
+        versao = VERSAO,
+        nfe = nfe,
+        ambiente = ambiente.codigo(),
+        aplicativo = retorno.aplicativo,
+        chave = chave,
+        recebida_em = retorno.recebida_em.format("%Y-%m-%dT%H:%M:%S%:z"),
+        protocolo = retorno.protocolo.as_deref().unwrap_or_default(),
+        digest = conteudo(nfe, "DigestValue").unwrap_or_default(),
+        status = retorno.status,
+        motivo = retorno.motivo,
+    )
+}
@@ -0,0 +1,219 @@
+//! Documento OpenAPI 3 da API, em `/api/v1/openapi.json`. É montado a
+//! partir das anotações dos handlers em `routes` e dos tipos de `erp_api`,
+//! e serve para gerar clientes tipados. As convenções comuns a todas as
+//! rotas (autenticação, empresa, idempotência e o corpo dos erros) são
+//! acrescentadas aqui, em [`Convencoes`], em vez de repetidas em cada
+//! handler.
+
+use std::{borrow::Cow, sync::OnceLock};
+
+use axum::Json;
+use utoipa::{
+    openapi::{
+        path::{Operation, ParameterBuilder, ParameterIn},
+        schema::{KnownFormat, ObjectBuilder, SchemaFormat, Type},
+        security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme},
+        ContentBuilder, OpenApi as Documentacao, Ref, RefOr, ResponseBuilder, Schema,
+    },
+    Modify, OpenApi, PartialSchema, ToSchema,
+};
+
+use crate::{error::CorpoErro, routes};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "ERP",
+        description = "API do ERP. Os valores monetários são strings decimais, como \"1234.50\"."
+    ),
+    servers((url = "/")),
+    nest(
+        (path = "/api/v1/auth", api = routes::auth::Api, tags = ["auth"]),
+        (path = "/api/v1/usuarios", api = routes::usuarios::Api, tags = ["usuarios"]),
+        (path = "/api/v1/empresas", api = routes::empresas::Api, tags = ["empresas"]),
+        (path = "/api/v1/auditoria", api = routes::auditoria::Api, tags = ["auditoria"]),
+        (path = "/api/v1/dashboard", api = routes::dashboard::Api, tags = ["dashboard"]),
+        (path = "/api/v1/clientes", api = routes::clientes::Api, tags = ["clientes"]),
+        (path = "/api/v1/produtos", api = routes::produtos::Api, tags = ["produtos"]),
+        (path = "/api/v1/vendas", api = routes::vendas::Api, tags = ["vendas"]),
+        (path = "/api/v1/vendas", api = routes::notas_fiscais::Api, tags = ["notas_fiscais"]),
+        (path = "/api/v1/estoque", api = routes::estoque::Api, tags = ["estoque"]),
+        (path = "/api/v1/fornecedores", api = routes::fornecedores::Api, tags = ["fornecedores"]),
+        (path = "/api/v1/compras", api = routes::compras::Api, tags = ["compras"]),
+        (path = "/api/v1/financeiro", api = routes::financeiro::Api, tags = ["financeiro"]),
+        (path = "/api/v1/financeiro", api = routes::faturas::Api, tags = ["faturas"]),
+        (path = "/api/v1/financeiro", api = routes::conciliacao::Api, tags = ["conciliacao"]),
+        (path = "/api/v1/financeiro/lancamentos", api = routes::lancamentos::Api, tags = ["lancamentos"]),
+        (path = "/api/v1/financeiro/recorrencias", api = routes::recorrencias::Api, tags = ["recorrencias"]),
+        (path = "/api/v1/financeiro/cotacoes", api = routes::cotacoes::Api, tags = ["cotacoes"]),
+        (path = "/api/v1/contabilidade", api = routes::contabilidade::Api, tags = ["contabilidade"]),
+        (path = "/api/v1/pix", api = routes::pix::Api, tags = ["pix"]),
+        (path = "/api/v1/boletos", api = routes::boletos::Api, tags = ["boletos"]),
+        (path = "/api/v1/relatorios", api = routes::relatorios::Api, tags = ["relatorios"]),
+        (path = "/api/v1/bancos", api = routes::bancos::Api, tags = ["bancos"]),
+    ),
+    components(schemas(CorpoErro)),
+    modifiers(&Convencoes)
+)]
+pub struct Documento;
+
+/// `GET /api/v1/openapi.json`. Público, como o login.
+pub async fn documento() -> Json<&'static Documentacao> {
+    static DOCUMENTO: OnceLock<Documentacao> = OnceLock::new();
+    Json(DOCUMENTO.get_or_init(Documento::openapi))
+}
+
+/// Acrescenta a todas as operações o que vale para a API inteira: o token
+/// JWT, o `Idempotency-Key` nos `POST` e as respostas de erro.
+struct Convencoes;
+
+impl Modify for Convencoes {
+    fn modify(&self, openapi: &mut Documentacao) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "token",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        openapi.security = Some(vec![SecurityRequirement::new(
+            "token",
+            Vec::<String>::new(),
+        )]);
+
+        for (caminho, item) in openapi.paths.paths.iter_mut() {
+            let publico = caminho.starts_with("/api/v1/auth");
+            if let Some(operacao) = item.post.as_mut().filter(|_| !publico) {
+                idempotencia(operacao);
+            }
+            for operacao in [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.delete,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                erros(operacao);
+            }
+        }
+    }
+}
+
+fn idempotencia(operacao: &mut Operation) {
+    operacao
+        .parameters
+        .get_or_insert_with(Vec::new)
+        .push(
+            ParameterBuilder::new()
+                .name("Idempotency-Key")
+                .parameter_in(ParameterIn::Header)
+                .schema(Some(String::schema()))
+                .description(Some(
+                    "Repetições com a mesma chave recebem a resposta da primeira, sem refazer a operação",
+                ))
+                .build(),
+        );
+}
+
+fn erros(operacao: &mut Operation) {
+    for (status, descricao) in [
+        (
+            "4XX",
+            "Requisição recusada; `campos` traz os erros de validação (422)",
+        ),
+        ("5XX", "Erro interno"),
+    ] {
+        operacao
+            .responses
+            .responses
+            .entry(status.to_string())
+            .or_insert_with(|| {
+                ResponseBuilder::new()
+                    .description(descricao)
+                    .content(
+                        "application/json",
+                        ContentBuilder::new()
+                            .schema(Some(Ref::from_schema_name(CorpoErro::name())))
+                            .build(),
+                    )
+                    .build()
+                    .into()
+            });
+    }
+}
+
+/// Corpo binário dos downloads (PDF, PNG, CSV, XML).
+pub struct Arquivo;
+
+impl PartialSchema for Arquivo {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Binary)))
+            .into()
+    }
+}
+
+impl ToSchema for Arquivo {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Arquivo")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documento_tem_as_rotas_e_as_convencoes() {
+        let documento = Documento::openapi();
+        let json = serde_json::to_value(&documento).unwrap();
+        let caminhos = &json["paths"];
+
+        assert!(caminhos["/api/v1/clientes/{id}"]["put"].is_object());
+        assert!(caminhos["/api/v1/financeiro/lancamentos"]["get"].is_object());
+        assert!(caminhos["/api/v1/vendas/{id}/nfe"]["post"].is_object());
+
+        let login = &caminhos["/api/v1/auth/login"]["post"];
+        assert_eq!(login["security"], serde_json::json!([{}]));
+        assert!(!json["components"]["securitySchemes"]["token"].is_null());
+
+        let parametros = |operacao: &serde_json::Value| -> Vec<String> {
+            operacao["parameters"]
+                .as_array()
+                .map(|lista| {
+                    lista
+                        .iter()
+                        .map(|p| p["name"].as_str().unwrap().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let criar = &caminhos["/api/v1/clientes"]["post"];
+        assert!(parametros(criar).contains(&"Idempotency-Key".to_string()));
+        assert!(parametros(criar).contains(&"X-Empresa-Id".to_string()));
+        assert!(!parametros(login).contains(&"Idempotency-Key".to_string()));
+        assert!(!criar["responses"]["4XX"].is_null());
+
+        let listar = parametros(&caminhos["/api/v1/clientes"]["get"]);
+        for nome in ["page", "limit", "sort", "q"] {
+            assert!(listar.contains(&nome.to_string()), "falta {}", nome);
+        }
+
+        let texto = json.to_string();
+        // Os ids saem sempre como strings hexadecimais.
+        assert!(!texto.contains("$oid"));
+        let esquemas = &json["components"]["schemas"];
+        for referencia in texto.split("\"#/components/schemas/").skip(1) {
+            let nome = &referencia[..referencia.find('"').unwrap()];
+            assert!(esquemas[nome].is_object(), "esquema {} ausente", nome);
+        }
+    }
+}
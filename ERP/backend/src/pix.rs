@@ -0,0 +1,305 @@
+//! BR Code do PIX: o payload EMV (copia e cola) das cobranças e o QR code
+//! que o representa, conforme o manual do BR Code do Banco Central.
+
+use qrcode::{render::svg, Color, EcLevel, QrCode};
+
+use crate::{
+    error::{AppError, Result},
+    models::Dinheiro,
+    validacao,
+};
+
+const GUI_PIX: &str = "br.gov.bcb.pix";
+/// Código ISO 4217 do real.
+const MOEDA_BRL: &str = "986";
+const MAXIMO_NOME: usize = 25;
+const MAXIMO_CIDADE: usize = 15;
+pub const MAXIMO_TXID: usize = 25;
+/// Txid das cobranças sem identificador próprio e das dinâmicas, cujo
+/// identificador fica no PSP.
+const TXID_AUSENTE: &str = "***";
+
+// Tamanho de cada módulo e da margem (quatro módulos) do QR code, em pixels.
+const PIXELS_POR_MODULO: u32 = 8;
+const MARGEM_MODULOS: u32 = 4;
+
+/// Dados de uma cobrança para o BR Code.
+pub struct BrCode<'a> {
+    pub chave: &'a str,
+    /// Nome do recebedor.
+    pub nome: &'a str,
+    pub cidade: &'a str,
+    pub valor: Dinheiro,
+    pub txid: Option<&'a str>,
+    /// URL do payload no PSP (sem `https://`); quando presente, o BR Code é
+    /// dinâmico e a chave e o valor vêm do PSP.
+    pub location: Option<&'a str>,
+}
+
+impl BrCode<'_> {
+    pub fn payload(&self) -> String {
+        let conta = match self.location {
+            Some(location) => campo("00", GUI_PIX) + &campo("25", location),
+            None => campo("00", GUI_PIX) + &campo("01", self.chave),
+        };
+
+        let mut payload = campo("00", "01");
+        if self.location.is_some() {
+            // 12: o QR code só pode ser pago uma vez.
+            payload += &campo("01", "12");
+        }
+        payload += &campo("26", &conta);
+        payload += &campo("52", "0000");
+        payload += &campo("53", MOEDA_BRL);
+        if self.location.is_none() {
+            payload += &campo("54", &self.valor.to_string());
+        }
+        payload += &campo("58", "BR");
+        payload += &campo("59", &texto_emv(self.nome, MAXIMO_NOME));
+        payload += &campo("60", &texto_emv(self.cidade, MAXIMO_CIDADE));
+
+        let txid = match (self.location, self.txid) {
+            (None, Some(txid)) => txid,
+            _ => TXID_AUSENTE,
+        };
+        payload += &campo("62", &campo("05", txid));
+
+        // O CRC cobre o payload inteiro, inclusive o id e o tamanho do campo 63.
+        payload += "6304";
+        let crc = crc16(payload.as_bytes());
+        payload + &format!("{:04X}", crc)
+    }
+}
+
+/// Campo EMV: id, tamanho com dois dígitos e valor.
+fn campo(id: &str, valor: &str) -> String {
+    format!("{}{:02}{}", id, valor.len(), valor)
+}
+
+/// CRC-16/CCITT-FALSE (polinômio 0x1021, valor inicial 0xFFFF).
+pub fn crc16(dados: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in dados {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Nome e cidade aceitam só ASCII: os acentos são removidos e o texto é
+/// cortado no tamanho máximo do campo.
+fn texto_emv(texto: &str, maximo: usize) -> String {
+    validacao::sem_acentos(texto.trim())
+        .chars()
+        .take(maximo)
+        .collect()
+}
+
+/// Chave no formato em que o DICT a registra: CPF e CNPJ só com os
+/// dígitos, e-mail e chave aleatória em minúsculas, telefone como
+/// `+5511999998888`. `None` se a chave não for de nenhum dos tipos.
+pub fn normalizar_chave(chave: &str) -> Option<String> {
+    let chave = chave.trim();
+
+    if chave.contains('@') {
+        let email = chave.to_lowercase();
+        let valido = email.len() <= 77
+            && email
+                .split_once('@')
+                .is_some_and(|(usuario, dominio)| !usuario.is_empty() && dominio.contains('.'))
+            && !email.contains(char::is_whitespace);
+        return valido.then_some(email);
+    }
+
+    if chave.starts_with('+') {
+        let digitos = validacao::somente_digitos(chave);
+        let valido = chave
+            .chars()
+            .skip(1)
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))
+            && (12..=14).contains(&digitos.len());
+        return valido.then(|| format!("+{}", digitos));
+    }
+
+    if chave.len() == 36 && chave.chars().filter(|&c| c == '-').count() == 4 {
+        let aleatoria = chave.to_lowercase();
+        let valida = aleatoria.split('-').map(str::len).eq([8, 4, 4, 4, 12])
+            && aleatoria.chars().all(|c| c == '-' || c.is_ascii_hexdigit());
+        return valida.then_some(aleatoria);
+    }
+
+    if validacao::cpf_valido(chave) {
+        return Some(validacao::somente_digitos(chave));
+    }
+    if validacao::cnpj_valido(chave) {
+        return Some(
+            chave
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_uppercase(),
+        );
+    }
+    None
+}
+
+/// Txid de cobrança estática: até 25 letras e algarismos.
+pub fn txid_valido(txid: &str) -> bool {
+    !txid.is_empty() && txid.len() <= MAXIMO_TXID && txid.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn qrcode(payload: &str) -> Result<QrCode> {
+    QrCode::with_error_correction_level(payload, EcLevel::M)
+        .map_err(|e| AppError::Internal(format!("Erro ao gerar o QR code: {}", e)))
+}
+
+pub fn qrcode_svg(payload: &str) -> Result<String> {
+    Ok(qrcode(payload)?
+        .render::<svg::Color>()
+        .module_dimensions(PIXELS_POR_MODULO, PIXELS_POR_MODULO)
+        .build())
+}
+
+/// PNG em tons de cinza, com margem branca.
+pub fn qrcode_png(payload: &str) -> Result<Vec<u8>> {
+    let codigo = qrcode(payload)?;
+    let modulos = codigo.width() as u32;
+    let cores = codigo.to_colors();
+    let lado = (modulos + 2 * MARGEM_MODULOS) * PIXELS_POR_MODULO;
+
+    let mut pixels = vec![255u8; (lado * lado) as usize];
+    for (indice, cor) in cores.iter().enumerate() {
+        if *cor != Color::Dark {
+            continue;
+        }
+        let coluna = indice as u32 % modulos + MARGEM_MODULOS;
+        let linha = indice as u32 / modulos + MARGEM_MODULOS;
+        for y in linha * PIXELS_POR_MODULO..(linha + 1) * PIXELS_POR_MODULO {
+            let inicio = (y * lado + coluna * PIXELS_POR_MODULO) as usize;
+            pixels[inicio..inicio + PIXELS_POR_MODULO as usize].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut codificador = png::Encoder::new(&mut png, lado, lado);
+    codificador.set_color(png::ColorType::Grayscale);
+    codificador.set_depth(png::BitDepth::Eight);
+    codificador
+        .write_header()
+        .and_then(|mut escritor| escritor.write_image_data(&pixels))
+        .map_err(|e| AppError::Internal(format!("Erro ao gerar o PNG: {}", e)))?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAVE: &str = "123e4567-e12b-12d1-a456-426655440000";
+
+    fn cobranca(valor: &str) -> BrCode<'static> {
+        BrCode {
+            chave: CHAVE,
+            nome: "Fulano de Tal",
+            cidade: "BRASILIA",
+            valor: valor.parse().unwrap(),
+            txid: None,
+            location: None,
+        }
+    }
+
+    #[test]
+    fn crc_do_exemplo_do_manual() {
+        // Exemplo de BR Code estático do manual do Banco Central.
+        let payload = "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+            5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***6304";
+        assert_eq!(crc16(payload.as_bytes()), 0x1D3D);
+    }
+
+    #[test]
+    fn payload_estatico() {
+        assert_eq!(
+            cobranca("10.00").payload(),
+            "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-426655440000\
+             520400005303986540510.005802BR5913Fulano de Tal6008BRASILIA62070503***6304288F"
+        );
+
+        let com_txid = BrCode {
+            txid: Some("PEDIDO42"),
+            nome: "João da Silva Comércio e Serviços",
+            cidade: "São Paulo",
+            ..cobranca("1.50")
+        }
+        .payload();
+        assert!(com_txid.contains("5925Joao da Silva Comercio e "));
+        assert!(com_txid.contains("6009Sao Paulo"));
+        assert!(com_txid.contains("62120508PEDIDO42"));
+        let (corpo, crc) = com_txid.split_at(com_txid.len() - 4);
+        assert_eq!(crc, format!("{:04X}", crc16(corpo.as_bytes())));
+    }
+
+    #[test]
+    fn payload_dinamico() {
+        let payload = BrCode {
+            location: Some("pix.exemplo.com/cob/1a"),
+            txid: Some("ignorado"),
+            ..cobranca("99.00")
+        }
+        .payload();
+        assert_eq!(
+            payload,
+            "00020101021226440014br.gov.bcb.pix2522pix.exemplo.com/cob/1a\
+             5204000053039865802BR5913Fulano de Tal6008BRASILIA62070503***6304429C"
+        );
+    }
+
+    #[test]
+    fn normaliza_chaves() {
+        assert_eq!(
+            normalizar_chave(" Fulano@Exemplo.com ").as_deref(),
+            Some("fulano@exemplo.com")
+        );
+        assert_eq!(
+            normalizar_chave("+55 (11) 99999-8888").as_deref(),
+            Some("+5511999998888")
+        );
+        assert_eq!(
+            normalizar_chave("123E4567-E12B-12D1-A456-426655440000").as_deref(),
+            Some(CHAVE)
+        );
+        assert_eq!(
+            normalizar_chave("529.982.247-25").as_deref(),
+            Some("52998224725")
+        );
+        assert_eq!(
+            normalizar_chave("11.222.333/0001-81").as_deref(),
+            Some("11222333000181")
+        );
+        assert_eq!(
+            normalizar_chave("12.abc.345/01de-35").as_deref(),
+            Some("12ABC34501DE35")
+        );
+        assert_eq!(normalizar_chave("sem-tipo"), None);
+        assert_eq!(normalizar_chave("+55 11 abc"), None);
+    }
+
+    #[test]
+    fn chave_cnpj_fora_do_ascii_e_recusada() {
+        assert_eq!(normalizar_chave("12345678901é1"), None);
+        assert_eq!(normalizar_chave("12.345.678/9012-é1"), None);
+    }
+
+    #[test]
+    fn txid() {
+        assert!(txid_valido("PEDIDO42"));
+        assert!(!txid_valido(""));
+        assert!(!txid_valido("PEDIDO-42"));
+        assert!(!txid_valido(&"A".repeat(26)));
+    }
+}
@@ -0,0 +1,217 @@
+//! Geração das ocorrências das recorrências do financeiro. As ocorrências
+//! são geradas em ordem, até um horizonte à frente de hoje, como
+//! lançamentos ou, nas recorrências em cartão, como compras na fatura. Uma
+//! tarefa em segundo plano mantém o horizonte em dia; os handlers geram
+//! além dele quando precisam alterar uma ocorrência ainda não gerada.
+
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    auditoria::Auditor,
+    cartoes, contabilidade,
+    error::{AppError, Result},
+    models::{Cartao, CompraCartao, Lancamento, Recorrencia, StatusLancamento},
+    repositorio::Repositorios,
+};
+
+const HORIZONTE_PADRAO_DIAS: u64 = 60;
+
+/// Intervalo entre as execuções da geração automática.
+const INTERVALO: Duration = Duration::from_secs(60 * 60);
+
+/// Ocorrências geradas de uma só vez, no máximo.
+const MAXIMO_POR_VEZ: usize = 400;
+
+/// Até onde gerar as ocorrências.
+#[derive(Debug, Clone, Copy)]
+pub enum Limite {
+    /// Ocorrências com data até a informada.
+    Data(NaiveDate),
+    /// Ocorrências até a de número informado.
+    Ocorrencia(u32),
+}
+
+/// Data até a qual as ocorrências ficam geradas: hoje mais
+/// `RECORRENCIAS_HORIZONTE_DIAS` (padrão: 60).
+pub fn horizonte() -> NaiveDate {
+    let dias = std::env::var("RECORRENCIAS_HORIZONTE_DIAS")
+        .ok()
+        .and_then(|valor| valor.parse::<u64>().ok())
+        .unwrap_or(HORIZONTE_PADRAO_DIAS);
+    let hoje = Utc::now().date_naive();
+    hoje.checked_add_days(Days::new(dias)).unwrap_or(hoje)
+}
+
+/// Gera as ocorrências que faltam até o limite. O novo número de geradas é
+/// gravado antes das ocorrências, de modo que duas gerações simultâneas
+/// nunca criem a mesma ocorrência.
+pub async fn gerar(
+    repos: &Repositorios,
+    auditor: &Auditor,
+    recorrencia: &mut Recorrencia,
+    limite: Limite,
+) -> Result<()> {
+    if !recorrencia.ativa {
+        return Ok(());
+    }
+
+    let lidas = recorrencia.geradas;
+    let mut novas = Vec::new();
+    let mut numero = lidas + 1;
+    while let Some(data) = recorrencia.data(numero) {
+        let incluir = match limite {
+            Limite::Data(ate) => data <= ate,
+            Limite::Ocorrencia(ate) => numero <= ate,
+        };
+        if !incluir {
+            break;
+        }
+        if novas.len() == MAXIMO_POR_VEZ {
+            return Err(AppError::BadRequest(format!(
+                "A ocorrência está além das próximas {} da recorrência",
+                MAXIMO_POR_VEZ
+            )));
+        }
+        novas.push((numero, data));
+        numero += 1;
+    }
+    if novas.is_empty() {
+        return Ok(());
+    }
+
+    recorrencia.geradas = numero - 1;
+    recorrencia.updated_at = Utc::now();
+    if !repos
+        .recorrencias
+        .salvar_geradas(recorrencia, lidas)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "A recorrência foi alterada por outra operação".to_string(),
+        ));
+    }
+
+    match recorrencia.cartao_id {
+        Some(cartao_id) => {
+            let cartao = repos
+                .cartoes
+                .buscar(recorrencia.empresa_id, cartao_id)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            let compras = novas
+                .into_iter()
+                .map(|(numero, data)| compra(recorrencia, &cartao, numero, data))
+                .collect();
+            for criada in repos.compras_cartao.criar_varias(compras).await? {
+                auditor.criacao(&criada).await?;
+                contabilidade::registrar_compra_cartao(repos, &criada, criada.valor).await?;
+            }
+        }
+        None => {
+            let lancamentos = novas
+                .into_iter()
+                .map(|(numero, data)| lancamento(recorrencia, numero, data))
+                .collect();
+            for criado in repos.lancamentos.criar_varios(lancamentos).await? {
+                auditor.criacao(&criado).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ocorrência de uma recorrência sem cartão. As parcelas de um
+/// parcelamento ficam no grupo da recorrência.
+pub fn lancamento(recorrencia: &Recorrencia, numero: u32, vencimento: NaiveDate) -> Lancamento {
+    let (parcela, total_parcelas) = recorrencia.parcela(numero);
+    let now = Utc::now();
+    Lancamento {
+        id: None,
+        empresa_id: recorrencia.empresa_id,
+        tipo: recorrencia.tipo,
+        descricao: recorrencia.descricao_da(numero),
+        categoria: recorrencia.categoria.clone(),
+        valor: recorrencia.valor,
+        moeda: recorrencia.moeda,
+        vencimento,
+        cliente_id: recorrencia.cliente_id,
+        contraparte: recorrencia.contraparte.clone(),
+        grupo_id: match recorrencia.ocorrencias {
+            Some(_) => recorrencia.id.unwrap_or_default(),
+            None => ObjectId::new(),
+        },
+        parcela,
+        total_parcelas,
+        status: StatusLancamento::Aberto,
+        conta_id: None,
+        data_pagamento: None,
+        valor_pago: None,
+        recorrencia_id: recorrencia.id,
+        ocorrencia: Some(numero),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Ocorrência de uma recorrência em cartão: uma compra à vista, na fatura
+/// da data da ocorrência.
+pub fn compra(
+    recorrencia: &Recorrencia,
+    cartao: &Cartao,
+    numero: u32,
+    data_compra: NaiveDate,
+) -> CompraCartao {
+    CompraCartao {
+        id: None,
+        empresa_id: recorrencia.empresa_id,
+        cartao_id: cartao.id.unwrap_or_default(),
+        compra_id: ObjectId::new(),
+        descricao: recorrencia.descricao_da(numero),
+        categoria: recorrencia.categoria.clone(),
+        valor: recorrencia.valor,
+        data_compra,
+        parcela: 1,
+        total_parcelas: 1,
+        competencia: cartoes::formatar_competencia(cartoes::competencia_da_compra(
+            data_compra,
+            cartao.dia_fechamento(),
+        )),
+        lancamento_id: None,
+        recorrencia_id: recorrencia.id,
+        ocorrencia: Some(numero),
+        created_at: Utc::now(),
+    }
+}
+
+/// Roda a geração automática a cada hora, em segundo plano.
+pub fn agendar(repos: Repositorios) {
+    tokio::spawn(async move {
+        let mut intervalo = tokio::time::interval(INTERVALO);
+        loop {
+            intervalo.tick().await;
+            if let Err(e) = gerar_todas(&repos).await {
+                tracing::error!("❌ Erro ao gerar as ocorrências das recorrências: {}", e);
+            }
+        }
+    });
+}
+
+/// Gera, em todas as empresas, as ocorrências até o horizonte. Uma
+/// recorrência com problema não impede as demais.
+async fn gerar_todas(repos: &Repositorios) -> Result<()> {
+    let ate = horizonte();
+    for mut recorrencia in repos.recorrencias.ativas().await? {
+        let auditor = Auditor::sistema(repos.clone(), recorrencia.empresa_id, "recorrencias");
+        if let Err(e) = gerar(repos, &auditor, &mut recorrencia, Limite::Data(ate)).await {
+            tracing::warn!(
+                "Recorrência {} não gerada: {}",
+                recorrencia.id.map(|id| id.to_hex()).unwrap_or_default(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,351 @@
+//! Exportação dos relatórios em CSV, XLSX e PDF. Todo relatório vira um
+//! [`Quadro`] (título, colunas e linhas) e cada formato o desenha.
+
+use chrono::{Datelike, NaiveDate};
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    error::{AppError, Result},
+    models::Dinheiro,
+};
+
+// Medidas do PDF, em milímetros; a fonte, em pontos.
+const PAGINA_LARGURA: Mm = Mm(297.0);
+const PAGINA_ALTURA: Mm = Mm(210.0);
+const MARGEM: f32 = 12.0;
+const ALTURA_LINHA: f32 = 5.0;
+const ESPACO_CELULA: f32 = 1.0;
+const TAMANHO_FONTE: f32 = 8.0;
+const MM_POR_PONTO: f32 = 25.4 / 72.0;
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Formato {
+    #[default]
+    Json,
+    Csv,
+    Xlsx,
+    Pdf,
+}
+
+impl Formato {
+    pub fn extensao(&self) -> &'static str {
+        match self {
+            Formato::Json => "json",
+            Formato::Csv => "csv",
+            Formato::Xlsx => "xlsx",
+            Formato::Pdf => "pdf",
+        }
+    }
+
+    pub fn tipo_conteudo(&self) -> &'static str {
+        match self {
+            Formato::Json => "application/json",
+            Formato::Csv => "text/csv; charset=utf-8",
+            Formato::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            Formato::Pdf => "application/pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Celula {
+    Texto(String),
+    Dinheiro(Dinheiro),
+    Inteiro(i64),
+    Data(NaiveDate),
+    Vazia,
+}
+
+impl Celula {
+    /// Valores numéricos são alinhados à direita no PDF.
+    fn numerica(&self) -> bool {
+        matches!(self, Celula::Dinheiro(_) | Celula::Inteiro(_))
+    }
+
+    /// Texto no padrão brasileiro (`1.234,56`, `31/12/2025`).
+    fn texto(&self) -> String {
+        match self {
+            Celula::Texto(texto) => texto.clone(),
+            Celula::Dinheiro(valor) => valor.formatado(),
+            Celula::Inteiro(valor) => valor.to_string(),
+            Celula::Data(data) => data.format("%d/%m/%Y").to_string(),
+            Celula::Vazia => String::new(),
+        }
+    }
+}
+
+impl From<&str> for Celula {
+    fn from(texto: &str) -> Self {
+        Celula::Texto(texto.to_string())
+    }
+}
+
+impl From<String> for Celula {
+    fn from(texto: String) -> Self {
+        Celula::Texto(texto)
+    }
+}
+
+impl From<Dinheiro> for Celula {
+    fn from(valor: Dinheiro) -> Self {
+        Celula::Dinheiro(valor)
+    }
+}
+
+impl From<i64> for Celula {
+    fn from(valor: i64) -> Self {
+        Celula::Inteiro(valor)
+    }
+}
+
+impl From<NaiveDate> for Celula {
+    fn from(data: NaiveDate) -> Self {
+        Celula::Data(data)
+    }
+}
+
+/// Relatório em forma de tabela, pronto para exportação.
+#[derive(Debug, Clone)]
+pub struct Quadro {
+    pub titulo: String,
+    /// Período ou data-base do relatório.
+    pub subtitulo: String,
+    pub colunas: Vec<String>,
+    pub linhas: Vec<Vec<Celula>>,
+}
+
+impl Quadro {
+    pub fn new(titulo: &str, subtitulo: String, colunas: &[&str]) -> Self {
+        Self {
+            titulo: titulo.to_string(),
+            subtitulo,
+            colunas: colunas.iter().map(|coluna| coluna.to_string()).collect(),
+            linhas: Vec::new(),
+        }
+    }
+
+    pub fn linha(&mut self, celulas: Vec<Celula>) {
+        self.linhas.push(celulas);
+    }
+
+    pub fn exportar(&self, formato: Formato) -> Result<Vec<u8>> {
+        match formato {
+            Formato::Json => Err(AppError::Internal(
+                "Relatórios em JSON não passam pela exportação".to_string(),
+            )),
+            Formato::Csv => Ok(self.csv()),
+            Formato::Xlsx => self.xlsx(),
+            Formato::Pdf => self.pdf(),
+        }
+    }
+
+    /// CSV no formato que o Excel em português abre direto: separador `;`,
+    /// vírgula decimal e BOM UTF-8.
+    fn csv(&self) -> Vec<u8> {
+        let mut csv = String::from("\u{feff}");
+        let cabecalho: Vec<String> = self.colunas.iter().map(|c| campo_csv(c)).collect();
+        csv.push_str(&cabecalho.join(";"));
+        csv.push_str("\r\n");
+
+        for linha in &self.linhas {
+            let campos: Vec<String> = linha
+                .iter()
+                .map(|celula| match celula {
+                    Celula::Dinheiro(valor) => valor.to_string().replace('.', ","),
+                    outra => campo_csv(&outra.texto()),
+                })
+                .collect();
+            csv.push_str(&campos.join(";"));
+            csv.push_str("\r\n");
+        }
+        csv.into_bytes()
+    }
+
+    fn xlsx(&self) -> Result<Vec<u8>> {
+        let mut planilha = Workbook::new();
+        let folha = planilha.add_worksheet();
+        let negrito = Format::new().set_bold();
+        let moeda = Format::new().set_num_format("#,##0.00");
+        let data = Format::new().set_num_format("dd/mm/yyyy");
+
+        folha
+            .write_string_with_format(0, 0, &self.titulo, &negrito)
+            .map_err(erro_xlsx)?;
+        folha
+            .write_string(1, 0, &self.subtitulo)
+            .map_err(erro_xlsx)?;
+        for (coluna, nome) in self.colunas.iter().enumerate() {
+            folha
+                .write_string_with_format(3, coluna as u16, nome, &negrito)
+                .map_err(erro_xlsx)?;
+        }
+
+        for (indice, linha) in self.linhas.iter().enumerate() {
+            let linha_xlsx = 4 + indice as u32;
+            for (coluna, celula) in linha.iter().enumerate() {
+                let coluna = coluna as u16;
+                match celula {
+                    Celula::Texto(texto) => folha.write_string(linha_xlsx, coluna, texto),
+                    // Duas casas decimais cabem exatas no número da planilha.
+                    Celula::Dinheiro(valor) => folha.write_number_with_format(
+                        linha_xlsx,
+                        coluna,
+                        valor.aproximado(),
+                        &moeda,
+                    ),
+                    Celula::Inteiro(valor) => folha.write_number(linha_xlsx, coluna, *valor as f64),
+                    Celula::Data(dia) => {
+                        let dia = ExcelDateTime::from_ymd(
+                            dia.year() as u16,
+                            dia.month() as u8,
+                            dia.day() as u8,
+                        )
+                        .map_err(erro_xlsx)?;
+                        folha.write_date_with_format(linha_xlsx, coluna, &dia, &data)
+                    }
+                    Celula::Vazia => continue,
+                }
+                .map_err(erro_xlsx)?;
+            }
+        }
+        folha.autofit();
+
+        planilha.save_to_buffer().map_err(erro_xlsx)
+    }
+
+    /// A4 paisagem, com as colunas repetidas no topo de cada página.
+    fn pdf(&self) -> Result<Vec<u8>> {
+        let (documento, pagina, camada) =
+            PdfDocument::new(&self.titulo, PAGINA_LARGURA, PAGINA_ALTURA, "Relatório");
+        let fonte = documento
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(erro_pdf)?;
+        let negrito = documento
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(erro_pdf)?;
+
+        let larguras = self.larguras_pdf();
+        let mut camada = documento.get_page(pagina).get_layer(camada);
+        let mut y = PAGINA_ALTURA.0 - MARGEM;
+
+        camada.use_text(&self.titulo, 14.0, Mm(MARGEM), Mm(y - 5.0), &negrito);
+        camada.use_text(&self.subtitulo, 9.0, Mm(MARGEM), Mm(y - 11.0), &fonte);
+        y -= 20.0;
+
+        let cabecalho: Vec<Celula> = self
+            .colunas
+            .iter()
+            .map(|coluna| Celula::Texto(coluna.clone()))
+            .collect();
+        linha_pdf(&camada, &cabecalho, &larguras, y, &negrito);
+        y -= ALTURA_LINHA;
+
+        for linha in &self.linhas {
+            if y < MARGEM {
+                let (pagina, nova) = documento.add_page(PAGINA_LARGURA, PAGINA_ALTURA, "Relatório");
+                camada = documento.get_page(pagina).get_layer(nova);
+                y = PAGINA_ALTURA.0 - MARGEM - ALTURA_LINHA;
+                linha_pdf(&camada, &cabecalho, &larguras, y, &negrito);
+                y -= ALTURA_LINHA;
+            }
+            linha_pdf(&camada, linha, &larguras, y, &fonte);
+            y -= ALTURA_LINHA;
+        }
+
+        documento.save_to_bytes().map_err(erro_pdf)
+    }
+
+    /// Largura de cada coluna pelo maior conteúdo, reduzidas na mesma
+    /// proporção quando não cabem na página.
+    fn larguras_pdf(&self) -> Vec<f32> {
+        let mut larguras: Vec<f32> = self
+            .colunas
+            .iter()
+            .map(|coluna| largura_texto(coluna) + 2.0 * ESPACO_CELULA)
+            .collect();
+        for linha in &self.linhas {
+            for (coluna, celula) in linha.iter().enumerate().take(larguras.len()) {
+                let largura = largura_texto(&celula.texto()) + 2.0 * ESPACO_CELULA;
+                larguras[coluna] = larguras[coluna].max(largura);
+            }
+        }
+
+        let disponivel = PAGINA_LARGURA.0 - 2.0 * MARGEM;
+        let total: f32 = larguras.iter().sum();
+        if total > disponivel {
+            for largura in &mut larguras {
+                *largura *= disponivel / total;
+            }
+        }
+        larguras
+    }
+}
+
+fn linha_pdf(
+    camada: &PdfLayerReference,
+    celulas: &[Celula],
+    larguras: &[f32],
+    y: f32,
+    fonte: &IndirectFontRef,
+) {
+    let mut x = MARGEM;
+    for (celula, largura) in celulas.iter().zip(larguras) {
+        let texto = truncar(&celula.texto(), largura - 2.0 * ESPACO_CELULA);
+        let inicio = if celula.numerica() {
+            x + largura - ESPACO_CELULA - largura_texto(&texto)
+        } else {
+            x + ESPACO_CELULA
+        };
+        camada.use_text(texto, TAMANHO_FONTE, Mm(inicio), Mm(y), fonte);
+        x += largura;
+    }
+}
+
+/// Largura aproximada, em milímetros, do texto na Helvetica do PDF. Os
+/// algarismos e a pontuação dos números têm a largura exata da fonte, o que
+/// basta para alinhar os valores à direita.
+fn largura_texto(texto: &str) -> f32 {
+    let milesimos: u32 = texto
+        .chars()
+        .map(|caractere| match caractere {
+            '0'..='9' | '$' => 556,
+            ' ' | ',' | '.' | '/' | ':' | 'i' | 'l' | 'j' | 'f' | 't' => 278,
+            '-' | '(' | ')' | 'r' => 333,
+            'm' | 'w' | 'M' | 'W' => 833,
+            caractere if caractere.is_uppercase() => 667,
+            _ => 556,
+        })
+        .sum();
+    milesimos as f32 / 1000.0 * TAMANHO_FONTE * MM_POR_PONTO
+}
+
+fn truncar(texto: &str, largura: f32) -> String {
+    if largura_texto(texto) <= largura {
+        return texto.to_string();
+    }
+    let mut truncado: String = texto.to_string();
+    while !truncado.is_empty() && largura_texto(&format!("{}...", truncado)) > largura {
+        truncado.pop();
+    }
+    format!("{}...", truncado)
+}
+
+fn campo_csv(texto: &str) -> String {
+    if texto.contains([';', '"', '\n', '\r']) {
+        format!("\"{}\"", texto.replace('"', "\"\""))
+    } else {
+        texto.to_string()
+    }
+}
+
+fn erro_xlsx(erro: rust_xlsxwriter::XlsxError) -> AppError {
+    AppError::Internal(format!("Erro ao gerar a planilha: {}", erro))
+}
+
+fn erro_pdf(erro: printpdf::Error) -> AppError {
+    AppError::Internal(format!("Erro ao gerar o PDF: {}", erro))
+}
@@ -0,0 +1,728 @@
+//! Relatórios financeiros: fluxo de caixa, DRE e aging das contas a pagar e
+//! a receber. Cada relatório sai em JSON ou, pelo seu [`Quadro`], em CSV,
+//! XLSX e PDF.
+//!
+//! Os valores saem na moeda pedida, em reais por padrão. Os da
+//! contabilidade, que é em reais, são convertidos pela cotação do fim de
+//! cada mês; os lançamentos em aberto, pela da data-base.
+
+mod exportar;
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub use exportar::{Celula, Formato, Quadro};
+
+use crate::{
+    cambio::Conversor,
+    contabilidade,
+    error::{AppError, Result},
+    models::*,
+    repositorio::{FiltroLancamentos, Repositorios},
+};
+
+/// Maior período aceito pelos relatórios mensais.
+const MAXIMO_MESES: usize = 36;
+
+/// Relatório que pode ser exportado.
+pub trait Relatorio: Serialize {
+    /// Início do nome do arquivo exportado.
+    const ARQUIVO: &'static str;
+
+    fn quadro(&self) -> Quadro;
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MesFluxo {
+    /// `AAAA-MM`.
+    pub mes: String,
+    pub entradas: Dinheiro,
+    pub saidas: Dinheiro,
+    /// Saldo ao fim do mês.
+    pub saldo: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FluxoConta {
+    pub conta_id: String,
+    pub conta: String,
+    /// Saldo no dia anterior ao início do período.
+    pub saldo_inicial: Dinheiro,
+    pub meses: Vec<MesFluxo>,
+    pub saldo_final: Dinheiro,
+}
+
+/// Projeção consolidada de todas as contas: parte do saldo atual e soma os
+/// lançamentos em aberto pelo vencimento. Os vencidos entram no mês atual.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FluxoProjetado {
+    pub saldo_inicial: Dinheiro,
+    pub meses: Vec<MesFluxo>,
+    pub saldo_final: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FluxoCaixa {
+    pub de: NaiveDate,
+    pub ate: NaiveDate,
+    /// Moeda dos valores.
+    pub moeda: Moeda,
+    /// Movimento de cada conta bancária, pelas partidas contábeis.
+    pub realizado: Vec<FluxoConta>,
+    /// Ausente quando o período já terminou ou o relatório é de uma só
+    /// conta, já que os lançamentos em aberto não têm conta definida.
+    pub projetado: Option<FluxoProjetado>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ValoresMensais {
+    /// Na ordem de [`Dre::meses`].
+    pub meses: Vec<Dinheiro>,
+    pub total: Dinheiro,
+}
+
+impl ValoresMensais {
+    fn new(meses: Vec<Dinheiro>) -> Self {
+        let total = meses.iter().copied().sum();
+        Self { meses, total }
+    }
+
+    fn somar<'a>(quantidade: usize, valores: impl Iterator<Item = &'a ValoresMensais>) -> Self {
+        let mut meses = vec![Dinheiro::ZERO; quantidade];
+        for valor in valores {
+            for (mes, parcela) in meses.iter_mut().zip(&valor.meses) {
+                *mes += *parcela;
+            }
+        }
+        Self::new(meses)
+    }
+
+    fn menos(&self, outro: &ValoresMensais) -> Self {
+        Self::new(
+            self.meses
+                .iter()
+                .zip(&outro.meses)
+                .map(|(a, b)| *a - *b)
+                .collect(),
+        )
+    }
+
+    fn celulas(&self) -> Vec<Celula> {
+        self.meses
+            .iter()
+            .chain([&self.total])
+            .map(|valor| Celula::Dinheiro(*valor))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinhaDre {
+    pub codigo: String,
+    pub nome: String,
+    #[serde(flatten)]
+    pub valores: ValoresMensais,
+}
+
+/// DRE simplificada: receitas e despesas das contas analíticas, por mês.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Dre {
+    pub de: NaiveDate,
+    pub ate: NaiveDate,
+    /// Moeda dos valores.
+    pub moeda: Moeda,
+    /// `AAAA-MM`.
+    pub meses: Vec<String>,
+    pub receitas: Vec<LinhaDre>,
+    pub despesas: Vec<LinhaDre>,
+    pub total_receitas: ValoresMensais,
+    pub total_despesas: ValoresMensais,
+    pub resultado: ValoresMensais,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum FaixaAging {
+    #[serde(rename = "A_VENCER")]
+    AVencer,
+    #[serde(rename = "VENCIDO_1_30")]
+    Ate30,
+    #[serde(rename = "VENCIDO_31_60")]
+    Ate60,
+    #[serde(rename = "VENCIDO_61_90")]
+    Ate90,
+    #[serde(rename = "VENCIDO_MAIS_90")]
+    Acima90,
+}
+
+impl FaixaAging {
+    const TODAS: [FaixaAging; 5] = [
+        FaixaAging::AVencer,
+        FaixaAging::Ate30,
+        FaixaAging::Ate60,
+        FaixaAging::Ate90,
+        FaixaAging::Acima90,
+    ];
+
+    fn do_atraso(dias: i64) -> Self {
+        match dias {
+            ..=0 => FaixaAging::AVencer,
+            1..=30 => FaixaAging::Ate30,
+            31..=60 => FaixaAging::Ate60,
+            61..=90 => FaixaAging::Ate90,
+            _ => FaixaAging::Acima90,
+        }
+    }
+
+    pub fn nome(&self) -> &'static str {
+        match self {
+            FaixaAging::AVencer => "A vencer",
+            FaixaAging::Ate30 => "Vencido de 1 a 30 dias",
+            FaixaAging::Ate60 => "Vencido de 31 a 60 dias",
+            FaixaAging::Ate90 => "Vencido de 61 a 90 dias",
+            FaixaAging::Acima90 => "Vencido há mais de 90 dias",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotalFaixa {
+    pub faixa: FaixaAging,
+    pub quantidade: u64,
+    pub valor: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ItemAging {
+    pub lancamento_id: Option<String>,
+    pub descricao: String,
+    pub contraparte: Option<String>,
+    pub vencimento: NaiveDate,
+    /// Negativo para os que ainda vão vencer.
+    pub dias_atraso: i64,
+    pub faixa: FaixaAging,
+    /// Na moeda do relatório, pela cotação da data-base.
+    pub valor: Dinheiro,
+    pub moeda_original: Moeda,
+    pub valor_original: Dinheiro,
+}
+
+/// Lançamentos em aberto agrupados pelo atraso na data-base.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Aging {
+    pub tipo: TipoLancamento,
+    pub data_base: NaiveDate,
+    /// Moeda dos valores.
+    pub moeda: Moeda,
+    pub faixas: Vec<TotalFaixa>,
+    pub total: Dinheiro,
+    pub itens: Vec<ItemAging>,
+}
+
+pub async fn fluxo_caixa(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    de: NaiveDate,
+    ate: NaiveDate,
+    conta_id: Option<ObjectId>,
+    moeda: Moeda,
+) -> Result<FluxoCaixa> {
+    let meses = meses(de, ate)?;
+    let fechamentos: Vec<NaiveDate> = meses.iter().map(|mes| fechamento(mes, ate)).collect();
+    let mut conversor = Conversor::new(repos, empresa_id, moeda);
+
+    let mut contas = repos.contas.listar(empresa_id).await?;
+    if let Some(conta_id) = conta_id {
+        contas.retain(|conta| conta.id == Some(conta_id));
+        if contas.is_empty() {
+            return Err(AppError::NotFound);
+        }
+    }
+
+    let plano = contabilidade::plano(repos, empresa_id).await?;
+    let saldos_iniciais = contabilidade::saldos_bancarios(repos, empresa_id, de.pred_opt()).await?;
+    let movimento = repos.partidas.movimento_mensal(empresa_id, de, ate).await?;
+    let sem_movimento = BTreeMap::new();
+
+    let mut realizado = Vec::with_capacity(contas.len());
+    for conta in &contas {
+        let Some(id) = conta.id else {
+            continue;
+        };
+        let contabil = plano
+            .contas()
+            .iter()
+            .find(|contabil| contabil.conta_bancaria_id == Some(id))
+            .and_then(|contabil| contabil.id);
+        let movimento = contabil
+            .and_then(|contabil| movimento.get(&contabil))
+            .unwrap_or(&sem_movimento);
+
+        // A conta contábil de um banco é do ativo: débito é entrada.
+        let saldo_inicial = saldos_iniciais.get(&id).copied().unwrap_or_default();
+        let saldo_inicial = conversor
+            .converter(saldo_inicial, Moeda::Brl, de.pred_opt().unwrap_or(de))
+            .await?;
+        let mut convertido = HashMap::new();
+        for (mes, &fim) in meses.iter().zip(&fechamentos) {
+            if let Some(m) = movimento.get(mes) {
+                let entradas = conversor.converter(m.debito, Moeda::Brl, fim).await?;
+                let saidas = conversor.converter(m.credito, Moeda::Brl, fim).await?;
+                convertido.insert(mes.as_str(), (entradas, saidas));
+            }
+        }
+        let (meses, saldo_final) = acumular(saldo_inicial, &meses, |mes| {
+            convertido.get(mes).copied().unwrap_or_default()
+        });
+
+        realizado.push(FluxoConta {
+            conta_id: id.to_hex(),
+            conta: format!("Conta {} {}", conta.banco, conta.conta),
+            saldo_inicial,
+            meses,
+            saldo_final,
+        });
+    }
+
+    let projetado = match conta_id {
+        None => projetar(repos, &mut conversor, empresa_id, de, ate).await?,
+        Some(_) => None,
+    };
+
+    Ok(FluxoCaixa {
+        de,
+        ate,
+        moeda: conversor.destino(),
+        realizado,
+        projetado,
+    })
+}
+
+/// Projeção do dia de hoje (ou de `de`, se posterior) até `ate`. Cada
+/// lançamento em aberto entra no mês do vencimento ou, se vencido, no mês
+/// atual; os previstos para antes do início vão para o saldo inicial.
+/// Tudo é convertido pela cotação de hoje.
+async fn projetar(
+    repos: &Repositorios,
+    conversor: &mut Conversor<'_>,
+    empresa_id: ObjectId,
+    de: NaiveDate,
+    ate: NaiveDate,
+) -> Result<Option<FluxoProjetado>> {
+    let hoje = Utc::now().date_naive();
+    if ate < hoje {
+        return Ok(None);
+    }
+    let inicio = de.max(hoje);
+    let meses = meses(inicio, ate)?;
+
+    let saldo_inicial: Dinheiro = contabilidade::saldos_bancarios(repos, empresa_id, None)
+        .await?
+        .into_values()
+        .sum();
+    let mut saldo_inicial = conversor.converter(saldo_inicial, Moeda::Brl, hoje).await?;
+
+    let filtro = FiltroLancamentos {
+        status: Some(StatusLancamento::Aberto),
+        vencimento_ate: Some(ate),
+        ..Default::default()
+    };
+    let mut previsto: HashMap<String, (Dinheiro, Dinheiro)> = HashMap::new();
+    for lancamento in repos.lancamentos.filtrar(empresa_id, &filtro).await? {
+        let data = lancamento.vencimento.max(hoje);
+        let valor = conversor
+            .converter(lancamento.valor, lancamento.moeda, hoje)
+            .await?;
+        if data < inicio {
+            match lancamento.tipo {
+                TipoLancamento::Receber => saldo_inicial += valor,
+                TipoLancamento::Pagar => saldo_inicial -= valor,
+            }
+            continue;
+        }
+
+        let (entradas, saidas) = previsto.entry(mes(data)).or_default();
+        match lancamento.tipo {
+            TipoLancamento::Receber => *entradas += valor,
+            TipoLancamento::Pagar => *saidas += valor,
+        }
+    }
+
+    let (meses, saldo_final) = acumular(saldo_inicial, &meses, |mes| {
+        previsto.get(mes).copied().unwrap_or_default()
+    });
+    Ok(Some(FluxoProjetado {
+        saldo_inicial,
+        meses,
+        saldo_final,
+    }))
+}
+
+pub async fn dre(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    de: NaiveDate,
+    ate: NaiveDate,
+    moeda: Moeda,
+) -> Result<Dre> {
+    let meses = meses(de, ate)?;
+    let plano = contabilidade::plano(repos, empresa_id).await?;
+    let movimento = repos.partidas.movimento_mensal(empresa_id, de, ate).await?;
+
+    let linhas = |tipo: TipoContaContabil| -> Vec<LinhaDre> {
+        plano
+            .contas()
+            .iter()
+            .filter(|conta| conta.tipo == tipo && !plano.sintetica(conta))
+            .filter_map(|conta| {
+                let movimento = movimento.get(&conta.id?)?;
+                let valores = meses
+                    .iter()
+                    .map(|mes| {
+                        movimento
+                            .get(mes)
+                            .map(|m| m.saldo(tipo))
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                Some(LinhaDre {
+                    codigo: conta.codigo.clone(),
+                    nome: conta.nome.clone(),
+                    valores: ValoresMensais::new(valores),
+                })
+            })
+            .collect()
+    };
+    let mut receitas = linhas(TipoContaContabil::Receita);
+    let mut despesas = linhas(TipoContaContabil::Despesa);
+
+    let mut conversor = Conversor::new(repos, empresa_id, moeda);
+    let fechamentos: Vec<NaiveDate> = meses.iter().map(|mes| fechamento(mes, ate)).collect();
+    for linha in receitas.iter_mut().chain(despesas.iter_mut()) {
+        let mut valores = Vec::with_capacity(meses.len());
+        for (&valor, &fim) in linha.valores.meses.iter().zip(&fechamentos) {
+            valores.push(conversor.converter(valor, Moeda::Brl, fim).await?);
+        }
+        linha.valores = ValoresMensais::new(valores);
+    }
+
+    let total_receitas =
+        ValoresMensais::somar(meses.len(), receitas.iter().map(|linha| &linha.valores));
+    let total_despesas =
+        ValoresMensais::somar(meses.len(), despesas.iter().map(|linha| &linha.valores));
+    let resultado = total_receitas.menos(&total_despesas);
+
+    Ok(Dre {
+        de,
+        ate,
+        moeda: conversor.destino(),
+        meses,
+        receitas,
+        despesas,
+        total_receitas,
+        total_despesas,
+        resultado,
+    })
+}
+
+pub async fn aging(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    tipo: TipoLancamento,
+    data_base: NaiveDate,
+    moeda: Moeda,
+) -> Result<Aging> {
+    let filtro = FiltroLancamentos {
+        tipo: Some(tipo),
+        status: Some(StatusLancamento::Aberto),
+        ..Default::default()
+    };
+    let lancamentos = repos.lancamentos.filtrar(empresa_id, &filtro).await?;
+
+    let clientes: HashMap<ObjectId, String> = if lancamentos.iter().any(|l| l.cliente_id.is_some())
+    {
+        repos
+            .clientes
+            .listar(empresa_id)
+            .await?
+            .into_iter()
+            .filter_map(|cliente| Some((cliente.id?, cliente.nome)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut conversor = Conversor::new(repos, empresa_id, moeda);
+    let mut itens = Vec::with_capacity(lancamentos.len());
+    for lancamento in lancamentos {
+        let dias_atraso = (data_base - lancamento.vencimento).num_days();
+        let contraparte = lancamento.contraparte.or_else(|| {
+            lancamento
+                .cliente_id
+                .and_then(|id| clientes.get(&id).cloned())
+        });
+        let valor = conversor
+            .converter(lancamento.valor, lancamento.moeda, data_base)
+            .await?;
+        itens.push(ItemAging {
+            lancamento_id: lancamento.id.map(|id| id.to_hex()),
+            descricao: lancamento.descricao,
+            contraparte,
+            vencimento: lancamento.vencimento,
+            dias_atraso,
+            faixa: FaixaAging::do_atraso(dias_atraso),
+            valor,
+            moeda_original: lancamento.moeda,
+            valor_original: lancamento.valor,
+        });
+    }
+
+    let faixas = FaixaAging::TODAS
+        .iter()
+        .map(|&faixa| {
+            let da_faixa = itens.iter().filter(|item| item.faixa == faixa);
+            TotalFaixa {
+                faixa,
+                quantidade: da_faixa.clone().count() as u64,
+                valor: da_faixa.map(|item| item.valor).sum(),
+            }
+        })
+        .collect();
+    let total = itens.iter().map(|item| item.valor).sum();
+
+    Ok(Aging {
+        tipo,
+        data_base,
+        moeda: conversor.destino(),
+        faixas,
+        total,
+        itens,
+    })
+}
+
+impl Relatorio for FluxoCaixa {
+    const ARQUIVO: &'static str = "fluxo-caixa";
+
+    fn quadro(&self) -> Quadro {
+        let mut quadro = Quadro::new(
+            "Fluxo de caixa",
+            em_moeda(periodo(self.de, self.ate), self.moeda),
+            &["Fluxo", "Conta", "Mês", "Entradas", "Saídas", "Saldo"],
+        );
+
+        let mut linhas = |fluxo: &str, conta: &str, saldo_inicial, meses: &[MesFluxo]| {
+            quadro.linha(vec![
+                fluxo.into(),
+                conta.into(),
+                "Saldo inicial".into(),
+                Celula::Vazia,
+                Celula::Vazia,
+                Celula::Dinheiro(saldo_inicial),
+            ]);
+            for mes in meses {
+                quadro.linha(vec![
+                    fluxo.into(),
+                    conta.into(),
+                    rotulo_mes(&mes.mes).into(),
+                    mes.entradas.into(),
+                    mes.saidas.into(),
+                    mes.saldo.into(),
+                ]);
+            }
+        };
+
+        for conta in &self.realizado {
+            linhas("Realizado", &conta.conta, conta.saldo_inicial, &conta.meses);
+        }
+        if let Some(projetado) = &self.projetado {
+            linhas(
+                "Projetado",
+                "Todas as contas",
+                projetado.saldo_inicial,
+                &projetado.meses,
+            );
+        }
+        quadro
+    }
+}
+
+impl Relatorio for Dre {
+    const ARQUIVO: &'static str = "dre";
+
+    fn quadro(&self) -> Quadro {
+        let rotulos: Vec<String> = self.meses.iter().map(|mes| rotulo_mes(mes)).collect();
+        let mut colunas = vec!["Código", "Conta"];
+        colunas.extend(rotulos.iter().map(String::as_str));
+        colunas.push("Total");
+
+        let mut quadro = Quadro::new(
+            "Demonstração do resultado",
+            em_moeda(periodo(self.de, self.ate), self.moeda),
+            &colunas,
+        );
+        let mut linha = |codigo: &str, nome: &str, valores: &ValoresMensais| {
+            let mut celulas = vec![codigo.into(), nome.into()];
+            celulas.extend(valores.celulas());
+            quadro.linha(celulas);
+        };
+
+        for receita in &self.receitas {
+            linha(&receita.codigo, &receita.nome, &receita.valores);
+        }
+        linha("", "Total de receitas", &self.total_receitas);
+        for despesa in &self.despesas {
+            linha(&despesa.codigo, &despesa.nome, &despesa.valores);
+        }
+        linha("", "Total de despesas", &self.total_despesas);
+        linha("", "Resultado", &self.resultado);
+        quadro
+    }
+}
+
+impl Relatorio for Aging {
+    const ARQUIVO: &'static str = "aging";
+
+    fn quadro(&self) -> Quadro {
+        let titulo = match self.tipo {
+            TipoLancamento::Receber => "Aging de contas a receber",
+            TipoLancamento::Pagar => "Aging de contas a pagar",
+        };
+        let mut quadro = Quadro::new(
+            titulo,
+            em_moeda(
+                format!("Data-base: {}", self.data_base.format("%d/%m/%Y")),
+                self.moeda,
+            ),
+            &[
+                "Vencimento",
+                "Descrição",
+                "Contraparte",
+                "Dias de atraso",
+                "Faixa",
+                "Valor",
+            ],
+        );
+
+        for item in &self.itens {
+            quadro.linha(vec![
+                item.vencimento.into(),
+                item.descricao.as_str().into(),
+                item.contraparte.clone().map_or(Celula::Vazia, Celula::from),
+                item.dias_atraso.max(0).into(),
+                item.faixa.nome().into(),
+                item.valor.into(),
+            ]);
+        }
+        for total in &self.faixas {
+            quadro.linha(vec![
+                Celula::Vazia,
+                format!("Total da faixa ({} lançamentos)", total.quantidade).into(),
+                Celula::Vazia,
+                Celula::Vazia,
+                total.faixa.nome().into(),
+                total.valor.into(),
+            ]);
+        }
+        quadro.linha(vec![
+            Celula::Vazia,
+            "Total geral".into(),
+            Celula::Vazia,
+            Celula::Vazia,
+            Celula::Vazia,
+            self.total.into(),
+        ]);
+        quadro
+    }
+}
+
+/// Meses (`AAAA-MM`) de `de` a `ate`.
+fn meses(de: NaiveDate, ate: NaiveDate) -> Result<Vec<String>> {
+    if de > ate {
+        return Err(AppError::BadRequest(
+            "A data inicial deve ser anterior à final".to_string(),
+        ));
+    }
+
+    let mut meses = Vec::new();
+    let mut atual = de.with_day(1).unwrap_or(de);
+    while atual <= ate {
+        if meses.len() == MAXIMO_MESES {
+            return Err(AppError::BadRequest(format!(
+                "O período pode ter no máximo {} meses",
+                MAXIMO_MESES
+            )));
+        }
+        meses.push(mes(atual));
+        atual = match atual.checked_add_months(Months::new(1)) {
+            Some(proximo) => proximo,
+            None => break,
+        };
+    }
+    Ok(meses)
+}
+
+fn mes(data: NaiveDate) -> String {
+    data.format("%Y-%m").to_string()
+}
+
+/// `2026-10` vira `10/2026`.
+fn rotulo_mes(mes: &str) -> String {
+    match mes.split_once('-') {
+        Some((ano, mes)) => format!("{}/{}", mes, ano),
+        None => mes.to_string(),
+    }
+}
+
+fn periodo(de: NaiveDate, ate: NaiveDate) -> String {
+    format!(
+        "Período: {} a {}",
+        de.format("%d/%m/%Y"),
+        ate.format("%d/%m/%Y")
+    )
+}
+
+/// Subtítulo com a moeda, quando não é o real.
+fn em_moeda(subtitulo: String, moeda: Moeda) -> String {
+    if moeda == Moeda::Brl {
+        subtitulo
+    } else {
+        format!("{} (valores em {})", subtitulo, moeda)
+    }
+}
+
+/// Último dia do mês (`AAAA-MM`), sem passar de `ate`: a data da cotação
+/// usada para converter os valores do mês.
+fn fechamento(mes: &str, ate: NaiveDate) -> NaiveDate {
+    NaiveDate::parse_from_str(&format!("{}-01", mes), "%Y-%m-%d")
+        .ok()
+        .and_then(|inicio| inicio.checked_add_months(Months::new(1)))
+        .and_then(|proximo| proximo.pred_opt())
+        .map_or(ate, |fim| fim.min(ate))
+}
+
+/// Entradas e saídas de cada mês, com o saldo acumulado; retorna também o
+/// saldo final.
+fn acumular(
+    saldo_inicial: Dinheiro,
+    meses: &[String],
+    movimento: impl Fn(&str) -> (Dinheiro, Dinheiro),
+) -> (Vec<MesFluxo>, Dinheiro) {
+    let mut saldo = saldo_inicial;
+    let meses = meses
+        .iter()
+        .map(|mes| {
+            let (entradas, saidas) = movimento(mes);
+            saldo += entradas - saidas;
+            MesFluxo {
+                mes: mes.clone(),
+                entradas,
+                saidas,
+                saldo,
+            }
+        })
+        .collect();
+    (meses, saldo)
+}
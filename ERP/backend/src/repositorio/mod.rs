@@ -0,0 +1,670 @@
+//! Acesso aos dados, com um trait por agregado. Há duas implementações: o
+//! MongoDB ([`mongo`]) e bancos SQL via SQLx ([`sql`], SQLite ou Postgres).
+//! A escolhida na inicialização fica em [`Repositorios`], que é o estado
+//! compartilhado pelos routers.
+
+mod mongo;
+mod sql;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use axum::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::*,
+    mongodb::MongoDb,
+};
+
+/// Registro gravado por um repositório: tem id próprio e pertence a uma
+/// empresa.
+pub trait Registro {
+    /// Nome da coleção (MongoDB) ou tabela (SQL).
+    const COLECAO: &'static str;
+
+    fn id(&self) -> Option<ObjectId>;
+    fn definir_id(&mut self, id: ObjectId);
+    fn empresa_id(&self) -> ObjectId;
+}
+
+macro_rules! registro {
+    ($tipo:ty, $colecao:literal) => {
+        impl Registro for $tipo {
+            const COLECAO: &'static str = $colecao;
+
+            fn id(&self) -> Option<ObjectId> {
+                self.id
+            }
+
+            fn definir_id(&mut self, id: ObjectId) {
+                self.id = Some(id);
+            }
+
+            fn empresa_id(&self) -> ObjectId {
+                self.empresa_id
+            }
+        }
+    };
+}
+
+registro!(Banco, "bancos");
+registro!(Cliente, "clientes");
+registro!(Fornecedor, "fornecedores");
+registro!(Produto, "produtos");
+registro!(MovimentacaoEstoque, "movimentacoes_estoque");
+registro!(Venda, "vendas");
+registro!(PedidoCompra, "pedidos_compra");
+registro!(ContaBancaria, "contas_bancarias");
+registro!(Cartao, "cartoes");
+registro!(Lancamento, "lancamentos");
+registro!(CompraCartao, "compras_cartao");
+registro!(Recorrencia, "recorrencias");
+registro!(Cotacao, "cotacoes");
+registro!(TransacaoBancaria, "transacoes_bancarias");
+registro!(ContaContabil, "contas_contabeis");
+registro!(Partida, "partidas");
+registro!(CobrancaPix, "cobrancas_pix");
+registro!(Boleto, "boletos");
+registro!(NotaFiscal, "notas_fiscais");
+registro!(RegistroAuditoria, "auditoria");
+
+/// Registro com controle de concorrência otimista: a versão aumenta a cada
+/// alteração pela API e é conferida antes de gravar.
+pub trait Versionado: Registro {
+    fn versao(&self) -> u32;
+    fn definir_versao(&mut self, versao: u32);
+}
+
+macro_rules! versionado {
+    ($tipo:ty) => {
+        impl Versionado for $tipo {
+            fn versao(&self) -> u32 {
+                self.versao
+            }
+
+            fn definir_versao(&mut self, versao: u32) {
+                self.versao = versao;
+            }
+        }
+    };
+}
+
+versionado!(Cliente);
+versionado!(Fornecedor);
+versionado!(ContaBancaria);
+versionado!(Cartao);
+
+/// Operações comuns aos agregados que pertencem a uma empresa. Toda
+/// consulta recebe o `empresa_id`, de modo que uma empresa nunca enxerga
+/// os registros de outra.
+#[async_trait]
+pub trait Repositorio<T>: Send + Sync {
+    async fn listar(&self, empresa_id: ObjectId) -> Result<Vec<T>>;
+    /// Uma página dos registros que atendem à consulta.
+    async fn paginar(&self, empresa_id: ObjectId, consulta: &Consulta<T>) -> Result<Pagina<T>>;
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<T>>;
+    /// Grava um registro novo; o id é gerado quando ausente.
+    async fn criar(&self, registro: T) -> Result<T>;
+    async fn criar_varios(&self, registros: Vec<T>) -> Result<Vec<T>>;
+    /// Substitui o registro gravado; retorna `false` se ele não existe mais.
+    async fn salvar(&self, registro: &T) -> Result<bool>;
+    async fn excluir(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool>;
+}
+
+/// Alterações condicionadas à versão lida, para que duas pessoas editando
+/// o mesmo registro não sobrescrevam uma à outra.
+#[async_trait]
+pub trait RepositorioVersionado<T: Versionado>: Repositorio<T> {
+    /// Grava o registro se a versão gravada ainda for a dele e a
+    /// incrementa; retorna `false`, sem alterar nada, se o registro mudou
+    /// ou não existe mais.
+    async fn salvar_versao(&self, registro: &mut T) -> Result<bool>;
+    /// Exclui o registro se a versão gravada ainda for `versao`.
+    async fn excluir_versao(&self, empresa_id: ObjectId, id: ObjectId, versao: u32)
+        -> Result<bool>;
+}
+
+#[async_trait]
+pub trait EmpresasRepositorio: Send + Sync {
+    /// Com `ids`, apenas as empresas informadas.
+    async fn listar(&self, ids: Option<&[ObjectId]>) -> Result<Vec<Empresa>>;
+    /// Uma página das empresas; com `ids`, apenas dentre as informadas.
+    async fn paginar(
+        &self,
+        ids: Option<&[ObjectId]>,
+        consulta: &Consulta<Empresa>,
+    ) -> Result<Pagina<Empresa>>;
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Empresa>>;
+    /// A empresa cadastrada há mais tempo.
+    async fn primeira(&self) -> Result<Option<Empresa>>;
+    async fn criar(&self, empresa: Empresa) -> Result<Empresa>;
+    async fn salvar(&self, empresa: &Empresa) -> Result<bool>;
+    /// Atribui à empresa os registros e usuários gravados antes da
+    /// separação por empresa.
+    async fn atribuir_sem_empresa(&self, empresa_id: ObjectId) -> Result<()>;
+}
+
+#[async_trait]
+pub trait UsuariosRepositorio: Send + Sync {
+    async fn paginar(&self, consulta: &Consulta<Usuario>) -> Result<Pagina<Usuario>>;
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Usuario>>;
+    /// `email` já normalizado em minúsculas.
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<Usuario>>;
+    async fn contar(&self) -> Result<u64>;
+    async fn criar(&self, usuario: Usuario) -> Result<Usuario>;
+    async fn salvar(&self, usuario: &Usuario) -> Result<bool>;
+    async fn excluir(&self, id: ObjectId) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait BancosRepositorio: Repositorio<Banco> {
+    /// Troca todos os bancos da empresa pelos informados; retorna os
+    /// gravados, já com os ids.
+    async fn substituir(&self, empresa_id: ObjectId, bancos: Vec<Banco>) -> Result<Vec<Banco>>;
+}
+
+#[async_trait]
+pub trait ProdutosRepositorio: Repositorio<Produto> {
+    /// Soma `delta` ao `estoque_atual`. Saídas (delta negativo) só são
+    /// aplicadas se houver saldo e entradas só se o estoque continuar num
+    /// `i32`; retorna `false` quando o produto não foi alterado.
+    async fn ajustar_estoque(&self, empresa_id: ObjectId, id: ObjectId, delta: i32)
+        -> Result<bool>;
+    /// Grava o cadastro do produto sem tocar no `estoque_atual`, que só
+    /// muda por movimentação.
+    async fn salvar_cadastro(&self, produto: &Produto) -> Result<bool>;
+    /// Produtos ativos com `estoque_atual` igual ou abaixo do mínimo.
+    async fn criticos(&self, empresa_id: ObjectId) -> Result<Vec<Produto>>;
+}
+
+#[derive(Debug, Default)]
+pub struct FiltroMovimentacoes {
+    pub produto_id: Option<ObjectId>,
+    pub tipo: Option<TipoMovimentacao>,
+}
+
+#[async_trait]
+pub trait MovimentacoesRepositorio: Send + Sync {
+    /// Em ordem cronológica.
+    async fn listar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroMovimentacoes,
+    ) -> Result<Vec<MovimentacaoEstoque>>;
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<MovimentacaoEstoque>,
+    ) -> Result<Pagina<MovimentacaoEstoque>>;
+    async fn criar(&self, movimentacao: MovimentacaoEstoque) -> Result<MovimentacaoEstoque>;
+}
+
+#[async_trait]
+pub trait VendasRepositorio: Repositorio<Venda> {
+    /// Finalizadas a partir do instante informado.
+    async fn finalizadas_desde(
+        &self,
+        empresa_id: ObjectId,
+        desde: DateTime<Utc>,
+    ) -> Result<Vec<Venda>>;
+    /// Grava a venda somente se ela ainda estiver aberta.
+    async fn salvar_aberta(&self, venda: &Venda) -> Result<bool>;
+    /// Troca o status somente se ele ainda for `de`.
+    async fn transicionar(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        de: StatusVenda,
+        para: StatusVenda,
+    ) -> Result<bool>;
+    async fn excluir_aberta(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait PedidosCompraRepositorio: Repositorio<PedidoCompra> {
+    /// Grava o pedido somente se ninguém o alterou desde a leitura, isto é,
+    /// se o `updated_at` gravado ainda for `lido_em`.
+    async fn salvar_versao(&self, pedido: &PedidoCompra, lido_em: DateTime<Utc>) -> Result<bool>;
+    /// Exclui o pedido somente se nada dele foi recebido.
+    async fn excluir_aberto(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool>;
+}
+
+#[derive(Debug, Default)]
+pub struct FiltroLancamentos {
+    pub tipo: Option<TipoLancamento>,
+    pub status: Option<StatusLancamento>,
+    pub cliente_id: Option<ObjectId>,
+    pub vencimento_de: Option<NaiveDate>,
+    pub vencimento_ate: Option<NaiveDate>,
+    pub valor_min: Option<Dinheiro>,
+    pub valor_max: Option<Dinheiro>,
+}
+
+#[async_trait]
+pub trait LancamentosRepositorio: Repositorio<Lancamento> {
+    /// Ordenados por vencimento e parcela.
+    async fn filtrar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroLancamentos,
+    ) -> Result<Vec<Lancamento>>;
+    /// Grava o lançamento somente se o status gravado ainda for `status`.
+    async fn salvar_no_status(
+        &self,
+        lancamento: &Lancamento,
+        status: StatusLancamento,
+    ) -> Result<bool>;
+    async fn excluir_nao_pago(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool>;
+    /// Se algum lançamento, em qualquer status, é da conta bancária.
+    async fn existe_da_conta(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<bool>;
+    /// Lançamentos gerados pela recorrência, pelo número da ocorrência.
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<Lancamento>>;
+    /// Soma, por conta bancária, os lançamentos liquidados: recebimentos
+    /// com sinal positivo e pagamentos com sinal negativo.
+    async fn movimentado_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>>;
+}
+
+#[async_trait]
+pub trait ComprasCartaoRepositorio: Send + Sync {
+    async fn criar_varias(&self, compras: Vec<CompraCartao>) -> Result<Vec<CompraCartao>>;
+    /// Com o filtro `cartao_id` ou `recorrencia_id` da consulta, as
+    /// parcelas de um cartão ou de uma recorrência.
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<CompraCartao>,
+    ) -> Result<Pagina<CompraCartao>>;
+    /// Das mais recentes para as mais antigas.
+    async fn listar_do_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>>;
+    /// Parcelas de uma fatura, por data da compra.
+    async fn da_competencia(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        competencia: &str,
+    ) -> Result<Vec<CompraCartao>>;
+    async fn contar_pagas(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64>;
+    /// Exclui todas as parcelas da compra; retorna quantas foram excluídas.
+    async fn excluir_compra(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64>;
+    /// Marca como pagas, pelo lançamento informado, as parcelas ainda em
+    /// aberto; retorna quantas foram marcadas.
+    async fn quitar(&self, ids: &[ObjectId], lancamento_id: ObjectId) -> Result<u64>;
+    /// Volta a deixar em aberto as parcelas quitadas pelo lançamento.
+    async fn reabrir(&self, lancamento_id: ObjectId) -> Result<()>;
+    /// Compras geradas pela recorrência, pelo número da ocorrência.
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>>;
+    /// Valor das parcelas ainda não quitadas, por cartão.
+    async fn utilizado_por_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>>;
+}
+
+#[async_trait]
+pub trait RecorrenciasRepositorio: Repositorio<Recorrencia> {
+    /// Recorrências ativas de todas as empresas, para a geração automática.
+    async fn ativas(&self) -> Result<Vec<Recorrencia>>;
+    /// Grava a recorrência somente se o número de ocorrências geradas
+    /// gravado ainda for `geradas`.
+    async fn salvar_geradas(&self, recorrencia: &Recorrencia, geradas: u32) -> Result<bool>;
+}
+
+#[async_trait]
+pub trait CotacoesRepositorio: Repositorio<Cotacao> {
+    /// A cotação mais recente da moeda até a data, inclusive.
+    async fn na_data(
+        &self,
+        empresa_id: ObjectId,
+        moeda: Moeda,
+        data: NaiveDate,
+    ) -> Result<Option<Cotacao>>;
+}
+
+#[async_trait]
+pub trait TransacoesRepositorio: Send + Sync {
+    /// Quais dos identificadores informados já foram importados na conta.
+    async fn identificadores_existentes(
+        &self,
+        conta_id: ObjectId,
+        identificadores: &[String],
+    ) -> Result<HashSet<String>>;
+    async fn criar_varias(
+        &self,
+        transacoes: Vec<TransacaoBancaria>,
+    ) -> Result<Vec<TransacaoBancaria>>;
+    /// Com o filtro `conta_id` da consulta, as transações de uma conta.
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<TransacaoBancaria>,
+    ) -> Result<Pagina<TransacaoBancaria>>;
+    async fn buscar(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        id: ObjectId,
+    ) -> Result<Option<TransacaoBancaria>>;
+    /// Concilia a transação se ela ainda estiver pendente; retorna se
+    /// conciliou.
+    async fn conciliar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<bool>;
+    /// Desfaz a conciliação da transação, se ela ainda for com o
+    /// lançamento informado.
+    async fn liberar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<()>;
+    /// Volta a deixar pendentes as transações conciliadas com o lançamento.
+    async fn desfazer_conciliacao(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<()>;
+}
+
+#[async_trait]
+pub trait BoletosRepositorio: Repositorio<Boleto> {
+    async fn do_lancamento(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<Option<Boleto>>;
+    /// Reserva o próximo sequencial de nosso número da conta, que não se
+    /// repete nem entre requisições simultâneas.
+    async fn proximo_sequencial(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u64>;
+    /// Reserva o número da próxima remessa da conta, como o sequencial.
+    async fn proxima_remessa(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u32>;
+    /// Boletos da conta ainda não remetidos, pelo sequencial.
+    async fn a_remeter(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<Vec<Boleto>>;
+    /// Marca como remetidos na `remessa` os boletos `ids` que ainda não
+    /// foram remetidos e retorna quantos marcou.
+    async fn remeter(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        ids: &[ObjectId],
+        remessa: u32,
+    ) -> Result<u64>;
+    /// Boletos enviados na remessa, pelo sequencial.
+    async fn da_remessa(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        remessa: u32,
+    ) -> Result<Vec<Boleto>>;
+}
+
+#[async_trait]
+pub trait NotasFiscaisRepositorio: Repositorio<NotaFiscal> {
+    async fn da_venda(
+        &self,
+        empresa_id: ObjectId,
+        venda_id: ObjectId,
+    ) -> Result<Option<NotaFiscal>>;
+    /// Reserva o próximo número do modelo e série.
+    async fn proximo_numero_da_serie(
+        &self,
+        empresa_id: ObjectId,
+        modelo: ModeloNota,
+        serie: u32,
+    ) -> Result<u32>;
+}
+
+#[async_trait]
+pub trait CobrancasPixRepositorio: Repositorio<CobrancaPix> {
+    async fn buscar_por_txid(
+        &self,
+        empresa_id: ObjectId,
+        txid: &str,
+    ) -> Result<Option<CobrancaPix>>;
+    /// Cobranças ainda não pagas da conta bancária.
+    async fn pendentes(&self, empresa_id: ObjectId, conta_id: ObjectId)
+        -> Result<Vec<CobrancaPix>>;
+    /// A cobrança paga pela transação do extrato, se houver.
+    async fn paga_pela_transacao(
+        &self,
+        empresa_id: ObjectId,
+        transacao_id: ObjectId,
+    ) -> Result<Option<CobrancaPix>>;
+}
+
+#[async_trait]
+pub trait ContasContabeisRepositorio: Repositorio<ContaContabil> {
+    /// Todas as contas da empresa, por código.
+    async fn plano(&self, empresa_id: ObjectId) -> Result<Vec<ContaContabil>>;
+}
+
+/// Livro diário. Não há alteração nem exclusão de partidas.
+#[async_trait]
+pub trait PartidasRepositorio: Send + Sync {
+    async fn criar(&self, partida: Partida) -> Result<Partida>;
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>>;
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<Partida>,
+    ) -> Result<Pagina<Partida>>;
+    /// Partidas geradas pelo registro informado, incluindo os estornos, em
+    /// ordem de gravação.
+    async fn da_origem(
+        &self,
+        empresa_id: ObjectId,
+        origem: OrigemPartida,
+        origem_id: ObjectId,
+    ) -> Result<Vec<Partida>>;
+    /// A partida que estornou a informada, se houver.
+    async fn estorno_de(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>>;
+    async fn existe_alguma(&self, empresa_id: ObjectId) -> Result<bool>;
+    /// Débitos e créditos somados por conta contábil, com `ate`, só das
+    /// partidas até essa data.
+    async fn movimento_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        ate: Option<NaiveDate>,
+    ) -> Result<HashMap<ObjectId, Movimento>>;
+    /// Linhas das contas contábeis informadas, com a data da partida.
+    async fn linhas_das_contas(
+        &self,
+        empresa_id: ObjectId,
+        contas: &[ObjectId],
+    ) -> Result<Vec<(NaiveDate, LinhaPartida)>>;
+    /// Débitos e créditos por conta contábil e mês (`AAAA-MM`) das partidas
+    /// entre `de` e `ate`, inclusive.
+    async fn movimento_mensal(
+        &self,
+        empresa_id: ObjectId,
+        de: NaiveDate,
+        ate: NaiveDate,
+    ) -> Result<HashMap<ObjectId, BTreeMap<String, Movimento>>>;
+}
+
+/// Trilha de auditoria. Só há inclusão; nada é alterado nem excluído.
+#[async_trait]
+pub trait AuditoriaRepositorio: Send + Sync {
+    async fn registrar(&self, registro: RegistroAuditoria) -> Result<RegistroAuditoria>;
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<RegistroAuditoria>,
+    ) -> Result<Pagina<RegistroAuditoria>>;
+}
+
+/// Respostas das requisições com `Idempotency-Key`, por usuário e chave.
+#[async_trait]
+pub trait IdempotenciaRepositorio: Send + Sync {
+    /// Reserva a chave para a requisição. Se ela já foi usada desde
+    /// `validas_desde`, não grava nada e retorna o registro existente; os
+    /// mais antigos são descartados.
+    async fn reservar(
+        &self,
+        requisicao: &RequisicaoIdempotente,
+        validas_desde: DateTime<Utc>,
+    ) -> Result<Option<RequisicaoIdempotente>>;
+    /// Grava a resposta da requisição reservada.
+    async fn concluir(&self, id: &str, resposta: &RespostaGravada) -> Result<()>;
+    /// Desfaz a reserva de uma requisição que falhou, para que possa ser
+    /// repetida.
+    async fn liberar(&self, id: &str) -> Result<()>;
+}
+
+/// Repositórios do armazenamento configurado. É o estado dos routers.
+#[derive(Clone)]
+pub struct Repositorios {
+    pub empresas: Arc<dyn EmpresasRepositorio>,
+    pub usuarios: Arc<dyn UsuariosRepositorio>,
+    pub bancos: Arc<dyn BancosRepositorio>,
+    pub clientes: Arc<dyn RepositorioVersionado<Cliente>>,
+    pub produtos: Arc<dyn ProdutosRepositorio>,
+    pub movimentacoes: Arc<dyn MovimentacoesRepositorio>,
+    pub vendas: Arc<dyn VendasRepositorio>,
+    pub fornecedores: Arc<dyn RepositorioVersionado<Fornecedor>>,
+    pub pedidos_compra: Arc<dyn PedidosCompraRepositorio>,
+    pub contas: Arc<dyn RepositorioVersionado<ContaBancaria>>,
+    pub cartoes: Arc<dyn RepositorioVersionado<Cartao>>,
+    pub lancamentos: Arc<dyn LancamentosRepositorio>,
+    pub compras_cartao: Arc<dyn ComprasCartaoRepositorio>,
+    pub recorrencias: Arc<dyn RecorrenciasRepositorio>,
+    pub cotacoes: Arc<dyn CotacoesRepositorio>,
+    pub transacoes: Arc<dyn TransacoesRepositorio>,
+    pub cobrancas_pix: Arc<dyn CobrancasPixRepositorio>,
+    pub boletos: Arc<dyn BoletosRepositorio>,
+    pub notas_fiscais: Arc<dyn NotasFiscaisRepositorio>,
+    pub contas_contabeis: Arc<dyn ContasContabeisRepositorio>,
+    pub partidas: Arc<dyn PartidasRepositorio>,
+    pub auditoria: Arc<dyn AuditoriaRepositorio>,
+    pub idempotencia: Arc<dyn IdempotenciaRepositorio>,
+}
+
+impl Repositorios {
+    fn com<B>(backend: B) -> Self
+    where
+        B: EmpresasRepositorio
+            + UsuariosRepositorio
+            + BancosRepositorio
+            + RepositorioVersionado<Cliente>
+            + ProdutosRepositorio
+            + MovimentacoesRepositorio
+            + VendasRepositorio
+            + RepositorioVersionado<Fornecedor>
+            + PedidosCompraRepositorio
+            + RepositorioVersionado<ContaBancaria>
+            + RepositorioVersionado<Cartao>
+            + LancamentosRepositorio
+            + ComprasCartaoRepositorio
+            + RecorrenciasRepositorio
+            + CotacoesRepositorio
+            + TransacoesRepositorio
+            + CobrancasPixRepositorio
+            + BoletosRepositorio
+            + NotasFiscaisRepositorio
+            + ContasContabeisRepositorio
+            + PartidasRepositorio
+            + AuditoriaRepositorio
+            + IdempotenciaRepositorio
+            + 'static,
+    {
+        let backend = Arc::new(backend);
+        Self {
+            empresas: backend.clone(),
+            usuarios: backend.clone(),
+            bancos: backend.clone(),
+            clientes: backend.clone(),
+            produtos: backend.clone(),
+            movimentacoes: backend.clone(),
+            vendas: backend.clone(),
+            fornecedores: backend.clone(),
+            pedidos_compra: backend.clone(),
+            contas: backend.clone(),
+            cartoes: backend.clone(),
+            lancamentos: backend.clone(),
+            compras_cartao: backend.clone(),
+            recorrencias: backend.clone(),
+            cotacoes: backend.clone(),
+            transacoes: backend.clone(),
+            cobrancas_pix: backend.clone(),
+            boletos: backend.clone(),
+            notas_fiscais: backend.clone(),
+            contas_contabeis: backend.clone(),
+            partidas: backend.clone(),
+            auditoria: backend.clone(),
+            idempotencia: backend,
+        }
+    }
+
+    /// SQLite em memória, vazio, para os testes.
+    #[cfg(test)]
+    pub async fn em_memoria() -> Self {
+        Self::com(sql::Sql::conectar("sqlite::memory:").await.unwrap())
+    }
+
+    /// Conecta ao armazenamento definido por `ARMAZENAMENTO` (`mongodb` ou
+    /// `sql`). Sem ela, usa o MongoDB quando `MONGO_ATLAS_URI` estiver
+    /// definida e, caso contrário, o banco SQL de `DATABASE_URL`.
+    pub async fn conectar() -> anyhow::Result<Self> {
+        let armazenamento = match std::env::var("ARMAZENAMENTO") {
+            Ok(valor) => valor.trim().to_lowercase(),
+            Err(_) if std::env::var("MONGO_ATLAS_URI").is_ok() => "mongodb".to_string(),
+            Err(_) => "sql".to_string(),
+        };
+
+        match armazenamento.as_str() {
+            "mongodb" => {
+                let uri = std::env::var("MONGO_ATLAS_URI")
+                    .context("A variável de ambiente MONGO_ATLAS_URI não está definida. Configure o arquivo .env do backend ou use ARMAZENAMENTO=sql com DATABASE_URL.")?;
+                let db_name = std::env::var("MONGO_DB_NAME").unwrap_or_else(|_| "erp".to_string());
+
+                tracing::info!("Tentando conectar ao MongoDB Atlas (db: {})...", db_name);
+                let mongo = match MongoDb::new(&uri, &db_name).await {
+                    Ok(db) => {
+                        tracing::info!("✅ Conectado ao MongoDB Atlas");
+                        db
+                    }
+                    Err(e) => {
+                        tracing::error!("❌ Erro ao conectar no MongoDB: {}", e);
+                        return Err(e.into());
+                    }
+                };
+                mongo::preparar(&mongo).await?;
+                Ok(Self::com(mongo))
+            }
+            "sql" => {
+                let url = std::env::var("DATABASE_URL")
+                    .context("Nenhum armazenamento configurado. Defina DATABASE_URL (ex.: sqlite://erp.db) ou MONGO_ATLAS_URI.")?;
+                let sql = sql::Sql::conectar(&url).await?;
+                tracing::info!("✅ Conectado ao banco SQL");
+                Ok(Self::com(sql))
+            }
+            outro => anyhow::bail!("ARMAZENAMENTO inválido: {} (use mongodb ou sql)", outro),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use axum::async_trait;
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::RegistroAuditoria,
+    mongodb::MongoDb,
+    repositorio::{AuditoriaRepositorio, Repositorio},
+};
+
+#[async_trait]
+impl AuditoriaRepositorio for MongoDb {
+    async fn registrar(&self, registro: RegistroAuditoria) -> Result<RegistroAuditoria> {
+        Repositorio::<RegistroAuditoria>::criar(self, registro).await
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<RegistroAuditoria>,
+    ) -> Result<Pagina<RegistroAuditoria>> {
+        Repositorio::<RegistroAuditoria>::paginar(self, empresa_id, consulta).await
+    }
+}
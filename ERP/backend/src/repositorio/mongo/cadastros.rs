@@ -0,0 +1,166 @@
+use axum::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::FindOneOptions,
+};
+
+use super::paginar_colecao;
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{Banco, Empresa, Usuario},
+    mongodb::MongoDb,
+    repositorio::{BancosRepositorio, EmpresasRepositorio, UsuariosRepositorio},
+};
+
+/// Coleções cujos documentos pertencem a uma empresa.
+const COLECOES_POR_EMPRESA: &[&str] = &[
+    "bancos",
+    "contas_bancarias",
+    "cartoes",
+    "clientes",
+    "produtos",
+    "vendas",
+    "movimentacoes_estoque",
+    "lancamentos",
+    "compras_cartao",
+    "transacoes_bancarias",
+];
+
+#[async_trait]
+impl EmpresasRepositorio for MongoDb {
+    async fn listar(&self, ids: Option<&[ObjectId]>) -> Result<Vec<Empresa>> {
+        let filtro = ids.map(|ids| doc! { "_id": { "$in": ids } });
+        Ok(self
+            .empresas()
+            .find(filtro, None)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn paginar(
+        &self,
+        ids: Option<&[ObjectId]>,
+        consulta: &Consulta<Empresa>,
+    ) -> Result<Pagina<Empresa>> {
+        let escopo = ids
+            .map(|ids| doc! { "_id": { "$in": ids } })
+            .unwrap_or_default();
+        paginar_colecao(self.empresas(), escopo, consulta).await
+    }
+
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Empresa>> {
+        Ok(self.empresas().find_one(doc! { "_id": id }, None).await?)
+    }
+
+    async fn primeira(&self) -> Result<Option<Empresa>> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "created_at": 1 })
+            .build();
+        Ok(self.empresas().find_one(None, options).await?)
+    }
+
+    async fn criar(&self, mut empresa: Empresa) -> Result<Empresa> {
+        empresa.id.get_or_insert_with(ObjectId::new);
+        self.empresas().insert_one(&empresa, None).await?;
+        Ok(empresa)
+    }
+
+    async fn salvar(&self, empresa: &Empresa) -> Result<bool> {
+        let result = self
+            .empresas()
+            .replace_one(doc! { "_id": empresa.id }, empresa, None)
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn atribuir_sem_empresa(&self, empresa_id: ObjectId) -> Result<()> {
+        for nome in COLECOES_POR_EMPRESA {
+            let result = self
+                .documentos(nome)
+                .update_many(
+                    doc! { "empresa_id": { "$exists": false } },
+                    doc! { "$set": { "empresa_id": empresa_id } },
+                    None,
+                )
+                .await?;
+            if result.modified_count > 0 {
+                tracing::info!(
+                    "{} documento(s) de {} atribuídos à empresa padrão",
+                    result.modified_count,
+                    nome
+                );
+            }
+        }
+
+        self.usuarios()
+            .update_many(
+                doc! { "$or": [{ "empresas": { "$exists": false } }, { "empresas": { "$size": 0 } }] },
+                doc! { "$set": { "empresas": [empresa_id] } },
+                None,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UsuariosRepositorio for MongoDb {
+    async fn paginar(&self, consulta: &Consulta<Usuario>) -> Result<Pagina<Usuario>> {
+        paginar_colecao(self.usuarios(), doc! {}, consulta).await
+    }
+
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Usuario>> {
+        Ok(self.usuarios().find_one(doc! { "_id": id }, None).await?)
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<Usuario>> {
+        Ok(self
+            .usuarios()
+            .find_one(doc! { "email": email }, None)
+            .await?)
+    }
+
+    async fn contar(&self) -> Result<u64> {
+        Ok(self.usuarios().count_documents(None, None).await?)
+    }
+
+    async fn criar(&self, mut usuario: Usuario) -> Result<Usuario> {
+        usuario.id.get_or_insert_with(ObjectId::new);
+        self.usuarios().insert_one(&usuario, None).await?;
+        Ok(usuario)
+    }
+
+    async fn salvar(&self, usuario: &Usuario) -> Result<bool> {
+        let result = self
+            .usuarios()
+            .replace_one(doc! { "_id": usuario.id }, usuario, None)
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn excluir(&self, id: ObjectId) -> Result<bool> {
+        let result = self.usuarios().delete_one(doc! { "_id": id }, None).await?;
+        Ok(result.deleted_count > 0)
+    }
+}
+
+#[async_trait]
+impl BancosRepositorio for MongoDb {
+    async fn substituir(&self, empresa_id: ObjectId, mut bancos: Vec<Banco>) -> Result<Vec<Banco>> {
+        for banco in &mut bancos {
+            banco.id.get_or_insert_with(ObjectId::new);
+        }
+        let colecao = self.colecao::<Banco>();
+        colecao
+            .delete_many(doc! { "empresa_id": empresa_id }, None)
+            .await?;
+        if !bancos.is_empty() {
+            colecao.insert_many(&bancos, None).await?;
+        }
+        Ok(bancos)
+    }
+}
@@ -0,0 +1,44 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{
+    error::Result,
+    models::{PedidoCompra, StatusPedidoCompra},
+    mongodb::MongoDb,
+    repositorio::PedidosCompraRepositorio,
+};
+
+#[async_trait]
+impl PedidosCompraRepositorio for MongoDb {
+    async fn salvar_versao(&self, pedido: &PedidoCompra, lido_em: DateTime<Utc>) -> Result<bool> {
+        let result = self
+            .colecao::<PedidoCompra>()
+            .replace_one(
+                doc! {
+                    "_id": pedido.id,
+                    "empresa_id": pedido.empresa_id,
+                    "updated_at": bson::DateTime::from_chrono(lido_em),
+                },
+                pedido,
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn excluir_aberto(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let result = self
+            .colecao::<PedidoCompra>()
+            .delete_one(
+                doc! {
+                    "_id": id,
+                    "empresa_id": empresa_id,
+                    "status": StatusPedidoCompra::Aberto.as_str(),
+                },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
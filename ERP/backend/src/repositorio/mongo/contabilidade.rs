@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::async_trait;
+use chrono::NaiveDate;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, Document},
+    options::FindOptions,
+};
+
+use super::dinheiro;
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{ContaContabil, LinhaPartida, Movimento, OrigemPartida, Partida},
+    mongodb::MongoDb,
+    repositorio::{ContasContabeisRepositorio, PartidasRepositorio, Repositorio},
+};
+
+#[async_trait]
+impl ContasContabeisRepositorio for MongoDb {
+    async fn plano(&self, empresa_id: ObjectId) -> Result<Vec<ContaContabil>> {
+        let options = FindOptions::builder().sort(doc! { "codigo": 1 }).build();
+        Ok(self
+            .colecao::<ContaContabil>()
+            .find(doc! { "empresa_id": empresa_id }, options)
+            .await?
+            .try_collect()
+            .await?)
+    }
+}
+
+#[async_trait]
+impl PartidasRepositorio for MongoDb {
+    async fn criar(&self, partida: Partida) -> Result<Partida> {
+        Repositorio::<Partida>::criar(self, partida).await
+    }
+
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>> {
+        Repositorio::<Partida>::buscar(self, empresa_id, id).await
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<Partida>,
+    ) -> Result<Pagina<Partida>> {
+        Repositorio::<Partida>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn da_origem(
+        &self,
+        empresa_id: ObjectId,
+        origem: OrigemPartida,
+        origem_id: ObjectId,
+    ) -> Result<Vec<Partida>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1, "_id": 1 })
+            .build();
+        Ok(self
+            .colecao::<Partida>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "origem": origem.as_str(),
+                    "origem_id": origem_id,
+                },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn estorno_de(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>> {
+        Ok(self
+            .colecao::<Partida>()
+            .find_one(doc! { "empresa_id": empresa_id, "estorno_de": id }, None)
+            .await?)
+    }
+
+    async fn existe_alguma(&self, empresa_id: ObjectId) -> Result<bool> {
+        Ok(self
+            .colecao::<Partida>()
+            .find_one(doc! { "empresa_id": empresa_id }, None)
+            .await?
+            .is_some())
+    }
+
+    async fn movimento_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        ate: Option<NaiveDate>,
+    ) -> Result<HashMap<ObjectId, Movimento>> {
+        let mut filtro = doc! { "empresa_id": empresa_id };
+        if let Some(ate) = ate {
+            filtro.insert("data", doc! { "$lte": ate.to_string() });
+        }
+
+        let pipeline = vec![
+            doc! { "$match": filtro },
+            doc! { "$unwind": "$linhas" },
+            doc! {
+                "$group": {
+                    "_id": "$linhas.conta_id",
+                    "debito": { "$sum": "$linhas.debito" },
+                    "credito": { "$sum": "$linhas.credito" },
+                }
+            },
+        ];
+
+        let grupos: Vec<Document> = self
+            .colecao::<Partida>()
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(grupos
+            .into_iter()
+            .filter_map(|grupo| {
+                let id = grupo.get_object_id("_id").ok()?;
+                let movimento = Movimento {
+                    debito: dinheiro(&grupo, "debito"),
+                    credito: dinheiro(&grupo, "credito"),
+                };
+                Some((id, movimento))
+            })
+            .collect())
+    }
+
+    async fn linhas_das_contas(
+        &self,
+        empresa_id: ObjectId,
+        contas: &[ObjectId],
+    ) -> Result<Vec<(NaiveDate, LinhaPartida)>> {
+        let da_conta = doc! { "linhas.conta_id": { "$in": contas } };
+        let mut filtro = da_conta.clone();
+        filtro.insert("empresa_id", empresa_id);
+        let pipeline = vec![
+            doc! { "$match": filtro },
+            doc! { "$unwind": "$linhas" },
+            doc! { "$match": da_conta },
+            doc! { "$sort": { "data": 1 } },
+        ];
+
+        let documentos: Vec<Document> = self
+            .colecao::<Partida>()
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(documentos
+            .iter()
+            .filter_map(|documento| {
+                let data = documento.get_str("data").ok()?.parse().ok()?;
+                let linha = documento.get_document("linhas").ok()?;
+                let linha = LinhaPartida {
+                    conta_id: linha.get_object_id("conta_id").ok()?,
+                    debito: dinheiro(linha, "debito"),
+                    credito: dinheiro(linha, "credito"),
+                    valor_moeda: linha
+                        .contains_key("valor_moeda")
+                        .then(|| dinheiro(linha, "valor_moeda")),
+                };
+                Some((data, linha))
+            })
+            .collect())
+    }
+
+    async fn movimento_mensal(
+        &self,
+        empresa_id: ObjectId,
+        de: NaiveDate,
+        ate: NaiveDate,
+    ) -> Result<HashMap<ObjectId, BTreeMap<String, Movimento>>> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "empresa_id": empresa_id,
+                    "data": { "$gte": de.to_string(), "$lte": ate.to_string() },
+                }
+            },
+            doc! { "$unwind": "$linhas" },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "conta_id": "$linhas.conta_id",
+                        "mes": { "$substrBytes": ["$data", 0, 7] },
+                    },
+                    "debito": { "$sum": "$linhas.debito" },
+                    "credito": { "$sum": "$linhas.credito" },
+                }
+            },
+        ];
+
+        let grupos: Vec<Document> = self
+            .colecao::<Partida>()
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+        let mut movimentos: HashMap<ObjectId, BTreeMap<String, Movimento>> = HashMap::new();
+        for grupo in grupos {
+            let Ok(chave) = grupo.get_document("_id") else {
+                continue;
+            };
+            let (Ok(conta_id), Ok(mes)) = (chave.get_object_id("conta_id"), chave.get_str("mes"))
+            else {
+                continue;
+            };
+            let movimento = Movimento {
+                debito: dinheiro(&grupo, "debito"),
+                credito: dinheiro(&grupo, "credito"),
+            };
+            movimentos
+                .entry(conta_id)
+                .or_default()
+                .insert(mes.to_string(), movimento);
+        }
+        Ok(movimentos)
+    }
+}
@@ -0,0 +1,132 @@
+use axum::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::FindOptions,
+};
+
+use super::para_bson;
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{MovimentacaoEstoque, Produto},
+    mongodb::MongoDb,
+    repositorio::{
+        FiltroMovimentacoes, MovimentacoesRepositorio, ProdutosRepositorio, Repositorio,
+    },
+};
+
+#[async_trait]
+impl ProdutosRepositorio for MongoDb {
+    async fn ajustar_estoque(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        delta: i32,
+    ) -> Result<bool> {
+        let mut filtro = doc! { "_id": id, "empresa_id": empresa_id };
+        if delta < 0 {
+            filtro.insert("estoque_atual", doc! { "$gte": -delta });
+        } else {
+            filtro.insert("estoque_atual", doc! { "$lte": i32::MAX - delta });
+        }
+
+        let result = self
+            .colecao::<Produto>()
+            .update_one(
+                filtro,
+                doc! {
+                    "$inc": { "estoque_atual": delta },
+                    "$set": { "updated_at": bson::DateTime::from_chrono(Utc::now()) },
+                },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn salvar_cadastro(&self, produto: &Produto) -> Result<bool> {
+        let result = self
+            .colecao::<Produto>()
+            .update_one(
+                doc! { "_id": produto.id, "empresa_id": produto.empresa_id },
+                doc! {
+                    "$set": {
+                        "nome": &produto.nome,
+                        "descricao": &produto.descricao,
+                        "codigo_barras": &produto.codigo_barras,
+                        "preco_custo": produto.preco_custo,
+                        "preco_venda": produto.preco_venda,
+                        "estoque_minimo": produto.estoque_minimo,
+                        "unidade": &produto.unidade,
+                        "fiscal": para_bson(&produto.fiscal)?,
+                        "ativo": produto.ativo,
+                        "updated_at": bson::DateTime::from_chrono(produto.updated_at),
+                    }
+                },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn criticos(&self, empresa_id: ObjectId) -> Result<Vec<Produto>> {
+        Ok(self
+            .colecao::<Produto>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "ativo": true,
+                    "$expr": { "$lte": ["$estoque_atual", "$estoque_minimo"] },
+                },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+}
+
+#[async_trait]
+impl MovimentacoesRepositorio for MongoDb {
+    async fn listar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroMovimentacoes,
+    ) -> Result<Vec<MovimentacaoEstoque>> {
+        let mut consulta = doc! { "empresa_id": empresa_id };
+        if let Some(produto_id) = filtro.produto_id {
+            consulta.insert("produto_id", produto_id);
+        }
+        if let Some(tipo) = filtro.tipo {
+            consulta.insert("tipo", bson::to_bson(&tipo).unwrap());
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1, "_id": 1 })
+            .build();
+        Ok(self
+            .colecao::<MovimentacaoEstoque>()
+            .find(consulta, options)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<MovimentacaoEstoque>,
+    ) -> Result<Pagina<MovimentacaoEstoque>> {
+        Repositorio::<MovimentacaoEstoque>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn criar(&self, mut movimentacao: MovimentacaoEstoque) -> Result<MovimentacaoEstoque> {
+        movimentacao.id.get_or_insert_with(ObjectId::new);
+        self.colecao::<MovimentacaoEstoque>()
+            .insert_one(&movimentacao, None)
+            .await?;
+        Ok(movimentacao)
+    }
+}
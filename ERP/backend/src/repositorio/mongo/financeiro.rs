@@ -0,0 +1,700 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::async_trait;
+use chrono::NaiveDate;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, Bson, Document},
+    options::{FindOneOptions, FindOptions},
+};
+
+use super::dinheiro;
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{
+        Boleto, CobrancaPix, CompraCartao, Cotacao, Dinheiro, Lancamento, Moeda, Recorrencia,
+        StatusBoleto, StatusCobranca, StatusLancamento, TransacaoBancaria,
+    },
+    mongodb::MongoDb,
+    repositorio::{
+        BoletosRepositorio, CobrancasPixRepositorio, ComprasCartaoRepositorio, CotacoesRepositorio,
+        FiltroLancamentos, LancamentosRepositorio, RecorrenciasRepositorio, Repositorio,
+        TransacoesRepositorio,
+    },
+};
+
+/// Lê o resultado de um `$group` por id com o campo `total`.
+fn totais_por_id(grupos: Vec<Document>) -> HashMap<ObjectId, Dinheiro> {
+    grupos
+        .into_iter()
+        .filter_map(|grupo| {
+            let id = grupo.get_object_id("_id").ok()?;
+            let total = dinheiro(&grupo, "total");
+            Some((id, total))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl LancamentosRepositorio for MongoDb {
+    async fn filtrar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroLancamentos,
+    ) -> Result<Vec<Lancamento>> {
+        let mut consulta = doc! { "empresa_id": empresa_id };
+        if let Some(tipo) = filtro.tipo {
+            consulta.insert("tipo", bson::to_bson(&tipo).unwrap());
+        }
+        if let Some(status) = filtro.status {
+            consulta.insert("status", status.as_str());
+        }
+        if let Some(cliente_id) = filtro.cliente_id {
+            consulta.insert("cliente_id", cliente_id);
+        }
+        let mut vencimento = doc! {};
+        if let Some(de) = filtro.vencimento_de {
+            vencimento.insert("$gte", de.to_string());
+        }
+        if let Some(ate) = filtro.vencimento_ate {
+            vencimento.insert("$lte", ate.to_string());
+        }
+        if !vencimento.is_empty() {
+            consulta.insert("vencimento", vencimento);
+        }
+        let mut valor = doc! {};
+        if let Some(minimo) = filtro.valor_min {
+            valor.insert("$gte", Bson::from(minimo));
+        }
+        if let Some(maximo) = filtro.valor_max {
+            valor.insert("$lte", Bson::from(maximo));
+        }
+        if !valor.is_empty() {
+            consulta.insert("valor", valor);
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "vencimento": 1, "parcela": 1 })
+            .build();
+        Ok(self
+            .colecao::<Lancamento>()
+            .find(consulta, options)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn salvar_no_status(
+        &self,
+        lancamento: &Lancamento,
+        status: StatusLancamento,
+    ) -> Result<bool> {
+        let result = self
+            .colecao::<Lancamento>()
+            .replace_one(
+                doc! {
+                    "_id": lancamento.id,
+                    "empresa_id": lancamento.empresa_id,
+                    "status": status.as_str(),
+                },
+                lancamento,
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn excluir_nao_pago(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let result = self
+            .colecao::<Lancamento>()
+            .delete_one(
+                doc! {
+                    "_id": id,
+                    "empresa_id": empresa_id,
+                    "status": { "$ne": StatusLancamento::Pago.as_str() },
+                },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn existe_da_conta(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<bool> {
+        Ok(self
+            .colecao::<Lancamento>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "conta_id": conta_id },
+                None,
+            )
+            .await?
+            .is_some())
+    }
+
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<Lancamento>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "ocorrencia": 1 })
+            .build();
+        Ok(self
+            .colecao::<Lancamento>()
+            .find(
+                doc! { "empresa_id": empresa_id, "recorrencia_id": recorrencia_id },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn movimentado_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>> {
+        let mut filtro = doc! {
+            "empresa_id": empresa_id,
+            "status": StatusLancamento::Pago.as_str(),
+            "conta_id": { "$ne": null },
+        };
+        if let Some(conta_id) = conta_id {
+            filtro.insert("conta_id", conta_id);
+        }
+
+        let pipeline = vec![
+            doc! { "$match": filtro },
+            doc! {
+                "$group": {
+                    "_id": "$conta_id",
+                    "total": {
+                        "$sum": {
+                            "$cond": [
+                                { "$eq": ["$tipo", "RECEBER"] },
+                                "$valor_pago",
+                                { "$multiply": ["$valor_pago", -1] },
+                            ]
+                        }
+                    },
+                }
+            },
+        ];
+
+        let grupos: Vec<Document> = self
+            .colecao::<Lancamento>()
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(totais_por_id(grupos))
+    }
+}
+
+#[async_trait]
+impl ComprasCartaoRepositorio for MongoDb {
+    async fn criar_varias(&self, mut compras: Vec<CompraCartao>) -> Result<Vec<CompraCartao>> {
+        if compras.is_empty() {
+            return Ok(compras);
+        }
+        for compra in &mut compras {
+            compra.id.get_or_insert_with(ObjectId::new);
+        }
+        self.colecao::<CompraCartao>()
+            .insert_many(&compras, None)
+            .await?;
+        Ok(compras)
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<CompraCartao>,
+    ) -> Result<Pagina<CompraCartao>> {
+        Repositorio::<CompraCartao>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn listar_do_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "data_compra": -1, "parcela": 1 })
+            .build();
+        Ok(self
+            .colecao::<CompraCartao>()
+            .find(
+                doc! { "empresa_id": empresa_id, "cartao_id": cartao_id },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn da_competencia(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        competencia: &str,
+    ) -> Result<Vec<CompraCartao>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "data_compra": 1 })
+            .build();
+        Ok(self
+            .colecao::<CompraCartao>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "cartao_id": cartao_id,
+                    "competencia": competencia,
+                },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn contar_pagas(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64> {
+        Ok(self
+            .colecao::<CompraCartao>()
+            .count_documents(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "cartao_id": cartao_id,
+                    "compra_id": compra_id,
+                    "lancamento_id": { "$ne": null },
+                },
+                None,
+            )
+            .await?)
+    }
+
+    async fn excluir_compra(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64> {
+        let result = self
+            .colecao::<CompraCartao>()
+            .delete_many(
+                doc! { "empresa_id": empresa_id, "cartao_id": cartao_id, "compra_id": compra_id },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    async fn quitar(&self, ids: &[ObjectId], lancamento_id: ObjectId) -> Result<u64> {
+        let result = self
+            .colecao::<CompraCartao>()
+            .update_many(
+                doc! { "_id": { "$in": ids }, "lancamento_id": null },
+                doc! { "$set": { "lancamento_id": lancamento_id } },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count)
+    }
+
+    async fn reabrir(&self, lancamento_id: ObjectId) -> Result<()> {
+        self.colecao::<CompraCartao>()
+            .update_many(
+                doc! { "lancamento_id": lancamento_id },
+                doc! { "$set": { "lancamento_id": null } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "ocorrencia": 1 })
+            .build();
+        Ok(self
+            .colecao::<CompraCartao>()
+            .find(
+                doc! { "empresa_id": empresa_id, "recorrencia_id": recorrencia_id },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn utilizado_por_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>> {
+        let mut filtro = doc! { "empresa_id": empresa_id, "lancamento_id": null };
+        if let Some(cartao_id) = cartao_id {
+            filtro.insert("cartao_id", cartao_id);
+        }
+
+        let pipeline = vec![
+            doc! { "$match": filtro },
+            doc! { "$group": { "_id": "$cartao_id", "total": { "$sum": "$valor" } } },
+        ];
+
+        let grupos: Vec<Document> = self
+            .colecao::<CompraCartao>()
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+        Ok(totais_por_id(grupos))
+    }
+}
+
+#[async_trait]
+impl RecorrenciasRepositorio for MongoDb {
+    async fn ativas(&self) -> Result<Vec<Recorrencia>> {
+        Ok(self
+            .colecao::<Recorrencia>()
+            .find(doc! { "ativa": true }, None)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn salvar_geradas(&self, recorrencia: &Recorrencia, geradas: u32) -> Result<bool> {
+        let result = self
+            .colecao::<Recorrencia>()
+            .replace_one(
+                doc! {
+                    "_id": recorrencia.id,
+                    "empresa_id": recorrencia.empresa_id,
+                    "geradas": geradas,
+                },
+                recorrencia,
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+}
+
+#[async_trait]
+impl CotacoesRepositorio for MongoDb {
+    async fn na_data(
+        &self,
+        empresa_id: ObjectId,
+        moeda: Moeda,
+        data: NaiveDate,
+    ) -> Result<Option<Cotacao>> {
+        let options = FindOneOptions::builder().sort(doc! { "data": -1 }).build();
+        Ok(self
+            .colecao::<Cotacao>()
+            .find_one(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "moeda": moeda.codigo(),
+                    "data": { "$lte": data.to_string() },
+                },
+                options,
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl TransacoesRepositorio for MongoDb {
+    async fn identificadores_existentes(
+        &self,
+        conta_id: ObjectId,
+        identificadores: &[String],
+    ) -> Result<HashSet<String>> {
+        let existentes: Vec<TransacaoBancaria> = self
+            .colecao::<TransacaoBancaria>()
+            .find(
+                doc! { "conta_id": conta_id, "identificador": { "$in": identificadores } },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?;
+        Ok(existentes
+            .into_iter()
+            .map(|transacao| transacao.identificador)
+            .collect())
+    }
+
+    async fn criar_varias(
+        &self,
+        mut transacoes: Vec<TransacaoBancaria>,
+    ) -> Result<Vec<TransacaoBancaria>> {
+        if transacoes.is_empty() {
+            return Ok(transacoes);
+        }
+        for transacao in &mut transacoes {
+            transacao.id.get_or_insert_with(ObjectId::new);
+        }
+        self.colecao::<TransacaoBancaria>()
+            .insert_many(&transacoes, None)
+            .await?;
+        Ok(transacoes)
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<TransacaoBancaria>,
+    ) -> Result<Pagina<TransacaoBancaria>> {
+        Repositorio::<TransacaoBancaria>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn buscar(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        id: ObjectId,
+    ) -> Result<Option<TransacaoBancaria>> {
+        Ok(self
+            .colecao::<TransacaoBancaria>()
+            .find_one(
+                doc! { "_id": id, "empresa_id": empresa_id, "conta_id": conta_id },
+                None,
+            )
+            .await?)
+    }
+
+    async fn conciliar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<bool> {
+        let result = self
+            .colecao::<TransacaoBancaria>()
+            .update_one(
+                doc! { "_id": id, "lancamento_id": null },
+                doc! { "$set": { "lancamento_id": lancamento_id } },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn liberar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<()> {
+        self.colecao::<TransacaoBancaria>()
+            .update_one(
+                doc! { "_id": id, "lancamento_id": lancamento_id },
+                doc! { "$set": { "lancamento_id": null } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn desfazer_conciliacao(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<()> {
+        self.colecao::<TransacaoBancaria>()
+            .update_many(
+                doc! { "empresa_id": empresa_id, "lancamento_id": lancamento_id },
+                doc! { "$set": { "lancamento_id": null } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CobrancasPixRepositorio for MongoDb {
+    async fn buscar_por_txid(
+        &self,
+        empresa_id: ObjectId,
+        txid: &str,
+    ) -> Result<Option<CobrancaPix>> {
+        Ok(self
+            .colecao::<CobrancaPix>()
+            .find_one(doc! { "empresa_id": empresa_id, "txid": txid }, None)
+            .await?)
+    }
+
+    async fn pendentes(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+    ) -> Result<Vec<CobrancaPix>> {
+        Ok(self
+            .colecao::<CobrancaPix>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "conta_id": conta_id,
+                    "status": StatusCobranca::Pendente.as_str(),
+                },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn paga_pela_transacao(
+        &self,
+        empresa_id: ObjectId,
+        transacao_id: ObjectId,
+    ) -> Result<Option<CobrancaPix>> {
+        Ok(self
+            .colecao::<CobrancaPix>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "transacao_id": transacao_id },
+                None,
+            )
+            .await?)
+    }
+}
+
+#[async_trait]
+impl BoletosRepositorio for MongoDb {
+    async fn do_lancamento(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<Option<Boleto>> {
+        Ok(self
+            .colecao::<Boleto>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "lancamento_id": lancamento_id },
+                None,
+            )
+            .await?)
+    }
+
+    async fn proximo_sequencial(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u64> {
+        let chave = format!("boletos:{}:{}:sequencial", empresa_id, conta_id);
+        self.proximo_numero(&chave, || self.maior_sequencial(empresa_id, conta_id))
+            .await
+    }
+
+    async fn proxima_remessa(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u32> {
+        let chave = format!("boletos:{}:{}:remessa", empresa_id, conta_id);
+        let numero = self
+            .proximo_numero(&chave, || self.maior_remessa(empresa_id, conta_id))
+            .await?;
+        Ok(numero as u32)
+    }
+
+    async fn a_remeter(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<Vec<Boleto>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "sequencial": 1 })
+            .build();
+        Ok(self
+            .colecao::<Boleto>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "conta_id": conta_id,
+                    "status": StatusBoleto::Emitido.as_str(),
+                },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn remeter(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        ids: &[ObjectId],
+        remessa: u32,
+    ) -> Result<u64> {
+        let result = self
+            .colecao::<Boleto>()
+            .update_many(
+                doc! {
+                    "_id": { "$in": ids },
+                    "empresa_id": empresa_id,
+                    "conta_id": conta_id,
+                    "status": StatusBoleto::Emitido.as_str(),
+                },
+                doc! {
+                    "$set": {
+                        "status": StatusBoleto::Remetido.as_str(),
+                        "remessa": remessa,
+                    },
+                },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count)
+    }
+
+    async fn da_remessa(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        remessa: u32,
+    ) -> Result<Vec<Boleto>> {
+        let options = FindOptions::builder()
+            .sort(doc! { "sequencial": 1 })
+            .build();
+        Ok(self
+            .colecao::<Boleto>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "conta_id": conta_id,
+                    "remessa": remessa,
+                },
+                options,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+}
+
+/// Maiores números usados pelos boletos gravados antes das sequências.
+impl MongoDb {
+    async fn maior_sequencial(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u64> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "sequencial": -1 })
+            .build();
+        let ultimo = self
+            .colecao::<Boleto>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "conta_id": conta_id },
+                options,
+            )
+            .await?;
+        Ok(ultimo.map(|boleto| boleto.sequencial).unwrap_or(0))
+    }
+
+    async fn maior_remessa(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u64> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "remessa": -1 })
+            .build();
+        let ultimo = self
+            .colecao::<Boleto>()
+            .find_one(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "conta_id": conta_id,
+                    "remessa": { "$ne": null },
+                },
+                options,
+            )
+            .await?;
+        Ok(ultimo
+            .and_then(|boleto| boleto.remessa)
+            .map_or(0, u64::from))
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::{
+    bson::{self, doc},
+    options::IndexOptions,
+    IndexModel,
+};
+
+use crate::{
+    error::{chave_duplicada, AppError, Result},
+    models::{RequisicaoIdempotente, RespostaGravada},
+    mongodb::MongoDb,
+    repositorio::IdempotenciaRepositorio,
+};
+
+/// Os registros expiram sozinhos um dia depois de criados; a conferência
+/// de `validas_desde` cobre o intervalo até o MongoDB removê-los.
+pub async fn preparar(mongo: &MongoDb) -> anyhow::Result<()> {
+    let indice = IndexModel::builder()
+        .keys(doc! { "created_at": 1 })
+        .options(
+            IndexOptions::builder()
+                .expire_after(Duration::from_secs(24 * 60 * 60))
+                .build(),
+        )
+        .build();
+    mongo.idempotencia().create_index(indice, None).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl IdempotenciaRepositorio for MongoDb {
+    async fn reservar(
+        &self,
+        requisicao: &RequisicaoIdempotente,
+        validas_desde: DateTime<Utc>,
+    ) -> Result<Option<RequisicaoIdempotente>> {
+        let colecao = self.idempotencia();
+        colecao
+            .delete_one(
+                doc! {
+                    "_id": &requisicao.id,
+                    "created_at": { "$lt": bson::DateTime::from_chrono(validas_desde) },
+                },
+                None,
+            )
+            .await?;
+
+        loop {
+            match colecao.insert_one(requisicao, None).await {
+                Ok(_) => return Ok(None),
+                Err(e) if chave_duplicada(&e) => {
+                    // Pode ter sido liberada entre a inserção e a leitura.
+                    if let Some(existente) = colecao
+                        .find_one(doc! { "_id": &requisicao.id }, None)
+                        .await?
+                    {
+                        return Ok(Some(existente));
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn concluir(&self, id: &str, resposta: &RespostaGravada) -> Result<()> {
+        let resposta = bson::to_bson(resposta).map_err(|e| AppError::Internal(e.to_string()))?;
+        self.idempotencia()
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "resposta": resposta } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn liberar(&self, id: &str) -> Result<()> {
+        self.idempotencia()
+            .delete_one(doc! { "_id": id, "resposta": null }, None)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,543 @@
+//! Repositórios sobre o MongoDB. Cada agregado é uma coleção com o nome de
+//! [`Registro::COLECAO`].
+
+mod auditoria;
+mod cadastros;
+mod compras;
+mod contabilidade;
+mod estoque;
+mod financeiro;
+mod idempotencia;
+mod vendas;
+
+use std::future::Future;
+
+use axum::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{self, doc, oid::ObjectId, Bson, Document},
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument},
+    Collection, IndexModel,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{Registro, Repositorio, RepositorioVersionado, Versionado};
+use crate::{
+    cartoes,
+    consulta::{Consulta, Listavel, Operador, Pagina, ValorFiltro},
+    error::{chave_duplicada, AppError, Result},
+    models::{Boleto, Dinheiro, NotaFiscal, Produto, TransacaoBancaria},
+    mongodb::MongoDb,
+};
+
+#[async_trait]
+impl<T> Repositorio<T> for MongoDb
+where
+    T: Registro + Listavel + Serialize + DeserializeOwned + Unpin + Send + Sync + 'static,
+{
+    async fn listar(&self, empresa_id: ObjectId) -> Result<Vec<T>> {
+        Ok(self
+            .colecao::<T>()
+            .find(doc! { "empresa_id": empresa_id }, None)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn paginar(&self, empresa_id: ObjectId, consulta: &Consulta<T>) -> Result<Pagina<T>> {
+        paginar_colecao(
+            self.colecao::<T>(),
+            doc! { "empresa_id": empresa_id },
+            consulta,
+        )
+        .await
+    }
+
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<T>> {
+        Ok(self
+            .colecao::<T>()
+            .find_one(doc! { "_id": id, "empresa_id": empresa_id }, None)
+            .await?)
+    }
+
+    async fn criar(&self, mut registro: T) -> Result<T> {
+        if registro.id().is_none() {
+            registro.definir_id(ObjectId::new());
+        }
+        self.colecao::<T>().insert_one(&registro, None).await?;
+        Ok(registro)
+    }
+
+    async fn criar_varios(&self, mut registros: Vec<T>) -> Result<Vec<T>> {
+        if registros.is_empty() {
+            return Ok(registros);
+        }
+        for registro in registros
+            .iter_mut()
+            .filter(|registro| registro.id().is_none())
+        {
+            registro.definir_id(ObjectId::new());
+        }
+        self.colecao::<T>().insert_many(&registros, None).await?;
+        Ok(registros)
+    }
+
+    async fn salvar(&self, registro: &T) -> Result<bool> {
+        let result = self
+            .colecao::<T>()
+            .replace_one(
+                doc! { "_id": registro.id(), "empresa_id": registro.empresa_id() },
+                registro,
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn excluir(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let result = self
+            .colecao::<T>()
+            .delete_one(doc! { "_id": id, "empresa_id": empresa_id }, None)
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
+
+#[async_trait]
+impl<T> RepositorioVersionado<T> for MongoDb
+where
+    T: Versionado + Listavel + Serialize + DeserializeOwned + Unpin + Send + Sync + 'static,
+{
+    async fn salvar_versao(&self, registro: &mut T) -> Result<bool> {
+        let lida = registro.versao();
+        registro.definir_versao(lida + 1);
+        let result = self
+            .colecao::<T>()
+            .replace_one(
+                doc! {
+                    "_id": registro.id(),
+                    "empresa_id": registro.empresa_id(),
+                    "versao": filtro_versao(lida),
+                },
+                &*registro,
+                None,
+            )
+            .await?;
+        if result.matched_count == 0 {
+            registro.definir_versao(lida);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    async fn excluir_versao(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        versao: u32,
+    ) -> Result<bool> {
+        let result = self
+            .colecao::<T>()
+            .delete_one(
+                doc! { "_id": id, "empresa_id": empresa_id, "versao": filtro_versao(versao) },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
+
+/// Os documentos gravados antes do controle de versão não têm o campo e
+/// estão na versão 0.
+fn filtro_versao(versao: u32) -> Bson {
+    if versao == 0 {
+        Bson::Document(doc! { "$in": [0, Bson::Null] })
+    } else {
+        Bson::Int64(i64::from(versao))
+    }
+}
+
+/// Uma página dos documentos da coleção dentro do `escopo` que atendem à
+/// consulta.
+async fn paginar_colecao<T>(
+    colecao: Collection<T>,
+    escopo: Document,
+    consulta: &Consulta<T>,
+) -> Result<Pagina<T>>
+where
+    T: Listavel + DeserializeOwned + Unpin + Send + Sync,
+{
+    let filtro = filtro_da_consulta(escopo, consulta);
+    let direcao = if consulta.ordem.decrescente { -1 } else { 1 };
+    let options = FindOptions::builder()
+        .sort(doc! { consulta.ordem.campo: direcao, "_id": direcao })
+        .skip(consulta.deslocamento())
+        .limit(consulta.limite as i64)
+        .build();
+
+    let total = colecao.count_documents(filtro.clone(), None).await?;
+    let itens = colecao.find(filtro, options).await?.try_collect().await?;
+    Ok(consulta.pagina(itens, total))
+}
+
+/// Filtro do MongoDB com os filtros e a busca da consulta, dentro do
+/// `escopo`.
+fn filtro_da_consulta<T: Listavel>(escopo: Document, consulta: &Consulta<T>) -> Document {
+    let mut condicoes: Vec<Document> = consulta
+        .filtros
+        .iter()
+        .map(|filtro| {
+            let valor = bson_do_valor(&filtro.valor);
+            let condicao = match filtro.operador {
+                Operador::Igual => valor,
+                Operador::De => Bson::Document(doc! { "$gte": valor }),
+                Operador::Ate => Bson::Document(doc! { "$lte": valor }),
+                Operador::Preenchido if filtro.valor == ValorFiltro::Booleano(true) => {
+                    Bson::Document(doc! { "$ne": null })
+                }
+                Operador::Preenchido => Bson::Null,
+            };
+            doc! { filtro.campo: condicao }
+        })
+        .collect();
+
+    if let Some(busca) = &consulta.busca {
+        let padrao = escapar_regex(busca);
+        let alternativas: Vec<Document> = T::BUSCA
+            .iter()
+            .map(|campo| doc! { *campo: { "$regex": &padrao, "$options": "i" } })
+            .collect();
+        condicoes.push(doc! { "$or": alternativas });
+    }
+
+    let mut filtro = escopo;
+    if !condicoes.is_empty() {
+        filtro.insert("$and", condicoes);
+    }
+    filtro
+}
+
+fn bson_do_valor(valor: &ValorFiltro) -> Bson {
+    match valor {
+        ValorFiltro::Texto(texto) => Bson::String(texto.clone()),
+        ValorFiltro::Numero(numero) => Bson::Double(*numero),
+        ValorFiltro::Dinheiro(valor) => Bson::from(*valor),
+        ValorFiltro::Booleano(booleano) => Bson::Boolean(*booleano),
+        // Datas sem horário são gravadas como texto `AAAA-MM-DD`.
+        ValorFiltro::Data(data) => Bson::String(data.to_string()),
+        ValorFiltro::Instante(instante) => Bson::DateTime(bson::DateTime::from_chrono(*instante)),
+        ValorFiltro::Id(id) => Bson::ObjectId(*id),
+    }
+}
+
+/// A busca é por texto literal, não por expressão regular.
+fn escapar_regex(texto: &str) -> String {
+    let mut escapado = String::with_capacity(texto.len());
+    for caractere in texto.chars() {
+        if "\\.+*?()|[]{}^$-".contains(caractere) {
+            escapado.push('\\');
+        }
+        escapado.push(caractere);
+    }
+    escapado
+}
+
+/// Lê um valor monetário de um resultado de agregação; `$sum` devolve
+/// Decimal128 sobre Decimal128 e zero quando não há documentos.
+fn dinheiro(documento: &Document, campo: &str) -> Dinheiro {
+    documento
+        .get(campo)
+        .and_then(|valor| Dinheiro::try_from(valor).ok())
+        .unwrap_or_default()
+}
+
+/// Contadores dos números que não podem se repetir mesmo com requisições
+/// simultâneas: `{ _id: chave, valor }`, com o último número reservado.
+const SEQUENCIAS: &str = "sequencias";
+
+impl MongoDb {
+    /// Reserva o próximo número da sequência `chave` com `$inc`. Na
+    /// primeira vez a sequência parte de `ultimo`, o maior número já usado
+    /// pelos documentos gravados antes dela.
+    async fn proximo_numero<F>(&self, chave: &str, ultimo: impl Fn() -> F + Send) -> Result<u64>
+    where
+        F: Future<Output = Result<u64>> + Send,
+    {
+        let sequencias = self.documentos(SEQUENCIAS);
+        loop {
+            let options = FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build();
+            if let Some(sequencia) = sequencias
+                .find_one_and_update(
+                    doc! { "_id": chave },
+                    doc! { "$inc": { "valor": 1_i64 } },
+                    options,
+                )
+                .await?
+            {
+                return Ok(sequencia.get_i64("valor").unwrap_or_default() as u64);
+            }
+
+            // Outra requisição pode criar a sequência antes; aí o próximo
+            // passo do laço a incrementa.
+            let proximo = ultimo().await? + 1;
+            match sequencias
+                .insert_one(doc! { "_id": chave, "valor": proximo as i64 }, None)
+                .await
+            {
+                Ok(_) => return Ok(proximo),
+                Err(erro) if chave_duplicada(&erro) => continue,
+                Err(erro) => return Err(erro.into()),
+            }
+        }
+    }
+}
+
+/// Ajustes em documentos gravados por versões anteriores do backend.
+pub async fn preparar(mongo: &MongoDb) -> anyhow::Result<()> {
+    mascarar_cartoes(mongo).await?;
+    idempotencia::preparar(mongo).await?;
+    criar_indices_unicos(mongo).await?;
+    converter_valores(mongo).await?;
+    converter_fiscal_produtos(mongo).await
+}
+
+/// Converte um valor para BSON pelo serializador binário, o mesmo com que
+/// o driver grava os documentos tipados: dinheiro e alíquotas ficam em
+/// Decimal128. O `bson::to_bson` usa a forma legível e os gravaria como
+/// texto.
+fn para_bson<T: Serialize + ?Sized>(valor: &T) -> Result<Bson> {
+    #[derive(Serialize)]
+    struct Envelope<'a, T: ?Sized> {
+        valor: &'a T,
+    }
+
+    let mut documento = bson::to_raw_document_buf(&Envelope { valor })
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .to_document()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(documento.remove("valor").unwrap_or(Bson::Null))
+}
+
+/// Os mesmos `UNIQUE` das migrations SQL, para que gravações concorrentes
+/// não dupliquem registros.
+async fn criar_indices_unicos(mongo: &MongoDb) -> anyhow::Result<()> {
+    let indices = [
+        (
+            TransacaoBancaria::COLECAO,
+            doc! { "empresa_id": 1, "conta_id": 1, "identificador": 1 },
+        ),
+        (
+            Boleto::COLECAO,
+            doc! { "empresa_id": 1, "conta_id": 1, "sequencial": 1 },
+        ),
+        (NotaFiscal::COLECAO, doc! { "chave": 1 }),
+        (
+            NotaFiscal::COLECAO,
+            doc! { "empresa_id": 1, "modelo": 1, "serie": 1, "numero": 1 },
+        ),
+        (NotaFiscal::COLECAO, doc! { "empresa_id": 1, "venda_id": 1 }),
+    ];
+    for (colecao, chaves) in indices {
+        let indice = IndexModel::builder()
+            .keys(chaves)
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        mongo.documentos(colecao).create_index(indice, None).await?;
+    }
+    Ok(())
+}
+
+/// Substitui o número completo dos cartões gravados antes da máscara pelos
+/// quatro últimos dígitos, preenchendo a bandeira quando ela estiver vazia.
+async fn mascarar_cartoes(mongo: &MongoDb) -> anyhow::Result<()> {
+    let colecao = mongo.documentos("cartoes");
+    let antigos: Vec<Document> = colecao
+        .find(doc! { "numero": { "$exists": true } }, None)
+        .await?
+        .try_collect()
+        .await?;
+
+    for cartao in &antigos {
+        let numero = cartao.get_str("numero").unwrap_or_default();
+        let mut set = doc! { "ultimos_digitos": cartoes::ultimos_digitos(numero) };
+        if cartao
+            .get_str("bandeira")
+            .unwrap_or_default()
+            .trim()
+            .is_empty()
+        {
+            if let Some(bandeira) = cartoes::detectar_bandeira(numero) {
+                set.insert("bandeira", bandeira);
+            }
+        }
+
+        colecao
+            .update_one(
+                doc! { "_id": cartao.get_object_id("_id")? },
+                doc! { "$set": set, "$unset": { "numero": "" } },
+                None,
+            )
+            .await?;
+    }
+
+    if !antigos.is_empty() {
+        tracing::info!(
+            "💳 {} cartão(ões) migrados para guardar apenas os últimos dígitos",
+            antigos.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Campos monetários por coleção; `lista.campo` indica um campo dos itens de
+/// uma lista.
+const CAMPOS_MONETARIOS: &[(&str, &[&str])] = &[
+    ("produtos", &["preco_custo", "preco_venda"]),
+    (
+        "vendas",
+        &[
+            "total",
+            "desconto",
+            "total_final",
+            "itens.preco_unitario",
+            "itens.subtotal",
+        ],
+    ),
+    ("contas_bancarias", &["saldo"]),
+    ("cartoes", &["limite"]),
+    ("lancamentos", &["valor", "valor_pago"]),
+    ("compras_cartao", &["valor"]),
+    ("transacoes_bancarias", &["valor"]),
+    ("partidas", &["linhas.debito", "linhas.credito"]),
+];
+
+/// Regrava como Decimal128 os valores que versões anteriores guardavam em
+/// ponto flutuante, arredondando-os para centavos.
+async fn converter_valores(mongo: &MongoDb) -> anyhow::Result<()> {
+    for (nome, campos) in CAMPOS_MONETARIOS {
+        let colecao = mongo.documentos(nome);
+        let antigos: Vec<Document> = colecao
+            .find(
+                doc! {
+                    "$or": campos
+                        .iter()
+                        .map(|campo| doc! { *campo: { "$type": ["double", "int", "long"] } })
+                        .collect::<Vec<_>>()
+                },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?;
+
+        for documento in &antigos {
+            let mut set = Document::new();
+            for campo in campos.iter() {
+                let (raiz, item) = match campo.split_once('.') {
+                    Some((lista, campo)) => (lista, Some(campo)),
+                    None => (*campo, None),
+                };
+                let mut valor = set
+                    .get(raiz)
+                    .or_else(|| documento.get(raiz))
+                    .cloned()
+                    .unwrap_or(Bson::Null);
+                let convertido = match (item, &mut valor) {
+                    (None, valor) => converter_valor(valor),
+                    (Some(item), Bson::Array(itens)) => {
+                        itens.iter_mut().fold(false, |convertido, elemento| {
+                            let valor = elemento.as_document_mut().and_then(|d| d.get_mut(item));
+                            valor.is_some_and(converter_valor) || convertido
+                        })
+                    }
+                    _ => false,
+                };
+                if convertido {
+                    set.insert(raiz, valor);
+                }
+            }
+
+            colecao
+                .update_one(
+                    doc! { "_id": documento.get_object_id("_id")? },
+                    doc! { "$set": set },
+                    None,
+                )
+                .await?;
+        }
+
+        if !antigos.is_empty() {
+            tracing::info!(
+                "💰 {} registro(s) de {} com valores convertidos para Decimal128",
+                antigos.len(),
+                nome
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Regrava como Decimal128 as alíquotas dos dados fiscais de produtos que
+/// versões anteriores gravavam como texto ao alterar o cadastro.
+async fn converter_fiscal_produtos(mongo: &MongoDb) -> anyhow::Result<()> {
+    let antigos: Vec<Produto> = mongo
+        .colecao::<Produto>()
+        .find(doc! { "fiscal.aliquota_icms": { "$type": "string" } }, None)
+        .await?
+        .try_collect()
+        .await?;
+
+    for produto in &antigos {
+        mongo
+            .colecao::<Produto>()
+            .update_one(
+                doc! { "_id": produto.id },
+                doc! { "$set": { "fiscal": para_bson(&produto.fiscal)? } },
+                None,
+            )
+            .await?;
+    }
+
+    if !antigos.is_empty() {
+        tracing::info!(
+            "💰 {} produto(s) com alíquotas convertidas para Decimal128",
+            antigos.len()
+        );
+    }
+    Ok(())
+}
+
+fn converter_valor(valor: &mut Bson) -> bool {
+    if !matches!(valor, Bson::Double(_) | Bson::Int32(_) | Bson::Int64(_)) {
+        return false;
+    }
+    match Dinheiro::try_from(&*valor) {
+        Ok(dinheiro) => {
+            *valor = dinheiro.into();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use erp_dinheiro::Aliquota;
+
+    use super::*;
+
+    #[test]
+    fn aliquotas_vao_como_decimal128() {
+        let aliquota: Aliquota = "18.00".parse().unwrap();
+        assert!(matches!(para_bson(&aliquota).unwrap(), Bson::Decimal128(_)));
+        assert!(matches!(bson::to_bson(&aliquota).unwrap(), Bson::String(_)));
+        assert!(matches!(
+            para_bson(&Some(aliquota)).unwrap(),
+            Bson::Decimal128(_)
+        ));
+    }
+}
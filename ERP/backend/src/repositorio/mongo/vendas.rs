@@ -0,0 +1,144 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::FindOneOptions,
+};
+
+use crate::{
+    error::Result,
+    models::{ModeloNota, NotaFiscal, StatusVenda, Venda},
+    mongodb::MongoDb,
+    repositorio::{NotasFiscaisRepositorio, VendasRepositorio},
+};
+
+#[async_trait]
+impl VendasRepositorio for MongoDb {
+    async fn finalizadas_desde(
+        &self,
+        empresa_id: ObjectId,
+        desde: DateTime<Utc>,
+    ) -> Result<Vec<Venda>> {
+        Ok(self
+            .colecao::<Venda>()
+            .find(
+                doc! {
+                    "empresa_id": empresa_id,
+                    "status": StatusVenda::Finalizada.as_str(),
+                    "created_at": { "$gte": bson::DateTime::from_chrono(desde) },
+                },
+                None,
+            )
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn salvar_aberta(&self, venda: &Venda) -> Result<bool> {
+        let result = self
+            .colecao::<Venda>()
+            .replace_one(
+                doc! {
+                    "_id": venda.id,
+                    "empresa_id": venda.empresa_id,
+                    "status": StatusVenda::Aberta.as_str(),
+                },
+                venda,
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn transicionar(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        de: StatusVenda,
+        para: StatusVenda,
+    ) -> Result<bool> {
+        let result = self
+            .colecao::<Venda>()
+            .update_one(
+                doc! { "_id": id, "empresa_id": empresa_id, "status": de.as_str() },
+                doc! {
+                    "$set": {
+                        "status": para.as_str(),
+                        "updated_at": bson::DateTime::from_chrono(Utc::now()),
+                    }
+                },
+                None,
+            )
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    async fn excluir_aberta(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let result = self
+            .colecao::<Venda>()
+            .delete_one(
+                doc! {
+                    "_id": id,
+                    "empresa_id": empresa_id,
+                    "status": StatusVenda::Aberta.as_str(),
+                },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
+}
+
+#[async_trait]
+impl NotasFiscaisRepositorio for MongoDb {
+    async fn da_venda(
+        &self,
+        empresa_id: ObjectId,
+        venda_id: ObjectId,
+    ) -> Result<Option<NotaFiscal>> {
+        Ok(self
+            .colecao::<NotaFiscal>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "venda_id": venda_id },
+                None,
+            )
+            .await?)
+    }
+
+    async fn proximo_numero_da_serie(
+        &self,
+        empresa_id: ObjectId,
+        modelo: ModeloNota,
+        serie: u32,
+    ) -> Result<u32> {
+        let chave = format!("notas_fiscais:{}:{}:{}", empresa_id, modelo.as_str(), serie);
+        let numero = self
+            .proximo_numero(&chave, || {
+                self.maior_numero_da_serie(empresa_id, modelo, serie)
+            })
+            .await?;
+        Ok(numero as u32)
+    }
+}
+
+impl MongoDb {
+    async fn maior_numero_da_serie(
+        &self,
+        empresa_id: ObjectId,
+        modelo: ModeloNota,
+        serie: u32,
+    ) -> Result<u64> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "numero": -1 })
+            .build();
+        let ultima = self
+            .colecao::<NotaFiscal>()
+            .find_one(
+                doc! { "empresa_id": empresa_id, "modelo": modelo.as_str(), "serie": serie },
+                options,
+            )
+            .await?;
+        Ok(ultima.map(|nota| nota.numero as u64).unwrap_or(0))
+    }
+}
@@ -0,0 +1,71 @@
+use axum::async_trait;
+use mongodb::bson::oid::ObjectId;
+
+use super::{Linha, Sql, Tabela, Valor};
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::RegistroAuditoria,
+    repositorio::{AuditoriaRepositorio, Repositorio},
+};
+
+/// As alterações ficam em JSON, pois só são lidas junto com o registro.
+impl Tabela for RegistroAuditoria {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "entidade",
+        "entidade_id",
+        "operacao",
+        "usuario_id",
+        "usuario",
+        "requisicao_id",
+        "instante",
+        "alteracoes",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.entidade.clone().into(),
+            self.entidade_id.into(),
+            self.operacao.as_str().into(),
+            self.usuario_id.into(),
+            self.usuario.clone().into(),
+            self.requisicao_id.clone().into(),
+            self.instante.into(),
+            Valor::json(&self.alteracoes),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            entidade: linha.texto("entidade")?,
+            entidade_id: linha.oid("entidade_id")?,
+            operacao: linha.enumerado("operacao")?,
+            usuario_id: linha.oid("usuario_id")?,
+            usuario: linha.texto("usuario")?,
+            requisicao_id: linha.texto("requisicao_id")?,
+            instante: linha.instante("instante")?,
+            alteracoes: linha.json("alteracoes")?,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditoriaRepositorio for Sql {
+    async fn registrar(&self, registro: RegistroAuditoria) -> Result<RegistroAuditoria> {
+        Repositorio::<RegistroAuditoria>::criar(self, registro).await
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<RegistroAuditoria>,
+    ) -> Result<Pagina<RegistroAuditoria>> {
+        Repositorio::<RegistroAuditoria>::paginar(self, empresa_id, consulta).await
+    }
+}
@@ -0,0 +1,530 @@
+use axum::async_trait;
+use mongodb::bson::oid::ObjectId;
+
+use super::{marcadores, selecionar, Linha, Sql, Tabela, Valor};
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{
+        Banco, Cartao, CarteiraBoleto, ChavePix, Cliente, ContaBancaria, Empresa, Fornecedor,
+        Usuario,
+    },
+    repositorio::{BancosRepositorio, EmpresasRepositorio, Registro, UsuariosRepositorio},
+};
+
+impl Tabela for Empresa {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "nome",
+        "documento",
+        "pais",
+        "fiscal",
+        "ativo",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.nome.clone().into(),
+            self.documento.clone().into(),
+            self.pais.clone().into(),
+            Valor::json_opt(self.fiscal.as_ref()),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            nome: linha.texto("nome")?,
+            documento: linha.texto_opt("documento")?,
+            pais: linha.texto("pais")?,
+            fiscal: linha.json_opt("fiscal")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+impl Tabela for Usuario {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "nome",
+        "email",
+        "senha_hash",
+        "papeis",
+        "empresas",
+        "ativo",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.nome.clone().into(),
+            self.email.clone().into(),
+            self.senha_hash.clone().into(),
+            Valor::json(&self.papeis),
+            Valor::json(&self.empresas),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            nome: linha.texto("nome")?,
+            email: linha.texto("email")?,
+            senha_hash: linha.texto("senha_hash")?,
+            papeis: linha.json("papeis")?,
+            empresas: linha.json("empresas")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+impl Tabela for Banco {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "codigo",
+        "nome",
+        "pais",
+        "tipo",
+        "ativo",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.codigo.clone().into(),
+            self.nome.clone().into(),
+            self.pais.clone().into(),
+            self.tipo.clone().into(),
+            self.ativo.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            codigo: linha.texto("codigo")?,
+            nome: linha.texto("nome")?,
+            pais: linha.texto("pais")?,
+            tipo: linha.texto("tipo")?,
+            ativo: linha.booleano("ativo")?,
+        })
+    }
+}
+
+impl Tabela for Cliente {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "nome",
+        "cpf_cnpj",
+        "telefone",
+        "email",
+        "endereco",
+        "numero",
+        "bairro",
+        "cidade",
+        "codigo_municipio",
+        "estado",
+        "cep",
+        "ativo",
+        "created_at",
+        "updated_at",
+        "versao",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.nome.clone().into(),
+            self.cpf_cnpj.clone().into(),
+            self.telefone.clone().into(),
+            self.email.clone().into(),
+            self.endereco.clone().into(),
+            self.numero.clone().into(),
+            self.bairro.clone().into(),
+            self.cidade.clone().into(),
+            self.codigo_municipio.clone().into(),
+            self.estado.clone().into(),
+            self.cep.clone().into(),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+            self.versao.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            nome: linha.texto("nome")?,
+            cpf_cnpj: linha.texto("cpf_cnpj")?,
+            telefone: linha.texto_opt("telefone")?,
+            email: linha.texto_opt("email")?,
+            endereco: linha.texto_opt("endereco")?,
+            numero: linha.texto_opt("numero")?,
+            bairro: linha.texto_opt("bairro")?,
+            cidade: linha.texto_opt("cidade")?,
+            codigo_municipio: linha.texto_opt("codigo_municipio")?,
+            estado: linha.texto_opt("estado")?,
+            cep: linha.texto_opt("cep")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+            versao: linha.inteiro("versao")? as u32,
+        })
+    }
+}
+
+impl Tabela for Fornecedor {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "nome",
+        "cpf_cnpj",
+        "contato",
+        "telefone",
+        "email",
+        "endereco",
+        "cidade",
+        "estado",
+        "cep",
+        "ativo",
+        "created_at",
+        "updated_at",
+        "versao",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.nome.clone().into(),
+            self.cpf_cnpj.clone().into(),
+            self.contato.clone().into(),
+            self.telefone.clone().into(),
+            self.email.clone().into(),
+            self.endereco.clone().into(),
+            self.cidade.clone().into(),
+            self.estado.clone().into(),
+            self.cep.clone().into(),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+            self.versao.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            nome: linha.texto("nome")?,
+            cpf_cnpj: linha.texto("cpf_cnpj")?,
+            contato: linha.texto_opt("contato")?,
+            telefone: linha.texto_opt("telefone")?,
+            email: linha.texto_opt("email")?,
+            endereco: linha.texto_opt("endereco")?,
+            cidade: linha.texto_opt("cidade")?,
+            estado: linha.texto_opt("estado")?,
+            cep: linha.texto_opt("cep")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+            versao: linha.inteiro("versao")? as u32,
+        })
+    }
+}
+
+impl Tabela for ContaBancaria {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "banco",
+        "agencia",
+        "conta",
+        "tipo",
+        "saldo",
+        "moeda",
+        "pix_chave",
+        "pix_cidade",
+        "boleto_banco",
+        "boleto_carteira",
+        "boleto_convenio",
+        "created_at",
+        "updated_at",
+        "versao",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.banco.clone().into(),
+            self.agencia.clone().into(),
+            self.conta.clone().into(),
+            self.tipo.clone().into(),
+            self.saldo.into(),
+            Valor::enumerado(&self.moeda),
+            self.pix.as_ref().map(|pix| pix.chave.clone()).into(),
+            self.pix.as_ref().map(|pix| pix.cidade.clone()).into(),
+            self.boleto
+                .as_ref()
+                .map(|boleto| boleto.banco.clone())
+                .into(),
+            self.boleto
+                .as_ref()
+                .map(|boleto| boleto.carteira.clone())
+                .into(),
+            self.boleto
+                .as_ref()
+                .and_then(|boleto| boleto.convenio.clone())
+                .into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+            self.versao.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            banco: linha.texto("banco")?,
+            agencia: linha.texto("agencia")?,
+            conta: linha.texto("conta")?,
+            tipo: linha.texto("tipo")?,
+            saldo: linha.dinheiro("saldo")?,
+            moeda: linha.enumerado("moeda")?,
+            pix: match (
+                linha.texto_opt("pix_chave")?,
+                linha.texto_opt("pix_cidade")?,
+            ) {
+                (Some(chave), Some(cidade)) => Some(ChavePix { chave, cidade }),
+                _ => None,
+            },
+            boleto: match (
+                linha.texto_opt("boleto_banco")?,
+                linha.texto_opt("boleto_carteira")?,
+            ) {
+                (Some(banco), Some(carteira)) => Some(CarteiraBoleto {
+                    banco,
+                    carteira,
+                    convenio: linha.texto_opt("boleto_convenio")?,
+                }),
+                _ => None,
+            },
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+            versao: linha.inteiro("versao")? as u32,
+        })
+    }
+}
+
+impl Tabela for Cartao {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "banco",
+        "ultimos_digitos",
+        "bandeira",
+        "limite",
+        "vencimento",
+        "fechamento",
+        "created_at",
+        "updated_at",
+        "versao",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.banco.clone().into(),
+            self.ultimos_digitos.clone().into(),
+            self.bandeira.clone().into(),
+            self.limite.into(),
+            self.vencimento.into(),
+            self.fechamento.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+            self.versao.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            banco: linha.texto("banco")?,
+            ultimos_digitos: linha.texto("ultimos_digitos")?,
+            bandeira: linha.texto("bandeira")?,
+            limite: linha.dinheiro("limite")?,
+            vencimento: linha.inteiro("vencimento")? as i32,
+            fechamento: linha.inteiro_opt("fechamento")?.map(|dia| dia as i32),
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+            versao: linha.inteiro("versao")? as u32,
+        })
+    }
+}
+
+#[async_trait]
+impl EmpresasRepositorio for Sql {
+    async fn listar(&self, ids: Option<&[ObjectId]>) -> Result<Vec<Empresa>> {
+        let Some(ids) = ids else {
+            return self
+                .consultar(&selecionar::<Empresa>("empresas"), Vec::new())
+                .await;
+        };
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "{} WHERE id IN ({})",
+            selecionar::<Empresa>("empresas"),
+            marcadores(1, ids.len())
+        );
+        self.consultar(&sql, ids.iter().map(|&id| id.into()).collect())
+            .await
+    }
+
+    async fn paginar(
+        &self,
+        ids: Option<&[ObjectId]>,
+        consulta: &Consulta<Empresa>,
+    ) -> Result<Pagina<Empresa>> {
+        let Some(ids) = ids else {
+            return self
+                .paginar_tabela("empresas", Vec::new(), Vec::new(), consulta)
+                .await;
+        };
+        if ids.is_empty() {
+            return Ok(consulta.pagina(Vec::new(), 0));
+        }
+
+        let condicao = format!("id IN ({})", marcadores(1, ids.len()));
+        let parametros = ids.iter().map(|&id| id.into()).collect();
+        self.paginar_tabela("empresas", vec![condicao], parametros, consulta)
+            .await
+    }
+
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Empresa>> {
+        let sql = format!("{} WHERE id = $1", selecionar::<Empresa>("empresas"));
+        self.consultar_um(&sql, vec![id.into()]).await
+    }
+
+    async fn primeira(&self) -> Result<Option<Empresa>> {
+        let sql = format!(
+            "{} ORDER BY created_at LIMIT 1",
+            selecionar::<Empresa>("empresas")
+        );
+        self.consultar_um(&sql, Vec::new()).await
+    }
+
+    async fn criar(&self, mut empresa: Empresa) -> Result<Empresa> {
+        empresa.id.get_or_insert_with(ObjectId::new);
+        self.inserir("empresas", std::slice::from_ref(&empresa))
+            .await?;
+        Ok(empresa)
+    }
+
+    async fn salvar(&self, empresa: &Empresa) -> Result<bool> {
+        self.atualizar("empresas", empresa, 1, None).await
+    }
+
+    /// No SQL o `empresa_id` é obrigatório desde a criação das tabelas;
+    /// restam apenas os usuários sem empresa.
+    async fn atribuir_sem_empresa(&self, empresa_id: ObjectId) -> Result<()> {
+        self.executar(
+            "UPDATE usuarios SET empresas = $1 WHERE empresas = '[]'",
+            vec![Valor::json(&[empresa_id])],
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UsuariosRepositorio for Sql {
+    async fn paginar(&self, consulta: &Consulta<Usuario>) -> Result<Pagina<Usuario>> {
+        self.paginar_tabela("usuarios", Vec::new(), Vec::new(), consulta)
+            .await
+    }
+
+    async fn buscar(&self, id: ObjectId) -> Result<Option<Usuario>> {
+        let sql = format!("{} WHERE id = $1", selecionar::<Usuario>("usuarios"));
+        self.consultar_um(&sql, vec![id.into()]).await
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> Result<Option<Usuario>> {
+        let sql = format!("{} WHERE email = $1", selecionar::<Usuario>("usuarios"));
+        self.consultar_um(&sql, vec![email.into()]).await
+    }
+
+    async fn contar(&self) -> Result<u64> {
+        let linhas = self
+            .consultar_linhas("SELECT COUNT(*) AS total FROM usuarios", Vec::new())
+            .await?;
+        let total = linhas
+            .first()
+            .map(|linha| Linha(linha).inteiro("total"))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(total as u64)
+    }
+
+    async fn criar(&self, mut usuario: Usuario) -> Result<Usuario> {
+        usuario.id.get_or_insert_with(ObjectId::new);
+        self.inserir("usuarios", std::slice::from_ref(&usuario))
+            .await?;
+        Ok(usuario)
+    }
+
+    async fn salvar(&self, usuario: &Usuario) -> Result<bool> {
+        self.atualizar("usuarios", usuario, 1, None).await
+    }
+
+    async fn excluir(&self, id: ObjectId) -> Result<bool> {
+        Ok(self
+            .executar("DELETE FROM usuarios WHERE id = $1", vec![id.into()])
+            .await?
+            > 0)
+    }
+}
+
+#[async_trait]
+impl BancosRepositorio for Sql {
+    async fn substituir(&self, empresa_id: ObjectId, mut bancos: Vec<Banco>) -> Result<Vec<Banco>> {
+        for banco in &mut bancos {
+            banco.id.get_or_insert_with(ObjectId::new);
+        }
+        self.executar(
+            "DELETE FROM bancos WHERE empresa_id = $1",
+            vec![empresa_id.into()],
+        )
+        .await?;
+        self.inserir(Banco::COLECAO, &bancos).await?;
+        Ok(bancos)
+    }
+}
@@ -0,0 +1,90 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use super::{Linha, Sql, Tabela, Valor};
+use crate::{
+    error::Result,
+    models::{PedidoCompra, StatusPedidoCompra},
+    repositorio::{PedidosCompraRepositorio, Registro},
+};
+
+/// Itens e recebimentos ficam embutidos na linha do pedido, em JSON, como
+/// os itens da venda.
+impl Tabela for PedidoCompra {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "fornecedor_id",
+        "itens",
+        "total",
+        "status",
+        "previsao_entrega",
+        "observacoes",
+        "recebimentos",
+        "usuario",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.fornecedor_id.into(),
+            Valor::json(&self.itens),
+            self.total.into(),
+            self.status.as_str().into(),
+            self.previsao_entrega.into(),
+            self.observacoes.clone().into(),
+            Valor::json(&self.recebimentos),
+            self.usuario.clone().into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            fornecedor_id: linha.oid("fornecedor_id")?,
+            itens: linha.json("itens")?,
+            total: linha.dinheiro("total")?,
+            status: linha.enumerado("status")?,
+            previsao_entrega: linha.data_opt("previsao_entrega")?,
+            observacoes: linha.texto_opt("observacoes")?,
+            recebimentos: linha.json("recebimentos")?,
+            usuario: linha.texto_opt("usuario")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl PedidosCompraRepositorio for Sql {
+    async fn salvar_versao(&self, pedido: &PedidoCompra, lido_em: DateTime<Utc>) -> Result<bool> {
+        self.atualizar(
+            PedidoCompra::COLECAO,
+            pedido,
+            2,
+            Some(("updated_at", lido_em.into())),
+        )
+        .await
+    }
+
+    async fn excluir_aberto(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let excluidos = self
+            .executar(
+                "DELETE FROM pedidos_compra WHERE id = $1 AND empresa_id = $2 AND status = $3",
+                vec![
+                    id.into(),
+                    empresa_id.into(),
+                    StatusPedidoCompra::Aberto.as_str().into(),
+                ],
+            )
+            .await?;
+        Ok(excluidos > 0)
+    }
+}
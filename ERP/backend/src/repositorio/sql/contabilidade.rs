@@ -0,0 +1,290 @@
+use std::collections::{BTreeMap, HashMap};
+
+use axum::async_trait;
+use chrono::NaiveDate;
+use mongodb::bson::oid::ObjectId;
+
+use super::{marcadores, selecionar, vincular, Linha, Sql, Tabela, Valor};
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{ContaContabil, LinhaPartida, Movimento, OrigemPartida, Partida},
+    repositorio::{ContasContabeisRepositorio, PartidasRepositorio, Registro, Repositorio},
+};
+
+impl Tabela for ContaContabil {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "codigo",
+        "nome",
+        "tipo",
+        "conta_bancaria_id",
+        "ativo",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.codigo.clone().into(),
+            self.nome.clone().into(),
+            self.tipo.as_str().into(),
+            self.conta_bancaria_id.into(),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            codigo: linha.texto("codigo")?,
+            nome: linha.texto("nome")?,
+            tipo: linha.enumerado("tipo")?,
+            conta_bancaria_id: linha.oid_opt("conta_bancaria_id")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+/// As linhas ficam em JSON na própria partida, para leitura, e também em
+/// `partidas_linhas`, onde são somadas por conta.
+impl Tabela for Partida {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "data",
+        "historico",
+        "origem",
+        "origem_id",
+        "estorno_de",
+        "linhas",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.data.into(),
+            self.historico.clone().into(),
+            self.origem.as_str().into(),
+            self.origem_id.into(),
+            self.estorno_de.into(),
+            Valor::json(&self.linhas),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            data: linha.data("data")?,
+            historico: linha.texto("historico")?,
+            origem: linha.enumerado("origem")?,
+            origem_id: linha.oid_opt("origem_id")?,
+            estorno_de: linha.oid_opt("estorno_de")?,
+            linhas: linha.json("linhas")?,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl ContasContabeisRepositorio for Sql {
+    async fn plano(&self, empresa_id: ObjectId) -> Result<Vec<ContaContabil>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 ORDER BY codigo",
+            selecionar::<ContaContabil>(ContaContabil::COLECAO)
+        );
+        self.consultar(&sql, vec![empresa_id.into()]).await
+    }
+}
+
+#[async_trait]
+impl PartidasRepositorio for Sql {
+    async fn criar(&self, mut partida: Partida) -> Result<Partida> {
+        let id = *partida.id.get_or_insert_with(ObjectId::new);
+
+        let sql_partida = format!(
+            "INSERT INTO partidas ({}) VALUES ({})",
+            Partida::COLUNAS.join(", "),
+            marcadores(1, Partida::COLUNAS.len())
+        );
+        let sql_linha = "INSERT INTO partidas_linhas \
+                         (partida_id, empresa_id, conta_id, data, debito, credito, valor_moeda) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7)";
+
+        let mut tx = self.pool.begin().await?;
+        vincular(sqlx::query(&sql_partida), partida.valores())
+            .execute(&mut *tx)
+            .await?;
+        for linha in &partida.linhas {
+            let parametros = vec![
+                id.into(),
+                partida.empresa_id.into(),
+                linha.conta_id.into(),
+                partida.data.into(),
+                linha.debito.into(),
+                linha.credito.into(),
+                linha.valor_moeda.into(),
+            ];
+            vincular(sqlx::query(sql_linha), parametros)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(partida)
+    }
+
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>> {
+        Repositorio::<Partida>::buscar(self, empresa_id, id).await
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<Partida>,
+    ) -> Result<Pagina<Partida>> {
+        Repositorio::<Partida>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn da_origem(
+        &self,
+        empresa_id: ObjectId,
+        origem: OrigemPartida,
+        origem_id: ObjectId,
+    ) -> Result<Vec<Partida>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND origem = $2 AND origem_id = $3 \
+             ORDER BY created_at, id",
+            selecionar::<Partida>(Partida::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![empresa_id.into(), origem.as_str().into(), origem_id.into()],
+        )
+        .await
+    }
+
+    async fn estorno_de(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<Partida>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND estorno_de = $2",
+            selecionar::<Partida>(Partida::COLECAO)
+        );
+        self.consultar_um(&sql, vec![empresa_id.into(), id.into()])
+            .await
+    }
+
+    async fn existe_alguma(&self, empresa_id: ObjectId) -> Result<bool> {
+        let linhas = self
+            .consultar_linhas(
+                "SELECT id FROM partidas WHERE empresa_id = $1 LIMIT 1",
+                vec![empresa_id.into()],
+            )
+            .await?;
+        Ok(!linhas.is_empty())
+    }
+
+    async fn movimento_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        ate: Option<NaiveDate>,
+    ) -> Result<HashMap<ObjectId, Movimento>> {
+        let mut sql = "SELECT conta_id, CAST(SUM(debito) AS BIGINT) AS debito, \
+                       CAST(SUM(credito) AS BIGINT) AS credito \
+                       FROM partidas_linhas WHERE empresa_id = $1"
+            .to_string();
+        let mut parametros = vec![empresa_id.into()];
+        if let Some(ate) = ate {
+            sql.push_str(" AND data <= $2");
+            parametros.push(ate.into());
+        }
+        sql.push_str(" GROUP BY conta_id");
+
+        let mut movimentos = HashMap::new();
+        for linha in self.consultar_linhas(&sql, parametros).await? {
+            let linha = Linha(&linha);
+            let movimento = Movimento {
+                debito: linha.dinheiro("debito")?,
+                credito: linha.dinheiro("credito")?,
+            };
+            movimentos.insert(linha.oid("conta_id")?, movimento);
+        }
+        Ok(movimentos)
+    }
+
+    async fn linhas_das_contas(
+        &self,
+        empresa_id: ObjectId,
+        contas: &[ObjectId],
+    ) -> Result<Vec<(NaiveDate, LinhaPartida)>> {
+        if contas.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT data, conta_id, debito, credito, valor_moeda FROM partidas_linhas \
+             WHERE empresa_id = $1 AND conta_id IN ({}) ORDER BY data",
+            marcadores(2, contas.len())
+        );
+        let mut parametros = vec![empresa_id.into()];
+        parametros.extend(contas.iter().map(|&id| Valor::from(id)));
+
+        self.consultar_linhas(&sql, parametros)
+            .await?
+            .iter()
+            .map(|linha| {
+                let linha = Linha(linha);
+                Ok((
+                    linha.data("data")?,
+                    LinhaPartida {
+                        conta_id: linha.oid("conta_id")?,
+                        debito: linha.dinheiro("debito")?,
+                        credito: linha.dinheiro("credito")?,
+                        valor_moeda: linha.dinheiro_opt("valor_moeda")?,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    async fn movimento_mensal(
+        &self,
+        empresa_id: ObjectId,
+        de: NaiveDate,
+        ate: NaiveDate,
+    ) -> Result<HashMap<ObjectId, BTreeMap<String, Movimento>>> {
+        let sql = "SELECT conta_id, SUBSTR(data, 1, 7) AS mes, \
+                   CAST(SUM(debito) AS BIGINT) AS debito, \
+                   CAST(SUM(credito) AS BIGINT) AS credito \
+                   FROM partidas_linhas \
+                   WHERE empresa_id = $1 AND data >= $2 AND data <= $3 \
+                   GROUP BY conta_id, SUBSTR(data, 1, 7)";
+
+        let mut movimentos: HashMap<ObjectId, BTreeMap<String, Movimento>> = HashMap::new();
+        for linha in self
+            .consultar_linhas(sql, vec![empresa_id.into(), de.into(), ate.into()])
+            .await?
+        {
+            let linha = Linha(&linha);
+            let movimento = Movimento {
+                debito: linha.dinheiro("debito")?,
+                credito: linha.dinheiro("credito")?,
+            };
+            movimentos
+                .entry(linha.oid("conta_id")?)
+                .or_default()
+                .insert(linha.texto("mes")?, movimento);
+        }
+        Ok(movimentos)
+    }
+}
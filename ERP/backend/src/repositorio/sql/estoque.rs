@@ -0,0 +1,221 @@
+use axum::async_trait;
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+
+use super::{selecionar, Linha, Sql, Tabela, Valor};
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{MovimentacaoEstoque, Produto},
+    repositorio::{
+        FiltroMovimentacoes, MovimentacoesRepositorio, ProdutosRepositorio, Registro, Repositorio,
+    },
+};
+
+impl Tabela for Produto {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "nome",
+        "descricao",
+        "codigo_barras",
+        "preco_custo",
+        "preco_venda",
+        "estoque_atual",
+        "estoque_minimo",
+        "unidade",
+        "fiscal",
+        "ativo",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.nome.clone().into(),
+            self.descricao.clone().into(),
+            self.codigo_barras.clone().into(),
+            self.preco_custo.into(),
+            self.preco_venda.into(),
+            self.estoque_atual.into(),
+            self.estoque_minimo.into(),
+            self.unidade.clone().into(),
+            Valor::json_opt(self.fiscal.as_ref()),
+            self.ativo.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            nome: linha.texto("nome")?,
+            descricao: linha.texto_opt("descricao")?,
+            codigo_barras: linha.texto_opt("codigo_barras")?,
+            preco_custo: linha.dinheiro("preco_custo")?,
+            preco_venda: linha.dinheiro("preco_venda")?,
+            estoque_atual: linha.inteiro("estoque_atual")? as i32,
+            estoque_minimo: linha.inteiro("estoque_minimo")? as i32,
+            unidade: linha.texto("unidade")?,
+            fiscal: linha.json_opt("fiscal")?,
+            ativo: linha.booleano("ativo")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+impl Tabela for MovimentacaoEstoque {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "produto_id",
+        "tipo",
+        "quantidade",
+        "motivo",
+        "usuario",
+        "venda_id",
+        "pedido_compra_id",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.produto_id.into(),
+            Valor::enumerado(&self.tipo),
+            self.quantidade.into(),
+            self.motivo.clone().into(),
+            self.usuario.clone().into(),
+            self.venda_id.into(),
+            self.pedido_compra_id.into(),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            produto_id: linha.oid("produto_id")?,
+            tipo: linha.enumerado("tipo")?,
+            quantidade: linha.inteiro("quantidade")? as i32,
+            motivo: linha.texto("motivo")?,
+            usuario: linha.texto_opt("usuario")?,
+            venda_id: linha.oid_opt("venda_id")?,
+            pedido_compra_id: linha.oid_opt("pedido_compra_id")?,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl ProdutosRepositorio for Sql {
+    async fn ajustar_estoque(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        delta: i32,
+    ) -> Result<bool> {
+        let mut sql = "UPDATE produtos SET estoque_atual = estoque_atual + $1, updated_at = $2 \
+                       WHERE id = $3 AND empresa_id = $4"
+            .to_string();
+        let mut parametros = vec![
+            delta.into(),
+            Utc::now().into(),
+            id.into(),
+            empresa_id.into(),
+        ];
+        if delta < 0 {
+            sql.push_str(" AND estoque_atual >= $5");
+            parametros.push((-delta).into());
+        } else {
+            sql.push_str(" AND estoque_atual <= $5");
+            parametros.push((i32::MAX - delta).into());
+        }
+        Ok(self.executar(&sql, parametros).await? > 0)
+    }
+
+    async fn salvar_cadastro(&self, produto: &Produto) -> Result<bool> {
+        let alterados = self
+            .executar(
+                "UPDATE produtos SET nome = $1, descricao = $2, codigo_barras = $3, \
+                 preco_custo = $4, preco_venda = $5, estoque_minimo = $6, unidade = $7, \
+                 fiscal = $8, ativo = $9, updated_at = $10 WHERE id = $11 AND empresa_id = $12",
+                vec![
+                    produto.nome.clone().into(),
+                    produto.descricao.clone().into(),
+                    produto.codigo_barras.clone().into(),
+                    produto.preco_custo.into(),
+                    produto.preco_venda.into(),
+                    produto.estoque_minimo.into(),
+                    produto.unidade.clone().into(),
+                    Valor::json_opt(produto.fiscal.as_ref()),
+                    produto.ativo.into(),
+                    produto.updated_at.into(),
+                    produto.id.into(),
+                    produto.empresa_id.into(),
+                ],
+            )
+            .await?;
+        Ok(alterados > 0)
+    }
+
+    async fn criticos(&self, empresa_id: ObjectId) -> Result<Vec<Produto>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND ativo = 1 AND estoque_atual <= estoque_minimo",
+            selecionar::<Produto>(Produto::COLECAO)
+        );
+        self.consultar(&sql, vec![empresa_id.into()]).await
+    }
+}
+
+#[async_trait]
+impl MovimentacoesRepositorio for Sql {
+    async fn listar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroMovimentacoes,
+    ) -> Result<Vec<MovimentacaoEstoque>> {
+        let mut condicoes = vec!["empresa_id = $1".to_string()];
+        let mut parametros: Vec<Valor> = vec![empresa_id.into()];
+        if let Some(produto_id) = filtro.produto_id {
+            parametros.push(produto_id.into());
+            condicoes.push(format!("produto_id = ${}", parametros.len()));
+        }
+        if let Some(tipo) = &filtro.tipo {
+            parametros.push(Valor::enumerado(tipo));
+            condicoes.push(format!("tipo = ${}", parametros.len()));
+        }
+
+        let sql = format!(
+            "{} WHERE {} ORDER BY created_at, id",
+            selecionar::<MovimentacaoEstoque>(MovimentacaoEstoque::COLECAO),
+            condicoes.join(" AND ")
+        );
+        self.consultar(&sql, parametros).await
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<MovimentacaoEstoque>,
+    ) -> Result<Pagina<MovimentacaoEstoque>> {
+        Repositorio::<MovimentacaoEstoque>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn criar(&self, mut movimentacao: MovimentacaoEstoque) -> Result<MovimentacaoEstoque> {
+        movimentacao.id.get_or_insert_with(ObjectId::new);
+        self.inserir(
+            MovimentacaoEstoque::COLECAO,
+            std::slice::from_ref(&movimentacao),
+        )
+        .await?;
+        Ok(movimentacao)
+    }
+}
@@ -0,0 +1,1012 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::async_trait;
+use chrono::NaiveDate;
+use mongodb::bson::oid::ObjectId;
+
+use super::{coluna_invalida, marcadores, selecionar, Linha, Sql, Tabela, Valor};
+use crate::{
+    consulta::{Consulta, Pagina},
+    error::Result,
+    models::{
+        Boleto, CobrancaPix, CompraCartao, Cotacao, Dinheiro, Lancamento, Moeda, Pagador,
+        Recorrencia, StatusBoleto, StatusCobranca, StatusLancamento, TaxaCambio, TransacaoBancaria,
+    },
+    repositorio::{
+        BoletosRepositorio, CobrancasPixRepositorio, ComprasCartaoRepositorio, CotacoesRepositorio,
+        FiltroLancamentos, LancamentosRepositorio, RecorrenciasRepositorio, Registro, Repositorio,
+        TransacoesRepositorio,
+    },
+};
+
+impl Tabela for Lancamento {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "tipo",
+        "descricao",
+        "categoria",
+        "valor",
+        "moeda",
+        "vencimento",
+        "cliente_id",
+        "contraparte",
+        "grupo_id",
+        "parcela",
+        "total_parcelas",
+        "status",
+        "conta_id",
+        "data_pagamento",
+        "valor_pago",
+        "recorrencia_id",
+        "ocorrencia",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            Valor::enumerado(&self.tipo),
+            self.descricao.clone().into(),
+            self.categoria.clone().into(),
+            self.valor.into(),
+            Valor::enumerado(&self.moeda),
+            self.vencimento.into(),
+            self.cliente_id.into(),
+            self.contraparte.clone().into(),
+            self.grupo_id.into(),
+            self.parcela.into(),
+            self.total_parcelas.into(),
+            self.status.as_str().into(),
+            self.conta_id.into(),
+            self.data_pagamento.into(),
+            self.valor_pago.into(),
+            self.recorrencia_id.into(),
+            Valor::Inteiro(self.ocorrencia.map(i64::from)),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            tipo: linha.enumerado("tipo")?,
+            descricao: linha.texto("descricao")?,
+            categoria: linha.texto("categoria")?,
+            valor: linha.dinheiro("valor")?,
+            moeda: linha.enumerado("moeda")?,
+            vencimento: linha.data("vencimento")?,
+            cliente_id: linha.oid_opt("cliente_id")?,
+            contraparte: linha.texto_opt("contraparte")?,
+            grupo_id: linha.oid("grupo_id")?,
+            parcela: linha.inteiro("parcela")? as u32,
+            total_parcelas: linha.inteiro("total_parcelas")? as u32,
+            status: linha.enumerado("status")?,
+            conta_id: linha.oid_opt("conta_id")?,
+            data_pagamento: linha.data_opt("data_pagamento")?,
+            valor_pago: linha.dinheiro_opt("valor_pago")?,
+            recorrencia_id: linha.oid_opt("recorrencia_id")?,
+            ocorrencia: linha.inteiro_opt("ocorrencia")?.map(|numero| numero as u32),
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+impl Tabela for CompraCartao {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "cartao_id",
+        "compra_id",
+        "descricao",
+        "categoria",
+        "valor",
+        "data_compra",
+        "parcela",
+        "total_parcelas",
+        "competencia",
+        "lancamento_id",
+        "recorrencia_id",
+        "ocorrencia",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.cartao_id.into(),
+            self.compra_id.into(),
+            self.descricao.clone().into(),
+            self.categoria.clone().into(),
+            self.valor.into(),
+            self.data_compra.into(),
+            self.parcela.into(),
+            self.total_parcelas.into(),
+            self.competencia.clone().into(),
+            self.lancamento_id.into(),
+            self.recorrencia_id.into(),
+            Valor::Inteiro(self.ocorrencia.map(i64::from)),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            cartao_id: linha.oid("cartao_id")?,
+            compra_id: linha.oid("compra_id")?,
+            descricao: linha.texto("descricao")?,
+            categoria: linha.texto("categoria")?,
+            valor: linha.dinheiro("valor")?,
+            data_compra: linha.data("data_compra")?,
+            parcela: linha.inteiro("parcela")? as u32,
+            total_parcelas: linha.inteiro("total_parcelas")? as u32,
+            competencia: linha.texto("competencia")?,
+            lancamento_id: linha.oid_opt("lancamento_id")?,
+            recorrencia_id: linha.oid_opt("recorrencia_id")?,
+            ocorrencia: linha.inteiro_opt("ocorrencia")?.map(|numero| numero as u32),
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+impl Tabela for Recorrencia {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "tipo",
+        "descricao",
+        "categoria",
+        "valor",
+        "moeda",
+        "frequencia",
+        "dia",
+        "inicio",
+        "ocorrencias",
+        "fim",
+        "conta_id",
+        "cartao_id",
+        "cliente_id",
+        "contraparte",
+        "geradas",
+        "ativa",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            Valor::enumerado(&self.tipo),
+            self.descricao.clone().into(),
+            self.categoria.clone().into(),
+            self.valor.into(),
+            Valor::enumerado(&self.moeda),
+            Valor::enumerado(&self.frequencia),
+            self.dia.into(),
+            self.inicio.into(),
+            Valor::Inteiro(self.ocorrencias.map(i64::from)),
+            self.fim.into(),
+            self.conta_id.into(),
+            self.cartao_id.into(),
+            self.cliente_id.into(),
+            self.contraparte.clone().into(),
+            self.geradas.into(),
+            self.ativa.into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            tipo: linha.enumerado("tipo")?,
+            descricao: linha.texto("descricao")?,
+            categoria: linha.texto("categoria")?,
+            valor: linha.dinheiro("valor")?,
+            moeda: linha.enumerado("moeda")?,
+            frequencia: linha.enumerado("frequencia")?,
+            dia: linha.inteiro("dia")? as u32,
+            inicio: linha.data("inicio")?,
+            ocorrencias: linha.inteiro_opt("ocorrencias")?.map(|total| total as u32),
+            fim: linha.data_opt("fim")?,
+            conta_id: linha.oid_opt("conta_id")?,
+            cartao_id: linha.oid_opt("cartao_id")?,
+            cliente_id: linha.oid_opt("cliente_id")?,
+            contraparte: linha.texto_opt("contraparte")?,
+            geradas: linha.inteiro("geradas")? as u32,
+            ativa: linha.booleano("ativa")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+/// A taxa fica em milionésimos, numa coluna inteira.
+impl Tabela for Cotacao {
+    const COLUNAS: &'static [&'static str] =
+        &["id", "empresa_id", "moeda", "data", "taxa", "created_at"];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            Valor::enumerado(&self.moeda),
+            self.data.into(),
+            Valor::Inteiro(Some(self.taxa.unidades())),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            moeda: linha.enumerado("moeda")?,
+            data: linha.data("data")?,
+            taxa: TaxaCambio::de_unidades(linha.inteiro("taxa")?)
+                .map_err(|e| coluna_invalida("taxa", e))?,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+impl Tabela for TransacaoBancaria {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "conta_id",
+        "data",
+        "valor",
+        "descricao",
+        "documento",
+        "identificador",
+        "origem",
+        "lancamento_id",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.conta_id.into(),
+            self.data.into(),
+            self.valor.into(),
+            self.descricao.clone().into(),
+            self.documento.clone().into(),
+            self.identificador.clone().into(),
+            Valor::enumerado(&self.origem),
+            self.lancamento_id.into(),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            conta_id: linha.oid("conta_id")?,
+            data: linha.data("data")?,
+            valor: linha.dinheiro("valor")?,
+            descricao: linha.texto("descricao")?,
+            documento: linha.texto_opt("documento")?,
+            identificador: linha.texto("identificador")?,
+            origem: linha.enumerado("origem")?,
+            lancamento_id: linha.oid_opt("lancamento_id")?,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+impl Tabela for CobrancaPix {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "conta_id",
+        "origem",
+        "origem_id",
+        "txid",
+        "valor",
+        "location",
+        "payload",
+        "status",
+        "transacao_id",
+        "data_pagamento",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.conta_id.into(),
+            Valor::enumerado(&self.origem),
+            self.origem_id.into(),
+            self.txid.clone().into(),
+            self.valor.into(),
+            self.location.clone().into(),
+            self.payload.clone().into(),
+            self.status.as_str().into(),
+            self.transacao_id.into(),
+            self.data_pagamento.into(),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            conta_id: linha.oid("conta_id")?,
+            origem: linha.enumerado("origem")?,
+            origem_id: linha.oid("origem_id")?,
+            txid: linha.texto("txid")?,
+            valor: linha.dinheiro("valor")?,
+            location: linha.texto_opt("location")?,
+            payload: linha.texto("payload")?,
+            status: linha.enumerado("status")?,
+            transacao_id: linha.oid_opt("transacao_id")?,
+            data_pagamento: linha.data_opt("data_pagamento")?,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+impl Tabela for Boleto {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "conta_id",
+        "lancamento_id",
+        "banco",
+        "sequencial",
+        "nosso_numero",
+        "numero_documento",
+        "valor",
+        "vencimento",
+        "pagador_nome",
+        "pagador_documento",
+        "pagador_endereco",
+        "pagador_cidade",
+        "pagador_estado",
+        "pagador_cep",
+        "codigo_barras",
+        "linha_digitavel",
+        "status",
+        "remessa",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.conta_id.into(),
+            self.lancamento_id.into(),
+            self.banco.clone().into(),
+            Valor::Inteiro(Some(self.sequencial as i64)),
+            self.nosso_numero.clone().into(),
+            self.numero_documento.clone().into(),
+            self.valor.into(),
+            self.vencimento.into(),
+            self.pagador.nome.clone().into(),
+            self.pagador.documento.clone().into(),
+            self.pagador.endereco.clone().into(),
+            self.pagador.cidade.clone().into(),
+            self.pagador.estado.clone().into(),
+            self.pagador.cep.clone().into(),
+            self.codigo_barras.clone().into(),
+            self.linha_digitavel.clone().into(),
+            self.status.as_str().into(),
+            Valor::Inteiro(self.remessa.map(i64::from)),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            conta_id: linha.oid("conta_id")?,
+            lancamento_id: linha.oid("lancamento_id")?,
+            banco: linha.texto("banco")?,
+            sequencial: linha.inteiro("sequencial")? as u64,
+            nosso_numero: linha.texto("nosso_numero")?,
+            numero_documento: linha.texto("numero_documento")?,
+            valor: linha.dinheiro("valor")?,
+            vencimento: linha.data("vencimento")?,
+            pagador: Pagador {
+                nome: linha.texto("pagador_nome")?,
+                documento: linha.texto("pagador_documento")?,
+                endereco: linha.texto_opt("pagador_endereco")?,
+                cidade: linha.texto_opt("pagador_cidade")?,
+                estado: linha.texto_opt("pagador_estado")?,
+                cep: linha.texto_opt("pagador_cep")?,
+            },
+            codigo_barras: linha.texto("codigo_barras")?,
+            linha_digitavel: linha.texto("linha_digitavel")?,
+            status: linha.enumerado("status")?,
+            remessa: linha.inteiro_opt("remessa")?.map(|remessa| remessa as u32),
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+/// Lê as linhas de um `GROUP BY` com as colunas `id` e `total` (em centavos).
+fn totais_por_id(linhas: &[sqlx::any::AnyRow]) -> Result<HashMap<ObjectId, Dinheiro>> {
+    linhas
+        .iter()
+        .map(|linha| {
+            let linha = Linha(linha);
+            Ok((linha.oid("id")?, linha.dinheiro("total")?))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl LancamentosRepositorio for Sql {
+    async fn filtrar(
+        &self,
+        empresa_id: ObjectId,
+        filtro: &FiltroLancamentos,
+    ) -> Result<Vec<Lancamento>> {
+        let mut condicoes = vec!["empresa_id = $1".to_string()];
+        let mut parametros: Vec<Valor> = vec![empresa_id.into()];
+        let mut condicao = |expressao: &str, valor: Valor| {
+            parametros.push(valor);
+            condicoes.push(format!("{} ${}", expressao, parametros.len()));
+        };
+
+        if let Some(tipo) = &filtro.tipo {
+            condicao("tipo =", Valor::enumerado(tipo));
+        }
+        if let Some(status) = filtro.status {
+            condicao("status =", status.as_str().into());
+        }
+        if let Some(cliente_id) = filtro.cliente_id {
+            condicao("cliente_id =", cliente_id.into());
+        }
+        if let Some(de) = filtro.vencimento_de {
+            condicao("vencimento >=", de.into());
+        }
+        if let Some(ate) = filtro.vencimento_ate {
+            condicao("vencimento <=", ate.into());
+        }
+        if let Some(minimo) = filtro.valor_min {
+            condicao("valor >=", minimo.into());
+        }
+        if let Some(maximo) = filtro.valor_max {
+            condicao("valor <=", maximo.into());
+        }
+
+        let sql = format!(
+            "{} WHERE {} ORDER BY vencimento, parcela",
+            selecionar::<Lancamento>(Lancamento::COLECAO),
+            condicoes.join(" AND ")
+        );
+        self.consultar(&sql, parametros).await
+    }
+
+    async fn salvar_no_status(
+        &self,
+        lancamento: &Lancamento,
+        status: StatusLancamento,
+    ) -> Result<bool> {
+        self.atualizar(
+            Lancamento::COLECAO,
+            lancamento,
+            2,
+            Some(("status", status.as_str().into())),
+        )
+        .await
+    }
+
+    async fn excluir_nao_pago(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let excluidos = self
+            .executar(
+                "DELETE FROM lancamentos WHERE id = $1 AND empresa_id = $2 AND status <> $3",
+                vec![
+                    id.into(),
+                    empresa_id.into(),
+                    StatusLancamento::Pago.as_str().into(),
+                ],
+            )
+            .await?;
+        Ok(excluidos > 0)
+    }
+
+    async fn existe_da_conta(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<bool> {
+        let linhas = self
+            .consultar_linhas(
+                "SELECT id FROM lancamentos WHERE empresa_id = $1 AND conta_id = $2 LIMIT 1",
+                vec![empresa_id.into(), conta_id.into()],
+            )
+            .await?;
+        Ok(!linhas.is_empty())
+    }
+
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<Lancamento>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND recorrencia_id = $2 ORDER BY ocorrencia",
+            selecionar::<Lancamento>(Lancamento::COLECAO)
+        );
+        self.consultar(&sql, vec![empresa_id.into(), recorrencia_id.into()])
+            .await
+    }
+
+    async fn movimentado_por_conta(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>> {
+        let mut sql = "SELECT conta_id AS id, \
+                       CAST(SUM(CASE WHEN tipo = 'RECEBER' THEN valor_pago ELSE -valor_pago END) AS BIGINT) AS total \
+                       FROM lancamentos \
+                       WHERE empresa_id = $1 AND status = $2 AND conta_id IS NOT NULL"
+            .to_string();
+        let mut parametros = vec![empresa_id.into(), StatusLancamento::Pago.as_str().into()];
+        if let Some(conta_id) = conta_id {
+            sql.push_str(" AND conta_id = $3");
+            parametros.push(conta_id.into());
+        }
+        sql.push_str(" GROUP BY conta_id");
+
+        totais_por_id(&self.consultar_linhas(&sql, parametros).await?)
+    }
+}
+
+#[async_trait]
+impl ComprasCartaoRepositorio for Sql {
+    async fn criar_varias(&self, mut compras: Vec<CompraCartao>) -> Result<Vec<CompraCartao>> {
+        for compra in &mut compras {
+            compra.id.get_or_insert_with(ObjectId::new);
+        }
+        self.inserir(CompraCartao::COLECAO, &compras).await?;
+        Ok(compras)
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<CompraCartao>,
+    ) -> Result<Pagina<CompraCartao>> {
+        Repositorio::<CompraCartao>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn listar_do_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND cartao_id = $2 ORDER BY data_compra DESC, parcela",
+            selecionar::<CompraCartao>(CompraCartao::COLECAO)
+        );
+        self.consultar(&sql, vec![empresa_id.into(), cartao_id.into()])
+            .await
+    }
+
+    async fn da_competencia(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        competencia: &str,
+    ) -> Result<Vec<CompraCartao>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND cartao_id = $2 AND competencia = $3 ORDER BY data_compra",
+            selecionar::<CompraCartao>(CompraCartao::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![empresa_id.into(), cartao_id.into(), competencia.into()],
+        )
+        .await
+    }
+
+    async fn contar_pagas(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64> {
+        let linhas = self
+            .consultar_linhas(
+                "SELECT COUNT(*) AS total FROM compras_cartao \
+                 WHERE empresa_id = $1 AND cartao_id = $2 AND compra_id = $3 \
+                 AND lancamento_id IS NOT NULL",
+                vec![empresa_id.into(), cartao_id.into(), compra_id.into()],
+            )
+            .await?;
+        let total = linhas
+            .first()
+            .map(|linha| Linha(linha).inteiro("total"))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(total as u64)
+    }
+
+    async fn excluir_compra(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: ObjectId,
+        compra_id: ObjectId,
+    ) -> Result<u64> {
+        self.executar(
+            "DELETE FROM compras_cartao WHERE empresa_id = $1 AND cartao_id = $2 AND compra_id = $3",
+            vec![empresa_id.into(), cartao_id.into(), compra_id.into()],
+        )
+        .await
+    }
+
+    async fn quitar(&self, ids: &[ObjectId], lancamento_id: ObjectId) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            "UPDATE compras_cartao SET lancamento_id = $1 \
+             WHERE lancamento_id IS NULL AND id IN ({})",
+            marcadores(2, ids.len())
+        );
+        let mut parametros = vec![lancamento_id.into()];
+        parametros.extend(ids.iter().map(|&id| Valor::from(id)));
+        self.executar(&sql, parametros).await
+    }
+
+    async fn reabrir(&self, lancamento_id: ObjectId) -> Result<()> {
+        self.executar(
+            "UPDATE compras_cartao SET lancamento_id = NULL WHERE lancamento_id = $1",
+            vec![lancamento_id.into()],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn da_recorrencia(
+        &self,
+        empresa_id: ObjectId,
+        recorrencia_id: ObjectId,
+    ) -> Result<Vec<CompraCartao>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND recorrencia_id = $2 ORDER BY ocorrencia",
+            selecionar::<CompraCartao>(CompraCartao::COLECAO)
+        );
+        self.consultar(&sql, vec![empresa_id.into(), recorrencia_id.into()])
+            .await
+    }
+
+    async fn utilizado_por_cartao(
+        &self,
+        empresa_id: ObjectId,
+        cartao_id: Option<ObjectId>,
+    ) -> Result<HashMap<ObjectId, Dinheiro>> {
+        let mut sql =
+            "SELECT cartao_id AS id, CAST(SUM(valor) AS BIGINT) AS total FROM compras_cartao \
+                       WHERE empresa_id = $1 AND lancamento_id IS NULL"
+                .to_string();
+        let mut parametros = vec![empresa_id.into()];
+        if let Some(cartao_id) = cartao_id {
+            sql.push_str(" AND cartao_id = $2");
+            parametros.push(cartao_id.into());
+        }
+        sql.push_str(" GROUP BY cartao_id");
+
+        totais_por_id(&self.consultar_linhas(&sql, parametros).await?)
+    }
+}
+
+#[async_trait]
+impl RecorrenciasRepositorio for Sql {
+    async fn ativas(&self) -> Result<Vec<Recorrencia>> {
+        let sql = format!(
+            "{} WHERE ativa = 1",
+            selecionar::<Recorrencia>(Recorrencia::COLECAO)
+        );
+        self.consultar(&sql, Vec::new()).await
+    }
+
+    async fn salvar_geradas(&self, recorrencia: &Recorrencia, geradas: u32) -> Result<bool> {
+        self.atualizar(
+            Recorrencia::COLECAO,
+            recorrencia,
+            2,
+            Some(("geradas", geradas.into())),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl CotacoesRepositorio for Sql {
+    async fn na_data(
+        &self,
+        empresa_id: ObjectId,
+        moeda: Moeda,
+        data: NaiveDate,
+    ) -> Result<Option<Cotacao>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND moeda = $2 AND data <= $3 ORDER BY data DESC LIMIT 1",
+            selecionar::<Cotacao>(Cotacao::COLECAO)
+        );
+        self.consultar_um(
+            &sql,
+            vec![empresa_id.into(), Valor::enumerado(&moeda), data.into()],
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl TransacoesRepositorio for Sql {
+    async fn identificadores_existentes(
+        &self,
+        conta_id: ObjectId,
+        identificadores: &[String],
+    ) -> Result<HashSet<String>> {
+        if identificadores.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let sql = format!(
+            "SELECT identificador FROM transacoes_bancarias \
+             WHERE conta_id = $1 AND identificador IN ({})",
+            marcadores(2, identificadores.len())
+        );
+        let mut parametros = vec![conta_id.into()];
+        parametros.extend(identificadores.iter().map(|id| Valor::from(id.clone())));
+
+        self.consultar_linhas(&sql, parametros)
+            .await?
+            .iter()
+            .map(|linha| Linha(linha).texto("identificador"))
+            .collect()
+    }
+
+    async fn criar_varias(
+        &self,
+        mut transacoes: Vec<TransacaoBancaria>,
+    ) -> Result<Vec<TransacaoBancaria>> {
+        for transacao in &mut transacoes {
+            transacao.id.get_or_insert_with(ObjectId::new);
+        }
+        self.inserir(TransacaoBancaria::COLECAO, &transacoes)
+            .await?;
+        Ok(transacoes)
+    }
+
+    async fn paginar(
+        &self,
+        empresa_id: ObjectId,
+        consulta: &Consulta<TransacaoBancaria>,
+    ) -> Result<Pagina<TransacaoBancaria>> {
+        Repositorio::<TransacaoBancaria>::paginar(self, empresa_id, consulta).await
+    }
+
+    async fn buscar(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        id: ObjectId,
+    ) -> Result<Option<TransacaoBancaria>> {
+        let sql = format!(
+            "{} WHERE id = $1 AND empresa_id = $2 AND conta_id = $3",
+            selecionar::<TransacaoBancaria>(TransacaoBancaria::COLECAO)
+        );
+        self.consultar_um(&sql, vec![id.into(), empresa_id.into(), conta_id.into()])
+            .await
+    }
+
+    async fn conciliar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<bool> {
+        let alteradas = self
+            .executar(
+                "UPDATE transacoes_bancarias SET lancamento_id = $1 \
+                 WHERE id = $2 AND lancamento_id IS NULL",
+                vec![lancamento_id.into(), id.into()],
+            )
+            .await?;
+        Ok(alteradas > 0)
+    }
+
+    async fn liberar(&self, id: ObjectId, lancamento_id: ObjectId) -> Result<()> {
+        self.executar(
+            "UPDATE transacoes_bancarias SET lancamento_id = NULL \
+             WHERE id = $1 AND lancamento_id = $2",
+            vec![id.into(), lancamento_id.into()],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn desfazer_conciliacao(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<()> {
+        self.executar(
+            "UPDATE transacoes_bancarias SET lancamento_id = NULL \
+             WHERE empresa_id = $1 AND lancamento_id = $2",
+            vec![empresa_id.into(), lancamento_id.into()],
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CobrancasPixRepositorio for Sql {
+    async fn buscar_por_txid(
+        &self,
+        empresa_id: ObjectId,
+        txid: &str,
+    ) -> Result<Option<CobrancaPix>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND txid = $2",
+            selecionar::<CobrancaPix>(CobrancaPix::COLECAO)
+        );
+        self.consultar_um(&sql, vec![empresa_id.into(), txid.into()])
+            .await
+    }
+
+    async fn pendentes(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+    ) -> Result<Vec<CobrancaPix>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND conta_id = $2 AND status = $3",
+            selecionar::<CobrancaPix>(CobrancaPix::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![
+                empresa_id.into(),
+                conta_id.into(),
+                StatusCobranca::Pendente.as_str().into(),
+            ],
+        )
+        .await
+    }
+
+    async fn paga_pela_transacao(
+        &self,
+        empresa_id: ObjectId,
+        transacao_id: ObjectId,
+    ) -> Result<Option<CobrancaPix>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND transacao_id = $2",
+            selecionar::<CobrancaPix>(CobrancaPix::COLECAO)
+        );
+        self.consultar_um(&sql, vec![empresa_id.into(), transacao_id.into()])
+            .await
+    }
+}
+
+#[async_trait]
+impl BoletosRepositorio for Sql {
+    async fn do_lancamento(
+        &self,
+        empresa_id: ObjectId,
+        lancamento_id: ObjectId,
+    ) -> Result<Option<Boleto>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND lancamento_id = $2",
+            selecionar::<Boleto>(Boleto::COLECAO)
+        );
+        self.consultar_um(&sql, vec![empresa_id.into(), lancamento_id.into()])
+            .await
+    }
+
+    async fn proximo_sequencial(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u64> {
+        let chave = format!("boletos:{}:{}:sequencial", empresa_id, conta_id);
+        self.proximo_numero(&chave, || {
+            self.maior_numero("sequencial", empresa_id, conta_id)
+        })
+        .await
+    }
+
+    async fn proxima_remessa(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<u32> {
+        let chave = format!("boletos:{}:{}:remessa", empresa_id, conta_id);
+        let numero = self
+            .proximo_numero(&chave, || {
+                self.maior_numero("remessa", empresa_id, conta_id)
+            })
+            .await?;
+        Ok(numero as u32)
+    }
+
+    async fn a_remeter(&self, empresa_id: ObjectId, conta_id: ObjectId) -> Result<Vec<Boleto>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND conta_id = $2 AND status = $3 ORDER BY sequencial",
+            selecionar::<Boleto>(Boleto::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![
+                empresa_id.into(),
+                conta_id.into(),
+                StatusBoleto::Emitido.as_str().into(),
+            ],
+        )
+        .await
+    }
+
+    async fn remeter(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        ids: &[ObjectId],
+        remessa: u32,
+    ) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let sql = format!(
+            "UPDATE boletos SET status = $1, remessa = $2 \
+             WHERE empresa_id = $3 AND conta_id = $4 AND status = $5 AND id IN ({})",
+            marcadores(6, ids.len())
+        );
+        let mut parametros = vec![
+            StatusBoleto::Remetido.as_str().into(),
+            remessa.into(),
+            empresa_id.into(),
+            conta_id.into(),
+            StatusBoleto::Emitido.as_str().into(),
+        ];
+        parametros.extend(ids.iter().map(|&id| Valor::from(id)));
+        self.executar(&sql, parametros).await
+    }
+
+    async fn da_remessa(
+        &self,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+        remessa: u32,
+    ) -> Result<Vec<Boleto>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND conta_id = $2 AND remessa = $3 ORDER BY sequencial",
+            selecionar::<Boleto>(Boleto::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![empresa_id.into(), conta_id.into(), remessa.into()],
+        )
+        .await
+    }
+}
+
+impl Sql {
+    /// Maior sequencial ou remessa (`coluna`) dos boletos da conta gravados
+    /// antes das sequências; 0 se nenhum.
+    async fn maior_numero(
+        &self,
+        coluna: &str,
+        empresa_id: ObjectId,
+        conta_id: ObjectId,
+    ) -> Result<u64> {
+        let sql = format!(
+            "SELECT CAST(COALESCE(MAX({}), 0) AS BIGINT) AS maximo \
+             FROM boletos WHERE empresa_id = $1 AND conta_id = $2",
+            coluna
+        );
+        let linhas = self
+            .consultar_linhas(&sql, vec![empresa_id.into(), conta_id.into()])
+            .await?;
+        match linhas.first() {
+            Some(linha) => Ok(Linha(linha).inteiro("maximo")? as u64),
+            None => Ok(0),
+        }
+    }
+}
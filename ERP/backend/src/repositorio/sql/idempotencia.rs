@@ -0,0 +1,121 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::{marcadores, selecionar, Linha, Sql, Tabela, Valor};
+use crate::{
+    error::Result,
+    models::{RequisicaoIdempotente, RespostaGravada},
+    repositorio::IdempotenciaRepositorio,
+};
+
+/// A resposta fica nas colunas `status`, `cabecalhos` (JSON) e `corpo`;
+/// `status` nulo indica requisição em andamento.
+impl Tabela for RequisicaoIdempotente {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "usuario_id",
+        "chave",
+        "impressao",
+        "status",
+        "cabecalhos",
+        "corpo",
+        "created_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        let resposta = self.resposta.as_ref();
+        vec![
+            self.id.clone().into(),
+            self.usuario_id.into(),
+            self.chave.clone().into(),
+            self.impressao.clone().into(),
+            Valor::Inteiro(resposta.map(|resposta| i64::from(resposta.status))),
+            Valor::json_opt(resposta.map(|resposta| &resposta.cabecalhos)),
+            Valor::Bytes(
+                resposta
+                    .map(|resposta| resposta.corpo.clone())
+                    .unwrap_or_default(),
+            ),
+            self.created_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        let resposta = match linha.inteiro_opt("status")? {
+            Some(status) => Some(RespostaGravada {
+                status: status as u16,
+                cabecalhos: linha.json("cabecalhos")?,
+                corpo: linha.bytes("corpo")?,
+            }),
+            None => None,
+        };
+        Ok(Self {
+            id: linha.texto("id")?,
+            usuario_id: linha.oid("usuario_id")?,
+            chave: linha.texto("chave")?,
+            impressao: linha.texto("impressao")?,
+            resposta,
+            created_at: linha.instante("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl IdempotenciaRepositorio for Sql {
+    async fn reservar(
+        &self,
+        requisicao: &RequisicaoIdempotente,
+        validas_desde: DateTime<Utc>,
+    ) -> Result<Option<RequisicaoIdempotente>> {
+        self.executar(
+            "DELETE FROM idempotencia WHERE created_at < $1",
+            vec![validas_desde.into()],
+        )
+        .await?;
+
+        let inserir = format!(
+            "INSERT INTO idempotencia ({}) VALUES ({}) ON CONFLICT (id) DO NOTHING",
+            RequisicaoIdempotente::COLUNAS.join(", "),
+            marcadores(1, RequisicaoIdempotente::COLUNAS.len())
+        );
+        let buscar = format!(
+            "{} WHERE id = $1",
+            selecionar::<RequisicaoIdempotente>("idempotencia")
+        );
+        loop {
+            if self.executar(&inserir, requisicao.valores()).await? > 0 {
+                return Ok(None);
+            }
+            // Pode ter sido liberada entre a inserção e a leitura.
+            let existente = self
+                .consultar_um(&buscar, vec![requisicao.id.clone().into()])
+                .await?;
+            if existente.is_some() {
+                return Ok(existente);
+            }
+        }
+    }
+
+    async fn concluir(&self, id: &str, resposta: &RespostaGravada) -> Result<()> {
+        self.executar(
+            "UPDATE idempotencia SET status = $1, cabecalhos = $2, corpo = $3 WHERE id = $4",
+            vec![
+                Valor::Inteiro(Some(i64::from(resposta.status))),
+                Valor::json(&resposta.cabecalhos),
+                resposta.corpo.clone().into(),
+                id.into(),
+            ],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn liberar(&self, id: &str) -> Result<()> {
+        self.executar(
+            "DELETE FROM idempotencia WHERE id = $1 AND status IS NULL",
+            vec![id.into()],
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,814 @@
+//! Repositórios sobre SQLite ou Postgres, via SQLx com o driver `Any`.
+//!
+//! Os ids continuam sendo `ObjectId`, gravados em hexadecimal, para que a
+//! API seja a mesma nos dois armazenamentos. Datas ficam em texto ISO 8601
+//! (que ordena como data), booleanos em inteiros, listas embutidas, como
+//! os itens da venda, em JSON e arquivos, como o DANFE, em `BLOB`/`BYTEA`. O esquema é criado pelas migrações de
+//! `database/migrations` (SQLite) e `database/migrations/postgres`.
+
+mod auditoria;
+mod cadastros;
+mod compras;
+mod contabilidade;
+mod estoque;
+mod financeiro;
+mod idempotencia;
+mod vendas;
+
+use std::future::Future;
+
+use axum::async_trait;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{
+    any::{AnyArguments, AnyPoolOptions, AnyRow},
+    migrate::MigrateDatabase,
+    query::Query,
+    Any, AnyPool, Row,
+};
+
+use super::{Registro, Repositorio, RepositorioVersionado, Versionado};
+use crate::{
+    consulta::{Consulta, Listavel, Operador, Pagina, ValorFiltro},
+    error::{AppError, Result},
+    models::Dinheiro,
+};
+
+pub struct Sql {
+    pool: AnyPool,
+}
+
+impl Sql {
+    /// Conecta ao banco de `url` (`sqlite://...` ou `postgres://...`),
+    /// criando-o se necessário, e aplica as migrações pendentes.
+    pub async fn conectar(url: &str) -> anyhow::Result<Self> {
+        sqlx::any::install_default_drivers();
+
+        // Cada conexão a um SQLite em memória abre um banco diferente.
+        let em_memoria = url.contains(":memory:");
+        if !em_memoria && !Any::database_exists(url).await? {
+            Any::create_database(url).await?;
+        }
+        let pool = AnyPoolOptions::new()
+            .max_connections(if em_memoria { 1 } else { 5 })
+            .connect(url)
+            .await?;
+
+        let migrator = if url.starts_with("postgres") {
+            sqlx::migrate!("../database/migrations/postgres")
+        } else {
+            sqlx::migrate!("../database/migrations")
+        };
+        migrator.run(&pool).await?;
+
+        Ok(Self { pool })
+    }
+
+    async fn consultar<T: Tabela>(&self, sql: &str, parametros: Vec<Valor>) -> Result<Vec<T>> {
+        let linhas = vincular(sqlx::query(sql), parametros)
+            .fetch_all(&self.pool)
+            .await?;
+        linhas
+            .iter()
+            .map(|linha| T::da_linha(&Linha(linha)))
+            .collect()
+    }
+
+    async fn consultar_um<T: Tabela>(
+        &self,
+        sql: &str,
+        parametros: Vec<Valor>,
+    ) -> Result<Option<T>> {
+        let linha = vincular(sqlx::query(sql), parametros)
+            .fetch_optional(&self.pool)
+            .await?;
+        linha.map(|linha| T::da_linha(&Linha(&linha))).transpose()
+    }
+
+    async fn consultar_linhas(&self, sql: &str, parametros: Vec<Valor>) -> Result<Vec<AnyRow>> {
+        Ok(vincular(sqlx::query(sql), parametros)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Executa o comando e retorna quantas linhas foram afetadas.
+    async fn executar(&self, sql: &str, parametros: Vec<Valor>) -> Result<u64> {
+        let result = vincular(sqlx::query(sql), parametros)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Reserva o próximo número da sequência `chave`. Na primeira vez a
+    /// sequência parte de `ultimo`, o maior número já usado pelos registros
+    /// gravados antes dela.
+    async fn proximo_numero<F>(&self, chave: &str, ultimo: impl Fn() -> F + Send) -> Result<u64>
+    where
+        F: Future<Output = Result<u64>> + Send,
+    {
+        loop {
+            let linhas = self
+                .consultar_linhas(
+                    "UPDATE sequencias SET valor = valor + 1 WHERE chave = $1 RETURNING valor",
+                    vec![chave.into()],
+                )
+                .await?;
+            if let Some(linha) = linhas.first() {
+                return Ok(Linha(linha).inteiro("valor")? as u64);
+            }
+
+            // Outra requisição pode criar a sequência antes; aí o próximo
+            // passo do laço a incrementa.
+            let proximo = ultimo().await? + 1;
+            let criada = self
+                .executar(
+                    "INSERT INTO sequencias (chave, valor) VALUES ($1, $2) \
+                     ON CONFLICT (chave) DO NOTHING",
+                    vec![chave.into(), Valor::Inteiro(Some(proximo as i64))],
+                )
+                .await?;
+            if criada > 0 {
+                return Ok(proximo);
+            }
+        }
+    }
+
+    /// Insere todos os registros numa única transação.
+    async fn inserir<T: Tabela>(&self, tabela: &str, registros: &[T]) -> Result<()> {
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            tabela,
+            T::COLUNAS.join(", "),
+            marcadores(1, T::COLUNAS.len())
+        );
+
+        let mut tx = self.pool.begin().await?;
+        for registro in registros {
+            vincular(sqlx::query(&sql), registro.valores())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Regrava as colunas do registro. As `chaves` primeiras colunas (o id
+    /// e, quando houver, o `empresa_id`) identificam a linha; `condicao`
+    /// restringe a alteração a um valor atual de outra coluna.
+    async fn atualizar<T: Tabela>(
+        &self,
+        tabela: &str,
+        registro: &T,
+        chaves: usize,
+        condicao: Option<(&str, Valor)>,
+    ) -> Result<bool> {
+        let mut valores = registro.valores();
+        let total = valores.len();
+        let atribuicoes: Vec<String> = T::COLUNAS[chaves..]
+            .iter()
+            .enumerate()
+            .map(|(indice, coluna)| format!("{} = ${}", coluna, indice + 1))
+            .collect();
+        let mut filtros: Vec<String> = T::COLUNAS[..chaves]
+            .iter()
+            .enumerate()
+            .map(|(indice, coluna)| format!("{} = ${}", coluna, total - chaves + indice + 1))
+            .collect();
+
+        // Os parâmetros seguem a ordem do comando: valores e depois chaves.
+        valores.rotate_left(chaves);
+        if let Some((coluna, valor)) = condicao {
+            filtros.push(format!("{} = ${}", coluna, total + 1));
+            valores.push(valor);
+        }
+
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            tabela,
+            atribuicoes.join(", "),
+            filtros.join(" AND ")
+        );
+        Ok(self.executar(&sql, valores).await? > 0)
+    }
+
+    /// Uma página dos registros da `tabela` que atendem às `condicoes`
+    /// (com os marcadores `$1`, `$2`... dos `parametros`) e à consulta.
+    async fn paginar_tabela<T: Tabela + Listavel>(
+        &self,
+        tabela: &str,
+        condicoes: Vec<String>,
+        parametros: Vec<Valor>,
+        consulta: &Consulta<T>,
+    ) -> Result<Pagina<T>> {
+        let (condicoes, mut parametros) = condicoes_da_consulta(condicoes, parametros, consulta);
+        let onde = if condicoes.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", condicoes)
+        };
+
+        let sql = format!("SELECT COUNT(*) AS total FROM {}{}", tabela, onde);
+        let total: i64 = vincular(sqlx::query(&sql), parametros.clone())
+            .fetch_one(&self.pool)
+            .await?
+            .try_get("total")?;
+
+        // Os campos de `ordem` vêm de `Listavel::CAMPOS`, nunca da requisição.
+        let direcao = if consulta.ordem.decrescente {
+            "DESC"
+        } else {
+            "ASC"
+        };
+        let sql = format!(
+            "{}{} ORDER BY {} {}, id {} LIMIT ${} OFFSET ${}",
+            selecionar::<T>(tabela),
+            onde,
+            consulta.ordem.campo,
+            direcao,
+            direcao,
+            parametros.len() + 1,
+            parametros.len() + 2
+        );
+        parametros.push(Valor::Inteiro(Some(consulta.limite as i64)));
+        parametros.push(Valor::Inteiro(Some(consulta.deslocamento() as i64)));
+        let itens = self.consultar(&sql, parametros).await?;
+
+        Ok(consulta.pagina(itens, total as u64))
+    }
+}
+
+#[async_trait]
+impl<T> Repositorio<T> for Sql
+where
+    T: Registro + Tabela + Listavel + Send + Sync + 'static,
+{
+    async fn listar(&self, empresa_id: ObjectId) -> Result<Vec<T>> {
+        let sql = format!("{} WHERE empresa_id = $1", selecionar::<T>(T::COLECAO));
+        self.consultar(&sql, vec![empresa_id.into()]).await
+    }
+
+    async fn paginar(&self, empresa_id: ObjectId, consulta: &Consulta<T>) -> Result<Pagina<T>> {
+        self.paginar_tabela(
+            T::COLECAO,
+            vec!["empresa_id = $1".to_string()],
+            vec![empresa_id.into()],
+            consulta,
+        )
+        .await
+    }
+
+    async fn buscar(&self, empresa_id: ObjectId, id: ObjectId) -> Result<Option<T>> {
+        let sql = format!(
+            "{} WHERE id = $1 AND empresa_id = $2",
+            selecionar::<T>(T::COLECAO)
+        );
+        self.consultar_um(&sql, vec![id.into(), empresa_id.into()])
+            .await
+    }
+
+    async fn criar(&self, mut registro: T) -> Result<T> {
+        if registro.id().is_none() {
+            registro.definir_id(ObjectId::new());
+        }
+        self.inserir(T::COLECAO, std::slice::from_ref(&registro))
+            .await?;
+        Ok(registro)
+    }
+
+    async fn criar_varios(&self, mut registros: Vec<T>) -> Result<Vec<T>> {
+        for registro in registros
+            .iter_mut()
+            .filter(|registro| registro.id().is_none())
+        {
+            registro.definir_id(ObjectId::new());
+        }
+        self.inserir(T::COLECAO, &registros).await?;
+        Ok(registros)
+    }
+
+    async fn salvar(&self, registro: &T) -> Result<bool> {
+        self.atualizar(T::COLECAO, registro, 2, None).await
+    }
+
+    async fn excluir(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let sql = format!(
+            "DELETE FROM {} WHERE id = $1 AND empresa_id = $2",
+            T::COLECAO
+        );
+        Ok(self
+            .executar(&sql, vec![id.into(), empresa_id.into()])
+            .await?
+            > 0)
+    }
+}
+
+#[async_trait]
+impl<T> RepositorioVersionado<T> for Sql
+where
+    T: Versionado + Tabela + Listavel + Send + Sync + 'static,
+{
+    async fn salvar_versao(&self, registro: &mut T) -> Result<bool> {
+        let lida = registro.versao();
+        registro.definir_versao(lida + 1);
+        let gravado = self
+            .atualizar(T::COLECAO, &*registro, 2, Some(("versao", lida.into())))
+            .await?;
+        if !gravado {
+            registro.definir_versao(lida);
+        }
+        Ok(gravado)
+    }
+
+    async fn excluir_versao(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        versao: u32,
+    ) -> Result<bool> {
+        let sql = format!(
+            "DELETE FROM {} WHERE id = $1 AND empresa_id = $2 AND versao = $3",
+            T::COLECAO
+        );
+        Ok(self
+            .executar(&sql, vec![id.into(), empresa_id.into(), versao.into()])
+            .await?
+            > 0)
+    }
+}
+
+/// Cláusula `WHERE` com os filtros e a busca da consulta, restrita à
+/// empresa, e os parâmetros dela.
+/// Acrescenta às `condicoes` iniciais, e seus `parametros`, os filtros e a
+/// busca da consulta.
+fn condicoes_da_consulta<T: Listavel>(
+    mut condicoes: Vec<String>,
+    mut parametros: Vec<Valor>,
+    consulta: &Consulta<T>,
+) -> (String, Vec<Valor>) {
+    for filtro in &consulta.filtros {
+        if filtro.operador == Operador::Preenchido {
+            let nulo = if filtro.valor == ValorFiltro::Booleano(true) {
+                "IS NOT NULL"
+            } else {
+                "IS NULL"
+            };
+            condicoes.push(format!("{} {}", filtro.campo, nulo));
+            continue;
+        }
+        parametros.push(match &filtro.valor {
+            ValorFiltro::Texto(texto) => texto.as_str().into(),
+            ValorFiltro::Numero(numero) => (*numero).into(),
+            ValorFiltro::Dinheiro(valor) => (*valor).into(),
+            ValorFiltro::Booleano(booleano) => (*booleano).into(),
+            ValorFiltro::Data(data) => (*data).into(),
+            ValorFiltro::Instante(instante) => (*instante).into(),
+            ValorFiltro::Id(id) => (*id).into(),
+        });
+        let operador = match filtro.operador {
+            Operador::Igual => "=",
+            Operador::De => ">=",
+            Operador::Ate => "<=",
+            Operador::Preenchido => unreachable!("tratado acima"),
+        };
+        condicoes.push(format!(
+            "{} {} ${}",
+            filtro.campo,
+            operador,
+            parametros.len()
+        ));
+    }
+
+    if let Some(busca) = &consulta.busca {
+        let literal: String = busca
+            .chars()
+            .flat_map(|caractere| match caractere {
+                '%' | '_' | '\\' => vec!['\\', caractere],
+                _ => vec![caractere],
+            })
+            .collect();
+        parametros.push(format!("%{}%", literal).into());
+        let alternativas: Vec<String> = T::BUSCA
+            .iter()
+            .map(|campo| format!("LOWER({}) LIKE ${} ESCAPE '\\'", campo, parametros.len()))
+            .collect();
+        condicoes.push(format!("({})", alternativas.join(" OR ")));
+    }
+
+    (condicoes.join(" AND "), parametros)
+}
+
+/// Mapeamento de um modelo para as colunas da sua tabela.
+trait Tabela: Sized {
+    /// Colunas na ordem de [`Tabela::valores`]. A primeira é o `id` e,
+    /// nas tabelas por empresa, a segunda é o `empresa_id`.
+    const COLUNAS: &'static [&'static str];
+
+    fn valores(&self) -> Vec<Valor>;
+    fn da_linha(linha: &Linha) -> Result<Self>;
+}
+
+fn selecionar<T: Tabela>(tabela: &str) -> String {
+    format!("SELECT {} FROM {}", T::COLUNAS.join(", "), tabela)
+}
+
+/// `$inicio, $inicio+1, ...` com `quantidade` marcadores.
+fn marcadores(inicio: usize, quantidade: usize) -> String {
+    (inicio..inicio + quantidade)
+        .map(|indice| format!("${}", indice))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parâmetro de um comando, já no tipo da coluna.
+#[derive(Clone)]
+enum Valor {
+    Texto(Option<String>),
+    Inteiro(Option<i64>),
+    Real(Option<f64>),
+    Bytes(Vec<u8>),
+}
+
+impl Valor {
+    /// Enums gravados com o mesmo nome que têm no JSON da API.
+    fn enumerado<E: Serialize>(valor: &E) -> Self {
+        Valor::Texto(
+            serde_json::to_value(valor)
+                .ok()
+                .and_then(|valor| valor.as_str().map(str::to_string)),
+        )
+    }
+
+    fn json<T: Serialize>(valor: &T) -> Self {
+        Valor::Texto(serde_json::to_string(valor).ok())
+    }
+
+    /// Documento embutido opcional, `NULL` quando ausente.
+    fn json_opt<T: Serialize>(valor: Option<&T>) -> Self {
+        Valor::Texto(valor.and_then(|valor| serde_json::to_string(valor).ok()))
+    }
+}
+
+impl From<String> for Valor {
+    fn from(valor: String) -> Self {
+        Valor::Texto(Some(valor))
+    }
+}
+
+impl From<&str> for Valor {
+    fn from(valor: &str) -> Self {
+        Valor::Texto(Some(valor.to_string()))
+    }
+}
+
+impl From<Option<String>> for Valor {
+    fn from(valor: Option<String>) -> Self {
+        Valor::Texto(valor)
+    }
+}
+
+impl From<ObjectId> for Valor {
+    fn from(valor: ObjectId) -> Self {
+        Valor::Texto(Some(valor.to_hex()))
+    }
+}
+
+impl From<Option<ObjectId>> for Valor {
+    fn from(valor: Option<ObjectId>) -> Self {
+        Valor::Texto(valor.map(|id| id.to_hex()))
+    }
+}
+
+impl From<f64> for Valor {
+    fn from(valor: f64) -> Self {
+        Valor::Real(Some(valor))
+    }
+}
+
+impl From<Option<f64>> for Valor {
+    fn from(valor: Option<f64>) -> Self {
+        Valor::Real(valor)
+    }
+}
+
+/// Valores monetários são gravados em centavos, em colunas inteiras.
+impl From<Dinheiro> for Valor {
+    fn from(valor: Dinheiro) -> Self {
+        Valor::Inteiro(Some(valor.centavos()))
+    }
+}
+
+impl From<Option<Dinheiro>> for Valor {
+    fn from(valor: Option<Dinheiro>) -> Self {
+        Valor::Inteiro(valor.map(|valor| valor.centavos()))
+    }
+}
+
+impl From<i32> for Valor {
+    fn from(valor: i32) -> Self {
+        Valor::Inteiro(Some(i64::from(valor)))
+    }
+}
+
+impl From<Option<i32>> for Valor {
+    fn from(valor: Option<i32>) -> Self {
+        Valor::Inteiro(valor.map(i64::from))
+    }
+}
+
+impl From<u32> for Valor {
+    fn from(valor: u32) -> Self {
+        Valor::Inteiro(Some(i64::from(valor)))
+    }
+}
+
+impl From<Vec<u8>> for Valor {
+    fn from(valor: Vec<u8>) -> Self {
+        Valor::Bytes(valor)
+    }
+}
+
+impl From<bool> for Valor {
+    fn from(valor: bool) -> Self {
+        Valor::Inteiro(Some(i64::from(valor)))
+    }
+}
+
+impl From<DateTime<Utc>> for Valor {
+    fn from(valor: DateTime<Utc>) -> Self {
+        Valor::Texto(Some(valor.to_rfc3339_opts(SecondsFormat::Micros, true)))
+    }
+}
+
+impl From<NaiveDate> for Valor {
+    fn from(valor: NaiveDate) -> Self {
+        Valor::Texto(Some(valor.to_string()))
+    }
+}
+
+impl From<Option<NaiveDate>> for Valor {
+    fn from(valor: Option<NaiveDate>) -> Self {
+        Valor::Texto(valor.map(|data| data.to_string()))
+    }
+}
+
+fn vincular<'q>(
+    mut query: Query<'q, Any, AnyArguments<'q>>,
+    parametros: Vec<Valor>,
+) -> Query<'q, Any, AnyArguments<'q>> {
+    for parametro in parametros {
+        query = match parametro {
+            Valor::Texto(valor) => query.bind(valor),
+            Valor::Inteiro(valor) => query.bind(valor),
+            Valor::Real(valor) => query.bind(valor),
+            Valor::Bytes(valor) => query.bind(valor),
+        };
+    }
+    query
+}
+
+/// Leitura tipada das colunas de uma linha.
+struct Linha<'a>(&'a AnyRow);
+
+fn coluna_invalida(coluna: &str, erro: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("Valor inválido na coluna {}: {}", coluna, erro))
+}
+
+impl Linha<'_> {
+    fn texto(&self, coluna: &str) -> Result<String> {
+        Ok(self.0.try_get(coluna)?)
+    }
+
+    fn texto_opt(&self, coluna: &str) -> Result<Option<String>> {
+        Ok(self.0.try_get(coluna)?)
+    }
+
+    fn inteiro(&self, coluna: &str) -> Result<i64> {
+        Ok(self.0.try_get(coluna)?)
+    }
+
+    fn inteiro_opt(&self, coluna: &str) -> Result<Option<i64>> {
+        Ok(self.0.try_get(coluna)?)
+    }
+
+    fn bytes(&self, coluna: &str) -> Result<Vec<u8>> {
+        Ok(self.0.try_get(coluna)?)
+    }
+
+    fn dinheiro(&self, coluna: &str) -> Result<Dinheiro> {
+        Ok(Dinheiro::de_centavos(self.inteiro(coluna)?))
+    }
+
+    fn dinheiro_opt(&self, coluna: &str) -> Result<Option<Dinheiro>> {
+        Ok(self.inteiro_opt(coluna)?.map(Dinheiro::de_centavos))
+    }
+
+    fn booleano(&self, coluna: &str) -> Result<bool> {
+        Ok(self.inteiro(coluna)? != 0)
+    }
+
+    fn oid(&self, coluna: &str) -> Result<ObjectId> {
+        ObjectId::parse_str(self.texto(coluna)?).map_err(|e| coluna_invalida(coluna, e))
+    }
+
+    fn oid_opt(&self, coluna: &str) -> Result<Option<ObjectId>> {
+        self.texto_opt(coluna)?
+            .map(|id| ObjectId::parse_str(id).map_err(|e| coluna_invalida(coluna, e)))
+            .transpose()
+    }
+
+    fn data(&self, coluna: &str) -> Result<NaiveDate> {
+        self.texto(coluna)?
+            .parse()
+            .map_err(|e| coluna_invalida(coluna, e))
+    }
+
+    fn data_opt(&self, coluna: &str) -> Result<Option<NaiveDate>> {
+        self.texto_opt(coluna)?
+            .map(|data| data.parse().map_err(|e| coluna_invalida(coluna, e)))
+            .transpose()
+    }
+
+    fn instante(&self, coluna: &str) -> Result<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.texto(coluna)?)
+            .map(|instante| instante.with_timezone(&Utc))
+            .map_err(|e| coluna_invalida(coluna, e))
+    }
+
+    fn enumerado<E: DeserializeOwned>(&self, coluna: &str) -> Result<E> {
+        serde_json::from_value(serde_json::Value::String(self.texto(coluna)?))
+            .map_err(|e| coluna_invalida(coluna, e))
+    }
+
+    fn json<T: DeserializeOwned>(&self, coluna: &str) -> Result<T> {
+        serde_json::from_str(&self.texto(coluna)?).map_err(|e| coluna_invalida(coluna, e))
+    }
+
+    fn json_opt<T: DeserializeOwned>(&self, coluna: &str) -> Result<Option<T>> {
+        self.texto_opt(coluna)?
+            .map(|texto| serde_json::from_str(&texto).map_err(|e| coluna_invalida(coluna, e)))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{
+        models::{Cliente, Produto},
+        repositorio::ProdutosRepositorio,
+    };
+
+    fn cliente(empresa_id: ObjectId) -> Cliente {
+        let agora = Utc::now();
+        Cliente {
+            id: None,
+            empresa_id,
+            nome: "Maria".to_string(),
+            cpf_cnpj: "529.982.247-25".to_string(),
+            telefone: None,
+            email: Some("maria@exemplo.com".to_string()),
+            endereco: None,
+            numero: None,
+            bairro: None,
+            cidade: Some("Curitiba".to_string()),
+            codigo_municipio: None,
+            estado: Some("PR".to_string()),
+            cep: None,
+            ativo: true,
+            created_at: agora,
+            updated_at: agora,
+            versao: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn aplica_todas_as_migracoes_no_sqlite() {
+        let sql = Sql::conectar("sqlite::memory:").await.unwrap();
+        let linhas = sql
+            .consultar_linhas(
+                "SELECT COUNT(*) AS total FROM _sqlx_migrations WHERE success = 1",
+                vec![],
+            )
+            .await
+            .unwrap();
+        let aplicadas = Linha(&linhas[0]).inteiro("total").unwrap();
+        let existentes = sqlx::migrate!("../database/migrations").iter().count();
+        assert_eq!(aplicadas as usize, existentes);
+
+        // Reabrir o mesmo banco não reaplica nada.
+        let arquivo = std::env::temp_dir().join(format!("erp-{}.db", ObjectId::new()));
+        let url = format!("sqlite://{}", arquivo.display());
+        Sql::conectar(&url).await.unwrap();
+        Sql::conectar(&url).await.unwrap();
+        std::fs::remove_file(arquivo).unwrap();
+    }
+
+    #[tokio::test]
+    async fn grava_le_altera_e_exclui_um_cadastro() {
+        let sql = Sql::conectar("sqlite::memory:").await.unwrap();
+        let empresa_id = ObjectId::new();
+
+        let criado = Repositorio::criar(&sql, cliente(empresa_id)).await.unwrap();
+        let id = criado.id.unwrap();
+        let lido: Cliente = Repositorio::buscar(&sql, empresa_id, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lido.nome, "Maria");
+        assert_eq!(lido.email.as_deref(), Some("maria@exemplo.com"));
+        assert_eq!(lido.telefone, None);
+        assert!(lido.ativo);
+        assert_eq!(
+            lido.created_at.timestamp_micros(),
+            criado.created_at.timestamp_micros()
+        );
+        let outra_empresa: Option<Cliente> = Repositorio::buscar(&sql, ObjectId::new(), id)
+            .await
+            .unwrap();
+        assert!(outra_empresa.is_none());
+
+        let mut alterado = lido.clone();
+        alterado.nome = "Maria Silva".to_string();
+        alterado.ativo = false;
+        assert!(sql.salvar_versao(&mut alterado).await.unwrap());
+        assert_eq!(alterado.versao, 1);
+        let mut desatualizado = lido;
+        assert!(!sql.salvar_versao(&mut desatualizado).await.unwrap());
+        assert_eq!(desatualizado.versao, 0);
+
+        let lido: Cliente = Repositorio::buscar(&sql, empresa_id, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lido.nome, "Maria Silva");
+        assert!(!lido.ativo);
+        let todos: Vec<Cliente> = Repositorio::listar(&sql, empresa_id).await.unwrap();
+        assert_eq!(todos.len(), 1);
+
+        assert!(
+            !RepositorioVersionado::<Cliente>::excluir_versao(&sql, empresa_id, id, 0)
+                .await
+                .unwrap()
+        );
+        assert!(Repositorio::<Cliente>::excluir(&sql, empresa_id, id)
+            .await
+            .unwrap());
+        assert!(!Repositorio::<Cliente>::excluir(&sql, empresa_id, id)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn preserva_valores_e_json_do_produto() {
+        let sql = Sql::conectar("sqlite::memory:").await.unwrap();
+        let empresa_id = ObjectId::new();
+        let agora = Utc::now();
+        let produto = Produto {
+            id: None,
+            empresa_id,
+            nome: "Caneta".to_string(),
+            descricao: Some("Azul".to_string()),
+            codigo_barras: None,
+            preco_custo: "4.35".parse().unwrap(),
+            preco_venda: "10.99".parse().unwrap(),
+            estoque_atual: 2,
+            estoque_minimo: 1,
+            unidade: "UN".to_string(),
+            fiscal: Some(
+                serde_json::from_value(serde_json::json!({
+                    "ncm": "96081000",
+                    "cfop": "5102",
+                    "cst_icms": "00",
+                    "aliquota_icms": "18.5",
+                    "cst_pis_cofins": "01",
+                }))
+                .unwrap(),
+            ),
+            ativo: true,
+            created_at: agora,
+            updated_at: agora,
+        };
+        let id = Repositorio::criar(&sql, produto.clone())
+            .await
+            .unwrap()
+            .id
+            .unwrap();
+
+        assert!(sql.ajustar_estoque(empresa_id, id, -2).await.unwrap());
+        assert!(!sql.ajustar_estoque(empresa_id, id, -1).await.unwrap());
+        let lido: Produto = Repositorio::buscar(&sql, empresa_id, id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(lido.estoque_atual, 0);
+        assert_eq!(lido.preco_custo, produto.preco_custo);
+        assert_eq!(lido.preco_venda, produto.preco_venda);
+        assert_eq!(
+            serde_json::to_value(&lido.fiscal).unwrap(),
+            serde_json::to_value(&produto.fiscal).unwrap()
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use super::{selecionar, Linha, Sql, Tabela, Valor};
+use crate::{
+    error::Result,
+    models::{ModeloNota, NotaFiscal, StatusVenda, Venda},
+    repositorio::{NotasFiscaisRepositorio, Registro, VendasRepositorio},
+};
+
+/// Os itens ficam embutidos na linha da venda, em JSON, como no documento
+/// do MongoDB: a venda é sempre lida e gravada inteira.
+impl Tabela for Venda {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "cliente_id",
+        "itens",
+        "total",
+        "desconto",
+        "total_final",
+        "tributos",
+        "forma_pagamento",
+        "status",
+        "observacoes",
+        "usuario",
+        "created_at",
+        "updated_at",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.cliente_id.into(),
+            Valor::json(&self.itens),
+            self.total.into(),
+            self.desconto.into(),
+            self.total_final.into(),
+            Valor::json_opt(self.tributos.as_ref()),
+            self.forma_pagamento.clone().into(),
+            self.status.as_str().into(),
+            self.observacoes.clone().into(),
+            self.usuario.clone().into(),
+            self.created_at.into(),
+            self.updated_at.into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            cliente_id: linha.oid_opt("cliente_id")?,
+            itens: linha.json("itens")?,
+            total: linha.dinheiro("total")?,
+            desconto: linha.dinheiro("desconto")?,
+            total_final: linha.dinheiro("total_final")?,
+            tributos: linha.json_opt("tributos")?,
+            forma_pagamento: linha.texto("forma_pagamento")?,
+            status: linha.enumerado("status")?,
+            observacoes: linha.texto_opt("observacoes")?,
+            usuario: linha.texto_opt("usuario")?,
+            created_at: linha.instante("created_at")?,
+            updated_at: linha.instante("updated_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl VendasRepositorio for Sql {
+    async fn finalizadas_desde(
+        &self,
+        empresa_id: ObjectId,
+        desde: DateTime<Utc>,
+    ) -> Result<Vec<Venda>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND status = $2 AND created_at >= $3",
+            selecionar::<Venda>(Venda::COLECAO)
+        );
+        self.consultar(
+            &sql,
+            vec![
+                empresa_id.into(),
+                StatusVenda::Finalizada.as_str().into(),
+                desde.into(),
+            ],
+        )
+        .await
+    }
+
+    async fn salvar_aberta(&self, venda: &Venda) -> Result<bool> {
+        self.atualizar(
+            Venda::COLECAO,
+            venda,
+            2,
+            Some(("status", StatusVenda::Aberta.as_str().into())),
+        )
+        .await
+    }
+
+    async fn transicionar(
+        &self,
+        empresa_id: ObjectId,
+        id: ObjectId,
+        de: StatusVenda,
+        para: StatusVenda,
+    ) -> Result<bool> {
+        let alteradas = self
+            .executar(
+                "UPDATE vendas SET status = $1, updated_at = $2 \
+                 WHERE id = $3 AND empresa_id = $4 AND status = $5",
+                vec![
+                    para.as_str().into(),
+                    Utc::now().into(),
+                    id.into(),
+                    empresa_id.into(),
+                    de.as_str().into(),
+                ],
+            )
+            .await?;
+        Ok(alteradas > 0)
+    }
+
+    async fn excluir_aberta(&self, empresa_id: ObjectId, id: ObjectId) -> Result<bool> {
+        let excluidas = self
+            .executar(
+                "DELETE FROM vendas WHERE id = $1 AND empresa_id = $2 AND status = $3",
+                vec![
+                    id.into(),
+                    empresa_id.into(),
+                    StatusVenda::Aberta.as_str().into(),
+                ],
+            )
+            .await?;
+        Ok(excluidas > 0)
+    }
+}
+
+impl Tabela for NotaFiscal {
+    const COLUNAS: &'static [&'static str] = &[
+        "id",
+        "empresa_id",
+        "venda_id",
+        "modelo",
+        "serie",
+        "numero",
+        "chave",
+        "ambiente",
+        "valor",
+        "status",
+        "protocolo",
+        "emitida_em",
+        "autorizada_em",
+        "xml",
+        "danfe",
+    ];
+
+    fn valores(&self) -> Vec<Valor> {
+        vec![
+            self.id.into(),
+            self.empresa_id.into(),
+            self.venda_id.into(),
+            self.modelo.as_str().into(),
+            self.serie.into(),
+            self.numero.into(),
+            self.chave.clone().into(),
+            Valor::enumerado(&self.ambiente),
+            self.valor.into(),
+            self.status.as_str().into(),
+            self.protocolo.clone().into(),
+            self.emitida_em.into(),
+            self.autorizada_em.into(),
+            self.xml.clone().into(),
+            self.danfe.clone().into(),
+        ]
+    }
+
+    fn da_linha(linha: &Linha) -> Result<Self> {
+        Ok(Self {
+            id: Some(linha.oid("id")?),
+            empresa_id: linha.oid("empresa_id")?,
+            venda_id: linha.oid("venda_id")?,
+            modelo: linha.enumerado("modelo")?,
+            serie: linha.inteiro("serie")? as u32,
+            numero: linha.inteiro("numero")? as u32,
+            chave: linha.texto("chave")?,
+            ambiente: linha.enumerado("ambiente")?,
+            valor: linha.dinheiro("valor")?,
+            status: linha.enumerado("status")?,
+            protocolo: linha.texto("protocolo")?,
+            emitida_em: linha.instante("emitida_em")?,
+            autorizada_em: linha.instante("autorizada_em")?,
+            xml: linha.texto("xml")?,
+            danfe: linha.bytes("danfe")?,
+        })
+    }
+}
+
+#[async_trait]
+impl NotasFiscaisRepositorio for Sql {
+    async fn da_venda(
+        &self,
+        empresa_id: ObjectId,
+        venda_id: ObjectId,
+    ) -> Result<Option<NotaFiscal>> {
+        let sql = format!(
+            "{} WHERE empresa_id = $1 AND venda_id = $2",
+            selecionar::<NotaFiscal>(NotaFiscal::COLECAO)
+        );
+        self.consultar_um(&sql, vec![empresa_id.into(), venda_id.into()])
+            .await
+    }
+
+    async fn proximo_numero_da_serie(
+        &self,
+        empresa_id: ObjectId,
+        modelo: ModeloNota,
+        serie: u32,
+    ) -> Result<u32> {
+        let chave = format!("notas_fiscais:{}:{}:{}", empresa_id, modelo.as_str(), serie);
+        let numero = self
+            .proximo_numero(&chave, || {
+                self.maior_numero_da_serie(empresa_id, modelo, serie)
+            })
+            .await?;
+        Ok(numero as u32)
+    }
+}
+
+impl Sql {
+    async fn maior_numero_da_serie(
+        &self,
+        empresa_id: ObjectId,
+        modelo: ModeloNota,
+        serie: u32,
+    ) -> Result<u64> {
+        let linhas = self
+            .consultar_linhas(
+                "SELECT CAST(COALESCE(MAX(numero), 0) AS BIGINT) AS maximo \
+                 FROM notas_fiscais WHERE empresa_id = $1 AND modelo = $2 AND serie = $3",
+                vec![empresa_id.into(), modelo.as_str().into(), serie.into()],
+            )
+            .await?;
+        match linhas.first() {
+            Some(linha) => Ok(Linha(linha).inteiro("maximo")? as u64),
+            None => Ok(0),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    consulta::{Consulta, Filtro, Operador, Pagina, ValorFiltro},
+    empresa::EmpresaAtual,
+    error::Result,
+    models::*,
+    repositorio::Repositorios,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct RegistroAuditoriaResponse {
+    pub id: Option<String>,
+    pub entidade: String,
+    pub entidade_id: String,
+    pub operacao: OperacaoAuditoria,
+    pub usuario_id: String,
+    pub usuario: String,
+    pub requisicao_id: String,
+    pub instante: DateTime<Utc>,
+    pub alteracoes: Vec<Alteracao>,
+}
+
+impl From<RegistroAuditoria> for RegistroAuditoriaResponse {
+    fn from(registro: RegistroAuditoria) -> Self {
+        Self {
+            id: registro.id.map(|id| id.to_hex()),
+            entidade: registro.entidade,
+            entidade_id: registro.entidade_id.to_hex(),
+            operacao: registro.operacao,
+            usuario_id: registro.usuario_id.to_hex(),
+            usuario: registro.usuario,
+            requisicao_id: registro.requisicao_id,
+            instante: registro.instante,
+            alteracoes: registro.alteracoes,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_auditoria))
+        .route("/:entidade/:id", get(historico))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_auditoria, historico))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<RegistroAuditoria>),
+    responses((status = 200, body = Pagina<RegistroAuditoriaResponse>))
+)]
+async fn list_auditoria(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<RegistroAuditoria>,
+) -> Result<Json<Pagina<RegistroAuditoriaResponse>>> {
+    let registros = repos.auditoria.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(registros.map(RegistroAuditoriaResponse::from)))
+}
+
+/// Histórico de um registro (`/auditoria/vendas/<id>`), do mais recente ao
+/// mais antigo.
+#[utoipa::path(
+    get,
+    path = "/{entidade}/{id}",
+    params(
+        EmpresaAtual,
+        ("entidade" = String, Path, description = "Coleção do registro, como `vendas`"),
+        ("id" = String, Path),
+        Consulta<RegistroAuditoria>
+    ),
+    responses((status = 200, body = Pagina<RegistroAuditoriaResponse>))
+)]
+async fn historico(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path((entidade, id)): Path<(String, String)>,
+    mut consulta: Consulta<RegistroAuditoria>,
+) -> Result<Json<Pagina<RegistroAuditoriaResponse>>> {
+    let oid = ObjectId::parse_str(&id)?;
+    consulta.filtros.push(Filtro {
+        campo: "entidade",
+        operador: Operador::Igual,
+        valor: ValorFiltro::Texto(entidade),
+    });
+    consulta.filtros.push(Filtro {
+        campo: "entidade_id",
+        operador: Operador::Igual,
+        valor: ValorFiltro::Id(oid),
+    });
+
+    let registros = repos.auditoria.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(registros.map(RegistroAuditoriaResponse::from)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    #[tokio::test]
+    async fn registra_criacao_alteracao_e_exclusao() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let clientes = || crate::routes::clientes::routes(ambiente.repos.clone());
+        let (_, cliente) = ambiente
+            .enviar(
+                clientes(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Maria", "cpf_cnpj": "529.982.247-25" })),
+            )
+            .await;
+        let id = cliente["id"].as_str().unwrap();
+        let uri = format!("/{id}");
+        ambiente
+            .enviar(
+                clientes(),
+                Method::PUT,
+                &uri,
+                Some(json!({ "nome": "Marta" })),
+            )
+            .await;
+        ambiente
+            .enviar(clientes(), Method::DELETE, &uri, None)
+            .await;
+
+        let (status, historico) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                &format!("/clientes/{id}"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{historico}");
+        let registros = historico["itens"].as_array().unwrap();
+        let operacoes: Vec<&str> = registros
+            .iter()
+            .map(|registro| registro["operacao"].as_str().unwrap())
+            .collect();
+        assert_eq!(operacoes, ["EXCLUSAO", "ALTERACAO", "CRIACAO"]);
+        assert!(registros
+            .iter()
+            .all(|registro| registro["usuario"] == "teste@exemplo.com"));
+        assert!(registros[1]["alteracoes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!({ "campo": "nome", "antes": "Maria", "depois": "Marta" })));
+
+        let outra = ambiente.outra_empresa().await;
+        let (_, historico) = outra
+            .enviar(
+                routes(outra.repos.clone()),
+                Method::GET,
+                &format!("/clientes/{id}"),
+                None,
+            )
+            .await;
+        assert_eq!(historico["total"], 0);
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auth::{self, ChavesJwt, UsuarioAutenticado},
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct LoginResponse {
+    pub token: String,
+    pub expira_em: String,
+    pub usuario: UsuarioLogado,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct UsuarioLogado {
+    pub id: String,
+    pub nome: String,
+    pub email: String,
+    pub papeis: Vec<Papel>,
+    pub empresas: Vec<String>,
+}
+
+impl From<UsuarioAutenticado> for UsuarioLogado {
+    fn from(usuario: UsuarioAutenticado) -> Self {
+        Self {
+            id: usuario.id.to_hex(),
+            nome: usuario.nome,
+            email: usuario.email,
+            papeis: usuario.papeis,
+            empresas: usuario.empresas.iter().map(|id| id.to_hex()).collect(),
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/login", post(login))
+        .route("/me", get(me))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(login, me))]
+pub struct Api;
+
+#[utoipa::path(
+    post,
+    path = "/login",
+    security(()),
+    responses((status = 200, body = LoginResponse))
+)]
+async fn login(
+    State(repos): State<Repositorios>,
+    Extension(chaves): Extension<Arc<ChavesJwt>>,
+    Json(input): Json<Login>,
+) -> Result<Json<LoginResponse>> {
+    let usuario = auth::find_usuario_por_email(&repos, &input.email)
+        .await?
+        .filter(|usuario| usuario.ativo)
+        .filter(|usuario| auth::verificar_senha(&input.senha, &usuario.senha_hash))
+        .ok_or(AppError::Unauthorized)?;
+
+    let (token, expira_em) = auth::emitir_token(&chaves, &usuario)?;
+
+    Ok(Json(LoginResponse {
+        token,
+        expira_em: expira_em.to_rfc3339(),
+        usuario: UsuarioAutenticado::do_cadastro(usuario)?.into(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/me",
+    responses((status = 200, body = UsuarioLogado))
+)]
+async fn me(usuario: UsuarioAutenticado) -> Json<UsuarioLogado> {
+    Json(usuario.into())
+}
@@ -1,33 +1,81 @@
-use axum::{extract::State, routing::get, Json, Router};
-use futures::stream::TryStreamExt;
-use mongodb::bson::doc;
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use erp_api::BancoResponse;
+use utoipa::OpenApi;
 
-use crate::{error::Result, models::Banco, mongodb::MongoDb};
+use crate::{
+    auditoria::Auditor,
+    auth::UsuarioAutenticado,
+    consulta::{Consulta, Pagina, ValorFiltro},
+    empresa::EmpresaAtual,
+    error::Result,
+    models::{Banco, Papel},
+    repositorio::Repositorios,
+};
 
-pub fn routes(mongo: MongoDb) -> Router {
+impl From<Banco> for BancoResponse {
+    fn from(banco: Banco) -> Self {
+        Self {
+            id: banco.id.map(|id| id.to_hex()),
+            codigo: banco.codigo,
+            nome: banco.nome,
+            pais: banco.pais,
+            tipo: banco.tipo,
+            ativo: banco.ativo,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
     Router::new()
         .route("/", get(list_bancos))
-        .route("/seed", get(seed_bancos))
-        .with_state(mongo)
+        .route("/seed", post(seed_bancos))
+        .with_state(repos)
 }
 
-async fn list_bancos(State(mongo): State<MongoDb>) -> Result<Json<Vec<Banco>>> {
-    let bancos: Vec<Banco> = mongo
-        .bancos()
-        .find(doc! { "ativo": true }, None)
-        .await?
-        .try_collect()
-        .await?;
-    Ok(Json(bancos))
+#[derive(OpenApi)]
+#[openapi(paths(list_bancos, seed_bancos))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Banco>),
+    responses((status = 200, body = Pagina<BancoResponse>))
+)]
+async fn list_bancos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    mut consulta: Consulta<Banco>,
+) -> Result<Json<Pagina<BancoResponse>>> {
+    // Os inativos só aparecem quando pedidos com `ativo=false`.
+    consulta.filtro_padrao("ativo", ValorFiltro::Booleano(true));
+    let bancos = repos.bancos.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(bancos.map(BancoResponse::from)))
 }
 
-async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
-    mongo.bancos().drop(None).await.ok();
+#[utoipa::path(
+    post,
+    path = "/seed",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn seed_bancos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    usuario: UsuarioAutenticado,
+) -> Result<Json<String>> {
+    usuario.exigir(&[Papel::Admin])?;
 
     let bancos = vec![
         // Brasil
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "001".into(),
             nome: "Banco do Brasil S.A.".into(),
             pais: "BR".into(),
@@ -36,6 +84,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "033".into(),
             nome: "Banco Santander Brasil S.A.".into(),
             pais: "BR".into(),
@@ -44,6 +93,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "104".into(),
             nome: "Caixa Econômica Federal".into(),
             pais: "BR".into(),
@@ -52,6 +102,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "237".into(),
             nome: "Banco Bradesco S.A.".into(),
             pais: "BR".into(),
@@ -60,6 +111,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "341".into(),
             nome: "Itaú Unibanco S.A.".into(),
             pais: "BR".into(),
@@ -68,6 +120,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "260".into(),
             nome: "Nu Pagamentos S.A. (Nubank)".into(),
             pais: "BR".into(),
@@ -76,6 +129,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "290".into(),
             nome: "Pagseguro Internet S.A.".into(),
             pais: "BR".into(),
@@ -84,6 +138,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "323".into(),
             nome: "Mercado Pago".into(),
             pais: "BR".into(),
@@ -92,6 +147,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "380".into(),
             nome: "PicPay Serviços S.A.".into(),
             pais: "BR".into(),
@@ -100,6 +156,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "336".into(),
             nome: "Banco C6 S.A.".into(),
             pais: "BR".into(),
@@ -108,6 +165,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "077".into(),
             nome: "Banco Inter S.A.".into(),
             pais: "BR".into(),
@@ -116,6 +174,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "212".into(),
             nome: "Banco Original S.A.".into(),
             pais: "BR".into(),
@@ -125,6 +184,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         // Portugal
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "0007".into(),
             nome: "Millennium BCP".into(),
             pais: "PT".into(),
@@ -133,6 +193,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "0033".into(),
             nome: "Santander Totta".into(),
             pais: "PT".into(),
@@ -141,6 +202,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "0035".into(),
             nome: "Caixa Geral de Depósitos".into(),
             pais: "PT".into(),
@@ -149,6 +211,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "0010".into(),
             nome: "Banco BPI".into(),
             pais: "PT".into(),
@@ -158,6 +221,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         // Internacional
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "HSBC".into(),
             nome: "HSBC Holdings".into(),
             pais: "GB".into(),
@@ -166,6 +230,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "CITI".into(),
             nome: "Citibank".into(),
             pais: "US".into(),
@@ -174,6 +239,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "JPMC".into(),
             nome: "JPMorgan Chase".into(),
             pais: "US".into(),
@@ -182,6 +248,7 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
         Banco {
             id: None,
+            empresa_id: empresa.id(),
             codigo: "BOFA".into(),
             nome: "Bank of America".into(),
             pais: "US".into(),
@@ -190,8 +257,59 @@ async fn seed_bancos(State(mongo): State<MongoDb>) -> Result<Json<String>> {
         },
     ];
 
-    mongo.bancos().insert_many(&bancos, None).await?;
-    let total = mongo.bancos().count_documents(None, None).await?;
+    let anteriores = repos.bancos.listar(empresa.id()).await?;
+    let inseridos = repos.bancos.substituir(empresa.id(), bancos).await?;
+    for banco in &anteriores {
+        auditor.exclusao(banco).await?;
+    }
+    for banco in &inseridos {
+        auditor.criacao(banco).await?;
+    }
+
+    Ok(Json(format!("✅ {} bancos inseridos!", inseridos.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    #[tokio::test]
+    async fn seed_exige_admin_e_fica_na_auditoria() {
+        let ambiente = Ambiente::novo(&[Papel::Financeiro]).await;
+        let rotas = || routes(ambiente.repos.clone());
+        let (status, _) = ambiente.enviar(rotas(), Method::POST, "/seed", None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = ambiente.enviar(rotas(), Method::GET, "/seed", None).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        let (_, bancos) = ambiente.enviar(rotas(), Method::GET, "/", None).await;
+        assert_eq!(bancos["total"], 0);
+
+        let mut admin = ambiente;
+        admin.usuario.papeis = vec![Papel::Admin];
+        let rotas = || routes(admin.repos.clone());
+        for _ in 0..2 {
+            let (status, _) = admin.enviar(rotas(), Method::POST, "/seed", None).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (_, bancos) = admin.enviar(rotas(), Method::GET, "/", None).await;
+        let total = bancos["total"].as_u64().unwrap();
+        assert!(total > 0);
 
-    Ok(Json(format!("✅ {} bancos inseridos!", total)))
+        // O segundo seed exclui os bancos do primeiro e cria outros.
+        let auditoria = || crate::routes::auditoria::routes(admin.repos.clone());
+        for (operacao, esperado) in [("CRIACAO", 2 * total), ("EXCLUSAO", total)] {
+            let (_, registros) = admin
+                .enviar(
+                    auditoria(),
+                    Method::GET,
+                    &format!("/?entidade=bancos&operacao={operacao}"),
+                    None,
+                )
+                .await;
+            assert_eq!(registros["total"], esperado, "{operacao}");
+        }
+    }
 }
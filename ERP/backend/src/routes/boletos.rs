@@ -0,0 +1,545 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    boleto::{self, Beneficiario},
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    openapi::Arquivo,
+    repositorio::Repositorios,
+    validacao::{self, Validar},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct BoletoResponse {
+    pub id: Option<String>,
+    pub conta_id: String,
+    pub lancamento_id: String,
+    pub banco: String,
+    pub nosso_numero: String,
+    pub numero_documento: String,
+    pub valor: Dinheiro,
+    pub vencimento: NaiveDate,
+    pub pagador: Pagador,
+    pub codigo_barras: String,
+    pub linha_digitavel: String,
+    pub status: StatusBoleto,
+    pub remessa: Option<u32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Boleto> for BoletoResponse {
+    fn from(boleto: Boleto) -> Self {
+        Self {
+            id: boleto.id.map(|id| id.to_hex()),
+            conta_id: boleto.conta_id.to_hex(),
+            lancamento_id: boleto.lancamento_id.to_hex(),
+            banco: boleto.banco,
+            nosso_numero: boleto.nosso_numero,
+            numero_documento: boleto.numero_documento,
+            valor: boleto.valor,
+            vencimento: boleto.vencimento,
+            pagador: boleto.pagador,
+            codigo_barras: boleto.codigo_barras,
+            linha_digitavel: boleto.linha_digitavel,
+            status: boleto.status,
+            remessa: boleto.remessa,
+            created_at: boleto.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RemessaQuery {
+    conta_id: String,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_boletos).post(create_boleto))
+        .route("/remessas", post(gerar_remessa))
+        .route("/remessas/:numero", get(get_remessa))
+        .route("/:id", get(get_boleto))
+        .route("/:id/pdf", get(get_pdf))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_boletos,
+    get_boleto,
+    create_boleto,
+    get_pdf,
+    gerar_remessa,
+    get_remessa
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Boleto>),
+    responses((status = 200, body = Pagina<BoletoResponse>))
+)]
+async fn list_boletos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Boleto>,
+) -> Result<Json<Pagina<BoletoResponse>>> {
+    let boletos = repos.boletos.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(boletos.map(BoletoResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = BoletoResponse))
+)]
+async fn get_boleto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<BoletoResponse>> {
+    let boleto = find_boleto(&repos, empresa, &id).await?;
+    Ok(Json(boleto.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = BoletoResponse))
+)]
+async fn create_boleto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateBoleto>,
+) -> Result<Json<BoletoResponse>> {
+    input.validar()?;
+
+    let conta_id = ObjectId::parse_str(&input.conta_id)?;
+    let beneficiario = find_beneficiario(&repos, empresa, conta_id).await?;
+
+    let lancamento_id = ObjectId::parse_str(&input.lancamento_id)?;
+    let lancamento = repos
+        .lancamentos
+        .buscar(empresa.id(), lancamento_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if lancamento.tipo != TipoLancamento::Receber || lancamento.status != StatusLancamento::Aberto {
+        return Err(AppError::Conflict(
+            "Só contas a receber em aberto podem ser cobradas por boleto".to_string(),
+        ));
+    }
+    if repos
+        .boletos
+        .do_lancamento(empresa.id(), lancamento_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Conflict(
+            "O lançamento já tem boleto emitido".to_string(),
+        ));
+    }
+
+    let cliente_id = lancamento.cliente_id.ok_or_else(|| {
+        AppError::BadRequest("Informe o cliente do lançamento para emitir o boleto".to_string())
+    })?;
+    let cliente = repos
+        .clientes
+        .buscar(empresa.id(), cliente_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if !validacao::cpf_valido(&cliente.cpf_cnpj) && !validacao::cnpj_valido(&cliente.cpf_cnpj) {
+        return Err(AppError::BadRequest(
+            "O pagador do boleto precisa de CPF ou CNPJ".to_string(),
+        ));
+    }
+
+    let vencimento = input.vencimento.unwrap_or(lancamento.vencimento);
+    if vencimento < Utc::now().date_naive() {
+        return Err(AppError::BadRequest(
+            "O vencimento do boleto não pode ser anterior a hoje".to_string(),
+        ));
+    }
+    let valor = input.valor.unwrap_or(lancamento.valor);
+
+    let sequencial = repos
+        .boletos
+        .proximo_sequencial(empresa.id(), conta_id)
+        .await?;
+    if sequencial > beneficiario.banco.maximo_sequencial() {
+        return Err(AppError::Conflict(
+            "Os nossos números da carteira se esgotaram".to_string(),
+        ));
+    }
+    let codigo_barras = beneficiario.codigo_barras(sequencial, valor, vencimento)?;
+
+    let boleto = repos
+        .boletos
+        .criar(Boleto {
+            id: None,
+            empresa_id: empresa.id(),
+            conta_id,
+            lancamento_id,
+            banco: beneficiario.banco.codigo().to_string(),
+            sequencial,
+            nosso_numero: beneficiario.nosso_numero(sequencial),
+            numero_documento: input
+                .numero_documento
+                .map(|numero| numero.trim().to_string())
+                .unwrap_or_else(|| sequencial.to_string()),
+            valor,
+            vencimento,
+            pagador: Pagador {
+                nome: cliente.nome,
+                documento: validacao::somente_digitos(&cliente.cpf_cnpj),
+                endereco: cliente.endereco,
+                cidade: cliente.cidade,
+                estado: cliente.estado,
+                cep: cliente.cep,
+            },
+            linha_digitavel: boleto::linha_digitavel(&codigo_barras),
+            codigo_barras,
+            status: StatusBoleto::Emitido,
+            remessa: None,
+            created_at: Utc::now(),
+        })
+        .await?;
+    auditor.criacao(&boleto).await?;
+
+    Ok(Json(boleto.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/pdf",
+    params(EmpresaAtual),
+    responses((status = 200, body = Arquivo, content_type = "application/pdf"))
+)]
+async fn get_pdf(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    let boleto = find_boleto(&repos, empresa, &id).await?;
+    let beneficiario = find_beneficiario(&repos, empresa, boleto.conta_id).await?;
+
+    let conteudo = boleto::pdf(&beneficiario, &boleto)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"boleto-{}.pdf\"", boleto.nosso_numero),
+            ),
+        ],
+        conteudo,
+    )
+        .into_response())
+}
+
+/// Remessa com os boletos da conta ainda não enviados ao banco, que passam
+/// a `REMETIDO`.
+#[utoipa::path(
+    post,
+    path = "/remessas",
+    params(EmpresaAtual),
+    responses((status = 200, body = Arquivo, content_type = "text/plain"))
+)]
+async fn gerar_remessa(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<GerarRemessa>,
+) -> Result<Response> {
+    let conta_id = ObjectId::parse_str(&input.conta_id)?;
+    let beneficiario = find_beneficiario(&repos, empresa, conta_id).await?;
+
+    let novos = repos.boletos.a_remeter(empresa.id(), conta_id).await?;
+    if novos.is_empty() {
+        return Err(AppError::BadRequest(
+            "Não há boletos novos para remeter nesta conta".to_string(),
+        ));
+    }
+    let numero = repos
+        .boletos
+        .proxima_remessa(empresa.id(), conta_id)
+        .await?;
+
+    // Uma remessa simultânea pode marcar parte dos boletos antes; eles
+    // ficam só nela.
+    let ids: Vec<ObjectId> = novos.iter().filter_map(|boleto| boleto.id).collect();
+    repos
+        .boletos
+        .remeter(empresa.id(), conta_id, &ids, numero)
+        .await?;
+    let boletos = repos
+        .boletos
+        .da_remessa(empresa.id(), conta_id, numero)
+        .await?;
+    if boletos.is_empty() {
+        return Err(AppError::Conflict(
+            "Os boletos novos já foram remetidos em outra remessa".to_string(),
+        ));
+    }
+    for boleto in &boletos {
+        if let Some(antes) = novos.iter().find(|novo| novo.id == boleto.id) {
+            auditor.alteracao(antes, boleto).await?;
+        }
+    }
+
+    let arquivo = boleto::remessa(&beneficiario, numero, Local::now().naive_local(), &boletos);
+
+    Ok(responder_remessa(&beneficiario, numero, arquivo))
+}
+
+/// Gera de novo o arquivo de uma remessa já enviada.
+#[utoipa::path(
+    get,
+    path = "/remessas/{numero}",
+    params(EmpresaAtual, ("numero" = u32, Path), RemessaQuery),
+    responses((status = 200, body = Arquivo, content_type = "text/plain"))
+)]
+async fn get_remessa(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(numero): Path<u32>,
+    Query(query): Query<RemessaQuery>,
+) -> Result<Response> {
+    let conta_id = ObjectId::parse_str(&query.conta_id)?;
+    let beneficiario = find_beneficiario(&repos, empresa, conta_id).await?;
+
+    let boletos = repos
+        .boletos
+        .da_remessa(empresa.id(), conta_id, numero)
+        .await?;
+    if boletos.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    let arquivo = boleto::remessa(&beneficiario, numero, Local::now().naive_local(), &boletos);
+    Ok(responder_remessa(&beneficiario, numero, arquivo))
+}
+
+fn responder_remessa(beneficiario: &Beneficiario, numero: u32, arquivo: String) -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/plain; charset=us-ascii".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"remessa-{}-{:06}.rem\"",
+                    beneficiario.banco.codigo(),
+                    numero
+                ),
+            ),
+        ],
+        arquivo,
+    )
+        .into_response()
+}
+
+async fn find_boleto(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<Boleto> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .boletos
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn find_beneficiario(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    conta_id: ObjectId,
+) -> Result<Beneficiario> {
+    let conta = repos
+        .contas
+        .buscar(empresa.id(), conta_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let dados = repos
+        .empresas
+        .buscar(empresa.id())
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Beneficiario::new(&conta, &dados)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        http::{Method, StatusCode},
+        response::IntoResponse,
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    /// Empresa com CNPJ, conta no Itaú com carteira de cobrança e dois
+    /// lançamentos a receber de um cliente; devolve a conta e os
+    /// lançamentos.
+    async fn cenario(ambiente: &Ambiente) -> (String, Vec<String>) {
+        let mut dados = ambiente
+            .repos
+            .empresas
+            .buscar(ambiente.empresa_id)
+            .await
+            .unwrap()
+            .unwrap();
+        dados.documento = Some("11222333000181".to_string());
+        ambiente.repos.empresas.salvar(&dados).await.unwrap();
+
+        let financeiro = || crate::routes::financeiro::routes(ambiente.repos.clone());
+        let (_, conta) = ambiente
+            .enviar(
+                financeiro(),
+                Method::POST,
+                "/contas",
+                Some(json!({
+                    "banco": "Itaú",
+                    "agencia": "1234",
+                    "conta": "12345-6",
+                    "tipo": "corrente",
+                    "saldo": "0.00",
+                    "boleto": { "banco": "341", "carteira": "109" },
+                })),
+            )
+            .await;
+        let (status, cliente) = ambiente
+            .enviar(
+                crate::routes::clientes::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Cliente", "cpf_cnpj": "529.982.247-25" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{cliente}");
+
+        let mut lancamentos = Vec::new();
+        for _ in 0..2 {
+            let (status, criados) = ambiente
+                .enviar(
+                    financeiro(),
+                    Method::POST,
+                    "/lancamentos",
+                    Some(json!({
+                        "tipo": "RECEBER",
+                        "descricao": "Mensalidade",
+                        "categoria": "Vendas",
+                        "valor": "150.75",
+                        "vencimento": "2099-03-10",
+                        "cliente_id": cliente["id"],
+                    })),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK, "{criados}");
+            lancamentos.push(criados[0]["id"].as_str().unwrap().to_string());
+        }
+        (conta["id"].as_str().unwrap().to_string(), lancamentos)
+    }
+
+    #[tokio::test]
+    async fn numera_boletos_e_remessas_sem_repetir() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (conta_id, lancamentos) = cenario(&ambiente).await;
+        let emitir = |lancamento_id: &str| {
+            ambiente.enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "lancamento_id": lancamento_id, "conta_id": conta_id })),
+            )
+        };
+
+        let ((status_a, a), (status_b, b)) =
+            tokio::join!(emitir(&lancamentos[0]), emitir(&lancamentos[1]));
+        assert_eq!(status_a, StatusCode::OK, "{a}");
+        assert_eq!(status_b, StatusCode::OK, "{b}");
+        let mut numeros = [
+            a["numero_documento"].as_str().unwrap(),
+            b["numero_documento"].as_str().unwrap(),
+        ];
+        numeros.sort();
+        assert_eq!(numeros, ["1", "2"]);
+
+        let remeter = || {
+            ambiente.enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/remessas",
+                Some(json!({ "conta_id": conta_id })),
+            )
+        };
+        let ((status_a, _), (status_b, _)) = tokio::join!(remeter(), remeter());
+        let mut status = [status_a, status_b];
+        status.sort();
+        assert_eq!(status[0], StatusCode::OK);
+        assert!(status[1].is_client_error());
+
+        let (_, boletos) = ambiente
+            .enviar(routes(ambiente.repos.clone()), Method::GET, "/", None)
+            .await;
+        let remessas: Vec<&Value> = boletos["itens"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|boleto| &boleto["remessa"])
+            .collect();
+        assert_eq!(remessas.len(), 2);
+        assert!(remessas[0].is_u64());
+        assert_eq!(remessas[0], remessas[1]);
+    }
+
+    #[tokio::test]
+    async fn sequencial_repetido_e_conflito() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (conta_id, lancamentos) = cenario(&ambiente).await;
+        let (status, boleto) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "lancamento_id": lancamentos[0], "conta_id": conta_id })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{boleto}");
+
+        let id = ObjectId::parse_str(boleto["id"].as_str().unwrap()).unwrap();
+        let mut repetido = repos_boleto(&ambiente, id).await;
+        repetido.id = None;
+        let erro = ambiente.repos.boletos.criar(repetido).await.unwrap_err();
+        assert!(erro.duplicado());
+        assert_eq!(erro.into_response().status(), StatusCode::CONFLICT);
+    }
+
+    async fn repos_boleto(ambiente: &Ambiente, id: ObjectId) -> Boleto {
+        ambiente
+            .repos
+            .boletos
+            .buscar(ambiente.empresa_id, id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+}
@@ -0,0 +1,415 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use erp_api::ClienteResponse;
+use mongodb::bson::oid::ObjectId;
+use utoipa::OpenApi;
+
+use crate::{
+    auditoria::Auditor,
+    concorrencia::{self, ComVersao, VersaoEsperada},
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, CorpoErro, Result},
+    models::*,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+impl From<Cliente> for ClienteResponse {
+    fn from(cliente: Cliente) -> Self {
+        Self {
+            id: cliente.id.map(|id| id.to_hex()),
+            nome: cliente.nome,
+            cpf_cnpj: cliente.cpf_cnpj,
+            telefone: cliente.telefone,
+            email: cliente.email,
+            endereco: cliente.endereco,
+            numero: cliente.numero,
+            bairro: cliente.bairro,
+            cidade: cliente.cidade,
+            codigo_municipio: cliente.codigo_municipio,
+            estado: cliente.estado,
+            cep: cliente.cep,
+            ativo: cliente.ativo,
+            versao: cliente.versao,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_clientes).post(create_cliente))
+        .route(
+            "/:id",
+            get(get_cliente).put(update_cliente).delete(delete_cliente),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_clientes,
+    get_cliente,
+    create_cliente,
+    update_cliente,
+    delete_cliente
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Cliente>),
+    responses((status = 200, body = Pagina<ClienteResponse>))
+)]
+async fn list_clientes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Cliente>,
+) -> Result<Json<Pagina<ClienteResponse>>> {
+    let clientes = repos.clientes.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(clientes.map(ClienteResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = ClienteResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
+async fn get_cliente(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<ComVersao<ClienteResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let cliente = repos
+        .clientes
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(ComVersao(cliente.versao, cliente.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = ClienteResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
+async fn create_cliente(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateCliente>,
+) -> Result<ComVersao<ClienteResponse>> {
+    input.validar()?;
+    let now = Utc::now();
+    let cliente = Cliente {
+        id: None,
+        empresa_id: empresa.id(),
+        nome: input.nome,
+        cpf_cnpj: input.cpf_cnpj,
+        telefone: input.telefone,
+        email: input.email,
+        endereco: input.endereco,
+        numero: input.numero,
+        bairro: input.bairro,
+        cidade: input.cidade,
+        codigo_municipio: input.codigo_municipio,
+        estado: input.estado,
+        cep: input.cep,
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+        versao: 0,
+    };
+
+    let created = repos.clientes.criar(cliente).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(ComVersao(created.versao, created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (
+            status = 200,
+            body = ClienteResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        ),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
+async fn update_cliente(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateCliente>,
+) -> Result<ComVersao<ClienteResponse>> {
+    input.validar()?;
+    let oid = ObjectId::parse_str(&id)?;
+
+    let mut cliente = repos
+        .clientes
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    versao.conferir(&cliente)?;
+    let antes = cliente.clone();
+
+    if let Some(nome) = input.nome {
+        cliente.nome = nome;
+    }
+    if let Some(cpf_cnpj) = input.cpf_cnpj {
+        cliente.cpf_cnpj = cpf_cnpj;
+    }
+    if let Some(telefone) = input.telefone {
+        cliente.telefone = Some(telefone);
+    }
+    if let Some(email) = input.email {
+        cliente.email = Some(email);
+    }
+    if let Some(endereco) = input.endereco {
+        cliente.endereco = Some(endereco);
+    }
+    if let Some(numero) = input.numero {
+        cliente.numero = Some(numero);
+    }
+    if let Some(bairro) = input.bairro {
+        cliente.bairro = Some(bairro);
+    }
+    if let Some(cidade) = input.cidade {
+        cliente.cidade = Some(cidade);
+    }
+    if let Some(codigo_municipio) = input.codigo_municipio {
+        cliente.codigo_municipio = Some(codigo_municipio);
+    }
+    if let Some(estado) = input.estado {
+        cliente.estado = Some(estado);
+    }
+    if let Some(cep) = input.cep {
+        cliente.cep = Some(cep);
+    }
+    if let Some(ativo) = input.ativo {
+        cliente.ativo = ativo;
+    }
+    cliente.updated_at = Utc::now();
+
+    if !repos.clientes.salvar_versao(&mut cliente).await? {
+        return Err(concorrencia::desatualizado());
+    }
+    auditor.alteracao(&antes, &cliente).await?;
+
+    Ok(ComVersao(cliente.versao, cliente.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (status = 200, body = String),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
+async fn delete_cliente(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if let Some(cliente) = repos.clientes.buscar(empresa.id(), oid).await? {
+        versao.conferir(&cliente)?;
+        if !repos
+            .clientes
+            .excluir_versao(empresa.id(), oid, cliente.versao)
+            .await?
+        {
+            return Err(concorrencia::desatualizado());
+        }
+        auditor.exclusao(&cliente).await?;
+    }
+    Ok(Json("Cliente excluído".to_string()))
+}
+
+/// Converte o id informado e garante que o cliente existe na empresa.
+pub(crate) async fn parse_cliente_id(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: Option<&str>,
+) -> Result<Option<ObjectId>> {
+    let Some(id) = id else {
+        return Ok(None);
+    };
+
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .clientes
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Some(oid))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{concorrencia::etag, routes::testes::Ambiente};
+
+    #[tokio::test]
+    async fn cadastra_consulta_e_exclui_cliente() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let rotas = || routes(ambiente.repos.clone());
+
+        let (status, erro) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "", "cpf_cnpj": "123.456.789-00", "email": "sem-arroba" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let campos: Vec<&str> = erro["campos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|campo| campo["campo"].as_str().unwrap())
+            .collect();
+        assert_eq!(campos, ["nome", "cpf_cnpj", "email"]);
+
+        let (status, cliente) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Maria", "cpf_cnpj": "529.982.247-25" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{cliente}");
+        let uri = format!("/{}", cliente["id"].as_str().unwrap());
+
+        let (status, lido) = ambiente.enviar(rotas(), Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(lido["nome"], "Maria");
+        assert_eq!(lido["ativo"], true);
+
+        let (status, pagina) = ambiente.enviar(rotas(), Method::GET, "/", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(pagina["total"], 1);
+
+        let (status, _) = ambiente.enviar(rotas(), Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = ambiente.enviar(rotas(), Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(rotas(), Method::PUT, &uri, Some(json!({ "nome": "Outra" })))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn cliente_de_outra_empresa_e_404() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let outra = ambiente.outra_empresa().await;
+        let (_, cliente) = outra
+            .enviar(
+                routes(outra.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Maria", "cpf_cnpj": "529.982.247-25" })),
+            )
+            .await;
+
+        let uri = format!("/{}", cliente["id"].as_str().unwrap());
+        let (status, _) = ambiente
+            .enviar(routes(ambiente.repos.clone()), Method::GET, &uri, None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(routes(ambiente.repos.clone()), Method::GET, "/abc", None)
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn if_match_confere_a_versao_e_recusa_etag_fraco() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let rotas = || routes(ambiente.repos.clone());
+        let (status, cliente) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Cliente", "cpf_cnpj": "529.982.247-25" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{cliente}");
+        let uri = format!("/{}", cliente["id"].as_str().unwrap());
+
+        let alterar = |if_match: String| {
+            let requisicao = axum::http::Request::builder()
+                .method(Method::PUT)
+                .uri(&uri)
+                .header(
+                    crate::empresa::CABECALHO_EMPRESA,
+                    ambiente.empresa_id.to_hex(),
+                )
+                .header("content-type", "application/json")
+                .header("if-match", if_match)
+                .body(axum::body::Body::from(
+                    json!({ "nome": "Outro" }).to_string(),
+                ))
+                .unwrap();
+            let rotas = rotas().layer(axum::Extension(ambiente.usuario.clone()));
+            async move {
+                use tower::ServiceExt;
+                rotas.oneshot(requisicao).await.unwrap().status()
+            }
+        };
+
+        let versao = cliente["versao"].as_u64().unwrap() as u32;
+        assert_eq!(
+            alterar(format!("W/{}", etag(versao))).await,
+            StatusCode::PRECONDITION_FAILED
+        );
+        assert_eq!(alterar(etag(versao + 7)).await, StatusCode::CONFLICT);
+        assert_eq!(alterar(etag(versao)).await, StatusCode::OK);
+        assert_eq!(alterar(etag(versao)).await, StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,999 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    auth::UsuarioAutenticado,
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    estoque::{self, NovaMovimentacao},
+    models::*,
+    repositorio::Repositorios,
+    validacao,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ItemPedidoCompraResponse {
+    pub id: String,
+    pub produto_id: String,
+    pub produto_nome: String,
+    pub quantidade: i32,
+    pub quantidade_recebida: i32,
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+}
+
+impl From<ItemPedidoCompra> for ItemPedidoCompraResponse {
+    fn from(item: ItemPedidoCompra) -> Self {
+        Self {
+            id: item.id.to_hex(),
+            produto_id: item.produto_id.to_hex(),
+            produto_nome: item.produto_nome,
+            quantidade: item.quantidade,
+            quantidade_recebida: item.quantidade_recebida,
+            preco_unitario: item.preco_unitario,
+            subtotal: item.subtotal,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ItemRecebidoResponse {
+    pub item_id: String,
+    pub produto_id: String,
+    pub quantidade: i32,
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct RecebimentoResponse {
+    pub id: String,
+    pub data: NaiveDate,
+    pub documento: Option<String>,
+    pub itens: Vec<ItemRecebidoResponse>,
+    pub valor: Dinheiro,
+    pub lancamento_id: Option<String>,
+    pub usuario: Option<String>,
+    pub created_at: String,
+}
+
+impl From<RecebimentoCompra> for RecebimentoResponse {
+    fn from(recebimento: RecebimentoCompra) -> Self {
+        Self {
+            id: recebimento.id.to_hex(),
+            data: recebimento.data,
+            documento: recebimento.documento,
+            itens: recebimento
+                .itens
+                .into_iter()
+                .map(|item| ItemRecebidoResponse {
+                    item_id: item.item_id.to_hex(),
+                    produto_id: item.produto_id.to_hex(),
+                    quantidade: item.quantidade,
+                    preco_unitario: item.preco_unitario,
+                    subtotal: item.subtotal,
+                })
+                .collect(),
+            valor: recebimento.valor,
+            lancamento_id: recebimento.lancamento_id.map(|id| id.to_hex()),
+            usuario: recebimento.usuario,
+            created_at: recebimento.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct PedidoCompraResponse {
+    pub id: Option<String>,
+    pub fornecedor_id: String,
+    pub itens: Vec<ItemPedidoCompraResponse>,
+    pub total: Dinheiro,
+    pub status: StatusPedidoCompra,
+    pub previsao_entrega: Option<NaiveDate>,
+    pub observacoes: Option<String>,
+    pub recebimentos: Vec<RecebimentoResponse>,
+    pub usuario: Option<String>,
+    pub created_at: String,
+}
+
+impl From<PedidoCompra> for PedidoCompraResponse {
+    fn from(pedido: PedidoCompra) -> Self {
+        Self {
+            id: pedido.id.map(|id| id.to_hex()),
+            fornecedor_id: pedido.fornecedor_id.to_hex(),
+            itens: pedido
+                .itens
+                .into_iter()
+                .map(ItemPedidoCompraResponse::from)
+                .collect(),
+            total: pedido.total,
+            status: pedido.status,
+            previsao_entrega: pedido.previsao_entrega,
+            observacoes: pedido.observacoes,
+            recebimentos: pedido
+                .recebimentos
+                .into_iter()
+                .map(RecebimentoResponse::from)
+                .collect(),
+            usuario: pedido.usuario,
+            created_at: pedido.created_at.to_rfc3339(),
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_pedidos).post(create_pedido))
+        .route(
+            "/:id",
+            get(get_pedido).put(update_pedido).delete(delete_pedido),
+        )
+        .route("/:id/receber", post(receber_pedido))
+        .route("/:id/cancelar", post(cancelar_pedido))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_pedidos,
+    get_pedido,
+    create_pedido,
+    update_pedido,
+    delete_pedido,
+    receber_pedido,
+    cancelar_pedido
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<PedidoCompra>),
+    responses((status = 200, body = Pagina<PedidoCompraResponse>))
+)]
+async fn list_pedidos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<PedidoCompra>,
+) -> Result<Json<Pagina<PedidoCompraResponse>>> {
+    let pedidos = repos
+        .pedidos_compra
+        .paginar(empresa.id(), &consulta)
+        .await?;
+    Ok(Json(pedidos.map(PedidoCompraResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = PedidoCompraResponse))
+)]
+async fn get_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<PedidoCompraResponse>> {
+    Ok(Json(find_pedido(&repos, empresa, &id).await?.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = PedidoCompraResponse))
+)]
+async fn create_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    usuario: UsuarioAutenticado,
+    Json(input): Json<CreatePedidoCompra>,
+) -> Result<Json<PedidoCompraResponse>> {
+    let fornecedor = find_fornecedor(&repos, empresa, &input.fornecedor_id).await?;
+    let itens = build_itens(&repos, empresa, input.itens).await?;
+    let total = validacao::somar("total", itens.iter().map(|item| item.subtotal))?;
+
+    let now = Utc::now();
+    let pedido = PedidoCompra {
+        id: None,
+        empresa_id: empresa.id(),
+        fornecedor_id: fornecedor.id.ok_or(AppError::NotFound)?,
+        total,
+        itens,
+        status: StatusPedidoCompra::Aberto,
+        previsao_entrega: input.previsao_entrega,
+        observacoes: input.observacoes,
+        recebimentos: Vec::new(),
+        usuario: Some(usuario.email),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = repos.pedidos_compra.criar(pedido).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = PedidoCompraResponse))
+)]
+async fn update_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<UpdatePedidoCompra>,
+) -> Result<Json<PedidoCompraResponse>> {
+    let mut pedido = find_pedido(&repos, empresa, &id).await?;
+    if pedido.status != StatusPedidoCompra::Aberto {
+        return Err(AppError::Conflict(format!(
+            "O pedido está {} e não pode ser alterado",
+            pedido.status.as_str()
+        )));
+    }
+    let antes = pedido.clone();
+
+    if let Some(fornecedor_id) = input.fornecedor_id {
+        let fornecedor = find_fornecedor(&repos, empresa, &fornecedor_id).await?;
+        pedido.fornecedor_id = fornecedor.id.ok_or(AppError::NotFound)?;
+    }
+    if let Some(itens) = input.itens {
+        pedido.itens = build_itens(&repos, empresa, itens).await?;
+        pedido.total = validacao::somar("total", pedido.itens.iter().map(|item| item.subtotal))?;
+    }
+    if let Some(previsao_entrega) = input.previsao_entrega {
+        pedido.previsao_entrega = Some(previsao_entrega);
+    }
+    if let Some(observacoes) = input.observacoes {
+        pedido.observacoes = Some(observacoes);
+    }
+
+    salvar(&repos, &mut pedido, &antes).await?;
+    auditor.alteracao(&antes, &pedido).await?;
+    Ok(Json(pedido.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn delete_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let pedido = find_pedido(&repos, empresa, &id).await?;
+    if pedido.status != StatusPedidoCompra::Aberto {
+        return Err(AppError::Conflict(format!(
+            "O pedido está {} e não pode ser excluído; cancele-o",
+            pedido.status.as_str()
+        )));
+    }
+
+    let oid = pedido.id.ok_or(AppError::NotFound)?;
+    if !repos
+        .pedidos_compra
+        .excluir_aberto(empresa.id(), oid)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "O pedido foi alterado por outra operação".to_string(),
+        ));
+    }
+    auditor.exclusao(&pedido).await?;
+    Ok(Json("Pedido de compra excluído".to_string()))
+}
+
+/// Recebe uma entrega do pedido: cada item dá entrada no estoque, o
+/// `preco_custo` do produto passa ao custo médio ponderado e o valor da
+/// entrega vira uma conta a pagar ao fornecedor.
+#[utoipa::path(
+    post,
+    path = "/{id}/receber",
+    params(EmpresaAtual),
+    responses((status = 200, body = PedidoCompraResponse))
+)]
+async fn receber_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    usuario: UsuarioAutenticado,
+    Path(id): Path<String>,
+    Json(input): Json<ReceberPedidoCompra>,
+) -> Result<Json<PedidoCompraResponse>> {
+    let mut pedido = find_pedido(&repos, empresa, &id).await?;
+    if !matches!(
+        pedido.status,
+        StatusPedidoCompra::Aberto | StatusPedidoCompra::Parcial
+    ) {
+        return Err(AppError::Conflict(format!(
+            "O pedido está {} e não pode ser recebido",
+            pedido.status.as_str()
+        )));
+    }
+    let antes = pedido.clone();
+    let pedido_id = pedido.id.ok_or(AppError::NotFound)?;
+    let fornecedor = repos
+        .fornecedores
+        .buscar(empresa.id(), pedido.fornecedor_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let itens = itens_recebidos(&mut pedido, input.itens)?;
+    let valor = validacao::somar("itens", itens.iter().map(|item| item.subtotal))?;
+    let data = input.data.unwrap_or_else(|| Utc::now().date_naive());
+    let lancamento_id = valor.positivo().then(ObjectId::new);
+
+    pedido.recebimentos.push(RecebimentoCompra {
+        id: ObjectId::new(),
+        data,
+        documento: input.documento.clone(),
+        itens: itens.clone(),
+        valor,
+        lancamento_id,
+        usuario: Some(usuario.email.clone()),
+        created_at: Utc::now(),
+    });
+    pedido.status = pedido.status_recebimento();
+
+    // Gravar o pedido primeiro garante que a mesma entrega não seja
+    // recebida duas vezes por requisições concorrentes; se a entrada no
+    // estoque ou a conta a pagar falharem, o recebimento é desfeito.
+    salvar(&repos, &mut pedido, &antes).await?;
+
+    let motivo = format!("Pedido de compra {}", id);
+    let mut custos: Vec<(ObjectId, Dinheiro)> = Vec::new();
+    let mut entradas: Vec<&ItemRecebido> = Vec::with_capacity(itens.len());
+    let resultado = async {
+        for item in &itens {
+            if let Some(anterior) = atualizar_custo(&repos, empresa, item).await? {
+                custos.push((item.produto_id, anterior));
+            }
+            estoque::movimentar(
+                &repos,
+                NovaMovimentacao {
+                    empresa_id: empresa.id(),
+                    produto_id: item.produto_id,
+                    tipo: TipoMovimentacao::Entrada,
+                    quantidade: item.quantidade,
+                    motivo: motivo.clone(),
+                    usuario: Some(usuario.email.clone()),
+                    venda_id: None,
+                    pedido_compra_id: Some(pedido_id),
+                },
+            )
+            .await?;
+            entradas.push(item);
+        }
+
+        let Some(lancamento_id) = lancamento_id else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        let descricao = match &input.documento {
+            Some(documento) => format!("Compra {} - documento {}", fornecedor.nome, documento),
+            None => format!("Compra {} - pedido {}", fornecedor.nome, id),
+        };
+        let conta = Lancamento {
+            id: Some(lancamento_id),
+            empresa_id: empresa.id(),
+            tipo: TipoLancamento::Pagar,
+            descricao,
+            categoria: "Compras".to_string(),
+            valor,
+            moeda: Moeda::Brl,
+            vencimento: input.vencimento.unwrap_or(data),
+            cliente_id: None,
+            contraparte: Some(fornecedor.nome.clone()),
+            grupo_id: ObjectId::new(),
+            parcela: 1,
+            total_parcelas: 1,
+            status: StatusLancamento::Aberto,
+            conta_id: None,
+            data_pagamento: None,
+            valor_pago: None,
+            recorrencia_id: None,
+            ocorrencia: None,
+            created_at: now,
+            updated_at: now,
+        };
+        repos.lancamentos.criar(conta).await.map(Some)
+    }
+    .await;
+
+    let conta = match resultado {
+        Ok(conta) => conta,
+        Err(erro) => {
+            desfazer_entradas(&repos, empresa, &usuario, pedido_id, &entradas, &custos).await?;
+            let mut restaurado = antes.clone();
+            salvar(&repos, &mut restaurado, &pedido).await?;
+            return Err(erro);
+        }
+    };
+    if let Some(conta) = &conta {
+        auditor.criacao(conta).await?;
+    }
+
+    auditor.alteracao(&antes, &pedido).await?;
+    Ok(Json(pedido.into()))
+}
+
+/// Cancela o que falta receber; o que já entrou no estoque fica.
+#[utoipa::path(
+    post,
+    path = "/{id}/cancelar",
+    params(EmpresaAtual),
+    responses((status = 200, body = PedidoCompraResponse))
+)]
+async fn cancelar_pedido(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<PedidoCompraResponse>> {
+    let mut pedido = find_pedido(&repos, empresa, &id).await?;
+    match pedido.status {
+        StatusPedidoCompra::Cancelado => {
+            return Err(AppError::Conflict("O pedido já está cancelado".to_string()));
+        }
+        StatusPedidoCompra::Recebido => {
+            return Err(AppError::Conflict(
+                "O pedido já foi recebido por completo".to_string(),
+            ));
+        }
+        StatusPedidoCompra::Aberto | StatusPedidoCompra::Parcial => {}
+    }
+    let antes = pedido.clone();
+
+    pedido.status = StatusPedidoCompra::Cancelado;
+    salvar(&repos, &mut pedido, &antes).await?;
+    auditor.alteracao(&antes, &pedido).await?;
+    Ok(Json(pedido.into()))
+}
+
+async fn find_pedido(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: &str,
+) -> Result<PedidoCompra> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .pedidos_compra
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn find_fornecedor(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: &str,
+) -> Result<Fornecedor> {
+    let oid = ObjectId::parse_str(id)?;
+    let fornecedor = repos
+        .fornecedores
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if !fornecedor.ativo {
+        return Err(AppError::BadRequest(format!(
+            "O fornecedor {} está inativo",
+            fornecedor.nome
+        )));
+    }
+    Ok(fornecedor)
+}
+
+/// Grava o pedido somente se ninguém o alterou desde que `antes` foi lido.
+async fn salvar(
+    repos: &Repositorios,
+    pedido: &mut PedidoCompra,
+    antes: &PedidoCompra,
+) -> Result<()> {
+    pedido.updated_at = Utc::now();
+    if !repos
+        .pedidos_compra
+        .salvar_versao(pedido, antes.updated_at)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "O pedido foi alterado por outra operação".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn build_itens(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    input: Vec<CreateItemPedidoCompra>,
+) -> Result<Vec<ItemPedidoCompra>> {
+    if input.is_empty() {
+        return Err(AppError::BadRequest(
+            "O pedido de compra deve ter ao menos um item".to_string(),
+        ));
+    }
+
+    let mut itens = Vec::with_capacity(input.len());
+    for item in input {
+        if item.quantidade <= 0 {
+            return Err(AppError::BadRequest(
+                "A quantidade do item deve ser maior que zero".to_string(),
+            ));
+        }
+        if item.preco_unitario.is_some_and(|preco| preco.negativo()) {
+            return Err(AppError::BadRequest(
+                "O preço unitário não pode ser negativo".to_string(),
+            ));
+        }
+
+        let produto_id = ObjectId::parse_str(&item.produto_id)?;
+        let produto = repos
+            .produtos
+            .buscar(empresa.id(), produto_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let preco_unitario = item.preco_unitario.unwrap_or(produto.preco_custo);
+
+        itens.push(ItemPedidoCompra {
+            id: ObjectId::new(),
+            produto_id,
+            produto_nome: produto.nome,
+            quantidade: item.quantidade,
+            quantidade_recebida: 0,
+            preco_unitario,
+            subtotal: validacao::multiplicar("itens", preco_unitario, item.quantidade)?,
+        });
+    }
+    Ok(itens)
+}
+
+/// Aplica a entrega aos itens do pedido e devolve o que foi recebido.
+/// Sem itens informados, recebe tudo o que falta.
+fn itens_recebidos(
+    pedido: &mut PedidoCompra,
+    input: Vec<ReceberItemCompra>,
+) -> Result<Vec<ItemRecebido>> {
+    let mut entregues: HashMap<ObjectId, (i32, Option<Dinheiro>)> = HashMap::new();
+    if input.is_empty() {
+        for item in &pedido.itens {
+            if item.pendente() > 0 {
+                entregues.insert(item.id, (item.pendente(), None));
+            }
+        }
+    } else {
+        for item in input {
+            if item.quantidade <= 0 {
+                return Err(AppError::BadRequest(
+                    "A quantidade recebida deve ser maior que zero".to_string(),
+                ));
+            }
+            if item.preco_unitario.is_some_and(|preco| preco.negativo()) {
+                return Err(AppError::BadRequest(
+                    "O preço unitário não pode ser negativo".to_string(),
+                ));
+            }
+            let item_id = ObjectId::parse_str(&item.item_id)?;
+            let entregue = entregues.entry(item_id).or_insert((0, None));
+            entregue.0 = entregue.0.checked_add(item.quantidade).ok_or_else(|| {
+                AppError::BadRequest("Quantidade recebida acima do máximo".to_string())
+            })?;
+            entregue.1 = item.preco_unitario.or(entregue.1);
+        }
+    }
+
+    if entregues.is_empty() {
+        return Err(AppError::Conflict(
+            "O pedido não tem itens pendentes".to_string(),
+        ));
+    }
+
+    let mut recebidos = Vec::with_capacity(entregues.len());
+    for item in pedido.itens.iter_mut() {
+        let Some((quantidade, preco)) = entregues.remove(&item.id) else {
+            continue;
+        };
+        if quantidade > item.pendente() {
+            return Err(AppError::BadRequest(format!(
+                "Quantidade recebida de {} maior que a pendente ({})",
+                item.produto_nome,
+                item.pendente()
+            )));
+        }
+        let preco_unitario = preco.unwrap_or(item.preco_unitario);
+        item.quantidade_recebida += quantidade;
+        recebidos.push(ItemRecebido {
+            item_id: item.id,
+            produto_id: item.produto_id,
+            quantidade,
+            preco_unitario,
+            subtotal: validacao::multiplicar("itens", preco_unitario, quantidade)?,
+        });
+    }
+
+    if !entregues.is_empty() {
+        return Err(AppError::NotFound);
+    }
+    Ok(recebidos)
+}
+
+/// Leva o `preco_custo` do produto ao custo médio ponderado com a entrada,
+/// antes de ela ser somada ao estoque. Retorna o custo anterior quando ele
+/// mudou.
+async fn atualizar_custo(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    item: &ItemRecebido,
+) -> Result<Option<Dinheiro>> {
+    let Some(mut produto) = repos.produtos.buscar(empresa.id(), item.produto_id).await? else {
+        return Err(AppError::NotFound);
+    };
+    let custo = estoque::custo_medio(
+        produto.preco_custo,
+        produto.estoque_atual,
+        item.preco_unitario,
+        item.quantidade,
+    )?;
+    if custo == produto.preco_custo {
+        return Ok(None);
+    }
+
+    let anterior = produto.preco_custo;
+    produto.preco_custo = custo;
+    produto.updated_at = Utc::now();
+    repos.produtos.salvar_cadastro(&produto).await?;
+    Ok(Some(anterior))
+}
+
+/// Estorna as entradas de um recebimento que falhou no meio e devolve aos
+/// produtos o custo de antes dele.
+async fn desfazer_entradas(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    usuario: &UsuarioAutenticado,
+    pedido_id: ObjectId,
+    entradas: &[&ItemRecebido],
+    custos: &[(ObjectId, Dinheiro)],
+) -> Result<()> {
+    for item in entradas {
+        estoque::movimentar(
+            repos,
+            NovaMovimentacao {
+                empresa_id: empresa.id(),
+                produto_id: item.produto_id,
+                tipo: TipoMovimentacao::Saida,
+                quantidade: item.quantidade,
+                motivo: format!(
+                    "Estorno do recebimento malsucedido do pedido de compra {}",
+                    pedido_id
+                ),
+                usuario: Some(usuario.email.clone()),
+                venda_id: None,
+                pedido_compra_id: Some(pedido_id),
+            },
+        )
+        .await?;
+    }
+
+    for (produto_id, custo) in custos.iter().rev() {
+        if let Some(mut produto) = repos.produtos.buscar(empresa.id(), *produto_id).await? {
+            produto.preco_custo = *custo;
+            produto.updated_at = Utc::now();
+            repos.produtos.salvar_cadastro(&produto).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    async fn produto(ambiente: &Ambiente, nome: &str) -> String {
+        let (status, produto) = ambiente
+            .enviar(
+                crate::routes::produtos::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": nome,
+                    "preco_custo": "4.00",
+                    "preco_venda": "10.00",
+                    "estoque_minimo": 0,
+                    "unidade": "UN",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{produto}");
+        produto["id"].as_str().unwrap().to_string()
+    }
+
+    /// Pedido aberto com uma unidade de cada produto a R$ 6,00.
+    async fn pedido(ambiente: &Ambiente, produtos: &[&str]) -> Value {
+        let (status, fornecedor) = ambiente
+            .enviar(
+                crate::routes::fornecedores::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Papelaria", "cpf_cnpj": "11.222.333/0001-81" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{fornecedor}");
+        let itens: Vec<Value> = produtos
+            .iter()
+            .map(|id| json!({ "produto_id": id, "quantidade": 1, "preco_unitario": "6.00" }))
+            .collect();
+        let (status, pedido) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({ "fornecedor_id": fornecedor["id"], "itens": itens })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{pedido}");
+        pedido
+    }
+
+    async fn buscar_produto(ambiente: &Ambiente, id: &str) -> Produto {
+        ambiente
+            .repos
+            .produtos
+            .buscar(ambiente.empresa_id, ObjectId::parse_str(id).unwrap())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn recebe_o_pedido_e_gera_a_conta_a_pagar() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta").await;
+        let pedido = pedido(&ambiente, &[&caneta]).await;
+        let uri = format!("/{}/receber", pedido["id"].as_str().unwrap());
+
+        let (status, recebido) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                &uri,
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{recebido}");
+        assert_eq!(recebido["status"], "RECEBIDO");
+        assert_eq!(recebido["recebimentos"][0]["valor"], "6.00");
+        let produto = buscar_produto(&ambiente, &caneta).await;
+        assert_eq!(produto.estoque_atual, 1);
+        assert_eq!(produto.preco_custo, "6.00".parse().unwrap());
+        let contas = ambiente
+            .repos
+            .lancamentos
+            .listar(ambiente.empresa_id)
+            .await
+            .unwrap();
+        assert_eq!(contas.len(), 1);
+
+        let (status, _) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                &uri,
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn recusa_quantidade_recebida_acima_do_maximo() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta").await;
+        let pedido = pedido(&ambiente, &[&caneta]).await;
+        let item_id = &pedido["itens"][0]["id"];
+
+        let (status, erro) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                &format!("/{}/receber", pedido["id"].as_str().unwrap()),
+                Some(json!({ "itens": [
+                    { "item_id": item_id, "quantidade": i32::MAX },
+                    { "item_id": item_id, "quantidade": i32::MAX },
+                ] })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{erro}");
+    }
+
+    #[tokio::test]
+    async fn desfaz_o_recebimento_que_falha_no_meio() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta").await;
+        let lapis = produto(&ambiente, "Lápis").await;
+        ambiente
+            .repos
+            .produtos
+            .ajustar_estoque(
+                ambiente.empresa_id,
+                ObjectId::parse_str(&lapis).unwrap(),
+                i32::MAX,
+            )
+            .await
+            .unwrap();
+        let pedido = pedido(&ambiente, &[&caneta, &lapis]).await;
+        let id = pedido["id"].as_str().unwrap();
+
+        let (status, erro) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                &format!("/{id}/receber"),
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{erro}");
+
+        let produto = buscar_produto(&ambiente, &caneta).await;
+        assert_eq!(produto.estoque_atual, 0);
+        assert_eq!(produto.preco_custo, "4.00".parse().unwrap());
+        let (_, pedido) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                &format!("/{id}"),
+                None,
+            )
+            .await;
+        assert_eq!(pedido["status"], "ABERTO");
+        assert_eq!(pedido["recebimentos"], json!([]));
+        assert_eq!(pedido["itens"][0]["quantidade_recebida"], 0);
+        assert!(ambiente
+            .repos
+            .lancamentos
+            .listar(ambiente.empresa_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn recebe_em_partes_e_cancela_o_restante() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta").await;
+        let pedido = pedido(&ambiente, &[&caneta]).await;
+        let uri = format!("/{}", pedido["id"].as_str().unwrap());
+        let rotas = || routes(ambiente.repos.clone());
+
+        let (status, alterado) = ambiente
+            .enviar(
+                rotas(),
+                Method::PUT,
+                &uri,
+                Some(json!({ "itens": [
+                    { "produto_id": caneta, "quantidade": 3, "preco_unitario": "6.00" },
+                ] })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{alterado}");
+        assert_eq!(alterado["total"], "18.00");
+
+        let (status, parcial) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                &format!("{uri}/receber"),
+                Some(json!({ "itens": [
+                    { "item_id": alterado["itens"][0]["id"], "quantidade": 1 },
+                ] })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{parcial}");
+        assert_eq!(parcial["status"], "PARCIAL");
+        let (status, _) = ambiente.enviar(rotas(), Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, cancelado) = ambiente
+            .enviar(rotas(), Method::POST, &format!("{uri}/cancelar"), None)
+            .await;
+        assert_eq!(status, StatusCode::OK, "{cancelado}");
+        assert_eq!(cancelado["status"], "CANCELADO");
+        assert_eq!(buscar_produto(&ambiente, &caneta).await.estoque_atual, 1);
+
+        for (metodo, caminho, corpo) in [
+            (Method::POST, format!("{uri}/receber"), Some(json!({}))),
+            (Method::POST, format!("{uri}/cancelar"), None),
+            (
+                Method::PUT,
+                uri.clone(),
+                Some(json!({ "observacoes": "x" })),
+            ),
+        ] {
+            let (status, _) = ambiente.enviar(rotas(), metodo, &caminho, corpo).await;
+            assert_eq!(status, StatusCode::CONFLICT, "{caminho}");
+        }
+    }
+
+    #[tokio::test]
+    async fn pedido_exige_fornecedor_ativo_e_produtos_existentes() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta").await;
+        let pedido = pedido(&ambiente, &[&caneta]).await;
+        let fornecedor_id = pedido["fornecedor_id"].as_str().unwrap();
+        let criar = |fornecedor_id: String, produto_id: String| {
+            Some(json!({
+                "fornecedor_id": fornecedor_id,
+                "itens": [{ "produto_id": produto_id, "quantidade": 1, "preco_unitario": "6.00" }],
+            }))
+        };
+
+        let (status, _) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                criar(ObjectId::new().to_hex(), caneta.clone()),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                criar(fornecedor_id.to_string(), ObjectId::new().to_hex()),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                &format!("/{}", ObjectId::new().to_hex()),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        ambiente
+            .enviar(
+                crate::routes::fornecedores::routes(ambiente.repos.clone()),
+                Method::PUT,
+                &format!("/{fornecedor_id}"),
+                Some(json!({ "ativo": false })),
+            )
+            .await;
+        let (status, erro) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                criar(fornecedor_id.to_string(), caneta),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{erro}");
+    }
+}
@@ -0,0 +1,608 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Duration, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina, ValorFiltro},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    importacao::{self, FormatoArquivo},
+    models::*,
+    openapi::Arquivo,
+    repositorio::{FiltroLancamentos, Repositorios},
+    routes::lancamentos::{find_lancamento, liquidar},
+};
+
+/// Distância máxima, em dias, entre a transação e o vencimento sugerido.
+const JANELA_SUGESTAO_DIAS: i64 = 15;
+const LIMITE_SUGESTOES: usize = 5;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct SugestaoResponse {
+    pub lancamento_id: String,
+    pub descricao: String,
+    pub valor: Dinheiro,
+    pub vencimento: NaiveDate,
+    pub diferenca_dias: i64,
+}
+
+impl SugestaoResponse {
+    fn new(lancamento: Lancamento, transacao: &TransacaoBancaria) -> Self {
+        Self {
+            lancamento_id: lancamento.id.map(|id| id.to_hex()).unwrap_or_default(),
+            descricao: lancamento.descricao,
+            valor: lancamento.valor,
+            vencimento: lancamento.vencimento,
+            diferenca_dias: (lancamento.vencimento - transacao.data).num_days().abs(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct TransacaoResponse {
+    pub id: Option<String>,
+    pub data: NaiveDate,
+    pub valor: Dinheiro,
+    pub descricao: String,
+    pub documento: Option<String>,
+    pub identificador: String,
+    pub origem: FormatoArquivo,
+    pub lancamento_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sugestoes: Option<Vec<SugestaoResponse>>,
+}
+
+impl From<TransacaoBancaria> for TransacaoResponse {
+    fn from(transacao: TransacaoBancaria) -> Self {
+        Self {
+            id: transacao.id.map(|id| id.to_hex()),
+            data: transacao.data,
+            valor: transacao.valor,
+            descricao: transacao.descricao,
+            documento: transacao.documento,
+            identificador: transacao.identificador,
+            origem: transacao.origem,
+            lancamento_id: transacao.lancamento_id.map(|id| id.to_hex()),
+            sugestoes: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ImportacaoResponse {
+    pub formato: FormatoArquivo,
+    pub importadas: usize,
+    pub duplicadas: usize,
+    pub transacoes: Vec<TransacaoResponse>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ImportacaoQuery {
+    pub formato: Option<FormatoArquivo>,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/contas/:id/importar", post(importar_arquivo))
+        .route("/contas/:id/transacoes", get(list_transacoes))
+        .route(
+            "/contas/:id/transacoes/:transacao_id/sugestoes",
+            get(get_sugestoes),
+        )
+        .route(
+            "/contas/:id/transacoes/:transacao_id/conciliar",
+            post(conciliar_transacao),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(importar_arquivo, list_transacoes, get_sugestoes, conciliar_transacao))]
+pub struct Api;
+
+#[utoipa::path(
+    post,
+    path = "/contas/{id}/importar",
+    request_body(
+        content = Arquivo,
+        content_type = "application/octet-stream",
+        description = "Extrato OFX ou retorno CNAB 240/400"
+    ),
+    params(EmpresaAtual, ImportacaoQuery),
+    responses((status = 200, body = ImportacaoResponse))
+)]
+async fn importar_arquivo(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Query(query): Query<ImportacaoQuery>,
+    body: Bytes,
+) -> Result<Json<ImportacaoResponse>> {
+    let conta_id = find_conta_id(&repos, empresa, &id).await?;
+    let (formato, lidas) = importacao::importar(&body, query.formato)
+        .map_err(|e| AppError::BadRequest(format!("Arquivo inválido: {}", e)))?;
+
+    let identificadores: Vec<String> = lidas
+        .iter()
+        .map(|transacao| transacao.identificador.clone())
+        .collect();
+    let mut vistos = repos
+        .transacoes
+        .identificadores_existentes(conta_id, &identificadores)
+        .await?;
+
+    let total_lidas = lidas.len();
+    let now = Utc::now();
+    let novas: Vec<TransacaoBancaria> = lidas
+        .into_iter()
+        .filter(|transacao| vistos.insert(transacao.identificador.clone()))
+        .map(|transacao| TransacaoBancaria {
+            id: None,
+            empresa_id: empresa.id(),
+            conta_id,
+            data: transacao.data,
+            valor: transacao.valor,
+            descricao: transacao.descricao,
+            documento: transacao.documento,
+            identificador: transacao.identificador,
+            origem: formato,
+            lancamento_id: None,
+            created_at: now,
+        })
+        .collect();
+
+    let mut transacoes = Vec::with_capacity(novas.len());
+    if !novas.is_empty() {
+        let mut cobrancas = repos
+            .cobrancas_pix
+            .pendentes(empresa.id(), conta_id)
+            .await?;
+        for transacao in repos.transacoes.criar_varias(novas).await? {
+            auditor.criacao(&transacao).await?;
+            pagar_cobranca(&repos, &auditor, &mut cobrancas, &transacao).await?;
+            let sugestoes = sugerir(&repos, &transacao).await?;
+            let mut response = TransacaoResponse::from(transacao);
+            response.sugestoes = Some(sugestoes);
+            transacoes.push(response);
+        }
+    }
+
+    Ok(Json(ImportacaoResponse {
+        formato,
+        importadas: transacoes.len(),
+        duplicadas: total_lidas - transacoes.len(),
+        transacoes,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/contas/{id}/transacoes",
+    params(EmpresaAtual, Consulta<TransacaoBancaria>),
+    responses((status = 200, body = Pagina<TransacaoResponse>))
+)]
+async fn list_transacoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+    mut consulta: Consulta<TransacaoBancaria>,
+) -> Result<Json<Pagina<TransacaoResponse>>> {
+    let conta_id = find_conta_id(&repos, empresa, &id).await?;
+    consulta.filtro_padrao("conta_id", ValorFiltro::Id(conta_id));
+
+    let transacoes = repos.transacoes.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(transacoes.map(TransacaoResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/contas/{id}/transacoes/{transacao_id}/sugestoes",
+    params(EmpresaAtual, ("id" = String, Path), ("transacao_id" = String, Path)),
+    responses((status = 200, body = Vec<SugestaoResponse>))
+)]
+async fn get_sugestoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path((id, transacao_id)): Path<(String, String)>,
+) -> Result<Json<Vec<SugestaoResponse>>> {
+    let transacao = find_transacao(&repos, empresa, &id, &transacao_id).await?;
+    Ok(Json(sugerir(&repos, &transacao).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/contas/{id}/transacoes/{transacao_id}/conciliar",
+    params(EmpresaAtual, ("id" = String, Path), ("transacao_id" = String, Path)),
+    responses((status = 200, body = TransacaoResponse))
+)]
+async fn conciliar_transacao(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, transacao_id)): Path<(String, String)>,
+    Json(input): Json<ConciliarTransacao>,
+) -> Result<Json<TransacaoResponse>> {
+    let transacao = find_transacao(&repos, empresa, &id, &transacao_id).await?;
+    if transacao.lancamento_id.is_some() {
+        return Err(AppError::Conflict(
+            "A transação já está conciliada".to_string(),
+        ));
+    }
+
+    let lancamento = find_lancamento(&repos, empresa, &input.lancamento_id).await?;
+    if tipo_esperado(&transacao) != lancamento.tipo {
+        return Err(AppError::BadRequest(
+            "Créditos só conciliam com contas a receber e débitos com contas a pagar".to_string(),
+        ));
+    }
+
+    // A transação é reservada antes de liquidar o lançamento, para que
+    // duas conciliações simultâneas não liquidem dois lançamentos.
+    let transacao_oid = transacao.id.ok_or(AppError::NotFound)?;
+    let lancamento_id = lancamento.id.ok_or(AppError::NotFound)?;
+    if !repos
+        .transacoes
+        .conciliar(transacao_oid, lancamento_id)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "A transação já está conciliada".to_string(),
+        ));
+    }
+
+    let liquidado = match liquidar(
+        &repos,
+        &lancamento,
+        transacao.conta_id,
+        transacao.data,
+        transacao.valor.abs(),
+    )
+    .await
+    {
+        Ok(liquidado) => liquidado,
+        Err(e) => {
+            repos
+                .transacoes
+                .liberar(transacao_oid, lancamento_id)
+                .await?;
+            return Err(e);
+        }
+    };
+    auditor.alteracao(&lancamento, &liquidado).await?;
+
+    let conciliada = find_transacao(&repos, empresa, &id, &transacao_id).await?;
+    auditor.alteracao(&transacao, &conciliada).await?;
+    Ok(Json(conciliada.into()))
+}
+
+async fn find_conta_id(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<ObjectId> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .contas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(oid)
+}
+
+async fn find_transacao(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    conta_id: &str,
+    transacao_id: &str,
+) -> Result<TransacaoBancaria> {
+    let conta_oid = ObjectId::parse_str(conta_id)?;
+    let oid = ObjectId::parse_str(transacao_id)?;
+    repos
+        .transacoes
+        .buscar(empresa.id(), conta_oid, oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+fn tipo_esperado(transacao: &TransacaoBancaria) -> TipoLancamento {
+    if !transacao.valor.negativo() {
+        TipoLancamento::Receber
+    } else {
+        TipoLancamento::Pagar
+    }
+}
+
+/// Lançamentos em aberto do mesmo tipo e valor, com vencimento próximo da
+/// data da transação, do mais próximo para o mais distante.
+async fn sugerir(
+    repos: &Repositorios,
+    transacao: &TransacaoBancaria,
+) -> Result<Vec<SugestaoResponse>> {
+    if transacao.lancamento_id.is_some() {
+        return Ok(Vec::new());
+    }
+
+    let valor = transacao.valor.abs();
+    let janela = Duration::days(JANELA_SUGESTAO_DIAS);
+    let filtro = FiltroLancamentos {
+        tipo: Some(tipo_esperado(transacao)),
+        status: Some(StatusLancamento::Aberto),
+        vencimento_de: Some(transacao.data - janela),
+        vencimento_ate: Some(transacao.data + janela),
+        valor_min: Some(valor),
+        valor_max: Some(valor),
+        ..Default::default()
+    };
+    let candidatos = repos
+        .lancamentos
+        .filtrar(transacao.empresa_id, &filtro)
+        .await?;
+
+    let mut sugestoes: Vec<SugestaoResponse> = candidatos
+        .into_iter()
+        .map(|lancamento| SugestaoResponse::new(lancamento, transacao))
+        .collect();
+    sugestoes.sort_by_key(|sugestao| sugestao.diferenca_dias);
+
+    // O lançamento cuja cobrança PIX esta transação pagou vem primeiro,
+    // qualquer que seja o vencimento.
+    let cobrado = match transacao.id {
+        Some(id) => repos
+            .cobrancas_pix
+            .paga_pela_transacao(transacao.empresa_id, id)
+            .await?
+            .filter(|cobranca| cobranca.origem == OrigemCobranca::Lancamento),
+        None => None,
+    };
+    if let Some(cobranca) = cobrado {
+        let id = cobranca.origem_id.to_hex();
+        match sugestoes
+            .iter()
+            .position(|sugestao| sugestao.lancamento_id == id)
+        {
+            Some(posicao) => {
+                let sugestao = sugestoes.remove(posicao);
+                sugestoes.insert(0, sugestao);
+            }
+            None => {
+                let lancamento = repos
+                    .lancamentos
+                    .buscar(transacao.empresa_id, cobranca.origem_id)
+                    .await?
+                    .filter(|lancamento| lancamento.status == StatusLancamento::Aberto);
+                if let Some(lancamento) = lancamento {
+                    sugestoes.insert(0, SugestaoResponse::new(lancamento, transacao));
+                }
+            }
+        }
+    }
+    sugestoes.truncate(LIMITE_SUGESTOES);
+
+    Ok(sugestoes)
+}
+
+/// Marca como paga a cobrança PIX pendente de mesmo valor cujo txid aparece
+/// no crédito: no documento, no identificador ou como palavra da descrição.
+async fn pagar_cobranca(
+    repos: &Repositorios,
+    auditor: &Auditor,
+    pendentes: &mut Vec<CobrancaPix>,
+    transacao: &TransacaoBancaria,
+) -> Result<()> {
+    if !transacao.valor.positivo() {
+        return Ok(());
+    }
+    let menciona = |txid: &str| {
+        transacao.documento.as_deref() == Some(txid)
+            || transacao.identificador == txid
+            || transacao
+                .descricao
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|palavra| palavra == txid)
+    };
+    let Some(posicao) = pendentes
+        .iter()
+        .position(|cobranca| cobranca.valor == transacao.valor && menciona(&cobranca.txid))
+    else {
+        return Ok(());
+    };
+
+    let mut cobranca = pendentes.swap_remove(posicao);
+    let antes = cobranca.clone();
+    cobranca.status = StatusCobranca::Paga;
+    cobranca.transacao_id = transacao.id;
+    cobranca.data_pagamento = Some(transacao.data);
+    repos.cobrancas_pix.salvar(&cobranca).await?;
+    auditor.alteracao(&antes, &cobranca).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    async fn enviar(
+        ambiente: &Ambiente,
+        metodo: Method,
+        uri: &str,
+        corpo: Value,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                crate::routes::financeiro::routes(ambiente.repos.clone()),
+                metodo,
+                uri,
+                Some(corpo),
+            )
+            .await
+    }
+
+    async fn lancamento(ambiente: &Ambiente) -> String {
+        let (status, criados) = enviar(
+            ambiente,
+            Method::POST,
+            "/lancamentos",
+            json!({
+                "tipo": "RECEBER",
+                "descricao": "Venda a prazo",
+                "categoria": "Vendas",
+                "valor": "100.00",
+                "vencimento": "2026-03-10",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{criados}");
+        criados[0]["id"].as_str().unwrap().to_string()
+    }
+
+    async fn transacao(ambiente: &Ambiente, conta_id: ObjectId, identificador: &str) -> ObjectId {
+        let criadas = ambiente
+            .repos
+            .transacoes
+            .criar_varias(vec![TransacaoBancaria {
+                id: None,
+                empresa_id: ambiente.empresa_id,
+                conta_id,
+                data: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+                valor: "100.00".parse().unwrap(),
+                descricao: "TED recebida".to_string(),
+                documento: None,
+                identificador: identificador.to_string(),
+                origem: FormatoArquivo::Ofx,
+                lancamento_id: None,
+                created_at: Utc::now(),
+            }])
+            .await
+            .unwrap();
+        criadas[0].id.unwrap()
+    }
+
+    async fn conta(ambiente: &Ambiente) -> ObjectId {
+        let (_, conta) = enviar(
+            ambiente,
+            Method::POST,
+            "/contas",
+            json!({
+                "banco": "Banco Teste",
+                "agencia": "0001",
+                "conta": "12345-6",
+                "tipo": "corrente",
+                "saldo": "0.00",
+            }),
+        )
+        .await;
+        ObjectId::parse_str(conta["id"].as_str().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn transacao_conciliada_nao_liquida_outro_lancamento() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let conta_id = conta(&ambiente).await;
+        let primeira = transacao(&ambiente, conta_id, "FIT-1").await;
+        let segunda = transacao(&ambiente, conta_id, "FIT-2").await;
+        let a = lancamento(&ambiente).await;
+        let b = lancamento(&ambiente).await;
+
+        let conciliar =
+            |transacao: ObjectId| format!("/contas/{conta_id}/transacoes/{transacao}/conciliar");
+        let (status, conciliada) = enviar(
+            &ambiente,
+            Method::POST,
+            &conciliar(primeira),
+            json!({ "lancamento_id": a }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{conciliada}");
+        assert_eq!(conciliada["lancamento_id"], a);
+
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            &conciliar(primeira),
+            json!({ "lancamento_id": b }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (_, pendente) = enviar(
+            &ambiente,
+            Method::GET,
+            &format!("/lancamentos/{b}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(pendente["status"], "ABERTO");
+
+        // O lançamento já pago recusa a liquidação e a transação volta a
+        // ficar pendente.
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            &conciliar(segunda),
+            json!({ "lancamento_id": a }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let segunda = ambiente
+            .repos
+            .transacoes
+            .buscar(ambiente.empresa_id, conta_id, segunda)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(segunda.lancamento_id, None);
+    }
+
+    #[tokio::test]
+    async fn lista_as_transacoes_da_conta_por_conciliacao() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let conta_id = conta(&ambiente).await;
+        let outra_conta = conta(&ambiente).await;
+        let conciliada = transacao(&ambiente, conta_id, "FIT-1").await;
+        transacao(&ambiente, conta_id, "FIT-2").await;
+        transacao(&ambiente, outra_conta, "FIT-3").await;
+        let lancamento_id = lancamento(&ambiente).await;
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            &format!("/contas/{conta_id}/transacoes/{conciliada}/conciliar"),
+            json!({ "lancamento_id": lancamento_id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let listar = |filtro: &str| format!("/contas/{conta_id}/transacoes{filtro}");
+        let (status, todas) = enviar(&ambiente, Method::GET, &listar(""), Value::Null).await;
+        assert_eq!(status, StatusCode::OK, "{todas}");
+        assert_eq!(todas["total"], 2);
+
+        let (_, pendentes) = enviar(
+            &ambiente,
+            Method::GET,
+            &listar("?conciliada=false"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(pendentes["total"], 1);
+        assert_eq!(pendentes["itens"][0]["identificador"], "FIT-2");
+
+        let (_, pagina) = enviar(
+            &ambiente,
+            Method::GET,
+            &listar("?conciliada=true&limit=1"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(pagina["total"], 1);
+        assert_eq!(pagina["itens"][0]["lancamento_id"], lancamento_id);
+    }
+}
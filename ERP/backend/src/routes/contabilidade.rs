@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina},
+    contabilidade::{self, NovaPartida, Plano},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ContaContabilResponse {
+    pub id: Option<String>,
+    pub codigo: String,
+    pub nome: String,
+    pub tipo: TipoContaContabil,
+    pub sintetica: bool,
+    pub conta_bancaria_id: Option<String>,
+    pub debito: Dinheiro,
+    pub credito: Dinheiro,
+    /// Saldo no sentido da natureza da conta, somando as subcontas.
+    pub saldo: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct LinhaPartidaResponse {
+    pub conta_id: String,
+    pub codigo: String,
+    pub nome: String,
+    pub debito: Dinheiro,
+    pub credito: Dinheiro,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct PartidaResponse {
+    pub id: Option<String>,
+    pub data: NaiveDate,
+    pub historico: String,
+    pub origem: OrigemPartida,
+    pub origem_id: Option<String>,
+    pub estorno_de: Option<String>,
+    pub linhas: Vec<LinhaPartidaResponse>,
+}
+
+impl PartidaResponse {
+    fn new(partida: Partida, plano: &Plano) -> Self {
+        Self {
+            id: partida.id.map(|id| id.to_hex()),
+            data: partida.data,
+            historico: partida.historico,
+            origem: partida.origem,
+            origem_id: partida.origem_id.map(|id| id.to_hex()),
+            estorno_de: partida.estorno_de.map(|id| id.to_hex()),
+            linhas: partida
+                .linhas
+                .into_iter()
+                .map(|linha| {
+                    let conta = plano.buscar(linha.conta_id);
+                    LinhaPartidaResponse {
+                        conta_id: linha.conta_id.to_hex(),
+                        codigo: conta.map(|c| c.codigo.clone()).unwrap_or_default(),
+                        nome: conta.map(|c| c.nome.clone()).unwrap_or_default(),
+                        debito: linha.debito,
+                        credito: linha.credito,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct BalanceteResponse {
+    pub ate: Option<NaiveDate>,
+    pub contas: Vec<ContaContabilResponse>,
+    /// Somas das contas analíticas; em livros fechados, são iguais.
+    pub total_debito: Dinheiro,
+    pub total_credito: Dinheiro,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SaldosQuery {
+    /// Considera só as partidas até esta data (inclusive).
+    ate: Option<NaiveDate>,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/contas", get(list_contas).post(create_conta))
+        .route("/partidas", get(list_partidas).post(create_partida))
+        .route("/partidas/:id", get(get_partida))
+        .route("/partidas/:id/estornar", post(estornar_partida))
+        .route("/balancete", get(balancete))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_contas,
+    create_conta,
+    list_partidas,
+    get_partida,
+    create_partida,
+    estornar_partida,
+    balancete
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/contas",
+    params(EmpresaAtual, SaldosQuery),
+    responses((status = 200, body = Vec<ContaContabilResponse>))
+)]
+async fn list_contas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<SaldosQuery>,
+) -> Result<Json<Vec<ContaContabilResponse>>> {
+    Ok(Json(saldos(&repos, empresa.id(), query.ate).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/contas",
+    params(EmpresaAtual),
+    responses((status = 200, body = ContaContabilResponse))
+)]
+async fn create_conta(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateContaContabil>,
+) -> Result<Json<ContaContabilResponse>> {
+    input.validar()?;
+    let plano = contabilidade::plano(&repos, empresa.id()).await?;
+
+    if plano.por_codigo(&input.codigo).is_some() {
+        return Err(AppError::Conflict(format!(
+            "Já existe a conta {}",
+            input.codigo
+        )));
+    }
+
+    if let Some((codigo_pai, _)) = input.codigo.rsplit_once('.') {
+        let pai = plano
+            .por_codigo(codigo_pai)
+            .ok_or_else(|| AppError::BadRequest(format!("A conta {} não existe", codigo_pai)))?;
+        if pai.tipo != input.tipo {
+            return Err(AppError::BadRequest(format!(
+                "A subconta deve ser do tipo da conta {} ({})",
+                pai.codigo,
+                pai.tipo.as_str()
+            )));
+        }
+        if !plano.aceita_subcontas(pai) {
+            return Err(AppError::Conflict(format!(
+                "A conta {} recebe partidas automáticas e não aceita subcontas",
+                pai.codigo
+            )));
+        }
+
+        // Uma conta analítica com partidas não pode virar sintética.
+        let movimento = repos
+            .partidas
+            .movimento_por_conta(empresa.id(), None)
+            .await?;
+        if !plano.sintetica(pai) && pai.id.is_some_and(|id| movimento.contains_key(&id)) {
+            return Err(AppError::Conflict(format!(
+                "A conta {} já tem partidas e não aceita subcontas",
+                pai.codigo
+            )));
+        }
+    }
+
+    let now = Utc::now();
+    let conta = repos
+        .contas_contabeis
+        .criar(ContaContabil {
+            id: None,
+            empresa_id: empresa.id(),
+            codigo: input.codigo,
+            nome: input.nome.trim().to_string(),
+            tipo: input.tipo,
+            conta_bancaria_id: None,
+            ativo: true,
+            created_at: now,
+            updated_at: now,
+        })
+        .await?;
+    auditor.criacao(&conta).await?;
+
+    Ok(Json(ContaContabilResponse {
+        id: conta.id.map(|id| id.to_hex()),
+        codigo: conta.codigo,
+        nome: conta.nome,
+        tipo: conta.tipo,
+        sintetica: false,
+        conta_bancaria_id: None,
+        debito: Dinheiro::ZERO,
+        credito: Dinheiro::ZERO,
+        saldo: Dinheiro::ZERO,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/partidas",
+    params(EmpresaAtual, Consulta<Partida>),
+    responses((status = 200, body = Pagina<PartidaResponse>))
+)]
+async fn list_partidas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Partida>,
+) -> Result<Json<Pagina<PartidaResponse>>> {
+    let plano = contabilidade::plano(&repos, empresa.id()).await?;
+    let partidas = repos.partidas.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(
+        partidas.map(|partida| PartidaResponse::new(partida, &plano)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/partidas/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = PartidaResponse))
+)]
+async fn get_partida(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<PartidaResponse>> {
+    let partida = find_partida(&repos, empresa, &id).await?;
+    let plano = contabilidade::plano(&repos, empresa.id()).await?;
+    Ok(Json(PartidaResponse::new(partida, &plano)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/partidas",
+    params(EmpresaAtual),
+    responses((status = 200, body = PartidaResponse))
+)]
+async fn create_partida(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreatePartida>,
+) -> Result<Json<PartidaResponse>> {
+    let plano = contabilidade::plano(&repos, empresa.id()).await?;
+
+    let linhas = input
+        .linhas
+        .iter()
+        .map(|linha| {
+            Ok(LinhaPartida {
+                conta_id: ObjectId::parse_str(&linha.conta_id)?,
+                debito: linha.debito,
+                credito: linha.credito,
+                valor_moeda: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let partida = contabilidade::registrar(
+        &repos,
+        &plano,
+        NovaPartida {
+            empresa_id: empresa.id(),
+            data: input.data.unwrap_or_else(|| Utc::now().date_naive()),
+            historico: input.historico.trim().to_string(),
+            origem: OrigemPartida::Manual,
+            origem_id: None,
+            linhas,
+        },
+    )
+    .await?;
+    auditor.criacao(&partida).await?;
+
+    Ok(Json(PartidaResponse::new(partida, &plano)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/partidas/{id}/estornar",
+    params(EmpresaAtual),
+    responses((status = 200, body = PartidaResponse))
+)]
+async fn estornar_partida(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<PartidaResponse>> {
+    let partida = find_partida(&repos, empresa, &id).await?;
+    let estorno = contabilidade::estornar_partida(&repos, &partida).await?;
+    auditor.criacao(&estorno).await?;
+    let plano = contabilidade::plano(&repos, empresa.id()).await?;
+    Ok(Json(PartidaResponse::new(estorno, &plano)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/balancete",
+    params(EmpresaAtual, SaldosQuery),
+    responses((status = 200, body = BalanceteResponse))
+)]
+async fn balancete(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<SaldosQuery>,
+) -> Result<Json<BalanceteResponse>> {
+    let contas = saldos(&repos, empresa.id(), query.ate).await?;
+    let analiticas = contas.iter().filter(|conta| !conta.sintetica);
+    let (total_debito, total_credito) = analiticas
+        .fold((Dinheiro::ZERO, Dinheiro::ZERO), |(d, c), conta| {
+            (d + conta.debito, c + conta.credito)
+        });
+
+    Ok(Json(BalanceteResponse {
+        ate: query.ate,
+        contas,
+        total_debito,
+        total_credito,
+    }))
+}
+
+/// Plano de contas com débitos, créditos e saldo de cada conta; as
+/// sintéticas somam as subcontas.
+async fn saldos(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    ate: Option<NaiveDate>,
+) -> Result<Vec<ContaContabilResponse>> {
+    let plano = contabilidade::plano(repos, empresa_id).await?;
+    let movimento: HashMap<ObjectId, Movimento> =
+        repos.partidas.movimento_por_conta(empresa_id, ate).await?;
+
+    Ok(plano
+        .contas()
+        .iter()
+        .map(|conta| {
+            let mut total = Movimento::default();
+            for outra in plano.contas() {
+                if outra.id == conta.id || conta.contem(&outra.codigo) {
+                    if let Some(m) = outra.id.and_then(|id| movimento.get(&id)) {
+                        total.somar(*m);
+                    }
+                }
+            }
+
+            ContaContabilResponse {
+                id: conta.id.map(|id| id.to_hex()),
+                codigo: conta.codigo.clone(),
+                nome: conta.nome.clone(),
+                tipo: conta.tipo,
+                sintetica: plano.sintetica(conta),
+                conta_bancaria_id: conta.conta_bancaria_id.map(|id| id.to_hex()),
+                debito: total.debito,
+                credito: total.credito,
+                saldo: total.saldo(conta.tipo),
+            }
+        })
+        .collect())
+}
+
+async fn find_partida(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<Partida> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .partidas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        contabilidade::{CAIXA, RECEITAS_DIVERSAS},
+        routes::testes::Ambiente,
+    };
+
+    async fn enviar(
+        ambiente: &Ambiente,
+        metodo: Method,
+        uri: &str,
+        corpo: Option<Value>,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(routes(ambiente.repos.clone()), metodo, uri, corpo)
+            .await
+    }
+
+    /// Contas do plano pelo código: `(id, saldo)`.
+    async fn contas(ambiente: &Ambiente) -> HashMap<String, (String, String)> {
+        let (status, contas) = enviar(ambiente, Method::GET, "/contas", None).await;
+        assert_eq!(status, StatusCode::OK, "{contas}");
+        contas
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|conta| {
+                (
+                    conta["codigo"].as_str().unwrap().to_string(),
+                    (
+                        conta["id"].as_str().unwrap().to_string(),
+                        conta["saldo"].as_str().unwrap().to_string(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn partida(debito: (&str, &str), credito: (&str, &str)) -> Value {
+        json!({
+            "historico": "Aporte",
+            "linhas": [
+                { "conta_id": debito.0, "debito": debito.1 },
+                { "conta_id": credito.0, "credito": credito.1 },
+            ],
+        })
+    }
+
+    fn campos(erro: &Value) -> Vec<&str> {
+        erro["campos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|campo| campo["campo"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn lanca_partida_e_estorna_uma_unica_vez() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let plano = contas(&ambiente).await;
+        let caixa = &plano[CAIXA].0;
+        let receitas = &plano[RECEITAS_DIVERSAS].0;
+
+        let (status, lancada) = enviar(
+            &ambiente,
+            Method::POST,
+            "/partidas",
+            Some(partida((caixa, "100.00"), (receitas, "100.00"))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{lancada}");
+        assert_eq!(contas(&ambiente).await[CAIXA].1, "100.00");
+        let (_, balancete) = enviar(&ambiente, Method::GET, "/balancete", None).await;
+        assert_eq!(balancete["total_debito"], "100.00");
+        assert_eq!(balancete["total_credito"], "100.00");
+
+        let estornar = format!("/partidas/{}/estornar", lancada["id"].as_str().unwrap());
+        let (status, estorno) = enviar(&ambiente, Method::POST, &estornar, None).await;
+        assert_eq!(status, StatusCode::OK, "{estorno}");
+        assert_eq!(estorno["estorno_de"], lancada["id"]);
+        assert_eq!(contas(&ambiente).await[CAIXA].1, "0.00");
+
+        let (status, _) = enviar(&ambiente, Method::POST, &estornar, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let uri = format!("/partidas/{}/estornar", estorno["id"].as_str().unwrap());
+        let (status, _) = enviar(&ambiente, Method::POST, &uri, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let uri = format!("/partidas/{}", ObjectId::new().to_hex());
+        let (status, _) = enviar(&ambiente, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn recusa_partida_desequilibrada_ou_em_conta_sintetica() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let plano = contas(&ambiente).await;
+        let caixa = &plano[CAIXA].0;
+        let receitas = &plano[RECEITAS_DIVERSAS].0;
+
+        let (status, erro) = enviar(
+            &ambiente,
+            Method::POST,
+            "/partidas",
+            Some(partida((caixa, "100.00"), (receitas, "90.00"))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(campos(&erro), ["linhas"]);
+
+        let (status, erro) = enviar(
+            &ambiente,
+            Method::POST,
+            "/partidas",
+            Some(partida((&plano["1.1"].0, "100.00"), (receitas, "100.00"))),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(campos(&erro), ["linhas[0].conta_id"]);
+        let (_, pagina) = enviar(&ambiente, Method::GET, "/partidas", None).await;
+        assert_eq!(pagina["total"], 0);
+    }
+
+    #[tokio::test]
+    async fn cria_subcontas_so_onde_o_plano_permite() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let conta = |codigo: &str, tipo: &str| {
+            Some(json!({ "codigo": codigo, "nome": "Aluguel", "tipo": tipo }))
+        };
+
+        let (status, criada) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            conta("5.1.50", "DESPESA"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{criada}");
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            conta("5.1.50", "DESPESA"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            conta(&format!("{CAIXA}.01"), "ATIVO"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) =
+            enviar(&ambiente, Method::POST, "/contas", conta("5.1.51", "ATIVO")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = enviar(&ambiente, Method::POST, "/contas", conta("5.x", "DESPESA")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Com partidas, a conta não pode mais virar sintética.
+        let plano = contas(&ambiente).await;
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            "/partidas",
+            Some(partida(
+                (&plano["5.1.50"].0, "10.00"),
+                (&plano[CAIXA].0, "10.00"),
+            )),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            conta("5.1.50.01", "DESPESA"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    importacao,
+    models::*,
+    openapi::Arquivo,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct CotacaoResponse {
+    pub id: Option<String>,
+    pub moeda: Moeda,
+    pub data: NaiveDate,
+    pub taxa: TaxaCambio,
+    pub created_at: String,
+}
+
+impl From<Cotacao> for CotacaoResponse {
+    fn from(cotacao: Cotacao) -> Self {
+        Self {
+            id: cotacao.id.map(|id| id.to_hex()),
+            moeda: cotacao.moeda,
+            data: cotacao.data,
+            taxa: cotacao.taxa,
+            created_at: cotacao.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ImportacaoCotacoesResponse {
+    pub importadas: usize,
+    pub atualizadas: usize,
+    pub inalteradas: usize,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_cotacoes).post(create_cotacao))
+        .route("/importar", post(importar_cotacoes))
+        .route("/:id", delete(delete_cotacao))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_cotacoes, create_cotacao, importar_cotacoes, delete_cotacao))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Cotacao>),
+    responses((status = 200, body = Pagina<CotacaoResponse>))
+)]
+async fn list_cotacoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Cotacao>,
+) -> Result<Json<Pagina<CotacaoResponse>>> {
+    let cotacoes = repos.cotacoes.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(cotacoes.map(CotacaoResponse::from)))
+}
+
+/// Grava a cotação, substituindo a da mesma moeda e data se já houver.
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = CotacaoResponse))
+)]
+async fn create_cotacao(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateCotacao>,
+) -> Result<Json<CotacaoResponse>> {
+    input.validar()?;
+
+    let existente = repos
+        .cotacoes
+        .na_data(empresa.id(), input.moeda, input.data)
+        .await?
+        .filter(|cotacao| cotacao.data == input.data);
+
+    if let Some(mut cotacao) = existente {
+        let antes = cotacao.clone();
+        cotacao.taxa = input.taxa;
+        if !repos.cotacoes.salvar(&cotacao).await? {
+            return Err(AppError::NotFound);
+        }
+        auditor.alteracao(&antes, &cotacao).await?;
+        return Ok(Json(cotacao.into()));
+    }
+
+    let cotacao = Cotacao {
+        id: None,
+        empresa_id: empresa.id(),
+        moeda: input.moeda,
+        data: input.data,
+        taxa: input.taxa,
+        created_at: Utc::now(),
+    };
+    let created = repos.cotacoes.criar(cotacao).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+/// Importa um arquivo de cotações (CSV ou o do Banco Central). As que já
+/// existem na mesma moeda e data são substituídas; se o arquivo repetir
+/// uma cotação, vale a última linha.
+#[utoipa::path(
+    post,
+    path = "/importar",
+    request_body(
+        content = Arquivo,
+        content_type = "text/csv",
+        description = "CSV ou arquivo de cotações do Banco Central"
+    ),
+    params(EmpresaAtual),
+    responses((status = 200, body = ImportacaoCotacoesResponse))
+)]
+async fn importar_cotacoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    body: Bytes,
+) -> Result<Json<ImportacaoCotacoesResponse>> {
+    let lidas = importacao::importar_cotacoes(&body)
+        .map_err(|e| AppError::BadRequest(format!("Arquivo inválido: {}", e)))?;
+
+    let mut taxas: HashMap<(Moeda, NaiveDate), TaxaCambio> = HashMap::new();
+    let mut ordem = Vec::new();
+    for lida in lidas {
+        if taxas.insert((lida.moeda, lida.data), lida.taxa).is_none() {
+            ordem.push((lida.moeda, lida.data));
+        }
+    }
+
+    let mut existentes: HashMap<(Moeda, NaiveDate), Cotacao> = repos
+        .cotacoes
+        .listar(empresa.id())
+        .await?
+        .into_iter()
+        .map(|cotacao| ((cotacao.moeda, cotacao.data), cotacao))
+        .collect();
+
+    let now = Utc::now();
+    let mut novas = Vec::new();
+    let mut atualizadas = 0;
+    let mut inalteradas = 0;
+    for chave in ordem {
+        let taxa = taxas[&chave];
+        match existentes.remove(&chave) {
+            Some(cotacao) if cotacao.taxa == taxa => inalteradas += 1,
+            Some(mut cotacao) => {
+                let antes = cotacao.clone();
+                cotacao.taxa = taxa;
+                if repos.cotacoes.salvar(&cotacao).await? {
+                    auditor.alteracao(&antes, &cotacao).await?;
+                    atualizadas += 1;
+                }
+            }
+            None => novas.push(Cotacao {
+                id: None,
+                empresa_id: empresa.id(),
+                moeda: chave.0,
+                data: chave.1,
+                taxa,
+                created_at: now,
+            }),
+        }
+    }
+
+    let importadas = novas.len();
+    if !novas.is_empty() {
+        for cotacao in repos.cotacoes.criar_varios(novas).await? {
+            auditor.criacao(&cotacao).await?;
+        }
+    }
+
+    Ok(Json(ImportacaoCotacoesResponse {
+        importadas,
+        atualizadas,
+        inalteradas,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn delete_cotacao(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if let Some(cotacao) = repos.cotacoes.buscar(empresa.id(), oid).await? {
+        if repos.cotacoes.excluir(empresa.id(), oid).await? {
+            auditor.exclusao(&cotacao).await?;
+        }
+    }
+    Ok(Json("Cotação excluída".to_string()))
+}
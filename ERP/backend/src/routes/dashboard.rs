@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, routing::get, Json, Router};
+use chrono::{Datelike, TimeZone, Utc};
+use utoipa::OpenApi;
+
+use crate::{
+    empresa::EmpresaAtual,
+    error::Result,
+    estoque,
+    models::{DashboardData, Dinheiro, ProdutoMaisVendido, ResumoMes, Venda, VendasHoje},
+    repositorio::Repositorios,
+};
+
+const LIMITE_MAIS_VENDIDOS: usize = 5;
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(get_dashboard))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_dashboard))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = DashboardData))
+)]
+async fn get_dashboard(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+) -> Result<Json<DashboardData>> {
+    let agora = Utc::now();
+    let inicio_dia = agora.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let inicio_mes = Utc
+        .with_ymd_and_hms(agora.year(), agora.month(), 1, 0, 0, 0)
+        .unwrap();
+
+    let vendas_mes = repos
+        .vendas
+        .finalizadas_desde(empresa.id(), inicio_mes)
+        .await?;
+
+    let vendas_dia: Vec<&Venda> = vendas_mes
+        .iter()
+        .filter(|venda| venda.created_at >= inicio_dia)
+        .collect();
+
+    let vendas_hoje = VendasHoje {
+        quantidade: vendas_dia.len() as i64,
+        valor_total: vendas_dia.iter().map(|venda| venda.total_final).sum(),
+    };
+
+    let valor_mes: Dinheiro = vendas_mes.iter().map(|venda| venda.total_final).sum();
+    let resumo_mes = ResumoMes {
+        total_vendas: vendas_mes.len() as i64,
+        valor_total: valor_mes,
+        ticket_medio: valor_mes.media(vendas_mes.len() as u32),
+    };
+
+    let mut acumulado: HashMap<String, ProdutoMaisVendido> = HashMap::new();
+    for item in vendas_mes.iter().flat_map(|venda| &venda.itens) {
+        let entrada = acumulado
+            .entry(item.produto_id.to_hex())
+            .or_insert_with(|| ProdutoMaisVendido {
+                produto_id: item.produto_id.to_hex(),
+                produto_nome: item.produto_nome.clone(),
+                total_vendido: 0,
+                valor_total: Dinheiro::ZERO,
+            });
+        entrada.total_vendido += i64::from(item.quantidade);
+        entrada.valor_total += item.subtotal;
+    }
+    let mut produtos_mais_vendidos: Vec<ProdutoMaisVendido> = acumulado.into_values().collect();
+    produtos_mais_vendidos.sort_by_key(|produto| std::cmp::Reverse(produto.total_vendido));
+    produtos_mais_vendidos.truncate(LIMITE_MAIS_VENDIDOS);
+
+    let estoque_critico = estoque::criticos(&repos, empresa.id()).await?;
+
+    Ok(Json(DashboardData {
+        vendas_hoje,
+        estoque_critico,
+        produtos_mais_vendidos,
+        resumo_mes,
+    }))
+}
@@ -0,0 +1,238 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    auth::UsuarioAutenticado,
+    consulta::{Consulta, Pagina},
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+/// Configuração fiscal sem a senha do certificado e o CSC.
+#[derive(Debug, Serialize, ToSchema)]
+struct FiscalResponse {
+    pub inscricao_estadual: String,
+    pub regime: RegimeTributario,
+    pub logradouro: String,
+    pub numero: String,
+    pub complemento: Option<String>,
+    pub bairro: String,
+    pub codigo_municipio: String,
+    pub municipio: String,
+    pub uf: String,
+    pub cep: String,
+    pub telefone: Option<String>,
+    pub ambiente: Ambiente,
+    pub serie: u32,
+    pub certificado: String,
+    pub nfce: Option<NfceResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct NfceResponse {
+    pub csc_id: String,
+    pub url_qrcode: String,
+    pub url_consulta: String,
+}
+
+impl From<ConfiguracaoFiscal> for FiscalResponse {
+    fn from(fiscal: ConfiguracaoFiscal) -> Self {
+        Self {
+            inscricao_estadual: fiscal.inscricao_estadual,
+            regime: fiscal.regime,
+            logradouro: fiscal.logradouro,
+            numero: fiscal.numero,
+            complemento: fiscal.complemento,
+            bairro: fiscal.bairro,
+            codigo_municipio: fiscal.codigo_municipio,
+            municipio: fiscal.municipio,
+            uf: fiscal.uf,
+            cep: fiscal.cep,
+            telefone: fiscal.telefone,
+            ambiente: fiscal.ambiente,
+            serie: fiscal.serie,
+            certificado: fiscal.certificado,
+            nfce: fiscal.nfce.map(|nfce| NfceResponse {
+                csc_id: nfce.csc_id,
+                url_qrcode: nfce.url_qrcode,
+                url_consulta: nfce.url_consulta,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct EmpresaResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub documento: Option<String>,
+    pub pais: String,
+    pub fiscal: Option<FiscalResponse>,
+    pub ativo: bool,
+}
+
+impl From<Empresa> for EmpresaResponse {
+    fn from(empresa: Empresa) -> Self {
+        Self {
+            id: empresa.id.map(|id| id.to_hex()),
+            nome: empresa.nome,
+            documento: empresa.documento,
+            pais: empresa.pais,
+            fiscal: empresa.fiscal.map(FiscalResponse::from),
+            ativo: empresa.ativo,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_empresas).post(create_empresa))
+        .route("/:id", get(get_empresa).put(update_empresa))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_empresas, get_empresa, create_empresa, update_empresa))]
+pub struct Api;
+
+/// Administradores veem todas as empresas; os demais, só as suas.
+#[utoipa::path(
+    get,
+    path = "",
+    params(Consulta<Empresa>),
+    responses((status = 200, body = Pagina<EmpresaResponse>))
+)]
+async fn list_empresas(
+    State(repos): State<Repositorios>,
+    usuario: UsuarioAutenticado,
+    consulta: Consulta<Empresa>,
+) -> Result<Json<Pagina<EmpresaResponse>>> {
+    let ids = if usuario.papeis.contains(&Papel::Admin) {
+        None
+    } else {
+        Some(usuario.empresas.as_slice())
+    };
+
+    let empresas = repos.empresas.paginar(ids, &consulta).await?;
+    Ok(Json(empresas.map(EmpresaResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    responses((status = 200, body = EmpresaResponse))
+)]
+async fn get_empresa(
+    State(repos): State<Repositorios>,
+    Path(id): Path<String>,
+    usuario: UsuarioAutenticado,
+) -> Result<Json<EmpresaResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if !usuario.empresas.contains(&oid) {
+        usuario.exigir(&[Papel::Admin])?;
+    }
+
+    let empresa = repos
+        .empresas
+        .buscar(oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(empresa.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    responses((status = 200, body = EmpresaResponse))
+)]
+async fn create_empresa(
+    State(repos): State<Repositorios>,
+    usuario: UsuarioAutenticado,
+    auditor: Auditor,
+    Json(input): Json<CreateEmpresa>,
+) -> Result<Json<EmpresaResponse>> {
+    usuario.exigir(&[Papel::Admin])?;
+    input.validar()?;
+    let fiscal = input.fiscal.as_ref().map(ConfiguracaoFiscal::normalizada);
+    if let Some(fiscal) = &fiscal {
+        fiscal.validar_segredos()?;
+    }
+
+    let now = Utc::now();
+    let empresa = Empresa {
+        id: None,
+        nome: input.nome,
+        documento: input.documento,
+        pais: input.pais.trim().to_uppercase(),
+        fiscal,
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = repos.empresas.criar(empresa).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    responses((status = 200, body = EmpresaResponse))
+)]
+async fn update_empresa(
+    State(repos): State<Repositorios>,
+    Path(id): Path<String>,
+    usuario: UsuarioAutenticado,
+    auditor: Auditor,
+    Json(input): Json<UpdateEmpresa>,
+) -> Result<Json<EmpresaResponse>> {
+    usuario.exigir(&[Papel::Admin])?;
+    let oid = ObjectId::parse_str(&id)?;
+    let mut empresa = repos
+        .empresas
+        .buscar(oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    input.validar_no_pais(&empresa.pais)?;
+    let antes = empresa.clone();
+
+    if let Some(nome) = input.nome {
+        empresa.nome = nome;
+    }
+    if let Some(documento) = input.documento {
+        empresa.documento = Some(documento);
+    }
+    if let Some(pais) = input.pais {
+        empresa.pais = pais.trim().to_uppercase();
+    }
+    if let Some(fiscal) = input.fiscal {
+        let fiscal = fiscal
+            .normalizada()
+            .mantendo_segredos(empresa.fiscal.as_ref());
+        fiscal.validar_segredos()?;
+        empresa.fiscal = Some(fiscal);
+    }
+    if let Some(ativo) = input.ativo {
+        empresa.ativo = ativo;
+    }
+    empresa.updated_at = Utc::now();
+
+    if !repos.empresas.salvar(&empresa).await? {
+        return Err(AppError::NotFound);
+    }
+    auditor.alteracao(&antes, &empresa).await?;
+
+    Ok(Json(empresa.into()))
+}
@@ -0,0 +1,349 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    auth::UsuarioAutenticado,
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    estoque::{self, NovaMovimentacao},
+    models::*,
+    repositorio::{FiltroMovimentacoes, Repositorios},
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct MovimentacaoResponse {
+    pub id: Option<String>,
+    pub produto_id: String,
+    pub tipo: TipoMovimentacao,
+    pub quantidade: i32,
+    pub motivo: String,
+    pub usuario: Option<String>,
+    pub venda_id: Option<String>,
+    pub pedido_compra_id: Option<String>,
+    pub created_at: String,
+}
+
+impl From<MovimentacaoEstoque> for MovimentacaoResponse {
+    fn from(movimentacao: MovimentacaoEstoque) -> Self {
+        Self {
+            id: movimentacao.id.map(|id| id.to_hex()),
+            produto_id: movimentacao.produto_id.to_hex(),
+            tipo: movimentacao.tipo,
+            quantidade: movimentacao.quantidade,
+            motivo: movimentacao.motivo,
+            usuario: movimentacao.usuario,
+            venda_id: movimentacao.venda_id.map(|id| id.to_hex()),
+            pedido_compra_id: movimentacao.pedido_compra_id.map(|id| id.to_hex()),
+            created_at: movimentacao.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct LinhaExtrato {
+    #[serde(flatten)]
+    pub movimentacao: MovimentacaoResponse,
+    pub saldo: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct ExtratoResponse {
+    pub produto_id: String,
+    pub produto_nome: String,
+    pub saldo_inicial: i32,
+    pub saldo_atual: i32,
+    pub movimentacoes: Vec<LinhaExtrato>,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route(
+            "/movimentacoes",
+            get(list_movimentacoes).post(create_movimentacao),
+        )
+        .route("/produtos/:id/extrato", get(get_extrato))
+        .route("/criticos", get(list_criticos))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_movimentacoes, create_movimentacao, get_extrato, list_criticos))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/movimentacoes",
+    params(EmpresaAtual, Consulta<MovimentacaoEstoque>),
+    responses((status = 200, body = Pagina<MovimentacaoResponse>))
+)]
+async fn list_movimentacoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<MovimentacaoEstoque>,
+) -> Result<Json<Pagina<MovimentacaoResponse>>> {
+    let movimentacoes = repos.movimentacoes.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(movimentacoes.map(MovimentacaoResponse::from)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/movimentacoes",
+    params(EmpresaAtual),
+    responses((status = 200, body = MovimentacaoResponse))
+)]
+async fn create_movimentacao(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    usuario: UsuarioAutenticado,
+    auditor: Auditor,
+    Json(input): Json<CreateMovimentacao>,
+) -> Result<Json<MovimentacaoResponse>> {
+    if input.motivo.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "Informe o motivo da movimentação".to_string(),
+        ));
+    }
+
+    let movimentacao = estoque::movimentar(
+        &repos,
+        NovaMovimentacao {
+            empresa_id: empresa.id(),
+            produto_id: ObjectId::parse_str(&input.produto_id)?,
+            tipo: input.tipo,
+            quantidade: input.quantidade,
+            motivo: input.motivo,
+            usuario: Some(usuario.email),
+            venda_id: None,
+            pedido_compra_id: None,
+        },
+    )
+    .await?;
+    auditor.criacao(&movimentacao).await?;
+
+    Ok(Json(movimentacao.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/produtos/{id}/extrato",
+    params(EmpresaAtual),
+    responses((status = 200, body = ExtratoResponse))
+)]
+async fn get_extrato(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<ExtratoResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let produto = repos
+        .produtos
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let filtro = FiltroMovimentacoes {
+        produto_id: Some(oid),
+        ..Default::default()
+    };
+    let movimentacoes = repos.movimentacoes.listar(empresa.id(), &filtro).await?;
+
+    // Produtos cadastrados antes do controle de movimentações podem ter um
+    // saldo que não aparece no histórico; ele vira o saldo inicial do extrato.
+    let total_movimentado: i32 = movimentacoes.iter().map(sinal).sum();
+    let saldo_inicial = produto.estoque_atual - total_movimentado;
+
+    let mut saldo = saldo_inicial;
+    let linhas = movimentacoes
+        .into_iter()
+        .map(|movimentacao| {
+            saldo += sinal(&movimentacao);
+            LinhaExtrato {
+                movimentacao: movimentacao.into(),
+                saldo,
+            }
+        })
+        .collect();
+
+    Ok(Json(ExtratoResponse {
+        produto_id: oid.to_hex(),
+        produto_nome: produto.nome,
+        saldo_inicial,
+        saldo_atual: produto.estoque_atual,
+        movimentacoes: linhas,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/criticos",
+    params(EmpresaAtual),
+    responses((status = 200, body = Vec<EstoqueCritico>))
+)]
+async fn list_criticos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+) -> Result<Json<Vec<EstoqueCritico>>> {
+    Ok(Json(estoque::criticos(&repos, empresa.id()).await?))
+}
+
+fn sinal(movimentacao: &MovimentacaoEstoque) -> i32 {
+    match movimentacao.tipo {
+        TipoMovimentacao::Entrada => movimentacao.quantidade,
+        TipoMovimentacao::Saida => -movimentacao.quantidade,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    async fn produto(ambiente: &Ambiente, nome: &str, estoque: i32, minimo: i32) -> String {
+        let (status, produto) = ambiente
+            .enviar(
+                crate::routes::produtos::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": nome,
+                    "preco_custo": "4.00",
+                    "preco_venda": "10.00",
+                    "estoque_atual": estoque,
+                    "estoque_minimo": minimo,
+                    "unidade": "UN",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{produto}");
+        produto["id"].as_str().unwrap().to_string()
+    }
+
+    async fn movimentar(
+        ambiente: &Ambiente,
+        produto_id: &str,
+        tipo: &str,
+        quantidade: i32,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/movimentacoes",
+                Some(json!({
+                    "produto_id": produto_id,
+                    "tipo": tipo,
+                    "quantidade": quantidade,
+                    "motivo": "Ajuste de inventário",
+                })),
+            )
+            .await
+    }
+
+    async fn extrato(ambiente: &Ambiente, produto_id: &str) -> Value {
+        let (status, extrato) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                &format!("/produtos/{produto_id}/extrato"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{extrato}");
+        extrato
+    }
+
+    #[tokio::test]
+    async fn saida_alem_do_estoque_e_recusada() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente, "Caneta", 3, 0).await;
+
+        let (status, erro) = movimentar(&ambiente, &produto_id, "SAIDA", 5).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(erro["error"]
+            .as_str()
+            .unwrap()
+            .contains("Estoque insuficiente"));
+        let antes = extrato(&ambiente, &produto_id).await;
+        assert_eq!(antes["saldo_atual"], 3);
+        assert_eq!(antes["movimentacoes"].as_array().unwrap().len(), 1);
+
+        let (status, _) = movimentar(&ambiente, &produto_id, "SAIDA", 3).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = movimentar(&ambiente, &produto_id, "SAIDA", 1).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(extrato(&ambiente, &produto_id).await["saldo_atual"], 0);
+    }
+
+    #[tokio::test]
+    async fn recusa_movimentacoes_invalidas() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente, "Caneta", 0, 0).await;
+
+        let (status, _) = movimentar(&ambiente, &produto_id, "ENTRADA", 0).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = movimentar(&ambiente, &ObjectId::new().to_hex(), "ENTRADA", 1).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                &format!("/produtos/{}/extrato", ObjectId::new().to_hex()),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn extrato_acumula_o_saldo_e_criticos_vem_por_falta() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let caneta = produto(&ambiente, "Caneta", 2, 5).await;
+        let lapis = produto(&ambiente, "Lápis", 0, 3).await;
+        produto(&ambiente, "Borracha", 10, 1).await;
+
+        movimentar(&ambiente, &caneta, "ENTRADA", 4).await;
+        movimentar(&ambiente, &caneta, "SAIDA", 1).await;
+        let extrato = extrato(&ambiente, &caneta).await;
+        let saldos: Vec<i64> = extrato["movimentacoes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|linha| linha["saldo"].as_i64().unwrap())
+            .collect();
+        assert_eq!(saldos, [2, 6, 5]);
+        assert_eq!(extrato["saldo_inicial"], 0);
+
+        let (status, criticos) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::GET,
+                "/criticos",
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let criticos: Vec<(&str, i64)> = criticos
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|critico| {
+                (
+                    critico["id"].as_str().unwrap(),
+                    critico["falta"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(criticos, [(lapis.as_str(), 3), (caneta.as_str(), 0)]);
+    }
+}
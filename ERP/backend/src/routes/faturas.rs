@@ -0,0 +1,625 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    cartoes::{competencia_da_compra, formatar_competencia},
+    consulta::{Consulta, Pagina, ValorFiltro},
+    contabilidade,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+    saldos,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct CompraCartaoResponse {
+    pub id: Option<String>,
+    pub compra_id: String,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    pub data_compra: NaiveDate,
+    pub parcela: u32,
+    pub total_parcelas: u32,
+    pub competencia: String,
+    pub pago: bool,
+    pub recorrencia_id: Option<String>,
+    pub ocorrencia: Option<u32>,
+}
+
+impl From<CompraCartao> for CompraCartaoResponse {
+    fn from(compra: CompraCartao) -> Self {
+        Self {
+            id: compra.id.map(|id| id.to_hex()),
+            compra_id: compra.compra_id.to_hex(),
+            descricao: compra.descricao,
+            categoria: compra.categoria,
+            valor: compra.valor,
+            data_compra: compra.data_compra,
+            parcela: compra.parcela,
+            total_parcelas: compra.total_parcelas,
+            competencia: compra.competencia,
+            pago: compra.lancamento_id.is_some(),
+            recorrencia_id: compra.recorrencia_id.map(|id| id.to_hex()),
+            ocorrencia: compra.ocorrencia,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+enum StatusFatura {
+    Aberta,
+    Fechada,
+    Paga,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct FaturaResponse {
+    pub competencia: String,
+    pub data_fechamento: NaiveDate,
+    pub data_vencimento: NaiveDate,
+    pub total: Dinheiro,
+    pub em_aberto: Dinheiro,
+    pub status: StatusFatura,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub itens: Option<Vec<CompraCartaoResponse>>,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route(
+            "/cartoes/:id/compras",
+            get(list_compras).post(create_compra),
+        )
+        .route("/cartoes/:id/compras/:compra_id", delete(delete_compra))
+        .route("/cartoes/:id/faturas", get(list_faturas))
+        .route("/cartoes/:id/faturas/:competencia", get(get_fatura))
+        .route(
+            "/cartoes/:id/faturas/:competencia/pagar",
+            post(pagar_fatura),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_compras,
+    create_compra,
+    delete_compra,
+    list_faturas,
+    get_fatura,
+    pagar_fatura
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/cartoes/{id}/compras",
+    params(EmpresaAtual, Consulta<CompraCartao>),
+    responses((status = 200, body = Pagina<CompraCartaoResponse>))
+)]
+async fn list_compras(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+    mut consulta: Consulta<CompraCartao>,
+) -> Result<Json<Pagina<CompraCartaoResponse>>> {
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let cartao_id = cartao.id.ok_or(AppError::NotFound)?;
+    consulta.filtro_padrao("cartao_id", ValorFiltro::Id(cartao_id));
+
+    let compras = repos
+        .compras_cartao
+        .paginar(empresa.id(), &consulta)
+        .await?;
+    Ok(Json(compras.map(CompraCartaoResponse::from)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cartoes/{id}/compras",
+    params(EmpresaAtual),
+    responses((status = 200, body = Vec<CompraCartaoResponse>))
+)]
+async fn create_compra(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<CreateCompraCartao>,
+) -> Result<Json<Vec<CompraCartaoResponse>>> {
+    input.validar()?;
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let cartao_id = cartao.id.ok_or(AppError::NotFound)?;
+    let total_parcelas = input.parcelas.unwrap_or(1);
+
+    let disponivel = cartao.limite - saldos::utilizado(&repos, empresa.id(), cartao_id).await?;
+    if input.valor > disponivel {
+        return Err(AppError::Conflict(format!(
+            "Limite insuficiente (disponível: {})",
+            disponivel
+        )));
+    }
+
+    let primeira = competencia_da_compra(input.data_compra, cartao.dia_fechamento());
+    let competencias = (0..total_parcelas)
+        .map(|indice| {
+            primeira
+                .checked_add_months(Months::new(indice))
+                .map(formatar_competencia)
+                .ok_or_else(|| {
+                    AppError::BadRequest(
+                        "A data da compra está fora do intervalo aceito".to_string(),
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let compra_id = ObjectId::new();
+    let now = Utc::now();
+
+    let compras: Vec<CompraCartao> = input
+        .valor
+        .dividir(total_parcelas)
+        .into_iter()
+        .zip(competencias)
+        .enumerate()
+        .map(|(indice, (valor, competencia))| CompraCartao {
+            id: None,
+            empresa_id: empresa.id(),
+            cartao_id,
+            compra_id,
+            descricao: input.descricao.clone(),
+            categoria: input.categoria.clone(),
+            valor,
+            data_compra: input.data_compra,
+            parcela: indice as u32 + 1,
+            total_parcelas,
+            competencia,
+            lancamento_id: None,
+            recorrencia_id: None,
+            ocorrencia: None,
+            created_at: now,
+        })
+        .collect();
+
+    let criadas = repos.compras_cartao.criar_varias(compras).await?;
+    for criada in &criadas {
+        auditor.criacao(criada).await?;
+    }
+    if let Some(primeira) = criadas.first() {
+        contabilidade::registrar_compra_cartao(&repos, primeira, input.valor).await?;
+    }
+
+    Ok(Json(
+        criadas
+            .into_iter()
+            .map(CompraCartaoResponse::from)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/cartoes/{id}/compras/{compra_id}",
+    params(EmpresaAtual, ("id" = String, Path), ("compra_id" = String, Path)),
+    responses((status = 200, body = String))
+)]
+async fn delete_compra(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, compra_id)): Path<(String, String)>,
+) -> Result<Json<String>> {
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let cartao_id = cartao.id.ok_or(AppError::NotFound)?;
+    let compra_id = ObjectId::parse_str(&compra_id)?;
+
+    let pagas = repos
+        .compras_cartao
+        .contar_pagas(empresa.id(), cartao_id, compra_id)
+        .await?;
+    if pagas > 0 {
+        return Err(AppError::Conflict(
+            "A compra tem parcelas em faturas já pagas".to_string(),
+        ));
+    }
+
+    let parcelas: Vec<CompraCartao> = repos
+        .compras_cartao
+        .listar_do_cartao(empresa.id(), cartao_id)
+        .await?
+        .into_iter()
+        .filter(|parcela| parcela.compra_id == compra_id)
+        .collect();
+    let excluidas = repos
+        .compras_cartao
+        .excluir_compra(empresa.id(), cartao_id, compra_id)
+        .await?;
+    if excluidas == 0 {
+        return Err(AppError::NotFound);
+    }
+    for parcela in &parcelas {
+        auditor.exclusao(parcela).await?;
+    }
+    contabilidade::estornar(&repos, empresa.id(), OrigemPartida::CompraCartao, compra_id).await?;
+    Ok(Json("Compra excluída".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cartoes/{id}/faturas",
+    params(EmpresaAtual),
+    responses((status = 200, body = Vec<FaturaResponse>))
+)]
+async fn list_faturas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<FaturaResponse>>> {
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let cartao_id = cartao.id.ok_or(AppError::NotFound)?;
+    let compras = repos
+        .compras_cartao
+        .listar_do_cartao(empresa.id(), cartao_id)
+        .await?;
+
+    let mut por_competencia: BTreeMap<String, Vec<CompraCartao>> = BTreeMap::new();
+    for compra in compras {
+        por_competencia
+            .entry(compra.competencia.clone())
+            .or_default()
+            .push(compra);
+    }
+
+    let mut faturas = Vec::with_capacity(por_competencia.len());
+    for (competencia, itens) in por_competencia {
+        let mes = parse_competencia(&competencia)?;
+        faturas.push(montar_fatura(&cartao, mes, itens, false));
+    }
+
+    Ok(Json(faturas))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cartoes/{id}/faturas/{competencia}",
+    params(
+        EmpresaAtual,
+        ("id" = String, Path),
+        ("competencia" = String, Path, description = "Mês da fatura, AAAA-MM")
+    ),
+    responses((status = 200, body = FaturaResponse))
+)]
+async fn get_fatura(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path((id, competencia)): Path<(String, String)>,
+) -> Result<Json<FaturaResponse>> {
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let mes = parse_competencia(&competencia)?;
+    let itens = itens_da_fatura(&repos, &cartao, mes).await?;
+
+    Ok(Json(montar_fatura(&cartao, mes, itens, true)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cartoes/{id}/faturas/{competencia}/pagar",
+    params(
+        EmpresaAtual,
+        ("id" = String, Path),
+        ("competencia" = String, Path, description = "Mês da fatura, AAAA-MM")
+    ),
+    responses((status = 200, body = FaturaResponse))
+)]
+async fn pagar_fatura(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, competencia)): Path<(String, String)>,
+    Json(input): Json<PagarFatura>,
+) -> Result<Json<FaturaResponse>> {
+    let cartao = find_cartao(&repos, empresa, &id).await?;
+    let mes = parse_competencia(&competencia)?;
+
+    let conta_id = ObjectId::parse_str(&input.conta_id)?;
+    let conta = repos
+        .contas
+        .buscar(empresa.id(), conta_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if conta.moeda != Moeda::Brl {
+        return Err(AppError::BadRequest(
+            "A fatura do cartão é paga em reais: use uma conta bancária em BRL".to_string(),
+        ));
+    }
+
+    let em_aberto: Vec<CompraCartao> = itens_da_fatura(&repos, &cartao, mes)
+        .await?
+        .into_iter()
+        .filter(|compra| compra.lancamento_id.is_none())
+        .collect();
+    if em_aberto.is_empty() {
+        return Err(AppError::Conflict(
+            "A fatura não possui valores em aberto".to_string(),
+        ));
+    }
+
+    // As parcelas são marcadas antes de gerar o pagamento; se outra
+    // operação quitou alguma delas no meio do caminho, a marcação é
+    // desfeita e nada é lançado.
+    let lancamento_id = ObjectId::new();
+    let ids: Vec<ObjectId> = em_aberto.iter().filter_map(|compra| compra.id).collect();
+    let quitadas = repos.compras_cartao.quitar(&ids, lancamento_id).await?;
+    if quitadas != em_aberto.len() as u64 {
+        repos.compras_cartao.reabrir(lancamento_id).await?;
+        return Err(AppError::Conflict(
+            "A fatura foi alterada por outra operação".to_string(),
+        ));
+    }
+
+    let total: Dinheiro = em_aberto.iter().map(|compra| compra.valor).sum();
+    let (_, data_vencimento) = datas_da_fatura(&cartao, mes);
+    let now = Utc::now();
+
+    // O pagamento vira um lançamento já liquidado; a partida debita a
+    // dívida do cartão e credita a conta bancária.
+    let pagamento = Lancamento {
+        id: Some(lancamento_id),
+        empresa_id: empresa.id(),
+        tipo: TipoLancamento::Pagar,
+        descricao: format!("Fatura cartão {} {}", cartao.banco, competencia),
+        categoria: "Cartão de crédito".to_string(),
+        valor: total,
+        moeda: Moeda::Brl,
+        vencimento: data_vencimento,
+        cliente_id: None,
+        contraparte: Some(cartao.banco.clone()),
+        grupo_id: ObjectId::new(),
+        parcela: 1,
+        total_parcelas: 1,
+        status: StatusLancamento::Pago,
+        conta_id: Some(conta_id),
+        data_pagamento: Some(input.data_pagamento.unwrap_or_else(|| now.date_naive())),
+        valor_pago: Some(total),
+        recorrencia_id: None,
+        ocorrencia: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let pagamento = match repos.lancamentos.criar(pagamento).await {
+        Ok(pagamento) => pagamento,
+        Err(e) => {
+            repos.compras_cartao.reabrir(lancamento_id).await?;
+            return Err(e);
+        }
+    };
+    auditor.criacao(&pagamento).await?;
+    contabilidade::registrar_pagamento_fatura(&repos, &pagamento).await?;
+
+    for compra in &em_aberto {
+        let quitada = CompraCartao {
+            lancamento_id: Some(lancamento_id),
+            ..compra.clone()
+        };
+        auditor.alteracao(compra, &quitada).await?;
+    }
+
+    let itens = itens_da_fatura(&repos, &cartao, mes).await?;
+    Ok(Json(montar_fatura(&cartao, mes, itens, true)))
+}
+
+async fn find_cartao(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<Cartao> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .cartoes
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn itens_da_fatura(
+    repos: &Repositorios,
+    cartao: &Cartao,
+    mes: NaiveDate,
+) -> Result<Vec<CompraCartao>> {
+    let cartao_id = cartao.id.ok_or(AppError::NotFound)?;
+    repos
+        .compras_cartao
+        .da_competencia(cartao.empresa_id, cartao_id, &formatar_competencia(mes))
+        .await
+}
+
+fn montar_fatura(
+    cartao: &Cartao,
+    mes: NaiveDate,
+    itens: Vec<CompraCartao>,
+    com_itens: bool,
+) -> FaturaResponse {
+    let (data_fechamento, data_vencimento) = datas_da_fatura(cartao, mes);
+    let total = itens.iter().map(|compra| compra.valor).sum();
+    let em_aberto: Dinheiro = itens
+        .iter()
+        .filter(|compra| compra.lancamento_id.is_none())
+        .map(|compra| compra.valor)
+        .sum();
+
+    let status = if em_aberto.is_zero() {
+        StatusFatura::Paga
+    } else if Utc::now().date_naive() < data_fechamento {
+        StatusFatura::Aberta
+    } else {
+        StatusFatura::Fechada
+    };
+
+    FaturaResponse {
+        competencia: formatar_competencia(mes),
+        data_fechamento,
+        data_vencimento,
+        total,
+        em_aberto,
+        status,
+        itens: com_itens.then(|| itens.into_iter().map(CompraCartaoResponse::from).collect()),
+    }
+}
+
+/// A fatura fecha no mês da competência; se o vencimento não vier depois
+/// do fechamento, ele cai no mês seguinte.
+fn datas_da_fatura(cartao: &Cartao, mes: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let fechamento = dia_do_mes(mes, cartao.dia_fechamento());
+    let mes_vencimento = if cartao.vencimento > cartao.dia_fechamento() {
+        mes
+    } else {
+        mes + Months::new(1)
+    };
+    (fechamento, dia_do_mes(mes_vencimento, cartao.vencimento))
+}
+
+/// Limita o dia ao último dia do mês (ex.: dia 31 em fevereiro).
+fn dia_do_mes(mes: NaiveDate, dia: i32) -> NaiveDate {
+    let ultimo_dia = (mes + Months::new(1)).pred_opt().unwrap().day();
+    mes.with_day((dia.max(1) as u32).min(ultimo_dia)).unwrap()
+}
+
+fn parse_competencia(competencia: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", competencia), "%Y-%m-%d").map_err(|_| {
+        AppError::BadRequest(format!(
+            "Competência inválida: {} (use AAAA-MM)",
+            competencia
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use crate::routes::testes::Ambiente;
+
+    async fn enviar(
+        ambiente: &Ambiente,
+        metodo: Method,
+        uri: &str,
+        corpo: Value,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                crate::routes::financeiro::routes(ambiente.repos.clone()),
+                metodo,
+                uri,
+                Some(corpo),
+            )
+            .await
+    }
+
+    /// Cartão com fechamento no dia 10 e vencimento no dia 20; devolve o id.
+    async fn cartao(ambiente: &Ambiente) -> String {
+        let (status, cartao) = enviar(
+            ambiente,
+            Method::POST,
+            "/cartoes",
+            json!({
+                "banco": "Banco Teste",
+                "numero": "4111111111111111",
+                "limite": "1000.00",
+                "vencimento": 20,
+                "fechamento": 10,
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{cartao}");
+        cartao["id"].as_str().unwrap().to_string()
+    }
+
+    async fn comprar(ambiente: &Ambiente, cartao_id: &str, parcelas: u32) -> (StatusCode, Value) {
+        enviar(
+            ambiente,
+            Method::POST,
+            &format!("/cartoes/{cartao_id}/compras"),
+            json!({
+                "descricao": "Notebook",
+                "categoria": "Equipamentos",
+                "valor": "300.00",
+                "data_compra": "2026-01-05",
+                "parcelas": parcelas,
+            }),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn limita_o_numero_de_parcelas() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let cartao_id = cartao(&ambiente).await;
+
+        let (status, parcelas) = comprar(&ambiente, &cartao_id, 3).await;
+        assert_eq!(status, StatusCode::OK, "{parcelas}");
+        let competencias: Vec<&str> = parcelas
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|parcela| parcela["competencia"].as_str().unwrap())
+            .collect();
+        assert_eq!(competencias, ["2026-01", "2026-02", "2026-03"]);
+
+        for parcelas in [0, 361] {
+            let (status, erro) = comprar(&ambiente, &cartao_id, parcelas).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(erro["campos"][0]["campo"], "parcelas");
+        }
+    }
+
+    #[tokio::test]
+    async fn fatura_paga_nao_e_paga_de_novo() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let cartao_id = cartao(&ambiente).await;
+        comprar(&ambiente, &cartao_id, 2).await;
+        let (status, conta) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            json!({
+                "banco": "Banco Teste",
+                "agencia": "0001",
+                "conta": "12345-6",
+                "tipo": "corrente",
+                "saldo": "1000.00",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{conta}");
+
+        let pagar = format!("/cartoes/{cartao_id}/faturas/2026-01/pagar");
+        let pagamento = json!({ "conta_id": conta["id"], "data_pagamento": "2026-01-20" });
+        let (status, fatura) = enviar(&ambiente, Method::POST, &pagar, pagamento.clone()).await;
+        assert_eq!(status, StatusCode::OK, "{fatura}");
+        assert_eq!(fatura["status"], "PAGA");
+        assert_eq!(fatura["em_aberto"], "0.00");
+
+        let (status, _) = enviar(&ambiente, Method::POST, &pagar, pagamento).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (_, seguinte) = enviar(
+            &ambiente,
+            Method::GET,
+            &format!("/cartoes/{cartao_id}/faturas/2026-02"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(seguinte["em_aberto"], "150.00");
+    }
+}
@@ -1,91 +1,99 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     routing::get,
     Json, Router,
 };
-use chrono::Utc;
-use futures::stream::TryStreamExt;
-use mongodb::bson::{doc, oid::ObjectId};
-use serde::Serialize;
+use chrono::{NaiveDate, Utc};
+use erp_api::{CartaoResponse, ContaResponse};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 use crate::{
-    error::{AppError, Result},
+    auditoria::Auditor,
+    cambio::{self, Conversor},
+    cartoes,
+    concorrencia::{self, ComVersao, VersaoEsperada},
+    consulta::{Consulta, Pagina},
+    contabilidade,
+    empresa::EmpresaAtual,
+    error::{AppError, CorpoErro, Result},
     models::*,
-    mongodb::MongoDb,
+    repositorio::Repositorios,
+    saldos,
+    validacao::Validar,
 };
 
-#[derive(Debug, Serialize)]
-struct ContaResponse {
-    pub id: Option<String>,
-    pub nome: String,
-    pub banco: String,
-    pub tipo_conta: String,
-    pub agencia: String,
-    pub numero_conta: String,
-    pub saldo_inicial: f64,
-    pub saldo_atual: f64,
-    pub ativo: bool,
+/// `saldo_atual` é o saldo na moeda da conta, de
+/// [`saldos::contas_bancarias`].
+fn conta_response(conta: ContaBancaria, saldo_atual: Dinheiro) -> ContaResponse {
+    ContaResponse {
+        id: conta.id.map(|id| id.to_hex()),
+        nome: format!("Conta {} {}", conta.banco, conta.conta),
+        banco: conta.banco,
+        tipo_conta: conta.tipo,
+        agencia: conta.agencia,
+        numero_conta: conta.conta,
+        moeda: conta.moeda,
+        saldo_inicial: conta.saldo,
+        saldo_atual,
+        pix: conta.pix,
+        boleto: conta.boleto,
+        ativo: true,
+        versao: conta.versao,
+    }
 }
 
-impl From<ContaBancaria> for ContaResponse {
-    fn from(conta: ContaBancaria) -> Self {
-        Self {
-            id: conta.id.map(|id| id.to_hex()),
-            nome: format!("Conta {} {}", conta.banco, conta.conta),
-            banco: conta.banco,
-            tipo_conta: conta.tipo,
-            agencia: conta.agencia,
-            numero_conta: conta.conta,
-            saldo_inicial: conta.saldo,
-            saldo_atual: conta.saldo,
-            ativo: true,
-        }
+/// `utilizado` é a soma das parcelas em aberto nas faturas do cartão.
+fn cartao_response(cartao: Cartao, utilizado: Dinheiro) -> CartaoResponse {
+    let dia_fechamento = cartao.dia_fechamento();
+
+    CartaoResponse {
+        id: cartao.id.map(|id| id.to_hex()),
+        nome: format!("Cartão {}", cartao.banco),
+        bandeira: cartao.bandeira,
+        ultimos_digitos: cartao.ultimos_digitos,
+        limite_total: cartao.limite,
+        limite_disponivel: cartao.limite - utilizado,
+        dia_vencimento: cartao.vencimento,
+        dia_fechamento,
+        ativo: true,
+        versao: cartao.versao,
     }
 }
 
-#[derive(Debug, Serialize)]
-struct CartaoResponse {
-    pub id: Option<String>,
-    pub nome: String,
-    pub bandeira: String,
-    pub ultimos_digitos: String,
-    pub limite_total: f64,
-    pub limite_disponivel: f64,
-    pub dia_vencimento: i32,
-    pub dia_fechamento: i32,
-    pub ativo: bool,
+/// Saldo atual de uma conta bancária, na moeda dela e convertido.
+#[derive(Debug, Serialize, ToSchema)]
+struct SaldoContaResponse {
+    pub conta_id: String,
+    pub conta: String,
+    pub moeda: Moeda,
+    pub saldo: Dinheiro,
+    /// Unidades da moeda consolidada por unidade da moeda da conta.
+    pub taxa: TaxaCambio,
+    pub saldo_convertido: Dinheiro,
 }
 
-impl From<Cartao> for CartaoResponse {
-    fn from(cartao: Cartao) -> Self {
-        let ultimos_digitos = cartao
-            .numero
-            .chars()
-            .rev()
-            .take(4)
-            .collect::<String>()
-            .chars()
-            .rev()
-            .collect();
-
-        let dia_fechamento = (cartao.vencimento - 7).max(1);
-
-        Self {
-            id: cartao.id.map(|id| id.to_hex()),
-            nome: format!("Cartão {}", cartao.banco),
-            bandeira: cartao.bandeira,
-            ultimos_digitos,
-            limite_total: cartao.limite,
-            limite_disponivel: cartao.limite,
-            dia_vencimento: cartao.vencimento,
-            dia_fechamento,
-            ativo: true,
-        }
-    }
+#[derive(Debug, Serialize, ToSchema)]
+struct SaldosConsolidadosResponse {
+    pub moeda: Moeda,
+    /// Data das cotações usadas.
+    pub data: NaiveDate,
+    pub contas: Vec<SaldoContaResponse>,
+    pub total: Dinheiro,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SaldosQuery {
+    /// Moeda do total. Padrão: reais.
+    #[serde(default)]
+    moeda: Moeda,
 }
 
-pub fn routes(mongo: MongoDb) -> Router {
+pub fn routes(repos: Repositorios) -> Router {
     Router::new()
+        .route("/saldos", get(saldos_consolidados))
         .route("/contas", get(list_contas).post(create_conta))
         .route(
             "/contas/:id",
@@ -96,251 +104,693 @@ pub fn routes(mongo: MongoDb) -> Router {
             "/cartoes/:id",
             get(get_cartao).put(update_cartao).delete(delete_cartao),
         )
-        .with_state(mongo)
+        .with_state(repos.clone())
+        .merge(super::faturas::routes(repos.clone()))
+        .merge(super::conciliacao::routes(repos.clone()))
+        .nest("/lancamentos", super::lancamentos::routes(repos.clone()))
+        .nest("/recorrencias", super::recorrencias::routes(repos.clone()))
+        .nest("/cotacoes", super::cotacoes::routes(repos))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    saldos_consolidados,
+    list_contas,
+    get_conta,
+    create_conta,
+    update_conta,
+    delete_conta,
+    list_cartoes,
+    get_cartao,
+    create_cartao,
+    update_cartao,
+    delete_cartao
+))]
+pub struct Api;
+
+/// Saldos atuais de todas as contas bancárias e o total numa só moeda,
+/// pelas cotações de hoje.
+#[utoipa::path(
+    get,
+    path = "/saldos",
+    params(EmpresaAtual, SaldosQuery),
+    responses((status = 200, body = SaldosConsolidadosResponse))
+)]
+async fn saldos_consolidados(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<SaldosQuery>,
+) -> Result<Json<SaldosConsolidadosResponse>> {
+    let hoje = Utc::now().date_naive();
+    let contas = repos.contas.listar(empresa.id()).await?;
+    let saldos = saldos::contas_bancarias(&repos, empresa.id(), &contas).await?;
+    let mut conversor = Conversor::new(&repos, empresa.id(), query.moeda);
+
+    let mut linhas = Vec::with_capacity(contas.len());
+    for conta in contas {
+        let Some(id) = conta.id else {
+            continue;
+        };
+        let saldo = saldos.get(&id).copied().unwrap_or_default();
+        linhas.push(SaldoContaResponse {
+            conta_id: id.to_hex(),
+            conta: format!("Conta {} {}", conta.banco, conta.conta),
+            moeda: conta.moeda,
+            saldo,
+            taxa: conversor.taxa(conta.moeda, hoje).await?,
+            saldo_convertido: conversor.converter(saldo, conta.moeda, hoje).await?,
+        });
+    }
+    linhas.sort_by(|a, b| a.conta.cmp(&b.conta));
+
+    Ok(Json(SaldosConsolidadosResponse {
+        moeda: query.moeda,
+        data: hoje,
+        total: linhas.iter().map(|linha| linha.saldo_convertido).sum(),
+        contas: linhas,
+    }))
 }
 
 // === CONTAS BANCÁRIAS ===
 
-async fn list_contas(State(mongo): State<MongoDb>) -> Result<Json<Vec<ContaResponse>>> {
-    let contas: Vec<ContaBancaria> = mongo
-        .contas_bancarias()
-        .find(None, None)
-        .await?
-        .try_collect()
-        .await?;
-    Ok(Json(contas.into_iter().map(ContaResponse::from).collect()))
+#[utoipa::path(
+    get,
+    path = "/contas",
+    params(EmpresaAtual, Consulta<ContaBancaria>),
+    responses((status = 200, body = Pagina<ContaResponse>))
+)]
+async fn list_contas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<ContaBancaria>,
+) -> Result<Json<Pagina<ContaResponse>>> {
+    let contas = repos.contas.paginar(empresa.id(), &consulta).await?;
+    let saldos = saldos::contas_bancarias(&repos, empresa.id(), &contas.itens).await?;
+    Ok(Json(contas.map(|conta| {
+        let saldo = conta
+            .id
+            .and_then(|id| saldos.get(&id).copied())
+            .unwrap_or_default();
+        conta_response(conta, saldo)
+    })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/contas/{id}",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = ContaResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
 async fn get_conta(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
     Path(id): Path<String>,
-) -> Result<Json<ContaResponse>> {
+) -> Result<ComVersao<ContaResponse>> {
     let oid = ObjectId::parse_str(&id)?;
-    let conta = mongo
-        .contas_bancarias()
-        .find_one(doc! { "_id": oid }, None)
+    let conta = repos
+        .contas
+        .buscar(empresa.id(), oid)
         .await?
         .ok_or(AppError::NotFound)?;
-    Ok(Json(conta.into()))
+    let saldo = saldos::conta_bancaria(&repos, &conta).await?;
+    Ok(ComVersao(conta.versao, conta_response(conta, saldo)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/contas",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = ContaResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
 async fn create_conta(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
     Json(input): Json<CreateContaBancaria>,
-) -> Result<Json<ContaResponse>> {
+) -> Result<ComVersao<ContaResponse>> {
+    input.validar()?;
     let now = Utc::now();
+    // O saldo inicial em moeda estrangeira é contabilizado pela cotação do
+    // dia: sem ela, a conta nem é gravada.
+    if !input.saldo.is_zero() {
+        cambio::taxa(&repos, empresa.id(), input.moeda, now.date_naive()).await?;
+    }
     let conta = ContaBancaria {
         id: None,
+        empresa_id: empresa.id(),
         banco: input.banco,
         agencia: input.agencia,
         conta: input.conta,
         tipo: input.tipo,
         saldo: input.saldo,
+        moeda: input.moeda,
+        pix: input.pix.as_ref().map(normalizar_pix),
+        boleto: input.boleto.as_ref().map(CarteiraBoleto::normalizada),
         created_at: now,
         updated_at: now,
+        versao: 0,
     };
 
-    let result = mongo.contas_bancarias().insert_one(&conta, None).await?;
-    let inserted_id = result.inserted_id.as_object_id().unwrap();
-
-    let created = mongo
-        .contas_bancarias()
-        .find_one(doc! { "_id": inserted_id }, None)
-        .await?
-        .unwrap();
+    let created = repos.contas.criar(conta).await?;
+    auditor.criacao(&created).await?;
+    contabilidade::registrar_saldo_inicial(&repos, &created, created.saldo).await?;
+    let saldo = created.saldo;
 
-    Ok(Json(created.into()))
+    Ok(ComVersao(created.versao, conta_response(created, saldo)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/contas/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (
+            status = 200,
+            body = ContaResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        ),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
 async fn update_conta(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
     Path(id): Path<String>,
     Json(input): Json<UpdateContaBancaria>,
-) -> Result<Json<ContaResponse>> {
+) -> Result<ComVersao<ContaResponse>> {
+    input.validar()?;
     let oid = ObjectId::parse_str(&id)?;
 
-    let mut update_doc = doc! {
-        "$set": {
-            "updated_at": bson::DateTime::from_chrono(Utc::now())
-        }
-    };
+    let mut conta = repos
+        .contas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    versao.conferir(&conta)?;
+    let antes = conta.clone();
 
     if let Some(banco) = input.banco {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("banco", banco);
+        conta.banco = banco;
     }
     if let Some(agencia) = input.agencia {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("agencia", agencia);
+        conta.agencia = agencia;
     }
-    if let Some(conta) = input.conta {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("conta", conta);
+    if let Some(numero) = input.conta {
+        conta.conta = numero;
     }
     if let Some(tipo) = input.tipo {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("tipo", tipo);
+        conta.tipo = tipo;
+    }
+    if let Some(pix) = input.pix {
+        conta.pix = Some(normalizar_pix(&pix));
+    }
+    if let Some(boleto) = input.boleto {
+        conta.boleto = Some(boleto.normalizada());
     }
+    let saldo_anterior = conta.saldo;
     if let Some(saldo) = input.saldo {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("saldo", saldo);
+        conta.saldo = saldo;
     }
-
-    mongo
-        .contas_bancarias()
-        .update_one(doc! { "_id": oid }, update_doc, None)
+    conta.updated_at = Utc::now();
+    if conta.saldo != saldo_anterior {
+        cambio::taxa(
+            &repos,
+            empresa.id(),
+            conta.moeda,
+            conta.updated_at.date_naive(),
+        )
         .await?;
+    }
 
-    let updated = mongo
-        .contas_bancarias()
-        .find_one(doc! { "_id": oid }, None)
-        .await?
-        .ok_or(AppError::NotFound)?;
+    if !repos.contas.salvar_versao(&mut conta).await? {
+        return Err(concorrencia::desatualizado());
+    }
+    auditor.alteracao(&antes, &conta).await?;
+    // Alterar o saldo inicial lança a diferença; partidas anteriores não
+    // são reescritas.
+    contabilidade::registrar_saldo_inicial(&repos, &conta, conta.saldo - saldo_anterior).await?;
+    let saldo = saldos::conta_bancaria(&repos, &conta).await?;
 
-    Ok(Json(updated.into()))
+    Ok(ComVersao(conta.versao, conta_response(conta, saldo)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/contas/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (status = 200, body = String),
+        (status = 404, body = CorpoErro),
+        (
+            status = 409,
+            description = "A conta tem lançamentos ou partidas, ou mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
 async fn delete_conta(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
     Path(id): Path<String>,
 ) -> Result<Json<String>> {
     let oid = ObjectId::parse_str(&id)?;
-    mongo
-        .contas_bancarias()
-        .delete_one(doc! { "_id": oid }, None)
-        .await?;
+    let conta = repos
+        .contas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    versao.conferir(&conta)?;
+
+    if repos.lancamentos.existe_da_conta(empresa.id(), oid).await?
+        || contabilidade::banco_movimentado(&repos, empresa.id(), oid).await?
+    {
+        return Err(AppError::Conflict(
+            "A conta tem lançamentos ou partidas e não pode ser excluída".to_string(),
+        ));
+    }
+
+    if !repos
+        .contas
+        .excluir_versao(empresa.id(), oid, conta.versao)
+        .await?
+    {
+        return Err(concorrencia::desatualizado());
+    }
+    auditor.exclusao(&conta).await?;
     Ok(Json("Conta excluída".to_string()))
 }
 
 // === CARTÕES ===
 
-async fn list_cartoes(State(mongo): State<MongoDb>) -> Result<Json<Vec<CartaoResponse>>> {
-    let cartoes: Vec<Cartao> = mongo
-        .cartoes()
-        .find(None, None)
-        .await?
-        .try_collect()
+#[utoipa::path(
+    get,
+    path = "/cartoes",
+    params(EmpresaAtual, Consulta<Cartao>),
+    responses((status = 200, body = Pagina<CartaoResponse>))
+)]
+async fn list_cartoes(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Cartao>,
+) -> Result<Json<Pagina<CartaoResponse>>> {
+    let cartoes = repos.cartoes.paginar(empresa.id(), &consulta).await?;
+    let utilizado = repos
+        .compras_cartao
+        .utilizado_por_cartao(empresa.id(), None)
         .await?;
-    Ok(Json(cartoes.into_iter().map(CartaoResponse::from).collect()))
+    Ok(Json(cartoes.map(|cartao| {
+        let total = cartao
+            .id
+            .and_then(|id| utilizado.get(&id).copied())
+            .unwrap_or_default();
+        cartao_response(cartao, total)
+    })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/cartoes/{id}",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = CartaoResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
 async fn get_cartao(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
     Path(id): Path<String>,
-) -> Result<Json<CartaoResponse>> {
+) -> Result<ComVersao<CartaoResponse>> {
     let oid = ObjectId::parse_str(&id)?;
-    let cartao = mongo
-        .cartoes()
-        .find_one(doc! { "_id": oid }, None)
+    let cartao = repos
+        .cartoes
+        .buscar(empresa.id(), oid)
         .await?
         .ok_or(AppError::NotFound)?;
-    Ok(Json(cartao.into()))
+    let utilizado = saldos::utilizado(&repos, empresa.id(), oid).await?;
+    Ok(ComVersao(cartao.versao, cartao_response(cartao, utilizado)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/cartoes",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = CartaoResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
 async fn create_cartao(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
     Json(input): Json<CreateCartao>,
-) -> Result<Json<CartaoResponse>> {
+) -> Result<ComVersao<CartaoResponse>> {
+    input.validar()?;
     let now = Utc::now();
     let cartao = Cartao {
         id: None,
+        empresa_id: empresa.id(),
         banco: input.banco,
-        numero: input.numero,
-        bandeira: input.bandeira,
+        ultimos_digitos: cartoes::ultimos_digitos(&input.numero),
+        bandeira: cartoes::detectar_bandeira(&input.numero)
+            .map(str::to_string)
+            .or(input.bandeira)
+            .unwrap_or_default(),
         limite: input.limite,
         vencimento: input.vencimento,
+        fechamento: input.fechamento,
         created_at: now,
         updated_at: now,
+        versao: 0,
     };
 
-    let result = mongo.cartoes().insert_one(&cartao, None).await?;
-    let inserted_id = result.inserted_id.as_object_id().unwrap();
-
-    let created = mongo
-        .cartoes()
-        .find_one(doc! { "_id": inserted_id }, None)
-        .await?
-        .unwrap();
+    let created = repos.cartoes.criar(cartao).await?;
+    auditor.criacao(&created).await?;
 
-    Ok(Json(created.into()))
+    Ok(ComVersao(
+        created.versao,
+        cartao_response(created, Dinheiro::ZERO),
+    ))
 }
 
+#[utoipa::path(
+    put,
+    path = "/cartoes/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (
+            status = 200,
+            body = CartaoResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        ),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
 async fn update_cartao(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
     Path(id): Path<String>,
     Json(input): Json<UpdateCartao>,
-) -> Result<Json<CartaoResponse>> {
+) -> Result<ComVersao<CartaoResponse>> {
+    input.validar()?;
     let oid = ObjectId::parse_str(&id)?;
 
-    let mut update_doc = doc! {
-        "$set": {
-            "updated_at": bson::DateTime::from_chrono(Utc::now())
-        }
-    };
+    let mut cartao = repos
+        .cartoes
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    versao.conferir(&cartao)?;
+    let antes = cartao.clone();
 
     if let Some(banco) = input.banco {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("banco", banco);
-    }
-    if let Some(numero) = input.numero {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("numero", numero);
+        cartao.banco = banco;
     }
     if let Some(bandeira) = input.bandeira {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("bandeira", bandeira);
+        cartao.bandeira = bandeira;
     }
     if let Some(limite) = input.limite {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("limite", limite);
+        cartao.limite = limite;
     }
     if let Some(vencimento) = input.vencimento {
-        update_doc
-            .get_document_mut("$set")
-            .unwrap()
-            .insert("vencimento", vencimento);
+        cartao.vencimento = vencimento;
     }
+    if let Some(fechamento) = input.fechamento {
+        cartao.fechamento = Some(fechamento);
+    }
+    cartao.updated_at = Utc::now();
 
-    mongo
-        .cartoes()
-        .update_one(doc! { "_id": oid }, update_doc, None)
-        .await?;
-
-    let updated = mongo
-        .cartoes()
-        .find_one(doc! { "_id": oid }, None)
-        .await?
-        .ok_or(AppError::NotFound)?;
+    if !repos.cartoes.salvar_versao(&mut cartao).await? {
+        return Err(concorrencia::desatualizado());
+    }
+    auditor.alteracao(&antes, &cartao).await?;
+    let utilizado = saldos::utilizado(&repos, empresa.id(), oid).await?;
 
-    Ok(Json(updated.into()))
+    Ok(ComVersao(cartao.versao, cartao_response(cartao, utilizado)))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/cartoes/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (status = 200, body = String),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
 async fn delete_cartao(
-    State(mongo): State<MongoDb>,
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
     Path(id): Path<String>,
 ) -> Result<Json<String>> {
     let oid = ObjectId::parse_str(&id)?;
-    mongo
-        .cartoes()
-        .delete_one(doc! { "_id": oid }, None)
-        .await?;
+    if let Some(cartao) = repos.cartoes.buscar(empresa.id(), oid).await? {
+        versao.conferir(&cartao)?;
+        if !repos
+            .cartoes
+            .excluir_versao(empresa.id(), oid, cartao.versao)
+            .await?
+        {
+            return Err(concorrencia::desatualizado());
+        }
+        auditor.exclusao(&cartao).await?;
+    }
     Ok(Json("Cartão excluído".to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use mongodb::bson::oid::ObjectId;
+    use serde_json::{json, Value};
+
+    use crate::routes::testes::Ambiente;
+
+    async fn enviar(
+        ambiente: &Ambiente,
+        metodo: Method,
+        uri: &str,
+        corpo: Option<Value>,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(super::routes(ambiente.repos.clone()), metodo, uri, corpo)
+            .await
+    }
+
+    async fn conta(ambiente: &Ambiente) -> String {
+        let (status, conta) = enviar(
+            ambiente,
+            Method::POST,
+            "/contas",
+            Some(json!({
+                "banco": "Banco Teste",
+                "agencia": "0001",
+                "conta": "12345-6",
+                "tipo": "corrente",
+                "saldo": "0.00",
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{conta}");
+        conta["id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn exclui_so_a_conta_sem_movimento() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (status, _) = enviar(
+            &ambiente,
+            Method::DELETE,
+            &format!("/contas/{}", ObjectId::new()),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let movimentada = conta(&ambiente).await;
+        let (_, criados) = enviar(
+            &ambiente,
+            Method::POST,
+            "/lancamentos",
+            Some(json!({
+                "tipo": "PAGAR",
+                "descricao": "Energia",
+                "categoria": "Utilidades",
+                "valor": "80.00",
+                "vencimento": "2026-03-10",
+            })),
+        )
+        .await;
+        let lancamento = criados[0]["id"].as_str().unwrap();
+        let (status, liquidado) = enviar(
+            &ambiente,
+            Method::POST,
+            &format!("/lancamentos/{lancamento}/liquidar"),
+            Some(json!({ "conta_id": movimentada, "data_pagamento": "2026-03-10" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{liquidado}");
+
+        let (status, _) = enviar(
+            &ambiente,
+            Method::DELETE,
+            &format!("/contas/{movimentada}"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let livre = conta(&ambiente).await;
+        let excluir = format!("/contas/{livre}");
+        let (status, _) = enviar(&ambiente, Method::DELETE, &excluir, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = enviar(&ambiente, Method::DELETE, &excluir, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    async fn liquidado(ambiente: &Ambiente, conta_id: &str, tipo: &str, valor: &str, data: &str) {
+        let (status, criados) = enviar(
+            ambiente,
+            Method::POST,
+            "/lancamentos",
+            Some(json!({
+                "tipo": tipo,
+                "descricao": "Câmbio",
+                "categoria": "Exportação",
+                "valor": valor,
+                "moeda": "USD",
+                "vencimento": data,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{criados}");
+        let (status, liquidado) = enviar(
+            ambiente,
+            Method::POST,
+            &format!(
+                "/lancamentos/{}/liquidar",
+                criados[0]["id"].as_str().unwrap()
+            ),
+            Some(json!({ "conta_id": conta_id, "data_pagamento": data })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{liquidado}");
+    }
+
+    #[tokio::test]
+    async fn saldo_em_moeda_estrangeira_sai_das_partidas() {
+        let ambiente = Ambiente::novo(&[]).await;
+        for (data, taxa) in [("2020-01-01", "5.00"), ("2021-01-01", "6.00")] {
+            let (status, cotacao) = enviar(
+                &ambiente,
+                Method::POST,
+                "/cotacoes",
+                Some(json!({ "moeda": "USD", "data": data, "taxa": taxa })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK, "{cotacao}");
+        }
+        let (status, conta) = enviar(
+            &ambiente,
+            Method::POST,
+            "/contas",
+            Some(json!({
+                "banco": "Banco Teste",
+                "agencia": "0001",
+                "conta": "98765-4",
+                "tipo": "corrente",
+                "moeda": "USD",
+                "saldo": "100.00",
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{conta}");
+        let conta_id = conta["id"].as_str().unwrap();
+
+        liquidado(&ambiente, conta_id, "RECEBER", "50.00", "2020-06-01").await;
+        liquidado(&ambiente, conta_id, "PAGAR", "30.00", "2021-06-01").await;
+
+        // Partida manual no banco, sem o valor em dólares: R$ 60,00 pela
+        // cotação de 6,00 são US$ 10,00.
+        let plano = ambiente
+            .repos
+            .contas_contabeis
+            .plano(ambiente.empresa_id)
+            .await
+            .unwrap();
+        let conta_id_oid = ObjectId::parse_str(conta_id).unwrap();
+        let banco = plano
+            .iter()
+            .find(|conta| conta.conta_bancaria_id == Some(conta_id_oid))
+            .and_then(|conta| conta.id)
+            .unwrap();
+        let outra = plano
+            .iter()
+            .find(|conta| conta.codigo == crate::contabilidade::SALDOS_INICIAIS)
+            .and_then(|conta| conta.id)
+            .unwrap();
+        ambiente
+            .repos
+            .partidas
+            .criar(crate::models::Partida {
+                id: None,
+                empresa_id: ambiente.empresa_id,
+                data: "2021-07-01".parse().unwrap(),
+                historico: "Ajuste".to_string(),
+                origem: crate::models::OrigemPartida::Manual,
+                origem_id: None,
+                estorno_de: None,
+                linhas: vec![
+                    crate::contabilidade::debito(banco, "60.00".parse().unwrap()),
+                    crate::contabilidade::credito(outra, "60.00".parse().unwrap()),
+                ],
+                created_at: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let (status, conta) =
+            enviar(&ambiente, Method::GET, &format!("/contas/{conta_id}"), None).await;
+        assert_eq!(status, StatusCode::OK, "{conta}");
+        assert_eq!(conta["saldo_atual"], "130.00");
+    }
+}
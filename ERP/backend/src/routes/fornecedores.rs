@@ -0,0 +1,329 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    concorrencia::{self, ComVersao, VersaoEsperada},
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, CorpoErro, Result},
+    models::*,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct FornecedorResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub cpf_cnpj: String,
+    pub contato: Option<String>,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub cidade: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: bool,
+    pub versao: u32,
+}
+
+impl From<Fornecedor> for FornecedorResponse {
+    fn from(fornecedor: Fornecedor) -> Self {
+        Self {
+            id: fornecedor.id.map(|id| id.to_hex()),
+            nome: fornecedor.nome,
+            cpf_cnpj: fornecedor.cpf_cnpj,
+            contato: fornecedor.contato,
+            telefone: fornecedor.telefone,
+            email: fornecedor.email,
+            endereco: fornecedor.endereco,
+            cidade: fornecedor.cidade,
+            estado: fornecedor.estado,
+            cep: fornecedor.cep,
+            ativo: fornecedor.ativo,
+            versao: fornecedor.versao,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_fornecedores).post(create_fornecedor))
+        .route(
+            "/:id",
+            get(get_fornecedor)
+                .put(update_fornecedor)
+                .delete(delete_fornecedor),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_fornecedores,
+    get_fornecedor,
+    create_fornecedor,
+    update_fornecedor,
+    delete_fornecedor
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Fornecedor>),
+    responses((status = 200, body = Pagina<FornecedorResponse>))
+)]
+async fn list_fornecedores(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Fornecedor>,
+) -> Result<Json<Pagina<FornecedorResponse>>> {
+    let fornecedores = repos.fornecedores.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(fornecedores.map(FornecedorResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = FornecedorResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
+async fn get_fornecedor(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<ComVersao<FornecedorResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let fornecedor = repos
+        .fornecedores
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(ComVersao(fornecedor.versao, fornecedor.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses(
+        (
+            status = 200,
+            body = FornecedorResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        )
+    )
+)]
+async fn create_fornecedor(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateFornecedor>,
+) -> Result<ComVersao<FornecedorResponse>> {
+    input.validar()?;
+    let now = Utc::now();
+    let fornecedor = Fornecedor {
+        id: None,
+        empresa_id: empresa.id(),
+        nome: input.nome,
+        cpf_cnpj: input.cpf_cnpj,
+        contato: input.contato,
+        telefone: input.telefone,
+        email: input.email,
+        endereco: input.endereco,
+        cidade: input.cidade,
+        estado: input.estado,
+        cep: input.cep,
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+        versao: 0,
+    };
+
+    let created = repos.fornecedores.criar(fornecedor).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(ComVersao(created.versao, created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (
+            status = 200,
+            body = FornecedorResponse,
+            headers(("ETag" = String, description = "Versão do registro, para o If-Match"))
+        ),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
+async fn update_fornecedor(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateFornecedor>,
+) -> Result<ComVersao<FornecedorResponse>> {
+    input.validar()?;
+    let oid = ObjectId::parse_str(&id)?;
+
+    let mut fornecedor = repos
+        .fornecedores
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    versao.conferir(&fornecedor)?;
+    let antes = fornecedor.clone();
+
+    if let Some(nome) = input.nome {
+        fornecedor.nome = nome;
+    }
+    if let Some(cpf_cnpj) = input.cpf_cnpj {
+        fornecedor.cpf_cnpj = cpf_cnpj;
+    }
+    if let Some(contato) = input.contato {
+        fornecedor.contato = Some(contato);
+    }
+    if let Some(telefone) = input.telefone {
+        fornecedor.telefone = Some(telefone);
+    }
+    if let Some(email) = input.email {
+        fornecedor.email = Some(email);
+    }
+    if let Some(endereco) = input.endereco {
+        fornecedor.endereco = Some(endereco);
+    }
+    if let Some(cidade) = input.cidade {
+        fornecedor.cidade = Some(cidade);
+    }
+    if let Some(estado) = input.estado {
+        fornecedor.estado = Some(estado);
+    }
+    if let Some(cep) = input.cep {
+        fornecedor.cep = Some(cep);
+    }
+    if let Some(ativo) = input.ativo {
+        fornecedor.ativo = ativo;
+    }
+    fornecedor.updated_at = Utc::now();
+
+    if !repos.fornecedores.salvar_versao(&mut fornecedor).await? {
+        return Err(concorrencia::desatualizado());
+    }
+    auditor.alteracao(&antes, &fornecedor).await?;
+
+    Ok(ComVersao(fornecedor.versao, fornecedor.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual, VersaoEsperada),
+    responses(
+        (status = 200, body = String),
+        (
+            status = 409,
+            description = "O registro mudou desde a versão do If-Match",
+            body = CorpoErro
+        )
+    )
+)]
+async fn delete_fornecedor(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    versao: VersaoEsperada,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if let Some(fornecedor) = repos.fornecedores.buscar(empresa.id(), oid).await? {
+        versao.conferir(&fornecedor)?;
+        if !repos
+            .fornecedores
+            .excluir_versao(empresa.id(), oid, fornecedor.versao)
+            .await?
+        {
+            return Err(concorrencia::desatualizado());
+        }
+        auditor.exclusao(&fornecedor).await?;
+    }
+    Ok(Json("Fornecedor excluído".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    #[tokio::test]
+    async fn cadastra_altera_e_exclui_fornecedor() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let rotas = || routes(ambiente.repos.clone());
+
+        let (status, erro) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Papelaria", "cpf_cnpj": "11.222.333/0001-82" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(erro["campos"][0]["campo"], "cpf_cnpj");
+
+        let (status, fornecedor) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({ "nome": "Papelaria", "cpf_cnpj": "11.222.333/0001-81" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{fornecedor}");
+        let uri = format!("/{}", fornecedor["id"].as_str().unwrap());
+
+        let (status, _) = ambiente
+            .enviar(rotas(), Method::PUT, &uri, Some(json!({ "nome": " " })))
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, alterado) = ambiente
+            .enviar(rotas(), Method::PUT, &uri, Some(json!({ "ativo": false })))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{alterado}");
+        assert_eq!(alterado["ativo"], false);
+
+        let (status, _) = ambiente.enviar(rotas(), Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = ambiente.enviar(rotas(), Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(rotas(), Method::PUT, &uri, Some(json!({ "ativo": true })))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,524 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{Months, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina},
+    contabilidade,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+    routes::clientes::parse_cliente_id,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct LancamentoResponse {
+    pub id: Option<String>,
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    pub moeda: Moeda,
+    pub vencimento: NaiveDate,
+    pub cliente_id: Option<String>,
+    pub contraparte: Option<String>,
+    pub grupo_id: String,
+    pub parcela: u32,
+    pub total_parcelas: u32,
+    pub status: StatusLancamento,
+    pub conta_id: Option<String>,
+    pub data_pagamento: Option<NaiveDate>,
+    pub valor_pago: Option<Dinheiro>,
+    pub recorrencia_id: Option<String>,
+    pub ocorrencia: Option<u32>,
+}
+
+impl From<Lancamento> for LancamentoResponse {
+    fn from(lancamento: Lancamento) -> Self {
+        Self {
+            id: lancamento.id.map(|id| id.to_hex()),
+            tipo: lancamento.tipo,
+            descricao: lancamento.descricao,
+            categoria: lancamento.categoria,
+            valor: lancamento.valor,
+            moeda: lancamento.moeda,
+            vencimento: lancamento.vencimento,
+            cliente_id: lancamento.cliente_id.map(|id| id.to_hex()),
+            contraparte: lancamento.contraparte,
+            grupo_id: lancamento.grupo_id.to_hex(),
+            parcela: lancamento.parcela,
+            total_parcelas: lancamento.total_parcelas,
+            status: lancamento.status,
+            conta_id: lancamento.conta_id.map(|id| id.to_hex()),
+            data_pagamento: lancamento.data_pagamento,
+            valor_pago: lancamento.valor_pago,
+            recorrencia_id: lancamento.recorrencia_id.map(|id| id.to_hex()),
+            ocorrencia: lancamento.ocorrencia,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_lancamentos).post(create_lancamento))
+        .route(
+            "/:id",
+            get(get_lancamento)
+                .put(update_lancamento)
+                .delete(delete_lancamento),
+        )
+        .route("/:id/liquidar", post(liquidar_lancamento))
+        .route("/:id/estornar", post(estornar_lancamento))
+        .route("/:id/cancelar", post(cancelar_lancamento))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_lancamentos,
+    get_lancamento,
+    create_lancamento,
+    update_lancamento,
+    delete_lancamento,
+    liquidar_lancamento,
+    estornar_lancamento,
+    cancelar_lancamento
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Lancamento>),
+    responses((status = 200, body = Pagina<LancamentoResponse>))
+)]
+async fn list_lancamentos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Lancamento>,
+) -> Result<Json<Pagina<LancamentoResponse>>> {
+    let lancamentos = repos.lancamentos.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(lancamentos.map(LancamentoResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = LancamentoResponse))
+)]
+async fn get_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<LancamentoResponse>> {
+    Ok(Json(find_lancamento(&repos, empresa, &id).await?.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = Vec<LancamentoResponse>))
+)]
+async fn create_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateLancamento>,
+) -> Result<Json<Vec<LancamentoResponse>>> {
+    input.validar()?;
+    let total_parcelas = input.parcelas.unwrap_or(1);
+
+    let cliente_id = parse_cliente_id(&repos, empresa, input.cliente_id.as_deref()).await?;
+    let grupo_id = ObjectId::new();
+    let now = Utc::now();
+
+    let lancamentos: Vec<Lancamento> = input
+        .valor
+        .dividir(total_parcelas)
+        .into_iter()
+        .enumerate()
+        .map(|(indice, valor)| {
+            let parcela = indice as u32 + 1;
+            let descricao = if total_parcelas > 1 {
+                format!("{} ({}/{})", input.descricao, parcela, total_parcelas)
+            } else {
+                input.descricao.clone()
+            };
+
+            Lancamento {
+                id: None,
+                empresa_id: empresa.id(),
+                tipo: input.tipo,
+                descricao,
+                categoria: input.categoria.clone(),
+                valor,
+                moeda: input.moeda,
+                vencimento: input
+                    .vencimento
+                    .checked_add_months(Months::new(indice as u32))
+                    .unwrap_or(input.vencimento),
+                cliente_id,
+                contraparte: input.contraparte.clone(),
+                grupo_id,
+                parcela,
+                total_parcelas,
+                status: StatusLancamento::Aberto,
+                conta_id: None,
+                data_pagamento: None,
+                valor_pago: None,
+                recorrencia_id: None,
+                ocorrencia: None,
+                created_at: now,
+                updated_at: now,
+            }
+        })
+        .collect();
+
+    let criados = repos.lancamentos.criar_varios(lancamentos).await?;
+    for criado in &criados {
+        auditor.criacao(criado).await?;
+    }
+
+    Ok(Json(
+        criados.into_iter().map(LancamentoResponse::from).collect(),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = LancamentoResponse))
+)]
+async fn update_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateLancamento>,
+) -> Result<Json<LancamentoResponse>> {
+    let mut lancamento = find_lancamento(&repos, empresa, &id).await?;
+    exigir_status(&lancamento, StatusLancamento::Aberto)?;
+    let antes = lancamento.clone();
+
+    if let Some(descricao) = input.descricao {
+        lancamento.descricao = descricao;
+    }
+    if let Some(categoria) = input.categoria {
+        lancamento.categoria = categoria;
+    }
+    if let Some(valor) = input.valor {
+        if !valor.positivo() {
+            return Err(AppError::BadRequest(
+                "O valor do lançamento deve ser maior que zero".to_string(),
+            ));
+        }
+        lancamento.valor = valor;
+    }
+    if let Some(vencimento) = input.vencimento {
+        lancamento.vencimento = vencimento;
+    }
+    if input.cliente_id.is_some() {
+        lancamento.cliente_id =
+            parse_cliente_id(&repos, empresa, input.cliente_id.as_deref()).await?;
+    }
+    if let Some(contraparte) = input.contraparte {
+        lancamento.contraparte = Some(contraparte);
+    }
+
+    let atualizado = atualizar(&repos, lancamento, StatusLancamento::Aberto).await?;
+    auditor.alteracao(&antes, &atualizado).await?;
+    Ok(Json(atualizado.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn delete_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let lancamento = find_lancamento(&repos, empresa, &id).await?;
+    if lancamento.status == StatusLancamento::Pago {
+        return Err(AppError::Conflict(
+            "Estorne a liquidação antes de excluir o lançamento".to_string(),
+        ));
+    }
+
+    let oid = lancamento.id.ok_or(AppError::NotFound)?;
+    if !repos
+        .lancamentos
+        .excluir_nao_pago(empresa.id(), oid)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "O lançamento foi alterado por outra operação".to_string(),
+        ));
+    }
+    auditor.exclusao(&lancamento).await?;
+    Ok(Json("Lançamento excluído".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/liquidar",
+    params(EmpresaAtual),
+    responses((status = 200, body = LancamentoResponse))
+)]
+async fn liquidar_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<LiquidarLancamento>,
+) -> Result<Json<LancamentoResponse>> {
+    let lancamento = find_lancamento(&repos, empresa, &id).await?;
+    let conta_id = match input.conta_id {
+        Some(conta_id) => ObjectId::parse_str(&conta_id)?,
+        None => conta_da_recorrencia(&repos, &lancamento)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Informe a conta bancária".to_string()))?,
+    };
+    let data_pagamento = input
+        .data_pagamento
+        .unwrap_or_else(|| Utc::now().date_naive());
+    let valor_pago = input.valor_pago.unwrap_or(lancamento.valor);
+
+    let liquidado = liquidar(&repos, &lancamento, conta_id, data_pagamento, valor_pago).await?;
+    auditor.alteracao(&lancamento, &liquidado).await?;
+    Ok(Json(liquidado.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/estornar",
+    params(EmpresaAtual),
+    responses((status = 200, body = LancamentoResponse))
+)]
+async fn estornar_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<LancamentoResponse>> {
+    let mut lancamento = find_lancamento(&repos, empresa, &id).await?;
+    exigir_status(&lancamento, StatusLancamento::Pago)?;
+    let antes = lancamento.clone();
+
+    lancamento.status = StatusLancamento::Aberto;
+    lancamento.conta_id = None;
+    lancamento.data_pagamento = None;
+    lancamento.valor_pago = None;
+    let atualizado = atualizar(&repos, lancamento, StatusLancamento::Pago).await?;
+    auditor.alteracao(&antes, &atualizado).await?;
+
+    // A transação bancária que liquidou o lançamento volta a ficar pendente.
+    if let Some(lancamento_id) = atualizado.id {
+        contabilidade::estornar(
+            &repos,
+            empresa.id(),
+            OrigemPartida::Lancamento,
+            lancamento_id,
+        )
+        .await?;
+        repos
+            .transacoes
+            .desfazer_conciliacao(empresa.id(), lancamento_id)
+            .await?;
+    }
+
+    Ok(Json(atualizado.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/cancelar",
+    params(EmpresaAtual),
+    responses((status = 200, body = LancamentoResponse))
+)]
+async fn cancelar_lancamento(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<LancamentoResponse>> {
+    let mut lancamento = find_lancamento(&repos, empresa, &id).await?;
+    exigir_status(&lancamento, StatusLancamento::Aberto)?;
+    let antes = lancamento.clone();
+
+    lancamento.status = StatusLancamento::Cancelado;
+    let atualizado = atualizar(&repos, lancamento, StatusLancamento::Aberto).await?;
+    auditor.alteracao(&antes, &atualizado).await?;
+    Ok(Json(atualizado.into()))
+}
+
+/// Liquida um lançamento em aberto contra a conta bancária informada, que
+/// deve pertencer à mesma empresa do lançamento e estar na mesma moeda.
+pub(crate) async fn liquidar(
+    repos: &Repositorios,
+    lancamento: &Lancamento,
+    conta_id: ObjectId,
+    data_pagamento: NaiveDate,
+    valor_pago: Dinheiro,
+) -> Result<Lancamento> {
+    exigir_status(lancamento, StatusLancamento::Aberto)?;
+
+    let conta = repos
+        .contas
+        .buscar(lancamento.empresa_id, conta_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if conta.moeda != lancamento.moeda {
+        return Err(AppError::BadRequest(format!(
+            "O lançamento é em {} e a conta bancária em {}",
+            lancamento.moeda, conta.moeda
+        )));
+    }
+
+    if !valor_pago.positivo() {
+        return Err(AppError::BadRequest(
+            "O valor pago deve ser maior que zero".to_string(),
+        ));
+    }
+
+    let mut liquidado = lancamento.clone();
+    liquidado.status = StatusLancamento::Pago;
+    liquidado.conta_id = Some(conta_id);
+    liquidado.data_pagamento = Some(data_pagamento);
+    liquidado.valor_pago = Some(valor_pago);
+    let liquidado = atualizar(repos, liquidado, StatusLancamento::Aberto).await?;
+    contabilidade::registrar_liquidacao(repos, &liquidado).await?;
+    Ok(liquidado)
+}
+
+/// Conta bancária da recorrência que gerou o lançamento, se houver.
+async fn conta_da_recorrencia(
+    repos: &Repositorios,
+    lancamento: &Lancamento,
+) -> Result<Option<ObjectId>> {
+    let Some(recorrencia_id) = lancamento.recorrencia_id else {
+        return Ok(None);
+    };
+    Ok(repos
+        .recorrencias
+        .buscar(lancamento.empresa_id, recorrencia_id)
+        .await?
+        .and_then(|recorrencia| recorrencia.conta_id))
+}
+
+pub(crate) async fn find_lancamento(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: &str,
+) -> Result<Lancamento> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .lancamentos
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+fn exigir_status(lancamento: &Lancamento, esperado: StatusLancamento) -> Result<()> {
+    if lancamento.status != esperado {
+        return Err(AppError::Conflict(format!(
+            "O lançamento está {} e a operação exige {}",
+            lancamento.status.as_str(),
+            esperado.as_str()
+        )));
+    }
+    Ok(())
+}
+
+/// Grava o lançamento somente se o status gravado ainda for `status`.
+pub(crate) async fn atualizar(
+    repos: &Repositorios,
+    mut lancamento: Lancamento,
+    status: StatusLancamento,
+) -> Result<Lancamento> {
+    lancamento.updated_at = Utc::now();
+    if !repos
+        .lancamentos
+        .salvar_no_status(&lancamento, status)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "O lançamento foi alterado por outra operação".to_string(),
+        ));
+    }
+    Ok(lancamento)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    async fn criar(ambiente: &Ambiente, parcelas: u32) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "tipo": "PAGAR",
+                    "descricao": "Aluguel",
+                    "categoria": "Despesas",
+                    "valor": "100.00",
+                    "vencimento": "2026-01-31",
+                    "parcelas": parcelas,
+                })),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn divide_o_valor_em_parcelas_mensais() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (status, parcelas) = criar(&ambiente, 3).await;
+        assert_eq!(status, StatusCode::OK, "{parcelas}");
+
+        let parcelas = parcelas.as_array().unwrap();
+        let valores: Vec<&str> = parcelas
+            .iter()
+            .map(|p| p["valor"].as_str().unwrap())
+            .collect();
+        let vencimentos: Vec<&str> = parcelas
+            .iter()
+            .map(|p| p["vencimento"].as_str().unwrap())
+            .collect();
+        assert_eq!(valores, ["33.34", "33.33", "33.33"]);
+        assert_eq!(vencimentos, ["2026-01-31", "2026-02-28", "2026-03-31"]);
+    }
+
+    #[tokio::test]
+    async fn limita_o_numero_de_parcelas() {
+        let ambiente = Ambiente::novo(&[]).await;
+        assert_eq!(criar(&ambiente, MAXIMO_PARCELAS).await.0, StatusCode::OK);
+
+        for parcelas in [0, MAXIMO_PARCELAS + 1, u32::MAX] {
+            let (status, erro) = criar(&ambiente, parcelas).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(erro["campos"][0]["campo"], "parcelas");
+        }
+    }
+}
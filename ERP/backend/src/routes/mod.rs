@@ -1,3 +1,26 @@
+pub mod auditoria;
+pub mod auth;
 pub mod bancos;
+pub mod boletos;
+pub mod clientes;
+pub mod compras;
+pub mod conciliacao;
+pub mod contabilidade;
+pub mod cotacoes;
+pub mod dashboard;
+pub mod empresas;
+pub mod estoque;
+pub mod faturas;
 pub mod financeiro;
-pub mod frontend;
+pub mod fornecedores;
+pub mod lancamentos;
+pub mod notas_fiscais;
+pub mod pix;
+pub mod produtos;
+pub mod recorrencias;
+pub mod relatorios;
+pub mod usuarios;
+pub mod vendas;
+
+#[cfg(test)]
+mod testes;
@@ -0,0 +1,440 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    nfe::{self, Certificado, Emissao, Sefaz},
+    openapi::Arquivo,
+    repositorio::Repositorios,
+    routes::vendas::produtos_da_venda,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct NotaFiscalResponse {
+    pub id: Option<String>,
+    pub venda_id: String,
+    pub modelo: ModeloNota,
+    pub serie: u32,
+    pub numero: u32,
+    pub chave: String,
+    pub ambiente: Ambiente,
+    pub valor: Dinheiro,
+    pub status: StatusNota,
+    pub protocolo: String,
+    pub emitida_em: DateTime<Utc>,
+    pub autorizada_em: DateTime<Utc>,
+}
+
+impl From<NotaFiscal> for NotaFiscalResponse {
+    fn from(nota: NotaFiscal) -> Self {
+        Self {
+            id: nota.id.map(|id| id.to_hex()),
+            venda_id: nota.venda_id.to_hex(),
+            modelo: nota.modelo,
+            serie: nota.serie,
+            numero: nota.numero,
+            chave: nota.chave,
+            ambiente: nota.ambiente,
+            valor: nota.valor,
+            status: nota.status,
+            protocolo: nota.protocolo,
+            emitida_em: nota.emitida_em,
+            autorizada_em: nota.autorizada_em,
+        }
+    }
+}
+
+/// Rotas da nota fiscal da venda, aninhadas em `/vendas`.
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/:id/nfe", get(get_nota).post(emitir_nota))
+        .route("/:id/nfe/xml", get(get_xml))
+        .route("/:id/nfe/danfe", get(get_danfe))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_nota, emitir_nota, get_xml, get_danfe))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/{id}/nfe",
+    params(EmpresaAtual),
+    responses((status = 200, body = NotaFiscalResponse))
+)]
+async fn get_nota(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<NotaFiscalResponse>> {
+    let nota = find_nota(&repos, empresa, &id).await?;
+    Ok(Json(nota.into()))
+}
+
+/// Monta, assina e envia a nota da venda finalizada. A nota é gravada em
+/// processamento antes do envio e, autorizada, recebe o XML de distribuição
+/// e o DANFE; se a SEFAZ não autorizar, a reserva é desfeita.
+#[utoipa::path(
+    post,
+    path = "/{id}/nfe",
+    request_body = Option<EmitirNota>,
+    params(EmpresaAtual),
+    responses(
+        (status = 200, body = NotaFiscalResponse),
+        (status = 409, description = "A venda não está finalizada ou já tem nota")
+    )
+)]
+async fn emitir_nota(
+    State(repos): State<Repositorios>,
+    Extension(sefaz): Extension<Arc<dyn Sefaz>>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    input: Option<Json<EmitirNota>>,
+) -> Result<Json<NotaFiscalResponse>> {
+    let modelo = input.map(|Json(input)| input.modelo).unwrap_or_default();
+    let venda_id = ObjectId::parse_str(&id)?;
+    let venda = repos
+        .vendas
+        .buscar(empresa.id(), venda_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if venda.status != StatusVenda::Finalizada {
+        return Err(AppError::Conflict(
+            "Só vendas finalizadas podem ter nota fiscal".to_string(),
+        ));
+    }
+    if let Some(nota) = repos.notas_fiscais.da_venda(empresa.id(), venda_id).await? {
+        return Err(nota_existente(&nota));
+    }
+
+    let dados = repos
+        .empresas
+        .buscar(empresa.id())
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let cliente = match venda.cliente_id {
+        Some(cliente_id) => repos.clientes.buscar(empresa.id(), cliente_id).await?,
+        None => None,
+    };
+    let produtos = produtos_da_venda(&repos, &venda).await?;
+    let venda = nfe::tributada(&dados, cliente.as_ref(), venda, &produtos)?;
+
+    let serie = dados
+        .fiscal
+        .as_ref()
+        .map(|fiscal| fiscal.serie)
+        .unwrap_or_default();
+    let numero = repos
+        .notas_fiscais
+        .proximo_numero_da_serie(empresa.id(), modelo, serie)
+        .await?;
+    let nota_id = ObjectId::new();
+    let emitida_em = Utc::now().with_timezone(&nfe::fuso());
+    let emissao = Emissao::new(
+        &dados,
+        modelo,
+        numero,
+        nota_id,
+        emitida_em,
+        &venda,
+        cliente.as_ref(),
+        &produtos,
+    )?;
+
+    let certificado = Certificado::carregar(
+        &emissao.fiscal.certificado,
+        &emissao.fiscal.senha_certificado,
+    )?;
+    let chave = emissao.chave();
+    let assinada = certificado.assinar(&emissao.xml())?;
+
+    // Reserva o número e a venda antes de enviar: uma emissão simultânea da
+    // mesma venda esbarra no índice único e não chega à SEFAZ.
+    let mut nota = repos
+        .notas_fiscais
+        .criar(NotaFiscal {
+            id: Some(nota_id),
+            empresa_id: empresa.id(),
+            venda_id,
+            modelo,
+            serie,
+            numero,
+            chave: chave.clone(),
+            ambiente: emissao.ambiente(),
+            valor: venda.total_final,
+            status: StatusNota::EmProcessamento,
+            protocolo: String::new(),
+            emitida_em: emitida_em.with_timezone(&Utc),
+            autorizada_em: emitida_em.with_timezone(&Utc),
+            xml: String::new(),
+            danfe: Vec::new(),
+        })
+        .await
+        .map_err(|erro| {
+            if erro.duplicado() {
+                AppError::Conflict("A venda já tem nota fiscal".to_string())
+            } else {
+                erro
+            }
+        })?;
+
+    let retorno = match sefaz.autorizar(emissao.ambiente(), &chave, &assinada).await {
+        Ok(retorno) if retorno.autorizada() && retorno.protocolo.is_some() => retorno,
+        resultado => {
+            // Sem autorização a reserva é desfeita para que a venda possa
+            // ser emitida de novo; o número fica para inutilização.
+            repos.notas_fiscais.excluir(empresa.id(), nota_id).await?;
+            let retorno = resultado?;
+            return Err(AppError::BadRequest(format!(
+                "Nota rejeitada pela SEFAZ ({}): {}",
+                retorno.status, retorno.motivo
+            )));
+        }
+    };
+
+    nota.danfe = nfe::danfe(&emissao, &chave, &retorno)?;
+    nota.xml = nfe::nota_processada(&assinada, &chave, emissao.ambiente(), &retorno);
+    nota.protocolo = retorno.protocolo.clone().unwrap_or_default();
+    nota.autorizada_em = retorno.recebida_em.with_timezone(&Utc);
+    nota.status = StatusNota::Autorizada;
+    repos.notas_fiscais.salvar(&nota).await?;
+    auditor.criacao(&nota).await?;
+
+    Ok(Json(nota.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/nfe/xml",
+    params(EmpresaAtual),
+    responses((status = 200, body = Arquivo, content_type = "application/xml"))
+)]
+async fn get_xml(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    let nota = autorizada(find_nota(&repos, empresa, &id).await?)?;
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "application/xml; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-procNFe.xml\"", nota.chave),
+            ),
+        ],
+        nota.xml,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/nfe/danfe",
+    params(EmpresaAtual),
+    responses((status = 200, body = Arquivo, content_type = "application/pdf"))
+)]
+async fn get_danfe(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    let nota = autorizada(find_nota(&repos, empresa, &id).await?)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"danfe-{}.pdf\"", nota.chave),
+            ),
+        ],
+        nota.danfe,
+    )
+        .into_response())
+}
+
+async fn find_nota(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<NotaFiscal> {
+    let venda_id = ObjectId::parse_str(id)?;
+    repos
+        .notas_fiscais
+        .da_venda(empresa.id(), venda_id)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// Só a nota autorizada tem XML e DANFE.
+fn autorizada(nota: NotaFiscal) -> Result<NotaFiscal> {
+    match nota.status {
+        StatusNota::Autorizada => Ok(nota),
+        StatusNota::EmProcessamento => Err(nota_existente(&nota)),
+    }
+}
+
+fn nota_existente(nota: &NotaFiscal) -> AppError {
+    AppError::Conflict(match nota.status {
+        StatusNota::Autorizada => "A venda já tem nota fiscal autorizada".to_string(),
+        StatusNota::EmProcessamento => "A nota fiscal da venda está em processamento".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{nfe::SefazLocal, routes::testes::Ambiente};
+
+    /// Venda finalizada de um produto qualquer; devolve o id.
+    async fn venda_finalizada(ambiente: &Ambiente) -> ObjectId {
+        let (_, produto) = ambiente
+            .enviar(
+                crate::routes::produtos::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": "Caneta",
+                    "preco_custo": "4.00",
+                    "preco_venda": "10.00",
+                    "estoque_minimo": 0,
+                    "unidade": "UN",
+                })),
+            )
+            .await;
+        let (status, venda) = ambiente
+            .enviar(
+                crate::routes::vendas::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "itens": [{ "produto_id": produto["id"], "quantidade": 1 }],
+                    "forma_pagamento": "dinheiro",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{venda}");
+        let id = ObjectId::parse_str(venda["id"].as_str().unwrap()).unwrap();
+        assert!(ambiente
+            .repos
+            .vendas
+            .transicionar(
+                ambiente.empresa_id,
+                id,
+                StatusVenda::Aberta,
+                StatusVenda::Finalizada
+            )
+            .await
+            .unwrap());
+        id
+    }
+
+    fn reserva(ambiente: &Ambiente, venda_id: ObjectId, numero: u32) -> NotaFiscal {
+        NotaFiscal {
+            id: None,
+            empresa_id: ambiente.empresa_id,
+            venda_id,
+            modelo: ModeloNota::Nfe,
+            serie: 1,
+            numero,
+            chave: format!("{:044}", numero),
+            ambiente: crate::models::Ambiente::Homologacao,
+            valor: Dinheiro::ZERO,
+            status: StatusNota::EmProcessamento,
+            protocolo: String::new(),
+            emitida_em: Utc::now(),
+            autorizada_em: Utc::now(),
+            xml: String::new(),
+            danfe: Vec::new(),
+        }
+    }
+
+    fn rotas(ambiente: &Ambiente) -> Router {
+        let sefaz: Arc<dyn Sefaz> = Arc::new(SefazLocal::default());
+        routes(ambiente.repos.clone()).layer(Extension(sefaz))
+    }
+
+    #[tokio::test]
+    async fn nota_em_processamento_reserva_a_venda() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let venda_id = venda_finalizada(&ambiente).await;
+        ambiente
+            .repos
+            .notas_fiscais
+            .criar(reserva(&ambiente, venda_id, 1))
+            .await
+            .unwrap();
+
+        let erro = ambiente
+            .repos
+            .notas_fiscais
+            .criar(reserva(&ambiente, venda_id, 2))
+            .await
+            .unwrap_err();
+        assert!(erro.duplicado());
+
+        let uri = format!("/{}/nfe", venda_id);
+        let (status, erro) = ambiente
+            .enviar(rotas(&ambiente), Method::POST, &uri, None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(
+            erro["error"],
+            "A nota fiscal da venda está em processamento"
+        );
+
+        let (status, nota) = ambiente
+            .enviar(rotas(&ambiente), Method::GET, &uri, None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(nota["status"], "EM_PROCESSAMENTO");
+        let (status, _) = ambiente
+            .enviar(rotas(&ambiente), Method::GET, &format!("{uri}/xml"), None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn numera_as_notas_da_serie_em_sequencia() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let venda_id = venda_finalizada(&ambiente).await;
+        ambiente
+            .repos
+            .notas_fiscais
+            .criar(reserva(&ambiente, venda_id, 41))
+            .await
+            .unwrap();
+
+        let notas = &ambiente.repos.notas_fiscais;
+        let (a, b) = tokio::join!(
+            notas.proximo_numero_da_serie(ambiente.empresa_id, ModeloNota::Nfe, 1),
+            notas.proximo_numero_da_serie(ambiente.empresa_id, ModeloNota::Nfe, 1),
+        );
+        let mut numeros = [a.unwrap(), b.unwrap()];
+        numeros.sort();
+        assert_eq!(numeros, [42, 43]);
+        let outra_serie = notas
+            .proximo_numero_da_serie(ambiente.empresa_id, ModeloNota::Nfe, 2)
+            .await
+            .unwrap();
+        assert_eq!(outra_serie, 1);
+    }
+}
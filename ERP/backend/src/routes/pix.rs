@@ -0,0 +1,285 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    openapi::Arquivo,
+    pix::{self, BrCode},
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct CobrancaPixResponse {
+    pub id: Option<String>,
+    pub conta_id: String,
+    pub origem: OrigemCobranca,
+    pub origem_id: String,
+    pub txid: String,
+    pub valor: Dinheiro,
+    pub location: Option<String>,
+    pub payload: String,
+    pub status: StatusCobranca,
+    pub transacao_id: Option<String>,
+    pub data_pagamento: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<CobrancaPix> for CobrancaPixResponse {
+    fn from(cobranca: CobrancaPix) -> Self {
+        Self {
+            id: cobranca.id.map(|id| id.to_hex()),
+            conta_id: cobranca.conta_id.to_hex(),
+            origem: cobranca.origem,
+            origem_id: cobranca.origem_id.to_hex(),
+            txid: cobranca.txid,
+            valor: cobranca.valor,
+            location: cobranca.location,
+            payload: cobranca.payload,
+            status: cobranca.status,
+            transacao_id: cobranca.transacao_id.map(|id| id.to_hex()),
+            data_pagamento: cobranca.data_pagamento,
+            created_at: cobranca.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum FormatoImagem {
+    #[default]
+    Png,
+    Svg,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct QrCodeQuery {
+    #[serde(default)]
+    #[param(inline)]
+    formato: FormatoImagem,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/cobrancas", get(list_cobrancas).post(create_cobranca))
+        .route("/cobrancas/:id", get(get_cobranca))
+        .route("/cobrancas/:id/qrcode", get(get_qrcode))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_cobrancas, get_cobranca, create_cobranca, get_qrcode))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/cobrancas",
+    params(EmpresaAtual, Consulta<CobrancaPix>),
+    responses((status = 200, body = Pagina<CobrancaPixResponse>))
+)]
+async fn list_cobrancas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<CobrancaPix>,
+) -> Result<Json<Pagina<CobrancaPixResponse>>> {
+    let cobrancas = repos.cobrancas_pix.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(cobrancas.map(CobrancaPixResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cobrancas/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = CobrancaPixResponse))
+)]
+async fn get_cobranca(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<CobrancaPixResponse>> {
+    let cobranca = find_cobranca(&repos, empresa, &id).await?;
+    Ok(Json(cobranca.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/cobrancas",
+    params(EmpresaAtual),
+    responses((status = 200, body = CobrancaPixResponse))
+)]
+async fn create_cobranca(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateCobrancaPix>,
+) -> Result<Json<CobrancaPixResponse>> {
+    input.validar()?;
+
+    let conta_id = ObjectId::parse_str(&input.conta_id)?;
+    let conta = repos
+        .contas
+        .buscar(empresa.id(), conta_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let chave = conta.pix.ok_or_else(|| {
+        AppError::BadRequest("A conta bancária não tem chave PIX cadastrada".to_string())
+    })?;
+
+    let origem_id = ObjectId::parse_str(&input.origem_id)?;
+    let valor_devido = match input.origem {
+        OrigemCobranca::Venda => {
+            let venda = repos
+                .vendas
+                .buscar(empresa.id(), origem_id)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            if venda.status == StatusVenda::Cancelada {
+                return Err(AppError::Conflict("A venda está cancelada".to_string()));
+            }
+            venda.total_final
+        }
+        OrigemCobranca::Lancamento => {
+            let lancamento = repos
+                .lancamentos
+                .buscar(empresa.id(), origem_id)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            if lancamento.tipo != TipoLancamento::Receber
+                || lancamento.status != StatusLancamento::Aberto
+            {
+                return Err(AppError::Conflict(
+                    "Só contas a receber em aberto podem ser cobradas por PIX".to_string(),
+                ));
+            }
+            lancamento.valor
+        }
+    };
+    let valor = input.valor.unwrap_or(valor_devido);
+    if !valor.positivo() {
+        return Err(AppError::BadRequest(
+            "O valor da cobrança deve ser maior que zero".to_string(),
+        ));
+    }
+
+    // O txid gerado é o próprio id da cobrança: 24 caracteres hexadecimais.
+    let id = ObjectId::new();
+    let txid = input.txid.unwrap_or_else(|| id.to_hex());
+    if repos
+        .cobrancas_pix
+        .buscar_por_txid(empresa.id(), &txid)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Conflict(format!(
+            "Já existe uma cobrança com o txid {}",
+            txid
+        )));
+    }
+    let location = input.location.map(|location| {
+        let location = location.trim();
+        location
+            .strip_prefix("https://")
+            .unwrap_or(location)
+            .to_string()
+    });
+
+    let nome = repos
+        .empresas
+        .buscar(empresa.id())
+        .await?
+        .map(|empresa| empresa.nome)
+        .unwrap_or_default();
+    let payload = BrCode {
+        chave: &chave.chave,
+        nome: &nome,
+        cidade: &chave.cidade,
+        valor,
+        txid: Some(&txid),
+        location: location.as_deref(),
+    }
+    .payload();
+
+    let cobranca = repos
+        .cobrancas_pix
+        .criar(CobrancaPix {
+            id: Some(id),
+            empresa_id: empresa.id(),
+            conta_id,
+            origem: input.origem,
+            origem_id,
+            txid,
+            valor,
+            location,
+            payload,
+            status: StatusCobranca::Pendente,
+            transacao_id: None,
+            data_pagamento: None,
+            created_at: Utc::now(),
+        })
+        .await?;
+    auditor.criacao(&cobranca).await?;
+
+    Ok(Json(cobranca.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/cobrancas/{id}/qrcode",
+    params(EmpresaAtual, QrCodeQuery),
+    responses(
+        (
+            status = 200,
+            content(
+                (Arquivo = "image/png"),
+                (Arquivo = "image/svg+xml")
+            )
+        )
+    )
+)]
+async fn get_qrcode(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+    Query(query): Query<QrCodeQuery>,
+) -> Result<Response> {
+    let cobranca = find_cobranca(&repos, empresa, &id).await?;
+    Ok(match query.formato {
+        FormatoImagem::Png => (
+            [(header::CONTENT_TYPE, "image/png")],
+            pix::qrcode_png(&cobranca.payload)?,
+        )
+            .into_response(),
+        FormatoImagem::Svg => (
+            [(header::CONTENT_TYPE, "image/svg+xml")],
+            pix::qrcode_svg(&cobranca.payload)?,
+        )
+            .into_response(),
+    })
+}
+
+async fn find_cobranca(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: &str,
+) -> Result<CobrancaPix> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .cobrancas_pix
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
@@ -0,0 +1,313 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use erp_api::ProdutoResponse;
+use mongodb::bson::oid::ObjectId;
+use utoipa::OpenApi;
+
+use crate::{
+    auditoria::Auditor,
+    consulta::{Consulta, Pagina},
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    estoque::{self, NovaMovimentacao},
+    models::*,
+    repositorio::Repositorios,
+    validacao::Validar,
+};
+
+impl From<Produto> for ProdutoResponse {
+    fn from(produto: Produto) -> Self {
+        Self {
+            id: produto.id.map(|id| id.to_hex()),
+            nome: produto.nome,
+            descricao: produto.descricao,
+            codigo_barras: produto.codigo_barras,
+            preco_custo: produto.preco_custo,
+            preco_venda: produto.preco_venda,
+            estoque_atual: produto.estoque_atual,
+            estoque_minimo: produto.estoque_minimo,
+            unidade: produto.unidade,
+            fiscal: produto.fiscal,
+            ativo: produto.ativo,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_produtos).post(create_produto))
+        .route(
+            "/:id",
+            get(get_produto).put(update_produto).delete(delete_produto),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_produtos,
+    get_produto,
+    create_produto,
+    update_produto,
+    delete_produto
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Produto>),
+    responses((status = 200, body = Pagina<ProdutoResponse>))
+)]
+async fn list_produtos(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Produto>,
+) -> Result<Json<Pagina<ProdutoResponse>>> {
+    let produtos = repos.produtos.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(produtos.map(ProdutoResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = ProdutoResponse))
+)]
+async fn get_produto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<ProdutoResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let produto = repos
+        .produtos
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(produto.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = ProdutoResponse))
+)]
+async fn create_produto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateProduto>,
+) -> Result<Json<ProdutoResponse>> {
+    input.validar()?;
+
+    let now = Utc::now();
+    let produto = Produto {
+        id: None,
+        empresa_id: empresa.id(),
+        nome: input.nome,
+        descricao: input.descricao,
+        codigo_barras: input.codigo_barras,
+        preco_custo: input.preco_custo,
+        preco_venda: input.preco_venda,
+        estoque_atual: 0,
+        estoque_minimo: input.estoque_minimo,
+        unidade: input.unidade,
+        fiscal: input.fiscal.as_ref().map(DadosFiscais::normalizados),
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut created = repos.produtos.criar(produto).await?;
+
+    if input.estoque_atual > 0 {
+        estoque::movimentar(
+            &repos,
+            NovaMovimentacao {
+                empresa_id: empresa.id(),
+                produto_id: created.id.expect("criar retorna o id gerado"),
+                tipo: TipoMovimentacao::Entrada,
+                quantidade: input.estoque_atual,
+                motivo: "Estoque inicial".to_string(),
+                usuario: None,
+                venda_id: None,
+                pedido_compra_id: None,
+            },
+        )
+        .await?;
+        created.estoque_atual = input.estoque_atual;
+    }
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = ProdutoResponse))
+)]
+async fn update_produto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateProduto>,
+) -> Result<Json<ProdutoResponse>> {
+    input.validar()?;
+    let oid = ObjectId::parse_str(&id)?;
+
+    let mut produto = repos
+        .produtos
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let antes = produto.clone();
+
+    if let Some(nome) = input.nome {
+        produto.nome = nome;
+    }
+    if let Some(descricao) = input.descricao {
+        produto.descricao = Some(descricao);
+    }
+    if let Some(codigo_barras) = input.codigo_barras {
+        produto.codigo_barras = Some(codigo_barras);
+    }
+    if let Some(preco_custo) = input.preco_custo {
+        produto.preco_custo = preco_custo;
+    }
+    if let Some(preco_venda) = input.preco_venda {
+        produto.preco_venda = preco_venda;
+    }
+    if let Some(estoque_minimo) = input.estoque_minimo {
+        produto.estoque_minimo = estoque_minimo;
+    }
+    if let Some(unidade) = input.unidade {
+        produto.unidade = unidade;
+    }
+    if let Some(fiscal) = input.fiscal {
+        produto.fiscal = Some(fiscal.normalizados());
+    }
+    if let Some(ativo) = input.ativo {
+        produto.ativo = ativo;
+    }
+    produto.updated_at = Utc::now();
+
+    if !repos.produtos.salvar_cadastro(&produto).await? {
+        return Err(AppError::NotFound);
+    }
+    auditor.alteracao(&antes, &produto).await?;
+
+    Ok(Json(produto.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn delete_produto(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if let Some(produto) = repos.produtos.buscar(empresa.id(), oid).await? {
+        if repos.produtos.excluir(empresa.id(), oid).await? {
+            auditor.exclusao(&produto).await?;
+        }
+    }
+    Ok(Json("Produto excluído".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::json;
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    #[tokio::test]
+    async fn cadastra_altera_e_exclui_produto() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let rotas = || routes(ambiente.repos.clone());
+
+        let (status, produto) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": "Caneta",
+                    "preco_custo": "4.00",
+                    "preco_venda": "10.00",
+                    "estoque_atual": 5,
+                    "estoque_minimo": 2,
+                    "unidade": "UN",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{produto}");
+        assert_eq!(produto["estoque_atual"], 5);
+        let uri = format!("/{}", produto["id"].as_str().unwrap());
+
+        let (status, alterado) = ambiente
+            .enviar(
+                rotas(),
+                Method::PUT,
+                &uri,
+                Some(json!({ "preco_venda": "12.50", "estoque_minimo": 3 })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{alterado}");
+        assert_eq!(alterado["preco_venda"], "12.50");
+        assert_eq!(alterado["estoque_atual"], 5);
+
+        let (status, _) = ambiente.enviar(rotas(), Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = ambiente.enviar(rotas(), Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = ambiente
+            .enviar(rotas(), Method::PUT, &uri, Some(json!({ "nome": "Lápis" })))
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn recusa_precos_e_estoques_negativos() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (status, erro) = ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": "Caneta",
+                    "preco_custo": "-1.00",
+                    "preco_venda": "10.00",
+                    "estoque_atual": -1,
+                    "estoque_minimo": 0,
+                    "unidade": "",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let campos: Vec<&str> = erro["campos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|campo| campo["campo"].as_str().unwrap())
+            .collect();
+        assert_eq!(campos, ["unidade", "preco_custo", "estoque_atual"]);
+    }
+}
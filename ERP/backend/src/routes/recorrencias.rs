@@ -0,0 +1,972 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, put},
+    Json, Router,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    cartoes,
+    consulta::{Campo, Consulta, Listavel, Pagina, ValorFiltro},
+    contabilidade,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    models::*,
+    recorrencias::{self, Limite},
+    repositorio::Repositorios,
+    routes::{clientes::parse_cliente_id, lancamentos},
+    validacao::Validar,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+struct RecorrenciaResponse {
+    pub id: Option<String>,
+    pub tipo: TipoLancamento,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    pub moeda: Moeda,
+    pub frequencia: Frequencia,
+    pub dia: u32,
+    pub inicio: NaiveDate,
+    pub ocorrencias: Option<u32>,
+    pub fim: Option<NaiveDate>,
+    pub conta_id: Option<String>,
+    pub cartao_id: Option<String>,
+    pub cliente_id: Option<String>,
+    pub contraparte: Option<String>,
+    pub geradas: u32,
+    /// Data da próxima ocorrência a ser gerada.
+    pub proxima: Option<NaiveDate>,
+    pub ativa: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Recorrencia> for RecorrenciaResponse {
+    fn from(recorrencia: Recorrencia) -> Self {
+        let proxima = if recorrencia.ativa {
+            recorrencia.data(recorrencia.geradas + 1)
+        } else {
+            None
+        };
+
+        Self {
+            id: recorrencia.id.map(|id| id.to_hex()),
+            tipo: recorrencia.tipo,
+            descricao: recorrencia.descricao,
+            categoria: recorrencia.categoria,
+            valor: recorrencia.valor,
+            moeda: recorrencia.moeda,
+            frequencia: recorrencia.frequencia,
+            dia: recorrencia.dia,
+            inicio: recorrencia.inicio,
+            ocorrencias: recorrencia.ocorrencias,
+            fim: recorrencia.fim,
+            conta_id: recorrencia.conta_id.map(|id| id.to_hex()),
+            cartao_id: recorrencia.cartao_id.map(|id| id.to_hex()),
+            cliente_id: recorrencia.cliente_id.map(|id| id.to_hex()),
+            contraparte: recorrencia.contraparte,
+            geradas: recorrencia.geradas,
+            proxima,
+            ativa: recorrencia.ativa,
+            created_at: recorrencia.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct OcorrenciaResponse {
+    pub numero: u32,
+    pub data: NaiveDate,
+    pub descricao: String,
+    pub categoria: String,
+    pub valor: Dinheiro,
+    /// Nas compras no cartão, `PAGO` quando a fatura já foi paga.
+    pub status: StatusLancamento,
+    pub lancamento_id: Option<String>,
+    pub compra_id: Option<String>,
+    pub competencia: Option<String>,
+}
+
+impl From<Ocorrencia> for OcorrenciaResponse {
+    fn from(ocorrencia: Ocorrencia) -> Self {
+        let numero = ocorrencia.numero();
+        let status = ocorrencia.status();
+        match ocorrencia {
+            Ocorrencia::Lancamento(lancamento) => Self {
+                numero,
+                data: lancamento.vencimento,
+                descricao: lancamento.descricao,
+                categoria: lancamento.categoria,
+                valor: lancamento.valor,
+                status,
+                lancamento_id: lancamento.id.map(|id| id.to_hex()),
+                compra_id: None,
+                competencia: None,
+            },
+            Ocorrencia::Compra(compra) => Self {
+                numero,
+                data: compra.data_compra,
+                descricao: compra.descricao,
+                categoria: compra.categoria,
+                valor: compra.valor,
+                status,
+                lancamento_id: None,
+                compra_id: Some(compra.compra_id.to_hex()),
+                competencia: Some(compra.competencia),
+            },
+        }
+    }
+}
+
+/// Ocorrência já gerada: um lançamento ou, nas recorrências em cartão, uma
+/// compra.
+enum Ocorrencia {
+    Lancamento(Lancamento),
+    Compra(CompraCartao),
+}
+
+impl Ocorrencia {
+    fn numero(&self) -> u32 {
+        match self {
+            Ocorrencia::Lancamento(lancamento) => lancamento.ocorrencia,
+            Ocorrencia::Compra(compra) => compra.ocorrencia,
+        }
+        .unwrap_or_default()
+    }
+
+    fn data(&self) -> NaiveDate {
+        match self {
+            Ocorrencia::Lancamento(lancamento) => lancamento.vencimento,
+            Ocorrencia::Compra(compra) => compra.data_compra,
+        }
+    }
+
+    fn status(&self) -> StatusLancamento {
+        match self {
+            Ocorrencia::Lancamento(lancamento) => lancamento.status,
+            Ocorrencia::Compra(compra) if compra.lancamento_id.is_some() => StatusLancamento::Pago,
+            Ocorrencia::Compra(_) => StatusLancamento::Aberto,
+        }
+    }
+}
+
+/// Lançamentos e compras têm os mesmos campos para listar as ocorrências.
+impl Listavel for Ocorrencia {
+    const CAMPOS: &'static [Campo] = &[Campo::numero("ocorrencia")];
+    const BUSCA: &'static [&'static str] = &["descricao", "categoria"];
+    const ORDEM: &'static str = "ocorrencia";
+}
+
+/// Valores de uma ocorrência depois de uma alteração.
+struct Valores {
+    descricao: String,
+    categoria: String,
+    valor: Dinheiro,
+    contraparte: Option<String>,
+    data: NaiveDate,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_recorrencias).post(create_recorrencia))
+        .route("/:id", get(get_recorrencia).delete(encerrar_recorrencia))
+        .route("/:id/ocorrencias", get(list_ocorrencias))
+        .route(
+            "/:id/ocorrencias/:numero",
+            put(alterar_ocorrencia).delete(excluir_ocorrencia),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_recorrencias,
+    get_recorrencia,
+    create_recorrencia,
+    encerrar_recorrencia,
+    list_ocorrencias,
+    alterar_ocorrencia,
+    excluir_ocorrencia
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Recorrencia>),
+    responses((status = 200, body = Pagina<RecorrenciaResponse>))
+)]
+async fn list_recorrencias(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Recorrencia>,
+) -> Result<Json<Pagina<RecorrenciaResponse>>> {
+    let recorrencias = repos.recorrencias.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(recorrencias.map(RecorrenciaResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = RecorrenciaResponse))
+)]
+async fn get_recorrencia(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<RecorrenciaResponse>> {
+    Ok(Json(find_recorrencia(&repos, empresa, &id).await?.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = RecorrenciaResponse))
+)]
+async fn create_recorrencia(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateRecorrencia>,
+) -> Result<Json<RecorrenciaResponse>> {
+    input.validar()?;
+
+    let conta = match input.conta_id.as_deref() {
+        Some(id) => Some(
+            repos
+                .contas
+                .buscar(empresa.id(), ObjectId::parse_str(id)?)
+                .await?
+                .ok_or(AppError::NotFound)?,
+        ),
+        None => None,
+    };
+    let moeda = match (&conta, input.moeda) {
+        (Some(conta), Some(moeda)) if moeda != conta.moeda => {
+            return Err(AppError::BadRequest(format!(
+                "A conta bancária é em {}; a recorrência deve ser na mesma moeda",
+                conta.moeda
+            )));
+        }
+        (Some(conta), _) => conta.moeda,
+        (None, moeda) => moeda.unwrap_or_default(),
+    };
+    let cartao_id = match input.cartao_id.as_deref() {
+        Some(id) => {
+            let oid = ObjectId::parse_str(id)?;
+            repos
+                .cartoes
+                .buscar(empresa.id(), oid)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            Some(oid)
+        }
+        None => None,
+    };
+    let cliente_id = parse_cliente_id(&repos, empresa, input.cliente_id.as_deref()).await?;
+
+    let now = Utc::now();
+    let recorrencia = Recorrencia {
+        id: None,
+        empresa_id: empresa.id(),
+        tipo: input.tipo,
+        descricao: input.descricao,
+        categoria: input.categoria,
+        valor: input.valor,
+        moeda,
+        frequencia: input.frequencia,
+        dia: input.dia.unwrap_or_else(|| input.inicio.day()),
+        inicio: input.inicio,
+        ocorrencias: input.ocorrencias,
+        fim: input.fim,
+        conta_id: conta.and_then(|conta| conta.id),
+        cartao_id,
+        cliente_id,
+        contraparte: input.contraparte,
+        geradas: 0,
+        ativa: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut criada = repos.recorrencias.criar(recorrencia).await?;
+    auditor.criacao(&criada).await?;
+    recorrencias::gerar(
+        &repos,
+        &auditor,
+        &mut criada,
+        Limite::Data(recorrencias::horizonte()),
+    )
+    .await?;
+
+    Ok(Json(criada.into()))
+}
+
+/// Encerra a recorrência: nada mais é gerado e as ocorrências em aberto de
+/// hoje em diante são canceladas. As anteriores e as pagas ficam.
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn encerrar_recorrencia(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let mut recorrencia = find_recorrencia(&repos, empresa, &id).await?;
+    if recorrencia.ativa {
+        let antes = recorrencia.clone();
+        recorrencia.ativa = false;
+        salvar(&repos, &mut recorrencia).await?;
+        auditor.alteracao(&antes, &recorrencia).await?;
+    }
+
+    let hoje = Utc::now().date_naive();
+    for ocorrencia in ocorrencias(&repos, &recorrencia).await? {
+        if ocorrencia.data() >= hoje && ocorrencia.status() == StatusLancamento::Aberto {
+            cancelar(&repos, &auditor, ocorrencia).await?;
+        }
+    }
+    Ok(Json("Recorrência encerrada".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/ocorrencias",
+    params(EmpresaAtual, Consulta<Ocorrencia>),
+    responses((status = 200, body = Pagina<OcorrenciaResponse>))
+)]
+async fn list_ocorrencias(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+    consulta: Consulta<Ocorrencia>,
+) -> Result<Json<Pagina<OcorrenciaResponse>>> {
+    let recorrencia = find_recorrencia(&repos, empresa, &id).await?;
+    let recorrencia_id = ValorFiltro::Id(recorrencia.id.ok_or(AppError::NotFound)?);
+
+    let pagina = if recorrencia.cartao_id.is_some() {
+        let mut consulta = consulta.converter::<CompraCartao>();
+        consulta.filtro_padrao("recorrencia_id", recorrencia_id);
+        repos
+            .compras_cartao
+            .paginar(empresa.id(), &consulta)
+            .await?
+            .map(Ocorrencia::Compra)
+    } else {
+        let mut consulta = consulta.converter::<Lancamento>();
+        consulta.filtro_padrao("recorrencia_id", recorrencia_id);
+        repos
+            .lancamentos
+            .paginar(empresa.id(), &consulta)
+            .await?
+            .map(Ocorrencia::Lancamento)
+    };
+    Ok(Json(pagina.map(OcorrenciaResponse::from)))
+}
+
+/// Altera só a ocorrência `numero` ou, com `ESTA_E_FUTURAS`, a regra e as
+/// ocorrências em aberto a partir dela. Ocorrências ainda não geradas são
+/// geradas antes, para que a alteração fique registrada nelas.
+#[utoipa::path(
+    put,
+    path = "/{id}/ocorrencias/{numero}",
+    params(
+        EmpresaAtual,
+        ("id" = String, Path),
+        ("numero" = u32, Path, description = "Número da ocorrência, a partir de 1")
+    ),
+    responses((status = 200, body = Vec<OcorrenciaResponse>))
+)]
+async fn alterar_ocorrencia(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, numero)): Path<(String, u32)>,
+    Json(input): Json<AlterarOcorrencia>,
+) -> Result<Json<Vec<OcorrenciaResponse>>> {
+    input.validar()?;
+    let mut recorrencia = find_recorrencia(&repos, empresa, &id).await?;
+    exigir_ocorrencia(&recorrencia, numero)?;
+
+    let alteradas = match input.escopo {
+        EscopoOcorrencia::Esta => {
+            recorrencias::gerar(
+                &repos,
+                &auditor,
+                &mut recorrencia,
+                Limite::Ocorrencia(numero),
+            )
+            .await?;
+            let ocorrencia = ocorrencias(&repos, &recorrencia)
+                .await?
+                .into_iter()
+                .find(|ocorrencia| ocorrencia.numero() == numero)
+                .ok_or(AppError::NotFound)?;
+            if ocorrencia.status() != StatusLancamento::Aberto {
+                return Err(AppError::Conflict(
+                    "A ocorrência já foi paga ou cancelada".to_string(),
+                ));
+            }
+
+            let valores = match &ocorrencia {
+                Ocorrencia::Lancamento(lancamento) => Valores {
+                    descricao: input
+                        .descricao
+                        .unwrap_or_else(|| lancamento.descricao.clone()),
+                    categoria: input
+                        .categoria
+                        .unwrap_or_else(|| lancamento.categoria.clone()),
+                    valor: input.valor.unwrap_or(lancamento.valor),
+                    contraparte: input.contraparte.or_else(|| lancamento.contraparte.clone()),
+                    data: input.data.unwrap_or(lancamento.vencimento),
+                },
+                Ocorrencia::Compra(compra) => Valores {
+                    descricao: input.descricao.unwrap_or_else(|| compra.descricao.clone()),
+                    categoria: input.categoria.unwrap_or_else(|| compra.categoria.clone()),
+                    valor: input.valor.unwrap_or(compra.valor),
+                    contraparte: None,
+                    data: input.data.unwrap_or(compra.data_compra),
+                },
+            };
+            vec![alterar(&repos, &auditor, ocorrencia, valores).await?]
+        }
+        EscopoOcorrencia::EstaEFuturas => {
+            if input.dia.is_some() && recorrencia.frequencia == Frequencia::Semanal {
+                return Err(AppError::BadRequest(
+                    "O dia só se aplica às recorrências mensais e anuais".to_string(),
+                ));
+            }
+            recorrencias::gerar(
+                &repos,
+                &auditor,
+                &mut recorrencia,
+                Limite::Ocorrencia(numero - 1),
+            )
+            .await?;
+
+            let antes = recorrencia.clone();
+            if let Some(descricao) = input.descricao {
+                recorrencia.descricao = descricao;
+            }
+            if let Some(categoria) = input.categoria {
+                recorrencia.categoria = categoria;
+            }
+            if let Some(valor) = input.valor {
+                recorrencia.valor = valor;
+            }
+            if let Some(contraparte) = input.contraparte {
+                recorrencia.contraparte = Some(contraparte);
+            }
+            if let Some(dia) = input.dia {
+                recorrencia.dia = dia;
+            }
+            salvar(&repos, &mut recorrencia).await?;
+            auditor.alteracao(&antes, &recorrencia).await?;
+
+            let mut alteradas = Vec::new();
+            for ocorrencia in ocorrencias(&repos, &recorrencia).await? {
+                let numero_da = ocorrencia.numero();
+                if numero_da < numero || ocorrencia.status() != StatusLancamento::Aberto {
+                    continue;
+                }
+                let valores = Valores {
+                    descricao: recorrencia.descricao_da(numero_da),
+                    categoria: recorrencia.categoria.clone(),
+                    valor: recorrencia.valor,
+                    contraparte: recorrencia.contraparte.clone(),
+                    data: recorrencia
+                        .data(numero_da)
+                        .unwrap_or_else(|| ocorrencia.data()),
+                };
+                alteradas.push(alterar(&repos, &auditor, ocorrencia, valores).await?);
+            }
+            alteradas
+        }
+    };
+
+    Ok(Json(
+        alteradas
+            .into_iter()
+            .map(OcorrenciaResponse::from)
+            .collect(),
+    ))
+}
+
+/// Cancela só a ocorrência `numero` ou, com `ESTA_E_FUTURAS`, encerra a
+/// recorrência antes dela e cancela as em aberto a partir dela.
+#[utoipa::path(
+    delete,
+    path = "/{id}/ocorrencias/{numero}",
+    params(
+        EmpresaAtual,
+        ("id" = String, Path),
+        ("numero" = u32, Path, description = "Número da ocorrência, a partir de 1"),
+        ExcluirOcorrencia
+    ),
+    responses((status = 200, body = String))
+)]
+async fn excluir_ocorrencia(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, numero)): Path<(String, u32)>,
+    Query(input): Query<ExcluirOcorrencia>,
+) -> Result<Json<String>> {
+    let mut recorrencia = find_recorrencia(&repos, empresa, &id).await?;
+    exigir_ocorrencia(&recorrencia, numero)?;
+
+    match input.escopo {
+        EscopoOcorrencia::Esta => {
+            recorrencias::gerar(
+                &repos,
+                &auditor,
+                &mut recorrencia,
+                Limite::Ocorrencia(numero),
+            )
+            .await?;
+            let ocorrencia = ocorrencias(&repos, &recorrencia)
+                .await?
+                .into_iter()
+                .find(|ocorrencia| ocorrencia.numero() == numero)
+                .ok_or(AppError::NotFound)?;
+            if ocorrencia.status() != StatusLancamento::Aberto {
+                return Err(AppError::Conflict(
+                    "A ocorrência já foi paga ou cancelada".to_string(),
+                ));
+            }
+            cancelar(&repos, &auditor, ocorrencia).await?;
+        }
+        EscopoOcorrencia::EstaEFuturas => {
+            recorrencias::gerar(
+                &repos,
+                &auditor,
+                &mut recorrencia,
+                Limite::Ocorrencia(numero - 1),
+            )
+            .await?;
+
+            let antes = recorrencia.clone();
+            match numero
+                .checked_sub(1)
+                .and_then(|anterior| recorrencia.data(anterior))
+            {
+                Some(fim) => recorrencia.fim = Some(fim),
+                None => recorrencia.ativa = false,
+            }
+            salvar(&repos, &mut recorrencia).await?;
+            auditor.alteracao(&antes, &recorrencia).await?;
+
+            for ocorrencia in ocorrencias(&repos, &recorrencia).await? {
+                if ocorrencia.numero() >= numero && ocorrencia.status() == StatusLancamento::Aberto
+                {
+                    cancelar(&repos, &auditor, ocorrencia).await?;
+                }
+            }
+        }
+    }
+    Ok(Json("Ocorrência excluída".to_string()))
+}
+
+async fn find_recorrencia(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    id: &str,
+) -> Result<Recorrencia> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .recorrencias
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+/// A ocorrência precisa já ter sido gerada ou ainda estar por vir.
+fn exigir_ocorrencia(recorrencia: &Recorrencia, numero: u32) -> Result<()> {
+    let existe = numero > 0
+        && (numero <= recorrencia.geradas
+            || (recorrencia.ativa && recorrencia.data(numero).is_some()));
+    if !existe {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Grava a regra somente se nenhuma outra geração tiver acontecido desde a
+/// leitura.
+async fn salvar(repos: &Repositorios, recorrencia: &mut Recorrencia) -> Result<()> {
+    recorrencia.updated_at = Utc::now();
+    if !repos
+        .recorrencias
+        .salvar_geradas(recorrencia, recorrencia.geradas)
+        .await?
+    {
+        return Err(AppError::Conflict(
+            "A recorrência foi alterada por outra operação".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Ocorrências já geradas, pelo número.
+async fn ocorrencias(repos: &Repositorios, recorrencia: &Recorrencia) -> Result<Vec<Ocorrencia>> {
+    let recorrencia_id = recorrencia.id.ok_or(AppError::NotFound)?;
+    let ocorrencias = if recorrencia.cartao_id.is_some() {
+        repos
+            .compras_cartao
+            .da_recorrencia(recorrencia.empresa_id, recorrencia_id)
+            .await?
+            .into_iter()
+            .map(Ocorrencia::Compra)
+            .collect()
+    } else {
+        repos
+            .lancamentos
+            .da_recorrencia(recorrencia.empresa_id, recorrencia_id)
+            .await?
+            .into_iter()
+            .map(Ocorrencia::Lancamento)
+            .collect()
+    };
+    Ok(ocorrencias)
+}
+
+/// Aplica os valores a uma ocorrência em aberto. A compra no cartão é
+/// substituída por outra, na fatura da nova data, com a partida refeita.
+async fn alterar(
+    repos: &Repositorios,
+    auditor: &Auditor,
+    ocorrencia: Ocorrencia,
+    valores: Valores,
+) -> Result<Ocorrencia> {
+    match ocorrencia {
+        Ocorrencia::Lancamento(lancamento) => {
+            let mut alterado = lancamento.clone();
+            alterado.descricao = valores.descricao;
+            alterado.categoria = valores.categoria;
+            alterado.valor = valores.valor;
+            alterado.contraparte = valores.contraparte;
+            alterado.vencimento = valores.data;
+            let alterado =
+                lancamentos::atualizar(repos, alterado, StatusLancamento::Aberto).await?;
+            auditor.alteracao(&lancamento, &alterado).await?;
+            Ok(Ocorrencia::Lancamento(alterado))
+        }
+        Ocorrencia::Compra(compra) => {
+            let cartao = repos
+                .cartoes
+                .buscar(compra.empresa_id, compra.cartao_id)
+                .await?
+                .ok_or(AppError::NotFound)?;
+            excluir_compra(repos, auditor, &compra).await?;
+
+            let nova = CompraCartao {
+                id: None,
+                compra_id: ObjectId::new(),
+                descricao: valores.descricao,
+                categoria: valores.categoria,
+                valor: valores.valor,
+                data_compra: valores.data,
+                competencia: cartoes::formatar_competencia(cartoes::competencia_da_compra(
+                    valores.data,
+                    cartao.dia_fechamento(),
+                )),
+                created_at: Utc::now(),
+                ..compra
+            };
+            let criada = repos
+                .compras_cartao
+                .criar_varias(vec![nova])
+                .await?
+                .pop()
+                .ok_or_else(|| AppError::Internal("Compra não gravada".to_string()))?;
+            auditor.criacao(&criada).await?;
+            contabilidade::registrar_compra_cartao(repos, &criada, criada.valor).await?;
+            Ok(Ocorrencia::Compra(criada))
+        }
+    }
+}
+
+/// Cancela o lançamento ou exclui a compra no cartão, estornando a partida.
+async fn cancelar(repos: &Repositorios, auditor: &Auditor, ocorrencia: Ocorrencia) -> Result<()> {
+    match ocorrencia {
+        Ocorrencia::Lancamento(lancamento) => {
+            let mut cancelado = lancamento.clone();
+            cancelado.status = StatusLancamento::Cancelado;
+            let cancelado =
+                lancamentos::atualizar(repos, cancelado, StatusLancamento::Aberto).await?;
+            auditor.alteracao(&lancamento, &cancelado).await?;
+        }
+        Ocorrencia::Compra(compra) => excluir_compra(repos, auditor, &compra).await?,
+    }
+    Ok(())
+}
+
+async fn excluir_compra(
+    repos: &Repositorios,
+    auditor: &Auditor,
+    compra: &CompraCartao,
+) -> Result<()> {
+    let excluidas = repos
+        .compras_cartao
+        .excluir_compra(compra.empresa_id, compra.cartao_id, compra.compra_id)
+        .await?;
+    if excluidas == 0 {
+        return Err(AppError::Conflict(
+            "A ocorrência foi alterada por outra operação".to_string(),
+        ));
+    }
+    auditor.exclusao(compra).await?;
+    contabilidade::estornar(
+        repos,
+        compra.empresa_id,
+        OrigemPartida::CompraCartao,
+        compra.compra_id,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use crate::routes::testes::Ambiente;
+
+    async fn enviar(
+        ambiente: &Ambiente,
+        metodo: Method,
+        uri: &str,
+        corpo: Option<Value>,
+    ) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                crate::routes::financeiro::routes(ambiente.repos.clone()),
+                metodo,
+                &format!("/recorrencias{uri}"),
+                corpo,
+            )
+            .await
+    }
+
+    /// Aluguel mensal de R$ 1.500,00 a partir de 05/01/2025; devolve o id.
+    async fn aluguel(ambiente: &Ambiente, ocorrencias: u32) -> String {
+        let (status, recorrencia) = enviar(
+            ambiente,
+            Method::POST,
+            "",
+            Some(json!({
+                "tipo": "PAGAR",
+                "descricao": "Aluguel",
+                "categoria": "Ocupação",
+                "valor": "1500.00",
+                "frequencia": "MENSAL",
+                "inicio": "2025-01-05",
+                "ocorrencias": ocorrencias,
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{recorrencia}");
+        recorrencia["id"].as_str().unwrap().to_string()
+    }
+
+    /// `(numero, data, valor, status)` de cada ocorrência.
+    async fn ocorrencias(ambiente: &Ambiente, id: &str) -> Vec<(u64, String, String, String)> {
+        let (status, pagina) =
+            enviar(ambiente, Method::GET, &format!("/{id}/ocorrencias"), None).await;
+        assert_eq!(status, StatusCode::OK, "{pagina}");
+        pagina["itens"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ocorrencia| {
+                (
+                    ocorrencia["numero"].as_u64().unwrap(),
+                    ocorrencia["data"].as_str().unwrap().to_string(),
+                    ocorrencia["valor"].as_str().unwrap().to_string(),
+                    ocorrencia["status"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn altera_esta_e_as_futuras_em_aberto() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let id = aluguel(&ambiente, 6).await;
+
+        let (status, _) = enviar(
+            &ambiente,
+            Method::DELETE,
+            &format!("/{id}/ocorrencias/3?escopo=ESTA"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = enviar(
+            &ambiente,
+            Method::PUT,
+            &format!("/{id}/ocorrencias/1"),
+            Some(json!({ "escopo": "ESTA", "valor": "1450.00", "data": "2025-01-06" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, alteradas) = enviar(
+            &ambiente,
+            Method::PUT,
+            &format!("/{id}/ocorrencias/2"),
+            Some(json!({ "escopo": "ESTA_E_FUTURAS", "valor": "1600.00", "dia": 10 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{alteradas}");
+        let numeros: Vec<u64> = alteradas
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ocorrencia| ocorrencia["numero"].as_u64().unwrap())
+            .collect();
+        assert_eq!(numeros, [2, 4, 5, 6]);
+
+        let linha = |numero, data: &str, valor: &str, status: &str| {
+            (
+                numero,
+                data.to_string(),
+                valor.to_string(),
+                status.to_string(),
+            )
+        };
+        assert_eq!(
+            ocorrencias(&ambiente, &id).await,
+            [
+                linha(1, "2025-01-06", "1450.00", "ABERTO"),
+                linha(2, "2025-02-10", "1600.00", "ABERTO"),
+                linha(3, "2025-03-05", "1500.00", "CANCELADO"),
+                linha(4, "2025-04-10", "1600.00", "ABERTO"),
+                linha(5, "2025-05-10", "1600.00", "ABERTO"),
+                linha(6, "2025-06-10", "1600.00", "ABERTO"),
+            ]
+        );
+        let (_, regra) = enviar(&ambiente, Method::GET, &format!("/{id}"), None).await;
+        assert_eq!(regra["valor"], "1600.00");
+        assert_eq!(regra["dia"], 10);
+    }
+
+    #[tokio::test]
+    async fn exclui_esta_e_as_futuras_encerrando_a_regra() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let id = aluguel(&ambiente, 6).await;
+
+        let (status, _) = enviar(
+            &ambiente,
+            Method::DELETE,
+            &format!("/{id}/ocorrencias/5?escopo=ESTA_E_FUTURAS"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let status: Vec<String> = ocorrencias(&ambiente, &id)
+            .await
+            .into_iter()
+            .map(|(_, _, _, status)| status)
+            .collect();
+        assert_eq!(
+            status,
+            [
+                "ABERTO",
+                "ABERTO",
+                "ABERTO",
+                "ABERTO",
+                "CANCELADO",
+                "CANCELADO"
+            ]
+        );
+        let (_, regra) = enviar(&ambiente, Method::GET, &format!("/{id}"), None).await;
+        assert_eq!(regra["fim"], "2025-04-05");
+    }
+
+    #[tokio::test]
+    async fn recusa_ocorrencias_inexistentes_fechadas_ou_invalidas() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let id = aluguel(&ambiente, 3).await;
+        let alterar = |numero: u32, corpo: Value| {
+            let ambiente = &ambiente;
+            let uri = format!("/{id}/ocorrencias/{numero}");
+            async move { enviar(ambiente, Method::PUT, &uri, Some(corpo)).await.0 }
+        };
+
+        let esta = json!({ "escopo": "ESTA", "valor": "10.00" });
+        assert_eq!(alterar(0, esta.clone()).await, StatusCode::NOT_FOUND);
+        assert_eq!(alterar(4, esta.clone()).await, StatusCode::NOT_FOUND);
+        assert_eq!(
+            alterar(1, json!({ "escopo": "ESTA", "valor": "0.00", "dia": 3 })).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            alterar(
+                1,
+                json!({ "escopo": "ESTA_E_FUTURAS", "data": "2025-01-02" })
+            )
+            .await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        enviar(
+            &ambiente,
+            Method::DELETE,
+            &format!("/{id}/ocorrencias/2?escopo=ESTA"),
+            None,
+        )
+        .await;
+        assert_eq!(alterar(2, esta).await, StatusCode::CONFLICT);
+        let uri = format!("/{}/ocorrencias", mongodb::bson::oid::ObjectId::new());
+        let (status, _) = enviar(&ambiente, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn pagina_as_ocorrencias_da_recorrencia() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let rotas = || crate::routes::financeiro::routes(ambiente.repos.clone());
+        let id = aluguel(&ambiente, 5).await;
+
+        let (status, pagina) = ambiente
+            .enviar(
+                rotas(),
+                Method::GET,
+                &format!("/recorrencias/{id}/ocorrencias?limit=2&page=2"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{pagina}");
+        assert_eq!(pagina["total"], 5);
+        let numeros: Vec<_> = pagina["itens"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ocorrencia| ocorrencia["numero"].as_u64().unwrap())
+            .collect();
+        assert_eq!(numeros, [3, 4]);
+
+        let (status, _) = ambiente
+            .enviar(
+                rotas(),
+                Method::GET,
+                &format!("/recorrencias/{id}/ocorrencias?sort=valor"),
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
@@ -0,0 +1,213 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::{
+    empresa::EmpresaAtual,
+    error::Result,
+    models::{Moeda, TipoLancamento},
+    openapi::Arquivo,
+    relatorios::{self, Formato, Relatorio},
+    repositorio::Repositorios,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FormatoQuery {
+    #[serde(default)]
+    #[param(inline)]
+    formato: Formato,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FluxoCaixaQuery {
+    /// Padrão: primeiro dia do mês atual.
+    de: Option<NaiveDate>,
+    /// Padrão: último dia do segundo mês seguinte.
+    ate: Option<NaiveDate>,
+    /// Só o realizado desta conta bancária.
+    conta_id: Option<String>,
+    /// Moeda dos valores. Padrão: BRL.
+    #[serde(default)]
+    moeda: Moeda,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DreQuery {
+    /// Padrão: 1º de janeiro do ano atual.
+    de: Option<NaiveDate>,
+    /// Padrão: hoje.
+    ate: Option<NaiveDate>,
+    /// Moeda dos valores. Padrão: BRL.
+    #[serde(default)]
+    moeda: Moeda,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AgingQuery {
+    tipo: TipoLancamento,
+    /// Padrão: hoje.
+    data_base: Option<NaiveDate>,
+    /// Moeda dos valores. Padrão: BRL.
+    #[serde(default)]
+    moeda: Moeda,
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/fluxo-caixa", get(fluxo_caixa))
+        .route("/dre", get(dre))
+        .route("/aging", get(aging))
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(fluxo_caixa, dre, aging))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "/fluxo-caixa",
+    params(EmpresaAtual, FluxoCaixaQuery, FormatoQuery),
+    responses(
+        (
+            status = 200,
+            description = "JSON ou, com `formato`, o arquivo como anexo",
+            content(
+                (relatorios::FluxoCaixa = "application/json"),
+                (Arquivo = "text/csv"),
+                (
+                    Arquivo = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                ),
+                (Arquivo = "application/pdf")
+            )
+        )
+    )
+)]
+async fn fluxo_caixa(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<FluxoCaixaQuery>,
+    Query(FormatoQuery { formato }): Query<FormatoQuery>,
+) -> Result<Response> {
+    let hoje = Utc::now().date_naive();
+    let inicio_do_mes = hoje.with_day(1).unwrap_or(hoje);
+    let de = query.de.unwrap_or(inicio_do_mes);
+    let ate = query.ate.unwrap_or_else(|| {
+        (inicio_do_mes + Months::new(3))
+            .pred_opt()
+            .unwrap_or(inicio_do_mes)
+    });
+    let conta_id = query
+        .conta_id
+        .as_deref()
+        .map(ObjectId::parse_str)
+        .transpose()?;
+
+    let fluxo =
+        relatorios::fluxo_caixa(&repos, empresa.id(), de, ate, conta_id, query.moeda).await?;
+    responder(formato, &fluxo)
+}
+
+#[utoipa::path(
+    get,
+    path = "/dre",
+    params(EmpresaAtual, DreQuery, FormatoQuery),
+    responses(
+        (
+            status = 200,
+            description = "JSON ou, com `formato`, o arquivo como anexo",
+            content(
+                (relatorios::Dre = "application/json"),
+                (Arquivo = "text/csv"),
+                (
+                    Arquivo = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                ),
+                (Arquivo = "application/pdf")
+            )
+        )
+    )
+)]
+async fn dre(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<DreQuery>,
+    Query(FormatoQuery { formato }): Query<FormatoQuery>,
+) -> Result<Response> {
+    let hoje = Utc::now().date_naive();
+    let de = query
+        .de
+        .unwrap_or_else(|| hoje.with_ordinal(1).unwrap_or(hoje));
+    let ate = query.ate.unwrap_or(hoje);
+
+    let dre = relatorios::dre(&repos, empresa.id(), de, ate, query.moeda).await?;
+    responder(formato, &dre)
+}
+
+#[utoipa::path(
+    get,
+    path = "/aging",
+    params(EmpresaAtual, AgingQuery, FormatoQuery),
+    responses(
+        (
+            status = 200,
+            description = "JSON ou, com `formato`, o arquivo como anexo",
+            content(
+                (relatorios::Aging = "application/json"),
+                (Arquivo = "text/csv"),
+                (
+                    Arquivo = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                ),
+                (Arquivo = "application/pdf")
+            )
+        )
+    )
+)]
+async fn aging(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Query(query): Query<AgingQuery>,
+    Query(FormatoQuery { formato }): Query<FormatoQuery>,
+) -> Result<Response> {
+    let data_base = query.data_base.unwrap_or_else(|| Utc::now().date_naive());
+
+    let aging = relatorios::aging(&repos, empresa.id(), query.tipo, data_base, query.moeda).await?;
+    responder(formato, &aging)
+}
+
+/// JSON, ou o arquivo no formato pedido como anexo.
+fn responder<R: Relatorio>(formato: Formato, relatorio: &R) -> Result<Response> {
+    if formato == Formato::Json {
+        return Ok(Json(relatorio).into_response());
+    }
+
+    let arquivo = format!(
+        "{}-{}.{}",
+        R::ARQUIVO,
+        Utc::now().date_naive().format("%Y-%m-%d"),
+        formato.extensao()
+    );
+    let conteudo = relatorio.quadro().exportar(formato)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, formato.tipo_conteudo().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", arquivo),
+            ),
+        ],
+        conteudo,
+    )
+        .into_response())
+}
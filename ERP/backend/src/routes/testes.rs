@@ -0,0 +1,97 @@
+//! Apoio aos testes das rotas: uma empresa e um usuário vinculado a ela
+//! sobre o banco SQLite em memória, e requisições JSON pelo `oneshot`.
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    Extension, Router,
+};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::{
+    auth::UsuarioAutenticado,
+    empresa::CABECALHO_EMPRESA,
+    models::{Empresa, Papel},
+    repositorio::Repositorios,
+};
+
+pub struct Ambiente {
+    pub repos: Repositorios,
+    pub empresa_id: ObjectId,
+    pub usuario: UsuarioAutenticado,
+}
+
+impl Ambiente {
+    /// Empresa nova num banco vazio, com um usuário com os `papeis`.
+    pub async fn novo(papeis: &[Papel]) -> Self {
+        Self::no_banco(Repositorios::em_memoria().await, papeis).await
+    }
+
+    /// Outra empresa no mesmo banco, com um usuário de mesmos papéis.
+    pub async fn outra_empresa(&self) -> Self {
+        Self::no_banco(self.repos.clone(), &self.usuario.papeis).await
+    }
+
+    async fn no_banco(repos: Repositorios, papeis: &[Papel]) -> Self {
+        let agora = Utc::now();
+        let empresa = repos
+            .empresas
+            .criar(Empresa {
+                id: None,
+                nome: "Empresa de Teste".to_string(),
+                documento: None,
+                pais: "BR".to_string(),
+                fiscal: None,
+                ativo: true,
+                created_at: agora,
+                updated_at: agora,
+            })
+            .await
+            .unwrap();
+        let empresa_id = empresa.id.unwrap();
+        let usuario = UsuarioAutenticado {
+            id: ObjectId::new(),
+            nome: "Teste".to_string(),
+            email: "teste@exemplo.com".to_string(),
+            papeis: papeis.to_vec(),
+            empresas: vec![empresa_id],
+        };
+        Self {
+            repos,
+            empresa_id,
+            usuario,
+        }
+    }
+
+    /// Envia a requisição a `rotas` como o usuário do ambiente e devolve o
+    /// status e o corpo JSON (`Null` quando vazio).
+    pub async fn enviar(
+        &self,
+        rotas: Router,
+        metodo: Method,
+        uri: &str,
+        corpo: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let requisicao = Request::builder()
+            .method(metodo)
+            .uri(uri)
+            .header(CABECALHO_EMPRESA, self.empresa_id.to_hex())
+            .header("content-type", "application/json")
+            .body(corpo.map_or_else(Body::empty, |corpo| Body::from(corpo.to_string())))
+            .unwrap();
+        let resposta = rotas
+            .layer(Extension(self.usuario.clone()))
+            .oneshot(requisicao)
+            .await
+            .unwrap();
+        let status = resposta.status();
+        let bytes = axum::body::to_bytes(resposta.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, json)
+    }
+}
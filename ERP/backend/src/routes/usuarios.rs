@@ -0,0 +1,230 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    auditoria::Auditor,
+    auth::{self, UsuarioAutenticado},
+    consulta::{Consulta, Pagina},
+    empresa::{parse_empresas, EmpresaAtual},
+    error::{AppError, Result},
+    models::*,
+    repositorio::Repositorios,
+};
+
+const TAMANHO_MINIMO_SENHA: usize = 8;
+
+#[derive(Debug, Serialize, ToSchema)]
+struct UsuarioResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub email: String,
+    pub papeis: Vec<Papel>,
+    pub empresas: Vec<String>,
+    pub ativo: bool,
+}
+
+impl From<Usuario> for UsuarioResponse {
+    fn from(usuario: Usuario) -> Self {
+        Self {
+            id: usuario.id.map(|id| id.to_hex()),
+            nome: usuario.nome,
+            email: usuario.email,
+            papeis: usuario.papeis,
+            empresas: usuario.empresas.iter().map(|id| id.to_hex()).collect(),
+            ativo: usuario.ativo,
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_usuarios).post(create_usuario))
+        .route(
+            "/:id",
+            get(get_usuario).put(update_usuario).delete(delete_usuario),
+        )
+        .with_state(repos)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_usuarios,
+    get_usuario,
+    create_usuario,
+    update_usuario,
+    delete_usuario
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(Consulta<Usuario>),
+    responses((status = 200, body = Pagina<UsuarioResponse>))
+)]
+async fn list_usuarios(
+    State(repos): State<Repositorios>,
+    consulta: Consulta<Usuario>,
+) -> Result<Json<Pagina<UsuarioResponse>>> {
+    let usuarios = repos.usuarios.paginar(&consulta).await?;
+    Ok(Json(usuarios.map(UsuarioResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    responses((status = 200, body = UsuarioResponse))
+)]
+async fn get_usuario(
+    State(repos): State<Repositorios>,
+    Path(id): Path<String>,
+) -> Result<Json<UsuarioResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let usuario = repos
+        .usuarios
+        .buscar(oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(usuario.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = UsuarioResponse))
+)]
+async fn create_usuario(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Json(input): Json<CreateUsuario>,
+) -> Result<Json<UsuarioResponse>> {
+    validar_senha(&input.senha)?;
+    if auth::find_usuario_por_email(&repos, &input.email)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Conflict(
+            "Já existe um usuário com este e-mail".to_string(),
+        ));
+    }
+
+    let mut empresas = parse_empresas(&repos, &input.empresas).await?;
+    if empresas.is_empty() {
+        empresas.push(empresa.id());
+    }
+
+    let now = Utc::now();
+    let usuario = Usuario {
+        id: None,
+        nome: input.nome,
+        email: input.email.trim().to_lowercase(),
+        senha_hash: auth::hash_senha(&input.senha)?,
+        papeis: input.papeis,
+        empresas,
+        ativo: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = repos.usuarios.criar(usuario).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    responses((status = 200, body = UsuarioResponse))
+)]
+async fn update_usuario(
+    State(repos): State<Repositorios>,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateUsuario>,
+) -> Result<Json<UsuarioResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+
+    let mut usuario = repos
+        .usuarios
+        .buscar(oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let antes = usuario.clone();
+
+    if let Some(nome) = input.nome {
+        usuario.nome = nome;
+    }
+    if let Some(senha) = input.senha {
+        validar_senha(&senha)?;
+        usuario.senha_hash = auth::hash_senha(&senha)?;
+    }
+    if let Some(papeis) = input.papeis {
+        usuario.papeis = papeis;
+    }
+    if let Some(empresas) = input.empresas {
+        let empresas = parse_empresas(&repos, &empresas).await?;
+        if empresas.is_empty() {
+            return Err(AppError::BadRequest(
+                "O usuário deve estar vinculado a pelo menos uma empresa".to_string(),
+            ));
+        }
+        usuario.empresas = empresas;
+    }
+    if let Some(ativo) = input.ativo {
+        usuario.ativo = ativo;
+    }
+    usuario.updated_at = Utc::now();
+
+    if !repos.usuarios.salvar(&usuario).await? {
+        return Err(AppError::NotFound);
+    }
+    auditor.alteracao(&antes, &usuario).await?;
+
+    Ok(Json(usuario.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    responses((status = 200, body = String))
+)]
+async fn delete_usuario(
+    State(repos): State<Repositorios>,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    usuario: UsuarioAutenticado,
+) -> Result<Json<String>> {
+    let oid = ObjectId::parse_str(&id)?;
+    if oid == usuario.id {
+        return Err(AppError::Conflict(
+            "Não é possível excluir o próprio usuário".to_string(),
+        ));
+    }
+
+    if let Some(excluido) = repos.usuarios.buscar(oid).await? {
+        if repos.usuarios.excluir(oid).await? {
+            auditor.exclusao(&excluido).await?;
+        }
+    }
+    Ok(Json("Usuário excluído".to_string()))
+}
+
+fn validar_senha(senha: &str) -> Result<()> {
+    if senha.chars().count() < TAMANHO_MINIMO_SENHA {
+        return Err(AppError::BadRequest(format!(
+            "A senha deve ter pelo menos {} caracteres",
+            TAMANHO_MINIMO_SENHA
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,808 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use std::collections::HashMap;
+
+use chrono::Utc;
+use erp_api::{ItemVendaResponse, VendaResponse};
+use mongodb::bson::oid::ObjectId;
+use utoipa::OpenApi;
+
+use crate::{
+    auditoria::Auditor,
+    auth::UsuarioAutenticado,
+    consulta::{Consulta, Pagina},
+    contabilidade,
+    empresa::EmpresaAtual,
+    error::{AppError, Result},
+    estoque::{self, NovaMovimentacao},
+    models::*,
+    repositorio::Repositorios,
+    routes::clientes::parse_cliente_id,
+    tributos::{self, Operacao},
+    validacao::{self, Validacao, Validar},
+};
+
+impl From<ItemVenda> for ItemVendaResponse {
+    fn from(item: ItemVenda) -> Self {
+        Self {
+            id: item.id.to_hex(),
+            produto_id: item.produto_id.to_hex(),
+            produto_nome: item.produto_nome,
+            quantidade: item.quantidade,
+            preco_unitario: item.preco_unitario,
+            subtotal: item.subtotal,
+            tributos: item.tributos,
+        }
+    }
+}
+
+impl From<Venda> for VendaResponse {
+    fn from(venda: Venda) -> Self {
+        Self {
+            id: venda.id.map(|id| id.to_hex()),
+            cliente_id: venda.cliente_id.map(|id| id.to_hex()),
+            itens: venda
+                .itens
+                .into_iter()
+                .map(ItemVendaResponse::from)
+                .collect(),
+            total: venda.total,
+            desconto: venda.desconto,
+            total_final: venda.total_final,
+            tributos: venda.tributos,
+            forma_pagamento: venda.forma_pagamento,
+            status: venda.status,
+            observacoes: venda.observacoes,
+            usuario: venda.usuario,
+            created_at: venda.created_at.to_rfc3339(),
+        }
+    }
+}
+
+pub fn routes(repos: Repositorios) -> Router {
+    Router::new()
+        .route("/", get(list_vendas).post(create_venda))
+        .route(
+            "/:id",
+            get(get_venda).put(update_venda).delete(delete_venda),
+        )
+        .route("/:id/itens", post(add_item))
+        .route("/:id/itens/:item_id", delete(remove_item))
+        .route("/:id/finalizar", post(finalizar_venda))
+        .route("/:id/cancelar", post(cancelar_venda))
+        .with_state(repos.clone())
+        .merge(super::notas_fiscais::routes(repos))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_vendas,
+    get_venda,
+    create_venda,
+    update_venda,
+    delete_venda,
+    add_item,
+    remove_item,
+    finalizar_venda,
+    cancelar_venda
+))]
+pub struct Api;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(EmpresaAtual, Consulta<Venda>),
+    responses((status = 200, body = Pagina<VendaResponse>))
+)]
+async fn list_vendas(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    consulta: Consulta<Venda>,
+) -> Result<Json<Pagina<VendaResponse>>> {
+    let vendas = repos.vendas.paginar(empresa.id(), &consulta).await?;
+    Ok(Json(vendas.map(VendaResponse::from)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn get_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    Path(id): Path<String>,
+) -> Result<Json<VendaResponse>> {
+    let oid = ObjectId::parse_str(&id)?;
+    let venda = repos
+        .vendas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(venda.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn create_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    usuario: UsuarioAutenticado,
+    Json(input): Json<CreateVenda>,
+) -> Result<Json<VendaResponse>> {
+    input.validar()?;
+    let cliente_id = parse_cliente_id(&repos, empresa, input.cliente_id.as_deref()).await?;
+
+    let mut itens = Vec::with_capacity(input.itens.len());
+    for item in input.itens {
+        itens.push(build_item(&repos, empresa, item).await?);
+    }
+
+    let now = Utc::now();
+    let mut venda = Venda {
+        id: None,
+        empresa_id: empresa.id(),
+        cliente_id,
+        itens,
+        total: Dinheiro::ZERO,
+        desconto: input.desconto,
+        total_final: Dinheiro::ZERO,
+        tributos: None,
+        forma_pagamento: input.forma_pagamento,
+        status: StatusVenda::Aberta,
+        observacoes: input.observacoes,
+        usuario: Some(usuario.email),
+        created_at: now,
+        updated_at: now,
+    };
+    recalcular(&repos, empresa, &mut venda).await?;
+
+    let created = repos.vendas.criar(venda).await?;
+    auditor.criacao(&created).await?;
+
+    Ok(Json(created.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn update_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateVenda>,
+) -> Result<Json<VendaResponse>> {
+    input.validar()?;
+    let mut venda = find_aberta(&repos, empresa, &id).await?;
+    let antes = venda.clone();
+
+    if input.cliente_id.is_some() {
+        venda.cliente_id = parse_cliente_id(&repos, empresa, input.cliente_id.as_deref()).await?;
+    }
+    if let Some(desconto) = input.desconto {
+        venda.desconto = desconto;
+    }
+    if let Some(forma_pagamento) = input.forma_pagamento {
+        venda.forma_pagamento = forma_pagamento;
+    }
+    if let Some(observacoes) = input.observacoes {
+        venda.observacoes = Some(observacoes);
+    }
+    recalcular(&repos, empresa, &mut venda).await?;
+
+    salvar_aberta(&repos, &mut venda).await?;
+    auditor.alteracao(&antes, &venda).await?;
+    Ok(Json(venda.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(EmpresaAtual),
+    responses((status = 200, body = String))
+)]
+async fn delete_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+) -> Result<Json<String>> {
+    let venda = find_aberta(&repos, empresa, &id).await?;
+    let oid = venda.id.ok_or(AppError::NotFound)?;
+    if !repos.vendas.excluir_aberta(empresa.id(), oid).await? {
+        return Err(AppError::Conflict(
+            "A venda foi alterada por outra operação".to_string(),
+        ));
+    }
+    auditor.exclusao(&venda).await?;
+    Ok(Json("Venda excluída".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/itens",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn add_item(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    Json(input): Json<CreateItemVenda>,
+) -> Result<Json<VendaResponse>> {
+    input.validar()?;
+    let mut venda = find_aberta(&repos, empresa, &id).await?;
+    let antes = venda.clone();
+    venda.itens.push(build_item(&repos, empresa, input).await?);
+    recalcular(&repos, empresa, &mut venda).await?;
+
+    salvar_aberta(&repos, &mut venda).await?;
+    auditor.alteracao(&antes, &venda).await?;
+    Ok(Json(venda.into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}/itens/{item_id}",
+    params(EmpresaAtual, ("id" = String, Path), ("item_id" = String, Path)),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn remove_item(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path((id, item_id)): Path<(String, String)>,
+) -> Result<Json<VendaResponse>> {
+    let item_oid = ObjectId::parse_str(&item_id)?;
+    let mut venda = find_aberta(&repos, empresa, &id).await?;
+    let antes = venda.clone();
+
+    venda.itens.retain(|item| item.id != item_oid);
+    if venda.itens.len() == antes.itens.len() {
+        return Err(AppError::NotFound);
+    }
+    recalcular(&repos, empresa, &mut venda).await?;
+
+    salvar_aberta(&repos, &mut venda).await?;
+    auditor.alteracao(&antes, &venda).await?;
+    Ok(Json(venda.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/finalizar",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn finalizar_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    usuario: UsuarioAutenticado,
+) -> Result<Json<VendaResponse>> {
+    let venda = find_aberta(&repos, empresa, &id).await?;
+    if venda.itens.is_empty() {
+        return Err(AppError::BadRequest(
+            "Não é possível finalizar uma venda sem itens".to_string(),
+        ));
+    }
+
+    transicionar(&repos, &venda, StatusVenda::Aberta, StatusVenda::Finalizada).await?;
+
+    let motivo = format!("Venda {}", id);
+    let mut baixados: Vec<&ItemVenda> = Vec::with_capacity(venda.itens.len());
+    for item in &venda.itens {
+        let baixa = estoque::movimentar(
+            &repos,
+            NovaMovimentacao {
+                empresa_id: venda.empresa_id,
+                produto_id: item.produto_id,
+                tipo: TipoMovimentacao::Saida,
+                quantidade: item.quantidade,
+                motivo: motivo.clone(),
+                usuario: Some(usuario.email.clone()),
+                venda_id: venda.id,
+                pedido_compra_id: None,
+            },
+        )
+        .await;
+
+        if let Err(e) = baixa {
+            estornar(
+                &repos,
+                &venda,
+                &baixados,
+                "Estorno da finalização malsucedida da venda",
+                &usuario,
+            )
+            .await?;
+            transicionar(&repos, &venda, StatusVenda::Finalizada, StatusVenda::Aberta).await?;
+            return Err(e);
+        }
+        baixados.push(item);
+    }
+
+    let finalizada = find_venda(&repos, empresa, &id).await?;
+    auditor.alteracao(&venda, &finalizada).await?;
+    contabilidade::registrar_venda(&repos, &finalizada).await?;
+    Ok(Json(finalizada.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/cancelar",
+    params(EmpresaAtual),
+    responses((status = 200, body = VendaResponse))
+)]
+async fn cancelar_venda(
+    State(repos): State<Repositorios>,
+    empresa: EmpresaAtual,
+    auditor: Auditor,
+    Path(id): Path<String>,
+    usuario: UsuarioAutenticado,
+) -> Result<Json<VendaResponse>> {
+    let venda = find_venda(&repos, empresa, &id).await?;
+
+    match venda.status {
+        StatusVenda::Cancelada => {
+            return Err(AppError::Conflict("A venda já está cancelada".to_string()));
+        }
+        StatusVenda::Aberta => {
+            transicionar(&repos, &venda, StatusVenda::Aberta, StatusVenda::Cancelada).await?;
+        }
+        StatusVenda::Finalizada => {
+            if repos
+                .notas_fiscais
+                .da_venda(empresa.id(), venda.id.unwrap_or_default())
+                .await?
+                .is_some()
+            {
+                return Err(AppError::Conflict(
+                    "A venda tem nota fiscal autorizada; cancele a nota na SEFAZ antes".to_string(),
+                ));
+            }
+            transicionar(
+                &repos,
+                &venda,
+                StatusVenda::Finalizada,
+                StatusVenda::Cancelada,
+            )
+            .await?;
+            let itens: Vec<&ItemVenda> = venda.itens.iter().collect();
+            estornar(&repos, &venda, &itens, "Cancelamento da venda", &usuario).await?;
+            if let Some(venda_id) = venda.id {
+                contabilidade::estornar(&repos, empresa.id(), OrigemPartida::Venda, venda_id)
+                    .await?;
+            }
+        }
+    }
+
+    let cancelada = find_venda(&repos, empresa, &id).await?;
+    auditor.alteracao(&venda, &cancelada).await?;
+    Ok(Json(cancelada.into()))
+}
+
+async fn find_venda(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<Venda> {
+    let oid = ObjectId::parse_str(id)?;
+    repos
+        .vendas
+        .buscar(empresa.id(), oid)
+        .await?
+        .ok_or(AppError::NotFound)
+}
+
+async fn find_aberta(repos: &Repositorios, empresa: EmpresaAtual, id: &str) -> Result<Venda> {
+    let venda = find_venda(repos, empresa, id).await?;
+    if venda.status != StatusVenda::Aberta {
+        return Err(AppError::Conflict(format!(
+            "A venda está {} e não pode ser alterada",
+            venda.status.as_str()
+        )));
+    }
+    Ok(venda)
+}
+
+/// Grava a venda somente se ela ainda estiver aberta.
+async fn salvar_aberta(repos: &Repositorios, venda: &mut Venda) -> Result<()> {
+    venda.updated_at = Utc::now();
+    if !repos.vendas.salvar_aberta(venda).await? {
+        return Err(AppError::Conflict(
+            "A venda foi alterada por outra operação".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Troca o status de forma atômica, falhando se outro processo já o alterou.
+async fn transicionar(
+    repos: &Repositorios,
+    venda: &Venda,
+    de: StatusVenda,
+    para: StatusVenda,
+) -> Result<()> {
+    let id = venda.id.ok_or(AppError::NotFound)?;
+    if !repos
+        .vendas
+        .transicionar(venda.empresa_id, id, de, para)
+        .await?
+    {
+        return Err(AppError::Conflict(format!(
+            "A venda não está mais {}",
+            de.as_str()
+        )));
+    }
+    Ok(())
+}
+
+async fn estornar(
+    repos: &Repositorios,
+    venda: &Venda,
+    itens: &[&ItemVenda],
+    motivo: &str,
+    usuario: &UsuarioAutenticado,
+) -> Result<()> {
+    let venda_id = venda.id.map(|id| id.to_hex()).unwrap_or_default();
+    for item in itens {
+        estoque::movimentar(
+            repos,
+            NovaMovimentacao {
+                empresa_id: venda.empresa_id,
+                produto_id: item.produto_id,
+                tipo: TipoMovimentacao::Entrada,
+                quantidade: item.quantidade,
+                motivo: format!("{} {}", motivo, venda_id),
+                usuario: Some(usuario.email.clone()),
+                venda_id: venda.id,
+                pedido_compra_id: None,
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn build_item(
+    repos: &Repositorios,
+    empresa: EmpresaAtual,
+    input: CreateItemVenda,
+) -> Result<ItemVenda> {
+    let produto_id = ObjectId::parse_str(&input.produto_id)?;
+    let produto = repos
+        .produtos
+        .buscar(empresa.id(), produto_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let preco_unitario = input.preco_unitario.unwrap_or(produto.preco_venda);
+
+    Ok(ItemVenda {
+        id: ObjectId::new(),
+        produto_id,
+        produto_nome: produto.nome,
+        quantidade: input.quantidade,
+        preco_unitario,
+        subtotal: validacao::multiplicar("itens", preco_unitario, input.quantidade)?,
+        tributos: None,
+    })
+}
+
+/// Soma os itens e calcula os tributos da venda. Sem a configuração fiscal
+/// da empresa a venda fica sem tributos; com ela, todos os produtos precisam
+/// dos dados fiscais do regime da empresa. O desconto não pode passar do
+/// total dos itens.
+async fn recalcular(repos: &Repositorios, empresa: EmpresaAtual, venda: &mut Venda) -> Result<()> {
+    venda.total = validacao::somar("total", venda.itens.iter().map(|item| item.subtotal))?;
+    Validacao::new()
+        .checar(
+            "desconto",
+            venda.desconto <= venda.total,
+            "O desconto não pode passar do total dos itens",
+        )
+        .concluir()?;
+    tributos::limpar(venda);
+
+    let Some(fiscal) = repos
+        .empresas
+        .buscar(empresa.id())
+        .await?
+        .and_then(|dados| dados.fiscal)
+    else {
+        return Ok(());
+    };
+    let cliente = match venda.cliente_id {
+        Some(cliente_id) => repos.clientes.buscar(empresa.id(), cliente_id).await?,
+        None => None,
+    };
+    let produtos = produtos_da_venda(repos, venda).await?;
+    tributos::calcular(&Operacao::new(&fiscal, cliente.as_ref()), venda, &produtos)
+}
+
+/// Produtos dos itens da venda, pelo id; os excluídos ficam de fora.
+pub async fn produtos_da_venda(
+    repos: &Repositorios,
+    venda: &Venda,
+) -> Result<HashMap<ObjectId, Produto>> {
+    let mut produtos = HashMap::new();
+    for item in &venda.itens {
+        if let Some(produto) = repos
+            .produtos
+            .buscar(venda.empresa_id, item.produto_id)
+            .await?
+        {
+            produtos.insert(item.produto_id, produto);
+        }
+    }
+    Ok(produtos)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::routes::testes::Ambiente;
+
+    /// Produto de R$ 10,00 sem dados fiscais; devolve o id.
+    async fn produto(ambiente: &Ambiente) -> String {
+        let (status, produto) = ambiente
+            .enviar(
+                crate::routes::produtos::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(json!({
+                    "nome": "Caneta",
+                    "preco_custo": "4.00",
+                    "preco_venda": "10.00",
+                    "estoque_minimo": 0,
+                    "unidade": "UN",
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{produto}");
+        produto["id"].as_str().unwrap().to_string()
+    }
+
+    async fn criar(ambiente: &Ambiente, venda: Value) -> (StatusCode, Value) {
+        ambiente
+            .enviar(
+                routes(ambiente.repos.clone()),
+                Method::POST,
+                "/",
+                Some(venda),
+            )
+            .await
+    }
+
+    fn campos(erro: &Value) -> Vec<&str> {
+        erro["campos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|campo| campo["campo"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn cria_venda_com_desconto() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente).await;
+
+        let (status, venda) = criar(
+            &ambiente,
+            json!({
+                "itens": [{ "produto_id": produto_id, "quantidade": 3 }],
+                "desconto": "5.00",
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{venda}");
+        assert_eq!(venda["total"], "30.00");
+        assert_eq!(venda["total_final"], "25.00");
+    }
+
+    #[tokio::test]
+    async fn recusa_itens_e_desconto_invalidos() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente).await;
+
+        let (status, erro) = criar(
+            &ambiente,
+            json!({
+                "itens": [
+                    { "produto_id": produto_id, "quantidade": 0 },
+                    { "produto_id": produto_id, "quantidade": 1, "preco_unitario": "-1.00" },
+                ],
+                "desconto": "-1.00",
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            campos(&erro),
+            ["desconto", "itens[0].quantidade", "itens[1].preco_unitario"]
+        );
+
+        let (status, erro) = criar(
+            &ambiente,
+            json!({
+                "itens": [{ "produto_id": produto_id, "quantidade": 1 }],
+                "desconto": "10.01",
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(campos(&erro), ["desconto"]);
+    }
+
+    #[tokio::test]
+    async fn recusa_valores_acima_do_limite() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente).await;
+        let item = |quantidade: i32| {
+            json!({
+                "produto_id": produto_id,
+                "quantidade": quantidade,
+                "preco_unitario": "999999999999.99",
+            })
+        };
+
+        let (status, erro) = criar(
+            &ambiente,
+            json!({ "itens": [item(2)], "forma_pagamento": "dinheiro" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(campos(&erro), ["itens"]);
+
+        let (status, erro) = criar(
+            &ambiente,
+            json!({ "itens": [item(1), item(1)], "forma_pagamento": "dinheiro" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(campos(&erro), ["total"]);
+
+        let (status, _) = criar(
+            &ambiente,
+            json!({
+                "itens": [{ "produto_id": produto_id, "quantidade": 1, "preco_unitario": "1e20" }],
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        assert!(status.is_client_error());
+    }
+
+    #[tokio::test]
+    async fn finaliza_cancela_e_recusa_alterar_venda_fechada() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let produto_id = produto(&ambiente).await;
+        let rotas = || routes(ambiente.repos.clone());
+        let estoque = |quantidade: i32| {
+            json!({
+                "produto_id": produto_id,
+                "tipo": "ENTRADA",
+                "quantidade": quantidade,
+                "motivo": "Compra",
+            })
+        };
+        ambiente
+            .enviar(
+                crate::routes::estoque::routes(ambiente.repos.clone()),
+                Method::POST,
+                "/movimentacoes",
+                Some(estoque(5)),
+            )
+            .await;
+
+        let (_, venda) = criar(
+            &ambiente,
+            json!({
+                "itens": [{ "produto_id": produto_id, "quantidade": 2 }],
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        let uri = format!("/{}", venda["id"].as_str().unwrap());
+
+        let (status, finalizada) = ambiente
+            .enviar(rotas(), Method::POST, &format!("{uri}/finalizar"), None)
+            .await;
+        assert_eq!(status, StatusCode::OK, "{finalizada}");
+        assert_eq!(finalizada["status"], "FINALIZADA");
+        let produto_uri = format!("/{produto_id}");
+        let produtos = || crate::routes::produtos::routes(ambiente.repos.clone());
+        let (_, lido) = ambiente
+            .enviar(produtos(), Method::GET, &produto_uri, None)
+            .await;
+        assert_eq!(lido["estoque_atual"], 3);
+
+        let (status, painel) = ambiente
+            .enviar(
+                crate::routes::dashboard::routes(ambiente.repos.clone()),
+                Method::GET,
+                "/",
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(painel["vendas_hoje"]["quantidade"], 1);
+        assert_eq!(painel["vendas_hoje"]["valor_total"], "20.00");
+
+        let (status, _) = ambiente.enviar(rotas(), Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = ambiente
+            .enviar(
+                rotas(),
+                Method::POST,
+                &format!("{uri}/itens"),
+                Some(json!({ "produto_id": produto_id, "quantidade": 1 })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, cancelada) = ambiente
+            .enviar(rotas(), Method::POST, &format!("{uri}/cancelar"), None)
+            .await;
+        assert_eq!(status, StatusCode::OK, "{cancelada}");
+        assert_eq!(cancelada["status"], "CANCELADA");
+        let (_, lido) = ambiente
+            .enviar(produtos(), Method::GET, &produto_uri, None)
+            .await;
+        assert_eq!(lido["estoque_atual"], 5);
+        let (status, _) = ambiente
+            .enviar(rotas(), Method::POST, &format!("{uri}/cancelar"), None)
+            .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn venda_inexistente_e_404() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let uri = format!("/{}", ObjectId::new().to_hex());
+        for (metodo, uri) in [
+            (Method::GET, uri.clone()),
+            (Method::DELETE, uri.clone()),
+            (Method::POST, format!("{uri}/finalizar")),
+            (Method::POST, format!("{uri}/cancelar")),
+        ] {
+            let (status, _) = ambiente
+                .enviar(routes(ambiente.repos.clone()), metodo, &uri, None)
+                .await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn produto_inexistente_e_404() {
+        let ambiente = Ambiente::novo(&[]).await;
+        let (status, _) = criar(
+            &ambiente,
+            json!({
+                "itens": [{ "produto_id": ObjectId::new().to_hex(), "quantidade": 1 }],
+                "forma_pagamento": "dinheiro",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
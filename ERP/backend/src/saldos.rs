@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    cambio::Conversor,
+    contabilidade,
+    error::Result,
+    models::{ContaBancaria, Dinheiro, Moeda},
+    repositorio::Repositorios,
+};
+
+/// Limite utilizado de um único cartão: parcelas ainda não quitadas.
+pub async fn utilizado(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    cartao_id: ObjectId,
+) -> Result<Dinheiro> {
+    Ok(repos
+        .compras_cartao
+        .utilizado_por_cartao(empresa_id, Some(cartao_id))
+        .await?
+        .remove(&cartao_id)
+        .unwrap_or_default())
+}
+
+/// Saldo atual de cada conta bancária na moeda dela, pelo id, sempre a
+/// partir das partidas. Nas contas em reais é o saldo contábil; nas demais
+/// soma o valor na moeda gravado em cada linha, ou, nas linhas sem ele, o
+/// valor em reais convertido pela cotação da data da partida.
+pub async fn contas_bancarias(
+    repos: &Repositorios,
+    empresa_id: ObjectId,
+    contas: &[ContaBancaria],
+) -> Result<HashMap<ObjectId, Dinheiro>> {
+    let mut saldos = contabilidade::saldos_bancarios(repos, empresa_id, None).await?;
+    let estrangeiras: HashMap<ObjectId, Moeda> = contas
+        .iter()
+        .filter(|conta| conta.moeda != Moeda::Brl)
+        .filter_map(|conta| Some((conta.id?, conta.moeda)))
+        .collect();
+    if !estrangeiras.is_empty() {
+        let contabeis: HashMap<ObjectId, ObjectId> = repos
+            .contas_contabeis
+            .plano(empresa_id)
+            .await?
+            .iter()
+            .filter_map(|conta| {
+                let conta_bancaria_id = conta.conta_bancaria_id?;
+                estrangeiras
+                    .contains_key(&conta_bancaria_id)
+                    .then_some((conta.id?, conta_bancaria_id))
+            })
+            .collect();
+        let ids: Vec<ObjectId> = contabeis.keys().copied().collect();
+        let linhas = repos.partidas.linhas_das_contas(empresa_id, &ids).await?;
+
+        let mut na_moeda: HashMap<ObjectId, Dinheiro> = HashMap::new();
+        let mut conversores: HashMap<Moeda, Conversor> = HashMap::new();
+        for (data, linha) in linhas {
+            let Some(&conta_bancaria_id) = contabeis.get(&linha.conta_id) else {
+                continue;
+            };
+            let moeda = estrangeiras[&conta_bancaria_id];
+            let entrada = linha.debito.positivo();
+            let valor = match linha.valor_moeda {
+                Some(valor) => valor,
+                None => {
+                    let em_reais = if entrada { linha.debito } else { linha.credito };
+                    conversores
+                        .entry(moeda)
+                        .or_insert_with(|| Conversor::new(repos, empresa_id, moeda))
+                        .converter(em_reais, Moeda::Brl, data)
+                        .await?
+                }
+            };
+            *na_moeda.entry(conta_bancaria_id).or_default() += if entrada { valor } else { -valor };
+        }
+        for id in estrangeiras.keys() {
+            saldos.insert(*id, na_moeda.get(id).copied().unwrap_or_default());
+        }
+    }
+
+    Ok(contas
+        .iter()
+        .filter_map(|conta| {
+            let id = conta.id?;
+            Some((id, saldos.get(&id).copied().unwrap_or_default()))
+        })
+        .collect())
+}
+
+pub async fn conta_bancaria(repos: &Repositorios, conta: &ContaBancaria) -> Result<Dinheiro> {
+    let Some(id) = conta.id else {
+        return Ok(conta.saldo);
+    };
+    Ok(
+        contas_bancarias(repos, conta.empresa_id, std::slice::from_ref(conta))
+            .await?
+            .remove(&id)
+            .unwrap_or_default(),
+    )
+}
@@ -0,0 +1,71 @@
+//! Alíquotas do ICMS por UF: a interna modal de cada estado e a
+//! interestadual da Resolução do Senado 22/1989 (e 13/2012, para
+//! importados).
+
+use erp_dinheiro::{Aliquota, Decimal};
+
+/// Alíquota interna modal do ICMS de cada UF, em décimos de ponto
+/// percentual. Produtos com alíquota própria a informam no cadastro.
+const INTERNAS: [(&str, i64); 27] = [
+    ("AC", 190),
+    ("AL", 190),
+    ("AM", 200),
+    ("AP", 180),
+    ("BA", 205),
+    ("CE", 200),
+    ("DF", 200),
+    ("ES", 170),
+    ("GO", 190),
+    ("MA", 230),
+    ("MG", 180),
+    ("MS", 170),
+    ("MT", 170),
+    ("PA", 190),
+    ("PB", 200),
+    ("PE", 205),
+    ("PI", 225),
+    ("PR", 195),
+    ("RJ", 200),
+    ("RN", 200),
+    ("RO", 195),
+    ("RR", 200),
+    ("RS", 170),
+    ("SC", 170),
+    ("SE", 190),
+    ("SP", 180),
+    ("TO", 200),
+];
+
+/// Estados do Sul e do Sudeste, exceto o Espírito Santo, que vendem a 7%
+/// para os demais.
+const SUL_SUDESTE: [&str; 6] = ["MG", "PR", "RJ", "RS", "SC", "SP"];
+
+/// Origens de mercadoria importada, ou nacional com conteúdo de importação
+/// acima de 40%, que pagam 4% entre estados.
+const ORIGENS_IMPORTADAS: [u8; 4] = [1, 2, 3, 8];
+
+fn percentual(decimos: i64) -> Aliquota {
+    Aliquota::exata(Decimal::new(decimos, 1)).expect("alíquota da tabela é válida")
+}
+
+/// Alíquota interna modal da UF; `None` para UF desconhecida.
+pub fn interna(uf: &str) -> Option<Aliquota> {
+    INTERNAS
+        .iter()
+        .find(|(sigla, _)| sigla.eq_ignore_ascii_case(uf.trim()))
+        .map(|(_, decimos)| percentual(*decimos))
+}
+
+/// Alíquota interestadual da venda de `origem` para `destino` de mercadoria
+/// com a `origem_mercadoria` (0 a 8) da NF-e.
+pub fn interestadual(origem: &str, destino: &str, origem_mercadoria: u8) -> Aliquota {
+    let origem = origem.trim().to_uppercase();
+    let destino = destino.trim().to_uppercase();
+    if ORIGENS_IMPORTADAS.contains(&origem_mercadoria) {
+        percentual(40)
+    } else if SUL_SUDESTE.contains(&origem.as_str()) && !SUL_SUDESTE.contains(&destino.as_str()) {
+        percentual(70)
+    } else {
+        percentual(120)
+    }
+}
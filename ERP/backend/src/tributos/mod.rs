@@ -0,0 +1,579 @@
+//! Cálculo dos tributos da venda: ICMS próprio, por substituição
+//! tributária (ST) e o diferencial de alíquotas (DIFAL), IPI, PIS e COFINS
+//! de cada item, a partir da classificação fiscal do produto, do regime da
+//! empresa e das UFs do emitente e do cliente.
+//!
+//! As vendas do ERP são a consumidor final, então o IPI entra na base do
+//! ICMS e, na venda para outra UF, o ICMS é cobrado pela alíquota
+//! interestadual e o diferencial vai para a UF de destino.
+
+mod aliquotas;
+
+use aliquotas::interna;
+
+use std::collections::HashMap;
+
+use erp_dinheiro::{Aliquota, Decimal};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{
+    error::{AppError, Result},
+    models::{
+        Cliente, ConfiguracaoFiscal, DadosFiscais, Dinheiro, Produto, RegimeTributario,
+        TotaisTributos, TributosItem, Venda,
+    },
+};
+
+/// Regime do emitente e UFs de origem e de destino da venda.
+#[derive(Debug, Clone)]
+pub struct Operacao {
+    pub regime: RegimeTributario,
+    pub uf_origem: String,
+    pub uf_destino: String,
+}
+
+impl Operacao {
+    /// Sem cliente, ou com cliente sem UF válida, a venda é dentro do
+    /// estado do emitente.
+    pub fn new(fiscal: &ConfiguracaoFiscal, cliente: Option<&Cliente>) -> Self {
+        let uf_origem = fiscal.uf.trim().to_uppercase();
+        let uf_destino = cliente
+            .and_then(|cliente| cliente.estado.as_deref())
+            .map(|uf| uf.trim().to_uppercase())
+            .filter(|uf| interna(uf).is_some())
+            .unwrap_or_else(|| uf_origem.clone());
+        Self {
+            regime: fiscal.regime,
+            uf_origem,
+            uf_destino,
+        }
+    }
+
+    pub fn interestadual(&self) -> bool {
+        self.uf_origem != self.uf_destino
+    }
+}
+
+/// Calcula os tributos de cada item e os totais da venda, com o desconto
+/// rateado pelos itens. O `total_final` passa a incluir o IPI e o ICMS ST.
+/// Todos os produtos precisam de dados fiscais do regime da empresa; em
+/// caso de erro a venda não é alterada.
+pub fn calcular(
+    operacao: &Operacao,
+    venda: &mut Venda,
+    produtos: &HashMap<ObjectId, Produto>,
+) -> Result<()> {
+    let simples = operacao.regime == RegimeTributario::SimplesNacional;
+    let liquido = (venda.total - venda.desconto).max(Dinheiro::ZERO);
+    let subtotais: Vec<Dinheiro> = venda.itens.iter().map(|item| item.subtotal).collect();
+    let descontos = (venda.total - liquido).ratear(&subtotais);
+
+    let calculados = venda
+        .itens
+        .iter()
+        .zip(descontos)
+        .map(|(item, desconto)| {
+            let produto = produtos.get(&item.produto_id).ok_or(AppError::NotFound)?;
+            let fiscal = produto.fiscal.as_ref().ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Informe os dados fiscais do produto {}",
+                    produto.nome
+                ))
+            })?;
+            if fiscal.simples_nacional() != simples {
+                return Err(AppError::BadRequest(format!(
+                    "O produto {} usa {} do ICMS, que não é do regime tributário da empresa",
+                    produto.nome,
+                    if simples { "CST" } else { "CSOSN" }
+                )));
+            }
+            Ok(tributar(
+                operacao,
+                fiscal,
+                item.subtotal - desconto,
+                desconto,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut totais = TotaisTributos::default();
+    for (item, tributos) in venda.itens.iter_mut().zip(calculados) {
+        totais.base_icms += tributos.base_icms;
+        totais.icms += tributos.icms;
+        totais.base_icms_st += tributos.base_icms_st;
+        totais.icms_st += tributos.icms_st;
+        totais.icms_difal += tributos.icms_difal;
+        totais.ipi += tributos.ipi;
+        totais.pis += tributos.pis;
+        totais.cofins += tributos.cofins;
+        item.tributos = Some(tributos);
+    }
+    venda.total_final = liquido + totais.ipi + totais.icms_st;
+    venda.tributos = Some(totais);
+    Ok(())
+}
+
+/// Remove os tributos calculados; o total final volta a ser o total menos
+/// o desconto.
+pub fn limpar(venda: &mut Venda) {
+    for item in &mut venda.itens {
+        item.tributos = None;
+    }
+    venda.tributos = None;
+    venda.total_final = (venda.total - venda.desconto).max(Dinheiro::ZERO);
+}
+
+/// Tributos de um item de `valor` (subtotal menos o `desconto` rateado).
+fn tributar(
+    operacao: &Operacao,
+    fiscal: &DadosFiscais,
+    valor: Dinheiro,
+    desconto: Dinheiro,
+) -> TributosItem {
+    let interestadual = operacao.interestadual();
+    let cst = fiscal.cst_icms.as_str();
+
+    let (base_ipi, aliquota_ipi) = match fiscal.cst_ipi.as_deref() {
+        Some("50" | "99") => (valor, fiscal.aliquota_ipi),
+        _ => (Dinheiro::ZERO, Aliquota::ZERO),
+    };
+    let ipi = aliquota_ipi.sobre(base_ipi);
+    // Na venda a consumidor final o IPI integra a base do ICMS.
+    let valor_com_ipi = valor + ipi;
+
+    let interna_origem = if fiscal.aliquota_icms.is_zero() {
+        interna(&operacao.uf_origem).unwrap_or_default()
+    } else {
+        fiscal.aliquota_icms
+    };
+    let (aliquota_operacao, interna_destino) = if interestadual {
+        (
+            aliquotas::interestadual(&operacao.uf_origem, &operacao.uf_destino, fiscal.origem),
+            interna(&operacao.uf_destino).unwrap_or_default(),
+        )
+    } else {
+        (interna_origem, interna_origem)
+    };
+
+    let (reducao_base_icms, base_icms) = match cst {
+        "00" | "10" => (Aliquota::ZERO, valor_com_ipi),
+        "20" => (
+            fiscal.reducao_base_icms,
+            valor_com_ipi - fiscal.reducao_base_icms.sobre(valor_com_ipi),
+        ),
+        _ => (Aliquota::ZERO, Dinheiro::ZERO),
+    };
+    let aliquota_icms = if matches!(cst, "00" | "10" | "20") {
+        aliquota_operacao
+    } else {
+        Aliquota::ZERO
+    };
+    let icms = aliquota_icms.sobre(base_icms);
+
+    let (mva_st, base_icms_st, aliquota_icms_st, icms_st) = if fiscal.com_st() {
+        let mva = if interestadual {
+            mva_ajustada(fiscal.mva_st, aliquota_operacao, interna_destino)
+        } else {
+            fiscal.mva_st
+        };
+        let base = valor_com_ipi + mva.sobre(valor_com_ipi);
+        // No Simples (CSOSN 202) o ICMS próprio não é destacado, mas ainda
+        // é deduzido do imposto retido.
+        let proprio = aliquota_operacao.sobre(valor_com_ipi);
+        let retido = (interna_destino.sobre(base) - proprio).max(Dinheiro::ZERO);
+        (mva, base, interna_destino, retido)
+    } else {
+        (
+            Aliquota::ZERO,
+            Dinheiro::ZERO,
+            Aliquota::ZERO,
+            Dinheiro::ZERO,
+        )
+    };
+
+    // O diferencial fica com quem vende no regime normal sem ST; o Simples
+    // está dispensado dele.
+    let (aliquota_icms_destino, icms_difal) = if interestadual
+        && matches!(cst, "00" | "20")
+        && operacao.regime == RegimeTributario::Normal
+    {
+        (
+            interna_destino,
+            (interna_destino.sobre(base_icms) - icms).max(Dinheiro::ZERO),
+        )
+    } else {
+        (Aliquota::ZERO, Dinheiro::ZERO)
+    };
+
+    let (base_pis_cofins, aliquota_pis, aliquota_cofins) =
+        if matches!(fiscal.cst_pis_cofins.as_str(), "01" | "02" | "49" | "99") {
+            // O ICMS destacado não compõe a base do PIS e da COFINS.
+            (
+                (valor - icms).max(Dinheiro::ZERO),
+                fiscal.aliquota_pis,
+                fiscal.aliquota_cofins,
+            )
+        } else {
+            (Dinheiro::ZERO, Aliquota::ZERO, Aliquota::ZERO)
+        };
+
+    TributosItem {
+        cfop: if interestadual {
+            format!("6{}", &fiscal.cfop[1..])
+        } else {
+            fiscal.cfop.clone()
+        },
+        origem: fiscal.origem,
+        cst_icms: fiscal.cst_icms.clone(),
+        desconto,
+        base_icms,
+        reducao_base_icms,
+        aliquota_icms,
+        icms,
+        mva_st,
+        base_icms_st,
+        aliquota_icms_st,
+        icms_st,
+        aliquota_icms_destino,
+        icms_difal,
+        cst_ipi: fiscal.cst_ipi.clone(),
+        base_ipi,
+        aliquota_ipi,
+        ipi,
+        cst_pis_cofins: fiscal.cst_pis_cofins.clone(),
+        base_pis_cofins,
+        aliquota_pis,
+        pis: aliquota_pis.sobre(base_pis_cofins),
+        aliquota_cofins,
+        cofins: aliquota_cofins.sobre(base_pis_cofins),
+    }
+}
+
+/// MVA ajustada da venda para outra UF, quando a alíquota interestadual é
+/// menor que a interna do destino: `(1 + MVA) × (1 − inter) / (1 − intra) − 1`.
+fn mva_ajustada(mva: Aliquota, interestadual: Aliquota, interna_destino: Aliquota) -> Aliquota {
+    if interestadual >= interna_destino {
+        return mva;
+    }
+    let cem = Decimal::ONE_HUNDRED;
+    let fator =
+        (cem + mva.decimal()) * (cem - interestadual.decimal()) / (cem - interna_destino.decimal());
+    Aliquota::arredondar(fator - cem)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::{aliquotas::interestadual, *};
+    use crate::models::{ItemVenda, StatusVenda};
+
+    fn d(valor: &str) -> Dinheiro {
+        valor.parse().unwrap()
+    }
+
+    fn a(valor: &str) -> Aliquota {
+        valor.parse().unwrap()
+    }
+
+    fn operacao(regime: RegimeTributario, origem: &str, destino: &str) -> Operacao {
+        Operacao {
+            regime,
+            uf_origem: origem.to_string(),
+            uf_destino: destino.to_string(),
+        }
+    }
+
+    fn normal(origem: &str, destino: &str) -> Operacao {
+        operacao(RegimeTributario::Normal, origem, destino)
+    }
+
+    /// Produto do regime normal com a alíquota modal da UF e PIS e COFINS
+    /// não cumulativos.
+    fn fiscal(cst_icms: &str) -> DadosFiscais {
+        DadosFiscais {
+            ncm: "84713012".to_string(),
+            cfop: "5102".to_string(),
+            origem: 0,
+            cst_icms: cst_icms.to_string(),
+            aliquota_icms: Aliquota::ZERO,
+            reducao_base_icms: Aliquota::ZERO,
+            cest: None,
+            mva_st: Aliquota::ZERO,
+            cst_ipi: None,
+            aliquota_ipi: Aliquota::ZERO,
+            cst_pis_cofins: "01".to_string(),
+            aliquota_pis: a("1.65"),
+            aliquota_cofins: a("7.6"),
+        }
+    }
+
+    /// Venda com uma unidade de cada produto, dado pelo preço e pelos dados
+    /// fiscais.
+    fn vender(
+        operacao: &Operacao,
+        fiscais: Vec<(&str, DadosFiscais)>,
+        desconto: &str,
+    ) -> Result<Venda> {
+        let agora = Utc::now();
+        let empresa_id = ObjectId::new();
+        let mut produtos = HashMap::new();
+        let mut itens = Vec::new();
+        for (preco, fiscal) in fiscais {
+            let produto = Produto {
+                id: Some(ObjectId::new()),
+                empresa_id,
+                nome: "Produto".to_string(),
+                descricao: None,
+                codigo_barras: None,
+                preco_custo: Dinheiro::ZERO,
+                preco_venda: d(preco),
+                estoque_atual: 10,
+                estoque_minimo: 0,
+                unidade: "UN".to_string(),
+                fiscal: Some(fiscal),
+                ativo: true,
+                created_at: agora,
+                updated_at: agora,
+            };
+            itens.push(ItemVenda {
+                id: ObjectId::new(),
+                produto_id: produto.id.unwrap(),
+                produto_nome: produto.nome.clone(),
+                quantidade: 1,
+                preco_unitario: d(preco),
+                subtotal: d(preco),
+                tributos: None,
+            });
+            produtos.insert(produto.id.unwrap(), produto);
+        }
+        let total = itens.iter().map(|item| item.subtotal).sum();
+        let mut venda = Venda {
+            id: Some(ObjectId::new()),
+            empresa_id,
+            cliente_id: None,
+            itens,
+            total,
+            desconto: d(desconto),
+            total_final: total,
+            tributos: None,
+            forma_pagamento: "dinheiro".to_string(),
+            status: StatusVenda::Aberta,
+            observacoes: None,
+            usuario: None,
+            created_at: agora,
+            updated_at: agora,
+        };
+        calcular(operacao, &mut venda, &produtos)?;
+        Ok(venda)
+    }
+
+    fn item(operacao: &Operacao, preco: &str, fiscal: DadosFiscais) -> TributosItem {
+        let venda = vender(operacao, vec![(preco, fiscal)], "0").unwrap();
+        venda.itens[0].tributos.clone().unwrap()
+    }
+
+    #[test]
+    fn aliquotas_interestaduais() {
+        assert_eq!(interestadual("SP", "RJ", 0), a("12"));
+        assert_eq!(interestadual("SP", "BA", 0), a("7"));
+        assert_eq!(interestadual("sp", "es", 0), a("7"));
+        assert_eq!(interestadual("BA", "SP", 0), a("12"));
+        assert_eq!(interestadual("ES", "BA", 0), a("12"));
+        assert_eq!(interestadual("SP", "BA", 1), a("4"));
+        assert_eq!(interestadual("GO", "PR", 8), a("4"));
+        assert_eq!(interna("BA"), Some(a("20.5")));
+        assert_eq!(interna("XX"), None);
+    }
+
+    #[test]
+    fn icms_dentro_do_estado_pela_aliquota_modal() {
+        let tributos = item(&normal("SP", "SP"), "100.00", fiscal("00"));
+        assert_eq!(tributos.cfop, "5102");
+        assert_eq!(tributos.base_icms, d("100.00"));
+        assert_eq!(tributos.aliquota_icms, a("18"));
+        assert_eq!(tributos.icms, d("18.00"));
+        assert_eq!(tributos.icms_difal, Dinheiro::ZERO);
+        // PIS e COFINS sem o ICMS na base: 82,00 × 1,65% e × 7,6%.
+        assert_eq!(tributos.base_pis_cofins, d("82.00"));
+        assert_eq!(tributos.pis, d("1.35"));
+        assert_eq!(tributos.cofins, d("6.23"));
+    }
+
+    #[test]
+    fn aliquota_propria_do_produto() {
+        let mut fiscal = fiscal("00");
+        fiscal.aliquota_icms = a("12");
+        let tributos = item(&normal("SP", "SP"), "250.00", fiscal);
+        assert_eq!(tributos.icms, d("30.00"));
+    }
+
+    #[test]
+    fn ipi_por_fora_e_na_base_do_icms() {
+        let mut fiscal = fiscal("00");
+        fiscal.cst_ipi = Some("50".to_string());
+        fiscal.aliquota_ipi = a("10");
+        let venda = vender(&normal("SP", "SP"), vec![("100.00", fiscal)], "0").unwrap();
+        let tributos = venda.itens[0].tributos.as_ref().unwrap();
+        assert_eq!(tributos.base_ipi, d("100.00"));
+        assert_eq!(tributos.ipi, d("10.00"));
+        assert_eq!(tributos.base_icms, d("110.00"));
+        assert_eq!(tributos.icms, d("19.80"));
+        assert_eq!(tributos.base_pis_cofins, d("80.20"));
+        assert_eq!(venda.total_final, d("110.00"));
+    }
+
+    #[test]
+    fn ipi_isento_nao_tem_valor() {
+        let mut fiscal = fiscal("00");
+        fiscal.cst_ipi = Some("52".to_string());
+        fiscal.aliquota_ipi = a("10");
+        let tributos = item(&normal("SP", "SP"), "100.00", fiscal);
+        assert_eq!(tributos.cst_ipi.as_deref(), Some("52"));
+        assert_eq!(tributos.ipi, Dinheiro::ZERO);
+        assert_eq!(tributos.base_icms, d("100.00"));
+    }
+
+    #[test]
+    fn base_reduzida_no_cst_20() {
+        let mut fiscal = fiscal("20");
+        fiscal.reducao_base_icms = a("61.11");
+        let tributos = item(&normal("SP", "SP"), "100.00", fiscal);
+        assert_eq!(tributos.reducao_base_icms, a("61.11"));
+        assert_eq!(tributos.base_icms, d("38.89"));
+        assert_eq!(tributos.icms, d("7.00"));
+    }
+
+    #[test]
+    fn substituicao_tributaria_dentro_do_estado() {
+        let mut fiscal = fiscal("10");
+        fiscal.cest = Some("2104400".to_string());
+        fiscal.mva_st = a("40");
+        let venda = vender(&normal("SP", "SP"), vec![("100.00", fiscal)], "0").unwrap();
+        let tributos = venda.itens[0].tributos.as_ref().unwrap();
+        assert_eq!(tributos.icms, d("18.00"));
+        assert_eq!(tributos.mva_st, a("40"));
+        assert_eq!(tributos.base_icms_st, d("140.00"));
+        assert_eq!(tributos.aliquota_icms_st, a("18"));
+        // 140,00 × 18% − 18,00 do ICMS próprio.
+        assert_eq!(tributos.icms_st, d("7.20"));
+        assert_eq!(venda.total_final, d("107.20"));
+    }
+
+    #[test]
+    fn substituicao_tributaria_para_outra_uf_com_mva_ajustada() {
+        let mut fiscal = fiscal("10");
+        fiscal.cest = Some("2104400".to_string());
+        fiscal.mva_st = a("40");
+        let tributos = item(&normal("SP", "PR"), "100.00", fiscal);
+        assert_eq!(tributos.cfop, "6102");
+        assert_eq!(tributos.aliquota_icms, a("12"));
+        assert_eq!(tributos.icms, d("12.00"));
+        // 1,40 × 0,88 / 0,805 − 1.
+        assert_eq!(tributos.mva_st, a("53.0435"));
+        assert_eq!(tributos.base_icms_st, d("153.04"));
+        assert_eq!(tributos.aliquota_icms_st, a("19.5"));
+        assert_eq!(tributos.icms_st, d("17.84"));
+        assert_eq!(tributos.icms_difal, Dinheiro::ZERO);
+    }
+
+    #[test]
+    fn difal_na_venda_a_consumidor_de_outra_uf() {
+        let tributos = item(&normal("SP", "BA"), "100.00", fiscal("00"));
+        assert_eq!(tributos.cfop, "6102");
+        assert_eq!(tributos.aliquota_icms, a("7"));
+        assert_eq!(tributos.icms, d("7.00"));
+        assert_eq!(tributos.aliquota_icms_destino, a("20.5"));
+        assert_eq!(tributos.icms_difal, d("13.50"));
+
+        let mut importado = fiscal("00");
+        importado.origem = 1;
+        let tributos = item(&normal("SP", "BA"), "100.00", importado);
+        assert_eq!(tributos.aliquota_icms, a("4"));
+        assert_eq!(tributos.icms_difal, d("16.50"));
+    }
+
+    #[test]
+    fn isento_e_cobrado_anteriormente_sem_icms() {
+        for cst in ["40", "41", "60"] {
+            let tributos = item(&normal("SP", "BA"), "100.00", fiscal(cst));
+            assert_eq!(tributos.base_icms, Dinheiro::ZERO);
+            assert_eq!(tributos.icms, Dinheiro::ZERO);
+            assert_eq!(tributos.icms_difal, Dinheiro::ZERO);
+            assert_eq!(tributos.base_pis_cofins, d("100.00"));
+        }
+    }
+
+    #[test]
+    fn simples_nacional_sem_icms_destacado() {
+        let simples = operacao(RegimeTributario::SimplesNacional, "SP", "BA");
+        let mut fiscal = fiscal("102");
+        fiscal.cst_pis_cofins = "99".to_string();
+        fiscal.aliquota_pis = Aliquota::ZERO;
+        fiscal.aliquota_cofins = Aliquota::ZERO;
+        let tributos = item(&simples, "100.00", fiscal);
+        assert_eq!(tributos.cfop, "6102");
+        assert_eq!(tributos.icms, Dinheiro::ZERO);
+        assert_eq!(tributos.icms_difal, Dinheiro::ZERO);
+        assert_eq!(tributos.pis, Dinheiro::ZERO);
+        assert_eq!(tributos.cofins, Dinheiro::ZERO);
+    }
+
+    #[test]
+    fn simples_nacional_com_st_deduz_o_icms_proprio() {
+        let simples = operacao(RegimeTributario::SimplesNacional, "SP", "SP");
+        let mut fiscal = fiscal("202");
+        fiscal.cest = Some("2104400".to_string());
+        fiscal.mva_st = a("40");
+        let tributos = item(&simples, "100.00", fiscal);
+        assert_eq!(tributos.icms, Dinheiro::ZERO);
+        assert_eq!(tributos.base_icms_st, d("140.00"));
+        assert_eq!(tributos.icms_st, d("7.20"));
+    }
+
+    #[test]
+    fn desconto_rateado_e_totais() {
+        let mut com_ipi = fiscal("00");
+        com_ipi.cst_ipi = Some("50".to_string());
+        com_ipi.aliquota_ipi = a("5");
+        let venda = vender(
+            &normal("SP", "SP"),
+            vec![("100.00", fiscal("00")), ("50.00", com_ipi)],
+            "30.00",
+        )
+        .unwrap();
+        let primeiro = venda.itens[0].tributos.as_ref().unwrap();
+        let segundo = venda.itens[1].tributos.as_ref().unwrap();
+        assert_eq!(primeiro.desconto, d("20.00"));
+        assert_eq!(primeiro.base_icms, d("80.00"));
+        assert_eq!(segundo.desconto, d("10.00"));
+        assert_eq!(segundo.ipi, d("2.00"));
+        assert_eq!(segundo.base_icms, d("42.00"));
+
+        let totais = venda.tributos.as_ref().unwrap();
+        assert_eq!(totais.base_icms, d("122.00"));
+        assert_eq!(totais.icms, d("21.96"));
+        assert_eq!(totais.ipi, d("2.00"));
+        assert_eq!(venda.total_final, d("122.00"));
+    }
+
+    #[test]
+    fn desconto_maior_que_o_total_zera_os_itens() {
+        let venda = vender(&normal("SP", "SP"), vec![("40.00", fiscal("00"))], "50.00").unwrap();
+        assert_eq!(
+            venda.itens[0].tributos.as_ref().unwrap().desconto,
+            d("40.00")
+        );
+        assert_eq!(venda.tributos.as_ref().unwrap().icms, Dinheiro::ZERO);
+        assert_eq!(venda.total_final, Dinheiro::ZERO);
+    }
+
+    #[test]
+    fn recusa_cst_de_outro_regime() {
+        let simples = operacao(RegimeTributario::SimplesNacional, "SP", "SP");
+        assert!(matches!(
+            vender(&simples, vec![("10.00", fiscal("00"))], "0"),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
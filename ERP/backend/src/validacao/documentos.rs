@@ -0,0 +1,348 @@
+//! Dígitos verificadores e formatos de documentos e dados bancários.
+
+/// Remove a pontuação usual de documentos (`.`, `-`, `/` e espaços).
+fn sem_pontuacao(valor: &str) -> String {
+    valor
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '/' | ' '))
+        .collect()
+}
+
+fn digitos(valor: &str) -> Option<Vec<u32>> {
+    valor.chars().map(|c| c.to_digit(10)).collect()
+}
+
+pub fn somente_digitos(valor: &str) -> String {
+    valor.chars().filter(char::is_ascii_digit).collect()
+}
+
+/// Texto em ASCII para os layouts bancários (BR Code, CNAB): as letras
+/// acentuadas perdem o acento e os demais caracteres fora do ASCII, ou de
+/// controle, são descartados.
+pub fn sem_acentos(texto: &str) -> String {
+    texto
+        .chars()
+        .filter_map(|caractere| match caractere {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => Some('a'),
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => Some('A'),
+            'é' | 'è' | 'ê' | 'ë' => Some('e'),
+            'É' | 'È' | 'Ê' | 'Ë' => Some('E'),
+            'í' | 'ì' | 'î' | 'ï' => Some('i'),
+            'Í' | 'Ì' | 'Î' | 'Ï' => Some('I'),
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => Some('o'),
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => Some('O'),
+            'ú' | 'ù' | 'û' | 'ü' => Some('u'),
+            'Ú' | 'Ù' | 'Û' | 'Ü' => Some('U'),
+            'ç' => Some('c'),
+            'Ç' => Some('C'),
+            'ñ' => Some('n'),
+            'Ñ' => Some('N'),
+            caractere if caractere.is_ascii() && !caractere.is_ascii_control() => Some(caractere),
+            _ => None,
+        })
+        .collect()
+}
+
+/// CPF (`000.000.000-00`) ou CNPJ (`00.000.000/0000-00`) com a pontuação
+/// usual; outros documentos ficam só com os algarismos.
+pub fn documento_formatado(documento: &str) -> String {
+    let digitos = somente_digitos(documento);
+    match digitos.len() {
+        11 => format!(
+            "{}.{}.{}-{}",
+            &digitos[..3],
+            &digitos[3..6],
+            &digitos[6..9],
+            &digitos[9..]
+        ),
+        14 => format!(
+            "{}.{}.{}/{}-{}",
+            &digitos[..2],
+            &digitos[2..5],
+            &digitos[5..8],
+            &digitos[8..12],
+            &digitos[12..]
+        ),
+        _ => digitos,
+    }
+}
+
+/// CPF com 11 dígitos e verificadores corretos.
+pub fn cpf_valido(valor: &str) -> bool {
+    let Some(d) = digitos(&sem_pontuacao(valor)) else {
+        return false;
+    };
+    if d.len() != 11 || d.iter().all(|&x| x == d[0]) {
+        return false;
+    }
+
+    let verificador = |n: usize| {
+        let soma: u32 = (0..n).map(|i| d[i] * (n as u32 + 1 - i as u32)).sum();
+        (soma * 10) % 11 % 10
+    };
+    verificador(9) == d[9] && verificador(10) == d[10]
+}
+
+/// CNPJ numérico ou alfanumérico (12 posições em `0-9A-Z` seguidas de dois
+/// dígitos verificadores), com os verificadores corretos.
+pub fn cnpj_valido(valor: &str) -> bool {
+    let limpo = sem_pontuacao(valor).to_uppercase();
+    // Tamanho e fatias abaixo contam bytes, que só equivalem a posições em
+    // ASCII.
+    if !limpo.is_ascii() || limpo.len() != 14 {
+        return false;
+    }
+
+    let (base, dv) = limpo.split_at(12);
+    let Some(dv) = digitos(dv) else {
+        return false;
+    };
+    if !base
+        .chars()
+        .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+    {
+        return false;
+    }
+    // No CNPJ alfanumérico cada posição vale o código ASCII menos 48, o que
+    // mantém os valores dos dígitos numéricos.
+    let mut valores: Vec<u32> = base.chars().map(|c| c as u32 - 48).collect();
+    if valores.iter().all(|&x| x == valores[0]) {
+        return false;
+    }
+
+    let verificador = |valores: &[u32]| {
+        let soma: u32 = valores
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, v)| v * (i as u32 % 8 + 2))
+            .sum();
+        match soma % 11 {
+            0 | 1 => 0,
+            resto => 11 - resto,
+        }
+    };
+
+    let primeiro = verificador(&valores);
+    valores.push(primeiro);
+    primeiro == dv[0] && verificador(&valores) == dv[1]
+}
+
+/// NIF português com 9 dígitos, prefixo atribuível e verificador correto.
+pub fn nif_valido(valor: &str) -> bool {
+    let limpo = sem_pontuacao(valor);
+    let Some(d) = digitos(&limpo) else {
+        return false;
+    };
+    if d.len() != 9 {
+        return false;
+    }
+
+    const PREFIXOS_DUPLOS: [&str; 12] = [
+        "45", "70", "71", "72", "74", "75", "77", "79", "90", "91", "98", "99",
+    ];
+    if !matches!(d[0], 1 | 2 | 3 | 5 | 6 | 8) && !PREFIXOS_DUPLOS.contains(&&limpo[..2]) {
+        return false;
+    }
+
+    let soma: u32 = (0..8).map(|i| d[i] * (9 - i as u32)).sum();
+    let verificador = match soma % 11 {
+        0 | 1 => 0,
+        resto => 11 - resto,
+    };
+    verificador == d[8]
+}
+
+/// Documento de pessoa física ou jurídica aceito no cadastro: CPF, CNPJ ou
+/// NIF, identificado pela quantidade de posições.
+pub fn documento_valido(valor: &str) -> bool {
+    let limpo = sem_pontuacao(valor);
+    if !limpo.is_ascii() {
+        return false;
+    }
+    match limpo.len() {
+        9 => nif_valido(valor),
+        11 => cpf_valido(valor),
+        14 => cnpj_valido(valor),
+        _ => false,
+    }
+}
+
+/// IBAN com estrutura e dígitos de controle (ISO 13616, módulo 97) válidos.
+pub fn iban_valido(valor: &str) -> bool {
+    let iban: String = valor
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    if !(15..=34).contains(&iban.len()) || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return false;
+    }
+
+    let (pais, resto) = iban.split_at(2);
+    if !pais.chars().all(|c| c.is_ascii_uppercase())
+        || !resto[..2].chars().all(|c| c.is_ascii_digit())
+    {
+        return false;
+    }
+    let tamanho_esperado = match pais {
+        "PT" => Some(25),
+        "BR" => Some(29),
+        "ES" => Some(24),
+        "FR" | "IT" => Some(27),
+        "DE" | "GB" | "IE" => Some(22),
+        _ => None,
+    };
+    if tamanho_esperado.is_some_and(|tamanho| tamanho != iban.len()) {
+        return false;
+    }
+
+    let rearranjado = iban[4..].chars().chain(iban[..4].chars());
+    let resto = rearranjado.fold(0u32, |acc, c| {
+        let valor = c.to_digit(36).unwrap_or(0);
+        if valor >= 10 {
+            (acc * 100 + valor) % 97
+        } else {
+            (acc * 10 + valor) % 97
+        }
+    });
+    resto == 1
+}
+
+/// Indica se o valor parece um IBAN (começa com o código do país).
+pub fn parece_iban(valor: &str) -> bool {
+    let mut chars = valor.trim().chars();
+    matches!(
+        (chars.next(), chars.next()),
+        (Some(a), Some(b)) if a.is_ascii_alphabetic() && b.is_ascii_alphabetic()
+    )
+}
+
+/// Número de cartão com 13 a 19 dígitos (espaços e hífens são ignorados)
+/// e dígito verificador de Luhn correto.
+pub fn cartao_valido(valor: &str) -> bool {
+    let limpo: String = valor.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    let Some(d) = digitos(&limpo) else {
+        return false;
+    };
+    if !(13..=19).contains(&d.len()) {
+        return false;
+    }
+
+    let soma: u32 = d
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &x)| {
+            if i % 2 == 1 {
+                let dobro = x * 2;
+                if dobro > 9 {
+                    dobro - 9
+                } else {
+                    dobro
+                }
+            } else {
+                x
+            }
+        })
+        .sum();
+    soma.is_multiple_of(10)
+}
+
+/// Número com até `max` dígitos e, opcionalmente, um dígito verificador
+/// separado por hífen (que pode ser `X`), como `1234-5`.
+fn numero_com_dv(valor: &str, max: usize) -> bool {
+    let (numero, dv) = match valor.trim().split_once('-') {
+        Some((numero, dv)) => (numero, Some(dv)),
+        None => (valor.trim(), None),
+    };
+    let numero_ok =
+        !numero.is_empty() && numero.len() <= max && numero.chars().all(|c| c.is_ascii_digit());
+    let dv_ok = dv.is_none_or(|dv| {
+        dv.len() == 1
+            && dv
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == 'X' || c == 'x')
+    });
+    numero_ok && dv_ok
+}
+
+pub fn agencia_valida(valor: &str) -> bool {
+    numero_com_dv(valor, 5)
+}
+
+pub fn conta_valida(valor: &str) -> bool {
+    numero_com_dv(valor, 13)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpf() {
+        assert!(cpf_valido("529.982.247-25"));
+        assert!(cpf_valido("52998224725"));
+        assert!(!cpf_valido("529.982.247-24"));
+        assert!(!cpf_valido("111.111.111-11"));
+        assert!(!cpf_valido("5299822472"));
+        assert!(!cpf_valido("5299822472é"));
+    }
+
+    #[test]
+    fn cnpj_numerico_e_alfanumerico() {
+        assert!(cnpj_valido("11.222.333/0001-81"));
+        assert!(!cnpj_valido("11.222.333/0001-80"));
+        assert!(!cnpj_valido("00.000.000/0000-00"));
+        assert!(cnpj_valido("12.ABC.345/01DE-35"));
+        assert!(cnpj_valido("12abc34501de35"));
+        assert!(!cnpj_valido("12.ABC.345/01DE-36"));
+        assert!(!cnpj_valido("12.ABC.345/01D*-35"));
+    }
+
+    #[test]
+    fn entrada_fora_do_ascii_nao_entra_em_panico() {
+        // 14 bytes com um caractere de dois bytes na posição 12.
+        assert!(!cnpj_valido("12345678901é1"));
+        assert!(!cnpj_valido("1234567890ééé"));
+        assert!(!documento_valido("12345678901é1"));
+        assert!(!documento_valido("1234567é9"));
+        assert!(!nif_valido("12345678é"));
+        assert!(!iban_valido("PT50ééé20123123456789015"));
+        assert!(!cartao_valido("4111 1111 1111 111é"));
+    }
+
+    #[test]
+    fn nif() {
+        assert!(nif_valido("123456789"));
+        assert!(nif_valido("123 456 789"));
+        assert!(!nif_valido("123456788"));
+        assert!(!nif_valido("423456780"));
+        assert!(nif_valido("451234561"));
+    }
+
+    #[test]
+    fn documento_pelo_tamanho() {
+        assert!(documento_valido("123456789"));
+        assert!(documento_valido("529.982.247-25"));
+        assert!(documento_valido("11.222.333/0001-81"));
+        assert!(!documento_valido("1234567890"));
+    }
+
+    #[test]
+    fn iban() {
+        assert!(iban_valido("GB82 WEST 1234 5698 7654 32"));
+        assert!(iban_valido("pt50000201231234567890154"));
+        assert!(!iban_valido("PT50000201231234567890155"));
+        assert!(!iban_valido("PT5000020123123456789015"));
+        assert!(!iban_valido("GB82"));
+    }
+
+    #[test]
+    fn cartao_luhn() {
+        assert!(cartao_valido("4111 1111 1111 1111"));
+        assert!(cartao_valido("5555-5555-5555-4444"));
+        assert!(!cartao_valido("4111111111111112"));
+        assert!(!cartao_valido("411111111111"));
+    }
+}
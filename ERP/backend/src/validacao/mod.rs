@@ -0,0 +1,73 @@
+//! Validação dos payloads da API. Os erros de todos os campos são
+//! acumulados e devolvidos de uma vez como `AppError::Validacao` (422).
+
+mod documentos;
+
+pub use documentos::*;
+
+use erp_dinheiro::Dinheiro;
+
+use crate::error::{AppError, ErroCampo, Result};
+
+/// Payloads que conferem os próprios campos antes de serem gravados.
+pub trait Validar {
+    fn validar(&self) -> Result<()>;
+}
+
+/// Acumula os campos inválidos de um payload.
+#[derive(Debug, Default)]
+pub struct Validacao {
+    erros: Vec<ErroCampo>,
+}
+
+impl Validacao {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra `mensagem` para o campo quando `valido` for falso.
+    pub fn checar(&mut self, campo: &str, valido: bool, mensagem: &str) -> &mut Self {
+        if !valido {
+            self.erros.push(ErroCampo {
+                campo: campo.to_string(),
+                mensagem: mensagem.to_string(),
+            });
+        }
+        self
+    }
+
+    pub fn obrigatorio(&mut self, campo: &str, valor: &str) -> &mut Self {
+        self.checar(campo, !valor.trim().is_empty(), "Campo obrigatório")
+    }
+
+    pub fn concluir(&mut self) -> Result<()> {
+        if self.erros.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validacao(std::mem::take(&mut self.erros)))
+        }
+    }
+}
+
+/// Soma valores recebidos na requisição; acima de [`Dinheiro::MAXIMO`] é
+/// um erro de validação em `campo`.
+pub fn somar(campo: &str, valores: impl IntoIterator<Item = Dinheiro>) -> Result<Dinheiro> {
+    Dinheiro::checked_sum(valores).ok_or_else(|| acima_do_limite(campo))
+}
+
+/// Preço vezes quantidade, com o mesmo limite de [`somar`].
+pub fn multiplicar(campo: &str, preco: Dinheiro, quantidade: i32) -> Result<Dinheiro> {
+    preco
+        .checked_mul(i64::from(quantidade))
+        .ok_or_else(|| acima_do_limite(campo))
+}
+
+fn acima_do_limite(campo: &str) -> AppError {
+    AppError::Validacao(vec![ErroCampo {
+        campo: campo.to_string(),
+        mensagem: format!(
+            "O valor passa do limite de {}",
+            Dinheiro::MAXIMO.formatado()
+        ),
+    }])
+}
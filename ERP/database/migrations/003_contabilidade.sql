@@ -0,0 +1,42 @@
+-- Plano de contas e livro diário. As partidas só recebem inserções; os
+-- estornos são partidas novas.
+
+CREATE TABLE contas_contabeis (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    codigo TEXT NOT NULL,
+    nome TEXT NOT NULL,
+    tipo TEXT NOT NULL,
+    conta_bancaria_id TEXT,
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (empresa_id, codigo)
+);
+
+-- linhas em JSON, repetidas em partidas_linhas para os saldos
+CREATE TABLE partidas (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    historico TEXT NOT NULL,
+    origem TEXT NOT NULL,
+    origem_id TEXT,
+    estorno_de TEXT,
+    linhas TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_partidas_empresa ON partidas(empresa_id, data);
+CREATE INDEX idx_partidas_origem ON partidas(empresa_id, origem, origem_id);
+
+CREATE TABLE partidas_linhas (
+    partida_id TEXT NOT NULL,
+    empresa_id TEXT NOT NULL,
+    conta_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    debito DOUBLE PRECISION NOT NULL DEFAULT 0,
+    credito DOUBLE PRECISION NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_partidas_linhas_conta ON partidas_linhas(empresa_id, conta_id, data);
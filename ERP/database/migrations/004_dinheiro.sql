@@ -0,0 +1,65 @@
+-- Valores monetários passam a ser guardados em centavos (BIGINT), sem as
+-- perdas de arredondamento do ponto flutuante. O SQLite não altera o tipo
+-- de uma coluna: cada uma é renomeada, recriada como BIGINT, preenchida com
+-- o valor arredondado e removida. As listas em JSON (itens da venda e
+-- linhas da partida) continuam legíveis e passam a texto na próxima gravação.
+
+ALTER TABLE produtos RENAME COLUMN preco_custo TO preco_custo_real;
+ALTER TABLE produtos ADD COLUMN preco_custo BIGINT NOT NULL DEFAULT 0;
+UPDATE produtos SET preco_custo = CAST(ROUND(preco_custo_real * 100) AS INTEGER);
+ALTER TABLE produtos DROP COLUMN preco_custo_real;
+ALTER TABLE produtos RENAME COLUMN preco_venda TO preco_venda_real;
+ALTER TABLE produtos ADD COLUMN preco_venda BIGINT NOT NULL DEFAULT 0;
+UPDATE produtos SET preco_venda = CAST(ROUND(preco_venda_real * 100) AS INTEGER);
+ALTER TABLE produtos DROP COLUMN preco_venda_real;
+
+ALTER TABLE vendas RENAME COLUMN total TO total_real;
+ALTER TABLE vendas ADD COLUMN total BIGINT NOT NULL DEFAULT 0;
+UPDATE vendas SET total = CAST(ROUND(total_real * 100) AS INTEGER);
+ALTER TABLE vendas DROP COLUMN total_real;
+ALTER TABLE vendas RENAME COLUMN desconto TO desconto_real;
+ALTER TABLE vendas ADD COLUMN desconto BIGINT NOT NULL DEFAULT 0;
+UPDATE vendas SET desconto = CAST(ROUND(desconto_real * 100) AS INTEGER);
+ALTER TABLE vendas DROP COLUMN desconto_real;
+ALTER TABLE vendas RENAME COLUMN total_final TO total_final_real;
+ALTER TABLE vendas ADD COLUMN total_final BIGINT NOT NULL DEFAULT 0;
+UPDATE vendas SET total_final = CAST(ROUND(total_final_real * 100) AS INTEGER);
+ALTER TABLE vendas DROP COLUMN total_final_real;
+
+ALTER TABLE contas_bancarias RENAME COLUMN saldo TO saldo_real;
+ALTER TABLE contas_bancarias ADD COLUMN saldo BIGINT NOT NULL DEFAULT 0;
+UPDATE contas_bancarias SET saldo = CAST(ROUND(saldo_real * 100) AS INTEGER);
+ALTER TABLE contas_bancarias DROP COLUMN saldo_real;
+
+ALTER TABLE cartoes RENAME COLUMN limite TO limite_real;
+ALTER TABLE cartoes ADD COLUMN limite BIGINT NOT NULL DEFAULT 0;
+UPDATE cartoes SET limite = CAST(ROUND(limite_real * 100) AS INTEGER);
+ALTER TABLE cartoes DROP COLUMN limite_real;
+
+ALTER TABLE lancamentos RENAME COLUMN valor TO valor_real;
+ALTER TABLE lancamentos ADD COLUMN valor BIGINT NOT NULL DEFAULT 0;
+UPDATE lancamentos SET valor = CAST(ROUND(valor_real * 100) AS INTEGER);
+ALTER TABLE lancamentos DROP COLUMN valor_real;
+ALTER TABLE lancamentos RENAME COLUMN valor_pago TO valor_pago_real;
+ALTER TABLE lancamentos ADD COLUMN valor_pago BIGINT;
+UPDATE lancamentos SET valor_pago = CAST(ROUND(valor_pago_real * 100) AS INTEGER);
+ALTER TABLE lancamentos DROP COLUMN valor_pago_real;
+
+ALTER TABLE compras_cartao RENAME COLUMN valor TO valor_real;
+ALTER TABLE compras_cartao ADD COLUMN valor BIGINT NOT NULL DEFAULT 0;
+UPDATE compras_cartao SET valor = CAST(ROUND(valor_real * 100) AS INTEGER);
+ALTER TABLE compras_cartao DROP COLUMN valor_real;
+
+ALTER TABLE transacoes_bancarias RENAME COLUMN valor TO valor_real;
+ALTER TABLE transacoes_bancarias ADD COLUMN valor BIGINT NOT NULL DEFAULT 0;
+UPDATE transacoes_bancarias SET valor = CAST(ROUND(valor_real * 100) AS INTEGER);
+ALTER TABLE transacoes_bancarias DROP COLUMN valor_real;
+
+ALTER TABLE partidas_linhas RENAME COLUMN debito TO debito_real;
+ALTER TABLE partidas_linhas ADD COLUMN debito BIGINT NOT NULL DEFAULT 0;
+UPDATE partidas_linhas SET debito = CAST(ROUND(debito_real * 100) AS INTEGER);
+ALTER TABLE partidas_linhas DROP COLUMN debito_real;
+ALTER TABLE partidas_linhas RENAME COLUMN credito TO credito_real;
+ALTER TABLE partidas_linhas ADD COLUMN credito BIGINT NOT NULL DEFAULT 0;
+UPDATE partidas_linhas SET credito = CAST(ROUND(credito_real * 100) AS INTEGER);
+ALTER TABLE partidas_linhas DROP COLUMN credito_real;
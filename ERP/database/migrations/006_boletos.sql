@@ -0,0 +1,34 @@
+-- Carteira de cobrança das contas bancárias e boletos de contas a
+-- receber. O sequencial do nosso número é único por conta.
+
+ALTER TABLE contas_bancarias ADD COLUMN boleto_banco TEXT;
+ALTER TABLE contas_bancarias ADD COLUMN boleto_carteira TEXT;
+ALTER TABLE contas_bancarias ADD COLUMN boleto_convenio TEXT;
+
+CREATE TABLE boletos (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    conta_id TEXT NOT NULL,
+    lancamento_id TEXT NOT NULL,
+    banco TEXT NOT NULL,
+    sequencial BIGINT NOT NULL,
+    nosso_numero TEXT NOT NULL,
+    numero_documento TEXT NOT NULL,
+    valor BIGINT NOT NULL,
+    vencimento TEXT NOT NULL,
+    pagador_nome TEXT NOT NULL,
+    pagador_documento TEXT NOT NULL,
+    pagador_endereco TEXT,
+    pagador_cidade TEXT,
+    pagador_estado TEXT,
+    pagador_cep TEXT,
+    codigo_barras TEXT NOT NULL,
+    linha_digitavel TEXT NOT NULL,
+    status TEXT NOT NULL,
+    remessa BIGINT,
+    created_at TEXT NOT NULL,
+    UNIQUE (empresa_id, conta_id, sequencial)
+);
+
+CREATE INDEX idx_boletos_lancamento ON boletos(empresa_id, lancamento_id);
+CREATE INDEX idx_boletos_conta ON boletos(conta_id, status);
@@ -0,0 +1,29 @@
+-- Dados fiscais de produtos, clientes e empresas e as notas fiscais
+-- autorizadas das vendas. A classificação do produto e a configuração do
+-- emitente ficam em JSON; o número da nota é único por modelo e série.
+
+ALTER TABLE produtos ADD COLUMN fiscal TEXT;
+ALTER TABLE empresas ADD COLUMN fiscal TEXT;
+ALTER TABLE clientes ADD COLUMN numero TEXT;
+ALTER TABLE clientes ADD COLUMN bairro TEXT;
+ALTER TABLE clientes ADD COLUMN codigo_municipio TEXT;
+
+CREATE TABLE notas_fiscais (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    venda_id TEXT NOT NULL,
+    modelo TEXT NOT NULL,
+    serie BIGINT NOT NULL,
+    numero BIGINT NOT NULL,
+    chave TEXT NOT NULL UNIQUE,
+    ambiente TEXT NOT NULL,
+    valor BIGINT NOT NULL,
+    protocolo TEXT NOT NULL,
+    emitida_em TEXT NOT NULL,
+    autorizada_em TEXT NOT NULL,
+    xml TEXT NOT NULL,
+    danfe BLOB NOT NULL,
+    UNIQUE (empresa_id, modelo, serie, numero)
+);
+
+CREATE INDEX idx_notas_fiscais_venda ON notas_fiscais(empresa_id, venda_id);
@@ -0,0 +1,4 @@
+-- Totais dos tributos da venda, em JSON como os itens, que passam a levar
+-- os tributos de cada um.
+
+ALTER TABLE vendas ADD COLUMN tributos TEXT;
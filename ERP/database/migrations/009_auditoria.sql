@@ -0,0 +1,18 @@
+-- Trilha de auditoria das alterações feitas pela API. Só recebe inserções:
+-- registros de auditoria nunca são alterados nem excluídos.
+
+CREATE TABLE auditoria (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    entidade TEXT NOT NULL,
+    entidade_id TEXT NOT NULL,
+    operacao TEXT NOT NULL,
+    usuario_id TEXT NOT NULL,
+    usuario TEXT NOT NULL,
+    requisicao_id TEXT NOT NULL,
+    instante TEXT NOT NULL,
+    alteracoes TEXT NOT NULL
+);
+
+CREATE INDEX idx_auditoria_entidade ON auditoria(empresa_id, entidade, entidade_id, instante);
+CREATE INDEX idx_auditoria_instante ON auditoria(empresa_id, instante);
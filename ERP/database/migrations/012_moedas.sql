@@ -0,0 +1,17 @@
+-- Contas bancárias, lançamentos e recorrências em moeda estrangeira, e a
+-- tabela de cotações diárias usada para convertê-los em reais. A taxa é
+-- gravada em milionésimos: 5432100 é 5,4321 reais por unidade da moeda.
+
+ALTER TABLE contas_bancarias ADD COLUMN moeda TEXT NOT NULL DEFAULT 'BRL';
+ALTER TABLE lancamentos ADD COLUMN moeda TEXT NOT NULL DEFAULT 'BRL';
+ALTER TABLE recorrencias ADD COLUMN moeda TEXT NOT NULL DEFAULT 'BRL';
+
+CREATE TABLE cotacoes (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    moeda TEXT NOT NULL,
+    data TEXT NOT NULL,
+    taxa BIGINT NOT NULL CHECK (taxa > 0),
+    created_at TEXT NOT NULL,
+    UNIQUE (empresa_id, moeda, data)
+);
@@ -0,0 +1,8 @@
+-- Contadores dos números que não podem se repetir mesmo com requisições
+-- simultâneas, como o nosso número e a remessa dos boletos. Cada chave
+-- guarda o último número reservado.
+
+CREATE TABLE sequencias (
+    chave TEXT PRIMARY KEY,
+    valor BIGINT NOT NULL
+);
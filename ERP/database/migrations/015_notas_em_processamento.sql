@@ -0,0 +1,7 @@
+-- A nota é gravada em processamento antes de ir para a SEFAZ, reservando
+-- o número e a venda; cada venda tem no máximo uma nota.
+
+ALTER TABLE notas_fiscais ADD COLUMN status TEXT NOT NULL DEFAULT 'AUTORIZADA';
+
+DROP INDEX idx_notas_fiscais_venda;
+CREATE UNIQUE INDEX idx_notas_fiscais_venda ON notas_fiscais(empresa_id, venda_id);
@@ -0,0 +1,5 @@
+-- Valor na moeda da conta bancária nas linhas de partida das contas em
+-- moeda estrangeira, de onde sai o saldo delas. As linhas gravadas antes
+-- ficam sem ele e são convertidas pela cotação da data da partida.
+
+ALTER TABLE partidas_linhas ADD COLUMN valor_moeda BIGINT;
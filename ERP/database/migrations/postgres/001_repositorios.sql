@@ -0,0 +1,203 @@
+-- Esquema usado pelos repositórios SQL do backend, no Postgres. É o mesmo
+-- de ../002_repositorios.sql (SQLite): ids são ObjectIds em hexadecimal,
+-- datas em texto ISO 8601 e booleanos em inteiros, para que as consultas
+-- sejam as mesmas nos dois bancos.
+
+CREATE TABLE empresas (
+    id TEXT PRIMARY KEY,
+    nome TEXT NOT NULL,
+    documento TEXT,
+    pais TEXT NOT NULL,
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- papeis e empresas são listas em JSON
+CREATE TABLE usuarios (
+    id TEXT PRIMARY KEY,
+    nome TEXT NOT NULL,
+    email TEXT NOT NULL UNIQUE,
+    senha_hash TEXT NOT NULL,
+    papeis TEXT NOT NULL,
+    empresas TEXT NOT NULL,
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE bancos (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    codigo TEXT NOT NULL,
+    nome TEXT NOT NULL,
+    pais TEXT NOT NULL,
+    tipo TEXT NOT NULL,
+    ativo BIGINT NOT NULL DEFAULT 1
+);
+
+CREATE INDEX idx_bancos_empresa ON bancos(empresa_id);
+
+CREATE TABLE clientes (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    nome TEXT NOT NULL,
+    cpf_cnpj TEXT NOT NULL,
+    telefone TEXT,
+    email TEXT,
+    endereco TEXT,
+    cidade TEXT,
+    estado TEXT,
+    cep TEXT,
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_clientes_empresa ON clientes(empresa_id);
+CREATE INDEX idx_clientes_cpf_cnpj ON clientes(cpf_cnpj);
+CREATE INDEX idx_clientes_nome ON clientes(nome);
+
+CREATE TABLE produtos (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    nome TEXT NOT NULL,
+    descricao TEXT,
+    codigo_barras TEXT,
+    preco_custo DOUBLE PRECISION NOT NULL DEFAULT 0,
+    preco_venda DOUBLE PRECISION NOT NULL DEFAULT 0,
+    estoque_atual BIGINT NOT NULL DEFAULT 0,
+    estoque_minimo BIGINT NOT NULL DEFAULT 0,
+    unidade TEXT NOT NULL DEFAULT 'UN',
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_produtos_empresa ON produtos(empresa_id);
+CREATE INDEX idx_produtos_nome ON produtos(nome);
+CREATE INDEX idx_produtos_codigo_barras ON produtos(codigo_barras);
+
+CREATE TABLE movimentacoes_estoque (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    produto_id TEXT NOT NULL,
+    tipo TEXT NOT NULL CHECK (tipo IN ('ENTRADA', 'SAIDA')),
+    quantidade BIGINT NOT NULL,
+    motivo TEXT NOT NULL,
+    usuario TEXT,
+    venda_id TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_movimentacoes_produto ON movimentacoes_estoque(empresa_id, produto_id);
+CREATE INDEX idx_movimentacoes_data ON movimentacoes_estoque(created_at);
+
+-- itens é a lista de itens da venda em JSON
+CREATE TABLE vendas (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    cliente_id TEXT,
+    itens TEXT NOT NULL,
+    total DOUBLE PRECISION NOT NULL DEFAULT 0,
+    desconto DOUBLE PRECISION NOT NULL DEFAULT 0,
+    total_final DOUBLE PRECISION NOT NULL DEFAULT 0,
+    forma_pagamento TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('ABERTA', 'FINALIZADA', 'CANCELADA')),
+    observacoes TEXT,
+    usuario TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_vendas_empresa ON vendas(empresa_id, status);
+CREATE INDEX idx_vendas_data ON vendas(created_at);
+
+CREATE TABLE contas_bancarias (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    banco TEXT NOT NULL,
+    agencia TEXT NOT NULL,
+    conta TEXT NOT NULL,
+    tipo TEXT NOT NULL,
+    saldo DOUBLE PRECISION NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_contas_bancarias_empresa ON contas_bancarias(empresa_id);
+
+CREATE TABLE cartoes (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    banco TEXT NOT NULL,
+    ultimos_digitos TEXT NOT NULL,
+    bandeira TEXT NOT NULL,
+    limite DOUBLE PRECISION NOT NULL DEFAULT 0,
+    vencimento BIGINT NOT NULL,
+    fechamento BIGINT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_cartoes_empresa ON cartoes(empresa_id);
+
+CREATE TABLE lancamentos (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    tipo TEXT NOT NULL CHECK (tipo IN ('PAGAR', 'RECEBER')),
+    descricao TEXT NOT NULL,
+    categoria TEXT NOT NULL,
+    valor DOUBLE PRECISION NOT NULL,
+    vencimento TEXT NOT NULL,
+    cliente_id TEXT,
+    contraparte TEXT,
+    grupo_id TEXT NOT NULL,
+    parcela BIGINT NOT NULL,
+    total_parcelas BIGINT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('ABERTO', 'PAGO', 'CANCELADO')),
+    conta_id TEXT,
+    data_pagamento TEXT,
+    valor_pago DOUBLE PRECISION,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_lancamentos_vencimento ON lancamentos(empresa_id, vencimento);
+CREATE INDEX idx_lancamentos_conta ON lancamentos(empresa_id, conta_id);
+
+CREATE TABLE compras_cartao (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    cartao_id TEXT NOT NULL,
+    compra_id TEXT NOT NULL,
+    descricao TEXT NOT NULL,
+    categoria TEXT NOT NULL,
+    valor DOUBLE PRECISION NOT NULL,
+    data_compra TEXT NOT NULL,
+    parcela BIGINT NOT NULL,
+    total_parcelas BIGINT NOT NULL,
+    competencia TEXT NOT NULL,
+    lancamento_id TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_compras_cartao_competencia ON compras_cartao(cartao_id, competencia);
+CREATE INDEX idx_compras_cartao_compra ON compras_cartao(cartao_id, compra_id);
+
+CREATE TABLE transacoes_bancarias (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    conta_id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    valor DOUBLE PRECISION NOT NULL,
+    descricao TEXT NOT NULL,
+    documento TEXT,
+    identificador TEXT NOT NULL,
+    origem TEXT NOT NULL,
+    lancamento_id TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (conta_id, identificador)
+);
+
+CREATE INDEX idx_transacoes_bancarias_lancamento ON transacoes_bancarias(empresa_id, lancamento_id);
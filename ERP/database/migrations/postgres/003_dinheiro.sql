@@ -0,0 +1,31 @@
+-- Valores monetários passam a ser guardados em centavos (BIGINT), sem as
+-- perdas de arredondamento do ponto flutuante.
+
+ALTER TABLE produtos
+    ALTER COLUMN preco_custo TYPE BIGINT USING ROUND(preco_custo * 100),
+    ALTER COLUMN preco_venda TYPE BIGINT USING ROUND(preco_venda * 100);
+
+ALTER TABLE vendas
+    ALTER COLUMN total TYPE BIGINT USING ROUND(total * 100),
+    ALTER COLUMN desconto TYPE BIGINT USING ROUND(desconto * 100),
+    ALTER COLUMN total_final TYPE BIGINT USING ROUND(total_final * 100);
+
+ALTER TABLE contas_bancarias
+    ALTER COLUMN saldo TYPE BIGINT USING ROUND(saldo * 100);
+
+ALTER TABLE cartoes
+    ALTER COLUMN limite TYPE BIGINT USING ROUND(limite * 100);
+
+ALTER TABLE lancamentos
+    ALTER COLUMN valor TYPE BIGINT USING ROUND(valor * 100),
+    ALTER COLUMN valor_pago TYPE BIGINT USING ROUND(valor_pago * 100);
+
+ALTER TABLE compras_cartao
+    ALTER COLUMN valor TYPE BIGINT USING ROUND(valor * 100);
+
+ALTER TABLE transacoes_bancarias
+    ALTER COLUMN valor TYPE BIGINT USING ROUND(valor * 100);
+
+ALTER TABLE partidas_linhas
+    ALTER COLUMN debito TYPE BIGINT USING ROUND(debito * 100),
+    ALTER COLUMN credito TYPE BIGINT USING ROUND(credito * 100);
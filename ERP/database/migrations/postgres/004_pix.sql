@@ -0,0 +1,25 @@
+-- Chave PIX das contas bancárias e cobranças PIX, no Postgres. É o mesmo
+-- de ../005_pix.sql (SQLite).
+
+ALTER TABLE contas_bancarias ADD COLUMN pix_chave TEXT;
+ALTER TABLE contas_bancarias ADD COLUMN pix_cidade TEXT;
+
+CREATE TABLE cobrancas_pix (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    conta_id TEXT NOT NULL,
+    origem TEXT NOT NULL,
+    origem_id TEXT NOT NULL,
+    txid TEXT NOT NULL,
+    valor BIGINT NOT NULL,
+    location TEXT,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    transacao_id TEXT,
+    data_pagamento TEXT,
+    created_at TEXT NOT NULL,
+    UNIQUE (empresa_id, txid)
+);
+
+CREATE INDEX idx_cobrancas_pix_origem ON cobrancas_pix(empresa_id, origem, origem_id);
+CREATE INDEX idx_cobrancas_pix_conta ON cobrancas_pix(conta_id, status);
@@ -0,0 +1,28 @@
+-- Dados fiscais e notas fiscais autorizadas, no Postgres. É o mesmo de
+-- ../007_notas_fiscais.sql (SQLite), com o DANFE em BYTEA.
+
+ALTER TABLE produtos ADD COLUMN fiscal TEXT;
+ALTER TABLE empresas ADD COLUMN fiscal TEXT;
+ALTER TABLE clientes ADD COLUMN numero TEXT;
+ALTER TABLE clientes ADD COLUMN bairro TEXT;
+ALTER TABLE clientes ADD COLUMN codigo_municipio TEXT;
+
+CREATE TABLE notas_fiscais (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    venda_id TEXT NOT NULL,
+    modelo TEXT NOT NULL,
+    serie BIGINT NOT NULL,
+    numero BIGINT NOT NULL,
+    chave TEXT NOT NULL UNIQUE,
+    ambiente TEXT NOT NULL,
+    valor BIGINT NOT NULL,
+    protocolo TEXT NOT NULL,
+    emitida_em TEXT NOT NULL,
+    autorizada_em TEXT NOT NULL,
+    xml TEXT NOT NULL,
+    danfe BYTEA NOT NULL,
+    UNIQUE (empresa_id, modelo, serie, numero)
+);
+
+CREATE INDEX idx_notas_fiscais_venda ON notas_fiscais(empresa_id, venda_id);
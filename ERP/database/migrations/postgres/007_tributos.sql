@@ -0,0 +1,4 @@
+-- Totais dos tributos da venda, no Postgres. É o mesmo de
+-- ../008_tributos.sql (SQLite).
+
+ALTER TABLE vendas ADD COLUMN tributos TEXT;
@@ -0,0 +1,43 @@
+-- Fornecedores e pedidos de compra, no Postgres. É o mesmo de
+-- ../010_compras.sql (SQLite).
+
+CREATE TABLE fornecedores (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    nome TEXT NOT NULL,
+    cpf_cnpj TEXT NOT NULL,
+    contato TEXT,
+    telefone TEXT,
+    email TEXT,
+    endereco TEXT,
+    cidade TEXT,
+    estado TEXT,
+    cep TEXT,
+    ativo BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_fornecedores_empresa ON fornecedores(empresa_id);
+CREATE INDEX idx_fornecedores_cpf_cnpj ON fornecedores(cpf_cnpj);
+CREATE INDEX idx_fornecedores_nome ON fornecedores(nome);
+
+CREATE TABLE pedidos_compra (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    fornecedor_id TEXT NOT NULL,
+    itens TEXT NOT NULL,
+    total BIGINT NOT NULL DEFAULT 0,
+    status TEXT NOT NULL CHECK (status IN ('ABERTO', 'PARCIAL', 'RECEBIDO', 'CANCELADO')),
+    previsao_entrega TEXT,
+    observacoes TEXT,
+    recebimentos TEXT NOT NULL,
+    usuario TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_pedidos_compra_empresa ON pedidos_compra(empresa_id, status);
+CREATE INDEX idx_pedidos_compra_fornecedor ON pedidos_compra(empresa_id, fornecedor_id);
+
+ALTER TABLE movimentacoes_estoque ADD COLUMN pedido_compra_id TEXT;
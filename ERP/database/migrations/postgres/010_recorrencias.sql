@@ -0,0 +1,35 @@
+-- Recorrências e parcelamentos do financeiro, no Postgres. É o mesmo de
+-- ../011_recorrencias.sql (SQLite).
+
+CREATE TABLE recorrencias (
+    id TEXT PRIMARY KEY,
+    empresa_id TEXT NOT NULL,
+    tipo TEXT NOT NULL CHECK (tipo IN ('PAGAR', 'RECEBER')),
+    descricao TEXT NOT NULL,
+    categoria TEXT NOT NULL,
+    valor BIGINT NOT NULL,
+    frequencia TEXT NOT NULL CHECK (frequencia IN ('SEMANAL', 'MENSAL', 'ANUAL')),
+    dia BIGINT NOT NULL,
+    inicio TEXT NOT NULL,
+    ocorrencias BIGINT,
+    fim TEXT,
+    conta_id TEXT,
+    cartao_id TEXT,
+    cliente_id TEXT,
+    contraparte TEXT,
+    geradas BIGINT NOT NULL DEFAULT 0,
+    ativa BIGINT NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_recorrencias_empresa ON recorrencias(empresa_id);
+CREATE INDEX idx_recorrencias_ativa ON recorrencias(ativa);
+
+ALTER TABLE lancamentos ADD COLUMN recorrencia_id TEXT;
+ALTER TABLE lancamentos ADD COLUMN ocorrencia BIGINT;
+CREATE INDEX idx_lancamentos_recorrencia ON lancamentos(empresa_id, recorrencia_id);
+
+ALTER TABLE compras_cartao ADD COLUMN recorrencia_id TEXT;
+ALTER TABLE compras_cartao ADD COLUMN ocorrencia BIGINT;
+CREATE INDEX idx_compras_cartao_recorrencia ON compras_cartao(empresa_id, recorrencia_id);
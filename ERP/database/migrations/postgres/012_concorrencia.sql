@@ -0,0 +1,20 @@
+-- Versão dos cadastros e respostas das requisições com Idempotency-Key, no
+-- Postgres. É o mesmo de ../013_concorrencia.sql (SQLite).
+
+ALTER TABLE clientes ADD COLUMN versao BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE fornecedores ADD COLUMN versao BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE contas_bancarias ADD COLUMN versao BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE cartoes ADD COLUMN versao BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE idempotencia (
+    id TEXT PRIMARY KEY,
+    usuario_id TEXT NOT NULL,
+    chave TEXT NOT NULL,
+    impressao TEXT NOT NULL,
+    status BIGINT,
+    cabecalhos TEXT,
+    corpo BYTEA NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_idempotencia_created_at ON idempotencia(created_at);
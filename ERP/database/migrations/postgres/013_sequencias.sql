@@ -0,0 +1,7 @@
+-- Contadores dos números reservados sob concorrência, no Postgres. É o
+-- mesmo de ../014_sequencias.sql (SQLite).
+
+CREATE TABLE sequencias (
+    chave TEXT PRIMARY KEY,
+    valor BIGINT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Notas em processamento e uma nota por venda, no Postgres. É o mesmo de
+-- ../015_notas_em_processamento.sql (SQLite).
+
+ALTER TABLE notas_fiscais ADD COLUMN status TEXT NOT NULL DEFAULT 'AUTORIZADA';
+
+DROP INDEX idx_notas_fiscais_venda;
+CREATE UNIQUE INDEX idx_notas_fiscais_venda ON notas_fiscais(empresa_id, venda_id);
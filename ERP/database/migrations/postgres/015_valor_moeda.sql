@@ -0,0 +1,4 @@
+-- Valor na moeda da conta nas linhas de partida, no Postgres. É o mesmo de
+-- ../016_valor_moeda.sql (SQLite).
+
+ALTER TABLE partidas_linhas ADD COLUMN valor_moeda BIGINT;
@@ -0,0 +1,134 @@
+use erp_dinheiro::{Aliquota, Dinheiro};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ClienteResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub cpf_cnpj: String,
+    pub telefone: Option<String>,
+    pub email: Option<String>,
+    pub endereco: Option<String>,
+    pub numero: Option<String>,
+    pub bairro: Option<String>,
+    pub cidade: Option<String>,
+    /// Código IBGE do município, para a NF-e.
+    pub codigo_municipio: Option<String>,
+    pub estado: Option<String>,
+    pub cep: Option<String>,
+    pub ativo: bool,
+    /// Versão do registro, a mesma do cabeçalho `ETag`.
+    pub versao: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BancoResponse {
+    pub id: Option<String>,
+    /// Código de compensação no Brasil, ou do banco no país.
+    pub codigo: String,
+    pub nome: String,
+    pub pais: String,
+    pub tipo: String,
+    pub ativo: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProdutoResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub descricao: Option<String>,
+    pub codigo_barras: Option<String>,
+    pub preco_custo: Dinheiro,
+    pub preco_venda: Dinheiro,
+    pub estoque_atual: i32,
+    pub estoque_minimo: i32,
+    pub unidade: String,
+    pub fiscal: Option<DadosFiscais>,
+    pub ativo: bool,
+}
+
+/// CST do ICMS (regime normal) aceitos na NF-e.
+pub const CSTS_ICMS: &[&str] = &["00", "10", "20", "40", "41", "50", "60"];
+/// CSOSN (Simples Nacional) aceitos na NF-e.
+pub const CSOSNS: &[&str] = &["102", "103", "202", "300", "400", "500"];
+/// CST e CSOSN com substituição tributária retida pelo emitente.
+pub const CSTS_COM_ST: &[&str] = &["10", "202"];
+/// CST de PIS e COFINS aceitos na NF-e.
+pub const CSTS_PIS_COFINS: &[&str] = &["01", "02", "04", "05", "06", "07", "08", "09", "49", "99"];
+/// CST do IPI nas saídas.
+pub const CSTS_IPI: &[&str] = &["50", "51", "52", "53", "54", "55", "99"];
+
+/// Classificação fiscal do produto. O ICMS usa CST no regime normal e
+/// CSOSN no Simples Nacional; PIS e COFINS compartilham o CST.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DadosFiscais {
+    pub ncm: String,
+    /// CFOP da venda dentro do estado (5xxx); para outro estado a NF-e usa
+    /// o 6xxx correspondente.
+    pub cfop: String,
+    /// Origem da mercadoria, de 0 (nacional) a 8.
+    #[serde(default)]
+    pub origem: u8,
+    pub cst_icms: String,
+    /// Alíquota interna do ICMS; zero usa a alíquota modal da UF.
+    #[serde(default)]
+    pub aliquota_icms: Aliquota,
+    /// Percentual de redução da base do ICMS (CST 20).
+    #[serde(default)]
+    pub reducao_base_icms: Aliquota,
+    /// Código especificador da substituição tributária, exigido com ST.
+    pub cest: Option<String>,
+    /// Margem de valor agregado da ST nas vendas dentro do estado; para
+    /// outra UF ela é ajustada pelas alíquotas.
+    #[serde(default)]
+    pub mva_st: Aliquota,
+    /// Sem CST o produto não tem IPI.
+    pub cst_ipi: Option<String>,
+    #[serde(default)]
+    pub aliquota_ipi: Aliquota,
+    pub cst_pis_cofins: String,
+    #[serde(default)]
+    pub aliquota_pis: Aliquota,
+    #[serde(default)]
+    pub aliquota_cofins: Aliquota,
+}
+
+impl DadosFiscais {
+    pub fn normalizados(&self) -> Self {
+        Self {
+            ncm: somente_digitos(&self.ncm),
+            cfop: self.cfop.trim().to_string(),
+            cst_icms: self.cst_icms.trim().to_string(),
+            cest: self
+                .cest
+                .as_deref()
+                .map(somente_digitos)
+                .filter(|cest| !cest.is_empty()),
+            cst_ipi: self
+                .cst_ipi
+                .as_deref()
+                .map(|cst| cst.trim().to_string())
+                .filter(|cst| !cst.is_empty()),
+            cst_pis_cofins: self.cst_pis_cofins.trim().to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// O ICMS é informado por CSOSN, do Simples Nacional.
+    pub fn simples_nacional(&self) -> bool {
+        CSOSNS.contains(&self.cst_icms.as_str())
+    }
+
+    /// O emitente retém o ICMS da substituição tributária.
+    pub fn com_st(&self) -> bool {
+        CSTS_COM_ST.contains(&self.cst_icms.as_str())
+    }
+}
+
+fn somente_digitos(valor: &str) -> String {
+    valor.chars().filter(char::is_ascii_digit).collect()
+}
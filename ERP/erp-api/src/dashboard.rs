@@ -0,0 +1,45 @@
+use erp_dinheiro::Dinheiro;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DashboardData {
+    pub vendas_hoje: VendasHoje,
+    pub estoque_critico: Vec<EstoqueCritico>,
+    pub produtos_mais_vendidos: Vec<ProdutoMaisVendido>,
+    pub resumo_mes: ResumoMes,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VendasHoje {
+    pub quantidade: i64,
+    pub valor_total: Dinheiro,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EstoqueCritico {
+    pub id: String,
+    pub nome: String,
+    pub estoque_atual: i32,
+    pub estoque_minimo: i32,
+    pub falta: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProdutoMaisVendido {
+    pub produto_id: String,
+    pub produto_nome: String,
+    pub total_vendido: i64,
+    pub valor_total: Dinheiro,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ResumoMes {
+    pub total_vendas: i64,
+    pub valor_total: Dinheiro,
+    pub ticket_medio: Dinheiro,
+}
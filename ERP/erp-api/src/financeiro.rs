@@ -0,0 +1,77 @@
+use erp_dinheiro::{Dinheiro, Moeda};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ContaResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub banco: String,
+    pub tipo_conta: String,
+    pub agencia: String,
+    pub numero_conta: String,
+    /// Moeda dos saldos da conta.
+    #[serde(default)]
+    pub moeda: Moeda,
+    pub saldo_inicial: Dinheiro,
+    /// Saldo de hoje, na moeda da conta.
+    pub saldo_atual: Dinheiro,
+    pub pix: Option<ChavePix>,
+    pub boleto: Option<CarteiraBoleto>,
+    pub ativo: bool,
+    /// Versão do registro, a mesma do cabeçalho `ETag`.
+    pub versao: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CartaoResponse {
+    pub id: Option<String>,
+    pub nome: String,
+    pub bandeira: String,
+    pub ultimos_digitos: String,
+    pub limite_total: Dinheiro,
+    /// Limite menos as parcelas em aberto nas faturas do cartão.
+    pub limite_disponivel: Dinheiro,
+    pub dia_vencimento: i32,
+    pub dia_fechamento: i32,
+    pub ativo: bool,
+    /// Versão do registro, a mesma do cabeçalho `ETag`.
+    pub versao: u32,
+}
+
+/// Chave PIX da conta bancária. A cidade do recebedor é obrigatória no BR
+/// Code; o nome é o da empresa.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChavePix {
+    pub chave: String,
+    pub cidade: String,
+}
+
+/// Carteira de cobrança registrada da conta no banco.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CarteiraBoleto {
+    /// Código FEBRABAN do banco emissor.
+    pub banco: String,
+    pub carteira: String,
+    /// Convênio (Banco do Brasil, 7 algarismos) ou código do beneficiário
+    /// (Caixa, 6 algarismos).
+    pub convenio: Option<String>,
+}
+
+impl CarteiraBoleto {
+    pub fn normalizada(&self) -> Self {
+        Self {
+            banco: self.banco.trim().to_string(),
+            carteira: self.carteira.trim().to_string(),
+            convenio: self
+                .convenio
+                .as_deref()
+                .map(str::trim)
+                .filter(|convenio| !convenio.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! # erp-api
+//!
+//! Tipos que trafegam na API do ERP, compartilhados entre o backend, que os
+//! serializa, e o frontend-wasm, que os lê. Manter um só tipo para os dois
+//! lados impede que o frontend descreva as respostas de outro jeito.
+//!
+//! Com a feature `openapi`, os tipos derivam os esquemas do documento
+//! OpenAPI publicado pelo backend em `/api/v1/openapi.json`.
+
+use serde::{Deserialize, Serialize};
+
+mod cadastros;
+mod dashboard;
+mod financeiro;
+mod vendas;
+
+pub use cadastros::*;
+pub use dashboard::*;
+pub use financeiro::*;
+pub use vendas::*;
+
+/// Envelope das listagens paginadas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Pagina<T> {
+    pub itens: Vec<T>,
+    /// Registros que atendem aos filtros, somando todas as páginas.
+    pub total: u64,
+    pub pagina: u64,
+    pub limite: u64,
+}
+
+impl<T> Pagina<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Pagina<U> {
+        Pagina {
+            itens: self.itens.into_iter().map(f).collect(),
+            total: self.total,
+            pagina: self.pagina,
+            limite: self.limite,
+        }
+    }
+}
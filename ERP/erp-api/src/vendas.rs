@@ -0,0 +1,114 @@
+use erp_dinheiro::{Aliquota, Dinheiro};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VendaResponse {
+    pub id: Option<String>,
+    pub cliente_id: Option<String>,
+    #[serde(default)]
+    pub itens: Vec<ItemVendaResponse>,
+    pub total: Dinheiro,
+    pub desconto: Dinheiro,
+    /// Total dos itens menos o desconto, mais o IPI e o ICMS ST, que o
+    /// cliente paga por fora do preço.
+    pub total_final: Dinheiro,
+    /// Totais dos tributos; sem a configuração fiscal da empresa ou os dados
+    /// fiscais de algum produto, a venda fica sem tributos.
+    pub tributos: Option<TotaisTributos>,
+    pub forma_pagamento: String,
+    pub status: StatusVenda,
+    pub observacoes: Option<String>,
+    /// E-mail do usuário que abriu a venda.
+    pub usuario: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ItemVendaResponse {
+    pub id: String,
+    pub produto_id: String,
+    pub produto_nome: String,
+    pub quantidade: i32,
+    pub preco_unitario: Dinheiro,
+    pub subtotal: Dinheiro,
+    pub tributos: Option<TributosItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StatusVenda {
+    Aberta,
+    Finalizada,
+    Cancelada,
+}
+
+impl StatusVenda {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusVenda::Aberta => "ABERTA",
+            StatusVenda::Finalizada => "FINALIZADA",
+            StatusVenda::Cancelada => "CANCELADA",
+        }
+    }
+}
+
+/// Tributos do item calculados pelo backend: o desconto rateado da venda e
+/// as bases, alíquotas e valores de cada imposto, como vão para a NF-e.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TributosItem {
+    /// CFOP da operação: 5xxx dentro do estado, 6xxx para outra UF.
+    pub cfop: String,
+    pub origem: u8,
+    /// CST (regime normal) ou CSOSN (Simples Nacional) do ICMS.
+    pub cst_icms: String,
+    pub desconto: Dinheiro,
+    pub base_icms: Dinheiro,
+    pub reducao_base_icms: Aliquota,
+    /// Alíquota interna, ou a interestadual na venda para outra UF.
+    pub aliquota_icms: Aliquota,
+    pub icms: Dinheiro,
+    /// MVA já ajustada na venda para outra UF.
+    pub mva_st: Aliquota,
+    pub base_icms_st: Dinheiro,
+    pub aliquota_icms_st: Aliquota,
+    pub icms_st: Dinheiro,
+    /// Alíquota interna da UF de destino, para o diferencial de alíquotas
+    /// na venda a consumidor final de outra UF.
+    pub aliquota_icms_destino: Aliquota,
+    pub icms_difal: Dinheiro,
+    pub cst_ipi: Option<String>,
+    pub base_ipi: Dinheiro,
+    pub aliquota_ipi: Aliquota,
+    pub ipi: Dinheiro,
+    pub cst_pis_cofins: String,
+    pub base_pis_cofins: Dinheiro,
+    pub aliquota_pis: Aliquota,
+    pub pis: Dinheiro,
+    pub aliquota_cofins: Aliquota,
+    pub cofins: Dinheiro,
+}
+
+impl TributosItem {
+    /// Venda para outra UF.
+    pub fn interestadual(&self) -> bool {
+        self.cfop.starts_with('6')
+    }
+}
+
+/// Soma dos tributos dos itens da venda.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TotaisTributos {
+    pub base_icms: Dinheiro,
+    pub icms: Dinheiro,
+    pub base_icms_st: Dinheiro,
+    pub icms_st: Dinheiro,
+    pub icms_difal: Dinheiro,
+    pub ipi: Dinheiro,
+    pub pis: Dinheiro,
+    pub cofins: Dinheiro,
+}
@@ -0,0 +1,227 @@
+use std::{fmt, str::FromStr};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Dinheiro, ErroDinheiro};
+
+/// Casas decimais das alíquotas, as mesmas dos percentuais da NF-e.
+pub const CASAS_ALIQUOTA: u32 = 4;
+
+/// Alíquota percentual exata, não negativa, com até quatro casas decimais
+/// (`18.0000` para 18%, `1.6500` para 1,65%). No JSON é uma string, como
+/// [`Dinheiro`]; no MongoDB, com a feature `bson`, um `Decimal128`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aliquota(Decimal);
+
+impl Aliquota {
+    pub const ZERO: Aliquota = Aliquota(Decimal::ZERO);
+
+    /// Aceita apenas alíquotas não negativas com até quatro casas.
+    pub fn exata(valor: Decimal) -> Result<Self, ErroDinheiro> {
+        if valor.is_sign_negative() && !valor.is_zero()
+            || valor.normalize().scale() > CASAS_ALIQUOTA
+        {
+            return Err(ErroDinheiro::AliquotaInvalida(valor.to_string()));
+        }
+        let mut valor = valor;
+        valor.rescale(CASAS_ALIQUOTA);
+        Ok(Aliquota(valor.abs()))
+    }
+
+    /// Arredonda para quatro casas, com o meio para longe do zero; valores
+    /// negativos viram zero. Para alíquotas calculadas, como a MVA ajustada.
+    pub fn arredondar(valor: Decimal) -> Self {
+        let mut valor = valor
+            .max(Decimal::ZERO)
+            .round_dp_with_strategy(CASAS_ALIQUOTA, RoundingStrategy::MidpointAwayFromZero);
+        valor.rescale(CASAS_ALIQUOTA);
+        Aliquota(valor)
+    }
+
+    /// Alíquota a partir de décimos de milésimo de ponto percentual, a
+    /// forma gravada no SQL (`180000` é 18%).
+    pub fn de_unidades(unidades: i64) -> Self {
+        Aliquota(Decimal::new(unidades.max(0), CASAS_ALIQUOTA))
+    }
+
+    /// Décimos de milésimo de ponto percentual.
+    pub fn unidades(&self) -> i64 {
+        let mut valor = self.0;
+        valor.rescale(CASAS_ALIQUOTA);
+        i64::try_from(valor.mantissa()).unwrap_or(i64::MAX)
+    }
+
+    pub fn decimal(&self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Imposto sobre a `base`, arredondado para o centavo.
+    pub fn sobre(&self, base: Dinheiro) -> Dinheiro {
+        base.percentual(self.0)
+    }
+}
+
+impl fmt::Display for Aliquota {
+    /// Sempre com quatro casas e ponto decimal (`18.0000`).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut valor = self.0;
+        valor.rescale(CASAS_ALIQUOTA);
+        write!(f, "{}", valor)
+    }
+}
+
+impl FromStr for Aliquota {
+    type Err = ErroDinheiro;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valor = Decimal::from_str(s.trim())
+            .map_err(|_| ErroDinheiro::AliquotaInvalida(s.to_string()))?;
+        Self::exata(valor)
+    }
+}
+
+impl TryFrom<f64> for Aliquota {
+    type Error = ErroDinheiro;
+
+    /// Parte da representação decimal mais curta do `f64`, então `1.65`
+    /// vira exatamente `1.6500`.
+    fn try_from(valor: f64) -> Result<Self, Self::Error> {
+        if !valor.is_finite() {
+            return Err(ErroDinheiro::AliquotaInvalida(valor.to_string()));
+        }
+        valor.to_string().parse()
+    }
+}
+
+impl Serialize for Aliquota {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "bson")]
+        if !serializer.is_human_readable() {
+            let decimal: bson::Decimal128 = self
+                .to_string()
+                .parse()
+                .expect("um decimal com quatro casas sempre cabe em Decimal128");
+            return decimal.serialize(serializer);
+        }
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Aliquota {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[cfg(feature = "bson")]
+        if !deserializer.is_human_readable() {
+            return match bson::Bson::deserialize(deserializer)? {
+                bson::Bson::Decimal128(decimal) => {
+                    let texto = decimal.to_string();
+                    Decimal::from_str(&texto)
+                        .or_else(|_| Decimal::from_scientific(&texto))
+                        .map_err(|_| ErroDinheiro::AliquotaInvalida(texto))
+                        .and_then(Aliquota::exata)
+                }
+                bson::Bson::Double(valor) => Aliquota::try_from(valor),
+                bson::Bson::Int32(valor) => Aliquota::exata(Decimal::from(valor)),
+                bson::Bson::Int64(valor) => Aliquota::exata(Decimal::from(valor)),
+                bson::Bson::String(texto) => texto.parse(),
+                outro => Err(ErroDinheiro::AliquotaInvalida(outro.to_string())),
+            }
+            .map_err(de::Error::custom);
+        }
+        deserializer.deserialize_any(VisitanteAliquota)
+    }
+}
+
+struct VisitanteAliquota;
+
+impl de::Visitor<'_> for VisitanteAliquota {
+    type Value = Aliquota;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("uma alíquota percentual como string (\"18.00\") ou número")
+    }
+
+    fn visit_str<E: de::Error>(self, valor: &str) -> Result<Aliquota, E> {
+        valor.parse().map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, valor: f64) -> Result<Aliquota, E> {
+        Aliquota::try_from(valor).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, valor: i64) -> Result<Aliquota, E> {
+        Aliquota::exata(Decimal::from(valor)).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, valor: u64) -> Result<Aliquota, E> {
+        Aliquota::exata(Decimal::from(valor)).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn a(valor: &str) -> Aliquota {
+        valor.parse().unwrap()
+    }
+
+    #[test]
+    fn le_e_escreve_com_quatro_casas() {
+        assert_eq!(a("18").to_string(), "18.0000");
+        assert_eq!(a("1.65").to_string(), "1.6500");
+        assert_eq!(a("0.0001").unidades(), 1);
+        assert_eq!(Aliquota::de_unidades(76000), a("7.6"));
+        assert!("1.23456".parse::<Aliquota>().is_err());
+        assert!("-1".parse::<Aliquota>().is_err());
+    }
+
+    #[test]
+    fn arredonda_calculadas_para_quatro_casas() {
+        let calculada: Decimal = "53.04347826".parse().unwrap();
+        assert_eq!(Aliquota::arredondar(calculada), a("53.0435"));
+        assert_eq!(
+            Aliquota::arredondar("1.00005".parse().unwrap()),
+            a("1.0001")
+        );
+        assert_eq!(Aliquota::arredondar(Decimal::NEGATIVE_ONE), Aliquota::ZERO);
+    }
+
+    #[test]
+    fn imposto_arredondado_para_o_centavo() {
+        let base: Dinheiro = "123.45".parse().unwrap();
+        assert_eq!(a("18").sobre(base), "22.22".parse().unwrap());
+        assert_eq!(a("1.65").sobre(base), "2.04".parse().unwrap());
+        assert_eq!(Aliquota::ZERO.sobre(base), Dinheiro::ZERO);
+    }
+
+    #[test]
+    fn json_como_string() {
+        assert_eq!(serde_json::to_string(&a("7.6")).unwrap(), "\"7.6000\"");
+        assert_eq!(serde_json::from_str::<Aliquota>("1.65").unwrap(), a("1.65"));
+        assert_eq!(serde_json::from_str::<Aliquota>("12").unwrap(), a("12"));
+        assert!(serde_json::from_str::<Aliquota>("\"abc\"").is_err());
+    }
+
+    #[cfg(feature = "bson")]
+    #[test]
+    fn bson_como_decimal128() {
+        #[derive(Serialize, Deserialize)]
+        struct Registro {
+            aliquota: Aliquota,
+        }
+
+        let bytes = bson::to_vec(&Registro { aliquota: a("3.5") }).unwrap();
+        let documento = bson::Document::from_reader(bytes.as_slice()).unwrap();
+        assert!(matches!(
+            documento.get("aliquota"),
+            Some(bson::Bson::Decimal128(_))
+        ));
+        let lido: Registro = bson::from_slice(&bytes).unwrap();
+        assert_eq!(lido.aliquota, a("3.5"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::{fmt, str::FromStr};
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Dinheiro, ErroDinheiro};
+
+/// Casas decimais das taxas de câmbio, as mesmas da PTAX.
+pub const CASAS_TAXA: u32 = 6;
+
+/// Taxa de câmbio exata e positiva, com até seis casas decimais: quanto uma
+/// unidade de uma moeda vale em outra (`5.432100` reais por dólar). No JSON
+/// é uma string, como [`Dinheiro`]; no MongoDB, com a feature `bson`, um
+/// `Decimal128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaxaCambio(Decimal);
+
+impl TaxaCambio {
+    /// A taxa de uma moeda para ela mesma.
+    pub const UM: TaxaCambio = TaxaCambio(Decimal::ONE);
+
+    /// Aceita apenas taxas positivas com até seis casas.
+    pub fn exata(valor: Decimal) -> Result<Self, ErroDinheiro> {
+        if valor <= Decimal::ZERO || valor.normalize().scale() > CASAS_TAXA {
+            return Err(ErroDinheiro::TaxaInvalida(valor.to_string()));
+        }
+        let mut valor = valor;
+        valor.rescale(CASAS_TAXA);
+        Ok(TaxaCambio(valor))
+    }
+
+    /// Arredonda para seis casas, com o meio para longe do zero; `None` se
+    /// o resultado não for positivo. Para taxas calculadas, como as
+    /// cruzadas.
+    pub fn arredondar(valor: Decimal) -> Option<Self> {
+        let mut valor =
+            valor.round_dp_with_strategy(CASAS_TAXA, RoundingStrategy::MidpointAwayFromZero);
+        valor.rescale(CASAS_TAXA);
+        (valor > Decimal::ZERO).then_some(TaxaCambio(valor))
+    }
+
+    /// Taxa a partir de milionésimos, a forma gravada no SQL (`5432100` é
+    /// 5,4321).
+    pub fn de_unidades(unidades: i64) -> Result<Self, ErroDinheiro> {
+        Self::exata(Decimal::new(unidades, CASAS_TAXA))
+    }
+
+    /// Milionésimos da taxa.
+    pub fn unidades(&self) -> i64 {
+        let mut valor = self.0;
+        valor.rescale(CASAS_TAXA);
+        i64::try_from(valor.mantissa()).unwrap_or(i64::MAX)
+    }
+
+    pub fn decimal(&self) -> Decimal {
+        self.0
+    }
+
+    /// Valor na outra moeda, arredondado para o centavo.
+    pub fn converter(&self, valor: Dinheiro) -> Dinheiro {
+        Dinheiro::arredondar(valor.decimal() * self.0)
+    }
+
+    /// Valor de volta na moeda de origem, arredondado para o centavo.
+    pub fn desconverter(&self, valor: Dinheiro) -> Dinheiro {
+        Dinheiro::arredondar(valor.decimal() / self.0)
+    }
+
+    /// Taxa cruzada entre duas moedas cotadas na mesma terceira: com
+    /// `self` em reais por dólar e `outra` em reais por euro, o resultado é
+    /// em euros por dólar.
+    pub fn cruzada(&self, outra: TaxaCambio) -> Option<Self> {
+        Self::arredondar(self.0 / outra.0)
+    }
+}
+
+impl fmt::Display for TaxaCambio {
+    /// Sempre com seis casas e ponto decimal (`5.432100`).
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut valor = self.0;
+        valor.rescale(CASAS_TAXA);
+        write!(f, "{}", valor)
+    }
+}
+
+impl FromStr for TaxaCambio {
+    type Err = ErroDinheiro;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valor =
+            Decimal::from_str(s.trim()).map_err(|_| ErroDinheiro::TaxaInvalida(s.to_string()))?;
+        Self::exata(valor)
+    }
+}
+
+impl TryFrom<f64> for TaxaCambio {
+    type Error = ErroDinheiro;
+
+    /// Parte da representação decimal mais curta do `f64`, então `5.4321`
+    /// vira exatamente `5.432100`.
+    fn try_from(valor: f64) -> Result<Self, Self::Error> {
+        if !valor.is_finite() {
+            return Err(ErroDinheiro::TaxaInvalida(valor.to_string()));
+        }
+        valor.to_string().parse()
+    }
+}
+
+impl Serialize for TaxaCambio {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "bson")]
+        if !serializer.is_human_readable() {
+            let decimal: bson::Decimal128 = self
+                .to_string()
+                .parse()
+                .expect("um decimal com seis casas sempre cabe em Decimal128");
+            return decimal.serialize(serializer);
+        }
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TaxaCambio {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[cfg(feature = "bson")]
+        if !deserializer.is_human_readable() {
+            return match bson::Bson::deserialize(deserializer)? {
+                bson::Bson::Decimal128(decimal) => {
+                    let texto = decimal.to_string();
+                    Decimal::from_str(&texto)
+                        .or_else(|_| Decimal::from_scientific(&texto))
+                        .map_err(|_| ErroDinheiro::TaxaInvalida(texto))
+                        .and_then(TaxaCambio::exata)
+                }
+                bson::Bson::Double(valor) => TaxaCambio::try_from(valor),
+                bson::Bson::String(texto) => texto.parse(),
+                outro => Err(ErroDinheiro::TaxaInvalida(outro.to_string())),
+            }
+            .map_err(de::Error::custom);
+        }
+        deserializer.deserialize_any(VisitanteTaxa)
+    }
+}
+
+struct VisitanteTaxa;
+
+impl de::Visitor<'_> for VisitanteTaxa {
+    type Value = TaxaCambio;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("uma taxa de câmbio como string (\"5.4321\") ou número")
+    }
+
+    fn visit_str<E: de::Error>(self, valor: &str) -> Result<TaxaCambio, E> {
+        valor.parse().map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, valor: f64) -> Result<TaxaCambio, E> {
+        TaxaCambio::try_from(valor).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, valor: i64) -> Result<TaxaCambio, E> {
+        TaxaCambio::exata(Decimal::from(valor)).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, valor: u64) -> Result<TaxaCambio, E> {
+        TaxaCambio::exata(Decimal::from(valor)).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(valor: &str) -> TaxaCambio {
+        valor.parse().unwrap()
+    }
+
+    fn d(valor: &str) -> Dinheiro {
+        valor.parse().unwrap()
+    }
+
+    #[test]
+    fn le_e_escreve_com_seis_casas() {
+        assert_eq!(t("5.4321").to_string(), "5.432100");
+        assert_eq!(t("1").unidades(), 1_000_000);
+        assert_eq!(TaxaCambio::de_unidades(5_432_100).unwrap(), t("5.4321"));
+        assert!("1.1234567".parse::<TaxaCambio>().is_err());
+        assert!("0".parse::<TaxaCambio>().is_err());
+        assert!("-5".parse::<TaxaCambio>().is_err());
+    }
+
+    #[test]
+    fn converte_arredondando_para_o_centavo() {
+        assert_eq!(t("5.4321").converter(d("100")), d("543.21"));
+        assert_eq!(t("5.4321").converter(d("0.01")), d("0.05"));
+        assert_eq!(t("5.4321").desconverter(d("543.21")), d("100"));
+        assert_eq!(TaxaCambio::UM.converter(d("12.34")), d("12.34"));
+    }
+
+    #[test]
+    fn taxa_cruzada() {
+        let dolar = t("5.00");
+        let euro = t("6.00");
+        assert_eq!(dolar.cruzada(euro), Some(t("0.833333")));
+        assert_eq!(euro.cruzada(dolar), Some(t("1.2")));
+    }
+
+    #[test]
+    fn json_como_string() {
+        assert_eq!(serde_json::to_string(&t("5.4321")).unwrap(), "\"5.432100\"");
+        assert_eq!(
+            serde_json::from_str::<TaxaCambio>("5.4321").unwrap(),
+            t("5.4321")
+        );
+        assert_eq!(serde_json::from_str::<TaxaCambio>("2").unwrap(), t("2"));
+        assert!(serde_json::from_str::<TaxaCambio>("\"0\"").is_err());
+    }
+}
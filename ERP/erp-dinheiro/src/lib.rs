@@ -0,0 +1,701 @@
+//! # erp-dinheiro
+//!
+//! Valores monetários do ERP, compartilhados entre o backend e o
+//! frontend-wasm.
+//!
+//! [`Dinheiro`] é um decimal exato sempre com duas casas (centavos). No JSON
+//! ele é uma string (`"1234.56"`), para que nenhum cliente o leia como
+//! ponto flutuante; no MongoDB, com a feature `bson`, é gravado como
+//! `Decimal128`.
+//!
+//! ## Regras de arredondamento
+//!
+//! - Valores calculados (percentuais, conversões) são arredondados para o
+//!   centavo, com o meio centavo arredondado para longe do zero
+//!   ([`Dinheiro::arredondar`]).
+//! - Strings com mais de duas casas decimais são rejeitadas; números JSON,
+//!   que já chegam inexatos, são arredondados.
+//! - Valores lidos acima de [`Dinheiro::MAXIMO`], em módulo, são
+//!   rejeitados; somas e produtos de valores recebidos usam as operações
+//!   `checked_*`, que também param nesse limite em vez de estourar.
+//! - Parcelas ([`Dinheiro::dividir`]) e rateios ([`Dinheiro::ratear`])
+//!   somam exatamente o total: os centavos que sobram da divisão vão para
+//!   as primeiras parcelas.
+//!
+//! [`Aliquota`] é o percentual exato dos impostos, com quatro casas, e
+//! [`TaxaCambio`], a taxa de conversão entre moedas, com seis.
+//!
+//! Com a feature `openapi`, os tipos descrevem o próprio esquema para o
+//! documento OpenAPI do backend.
+
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use rust_decimal::RoundingStrategy;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+mod aliquota;
+mod cambio;
+#[cfg(feature = "openapi")]
+mod openapi;
+
+pub use aliquota::{Aliquota, CASAS_ALIQUOTA};
+pub use cambio::{TaxaCambio, CASAS_TAXA};
+pub use rust_decimal::Decimal;
+
+/// Casas decimais de todos os valores monetários.
+pub const CASAS_DECIMAIS: u32 = 2;
+
+/// Valor monetário exato, em unidades da moeda com duas casas decimais.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Dinheiro(Decimal);
+
+/// Valor que não pôde ser lido como [`Dinheiro`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErroDinheiro {
+    /// O texto não é um número decimal.
+    Invalido(String),
+    /// O valor tem mais casas decimais que a moeda permite.
+    CasasDecimais(String),
+    /// A alíquota é negativa ou tem mais de quatro casas decimais.
+    AliquotaInvalida(String),
+    /// A taxa de câmbio não é positiva ou tem mais de seis casas decimais.
+    TaxaInvalida(String),
+    /// O valor passa de [`Dinheiro::MAXIMO`] em módulo.
+    ForaDoLimite(String),
+}
+
+impl fmt::Display for ErroDinheiro {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErroDinheiro::Invalido(valor) => write!(f, "valor monetário inválido: {}", valor),
+            ErroDinheiro::CasasDecimais(valor) => write!(
+                f,
+                "valor monetário com mais de {} casas decimais: {}",
+                CASAS_DECIMAIS, valor
+            ),
+            ErroDinheiro::AliquotaInvalida(valor) => write!(
+                f,
+                "alíquota inválida: {} (use um percentual não negativo com até {} casas decimais)",
+                valor, CASAS_ALIQUOTA
+            ),
+            ErroDinheiro::TaxaInvalida(valor) => write!(
+                f,
+                "taxa de câmbio inválida: {} (use um valor positivo com até {} casas decimais)",
+                valor, CASAS_TAXA
+            ),
+            ErroDinheiro::ForaDoLimite(valor) => write!(
+                f,
+                "valor monetário acima do limite de {}: {}",
+                Dinheiro::MAXIMO,
+                valor
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ErroDinheiro {}
+
+impl Dinheiro {
+    pub const ZERO: Dinheiro = Dinheiro(Decimal::ZERO);
+    /// Maior valor aceito em módulo, `999999999999.99`: com ele os centavos
+    /// de qualquer soma razoável cabem em `i64`.
+    pub const MAXIMO: Dinheiro = Dinheiro(Decimal::from_parts(
+        0x107A_3FFF,
+        0x5AF3,
+        0,
+        false,
+        CASAS_DECIMAIS,
+    ));
+
+    /// Arredonda para o centavo, com o meio centavo para longe do zero
+    /// (`0,005` vira `0,01` e `-0,005` vira `-0,01`).
+    pub fn arredondar(valor: Decimal) -> Self {
+        let mut valor =
+            valor.round_dp_with_strategy(CASAS_DECIMAIS, RoundingStrategy::MidpointAwayFromZero);
+        valor.rescale(CASAS_DECIMAIS);
+        Dinheiro(valor)
+    }
+
+    /// Aceita apenas valores que já estão em centavos e dentro do limite.
+    pub fn exato(valor: Decimal) -> Result<Self, ErroDinheiro> {
+        if valor.normalize().scale() > CASAS_DECIMAIS {
+            return Err(ErroDinheiro::CasasDecimais(valor.to_string()));
+        }
+        Self::arredondar(valor).limitado()
+    }
+
+    /// O próprio valor, se estiver dentro de [`Dinheiro::MAXIMO`].
+    fn limitado(self) -> Result<Self, ErroDinheiro> {
+        if self.0.abs() > Self::MAXIMO.0 {
+            return Err(ErroDinheiro::ForaDoLimite(self.to_string()));
+        }
+        Ok(self)
+    }
+
+    pub fn de_centavos(centavos: i64) -> Self {
+        Dinheiro(Decimal::new(centavos, CASAS_DECIMAIS))
+    }
+
+    /// Valor em centavos. Cabe em `i64` para qualquer valor lido pelo
+    /// sistema; o excedente satura.
+    pub fn centavos(&self) -> i64 {
+        let mut centavos = self.0;
+        centavos.rescale(CASAS_DECIMAIS);
+        i64::try_from(centavos.mantissa()).unwrap_or(if centavos.is_sign_negative() {
+            i64::MIN
+        } else {
+            i64::MAX
+        })
+    }
+
+    /// Valor de ponto flutuante aproximado, para gráficos e exibição; nunca
+    /// use o resultado em cálculos.
+    pub fn aproximado(&self) -> f64 {
+        self.centavos() as f64 / 100.0
+    }
+
+    pub fn decimal(&self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn positivo(&self) -> bool {
+        self.0 > Decimal::ZERO
+    }
+
+    pub fn negativo(&self) -> bool {
+        self.0 < Decimal::ZERO
+    }
+
+    pub fn abs(self) -> Self {
+        Dinheiro(self.0.abs())
+    }
+
+    /// Preço vezes quantidade; exato.
+    pub fn vezes(self, quantidade: i64) -> Self {
+        Dinheiro(self.0 * Decimal::from(quantidade))
+    }
+
+    /// Soma; `None` quando o resultado passa de [`Dinheiro::MAXIMO`].
+    pub fn checked_add(self, outro: Dinheiro) -> Option<Self> {
+        self.0
+            .checked_add(outro.0)
+            .and_then(|valor| Dinheiro(valor).limitado().ok())
+    }
+
+    /// Subtração; `None` quando o resultado passa de [`Dinheiro::MAXIMO`].
+    pub fn checked_sub(self, outro: Dinheiro) -> Option<Self> {
+        self.0
+            .checked_sub(outro.0)
+            .and_then(|valor| Dinheiro(valor).limitado().ok())
+    }
+
+    /// [`Dinheiro::vezes`]; `None` quando o resultado passa de
+    /// [`Dinheiro::MAXIMO`].
+    pub fn checked_mul(self, quantidade: i64) -> Option<Self> {
+        self.0
+            .checked_mul(Decimal::from(quantidade))
+            .and_then(|valor| Dinheiro(valor).limitado().ok())
+    }
+
+    /// Soma dos valores; `None` quando alguma soma parcial passa de
+    /// [`Dinheiro::MAXIMO`].
+    pub fn checked_sum(valores: impl IntoIterator<Item = Dinheiro>) -> Option<Self> {
+        valores
+            .into_iter()
+            .try_fold(Dinheiro::ZERO, Dinheiro::checked_add)
+    }
+
+    /// `percentual` por cento do valor, arredondado (ex.: `percentual(10)`
+    /// para 10%).
+    pub fn percentual(self, percentual: Decimal) -> Self {
+        Self::arredondar(self.0 * percentual / Decimal::ONE_HUNDRED)
+    }
+
+    /// Média de `quantidade` valores que somam `self`, arredondada (ex.:
+    /// ticket médio); zero quando não há valores. Para parcelas que precisam
+    /// fechar o total, use [`Dinheiro::dividir`].
+    pub fn media(self, quantidade: u32) -> Self {
+        if quantidade == 0 {
+            return Dinheiro::ZERO;
+        }
+        Self::arredondar(self.0 / Decimal::from(quantidade))
+    }
+
+    /// Divide o valor em `partes` parcelas que somam exatamente o total;
+    /// os centavos restantes vão para as primeiras parcelas.
+    pub fn dividir(self, partes: u32) -> Vec<Self> {
+        if partes == 0 {
+            return Vec::new();
+        }
+        self.ratear(&vec![Dinheiro::de_centavos(1); partes as usize])
+    }
+
+    /// Reparte o valor proporcionalmente aos `pesos` (ex.: um desconto entre
+    /// os itens da venda). As partes somam exatamente o total; os centavos
+    /// restantes vão, um a um, para as primeiras partes com peso. Sem pesos
+    /// positivos, tudo fica na primeira parte.
+    pub fn ratear(self, pesos: &[Dinheiro]) -> Vec<Self> {
+        if pesos.is_empty() {
+            return Vec::new();
+        }
+
+        let soma: i128 = pesos
+            .iter()
+            .map(|peso| peso.centavos().max(0) as i128)
+            .sum();
+        let total = self.centavos() as i128;
+        if soma == 0 {
+            let mut partes = vec![Dinheiro::ZERO; pesos.len()];
+            partes[0] = self;
+            return partes;
+        }
+
+        let mut partes: Vec<i128> = pesos
+            .iter()
+            .map(|peso| total * peso.centavos().max(0) as i128 / soma)
+            .collect();
+        // A divisão trunca cada parte em menos de um centavo, então sobram
+        // menos centavos do que partes com peso.
+        let mut restante = total - partes.iter().sum::<i128>();
+        let passo = restante.signum();
+        for (parte, peso) in partes.iter_mut().zip(pesos) {
+            if restante == 0 {
+                break;
+            }
+            if peso.positivo() {
+                *parte += passo;
+                restante -= passo;
+            }
+        }
+
+        partes
+            .into_iter()
+            .map(|centavos| Dinheiro::de_centavos(centavos as i64))
+            .collect()
+    }
+
+    /// No padrão brasileiro, sem símbolo: `1.234,56`, `-10,00`.
+    pub fn formatado(&self) -> String {
+        let texto = self.abs().to_string();
+        let (inteiro, fracao) = texto.split_once('.').unwrap_or((&texto, "00"));
+
+        let mut milhares = String::with_capacity(inteiro.len() + inteiro.len() / 3);
+        for (indice, digito) in inteiro.chars().enumerate() {
+            if indice > 0 && (inteiro.len() - indice) % 3 == 0 {
+                milhares.push('.');
+            }
+            milhares.push(digito);
+        }
+
+        let sinal = if self.negativo() { "-" } else { "" };
+        format!("{}{},{}", sinal, milhares, fracao)
+    }
+}
+
+impl fmt::Display for Dinheiro {
+    /// Sempre com duas casas e ponto decimal (`1234.50`), o mesmo formato
+    /// do JSON.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut valor = self.0;
+        valor.rescale(CASAS_DECIMAIS);
+        write!(f, "{}", valor)
+    }
+}
+
+impl FromStr for Dinheiro {
+    type Err = ErroDinheiro;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let texto = s.trim();
+        let valor = Decimal::from_str(texto)
+            .or_else(|_| Decimal::from_scientific(texto))
+            .map_err(|_| ErroDinheiro::Invalido(s.to_string()))?;
+        Self::exato(valor)
+    }
+}
+
+impl TryFrom<f64> for Dinheiro {
+    type Error = ErroDinheiro;
+
+    /// Arredonda para o centavo; parte da representação decimal mais curta
+    /// do `f64`, então `0.1` vira exatamente `0.10`.
+    fn try_from(valor: f64) -> Result<Self, Self::Error> {
+        if !valor.is_finite() {
+            return Err(ErroDinheiro::Invalido(valor.to_string()));
+        }
+        let texto = valor.to_string();
+        Decimal::from_str(&texto)
+            .or_else(|_| Decimal::from_scientific(&format!("{:e}", valor)))
+            .map_err(|_| ErroDinheiro::Invalido(texto))
+            .and_then(|valor| Self::arredondar(valor).limitado())
+    }
+}
+
+impl From<i64> for Dinheiro {
+    fn from(valor: i64) -> Self {
+        Self::arredondar(Decimal::from(valor))
+    }
+}
+
+impl Add for Dinheiro {
+    type Output = Dinheiro;
+
+    fn add(self, outro: Dinheiro) -> Dinheiro {
+        Dinheiro(self.0 + outro.0)
+    }
+}
+
+impl Sub for Dinheiro {
+    type Output = Dinheiro;
+
+    fn sub(self, outro: Dinheiro) -> Dinheiro {
+        Dinheiro(self.0 - outro.0)
+    }
+}
+
+impl Neg for Dinheiro {
+    type Output = Dinheiro;
+
+    fn neg(self) -> Dinheiro {
+        Dinheiro(-self.0)
+    }
+}
+
+impl AddAssign for Dinheiro {
+    fn add_assign(&mut self, outro: Dinheiro) {
+        self.0 += outro.0;
+    }
+}
+
+impl SubAssign for Dinheiro {
+    fn sub_assign(&mut self, outro: Dinheiro) {
+        self.0 -= outro.0;
+    }
+}
+
+impl Sum for Dinheiro {
+    fn sum<I: Iterator<Item = Dinheiro>>(iter: I) -> Self {
+        iter.fold(Dinheiro::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Dinheiro> for Dinheiro {
+    fn sum<I: Iterator<Item = &'a Dinheiro>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Serialize for Dinheiro {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "bson")]
+        if !serializer.is_human_readable() {
+            return bson::Decimal128::from(*self).serialize(serializer);
+        }
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Dinheiro {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[cfg(feature = "bson")]
+        if !deserializer.is_human_readable() {
+            let valor = bson::Bson::deserialize(deserializer)?;
+            return Dinheiro::try_from(&valor).map_err(de::Error::custom);
+        }
+        deserializer.deserialize_any(VisitanteDinheiro)
+    }
+}
+
+struct VisitanteDinheiro;
+
+impl de::Visitor<'_> for VisitanteDinheiro {
+    type Value = Dinheiro;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("um valor monetário como string (\"1234.56\") ou número")
+    }
+
+    fn visit_str<E: de::Error>(self, valor: &str) -> Result<Dinheiro, E> {
+        valor.parse().map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, valor: f64) -> Result<Dinheiro, E> {
+        Dinheiro::try_from(valor).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, valor: i64) -> Result<Dinheiro, E> {
+        Dinheiro::from(valor).limitado().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, valor: u64) -> Result<Dinheiro, E> {
+        i64::try_from(valor)
+            .map_err(|_| ErroDinheiro::ForaDoLimite(valor.to_string()))
+            .and_then(|valor| Dinheiro::from(valor).limitado())
+            .map_err(E::custom)
+    }
+}
+
+#[cfg(feature = "bson")]
+mod conversoes_bson {
+    use super::*;
+    use bson::{Bson, Decimal128};
+
+    impl From<Dinheiro> for Decimal128 {
+        fn from(valor: Dinheiro) -> Self {
+            valor
+                .to_string()
+                .parse()
+                .expect("um decimal com duas casas sempre cabe em Decimal128")
+        }
+    }
+
+    impl From<Dinheiro> for Bson {
+        fn from(valor: Dinheiro) -> Self {
+            Bson::Decimal128(valor.into())
+        }
+    }
+
+    impl TryFrom<&Bson> for Dinheiro {
+        type Error = ErroDinheiro;
+
+        /// Aceita os `Decimal128` gravados pelo sistema e os números de
+        /// versões anteriores, arredondados para o centavo.
+        fn try_from(valor: &Bson) -> Result<Self, Self::Error> {
+            match valor {
+                Bson::Decimal128(decimal) => {
+                    let texto = decimal.to_string();
+                    Decimal::from_str(&texto)
+                        .or_else(|_| Decimal::from_scientific(&texto))
+                        .map(Dinheiro::arredondar)
+                        .map_err(|_| ErroDinheiro::Invalido(texto))
+                }
+                Bson::Double(valor) => Dinheiro::try_from(*valor),
+                Bson::Int32(valor) => Ok(Dinheiro::from(i64::from(*valor))),
+                Bson::Int64(valor) => Ok(Dinheiro::from(*valor)),
+                Bson::String(texto) => texto.parse(),
+                outro => Err(ErroDinheiro::Invalido(outro.to_string())),
+            }
+        }
+    }
+}
+
+/// Moedas aceitas, pelo código ISO 4217. Todas têm duas casas decimais.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum Moeda {
+    #[default]
+    #[serde(rename = "BRL")]
+    Brl,
+    #[serde(rename = "EUR")]
+    Eur,
+    #[serde(rename = "USD")]
+    Usd,
+}
+
+impl Moeda {
+    pub const TODAS: &'static [Moeda] = &[Moeda::Brl, Moeda::Eur, Moeda::Usd];
+
+    pub fn codigo(&self) -> &'static str {
+        match self {
+            Moeda::Brl => "BRL",
+            Moeda::Eur => "EUR",
+            Moeda::Usd => "USD",
+        }
+    }
+
+    pub fn simbolo(&self) -> &'static str {
+        match self {
+            Moeda::Brl => "R$",
+            Moeda::Eur => "€",
+            Moeda::Usd => "US$",
+        }
+    }
+
+    pub fn casas_decimais(&self) -> u32 {
+        CASAS_DECIMAIS
+    }
+
+    /// Formata no padrão brasileiro, com o símbolo da moeda:
+    /// `R$ 1.234,56`, `-€ 10,00`.
+    pub fn formatar(&self, valor: Dinheiro) -> String {
+        let sinal = if valor.negativo() { "-" } else { "" };
+        format!("{}{} {}", sinal, self.simbolo(), valor.abs().formatado())
+    }
+}
+
+impl fmt::Display for Moeda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.codigo())
+    }
+}
+
+impl FromStr for Moeda {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Moeda::TODAS
+            .iter()
+            .find(|moeda| moeda.codigo().eq_ignore_ascii_case(s.trim()))
+            .copied()
+            .ok_or_else(|| format!("moeda não suportada: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(valor: &str) -> Dinheiro {
+        valor.parse().unwrap()
+    }
+
+    #[test]
+    fn le_e_escreve_com_duas_casas() {
+        assert_eq!(d("10").to_string(), "10.00");
+        assert_eq!(d("0.1").to_string(), "0.10");
+        assert_eq!(d("-3.5").to_string(), "-3.50");
+        assert_eq!(d("1.230").to_string(), "1.23");
+        assert!("1.234".parse::<Dinheiro>().is_err());
+        assert!("abc".parse::<Dinheiro>().is_err());
+    }
+
+    #[test]
+    fn soma_sem_erro_de_ponto_flutuante() {
+        let total: Dinheiro = [d("0.1"), d("0.2")].iter().sum();
+        assert_eq!(total, d("0.3"));
+        assert_eq!(d("19.90").vezes(3), d("59.70"));
+    }
+
+    #[test]
+    fn arredonda_meio_centavo_para_longe_do_zero() {
+        assert_eq!(Dinheiro::arredondar(Decimal::new(1005, 3)), d("1.01"));
+        assert_eq!(Dinheiro::arredondar(Decimal::new(-1005, 3)), d("-1.01"));
+        assert_eq!(Dinheiro::arredondar(Decimal::new(1004, 3)), d("1.00"));
+        assert_eq!(d("99.99").percentual(Decimal::new(15, 0)), d("15.00"));
+        assert_eq!(Dinheiro::try_from(0.1 + 0.2).unwrap(), d("0.30"));
+    }
+
+    #[test]
+    fn parcelas_somam_o_total() {
+        let parcelas = d("100").dividir(3);
+        assert_eq!(parcelas, vec![d("33.34"), d("33.33"), d("33.33")]);
+        assert_eq!(parcelas.iter().sum::<Dinheiro>(), d("100"));
+
+        let parcelas = d("0.05").dividir(3);
+        assert_eq!(parcelas, vec![d("0.02"), d("0.02"), d("0.01")]);
+
+        let parcelas = d("-10").dividir(3);
+        assert_eq!(parcelas, vec![d("-3.34"), d("-3.33"), d("-3.33")]);
+        assert!(d("10").dividir(0).is_empty());
+    }
+
+    #[test]
+    fn recusa_valores_acima_do_limite() {
+        assert_eq!(Dinheiro::MAXIMO, d("999999999999.99"));
+        assert_eq!(-Dinheiro::MAXIMO, d("-999999999999.99"));
+        assert!(matches!(
+            "1000000000000".parse::<Dinheiro>(),
+            Err(ErroDinheiro::ForaDoLimite(_))
+        ));
+        assert!("1e30".parse::<Dinheiro>().is_err());
+        assert!(serde_json::from_str::<Dinheiro>("1e15").is_err());
+        assert!(serde_json::from_str::<Dinheiro>("18446744073709551615").is_err());
+        assert!(serde_json::from_str::<Dinheiro>("-1000000000000").is_err());
+    }
+
+    #[test]
+    fn operacoes_checadas_param_no_limite() {
+        assert_eq!(d("1.50").checked_add(d("2.25")), Some(d("3.75")));
+        assert_eq!(Dinheiro::MAXIMO.checked_add(d("0.01")), None);
+        assert_eq!(
+            d("1").checked_sub(Dinheiro::MAXIMO),
+            Some(d("-999999999998.99"))
+        );
+        assert_eq!((-Dinheiro::MAXIMO).checked_sub(d("0.01")), None);
+        assert_eq!(d("19.90").checked_mul(3), Some(d("59.70")));
+        assert_eq!(Dinheiro::MAXIMO.checked_mul(i64::MAX), None);
+        assert_eq!(Dinheiro::checked_sum([d("0.1"), d("0.2")]), Some(d("0.3")));
+        assert_eq!(
+            Dinheiro::checked_sum([Dinheiro::MAXIMO, Dinheiro::MAXIMO]),
+            None
+        );
+    }
+
+    #[test]
+    fn media_arredondada() {
+        assert_eq!(d("100").media(3), d("33.33"));
+        assert_eq!(d("0.05").media(2), d("0.03"));
+        assert_eq!(d("10").media(0), Dinheiro::ZERO);
+    }
+
+    #[test]
+    fn rateio_proporcional() {
+        let partes = d("10").ratear(&[d("50"), d("30"), d("20")]);
+        assert_eq!(partes, vec![d("5"), d("3"), d("2")]);
+
+        let partes = d("1").ratear(&[d("1"), d("1"), d("1")]);
+        assert_eq!(partes, vec![d("0.34"), d("0.33"), d("0.33")]);
+
+        let partes = d("5").ratear(&[Dinheiro::ZERO, Dinheiro::ZERO]);
+        assert_eq!(partes, vec![d("5"), Dinheiro::ZERO]);
+    }
+
+    #[test]
+    fn json_como_string() {
+        assert_eq!(serde_json::to_string(&d("12.5")).unwrap(), "\"12.50\"");
+        assert_eq!(
+            serde_json::from_str::<Dinheiro>("\"12.5\"").unwrap(),
+            d("12.5")
+        );
+        assert_eq!(
+            serde_json::from_str::<Dinheiro>("12.345").unwrap(),
+            d("12.35")
+        );
+        assert_eq!(serde_json::from_str::<Dinheiro>("7").unwrap(), d("7"));
+        assert!(serde_json::from_str::<Dinheiro>("\"12.345\"").is_err());
+    }
+
+    #[test]
+    fn formata_moedas() {
+        assert_eq!(Moeda::Brl.formatar(d("1234567.8")), "R$ 1.234.567,80");
+        assert_eq!(Moeda::Eur.formatar(d("-10")), "-€ 10,00");
+        assert_eq!(Moeda::Usd.formatar(d("0.5")), "US$ 0,50");
+        assert_eq!(d("-1234.5").formatado(), "-1.234,50");
+        assert_eq!("usd".parse::<Moeda>(), Ok(Moeda::Usd));
+        assert_eq!(serde_json::to_string(&Moeda::Eur).unwrap(), "\"EUR\"");
+    }
+
+    #[cfg(feature = "bson")]
+    #[test]
+    fn bson_como_decimal128() {
+        #[derive(Serialize, Deserialize)]
+        struct Registro {
+            valor: Dinheiro,
+        }
+
+        let bytes = bson::to_vec(&Registro {
+            valor: d("1234.56"),
+        })
+        .unwrap();
+        let documento = bson::Document::from_reader(bytes.as_slice()).unwrap();
+        assert!(matches!(
+            documento.get("valor"),
+            Some(bson::Bson::Decimal128(_))
+        ));
+        let lido: Registro = bson::from_slice(&bytes).unwrap();
+        assert_eq!(lido.valor, d("1234.56"));
+
+        let antigo = bson::to_vec(&bson::doc! { "valor": 33.333333 }).unwrap();
+        let lido: Registro = bson::from_slice(&antigo).unwrap();
+        assert_eq!(lido.valor, d("33.33"));
+    }
+}
@@ -0,0 +1,75 @@
+//! Esquemas OpenAPI: os três decimais trafegam como strings, com o número
+//! de casas de cada um.
+
+use std::borrow::Cow;
+
+use utoipa::{
+    openapi::{
+        schema::{ObjectBuilder, Type},
+        RefOr, Schema,
+    },
+    PartialSchema, ToSchema,
+};
+
+use crate::{Aliquota, Dinheiro, TaxaCambio, CASAS_ALIQUOTA, CASAS_DECIMAIS, CASAS_TAXA};
+
+fn decimal(casas: u32, negativo: bool, descricao: &str, exemplo: &str) -> RefOr<Schema> {
+    let sinal = if negativo { "-?" } else { "" };
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .pattern(Some(format!(r"^{}\d+(\.\d{{1,{}}})?$", sinal, casas)))
+        .description(Some(descricao))
+        .examples([exemplo])
+        .into()
+}
+
+impl PartialSchema for Dinheiro {
+    fn schema() -> RefOr<Schema> {
+        decimal(
+            CASAS_DECIMAIS,
+            true,
+            "Valor monetário exato, com até duas casas decimais e até 999999999999.99 em módulo.",
+            "1234.50",
+        )
+    }
+}
+
+impl ToSchema for Dinheiro {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Dinheiro")
+    }
+}
+
+impl PartialSchema for Aliquota {
+    fn schema() -> RefOr<Schema> {
+        decimal(
+            CASAS_ALIQUOTA,
+            false,
+            "Alíquota percentual, não negativa, com até quatro casas decimais.",
+            "18.0000",
+        )
+    }
+}
+
+impl ToSchema for Aliquota {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Aliquota")
+    }
+}
+
+impl PartialSchema for TaxaCambio {
+    fn schema() -> RefOr<Schema> {
+        decimal(
+            CASAS_TAXA,
+            false,
+            "Taxa de câmbio positiva, com até seis casas decimais.",
+            "5.432100",
+        )
+    }
+}
+
+impl ToSchema for TaxaCambio {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("TaxaCambio")
+    }
+}
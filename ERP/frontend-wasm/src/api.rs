@@ -5,6 +5,28 @@ use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
 const API_BASE: &str = "http://localhost:3000/api/v1";
+const CHAVE_TOKEN: &str = "erp_token";
+const CHAVE_EMPRESA: &str = "erp_empresa";
+
+fn ler_storage(chave: &str) -> Option<String> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(chave).ok().flatten())
+}
+
+/// Anexa o token e a empresa selecionada salvos no `localStorage` (se
+/// houver) aos cabeçalhos `Authorization` e `X-Empresa-Id`.
+fn autenticar(request: &Request) -> Result<(), JsValue> {
+    if let Some(token) = ler_storage(CHAVE_TOKEN) {
+        request
+            .headers()
+            .set("Authorization", &format!("Bearer {}", token))?;
+    }
+    if let Some(empresa) = ler_storage(CHAVE_EMPRESA) {
+        request.headers().set("X-Empresa-Id", &empresa)?;
+    }
+    Ok(())
+}
 
 pub async fn fetch_json<T: DeserializeOwned>(path: &str) -> Result<T, JsValue> {
     let url = format!("{}{}", API_BASE, path);
@@ -15,6 +37,7 @@ pub async fn fetch_json<T: DeserializeOwned>(path: &str) -> Result<T, JsValue> {
 
     let request = Request::new_with_str_and_init(&url, &opts)?;
     request.headers().set("Accept", "application/json")?;
+    autenticar(&request)?;
     let window = web_sys::window().unwrap();
     let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
     let resp: Response = resp_value.dyn_into()?;
@@ -36,6 +59,7 @@ pub async fn post_json<T: DeserializeOwned>(path: &str, body: &str) -> Result<T,
     let request = Request::new_with_str_and_init(&url, &opts)?;
     request.headers().set("Content-Type", "application/json")?;
     request.headers().set("Accept", "application/json")?;
+    autenticar(&request)?;
     let window = web_sys::window().unwrap();
     let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
     let resp: Response = resp_value.dyn_into()?;
@@ -54,6 +78,7 @@ pub async fn delete(path: &str) -> Result<(), JsValue> {
     opts.set_mode(RequestMode::Cors);
 
     let request = Request::new_with_str_and_init(&url, &opts)?;
+    autenticar(&request)?;
     let window = web_sys::window().unwrap();
     JsFuture::from(window.fetch_with_request(&request)).await?;
 
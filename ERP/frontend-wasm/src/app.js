@@ -34,16 +34,27 @@ class FinanceiroApp {
         }
     }
     async fetchContas() {
-        const response = await fetch(`${API_BASE_URL}/financeiro/contas`);
+        const response = await fetch(`${API_BASE_URL}/financeiro/contas?limit=200`);
         if (!response.ok)
             throw new Error('Erro ao buscar contas');
-        return response.json();
+        const pagina = await response.json();
+        // A API envia os valores monetários como texto ("1234.50")
+        return pagina.itens.map(conta => ({
+            ...conta,
+            saldo_inicial: Number(conta.saldo_inicial),
+            saldo_atual: Number(conta.saldo_atual),
+        }));
     }
     async fetchCartoes() {
-        const response = await fetch(`${API_BASE_URL}/financeiro/cartoes`);
+        const response = await fetch(`${API_BASE_URL}/financeiro/cartoes?limit=200`);
         if (!response.ok)
             throw new Error('Erro ao buscar cartões');
-        return response.json();
+        const pagina = await response.json();
+        return pagina.itens.map(cartao => ({
+            ...cartao,
+            limite_total: Number(cartao.limite_total),
+            limite_disponivel: Number(cartao.limite_disponivel),
+        }));
     }
     renderContas() {
         const tbody = document.getElementById('contasTable');
@@ -23,6 +23,13 @@ interface Cartao {
     ativo: boolean;
 }
 
+interface Pagina<T> {
+    itens: T[];
+    total: number;
+    pagina: number;
+    limite: number;
+}
+
 interface BancoInfo {
     codigo: string;
     nome: string;
@@ -76,15 +83,26 @@ class FinanceiroApp {
     }
 
     private async fetchContas(): Promise<ContaBancaria[]> {
-        const response = await fetch(`${API_BASE_URL}/financeiro/contas`);
+        const response = await fetch(`${API_BASE_URL}/financeiro/contas?limit=200`);
         if (!response.ok) throw new Error('Erro ao buscar contas');
-        return await response.json() as ContaBancaria[];
+        const pagina = await response.json() as Pagina<ContaBancaria>;
+        // A API envia os valores monetários como texto ("1234.50")
+        return pagina.itens.map(conta => ({
+            ...conta,
+            saldo_inicial: Number(conta.saldo_inicial),
+            saldo_atual: Number(conta.saldo_atual),
+        }));
     }
 
     private async fetchCartoes(): Promise<Cartao[]> {
-        const response = await fetch(`${API_BASE_URL}/financeiro/cartoes`);
+        const response = await fetch(`${API_BASE_URL}/financeiro/cartoes?limit=200`);
         if (!response.ok) throw new Error('Erro ao buscar cartões');
-        return await response.json() as Cartao[];
+        const pagina = await response.json() as Pagina<Cartao>;
+        return pagina.itens.map(cartao => ({
+            ...cartao,
+            limite_total: Number(cartao.limite_total),
+            limite_disponivel: Number(cartao.limite_disponivel),
+        }));
     }
 
     private renderContas() {
@@ -1,8 +1,9 @@
+use erp_dinheiro::{Dinheiro, Moeda};
 use wasm_bindgen::JsCast;
 use web_sys::{window, Element};
 
-pub fn format_currency(value: f64) -> String {
-    format!("R$ {:.2}", value).replace('.', ",")
+pub fn format_currency(value: Dinheiro) -> String {
+    Moeda::Brl.formatar(value)
 }
 
 pub fn format_date(date_str: &str) -> String {
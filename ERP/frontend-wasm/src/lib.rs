@@ -6,7 +6,6 @@ use web_sys::{window, Document};
 
 mod api;
 mod components;
-mod models;
 mod pages;
 
 use pages::{Clientes, Contas, Dashboard, Produtos, Vendas};
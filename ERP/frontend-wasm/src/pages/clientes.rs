@@ -1,4 +1,5 @@
-use crate::{api, components, models::Cliente};
+use crate::{api, components};
+use erp_api::{ClienteResponse, Pagina};
 use wasm_bindgen::prelude::*;
 
 pub struct Clientes;
@@ -7,15 +8,15 @@ impl Clientes {
     pub async fn load() -> Result<(), JsValue> {
         components::show_loading(true);
 
-        let clientes: Vec<Cliente> = api::fetch_json("/clientes").await?;
+        let clientes: Pagina<ClienteResponse> = api::fetch_json("/clientes?limit=200").await?;
 
-        Self::render(&clientes)?;
+        Self::render(&clientes.itens)?;
 
         components::show_loading(false);
         Ok(())
     }
 
-    fn render(clientes: &[Cliente]) -> Result<(), JsValue> {
+    fn render(clientes: &[ClienteResponse]) -> Result<(), JsValue> {
         let mut html = String::new();
 
         if clientes.is_empty() {
@@ -30,8 +31,8 @@ impl Clientes {
                         <td>{}</td>
                         <td>{}</td>
                         <td>
-                            <button class="btn btn-warning" onclick="editarCliente({})">Editar</button>
-                            <button class="btn btn-danger" onclick="deletarCliente({})">Excluir</button>
+                            <button class="btn btn-warning" onclick="editarCliente('{}')">Editar</button>
+                            <button class="btn btn-danger" onclick="deletarCliente('{}')">Excluir</button>
                         </td>
                     </tr>"#,
                     c.nome,
@@ -39,8 +40,8 @@ impl Clientes {
                     c.telefone.as_deref().unwrap_or("-"),
                     c.email.as_deref().unwrap_or("-"),
                     c.cidade.as_deref().unwrap_or("-"),
-                    c.id,
-                    c.id
+                    c.id.as_deref().unwrap_or(""),
+                    c.id.as_deref().unwrap_or("")
                 ));
             }
         }
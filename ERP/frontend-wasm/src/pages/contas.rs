@@ -1,7 +1,5 @@
-use crate::{
-    api, components,
-    models::{Cartao, ContaBancaria},
-};
+use crate::{api, components};
+use erp_api::{CartaoResponse, ContaResponse, Pagina};
 use wasm_bindgen::prelude::*;
 
 pub struct Contas;
@@ -11,17 +9,18 @@ impl Contas {
         components::show_loading(true);
 
         // Carregar contas e cartões em paralelo
-        let contas: Vec<ContaBancaria> = api::fetch_json("/financeiro/contas").await?;
-        let cartoes: Vec<Cartao> = api::fetch_json("/financeiro/cartoes").await?;
+        let contas: Pagina<ContaResponse> = api::fetch_json("/financeiro/contas?limit=200").await?;
+        let cartoes: Pagina<CartaoResponse> =
+            api::fetch_json("/financeiro/cartoes?limit=200").await?;
 
-        Self::render_contas(&contas)?;
-        Self::render_cartoes(&cartoes)?;
+        Self::render_contas(&contas.itens)?;
+        Self::render_cartoes(&cartoes.itens)?;
 
         components::show_loading(false);
         Ok(())
     }
 
-    fn render_contas(contas: &[ContaBancaria]) -> Result<(), JsValue> {
+    fn render_contas(contas: &[ContaResponse]) -> Result<(), JsValue> {
         let mut html = String::new();
 
         if contas.is_empty() {
@@ -42,7 +41,7 @@ impl Contas {
                         <td>{}</td>
                         <td>{}</td>
                         <td>{}</td>
-                        <td>R$ {:.2}</td>
+                        <td>{}</td>
                         <td>
                             <button class="btn btn-sm btn-primary" onclick="editarConta('{}')">✏️</button>
                             <button class="btn btn-sm btn-danger" onclick="deletarConta('{}')">🗑️</button>
@@ -53,7 +52,7 @@ impl Contas {
                     tipo_display,
                     conta.agencia,
                     conta.numero_conta,
-                    conta.saldo_atual,
+                    conta.moeda.formatar(conta.saldo_atual),
                     conta.id.as_deref().unwrap_or(""),
                     conta.id.as_deref().unwrap_or(""),
                 ));
@@ -64,7 +63,7 @@ impl Contas {
         Ok(())
     }
 
-    fn render_cartoes(cartoes: &[Cartao]) -> Result<(), JsValue> {
+    fn render_cartoes(cartoes: &[CartaoResponse]) -> Result<(), JsValue> {
         let mut html = String::new();
 
         if cartoes.is_empty() {
@@ -85,7 +84,7 @@ impl Contas {
                         <td>{}</td>
                         <td>{}</td>
                         <td>**** {}</td>
-                        <td>R$ {:.2}</td>
+                        <td>{}</td>
                         <td>{}/{}</td>
                         <td>
                             <button class="btn btn-sm btn-primary" onclick="editarCartao('{}')">✏️</button>
@@ -95,7 +94,7 @@ impl Contas {
                     cartao.nome,
                     bandeira_emoji,
                     cartao.ultimos_digitos,
-                    cartao.limite_disponivel,
+                    components::format_currency(cartao.limite_disponivel),
                     cartao.dia_fechamento,
                     cartao.dia_vencimento,
                     cartao.id.as_deref().unwrap_or(""),
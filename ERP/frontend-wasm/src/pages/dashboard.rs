@@ -1,4 +1,5 @@
-use crate::{api, components, models::DashboardData};
+use crate::{api, components};
+use erp_api::DashboardData;
 use wasm_bindgen::prelude::*;
 
 pub struct Dashboard;
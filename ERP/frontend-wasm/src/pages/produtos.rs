@@ -1,4 +1,5 @@
-use crate::{api, components, models::Produto};
+use crate::{api, components};
+use erp_api::{Pagina, ProdutoResponse};
 use wasm_bindgen::prelude::*;
 
 pub struct Produtos;
@@ -7,15 +8,15 @@ impl Produtos {
     pub async fn load() -> Result<(), JsValue> {
         components::show_loading(true);
 
-        let produtos: Vec<Produto> = api::fetch_json("/produtos").await?;
+        let produtos: Pagina<ProdutoResponse> = api::fetch_json("/produtos?limit=200").await?;
 
-        Self::render(&produtos)?;
+        Self::render(&produtos.itens)?;
 
         components::show_loading(false);
         Ok(())
     }
 
-    fn render(produtos: &[Produto]) -> Result<(), JsValue> {
+    fn render(produtos: &[ProdutoResponse]) -> Result<(), JsValue> {
         let mut html = String::new();
 
         if produtos.is_empty() {
@@ -38,8 +39,8 @@ impl Produtos {
                         <td>{}</td>
                         <td>{}</td>
                         <td>
-                            <button class="btn btn-primary" onclick="movimentarEstoque({})">Movimentar</button>
-                            <button class="btn btn-warning" onclick="editarProduto({})">Editar</button>
+                            <button class="btn btn-primary" onclick="movimentarEstoque('{}')">Movimentar</button>
+                            <button class="btn btn-warning" onclick="editarProduto('{}')">Editar</button>
                         </td>
                     </tr>"#,
                     p.nome,
@@ -47,8 +48,8 @@ impl Produtos {
                     components::format_currency(p.preco_venda),
                     p.estoque_atual,
                     badge,
-                    p.id,
-                    p.id
+                    p.id.as_deref().unwrap_or(""),
+                    p.id.as_deref().unwrap_or("")
                 ));
             }
         }
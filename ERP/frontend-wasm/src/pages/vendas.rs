@@ -1,4 +1,5 @@
-use crate::{api, components, models::Venda};
+use crate::{api, components};
+use erp_api::{Pagina, StatusVenda, VendaResponse};
 use wasm_bindgen::prelude::*;
 
 pub struct Vendas;
@@ -7,26 +8,29 @@ impl Vendas {
     pub async fn load() -> Result<(), JsValue> {
         components::show_loading(true);
 
-        let vendas: Vec<Venda> = api::fetch_json("/vendas").await?;
+        let vendas: Pagina<VendaResponse> = api::fetch_json("/vendas?limit=200").await?;
 
-        Self::render(&vendas)?;
+        Self::render(&vendas.itens)?;
 
         components::show_loading(false);
         Ok(())
     }
 
-    fn render(vendas: &[Venda]) -> Result<(), JsValue> {
+    fn render(vendas: &[VendaResponse]) -> Result<(), JsValue> {
         let mut html = String::new();
 
         if vendas.is_empty() {
             html.push_str(r#"<tr><td colspan="6" style="text-align: center;">Nenhuma venda registrada</td></tr>"#);
         } else {
             for v in vendas {
-                let badge = match v.status.as_str() {
-                    "FINALIZADA" => r#"<span class="badge badge-success">Finalizada</span>"#,
-                    "ABERTA" => r#"<span class="badge badge-warning">Aberta</span>"#,
-                    "CANCELADA" => r#"<span class="badge badge-danger">Cancelada</span>"#,
-                    _ => "",
+                let badge = match v.status {
+                    StatusVenda::Finalizada => {
+                        r#"<span class="badge badge-success">Finalizada</span>"#
+                    }
+                    StatusVenda::Aberta => r#"<span class="badge badge-warning">Aberta</span>"#,
+                    StatusVenda::Cancelada => {
+                        r#"<span class="badge badge-danger">Cancelada</span>"#
+                    }
                 };
 
                 html.push_str(&format!(
@@ -37,16 +41,17 @@ impl Vendas {
                         <td>{}</td>
                         <td>{}</td>
                         <td>
-                            <button class="btn btn-primary" onclick="verVenda({})">Ver</button>
+                            <button class="btn btn-primary" onclick="verVenda('{}')">Ver</button>
                         </td>
                     </tr>"#,
-                    v.id,
+                    v.id.as_deref().unwrap_or(""),
                     v.cliente_id
+                        .as_ref()
                         .map(|id| format!("Cliente #{}", id))
                         .unwrap_or("-".to_string()),
                     components::format_currency(v.total_final),
                     badge,
-                    v.id
+                    v.id.as_deref().unwrap_or("")
                 ));
             }
         }